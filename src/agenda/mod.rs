@@ -0,0 +1,30 @@
+//! The compact layout's one right-hand pane: the selected day's events and
+//! the tasks due that day in a single list, in the order the day runs.
+
+use chrono::NaiveDate;
+
+use crate::model::{Event, Task};
+use crate::tasks;
+
+/// A row of the agenda, by index into the app's events or tasks, so the
+/// event and task keys work on it unchanged.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Item {
+    Event(usize),
+    Task(usize),
+}
+
+/// All-day events first, then the tasks due on `date` (on `today` also the
+/// overdue ones, which have nowhere else to show), then timed events by
+/// start. Tasks carry only a date, so they lead the day's timed events;
+/// among themselves they keep the task list's sort.
+pub fn items(events: &[Event], tasks: &[Task], date: NaiveDate, today: NaiveDate) -> Vec<Item> {
+    let due = tasks.iter().enumerate()
+        .filter(|(_, t)| {
+            t.due.is_some_and(|d| d.date_naive() == date) || (date == today && tasks::is_overdue(t, today))
+        })
+        .map(|(i, _)| Item::Task(i));
+    let all_day = events.iter().enumerate().filter(|(_, e)| e.all_day).map(|(i, _)| Item::Event(i));
+    let timed   = events.iter().enumerate().filter(|(_, e)| !e.all_day).map(|(i, _)| Item::Event(i));
+    all_day.chain(due).chain(timed).collect()
+}
@@ -0,0 +1,79 @@
+//! Notices the date rolling over and the machine waking from sleep, so the
+//! "today" highlight, overdue marks and day lists don't go stale in a TUI
+//! that stays open for days. Also notices the system timezone changing
+//! (travel), which the app asks about.
+
+use chrono::{Local, NaiveDate};
+use chrono_tz::Tz;
+use std::time::{Duration, Instant, SystemTime};
+
+use crate::calendar::dst;
+
+/// How often the clock is actually read.
+const CHECK_EVERY: Duration = Duration::from_secs(1);
+
+/// Wall time moving this much further than monotonic time means a suspend
+/// (the monotonic clock stops while asleep) or a manual/NTP clock change.
+const JUMP: Duration = Duration::from_secs(5);
+
+/// How often the system timezone is read, besides on every wake.
+const ZONE_EVERY: Duration = Duration::from_secs(60);
+
+pub struct Jump {
+    /// Set when the local date changed; holds the old one.
+    pub previous_day: Option<NaiveDate>,
+    /// Resumed from sleep or the clock was reset.
+    pub woke: bool,
+    /// Set when the system timezone changed; holds the new one.
+    pub zone: Option<Tz>,
+}
+
+pub struct WallClock {
+    today:   NaiveDate,
+    wall:    SystemTime,
+    mono:    Instant,
+    zone:    Option<Tz>,
+    zone_at: Instant,
+}
+
+impl WallClock {
+    pub fn new() -> Self {
+        Self {
+            today:   Local::now().date_naive(),
+            wall:    SystemTime::now(),
+            mono:    Instant::now(),
+            zone:    dst::system(),
+            zone_at: Instant::now(),
+        }
+    }
+
+    /// What changed since the last call, if anything.
+    pub fn check(&mut self) -> Option<Jump> {
+        let mono = self.mono.elapsed();
+        if mono < CHECK_EVERY { return None; }
+
+        let now  = SystemTime::now();
+        let woke = match now.duration_since(self.wall) {
+            Ok(wall) => wall.saturating_sub(mono) > JUMP,
+            // Clock set backwards
+            Err(e)   => e.duration() > JUMP,
+        };
+        self.wall = now;
+        self.mono = Instant::now();
+
+        let today = Local::now().date_naive();
+        let previous_day = (today != self.today).then(|| std::mem::replace(&mut self.today, today));
+
+        let mut zone = None;
+        if woke || self.zone_at.elapsed() >= ZONE_EVERY {
+            self.zone_at = Instant::now();
+            let now = dst::system();
+            if now.is_some() && now != self.zone {
+                self.zone = now;
+                zone      = now;
+            }
+        }
+
+        (woke || previous_day.is_some() || zone.is_some()).then_some(Jump { previous_day, woke, zone })
+    }
+}
@@ -1,20 +1,78 @@
+mod clock;
+pub mod palette;
+
 use anyhow::Result;
-use chrono::{Datelike, Duration, Local, NaiveDate};
+use chrono::{DateTime, Datelike, Duration, Local, NaiveDate, NaiveDateTime, NaiveTime, Utc};
 use crossterm::{
-    event::{self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyModifiers},
+    event::{
+        self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyEvent, KeyModifiers,
+        MouseButton, MouseEvent, MouseEventKind,
+    },
     execute,
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
-use ratatui::{backend::CrosstermBackend, Terminal};
+use ratatui::{backend::CrosstermBackend, layout::Rect, Terminal};
+use std::collections::{HashMap, HashSet};
 use std::io;
+use std::time::Instant;
+use tokio::sync::{mpsc, oneshot};
 
+use clock::{Jump, WallClock};
 use crate::{
-    db::{Database, Event as DbEvent, Task},
-    sync::worker::{SyncEvent, SyncWorker},
-    theme::ThemeConfig,
-    ui::{draw, EventFormStep, InputMode, TimeField, UiState},
+    agenda,
+    config::{AppConfig, Urgency},
+    calendar::{add_days, day_bounds, days_in_month, dst::{self, DstShift}, month_bounds, parse_date, shift_month, week_start},
+    dedupe::{self, Field, Picks},
+    digest::Digest,
+    export,
+    db::{
+        watch::Watcher, Attendee, Checklist, ChecklistItem, Database, Event as DbEvent, FeedEvent, Forecast, Habit, Meal,
+        Quarantined, Rsvp, SyncLogEntry, Task, Tag, Timer, WorkingLocation, EVENT_COLORS, OUTLOOK_PREFIX,
+        TASKWARRIOR_PREFIX, TODOIST_PREFIX,
+    },
+    focus::{self, pomodoro, FocusWeek},
+    habits::{self, Checks},
+    holidays::{Holiday, HolidayEngine},
+    logs::{self, LogLine},
+    notify::Notification,
+    planner::{self, DayPlan, PlannedBlock},
+    plugins::{actions::{Actions, Subject}, Plugins},
+    attendance::{self, Attendance, MeetingLoad},
+    pto::{self, PtoYear},
+    sync::{validate, worker::{SyncEvent, SyncWorker}},
+    tasks::{self, TaskFilter, TaskSort},
+    theme::{self, ThemeConfig},
+    timelog::{self, GroupBy, WeekReport},
+    ui::{self, draw, EventFormStep, GuestCheck, InputMode, TimeField, UiState},
 };
 
+/// Lines the `O` viewer reads back.
+const LOG_VIEW_LINES: usize = 500;
+/// Newest sync log entries `Y` lists.
+const SYNC_LOG_LINES: i64 = 500;
+
+/// How long a reminder toast stays up.
+const TOAST_FOR: std::time::Duration = std::time::Duration::from_secs(15);
+
+/// How often the "starting soon" banner is looked up, and how long after
+/// its start an event stays on it.
+const BANNER_CHECK: std::time::Duration = std::time::Duration::from_secs(15);
+const BANNER_GRACE: i64 = 5;
+/// `z` / `Z` put the banner away for this many minutes.
+const SNOOZE_SHORT: i64 = 5;
+const SNOOZE_LONG:  i64 = 10;
+
+/// `+` / `-` change an event's lead time by this many minutes, up to
+/// `MAX_LEAD`.
+const LEAD_STEP: i64 = 5;
+const MAX_LEAD:  i64 = 240;
+
+/// The largest count typed before a calendar motion.
+const MAX_COUNT: u32 = 999;
+
+/// The system timezone last adopted, kept between runs.
+const ZONE_SETTING: &str = "zone";
+
 // ─── Panel focus model ────────────────────────────────────────────────────────
 
 #[derive(Debug, Clone, PartialEq)]
@@ -25,6 +83,32 @@ pub enum Panel {
     EventDetail,
     TaskDetail,
     Help,
+    Stats,
+    Onboarding,
+    MealPlan,
+    Checklists,
+    IdealWeek,
+    Quarantine,
+    ThemeEditor,
+    ThemePicker,
+    Logs,
+    Reports,
+    Habits,
+    Export,
+    EventInfo,
+    ZoneChange,
+    Bulk,
+    Confirm,
+    Dedupe,
+    Plugins,
+    TaskEdit,
+    Planning,
+    SendTo,
+    SyncLog,
+    Today,
+    Palette,
+    GoTo,
+    Holidays,
 }
 
 // ─── App state ────────────────────────────────────────────────────────────────
@@ -32,44 +116,394 @@ pub enum Panel {
 pub struct App {
     pub db:            Database,
     pub theme:         ThemeConfig,
+    /// Everything `T` offers, scanned at startup and after saving a theme.
+    pub themes:        Vec<ThemeConfig>,
+    pub config:        AppConfig,
     pub sync:          Option<SyncWorker>,
+    /// Seconds between auto-syncs, from `[sync]` until `Space I` changes
+    /// it; None when they're off.
+    pub sync_every:    Option<u64>,
     pub selected_date: NaiveDate,
     pub view_month:    u32,
     pub view_year:     i32,
     pub active_panel:  Panel,
     pub events:        Vec<DbEvent>,
+    /// Ids of the selected day's events that overlap another (tag filter
+    /// or not), marked ⚠ in the list.
+    pub day_conflicts: HashSet<String>,
+    /// Read-only events from ICS subscriptions on the selected day.
+    pub feed_events:   Vec<FeedEvent>,
+    /// Tasks as listed: narrowed by task_filter, in task_sort order.
     pub tasks:         Vec<Task>,
+    pub task_filter:   TaskFilter,
+    pub task_sort:     TaskSort,
+    /// Every tag on a task, for cycling the filter.
+    pub task_tags:     Vec<String>,
     pub event_cursor:  usize,
     pub task_cursor:   usize,
+    /// Meals for the week containing selected_date (when meal_plan is on).
+    pub meals:         Vec<Meal>,
+    /// Cached forecast from today on (when `[weather]` is set).
+    pub forecast:      Vec<Forecast>,
+    /// Local checklists and the items of the selected one (when open).
+    pub checklists:    Vec<Checklist>,
+    pub checklist_items: Vec<ChecklistItem>,
+    /// Habits in order, and every day each was done.
+    pub habits:        Vec<Habit>,
+    pub habit_checks:  Checks,
+    /// The selected week's events and ideal-week blocks (when `W` is open).
+    pub week_events:   Vec<DbEvent>,
+    pub week_plan:     Vec<PlannedBlock>,
+    /// Zone used for DST warnings when an event doesn't carry its own.
+    pub tz:            chrono_tz::Tz,
+    pub holidays:      HolidayEngine,
+    /// Holidays in view_month/view_year, for the month grid and day list.
+    pub month_holidays: Vec<Holiday>,
+    /// From Google's working-location events, for the day badges.
+    pub working_locations: Vec<WorkingLocation>,
+    /// view_month's events, for the dots on the month grid.
+    pub month_events:  Vec<DbEvent>,
+    pub tags:          Vec<Tag>,
+    /// `f` in the events panel: only events with this tag are listed.
+    pub tag_filter:    Option<String>,
+    /// Imported items that failed validation and await review (`Q`).
+    pub quarantine:    Vec<Quarantined>,
+    /// The event `Enter` opened in the event list.
+    pub event_info:    Option<EventInfo>,
+    /// The system timezone moved since it was last adopted; asked about
+    /// once nothing else is open.
+    pub zone_change:   Option<ZoneChange>,
+    /// The zone `k` declined this session, so it isn't asked again.
+    zone_kept:         Option<chrono_tz::Tz>,
+    /// The batch action waiting for confirmation, with what it applies to.
+    pub bulk:          Option<Bulk>,
+    /// The event `M` picked up, waiting for a day on the calendar.
+    pub moving:        Option<Moving>,
+    /// The destructive action waiting for `y`.
+    pub confirm:       Option<Confirm>,
+    /// The day plan `p` proposed, until it's accepted or dismissed.
+    pub planning:      Option<Planning>,
+    /// All-day events and holidays held above the day's list (`^`).
+    pub pin_all_day:   bool,
+    /// Holiday countries left off the calendar, and holidays dimmed on the
+    /// grid: `[ui]` until `V` changes them.
+    pub hide_holidays: Vec<String>,
+    pub dim_holidays:  bool,
+    /// The task `e` opened in the task list.
+    pub task_edit:     Option<TaskEdit>,
+    /// The `U` review of likely duplicate events.
+    pub dedupe:        Option<Dedupe>,
+    /// `[[panels]]` and what their commands last printed (`C`).
+    pub plugins:       Plugins,
+    /// `[[actions]]`, and the item `|` is sending while one is picked.
+    pub actions:       Actions,
+    pub send_to:       Option<SendTo>,
+    /// The latest log lines at ui.log_level and above (when `O` is open).
+    pub log_lines:     Vec<LogLine>,
+    /// The newest sync log entries, oldest first (when `Y` is open).
+    pub sync_log:      Vec<SyncLogEntry>,
     pub ui:            UiState,
     pub sync_status:   String,
     pub running:       bool,
+    /// PTO balances per year, newest first (filled when Stats opens).
+    pub pto_summary:   Vec<PtoYear>,
+    pub meeting_load:  MeetingLoad,
+    pub focus_weeks:   Vec<FocusWeek>,
+    /// Today at a glance, for `Space G` and on startup.
+    pub digest:        Option<Digest>,
+    /// The running `P` session, if any.
+    pub pomodoro:      Option<pomodoro::Session>,
+    /// The running `Ctrl+t` timer, if any; kept in the database.
+    pub timer:         Option<Timer>,
+    /// The `H` time report: Monday of the week shown, and its totals.
+    pub report_week:   NaiveDate,
+    pub report_group:  GroupBy,
+    pub report:        WeekReport,
+    /// Until the first data load: panels draw skeletons.
+    pub loading:       bool,
+    startup:           Option<oneshot::Receiver<Result<Startup>>>,
+    /// Onboarding opens by itself only once per session.
+    auth_prompted:     bool,
+    /// Spots writes from other processes; None if it couldn't be opened.
+    watcher:           Option<Watcher>,
+    clock:             WallClock,
+    /// Reminders from the bell backend, and the one on screen since when.
+    toasts:            Option<mpsc::UnboundedReceiver<Notification>>,
+    pub toast:         Option<(Notification, Instant)>,
+    /// Events starting soon that weren't snoozed or dismissed, soonest first,
+    /// and when that was looked up.
+    pub banner:        Vec<DbEvent>,
+    banner_checked:    Option<Instant>,
+    /// Where `Ctrl+p` was pressed, and the key of the command picked in it,
+    /// sent once the palette has closed.
+    palette_from:      Option<Panel>,
+    palette_run:       Option<KeyEvent>,
+    /// Settings picked in the palette: config.toml opens in `$EDITOR` once
+    /// the event loop has the terminal.
+    edit_config:       bool,
+}
+
+/// The event shown by `Enter` in the event list, with where it lives.
+pub struct EventInfo {
+    pub event:    DbEvent,
+    /// The Google calendar's name, once a sync has seen it.
+    pub calendar: Option<String>,
+    pub guests:   Vec<Attendee>,
+}
+
+impl EventInfo {
+    /// Your own answer, when you're among the guests.
+    pub fn response(&self) -> Option<Rsvp> {
+        self.guests.iter().find(|g| g.is_self).map(|g| g.response)
+    }
+}
+
+/// The system timezone, before and after the machine travelled.
+pub struct ZoneChange {
+    pub from:     chrono_tz::Tz,
+    pub to:       chrono_tz::Tz,
+    /// Upcoming events typed in here, whose wall times assume `from`.
+    pub floating: Vec<DbEvent>,
+}
+
+/// What the `v` selection's batch keys do.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BulkAction {
+    Delete,
+    Move,
+    Calendar,
+    Complete,
+}
+
+/// A batch action over the `v` selection, confirmed once in its overlay.
+pub struct Bulk {
+    pub action:  BulkAction,
+    pub events:  Vec<DbEvent>,
+    pub tasks:   Vec<Task>,
+    /// Move: the date typed, `YYYY-MM-DD` or an offset (`+3d`, `-1w`).
+    pub input:   String,
+    /// Calendar: the calendars (or task lists) offered, as (id, label),
+    /// and the one picked.
+    pub targets: Vec<(String, String)>,
+    pub target:  usize,
+    /// The list to go back to.
+    pub from:    Panel,
+}
+
+impl Bulk {
+    /// "3 events and 1 task": what the action applies to. Completing
+    /// leaves events be, and a mixed selection changes calendars for its
+    /// events only.
+    pub fn describe(&self) -> String {
+        let n = |n: usize, what: &str| format!("{n} {what}{}", if n == 1 { "" } else { "s" });
+        let events = if self.action == BulkAction::Complete { 0 } else { self.events.len() };
+        let tasks  = if self.action == BulkAction::Calendar && events > 0 { 0 } else { self.tasks.len() };
+        match (events, tasks) {
+            (e, 0) => n(e, "event"),
+            (0, t) => n(t, "task"),
+            (e, t) => format!("{} and {}", n(e, "event"), n(t, "task")),
+        }
+    }
+}
+
+/// What `y` in the confirmation overlay goes on to do.
+pub enum ConfirmAction {
+    DeleteEvent(DbEvent),
+    DeleteTask(Task),
+    Disconnect,
+}
+
+impl ConfirmAction {
+    pub fn question(&self) -> String {
+        match self {
+            Self::DeleteEvent(e) => format!("Delete “{}”?", e.title),
+            Self::DeleteTask(t)  => format!("Delete the task “{}”?", t.title),
+            Self::Disconnect     => "Disconnect Google?".into(),
+        }
+    }
+
+    /// What else happens, for the line under the question.
+    pub fn consequence(&self) -> &'static str {
+        match self {
+            Self::DeleteEvent(e) if e.sync_id.is_some() => "It's deleted from Google Calendar too on the next sync.",
+            Self::DeleteTask(t) if t.sync_id.is_some()  => "It's deleted from Google Tasks too on the next sync.",
+            Self::DeleteEvent(_) | Self::DeleteTask(_)  => "It's only on this machine, so it's gone for good.",
+            Self::Disconnect => "Access is revoked and syncing stops; local data stays. A to connect again.",
+        }
+    }
+}
+
+/// A destructive action asked about before it's done
+/// (`[ui] skip_confirm` turns the asking off).
+pub struct Confirm {
+    pub action: ConfirmAction,
+    /// Where to go back to either way.
+    pub from:   Panel,
+}
+
+/// `|` on an event or task: the actions to pick from.
+pub struct SendTo {
+    pub subject: Subject,
+    /// Indices into `[[actions]]` offered for the item.
+    pub choices: Vec<usize>,
+    pub from:    Panel,
+}
+
+/// An event being moved to another day: dropped on the day picked on the
+/// calendar, or on a typed date (`+1d`, `-2w`, `next fri`, `mar 14`).
+pub struct Moving {
+    pub event: DbEvent,
+    /// What's typed, as `parse_date` reads it; None while picking a day.
+    pub typed: Option<String>,
+}
+
+/// The selected day's tasks fitted into its free time, for `Enter` to put
+/// on the calendar.
+pub struct Planning {
+    pub date:     NaiveDate,
+    pub plan:     DayPlan,
+    /// Tasks taken out of the proposal with `x`.
+    pub left_out: HashSet<String>,
+    pub cursor:   usize,
+}
+
+/// The fields of the task edit form, in Tab order.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TaskField {
+    Title,
+    Notes,
+    Due,
+    Priority,
+}
+
+impl TaskField {
+    pub const ALL: [TaskField; 4] = [Self::Title, Self::Notes, Self::Due, Self::Priority];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Title    => "Title",
+            Self::Notes    => "Notes",
+            Self::Due      => "Due",
+            Self::Priority => "Priority",
+        }
+    }
+
+    fn step(self, by: isize) -> Self {
+        let i = Self::ALL.iter().position(|&f| f == self).unwrap_or(0) as isize;
+        Self::ALL[(i + by).rem_euclid(Self::ALL.len() as isize) as usize]
+    }
+}
+
+/// A task being edited, its fields as typed so far.
+pub struct TaskEdit {
+    pub task:     Task,
+    pub field:    TaskField,
+    /// With the task's `#tags` and `~estimate`, as the new-task form takes
+    /// them.
+    pub title:    String,
+    pub notes:    String,
+    /// `YYYY-MM-DD`, an offset from today (`+3d`, `-1w`), or empty for none.
+    pub due:      String,
+    pub priority: i64,
+}
+
+/// Likely duplicate events, reviewed one group at a time.
+pub struct Dedupe {
+    pub events:    Vec<DbEvent>,
+    /// Groups as indices into `events` (see `dedupe::clusters`).
+    pub clusters:  Vec<Vec<usize>>,
+    pub cluster:   usize,
+    /// The field row selected, and which member's value each row takes.
+    pub field:     usize,
+    pub picks:     Picks,
+    /// Synced calendars' names, by id.
+    pub calendars: HashMap<String, String>,
+}
+
+impl Dedupe {
+    pub fn group(&self) -> Vec<DbEvent> {
+        self.clusters.get(self.cluster).map_or_else(Vec::new, |g| {
+            g.iter().map(|&i| self.events[i].clone()).collect()
+        })
+    }
+}
+
+/// What finishes in the background while the first frame is on screen.
+pub struct Startup {
+    /// The same database, with the change journal attached.
+    pub db:     Database,
+    pub sync:   SyncWorker,
+    pub toasts: mpsc::UnboundedReceiver<Notification>,
 }
 
 impl App {
-    pub async fn new(db: Database, theme: ThemeConfig) -> Result<Self> {
-        let today  = Local::now().date_naive();
-        let events = db.events_in_range(
-            today.and_hms_opt(0, 0, 0).unwrap().and_utc(),
-            today.and_hms_opt(23, 59, 59).unwrap().and_utc(),
-        ).await.unwrap_or_default();
-        let tasks = db.all_tasks().await.unwrap_or_default();
-
-        Ok(Self {
-            db, theme, sync: None,
+    /// Runs no queries: the first frame is a skeleton, and the data loads
+    /// right after it (see `load_initial`).
+    pub fn new(db: Database, theme: ThemeConfig, config: AppConfig) -> Self {
+        let today    = Local::now().date_naive();
+        let tz       = dst::zone(config.timezone.as_deref());
+        let holidays = HolidayEngine::from_config(&config.holidays);
+        let plugins  = Plugins::new(config.panels.clone());
+        let actions  = Actions::new(config.actions.clone());
+        let pin      = config.ui.pin_all_day;
+        let hidden   = config.ui.hide_holidays.clone();
+        let dim      = config.ui.dim_holidays;
+        let every    = config.sync.clone().unwrap_or_default().every();
+
+        Self {
+            db, theme, config, sync: None, sync_every: every,
+            themes: ThemeConfig::all_themes(),
             selected_date: today,
             view_month:    today.month(),
             view_year:     today.year(),
             active_panel:  Panel::Calendar,
-            events, tasks,
+            events: Vec::new(), day_conflicts: HashSet::new(), feed_events: Vec::new(),
+            tasks: Vec::new(), task_filter: TaskFilter::All, task_sort: TaskSort::Priority,
+            task_tags: Vec::new(),
             event_cursor: 0, task_cursor: 0,
+            meals: Vec::new(), forecast: Vec::new(), tz, holidays, month_holidays: Vec::new(), quarantine: Vec::new(),
+            log_lines: Vec::new(), sync_log: Vec::new(), event_info: None, zone_change: None, zone_kept: None, bulk: None,
+            moving: None, confirm: None, dedupe: None, plugins, task_edit: None,
+            actions, send_to: None,
+            planning: None, pin_all_day: pin, hide_holidays: hidden, dim_holidays: dim,
+            working_locations: Vec::new(), tags: Vec::new(),
+            month_events: Vec::new(), tag_filter: None,
+            checklists: Vec::new(), checklist_items: Vec::new(),
+            habits: Vec::new(), habit_checks: Checks::new(),
+            week_events: Vec::new(), week_plan: Vec::new(),
             ui: UiState::default(),
             sync_status: String::new(),
             running: true,
-        })
+            pto_summary: Vec::new(),
+            meeting_load: MeetingLoad::default(),
+            focus_weeks: Vec::new(),
+            digest: None,
+            pomodoro: None,
+            timer: None,
+            report_week: week_start(today),
+            report_group: GroupBy::Tag,
+            report: WeekReport::default(),
+            loading: true,
+            startup: None,
+            auth_prompted: false,
+            watcher: None,
+            clock: WallClock::new(),
+            toasts: None,
+            toast:  None,
+            banner: Vec::new(),
+            banner_checked: None,
+            palette_from: None,
+            palette_run:  None,
+            edit_config:  false,
+        }
     }
 
-    pub fn attach_sync_worker(&mut self, w: SyncWorker) { self.sync = Some(w); }
+    /// Hands over the background startup; keys other than `q` wait for it,
+    /// so nothing is written before the journal is attached.
+    pub fn start_up(&mut self, rx: oneshot::Receiver<Result<Startup>>) {
+        self.startup     = Some(rx);
+        self.sync_status = "Starting…".into();
+    }
 
     // ── TUI loop ──────────────────────────────────────────────────────────────
 
@@ -96,6 +530,12 @@ impl App {
 
         while self.running {
             term.draw(|f| draw(f, self))?;
+            if self.loading {
+                tracing::info!("First frame drawn");
+                self.load_initial().await;
+                continue;
+            }
+            self.poll_startup().await?;
 
             // Drain sync events into a local Vec first — avoids holding an
             // immutable borrow on self.sync while calling &mut self methods.
@@ -106,148 +546,2624 @@ impl App {
                     buf
                 } else { vec![] }
             } else { vec![] };
+            let feeds  = pending.iter().any(|e| matches!(e, SyncEvent::FeedsUpdated(_)));
+            let sky    = pending.iter().any(|e| matches!(e, SyncEvent::WeatherUpdated));
+            let synced = feeds || pending.iter().any(|e| matches!(e, SyncEvent::SyncComplete { .. }));
             for ev in pending { self.on_sync_event(ev); }
+            if feeds  { self.reload_feed_events().await; }
+            if sky    { self.reload_forecast().await; }
+            if synced { self.quarantine = self.db.quarantined().await.unwrap_or_default(); }
+            if synced && self.active_panel == Panel::SyncLog { self.reload_sync_log().await; }
+
+            // Never swap data out from under a half-typed form; the change
+            // stays pending until input is finished.
+            if self.ui.input_mode != InputMode::Insert {
+                if let Some(w) = self.watcher.as_mut() {
+                    if w.changed().await { self.reload_data().await; }
+                }
+            }
+
+            if let Some(jump) = self.clock.check() { self.on_clock_jump(jump).await; }
+            self.show_zone_change();
+            self.poll_toasts(term)?;
+            self.poll_banner().await;
+            self.poll_pomodoro(term).await?;
+            self.poll_plugins();
+            if let Some(status) = self.actions.poll() { self.sync_status = status; }
+
+            if event::poll(tick)? {
+                match event::read()? {
+                    Event::Key(key)     => self.on_key(key).await?,
+                    Event::Mouse(mouse) => self.on_mouse(mouse).await?,
+                    _ => {}
+                }
+            }
+            if let Some(key) = self.palette_run.take() { self.on_key(key).await?; }
+            if std::mem::take(&mut self.edit_config) { self.edit_config_file(term)?; }
+        }
+
+        if let Some(ref w) = self.sync { w.shutdown().await; }
+        Ok(())
+    }
+
+    /// First data load, right after the skeleton frame.
+    async fn load_initial(&mut self) {
+        self.watcher = self.db.watch().await
+            .map_err(|e| tracing::warn!("DB watch unavailable: {e}"))
+            .ok();
+        self.refresh().await;
+        self.reload_quarantine().await;
+        if let Some(zone) = dst::system() { self.check_zone(zone).await; }
+        if self.config.ui.startup_digest && self.active_panel == Panel::Calendar {
+            self.load_digest().await;
+            if self.digest.as_ref().is_some_and(|d| !d.is_empty()) { self.active_panel = Panel::Today; }
+        }
+        self.loading = false;
+    }
+
+    /// Takes over the journalled database, sync worker and reminders once
+    /// the background startup is done, then runs the first sync.
+    async fn poll_startup(&mut self) -> Result<()> {
+        let Some(rx) = self.startup.as_mut() else { return Ok(()) };
+        let ready = match rx.try_recv() {
+            Ok(ready)                                 => ready?,
+            Err(oneshot::error::TryRecvError::Empty)  => return Ok(()),
+            Err(oneshot::error::TryRecvError::Closed) => anyhow::bail!("Startup ended without finishing"),
+        };
+        self.startup     = None;
+        self.db          = ready.db;
+        self.toasts      = Some(ready.toasts);
+        self.sync_status = String::new();
+        let synced = self.config.google.is_some() || self.config.outlook.is_some() || self.config.todoist.is_some()
+            || self.config.taskwarrior.is_some();
+        if synced { ready.sync.sync_now().await; }
+        self.sync = Some(ready.sync);
+        Ok(())
+    }
+
+    /// Shows the newest reminder from the bell backend (ringing the terminal
+    /// bell unless it's low urgency) and clears it after `TOAST_FOR`.
+    fn poll_toasts(&mut self, term: &mut Terminal<CrosstermBackend<io::Stdout>>) -> Result<()> {
+        let mut newest = None;
+        if let Some(rx) = self.toasts.as_mut() {
+            while let Ok(n) = rx.try_recv() { newest = Some(n); }
+        }
+        if let Some(n) = newest {
+            if n.urgency != Urgency::Low {
+                execute!(term.backend_mut(), crossterm::style::Print('\x07'))?;
+            }
+            self.toast = Some((n, Instant::now()));
+        }
+        if self.toast.as_ref().is_some_and(|(_, at)| at.elapsed() >= TOAST_FOR) {
+            self.toast = None;
+        }
+        Ok(())
+    }
+
+    /// Refreshes the "starting soon" banner every `BANNER_CHECK`, or at once
+    /// after a snooze or dismissal.
+    async fn poll_banner(&mut self) {
+        let within = self.config.notify.banner_minutes;
+        if within <= 0 || self.banner_checked.is_some_and(|at| at.elapsed() < BANNER_CHECK) { return; }
+        self.banner_checked = Some(Instant::now());
+        let now = Local::now().naive_local().and_utc();
+        self.banner = self.db.banner_events(now, Duration::minutes(within), Duration::minutes(BANNER_GRACE)).await
+            .unwrap_or_default();
+    }
+
+    /// `z` / `Z` on the banner: hides the first event on it for `minutes`,
+    /// then shows and sends its reminder again.
+    async fn snooze_banner(&mut self, minutes: i64) -> Result<()> {
+        let Some(ev) = self.banner.first() else { return Ok(()) };
+        self.db.snooze_reminder(ev, Utc::now() + Duration::minutes(minutes)).await?;
+        self.sync_status    = format!("Snoozed “{}” for {minutes} min", ev.title);
+        self.banner_checked = None;
+        Ok(())
+    }
+
+    /// `Ctrl+x` on the banner: no more reminders for the first event on it.
+    async fn dismiss_banner(&mut self) -> Result<()> {
+        let Some(ev) = self.banner.first() else { return Ok(()) };
+        self.db.dismiss_reminder(ev).await?;
+        self.sync_status    = format!("Dismissed “{}”", ev.title);
+        self.banner_checked = None;
+        Ok(())
+    }
+
+    /// Hands the terminal to `$VISUAL` / `$EDITOR` (vi without either) on
+    /// config.toml, then says whether what was saved still parses. Settings
+    /// are read at startup, so they apply from the next one.
+    fn edit_config_file(&mut self, term: &mut Terminal<CrosstermBackend<io::Stdout>>) -> Result<()> {
+        let path   = AppConfig::path();
+        let editor = std::env::var("VISUAL").or_else(|_| std::env::var("EDITOR")).unwrap_or_else(|_| "vi".into());
+        if let Some(dir) = path.parent() { std::fs::create_dir_all(dir)?; }
+
+        disable_raw_mode()?;
+        execute!(term.backend_mut(), LeaveAlternateScreen, DisableMouseCapture)?;
+        let status = std::process::Command::new(&editor).arg(&path).status();
+        enable_raw_mode()?;
+        execute!(term.backend_mut(), EnterAlternateScreen, EnableMouseCapture)?;
+        term.clear()?;
+
+        self.sync_status = match (status, AppConfig::load()) {
+            (Err(e), _)     => format!("Couldn't run {editor}: {e}"),
+            (Ok(_), Err(e)) => format!("config.toml doesn't parse: {e}"),
+            (Ok(_), Ok(_))  => "Settings saved — they apply the next time lm starts".into(),
+        };
+        Ok(())
+    }
+
+    /// Ends a session that has run its length: rings the bell and, with
+    /// `log_to_calendar`, saves it on the focus layer.
+    async fn poll_pomodoro(&mut self, term: &mut Terminal<CrosstermBackend<io::Stdout>>) -> Result<()> {
+        let Some(session) = self.pomodoro.take_if(|s| s.done()) else { return Ok(()) };
+        execute!(term.backend_mut(), crossterm::style::Print('\x07'))?;
+        self.sync_status = if self.config.pomodoro.log_to_calendar {
+            self.db.upsert_event(&session.to_event()).await?;
+            self.reload_data().await;
+            format!("{} done — logged", session.title())
+        } else {
+            format!("{} done", session.title())
+        };
+        Ok(())
+    }
+
+    /// `P`: starts a session, named after the selected task when the task
+    /// list has focus, or stops the running one without logging it.
+    fn toggle_pomodoro(&mut self) {
+        if let Some(session) = self.pomodoro.take() {
+            self.sync_status = format!("🍅 Stopped after {} min — not logged", session.elapsed().as_secs() / 60);
+            return;
+        }
+        let label = (self.active_panel == Panel::TaskList)
+            .then(|| self.tasks.get(self.task_cursor).map(|t| t.title.clone()))
+            .flatten();
+        let session = pomodoro::Session::start(self.config.pomodoro.minutes, label);
+        self.sync_status = format!("{} — {} min, P to stop", session.title(), self.config.pomodoro.minutes.max(1));
+        self.pomodoro = Some(session);
+    }
+
+    /// `Ctrl+t`: starts a timer, named after the selected task when the task
+    /// list has focus, or stops the running one and records the time entry.
+    async fn toggle_timer(&mut self) -> Result<()> {
+        let now = Local::now().naive_local().and_utc();
+        if let Some(timer) = self.db.running_timer().await? {
+            let entry = timelog::entry(&timer, now);
+            if now - timer.started_at < Duration::minutes(1) {
+                self.db.stop_timer(None).await?;
+                self.sync_status = "⏱ Stopped under a minute — not logged".into();
+            } else {
+                self.db.stop_timer(Some(&entry)).await?;
+                self.sync_status = format!("⏱ Logged {} {}", timelog::running_for(&timer, now), entry.title);
+            }
+            self.timer = None;
+            self.reload_data().await;
+            return Ok(());
+        }
+
+        let task = (self.active_panel == Panel::TaskList)
+            .then(|| self.tasks.get(self.task_cursor))
+            .flatten();
+        // A task tag that is also an event tag files the entry under it
+        let tag = task
+            .and_then(|t| t.tags.iter().find(|tag| self.tags.iter().any(|x| &x.name == *tag)))
+            .cloned()
+            .or_else(|| self.tag_filter.clone());
+        let timer = Timer { label: task.map(|t| t.title.clone()), tag, started_at: now };
+        self.db.start_timer(&timer).await?;
+        self.sync_status = format!("⏱ Timer started — Ctrl+t to stop{}", timer.label.as_ref().map_or_else(String::new, |l| format!(" ({l})")));
+        self.timer = Some(timer);
+        Ok(())
+    }
+
+    /// Midnight or a resume from sleep: everything derived from "now" is
+    /// stale. A selection that was on the old today follows to the new one.
+    async fn on_clock_jump(&mut self, jump: Jump) {
+        if let Some(prev) = jump.previous_day {
+            if self.selected_date == prev {
+                let today = Local::now().date_naive();
+                self.selected_date = today;
+                self.view_month    = today.month();
+                self.view_year     = today.year();
+            }
+        }
+        self.reload_data().await;
+        if jump.woke {
+            if let Some(ref w) = self.sync { w.sync_now().await; }
+            self.sync_status = "↻ Resumed — refreshed".into();
+        } else if jump.previous_day.is_some() {
+            self.sync_status = format!("↻ {}", Local::now().format("%A, %b %-d"));
+        }
+        if let Some(zone) = jump.zone { self.check_zone(zone).await; }
+    }
+
+    // ── Timezone changes ──────────────────────────────────────────────────────
+
+    /// Compares the system zone with the one last adopted. The first run
+    /// just records it; a zone pinned in config never follows the machine.
+    async fn check_zone(&mut self, now: chrono_tz::Tz) {
+        if self.config.timezone.is_some() || self.zone_kept == Some(now) { return; }
+        let seen = self.db.setting(ZONE_SETTING).await.unwrap_or_default();
+        match seen.and_then(|n| n.parse::<chrono_tz::Tz>().ok()) {
+            Some(from) if from != now => {
+                let today    = Local::now().naive_local().and_utc();
+                let floating = self.db.floating_events(today).await.unwrap_or_default();
+                self.zone_change = Some(ZoneChange { from, to: now, floating });
+            }
+            Some(_) => {
+                // Back where it was before anyone answered
+                self.zone_change = None;
+                self.tz          = now;
+                if self.active_panel == Panel::ZoneChange { self.active_panel = Panel::Calendar; }
+            }
+            None => {
+                if let Err(e) = self.db.set_setting(ZONE_SETTING, now.name()).await {
+                    tracing::warn!("Saving the system timezone failed: {e}");
+                }
+            }
+        }
+    }
+
+    /// Opens the pending zone question, but not over a form or overlay.
+    fn show_zone_change(&mut self) {
+        let idle = self.startup.is_none()
+            && self.ui.input_mode != InputMode::Insert
+            && matches!(self.active_panel, Panel::Calendar | Panel::EventList | Panel::TaskList);
+        if idle && self.zone_change.is_some() { self.active_panel = Panel::ZoneChange; }
+    }
+
+    async fn key_zone_change(&mut self, key: KeyEvent) -> Result<()> {
+        match key.code {
+            KeyCode::Enter     => self.adopt_zone(false).await?,
+            KeyCode::Char('a') => self.adopt_zone(true).await?,
+            KeyCode::Char('k') => self.keep_zone(),
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// Switches to the new zone. With `shift`, events typed in under the old
+    /// one move so they keep their moment: 09:00 in Berlin becomes 03:00 in
+    /// New York. Without it they keep their clock time.
+    async fn adopt_zone(&mut self, shift: bool) -> Result<()> {
+        let Some(change) = self.zone_change.take() else { return Ok(()) };
+        self.active_panel = Panel::Calendar;
+        if shift {
+            for ev in &change.floating {
+                let mut e    = ev.clone();
+                e.start      = dst::rebase(e.start, change.from, change.to);
+                e.end        = dst::rebase(e.end, change.from, change.to);
+                e.dirty      = true;
+                e.updated_at = chrono::Utc::now();
+                self.db.upsert_event(&e).await?;
+            }
+        }
+        self.db.set_setting(ZONE_SETTING, change.to.name()).await?;
+        self.tz = change.to;
+        self.sync_status = if shift && !change.floating.is_empty() {
+            format!("🌐 {} — moved {} event(s) to keep their {} time", change.to, change.floating.len(), change.from)
+        } else {
+            format!("🌐 {} — times stay as entered", change.to)
+        };
+        self.reload_data().await;
+        Ok(())
+    }
+
+    /// Stays on the old zone for DST checks until the next start.
+    fn keep_zone(&mut self) {
+        let Some(change) = self.zone_change.take() else { return };
+        self.active_panel = Panel::Calendar;
+        self.tz           = change.from;
+        self.zone_kept    = Some(change.to);
+        self.sync_status  = format!("🌐 Keeping {} — asked again next start", change.from);
+    }
+
+    fn on_sync_event(&mut self, ev: SyncEvent) {
+        self.sync_status = match ev {
+            SyncEvent::SyncStarted                        => "⟳ Syncing…".into(),
+            SyncEvent::SyncComplete { pulled, read_only: true, .. } =>
+                format!("✓ +{pulled} pulled · read-only"),
+            SyncEvent::SyncComplete { pulled, pushed, .. } =>
+                format!("✓ +{pulled} pulled, {pushed} pushed"),
+            SyncEvent::SyncError(msg) => {
+                if self.active_panel == Panel::Onboarding {
+                    self.ui.auth_status = format!("✗ {msg}");
+                }
+                format!("✗ {msg}")
+            }
+            SyncEvent::AuthRequired => {
+                if !self.auth_prompted && self.active_panel == Panel::Calendar {
+                    self.auth_prompted = true;
+                    self.open_onboarding();
+                }
+                "Auth required — press A to connect Google".into()
+            }
+            SyncEvent::AuthUrl(url) => {
+                // Browser may not open (e.g. no display); the URL stays on screen
+                let _ = open::that(&url);
+                self.ui.auth_url    = Some(url);
+                self.ui.auth_status = "Waiting for Google to redirect back (listening on :8085)…".into();
+                "Waiting for Google authorization…".into()
+            }
+            SyncEvent::AuthComplete => {
+                if self.active_panel == Panel::Onboarding {
+                    self.active_panel  = Panel::Calendar;
+                    self.ui.input_mode = InputMode::Normal;
+                }
+                self.ui.auth_url = None;
+                "✓ Google connected".into()
+            }
+            SyncEvent::FeedsUpdated(n) => format!("✓ {n} feed events"),
+            SyncEvent::VaultImported(n) => format!("✓ {n} change(s) from the vault"),
+            SyncEvent::WeatherUpdated => return,
+            SyncEvent::Availability(answer) => {
+                // The guests were edited (or the form closed) since asking
+                if !matches!(self.ui.guest_check, GuestCheck::Pending) { return; }
+                let status = match &answer {
+                    Ok(list) => match list.iter().filter(|a| !a.busy.is_empty()).count() {
+                        0    => "✓ Guests are free — Enter to save".to_owned(),
+                        busy => format!("⚠ {busy} guest(s) busy — Enter saves anyway"),
+                    },
+                    Err(e) => format!("✗ Availability check failed: {e}"),
+                };
+                self.ui.guest_check = match answer {
+                    Ok(list) => GuestCheck::Done(list),
+                    Err(e)   => GuestCheck::Failed(e),
+                };
+                status
+            }
+            SyncEvent::Disconnected => {
+                // Don't pop onboarding straight back up after an explicit sign-out
+                self.auth_prompted = true;
+                "Google disconnected — press A to reconnect".into()
+            }
+        };
+    }
+
+    fn open_onboarding(&mut self) {
+        self.ui.auth_url    = None;
+        self.ui.auth_code.clear();
+        self.ui.auth_status = String::new();
+        self.ui.input_mode  = InputMode::Insert;
+        self.active_panel   = Panel::Onboarding;
+    }
+
+    // ── Input ─────────────────────────────────────────────────────────────────
+
+    async fn on_key(&mut self, key: crossterm::event::KeyEvent) -> Result<()> {
+        // Global keys (handled before panel-specific logic)
+        let typing = self.ui.input_mode == InputMode::Insert;
+        if self.startup.is_some() && key.code != KeyCode::Char('q') { return Ok(()); }
+        // After `Space`, a key from the popup goes on as itself; any other
+        // closes it
+        if std::mem::take(&mut self.ui.leader) {
+            let listed = |c: char| ui::leader_keys(self).iter().any(|(_, keys)| keys.iter().any(|(k, _)| *k == c));
+            match key.code {
+                KeyCode::Char(c) if listed(c) => {}
+                KeyCode::Esc | KeyCode::Char(' ') => return Ok(()),
+                _ => {
+                    self.sync_status = "Nothing on Space + that key — ? lists them all".into();
+                    return Ok(());
+                }
+            }
+        }
+        match (key.code, key.modifiers) {
+            (KeyCode::Char('q'), _) if !typing => { self.running = false; return Ok(()); }
+            (KeyCode::Char('s'), KeyModifiers::CONTROL) => {
+                if let Some(ref w) = self.sync { w.sync_now().await; }
+                return Ok(());
+            }
+            (KeyCode::Char('t'), KeyModifiers::CONTROL) => { self.toggle_timer().await?; return Ok(()); }
+            (KeyCode::Char('p'), KeyModifiers::CONTROL) if !typing => { self.open_palette(); return Ok(()); }
+            (KeyCode::Char('z'), _) if !typing && !self.banner.is_empty() => {
+                self.snooze_banner(SNOOZE_SHORT).await?;
+                return Ok(());
+            }
+            (KeyCode::Char('Z'), _) if !typing && !self.banner.is_empty() => {
+                self.snooze_banner(SNOOZE_LONG).await?;
+                return Ok(());
+            }
+            (KeyCode::Char('x'), KeyModifiers::CONTROL) if !self.banner.is_empty() => {
+                self.dismiss_banner().await?;
+                return Ok(());
+            }
+            (KeyCode::Char(c @ '1'..='9'), KeyModifiers::ALT) => {
+                let n = c as usize - '1' as usize;
+                if let Some(h) = self.habits_today().get(n).map(|h| (*h).clone()) {
+                    self.toggle_habit(&h, Local::now().date_naive()).await?;
+                }
+                return Ok(());
+            }
+            (KeyCode::Char('?'), _) if !typing => { self.active_panel = Panel::Help; return Ok(()); }
+            (KeyCode::Char('r'), _) if !typing => { self.reload_panel().await; return Ok(()); }
+            (KeyCode::Char('P'), _) if !typing => { self.toggle_pomodoro(); return Ok(()); }
+            (KeyCode::Char('^'), _) if !typing
+                && matches!(self.active_panel, Panel::Calendar | Panel::EventList | Panel::TaskList) =>
+            {
+                self.toggle_pin_all_day().await;
+                return Ok(());
+            }
+            // Checklists use R for "reset"
+            (KeyCode::Char('R'), _) if !typing && self.active_panel != Panel::Checklists => {
+                self.reload_all().await;
+                return Ok(());
+            }
+            (KeyCode::Esc, _) if typing && matches!(
+                self.active_panel,
+                Panel::MealPlan | Panel::Checklists | Panel::Quarantine | Panel::ThemeEditor | Panel::Habits
+                    | Panel::Export
+            ) => {
+                // Cancel the edit but keep the overlay open
+                self.ui.input_mode = InputMode::Normal;
+                return Ok(());
+            }
+            (KeyCode::Esc, _) if self.active_panel == Panel::TaskEdit => {
+                self.task_edit     = None;
+                self.active_panel  = Panel::TaskList;
+                self.ui.input_mode = InputMode::Normal;
+                return Ok(());
+            }
+            (KeyCode::Esc, _) if self.active_panel == Panel::Planning => {
+                self.planning     = None;
+                self.active_panel = Panel::Calendar;
+                return Ok(());
+            }
+            (KeyCode::Esc, _) if self.active_panel == Panel::Palette => {
+                self.close_palette();
+                return Ok(());
+            }
+            (KeyCode::Esc, _) if self.active_panel == Panel::SendTo => {
+                if let Some(s) = self.send_to.take() { self.active_panel = s.from; }
+                return Ok(());
+            }
+            (KeyCode::Esc, _) if self.active_panel == Panel::Confirm => {
+                if let Some(c) = self.confirm.take() { self.active_panel = c.from; }
+                return Ok(());
+            }
+            // Backing out of a batch keeps the selection; Esc again drops it
+            (KeyCode::Esc, _) if self.active_panel == Panel::Bulk => {
+                if let Some(bulk) = self.bulk.take() { self.active_panel = bulk.from; }
+                self.ui.input_mode = InputMode::Normal;
+                return Ok(());
+            }
+            (KeyCode::Esc, _) if self.moving.is_some() => {
+                self.moving        = None;
+                self.active_panel  = Panel::EventList;
+                self.ui.input_mode = InputMode::Normal;
+                self.sync_status   = "Move cancelled".into();
+                return Ok(());
+            }
+            (KeyCode::Esc, _) if self.ui.visual.is_some() => {
+                self.ui.visual   = None;
+                self.sync_status = String::new();
+                return Ok(());
+            }
+            (KeyCode::Esc, _) => {
+                // Dismissing the zone question is answering "keep"
+                if self.active_panel == Panel::ZoneChange { self.keep_zone(); }
+                // Leaving the editor without saving drops the preview
+                if let Some(original) = self.ui.theme_original.take() {
+                    self.theme = original;
+                }
+                self.active_panel       = Panel::Calendar;
+                self.ui.input_mode      = InputMode::Normal;
+                self.ui.event_form_step = EventFormStep::Title;
+                self.ui.count           = None;
+                return Ok(());
+            }
+            _ => {}
+        }
+
+        let panel = self.active_panel.clone();
+        // The compact agenda moves between events and tasks itself; the
+        // rest of their keys act on whichever is selected
+        let agenda     = self.config.ui.compact && matches!(panel, Panel::EventList | Panel::TaskList);
+        let agenda_row = if agenda { self.agenda_cursor() } else { None };
+        if matches!(panel, Panel::EventList | Panel::TaskList) && self.key_visual(key).await {
+            return Ok(());
+        }
+        if agenda && self.key_agenda(key) {
+            return Ok(());
+        }
+        if panel == Panel::Calendar && self.moving.is_some() && self.key_moving(key).await? {
+            return Ok(());
+        }
+        match panel {
+            Panel::Calendar     => self.key_calendar(key).await?,
+            Panel::EventList    => self.key_events(key).await?,
+            Panel::TaskList     => self.key_tasks(key).await?,
+            Panel::EventDetail
+            | Panel::TaskDetail => self.key_form(key).await?,
+            Panel::Onboarding   => self.key_onboarding(key).await,
+            Panel::MealPlan     => self.key_meals(key).await?,
+            Panel::Checklists   => self.key_checklists(key).await?,
+            Panel::IdealWeek    => self.key_ideal_week(key).await?,
+            Panel::Quarantine   => self.key_quarantine(key).await?,
+            Panel::ThemeEditor  => self.key_theme_editor(key),
+            Panel::ThemePicker  => self.key_theme_picker(key),
+            Panel::Holidays     => self.key_holidays(key),
+            Panel::Logs         => self.key_logs(key),
+            Panel::SyncLog      => self.key_sync_log(key),
+            Panel::Reports      => self.key_reports(key).await,
+            Panel::Habits       => self.key_habits(key).await?,
+            Panel::Export       => self.key_export(key).await?,
+            Panel::EventInfo    => self.key_event_info(key).await?,
+            Panel::ZoneChange   => self.key_zone_change(key).await?,
+            Panel::Bulk         => self.key_bulk(key).await?,
+            Panel::Confirm      => self.key_confirm(key).await?,
+            Panel::Dedupe       => self.key_dedupe(key).await?,
+            Panel::Plugins      => self.key_plugins(key),
+            Panel::SendTo       => self.key_send_to(key),
+            Panel::Palette      => self.key_palette(key),
+            Panel::GoTo         => self.key_go_to(key).await,
+            Panel::TaskEdit     => self.key_task_edit(key).await?,
+            Panel::Planning     => self.key_planning(key).await?,
+            Panel::Help         => self.key_help(key),
+            Panel::Stats
+            | Panel::Today      => {}
+        }
+        // A completed task re-sorts and a deleted event goes, so the
+        // selection stays on the same agenda row rather than the same index
+        if let Some(row) = agenda_row {
+            let on_list = matches!(self.active_panel, Panel::EventList | Panel::TaskList);
+            if on_list && self.agenda_cursor().is_none() {
+                self.focus_agenda(row.min(self.agenda().len().saturating_sub(1)));
+            }
+        }
+        // The calendar moved under a picked-up event
+        if self.moving.is_some() { self.moving_status(); }
+        Ok(())
+    }
+
+    // ── Mouse ─────────────────────────────────────────────────────────────────
+
+    /// Clicks select days and list rows (focusing their panel), the wheel
+    /// scrolls whatever is under the pointer, and status-bar hints act like
+    /// their key. Ignored while typing.
+    async fn on_mouse(&mut self, m: MouseEvent) -> Result<()> {
+        if self.ui.input_mode == InputMode::Insert { return Ok(()); }
+        self.ui.leader = false;
+        let (w, h)  = crossterm::terminal::size()?;
+        let areas   = ui::layout(Rect::new(0, 0, w, h), &self.config.ui, self.habit_rows());
+        let overlay = !matches!(self.active_panel, Panel::Calendar | Panel::EventList | Panel::TaskList);
+        let (x, y)  = (m.column, m.row);
+        // A click picks one item; scrolling still extends a `v` selection.
+        // Clicking outside the calendar puts a picked-up event back.
+        if !overlay && matches!(m.kind, MouseEventKind::Down(_)) {
+            self.ui.visual = None;
+            if self.moving.is_some() && ui::panel_at(&areas, x, y) != Some(Panel::Calendar) {
+                self.moving        = None;
+                self.ui.input_mode = InputMode::Normal;
+                self.sync_status   = "Move cancelled".into();
+            }
+        }
+
+        match m.kind {
+            MouseEventKind::ScrollDown | MouseEventKind::ScrollUp => {
+                let code = if m.kind == MouseEventKind::ScrollDown { KeyCode::Down } else { KeyCode::Up };
+                if !overlay {
+                    let Some(panel) = ui::panel_at(&areas, x, y) else { return Ok(()) };
+                    self.active_panel = panel;
+                }
+                self.on_key(KeyEvent::new(code, KeyModifiers::NONE)).await?;
+            }
+            MouseEventKind::Down(MouseButton::Left) if overlay => {
+                // Read-only overlays close on click; the rest are keyboard-driven
+                if matches!(self.active_panel, Panel::Help | Panel::Stats | Panel::Today | Panel::EventInfo) {
+                    self.active_panel = Panel::Calendar;
+                }
+            }
+            MouseEventKind::Down(MouseButton::Left) if y == areas.status.y => {
+                if let Some(key) = ui::status_key_at(self, areas.status, x) {
+                    self.click_status_hint(key).await?;
+                }
+            }
+            MouseEventKind::Down(MouseButton::Left) => match ui::panel_at(&areas, x, y) {
+                Some(Panel::Calendar) => {
+                    let Some(date) = ui::calendar_date_at(self, areas.calendar, x, y) else {
+                        self.active_panel = Panel::Calendar;
+                        return Ok(());
+                    };
+                    if date == self.selected_date && self.moving.is_some() {
+                        self.drop_moving().await?;
+                    } else if date == self.selected_date && self.active_panel == Panel::Calendar {
+                        // Second click on the selected day opens its events
+                        self.active_panel = Panel::EventList;
+                    } else {
+                        self.active_panel = Panel::Calendar;
+                        self.shift_day((date - self.selected_date).num_days()).await;
+                        if let Some(m) = self.moving.as_mut() {
+                            m.typed            = None;
+                            self.ui.input_mode = InputMode::Normal;
+                        }
+                        self.moving_status();
+                    }
+                }
+                Some(Panel::EventList) if self.config.ui.compact => {
+                    let row = ui::agenda_index_at(self, areas.events, y).or(self.agenda_cursor());
+                    self.focus_agenda(row.unwrap_or(0));
+                }
+                Some(Panel::EventList) => {
+                    let hit = ui::event_index_at(self, areas.events, y);
+                    self.active_panel = Panel::EventList;
+                    if let Some(i) = hit { self.event_cursor = i; }
+                }
+                Some(Panel::TaskList) => {
+                    let hit = ui::task_index_at(self, areas.tasks, y);
+                    self.active_panel = Panel::TaskList;
+                    if let Some(i) = hit { self.task_cursor = i; }
+                }
+                _ => {}
+            },
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// A click on a status-bar hint: the key, sent to the panel it belongs to.
+    async fn click_status_hint(&mut self, key: &str) -> Result<()> {
+        let (code, mods, panel) = match key {
+            "n" | "N" | "[" | "]" | "t" | "Q" => {
+                (KeyCode::Char(key.chars().next().unwrap_or(' ')), KeyModifiers::NONE, Some(Panel::Calendar))
+            }
+            "Space" => (KeyCode::Char(' '), KeyModifiers::NONE, Some(Panel::TaskList)),
+            // Deleting needs the event already in focus, never a stray click
+            "d" if self.active_panel == Panel::EventList => (KeyCode::Char('d'), KeyModifiers::NONE, None),
+            "Tab"   => (KeyCode::Tab, KeyModifiers::NONE, None),
+            "?"     => (KeyCode::Char('?'), KeyModifiers::NONE, None),
+            "q"     => (KeyCode::Char('q'), KeyModifiers::NONE, None),
+            "^s"    => (KeyCode::Char('s'), KeyModifiers::CONTROL, None),
+            _       => return Ok(()),
+        };
+        if let Some(p) = panel { self.active_panel = p; }
+        self.on_key(KeyEvent::new(code, mods)).await
+    }
+
+    /// Selects `date`, with its month on the grid.
+    async fn go_to(&mut self, date: NaiveDate) {
+        self.selected_date = date;
+        self.view_month    = date.month();
+        self.view_year     = date.year();
+        self.refresh().await;
+    }
+
+    /// The `g` prompt: anything `calendar::parse_date` reads, from today.
+    /// What it can't read stays in the prompt to fix.
+    async fn key_go_to(&mut self, key: KeyEvent) {
+        match key.code {
+            KeyCode::Char(c)   => self.ui.goto_input.push(c),
+            KeyCode::Backspace => { self.ui.goto_input.pop(); }
+            KeyCode::Enter     => match parse_date(&self.ui.goto_input, Local::now().date_naive()) {
+                Some(date) => {
+                    self.ui.input_mode = InputMode::Normal;
+                    self.active_panel  = Panel::Calendar;
+                    self.sync_status   = String::new();
+                    self.go_to(date).await;
+                }
+                None => self.sync_status = format!("Can't read “{}” as a date", self.ui.goto_input.trim()),
+            },
+            _ => {}
+        }
+    }
+
+    async fn key_calendar(&mut self, key: crossterm::event::KeyEvent) -> Result<()> {
+        // Digits build a count for the motion after them (`3l`, `2]`, `25%`);
+        // any other key uses it up
+        if let KeyCode::Char(d @ '0'..='9') = key.code {
+            if d != '0' || self.ui.count.is_some() {
+                let count     = self.ui.count.unwrap_or(0) * 10 + d.to_digit(10).unwrap_or(0);
+                self.ui.count = Some(count.min(MAX_COUNT));
+                return Ok(());
+            }
+        }
+        let count = self.ui.count.take();
+        let n     = count.unwrap_or(1) as i64;
+        match key.code {
+            KeyCode::Right | KeyCode::Char('l') => self.shift_day(n).await,
+            KeyCode::Left  | KeyCode::Char('h') => self.shift_day(-n).await,
+            KeyCode::Down  | KeyCode::Char('j') => self.shift_day(7 * n).await,
+            KeyCode::Up    | KeyCode::Char('k') => self.shift_day(-7 * n).await,
+            KeyCode::Char(']') => self.shift_view(n).await,
+            KeyCode::Char('[') => self.shift_view(-n).await,
+            KeyCode::Char('0') => self.jump_in_month(0).await,
+            KeyCode::Char('$') => self.jump_in_month(100).await,
+            KeyCode::Char('%') => self.jump_in_month(count.unwrap_or(50).min(100)).await,
+            KeyCode::Char('t') => self.go_to(Local::now().date_naive()).await,
+            KeyCode::Char('g') => {
+                self.ui.goto_input.clear();
+                self.ui.input_mode = InputMode::Insert;
+                self.active_panel  = Panel::GoTo;
+            }
+            KeyCode::Enter | KeyCode::Tab if self.config.ui.compact => self.focus_agenda(0),
+            KeyCode::Enter => self.active_panel = Panel::EventList,
+            KeyCode::Tab   => self.active_panel = Panel::TaskList,
+            KeyCode::Char('n') => {
+                self.ui.new_event_title.clear();
+                self.ui.event_form_step = EventFormStep::Title;
+                self.ui.event_start_h   = 9;
+                self.ui.event_start_m   = 0;
+                self.ui.event_end_h     = 10;
+                self.ui.event_end_m     = 0;
+                self.ui.time_field      = TimeField::Hour;
+                self.ui.event_lead      = 0;
+                self.ui.event_tag.clear();
+                self.ui.event_color     = 0;
+                self.ui.event_guests.clear();
+                self.ui.guest_check     = GuestCheck::NotChecked;
+                self.ui.event_conflicts = None;
+                self.ui.input_mode      = InputMode::Insert;
+                self.active_panel       = Panel::EventDetail;
+            }
+            KeyCode::Char('N') => {
+                self.ui.new_task_title.clear();
+                self.ui.input_mode = InputMode::Insert;
+                self.active_panel  = Panel::TaskDetail;
+            }
+            KeyCode::Char('S') => {
+                self.load_stats().await;
+                self.active_panel = Panel::Stats;
+            }
+            KeyCode::Char('G') => {
+                self.load_digest().await;
+                self.active_panel = Panel::Today;
+            }
+            KeyCode::Char('A') => self.open_onboarding(),
+            KeyCode::Char('D') if self.sync.is_some() => self.ask(ConfirmAction::Disconnect).await?,
+            KeyCode::Char('I') if self.sync.is_some() => self.cycle_sync_interval().await,
+            KeyCode::Char('L') => self.open_checklists().await,
+            KeyCode::Char('W') => {
+                self.ui.plan_cursor = 0;
+                self.active_panel   = Panel::IdealWeek;
+                self.load_week_plan().await;
+            }
+            KeyCode::Char('Q') => {
+                self.quarantine       = self.db.quarantined().await.unwrap_or_default();
+                self.ui.review_cursor = 0;
+                self.active_panel     = Panel::Quarantine;
+            }
+            KeyCode::Char('X') => self.open_export(export::Scope::Day),
+            KeyCode::Char('U') => self.open_dedupe().await,
+            KeyCode::Char('p') => self.open_planning().await,
+            KeyCode::Char(' ') => self.ui.leader = true,
+            KeyCode::Char('C') if self.plugins.panels.is_empty() => {
+                self.sync_status = "No custom panels — add [[panels]] to config.toml".into();
+            }
+            KeyCode::Char('C') => {
+                self.ui.plugin_cursor = 0;
+                self.active_panel     = Panel::Plugins;
+            }
+            KeyCode::Char('B') => {
+                self.ui.habit_cursor = 0;
+                self.ui.input_mode   = InputMode::Normal;
+                self.active_panel    = Panel::Habits;
+            }
+            KeyCode::Char('H') => {
+                self.report_week  = week_start(self.selected_date);
+                self.load_report().await;
+                self.active_panel = Panel::Reports;
+            }
+            KeyCode::Char('O') => {
+                self.ui.log_scroll = 0;
+                self.reload_logs();
+                self.active_panel  = Panel::Logs;
+            }
+            KeyCode::Char('Y') => {
+                self.ui.sync_log_scroll = 0;
+                self.reload_sync_log().await;
+                self.active_panel       = Panel::SyncLog;
+            }
+            KeyCode::Char('M') if self.config.ui.meal_plan => {
+                self.ui.meal_slot = 0;
+                self.active_panel = Panel::MealPlan;
+            }
+            KeyCode::Char('T') => {
+                self.ui.theme_original = Some(self.theme.clone());
+                self.ui.theme_cursor   = self.themes.iter().position(|t| t.name == self.theme.name).unwrap_or(0);
+                self.active_panel      = Panel::ThemePicker;
+            }
+            KeyCode::Char('E') => {
+                self.ui.theme_original = Some(self.theme.clone());
+                self.open_theme_editor();
+            }
+            KeyCode::Char('V') => {
+                self.ui.holiday_cursor = 0;
+                self.active_panel      = Panel::Holidays;
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    // ── Habits ────────────────────────────────────────────────────────────────
+
+    async fn reload_habits(&mut self) {
+        self.habits          = self.db.habits().await.unwrap_or_default();
+        self.habit_checks    = self.db.habit_checks().await.unwrap_or_default();
+        self.ui.habit_cursor = self.ui.habit_cursor.min(self.habits.len().saturating_sub(1));
+    }
+
+    /// The habits due today, as numbered in the strip.
+    pub fn habits_today(&self) -> Vec<&Habit> {
+        let today = Local::now().date_naive();
+        self.habits.iter().filter(|h| habits::due(h, today)).take(9).collect()
+    }
+
+    /// Height of the habits strip's contents; 0 hides it.
+    pub fn habit_rows(&self) -> u16 { self.habits_today().len() as u16 }
+
+    async fn toggle_habit(&mut self, h: &Habit, date: NaiveDate) -> Result<()> {
+        let done = self.db.toggle_habit(&h.id, date).await?;
+        self.reload_habits().await;
+        let streak = habits::streak(h, &self.habit_checks, Local::now().date_naive());
+        self.sync_status = match (done, streak) {
+            (true, n) if n > 1 => format!("✓ {} — {n} in a row", h.name),
+            (true, _)          => format!("✓ {}", h.name),
+            (false, _)         => format!("{} unchecked", h.name),
+        };
+        Ok(())
+    }
+
+    /// The `B` overlay lists every habit; Space toggles it for the selected
+    /// day, so a missed check-in can be filled in afterwards.
+    async fn key_habits(&mut self, key: crossterm::event::KeyEvent) -> Result<()> {
+        if self.ui.input_mode == InputMode::Insert {
+            match key.code {
+                KeyCode::Char(c)   => self.ui.habit_input.push(c),
+                KeyCode::Backspace => { self.ui.habit_input.pop(); }
+                KeyCode::Enter => {
+                    let (name, schedule) = habits::split_schedule(&std::mem::take(&mut self.ui.habit_input));
+                    self.ui.input_mode = InputMode::Normal;
+                    if name.is_empty() { return Ok(()); }
+                    self.db.add_habit(&name, &schedule.label()).await?;
+                    self.reload_habits().await;
+                    self.ui.habit_cursor = self.habits.len().saturating_sub(1);
+                    self.sync_status     = format!("Habit added: {name} ({})", schedule.label());
+                }
+                _ => {}
+            }
+            return Ok(());
+        }
+
+        let habit = self.habits.get(self.ui.habit_cursor).cloned();
+        match key.code {
+            KeyCode::Down | KeyCode::Char('j') => {
+                self.ui.habit_cursor = (self.ui.habit_cursor + 1).min(self.habits.len().saturating_sub(1));
+            }
+            KeyCode::Up | KeyCode::Char('k') => self.ui.habit_cursor = self.ui.habit_cursor.saturating_sub(1),
+            KeyCode::Char('n') | KeyCode::Char('a') => {
+                self.ui.habit_input.clear();
+                self.ui.input_mode = InputMode::Insert;
+            }
+            KeyCode::Char(' ') | KeyCode::Enter => {
+                if let Some(h) = habit { self.toggle_habit(&h, self.selected_date).await?; }
+            }
+            KeyCode::Char('x') | KeyCode::Delete => {
+                if let Some(h) = habit {
+                    self.db.delete_habit(&h.id).await?;
+                    self.reload_habits().await;
+                    self.sync_status = format!("Habit removed: {}", h.name);
+                }
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    // ── Compact agenda ────────────────────────────────────────────────────────
+
+    /// The selected day's events and due tasks, for the compact layout.
+    pub fn agenda(&self) -> Vec<agenda::Item> {
+        agenda::items(&self.events, &self.tasks, self.selected_date, Local::now().date_naive())
+    }
+
+    /// How many of the day's events lead it in the pinned band: its all-day
+    /// ones, which then sort first; none unless pinning.
+    pub fn pinned_events(&self) -> usize {
+        if !self.pin_all_day { return 0; }
+        self.events.iter().take_while(|e| e.all_day).count()
+    }
+
+    /// The same for the compact agenda, which puts all-day events first
+    /// anyway.
+    pub fn pinned_agenda(&self, items: &[agenda::Item]) -> usize {
+        if !self.pin_all_day { return 0; }
+        items.iter().take_while(|i| matches!(i, agenda::Item::Event(e) if self.events[*e].all_day)).count()
+    }
+
+    async fn toggle_pin_all_day(&mut self) {
+        // Keep the same event selected as the order changes under it
+        let selected = self.events.get(self.event_cursor).map(|e| e.id.clone());
+        self.pin_all_day = !self.pin_all_day;
+        self.reload_data().await;
+        if let Some(i) = selected.and_then(|id| self.events.iter().position(|e| e.id == id)) {
+            self.event_cursor = i;
+        }
+        self.sync_status = if self.pin_all_day {
+            "All-day events and holidays pinned above the day".into()
+        } else {
+            "All-day events back in time order".into()
+        };
+    }
+
+    /// `V`: a row per `[holidays]` country to show or hide it, then one to
+    /// dim them all, each flipped with Space or Enter, for this session.
+    fn key_holidays(&mut self, key: crossterm::event::KeyEvent) {
+        let countries: Vec<String> = self.holidays.countries().into_iter().map(str::to_owned).collect();
+        match key.code {
+            KeyCode::Down | KeyCode::Char('j') => self.ui.holiday_cursor = (self.ui.holiday_cursor + 1).min(countries.len()),
+            KeyCode::Up   | KeyCode::Char('k') => self.ui.holiday_cursor = self.ui.holiday_cursor.saturating_sub(1),
+            KeyCode::Char(' ') | KeyCode::Enter => match countries.get(self.ui.holiday_cursor) {
+                Some(c) => {
+                    let before = self.hide_holidays.len();
+                    self.hide_holidays.retain(|h| !h.eq_ignore_ascii_case(c));
+                    if self.hide_holidays.len() == before { self.hide_holidays.push(c.clone()); }
+                    self.reload_holidays();
+                }
+                None => self.dim_holidays = !self.dim_holidays,
+            },
+            _ => {}
+        }
+    }
+
+    /// Whether `country`'s holidays are on the calendar.
+    pub fn holidays_shown(&self, country: &str) -> bool {
+        !self.hide_holidays.iter().any(|h| h.eq_ignore_ascii_case(country))
+    }
+
+    /// Off → 1 → 5 → 15 → 60 min → off, for this session (`[sync]` sets
+    /// where it starts).
+    async fn cycle_sync_interval(&mut self) {
+        const STEPS: [u64; 4] = [60, 300, 900, 3600];
+        self.sync_every = match self.sync_every {
+            None    => Some(STEPS[0]),
+            Some(s) => STEPS.into_iter().find(|&n| n > s),
+        };
+        if let Some(ref w) = self.sync { w.set_interval(self.sync_every).await; }
+        self.sync_status = match self.sync_every {
+            Some(s) => format!("↻ Auto-sync every {} min this session", s / 60),
+            None    => "↻ Auto-sync off this session — Ctrl+s syncs".into(),
+        };
+    }
+
+    /// The agenda row the event or task focus is on.
+    pub fn agenda_cursor(&self) -> Option<usize> {
+        let want = match self.active_panel {
+            Panel::EventList => agenda::Item::Event(self.event_cursor),
+            Panel::TaskList  => agenda::Item::Task(self.task_cursor),
+            _                => return None,
+        };
+        self.agenda().iter().position(|i| *i == want)
+    }
+
+    /// Focuses agenda row `row`: the events panel for an event, the tasks
+    /// panel for a task.
+    fn focus_agenda(&mut self, row: usize) {
+        match self.agenda().get(row).copied() {
+            Some(agenda::Item::Event(i)) => { self.active_panel = Panel::EventList; self.event_cursor = i; }
+            Some(agenda::Item::Task(i))  => { self.active_panel = Panel::TaskList;  self.task_cursor  = i; }
+            None                         => self.active_panel = Panel::EventList,
+        }
+    }
+
+    /// Moving through the agenda; false for keys the event or task panel
+    /// handles.
+    fn key_agenda(&mut self, key: KeyEvent) -> bool {
+        let len = self.agenda().len();
+        let cur = self.agenda_cursor().unwrap_or(0);
+        let gg  = std::mem::take(&mut self.ui.pending_g);
+        if let Some(row) = list_jump(key.code, gg, cur, len, self.ui.agenda_rows.get()) {
+            self.focus_agenda(row);
+            return true;
+        }
+        match key.code {
+            KeyCode::Char('g')                 => self.ui.pending_g = true,
+            KeyCode::Down | KeyCode::Char('j') => self.focus_agenda((cur + 1).min(len.saturating_sub(1))),
+            KeyCode::Up   | KeyCode::Char('k') => self.focus_agenda(cur.saturating_sub(1)),
+            KeyCode::Tab                       => self.active_panel = Panel::Calendar,
+            _                                  => return false,
+        }
+        true
+    }
+
+    // ── Visual selection and batch actions ────────────────────────────────────
+
+    /// Where `v` anchors: the agenda row in the compact layout, else the
+    /// focused list's cursor.
+    fn visual_position(&self) -> usize {
+        if self.config.ui.compact { return self.agenda_cursor().unwrap_or(0); }
+        if self.active_panel == Panel::TaskList { self.task_cursor } else { self.event_cursor }
+    }
+
+    /// Events and tasks between the `v` anchor and the cursor, by index.
+    pub fn visual_selection(&self) -> (Vec<usize>, Vec<usize>) {
+        let Some(anchor) = self.ui.visual else { return (Vec::new(), Vec::new()) };
+        let cur      = self.visual_position();
+        let (lo, hi) = (anchor.min(cur), anchor.max(cur));
+        match self.active_panel {
+            Panel::EventList | Panel::TaskList if self.config.ui.compact => {
+                let rows = self.agenda();
+                let (mut events, mut tasks) = (Vec::new(), Vec::new());
+                for row in rows.iter().take(hi + 1).skip(lo) {
+                    match *row {
+                        agenda::Item::Event(i) => events.push(i),
+                        agenda::Item::Task(i)  => tasks.push(i),
+                    }
+                }
+                (events, tasks)
+            }
+            Panel::EventList => ((lo..=hi).filter(|&i| i < self.events.len()).collect(), Vec::new()),
+            Panel::TaskList  => (Vec::new(), (lo..=hi).filter(|&i| i < self.tasks.len()).collect()),
+            _                => (Vec::new(), Vec::new()),
+        }
+    }
+
+    /// `v` and, while selecting, the batch keys. Moving keys fall through
+    /// to the list and extend the selection; anything else is swallowed so
+    /// a stray key can't act on the one item under the cursor. True when
+    /// handled here.
+    async fn key_visual(&mut self, key: KeyEvent) -> bool {
+        let selecting = self.ui.visual.is_some();
+        match key.code {
+            KeyCode::Char('v') if selecting => {
+                self.ui.visual   = None;
+                self.sync_status = String::new();
+            }
+            KeyCode::Char('v') => {
+                self.ui.visual   = Some(self.visual_position());
+                self.sync_status = "-- VISUAL --  j/k extend  d delete  m move  c calendar/list  x complete".into();
+            }
+            _ if !selecting => return false,
+            KeyCode::Char('d') | KeyCode::Delete   => self.open_bulk(BulkAction::Delete).await,
+            KeyCode::Char('m')                     => self.open_bulk(BulkAction::Move).await,
+            KeyCode::Char('c')                     => self.open_bulk(BulkAction::Calendar).await,
+            KeyCode::Char('x') | KeyCode::Char(' ') => self.open_bulk(BulkAction::Complete).await,
+            KeyCode::Tab => {
+                self.ui.visual = None;
+                return false;
+            }
+            KeyCode::Up | KeyCode::Down | KeyCode::PageUp | KeyCode::PageDown | KeyCode::Home | KeyCode::End
+            | KeyCode::Char('j' | 'k' | 'g' | 'G') => return false,
+            _ => {}
+        }
+        true
+    }
+
+    async fn open_bulk(&mut self, action: BulkAction) {
+        let (ev_idx, task_idx) = self.visual_selection();
+        let events: Vec<DbEvent> = ev_idx.iter().filter_map(|&i| self.events.get(i).cloned()).collect();
+        let tasks: Vec<Task>     = task_idx.iter().filter_map(|&i| self.tasks.get(i).cloned()).collect();
+
+        let mut targets = Vec::new();
+        match action {
+            BulkAction::Complete if tasks.is_empty() => {
+                self.sync_status = "No tasks selected to complete".into();
+                return;
+            }
+            BulkAction::Calendar => {
+                // Events go between calendars; a selection of only tasks
+                // goes between task lists; never onto a pull-only one
+                let mut ids = match (&self.config.google, events.is_empty()) {
+                    (Some(g), false) => g.calendar_ids.clone(),
+                    (Some(g), true)  => g.task_list_ids.clone(),
+                    (None, _)        => Vec::new(),
+                };
+                if let Some(ref o) = self.config.outlook {
+                    let remote = if events.is_empty() { &o.task_list_ids } else { &o.calendar_ids };
+                    ids.extend(remote.iter().map(|id| format!("{OUTLOOK_PREFIX}{id}")));
+                }
+                if let (Some(ref t), true) = (&self.config.todoist, events.is_empty()) {
+                    ids.extend(t.projects.iter().map(|id| format!("{TODOIST_PREFIX}{id}")));
+                }
+                if let (Some(ref t), true) = (&self.config.taskwarrior, events.is_empty()) {
+                    ids.extend(t.projects.iter().map(|p| format!("{TASKWARRIOR_PREFIX}{p}")));
+                }
+                let pushes = |id: &String| self.config.google.as_ref().is_none_or(|g| g.direction(id).pushes());
+                for id in ids.into_iter().filter(pushes) {
+                    let name = self.db.calendar_name(&id).await.ok().flatten();
+                    targets.push((id.clone(), name.unwrap_or(id)));
+                }
+                if targets.len() < 2 {
+                    self.sync_status = "Only one calendar or task list is synced — nothing to move to".into();
+                    return;
+                }
+            }
+            _ => {}
+        }
+        if events.is_empty() && tasks.is_empty() { return; }
+
+        let typing = action == BulkAction::Move;
+        self.bulk = Some(Bulk {
+            action, events, tasks, targets,
+            input:  if typing { self.selected_date.format("%Y-%m-%d").to_string() } else { String::new() },
+            target: 0,
+            from:   self.active_panel.clone(),
+        });
+        self.ui.input_mode = if typing { InputMode::Insert } else { InputMode::Normal };
+        self.active_panel  = Panel::Bulk;
+    }
+
+    async fn key_bulk(&mut self, key: KeyEvent) -> Result<()> {
+        let Some(bulk) = self.bulk.as_mut() else { return Ok(()) };
+        match key.code {
+            KeyCode::Enter => self.apply_bulk().await?,
+            KeyCode::Tab if !bulk.targets.is_empty() => bulk.target = (bulk.target + 1) % bulk.targets.len(),
+            KeyCode::Char(c) if bulk.action == BulkAction::Move => bulk.input.push(c),
+            KeyCode::Backspace => { bulk.input.pop(); }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// Applies the confirmed batch, then refreshes and pushes once.
+    async fn apply_bulk(&mut self) -> Result<()> {
+        let Some(bulk) = self.bulk.as_ref() else { return Ok(()) };
+        let date = match bulk.action {
+            BulkAction::Move => match parse_date(&bulk.input, self.selected_date) {
+                Some(d) => Some(d),
+                None    => {
+                    self.sync_status = format!("✗ Not a date: {}", bulk.input.trim());
+                    return Ok(());
+                }
+            },
+            _ => None,
+        };
+        let Some(bulk) = self.bulk.take() else { return Ok(()) };
+        let now = chrono::Utc::now();
+        let mut skipped = 0;
+
+        for ev in &bulk.events {
+            let mut e = ev.clone();
+            match bulk.action {
+                BulkAction::Delete   => e.deleted = true,
+                BulkAction::Move     => {
+                    let days = Duration::days(date.map_or(0, |d| (d - e.start.date_naive()).num_days()));
+                    e.start += days;
+                    e.end   += days;
+                }
+                BulkAction::Calendar => {
+                    let target = &bulk.targets[bulk.target].0;
+                    if e.is_local_layer() || e.calendar_id.as_deref().unwrap_or("primary") == target {
+                        skipped += 1;
+                        continue;
+                    }
+                    // Google moves an event between calendars as a delete
+                    // there and an insert here
+                    if e.sync_id.is_some() {
+                        let mut gone = e.clone();
+                        gone.deleted    = true;
+                        gone.dirty      = true;
+                        gone.updated_at = now;
+                        self.db.upsert_event(&gone).await?;
+                        let guests = self.db.attendees(&e.id).await?;
+                        e.id      = uuid::Uuid::new_v4().to_string();
+                        e.sync_id = None;
+                        e.etag    = None;
+                        // Invited afresh from the new calendar; the answers were to the old event
+                        let emails: Vec<String> = guests.into_iter().filter(|g| !g.is_self).map(|g| g.email).collect();
+                        if !emails.is_empty() { self.db.set_attendees(&e.id, &emails).await?; }
+                    }
+                    e.calendar_id = Some(target.clone());
+                }
+                BulkAction::Complete => continue,
+            }
+            e.dirty      = !e.is_local_layer();
+            e.updated_at = now;
+            self.db.upsert_event(&e).await?;
+        }
+
+        for task in &bulk.tasks {
+            let mut t = task.clone();
+            match bulk.action {
+                BulkAction::Delete   => t.deleted = true,
+                BulkAction::Move     => t.due = date.map(due_at),
+                // Calendars are for events when the selection has any
+                BulkAction::Calendar if !bulk.events.is_empty() => continue,
+                BulkAction::Calendar => {
+                    let target = &bulk.targets[bulk.target].0;
+                    if t.task_list_id.as_deref().unwrap_or("@default") == target {
+                        skipped += 1;
+                        continue;
+                    }
+                    // Same for tasks between lists
+                    if t.sync_id.is_some() {
+                        let mut gone = t.clone();
+                        gone.deleted    = true;
+                        gone.dirty      = true;
+                        gone.updated_at = now;
+                        self.db.upsert_task(&gone).await?;
+                        t.id      = uuid::Uuid::new_v4().to_string();
+                        t.sync_id = None;
+                    }
+                    t.task_list_id = Some(target.clone());
+                }
+                BulkAction::Complete if t.completed => continue,
+                BulkAction::Complete => t.completed = true,
+            }
+            t.dirty      = true;
+            t.updated_at = now;
+            self.db.upsert_task(&t).await?;
+            if t.completed && t.repeat.is_some() && bulk.action == BulkAction::Complete {
+                self.repeat_task(&t).await?;
+            }
+        }
+
+        let what = bulk.describe();
+        let mut status = match bulk.action {
+            BulkAction::Delete   => format!("✓ Deleted {what}"),
+            BulkAction::Move     => format!("✓ Moved {what} to {}", date.map_or_else(String::new, |d| d.format("%a %b %-d").to_string())),
+            BulkAction::Calendar => format!("✓ Moved {what} to {}", bulk.targets[bulk.target].1),
+            BulkAction::Complete => format!("✓ Completed {what}"),
+        };
+        if skipped > 0 { status.push_str(&format!(" ({skipped} already there or local, left alone)")); }
+
+        self.ui.visual     = None;
+        self.ui.input_mode = InputMode::Normal;
+        self.active_panel  = bulk.from;
+        self.reload_data().await;
+        if let Some(ref w) = self.sync { w.push_dirty().await; }
+        self.sync_status = status;
+        Ok(())
+    }
+
+    // ── Confirmation ──────────────────────────────────────────────────────────
+
+    /// Asks before `action`, or just does it with `[ui] skip_confirm`.
+    async fn ask(&mut self, action: ConfirmAction) -> Result<()> {
+        if self.config.ui.skip_confirm {
+            return self.perform(action).await;
+        }
+        self.confirm      = Some(Confirm { action, from: self.active_panel.clone() });
+        self.active_panel = Panel::Confirm;
+        Ok(())
+    }
+
+    async fn key_confirm(&mut self, key: KeyEvent) -> Result<()> {
+        match key.code {
+            KeyCode::Char('y') | KeyCode::Enter => {
+                let Some(c) = self.confirm.take() else { return Ok(()) };
+                self.active_panel = c.from;
+                self.perform(c.action).await?;
+            }
+            KeyCode::Char('n') => {
+                if let Some(c) = self.confirm.take() { self.active_panel = c.from; }
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    async fn perform(&mut self, action: ConfirmAction) -> Result<()> {
+        let now = chrono::Utc::now();
+        match action {
+            ConfirmAction::DeleteEvent(mut e) => {
+                e.deleted    = true;
+                e.dirty      = true;
+                e.updated_at = now;
+                self.db.upsert_event(&e).await?;
+                self.reload_data().await;
+                if let Some(ref w) = self.sync { w.push_dirty().await; }
+            }
+            ConfirmAction::DeleteTask(mut t) => {
+                t.deleted    = true;
+                t.dirty      = true;
+                t.updated_at = now;
+                self.db.upsert_task(&t).await?;
+                self.reload_data().await;
+                if let Some(ref w) = self.sync { w.push_dirty().await; }
+            }
+            ConfirmAction::Disconnect => {
+                if let Some(ref w) = self.sync { w.revoke().await; }
+            }
+        }
+        Ok(())
+    }
+
+    // ── Plan my day ───────────────────────────────────────────────────────────
+
+    /// `p`: proposes blocks for the selected day's tasks (today's from now
+    /// on); nothing is written until `Enter`.
+    async fn open_planning(&mut self) {
+        let today = Local::now().date_naive();
+        if self.selected_date < today {
+            self.sync_status = "Pick today or a later day to plan".into();
+            return;
+        }
+        self.planning     = Some(Planning { date: self.selected_date, plan: DayPlan::default(), left_out: HashSet::new(), cursor: 0 });
+        self.active_panel = Panel::Planning;
+        self.replan().await;
+    }
+
+    /// Fits the tasks again, around what's on the calendar now.
+    async fn replan(&mut self) {
+        let Some(date) = self.planning.as_ref().map(|p| p.date) else { return };
+        let (from, to) = day_bounds(date);
+        let events     = self.db.events_overlapping(from, to).await.unwrap_or_default();
+        let all_tasks  = self.db.all_tasks().await.unwrap_or_default();
+        // The `[focus]` working day, 09:00–17:00 if it doesn't parse
+        let hours = (
+            planner::parse_hm(&self.config.focus.day_start).unwrap_or(NaiveTime::from_hms_opt(9, 0, 0).unwrap_or_default()),
+            planner::parse_hm(&self.config.focus.day_end).unwrap_or(NaiveTime::from_hms_opt(17, 0, 0).unwrap_or_default()),
+        );
+        let now = Local::now().naive_local().and_utc();
+        let Some(p) = self.planning.as_mut() else { return };
+        p.plan   = planner::plan_day(&all_tasks, &events, date, Local::now().date_naive(), hours, now, &p.left_out);
+        p.cursor = p.cursor.min(p.plan.blocks.len().saturating_sub(1));
+    }
+
+    async fn key_planning(&mut self, key: KeyEvent) -> Result<()> {
+        let Some(p) = self.planning.as_mut() else { return Ok(()) };
+        match key.code {
+            KeyCode::Down | KeyCode::Char('j') => p.cursor = (p.cursor + 1).min(p.plan.blocks.len().saturating_sub(1)),
+            KeyCode::Up   | KeyCode::Char('k') => p.cursor = p.cursor.saturating_sub(1),
+            // Leaving one out frees its time for the rest
+            KeyCode::Char('x') | KeyCode::Char('d') => {
+                if let Some(b) = p.plan.blocks.get(p.cursor) {
+                    p.left_out.insert(b.task.id.clone());
+                    self.replan().await;
+                }
+            }
+            KeyCode::Enter | KeyCode::Char('y') => self.accept_plan().await?,
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// Puts the proposed blocks on the plan layer: on this machine only,
+    /// counted as busy by later plans.
+    async fn accept_plan(&mut self) -> Result<()> {
+        let Some(p) = self.planning.take() else { return Ok(()) };
+        for block in &p.plan.blocks {
+            let ev = planner::block_event(block);
+            if let Some(ref tag) = ev.tag { self.db.ensure_tag(tag).await?; }
+            self.db.upsert_event(&ev).await?;
+        }
+        self.active_panel = Panel::Calendar;
+        self.reload_data().await;
+        let n = p.plan.blocks.len();
+        self.sync_status = match n {
+            0 => "Nothing planned".into(),
+            1 => format!("✓ Planned 1 task on {}", p.date.format("%a %b %-d")),
+            n => format!("✓ Planned {n} tasks on {}", p.date.format("%a %b %-d")),
+        };
+        Ok(())
+    }
+
+    // ── Duplicates ────────────────────────────────────────────────────────────
+
+    async fn open_dedupe(&mut self) {
+        let events = match self.db.calendar_events().await {
+            Ok(v)  => v,
+            Err(e) => {
+                self.sync_status = format!("✗ {e}");
+                return;
+            }
+        };
+        let clusters = dedupe::clusters(&events);
+        if clusters.is_empty() {
+            self.sync_status = "✓ No likely duplicates".into();
+            return;
+        }
+        let mut calendars = HashMap::new();
+        for id in clusters.iter().flatten().filter_map(|&i| events[i].calendar_id.clone()) {
+            if calendars.contains_key(&id) { continue; }
+            if let Ok(Some(name)) = self.db.calendar_name(&id).await { calendars.insert(id, name); }
+        }
+        let picks = dedupe::suggest(&clusters[0].iter().map(|&i| events[i].clone()).collect::<Vec<_>>());
+        self.dedupe       = Some(Dedupe { events, clusters, cluster: 0, field: 0, picks, calendars });
+        self.active_panel = Panel::Dedupe;
+    }
+
+    async fn key_dedupe(&mut self, key: KeyEvent) -> Result<()> {
+        let Some(d) = self.dedupe.as_mut() else { return Ok(()) };
+        let members = d.clusters.get(d.cluster).map_or(1, Vec::len);
+        match key.code {
+            KeyCode::Down  | KeyCode::Char('j') => d.field = (d.field + 1).min(Field::ALL.len() - 1),
+            KeyCode::Up    | KeyCode::Char('k') => d.field = d.field.saturating_sub(1),
+            KeyCode::Right | KeyCode::Char('l') => d.picks[d.field] = (d.picks[d.field] + 1) % members,
+            KeyCode::Left  | KeyCode::Char('h') => d.picks[d.field] = (d.picks[d.field] + members - 1) % members,
+            KeyCode::Tab   | KeyCode::Char('n') => self.show_cluster(1),
+            KeyCode::BackTab | KeyCode::Char('p') => self.show_cluster(-1),
+            KeyCode::Enter => self.merge_dedupe().await?,
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// Steps to the next or previous group, starting over from the
+    /// suggested picks.
+    fn show_cluster(&mut self, by: isize) {
+        let Some(d) = self.dedupe.as_mut() else { return };
+        let n     = d.clusters.len() as isize;
+        d.cluster = (d.cluster as isize + by).rem_euclid(n.max(1)) as usize;
+        d.field   = 0;
+        d.picks   = dedupe::suggest(&d.group());
+    }
+
+    /// Writes the merged group, then looks again: what's left of the
+    /// others stays in review, on the group that came next.
+    async fn merge_dedupe(&mut self) -> Result<()> {
+        let Some(d) = self.dedupe.as_ref() else { return Ok(()) };
+        let group  = d.group();
+        if group.len() < 2 { return Ok(()); }
+        let merged = dedupe::merge(&group, &d.picks, Utc::now());
+        let at     = d.cluster;
+
+        for ev in &merged.dropped {
+            self.db.upsert_event(ev).await?;
+        }
+        self.db.upsert_event(&merged.kept).await?;
+        self.reload_data().await;
+        if let Some(ref w) = self.sync { w.push_dirty().await; }
+        self.sync_status = format!("✓ Merged {} into “{}”", merged.dropped.len() + 1, merged.kept.title);
+
+        let events   = self.db.calendar_events().await.unwrap_or_default();
+        let clusters = dedupe::clusters(&events);
+        let Some(d) = self.dedupe.as_mut() else { return Ok(()) };
+        if clusters.is_empty() {
+            self.dedupe       = None;
+            self.active_panel = Panel::Calendar;
+            self.sync_status.push_str(" — no likely duplicates left");
+            return Ok(());
+        }
+        d.cluster  = at.min(clusters.len() - 1);
+        d.events   = events;
+        d.clusters = clusters;
+        d.field    = 0;
+        d.picks    = dedupe::suggest(&d.group());
+        Ok(())
+    }
+
+    // ── Custom panels ─────────────────────────────────────────────────────────
+
+    /// Takes in finished runs, and while the overlay is open reruns the
+    /// panel shown once it's older than its `every`.
+    fn poll_plugins(&mut self) {
+        if self.plugins.poll() {
+            let len = self.plugins.states.get(self.ui.plugin_tab).map_or(0, |s| s.items.len());
+            self.ui.plugin_cursor = self.ui.plugin_cursor.min(len.saturating_sub(1));
+        }
+        if self.active_panel == Panel::Plugins && self.plugins.stale(self.ui.plugin_tab) {
+            self.plugins.refresh(self.ui.plugin_tab);
+        }
+    }
+
+    fn key_plugins(&mut self, key: KeyEvent) {
+        let panels = self.plugins.panels.len().max(1);
+        let items  = self.plugins.states.get(self.ui.plugin_tab).map_or(0, |s| s.items.len());
+        let tab    = match key.code {
+            KeyCode::Tab   | KeyCode::Right | KeyCode::Char('l') => Some((self.ui.plugin_tab + 1) % panels),
+            KeyCode::BackTab | KeyCode::Left | KeyCode::Char('h') => Some((self.ui.plugin_tab + panels - 1) % panels),
+            KeyCode::Char(c @ '1'..='9') => Some((c as usize - '1' as usize).min(panels - 1)),
+            KeyCode::Down | KeyCode::Char('j') => {
+                self.ui.plugin_cursor = (self.ui.plugin_cursor + 1).min(items.saturating_sub(1));
+                None
+            }
+            KeyCode::Up | KeyCode::Char('k') => {
+                self.ui.plugin_cursor = self.ui.plugin_cursor.saturating_sub(1);
+                None
+            }
+            KeyCode::Enter => {
+                let url = self.plugins.states.get(self.ui.plugin_tab)
+                    .and_then(|s| s.items.get(self.ui.plugin_cursor))
+                    .and_then(|i| i.url.clone());
+                if let Some(url) = url {
+                    let _ = open::that(&url);
+                    self.sync_status = format!("↗ {url}");
+                }
+                None
+            }
+            _ => None,
+        };
+        if let Some(tab) = tab {
+            self.ui.plugin_tab    = tab;
+            self.ui.plugin_cursor = 0;
+        }
+    }
+
+    // ── Send to ───────────────────────────────────────────────────────────────
+
+    /// `|`: with one action for the item, runs it; with more, lists them.
+    fn open_send_to(&mut self, subject: Result<Subject>) {
+        let subject = match subject {
+            Ok(s)  => s,
+            Err(e) => { self.sync_status = format!("✗ {e}"); return; }
+        };
+        let choices = self.actions.for_kind(subject.kind);
+        match choices[..] {
+            [] if self.actions.list.is_empty() => {
+                self.sync_status = "No actions — add [[actions]] to config.toml".into();
+            }
+            []  => self.sync_status = format!("No actions for a {}", subject.kind),
+            [i] => self.send(i, subject),
+            _   => {
+                self.ui.action_cursor = 0;
+                self.send_to          = Some(SendTo { subject, choices, from: self.active_panel.clone() });
+                self.active_panel     = Panel::SendTo;
+            }
+        }
+    }
+
+    fn send(&mut self, i: usize, subject: Subject) {
+        let name = self.actions.list.get(i).map_or("", |a| a.name.as_str());
+        self.sync_status = format!("↪ “{}” → {name}…", subject.title);
+        self.actions.send(i, subject);
+    }
+
+    fn key_send_to(&mut self, key: KeyEvent) {
+        let Some(send_to) = self.send_to.as_ref() else { return };
+        let last = send_to.choices.len().saturating_sub(1);
+        match key.code {
+            KeyCode::Down | KeyCode::Char('j') => self.ui.action_cursor = (self.ui.action_cursor + 1).min(last),
+            KeyCode::Up | KeyCode::Char('k')   => self.ui.action_cursor = self.ui.action_cursor.saturating_sub(1),
+            KeyCode::Char(c @ '1'..='9') => {
+                let n = c as usize - '1' as usize;
+                if n <= last { self.ui.action_cursor = n; self.pick_action(); }
+            }
+            KeyCode::Enter => self.pick_action(),
+            _ => {}
+        }
+    }
+
+    fn pick_action(&mut self) {
+        let Some(send_to) = self.send_to.take() else { return };
+        self.active_panel = send_to.from;
+        if let Some(&i) = send_to.choices.get(self.ui.action_cursor) {
+            self.send(i, send_to.subject);
+        }
+    }
+
+    // ── Moving an event ───────────────────────────────────────────────────────
+
+    /// While an event is picked up: the calendar moves as usual, `+` / `-`
+    /// start an offset and `g` a date, Enter drops it. Other keys are
+    /// swallowed so the calendar's own actions don't fire mid-move. True
+    /// when handled here.
+    async fn key_moving(&mut self, key: KeyEvent) -> Result<bool> {
+        let Some(moving) = self.moving.as_mut() else { return Ok(false) };
+        match (key.code, moving.typed.as_mut()) {
+            (KeyCode::Enter, _) => {
+                self.drop_moving().await?;
+                return Ok(true);
+            }
+            (KeyCode::Char(c @ ('+' | '-')), None) => moving.typed = Some(c.to_string()),
+            (KeyCode::Char('g'), None)              => moving.typed = Some(String::new()),
+            (KeyCode::Char(c), Some(typed))         => typed.push(c),
+            (KeyCode::Backspace, Some(typed)) if typed.is_empty() => moving.typed = None,
+            (KeyCode::Backspace, Some(typed))       => { typed.pop(); }
+            (KeyCode::Left | KeyCode::Right | KeyCode::Up | KeyCode::Down, _)
+            | (KeyCode::Char('h' | 'j' | 'k' | 'l' | '[' | ']' | 't'), None) => {
+                moving.typed       = None;
+                self.ui.input_mode = InputMode::Normal;
+                return Ok(false);
+            }
+            _ => {}
+        }
+        // Typing keeps `q`, `r` and the other global keys out of the date
+        self.ui.input_mode = if moving.typed.is_some() { InputMode::Insert } else { InputMode::Normal };
+        self.moving_status();
+        Ok(true)
+    }
+
+    /// Where the event would land: the typed date, offsets and weekdays
+    /// counted from the event's own day, or the day selected on the calendar.
+    fn moving_target(&self) -> Option<NaiveDate> {
+        let moving = self.moving.as_ref()?;
+        match moving.typed {
+            Some(ref typed) => parse_date(typed, moving.event.start.date_naive()),
+            None            => Some(self.selected_date),
+        }
+    }
+
+    fn moving_status(&mut self) {
+        let Some(ref moving) = self.moving else { return };
+        let target = match (self.moving_target(), moving.typed.as_deref()) {
+            (Some(d), None)        => d.format("%a %b %-d").to_string(),
+            (Some(d), Some(typed)) => format!("{typed} = {}", d.format("%a %b %-d")),
+            (None, typed)          => format!("{}…", typed.unwrap_or_default()),
+        };
+        self.sync_status = format!(
+            "Moving “{}” → {target}   hjkl: pick a day  +1d / -1w: offset  g: date  Enter: drop  Esc: cancel",
+            moving.event.title,
+        );
+    }
+
+    /// Moves the picked-up event to its target day, keeping its times and
+    /// length, and goes to that day with it selected.
+    async fn drop_moving(&mut self) -> Result<()> {
+        let Some(target) = self.moving_target() else {
+            self.sync_status = "✗ Not a date — try +1d, -2w, next fri or mar 14".into();
+            return Ok(());
+        };
+        let Some(Moving { event, .. }) = self.moving.take() else { return Ok(()) };
+        self.ui.input_mode = InputMode::Normal;
+        let days = Duration::days((target - event.start.date_naive()).num_days());
+        if days.is_zero() {
+            self.active_panel = Panel::EventList;
+            self.sync_status  = "Already on that day".into();
+            return Ok(());
+        }
+
+        let mut e    = event;
+        e.start     += days;
+        e.end       += days;
+        e.dirty      = !e.is_local_layer();
+        e.updated_at = chrono::Utc::now();
+        self.db.upsert_event(&e).await?;
+
+        self.selected_date = target;
+        self.view_month    = target.month();
+        self.view_year     = target.year();
+        self.refresh().await;
+        self.event_cursor  = self.events.iter().position(|x| x.id == e.id).unwrap_or(0);
+        self.active_panel  = Panel::EventList;
+        if let Some(ref w) = self.sync { w.push_dirty().await; }
+        self.sync_status   = format!("✓ Moved “{}” to {}", e.title, target.format("%a %b %-d"));
+        Ok(())
+    }
+
+    // ── Event details ─────────────────────────────────────────────────────────
+
+    async fn open_event_info(&mut self) {
+        let Some(event) = self.events.get(self.event_cursor).cloned() else { return };
+        let calendar = match event.calendar_id.as_deref() {
+            Some(id) if !event.is_local_layer() => self.db.calendar_name(id).await.unwrap_or_default(),
+            _                                   => None,
+        };
+        let guests = self.db.attendees(&event.id).await.unwrap_or_default();
+        self.event_info   = Some(EventInfo { event, calendar, guests });
+        self.active_panel = Panel::EventInfo;
+    }
+
+    async fn key_event_info(&mut self, key: KeyEvent) -> Result<()> {
+        match key.code {
+            KeyCode::Enter     => self.active_panel = Panel::EventList,
+            KeyCode::Char('y') => self.respond(Rsvp::Accepted).await?,
+            KeyCode::Char('n') => self.respond(Rsvp::Declined).await?,
+            KeyCode::Char('m') => self.respond(Rsvp::Tentative).await?,
+            KeyCode::Char('o') => {
+                if let Some(ev) = self.event_info.as_ref().map(|i| i.event.clone()) { self.join_call(&ev); }
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// Answers the invitation shown; it reaches Google on the next push.
+    async fn respond(&mut self, response: Rsvp) -> Result<()> {
+        let Some(ref mut info) = self.event_info else { return Ok(()) };
+        if info.response().is_none() {
+            self.sync_status = "You're not a guest of this event".into();
+            return Ok(());
+        }
+        self.db.set_response(&info.event.id, response).await?;
+        info.guests = self.db.attendees(&info.event.id).await?;
+        self.sync_status = format!("✓ Replied {} to “{}”", response.label(), info.event.title);
+        if let Some(ref w) = self.sync { w.push_dirty().await; }
+        Ok(())
+    }
+
+    /// Opens the event's video call in the browser (or the Zoom / Teams app
+    /// that claims the link).
+    fn join_call(&mut self, ev: &DbEvent) {
+        let Some(url) = ev.meeting_url() else {
+            self.sync_status = format!("No call link on “{}”", ev.title);
+            return;
+        };
+        self.sync_status = match open::that(url) {
+            Ok(()) => format!("📹 Joining “{}” — {url}", ev.title),
+            Err(e) => format!("Couldn't open {url}: {e}"),
+        };
+    }
+
+    // ── Export ────────────────────────────────────────────────────────────────
+
+    fn open_export(&mut self, scope: export::Scope) {
+        self.ui.export_scope = scope;
+        self.ui.export_path  = export::default_path(scope, self.ui.export_format, self.selected_date);
+        self.ui.input_mode   = InputMode::Normal;
+        self.active_panel    = Panel::Export;
+    }
+
+    async fn key_export(&mut self, key: crossterm::event::KeyEvent) -> Result<()> {
+        if self.ui.input_mode == InputMode::Insert {
+            match key.code {
+                KeyCode::Char(c)   => self.ui.export_path.push(c),
+                KeyCode::Backspace => { self.ui.export_path.pop(); }
+                KeyCode::Enter     => self.ui.input_mode = InputMode::Normal,
+                _ => {}
+            }
+            return Ok(());
+        }
+
+        // A new scope or format gets a new default name; a typed path stays
+        let (scope, format) = (self.ui.export_scope, self.ui.export_format);
+        let typed = self.ui.export_path != export::default_path(scope, format, self.selected_date);
+        match key.code {
+            KeyCode::Char('s') => self.ui.export_scope  = scope.next(),
+            KeyCode::Char('f') => self.ui.export_format = format.next(),
+            KeyCode::Char('e') | KeyCode::Char('i') => { self.ui.input_mode = InputMode::Insert; return Ok(()); }
+            KeyCode::Enter => {
+                self.sync_status = match self.write_export().await {
+                    Ok(msg) => msg,
+                    Err(e)  => format!("✗ Export failed: {e}"),
+                };
+                self.active_panel = Panel::Calendar;
+                return Ok(());
+            }
+            _ => return Ok(()),
+        }
+        if !typed {
+            self.ui.export_path = export::default_path(self.ui.export_scope, self.ui.export_format, self.selected_date);
+        }
+        Ok(())
+    }
+
+    /// Writes the export overlay's choice; the status line says what went where.
+    async fn write_export(&self) -> Result<String> {
+        let scope  = self.ui.export_scope;
+        let (events, tasks) = match scope.days(self.selected_date) {
+            Some((from, to)) => {
+                let events = self.db.events_overlapping(day_bounds(from).0, day_bounds(to).1).await?;
+                (self.filter_tagged(events), Vec::new())
+            }
+            None => (Vec::new(), self.tasks.clone()),
+        };
+        let text = export::render(self.ui.export_format, &scope.describe(self.selected_date), &events, &tasks)?;
+        let path = export::expand(self.ui.export_path.trim());
+        std::fs::write(&path, text)?;
+        Ok(match scope {
+            export::Scope::Tasks => format!("✓ Exported {} tasks to {}", tasks.len(), path.display()),
+            _                    => format!("✓ Exported {} events to {}", events.len(), path.display()),
+        })
+    }
+
+    // ── Time report ───────────────────────────────────────────────────────────
+
+    async fn load_report(&mut self) {
+        let from = day_bounds(self.report_week).0;
+        let to   = day_bounds(add_days(self.report_week, 6).unwrap_or(self.report_week)).1;
+        let events = self.db.events_overlapping(from, to).await.unwrap_or_default();
+        self.report = timelog::week_report(&events, self.report_week, self.report_group);
+    }
+
+    async fn key_reports(&mut self, key: crossterm::event::KeyEvent) {
+        let step = match key.code {
+            KeyCode::Left  | KeyCode::Char('h') | KeyCode::Char('[') => -7,
+            KeyCode::Right | KeyCode::Char('l') | KeyCode::Char(']') => 7,
+            KeyCode::Char('g') => {
+                self.report_group = self.report_group.next();
+                0
+            }
+            _ => return,
+        };
+        self.report_week = add_days(self.report_week, step).unwrap_or(self.report_week);
+        self.load_report().await;
+    }
+
+    // ── Log viewer ────────────────────────────────────────────────────────────
+
+    /// Reads the tail of the log files; ui.log_scroll counts lines up from
+    /// the newest, so new lines keep the view where it was.
+    fn reload_logs(&mut self) {
+        match logs::read(self.ui.log_level, LOG_VIEW_LINES) {
+            Ok(lines) => self.log_lines = lines,
+            Err(e)    => self.sync_status = format!("✗ Can't read the log: {e}"),
+        }
+        self.ui.log_scroll = self.ui.log_scroll.min(self.log_lines.len().saturating_sub(1));
+    }
+
+    fn key_logs(&mut self, key: crossterm::event::KeyEvent) {
+        let top  = self.log_lines.len().saturating_sub(1);
+        let page = 10;
+        match key.code {
+            KeyCode::Up   | KeyCode::Char('k') => self.ui.log_scroll = (self.ui.log_scroll + 1).min(top),
+            KeyCode::Down | KeyCode::Char('j') => self.ui.log_scroll = self.ui.log_scroll.saturating_sub(1),
+            KeyCode::PageUp   => self.ui.log_scroll = (self.ui.log_scroll + page).min(top),
+            KeyCode::PageDown => self.ui.log_scroll = self.ui.log_scroll.saturating_sub(page),
+            KeyCode::Char('g') => self.ui.log_scroll = top,
+            KeyCode::Char('G') => self.ui.log_scroll = 0,
+            KeyCode::Char('l') => {
+                self.ui.log_level  = self.ui.log_level.next();
+                self.ui.log_scroll = 0;
+                self.reload_logs();
+                self.sync_status   = format!("Log: {} and above", self.ui.log_level.label());
+            }
+            _ => {}
+        }
+    }
+
+    async fn reload_sync_log(&mut self) {
+        match self.db.sync_log(SYNC_LOG_LINES).await {
+            Ok(mut entries) => {
+                entries.reverse();
+                self.sync_log = entries;
+            }
+            Err(e) => self.sync_status = format!("✗ Can't read the sync log: {e}"),
+        }
+        self.ui.sync_log_scroll = self.ui.sync_log_scroll.min(self.sync_log.len().saturating_sub(1));
+    }
+
+    fn key_sync_log(&mut self, key: crossterm::event::KeyEvent) {
+        let top    = self.sync_log.len().saturating_sub(1);
+        let page   = 10;
+        let scroll = &mut self.ui.sync_log_scroll;
+        match key.code {
+            KeyCode::Up   | KeyCode::Char('k') => *scroll = (*scroll + 1).min(top),
+            KeyCode::Down | KeyCode::Char('j') => *scroll = scroll.saturating_sub(1),
+            KeyCode::PageUp    => *scroll = (*scroll + page).min(top),
+            KeyCode::PageDown  => *scroll = scroll.saturating_sub(page),
+            KeyCode::Char('g') => *scroll = top,
+            KeyCode::Char('G') => *scroll = 0,
+            _ => {}
+        }
+    }
+
+    /// The draw clamps `help_scroll` to the last screenful.
+    fn key_help(&mut self, key: crossterm::event::KeyEvent) {
+        let first = self.ui.help_scroll.get();
+        let page  = self.ui.help_rows.get().max(1) as usize;
+        let first = match key.code {
+            KeyCode::Down | KeyCode::Char('j') => first.saturating_add(1),
+            KeyCode::Up   | KeyCode::Char('k') => first.saturating_sub(1),
+            KeyCode::PageDown  => first.saturating_add(page),
+            KeyCode::PageUp    => first.saturating_sub(page),
+            KeyCode::Char('g') => 0,
+            KeyCode::Char('G') => usize::MAX,
+            _ => return,
+        };
+        self.ui.help_scroll.set(first);
+    }
+
+    // ── Themes ────────────────────────────────────────────────────────────────
+
+    /// Moving through the list previews each theme; Enter keeps it and Esc
+    /// restores `ui.theme_original`.
+    fn key_theme_picker(&mut self, key: crossterm::event::KeyEvent) {
+        let last = self.themes.len().saturating_sub(1);
+        match key.code {
+            KeyCode::Down | KeyCode::Char('j') => self.ui.theme_cursor = (self.ui.theme_cursor + 1).min(last),
+            KeyCode::Up   | KeyCode::Char('k') => self.ui.theme_cursor = self.ui.theme_cursor.saturating_sub(1),
+            KeyCode::Enter => {
+                self.ui.theme_original = None;
+                self.active_panel      = Panel::Calendar;
+                self.sync_status = match self.theme.select() {
+                    Ok(())  => format!("Theme: {}", self.theme.name),
+                    Err(e)  => format!("✗ Theme not saved: {e}"),
+                };
+                return;
+            }
+            KeyCode::Char('e') => {
+                self.open_theme_editor();
+                return;
+            }
+            _ => return,
+        }
+        if let Some(t) = self.themes.get(self.ui.theme_cursor) {
+            self.theme = t.clone();
+        }
+    }
+
+    fn open_theme_editor(&mut self) {
+        self.ui.theme_field  = 0;
+        self.ui.theme_swatch = swatch_of(&ThemeConfig::palette(), self.theme.color(0), 0);
+        self.active_panel    = Panel::ThemeEditor;
+    }
+
+    /// Edits `self.theme` in place so the whole screen previews the change;
+    /// Esc restores `ui.theme_original`.
+    fn key_theme_editor(&mut self, key: crossterm::event::KeyEvent) {
+        let field = self.ui.theme_field;
+
+        if self.ui.input_mode == InputMode::Insert {
+            match key.code {
+                KeyCode::Char(c)   => self.ui.theme_input.push(c),
+                KeyCode::Backspace => { self.ui.theme_input.pop(); }
+                KeyCode::Enter if self.ui.theme_naming => {
+                    let name = self.ui.theme_input.trim().to_owned();
+                    if name.is_empty() { return; }
+                    self.theme.name = name;
+                    let saved = self.theme.save_custom().and_then(|path| self.theme.select().map(|()| path));
+                    match saved {
+                        Ok(path) => {
+                            self.themes            = ThemeConfig::all_themes();
+                            self.sync_status       = format!("✓ Saved theme to {}", path.display());
+                            self.ui.theme_original = None;
+                            self.ui.input_mode     = InputMode::Normal;
+                            self.active_panel      = Panel::Calendar;
+                        }
+                        Err(e) => self.sync_status = format!("✗ Theme not saved: {e}"),
+                    }
+                }
+                KeyCode::Enter => {
+                    if theme::is_color(&self.ui.theme_input) {
+                        self.theme.set_color(field, &self.ui.theme_input);
+                        self.ui.input_mode = InputMode::Normal;
+                    } else {
+                        self.sync_status = "Enter #rrggbb, an ANSI name like lightblue, 0–255 or default".into();
+                    }
+                }
+                _ => {}
+            }
+            return;
+        }
+
+        let palette = ThemeConfig::palette();
+        match key.code {
+            KeyCode::Down | KeyCode::Char('j') => {
+                self.ui.theme_field = (field + 1).min(theme::COLOR_FIELDS.len() - 1);
+                self.ui.theme_swatch = swatch_of(&palette, self.theme.color(self.ui.theme_field), self.ui.theme_swatch);
+            }
+            KeyCode::Up | KeyCode::Char('k') => {
+                self.ui.theme_field = field.saturating_sub(1);
+                self.ui.theme_swatch = swatch_of(&palette, self.theme.color(self.ui.theme_field), self.ui.theme_swatch);
+            }
+            // Moving through the palette applies the swatch straight away
+            KeyCode::Right | KeyCode::Char('l') | KeyCode::Left | KeyCode::Char('h') => {
+                let forward = matches!(key.code, KeyCode::Right | KeyCode::Char('l'));
+                let n       = palette.len();
+                self.ui.theme_swatch = if forward { (self.ui.theme_swatch + 1) % n } else { (self.ui.theme_swatch + n - 1) % n };
+                self.theme.set_color(field, &palette[self.ui.theme_swatch]);
+            }
+            KeyCode::Enter | KeyCode::Char('#') => {
+                self.ui.theme_input  = self.theme.color(field).to_owned();
+                self.ui.theme_naming = false;
+                self.ui.input_mode   = InputMode::Insert;
+            }
+            KeyCode::Char('u') => {
+                if let Some(original) = &self.ui.theme_original {
+                    self.theme.set_color(field, original.color(field));
+                }
+            }
+            KeyCode::Char('s') => {
+                let base = self.ui.theme_original.as_ref().map_or(&self.theme.name, |t| &t.name);
+                self.ui.theme_input  = format!("{base}-custom");
+                self.ui.theme_naming = true;
+                self.ui.input_mode   = InputMode::Insert;
+            }
+            _ => {}
+        }
+    }
+
+    async fn key_events(&mut self, key: crossterm::event::KeyEvent) -> Result<()> {
+        let gg = std::mem::take(&mut self.ui.pending_g);
+        if let Some(c) = list_jump(key.code, gg, self.event_cursor, self.events.len(), self.ui.event_rows.get()) {
+            self.event_cursor = c;
+            return Ok(());
+        }
+        match key.code {
+            KeyCode::Enter => self.open_event_info().await,
+            KeyCode::Char('X') => self.open_export(export::Scope::Day),
+            KeyCode::Char('|') => {
+                if let Some(ev) = self.events.get(self.event_cursor).cloned() {
+                    self.open_send_to(Subject::new("event", &ev.id, &ev.title, &ev));
+                }
+            }
+            KeyCode::Char('o') => {
+                if let Some(ev) = self.events.get(self.event_cursor).cloned() { self.join_call(&ev); }
+            }
+            KeyCode::Char('g') => self.ui.pending_g = true,
+            // Long jumps in an empty list: nothing to move, stay put
+            KeyCode::Char('G') | KeyCode::PageUp | KeyCode::PageDown | KeyCode::Home | KeyCode::End => {}
+            KeyCode::Down | KeyCode::Char('j') => {
+                if self.event_cursor + 1 < self.events.len() { self.event_cursor += 1; }
+            }
+            KeyCode::Up | KeyCode::Char('k') => {
+                self.event_cursor = self.event_cursor.saturating_sub(1);
+            }
+            KeyCode::Char('d') | KeyCode::Delete => {
+                if let Some(ev) = self.events.get(self.event_cursor).cloned() {
+                    self.ask(ConfirmAction::DeleteEvent(ev)).await?;
+                }
+            }
+            KeyCode::Char('p') => {
+                // PTO is local-only, so no dirty flag / push
+                if let Some(mut e) = self.events.get(self.event_cursor).cloned() {
+                    e.pto = !e.pto;
+                    self.db.upsert_event(&e).await?;
+                    let cursor = self.event_cursor;
+                    self.refresh().await;
+                    self.event_cursor = cursor.min(self.events.len().saturating_sub(1));
+                }
+            }
+            KeyCode::Char('M') => {
+                if let Some(ev) = self.events.get(self.event_cursor).cloned() {
+                    self.moving       = Some(Moving { event: ev, typed: None });
+                    self.active_panel = Panel::Calendar;
+                    self.moving_status();
+                }
+            }
+            KeyCode::Char('m') => {
+                // So is counting an event as tracked time
+                if let Some(mut e) = self.events.get(self.event_cursor).cloned() {
+                    if e.all_day {
+                        self.sync_status = "All-day events can't be time entries".into();
+                        return Ok(());
+                    }
+                    e.time_entry = !e.time_entry;
+                    self.db.upsert_event(&e).await?;
+                    self.sync_status = if e.time_entry { "⏱ Counted as tracked time".into() } else { "No longer tracked time".into() };
+                    let cursor = self.event_cursor;
+                    self.refresh().await;
+                    self.event_cursor = cursor.min(self.events.len().saturating_sub(1));
+                }
+            }
+            KeyCode::Char('a') => {
+                // Check-in is local-only too, and only makes sense once it started
+                if let Some(mut e) = self.events.get(self.event_cursor).cloned() {
+                    if e.start > chrono::Utc::now() {
+                        self.sync_status = "Only past events can be checked in".into();
+                        return Ok(());
+                    }
+                    e.attendance = Attendance::cycle(e.attendance);
+                    self.db.upsert_event(&e).await?;
+                    self.sync_status = match e.attendance {
+                        Some(a) => format!("{} Marked {}", a.glyph(), a.as_str()),
+                        None    => "Check-in cleared".into(),
+                    };
+                    let cursor = self.event_cursor;
+                    self.refresh().await;
+                    self.event_cursor = cursor.min(self.events.len().saturating_sub(1));
+                }
+            }
+            KeyCode::Char(c @ ('+' | '-')) => {
+                // Lead time is local-only as well
+                if let Some(mut e) = self.events.get(self.event_cursor).cloned() {
+                    if e.all_day {
+                        self.sync_status = "All-day events have no lead time".into();
+                        return Ok(());
+                    }
+                    e.lead_minutes = step_lead(e.lead_minutes, if c == '+' { 1 } else { -1 });
+                    self.db.upsert_event(&e).await?;
+                    self.sync_status = match e.lead_minutes {
+                        0    => "Lead time cleared".into(),
+                        mins => format!("{mins} min lead time — from {}", e.lead_start().format("%H:%M")),
+                    };
+                    let cursor = self.event_cursor;
+                    self.refresh().await;
+                    self.event_cursor = cursor.min(self.events.len().saturating_sub(1));
+                }
+            }
+            KeyCode::Char('#') => {
+                // Tags are local-only as well
+                if self.tags.is_empty() {
+                    self.sync_status = "No tags yet — add one in the event form (n)".into();
+                    return Ok(());
+                }
+                if let Some(mut e) = self.events.get(self.event_cursor).cloned() {
+                    e.tag = self.next_tag(e.tag.as_ref());
+                    self.db.upsert_event(&e).await?;
+                    self.sync_status = match e.tag {
+                        Some(ref t) => format!("Tagged #{t}"),
+                        None        => "Tag cleared".into(),
+                    };
+                    let cursor = self.event_cursor;
+                    self.refresh().await;
+                    self.event_cursor = cursor.min(self.events.len().saturating_sub(1));
+                }
+            }
+            KeyCode::Char('f') => {
+                if self.tags.is_empty() && self.tag_filter.is_none() {
+                    self.sync_status = "No tags to filter by".into();
+                    return Ok(());
+                }
+                self.tag_filter = self.next_tag(self.tag_filter.as_ref());
+                self.sync_status = match self.tag_filter {
+                    Some(ref t) => format!("Showing #{t} only"),
+                    None        => "Showing all events".into(),
+                };
+                self.load_day_events().await;
+                self.reload_month_events().await;
+                self.event_cursor = 0;
+            }
+            KeyCode::Tab => self.active_panel = Panel::TaskList,
+            _            => self.active_panel = Panel::Calendar,
+        }
+        Ok(())
+    }
+
+    /// No tag → each tag in name order → no tag. Unknown names start over.
+    fn next_tag(&self, current: Option<&String>) -> Option<String> {
+        let next = match current.and_then(|c| self.tags.iter().position(|t| &t.name == c)) {
+            Some(i) => self.tags.get(i + 1),
+            None    => self.tags.first(),
+        };
+        next.map(|t| t.name.clone())
+    }
+
+    async fn key_tasks(&mut self, key: crossterm::event::KeyEvent) -> Result<()> {
+        let gg = std::mem::take(&mut self.ui.pending_g);
+        if let Some(c) = list_jump(key.code, gg, self.task_cursor, self.tasks.len(), self.ui.task_rows.get()) {
+            self.task_cursor = c;
+            return Ok(());
+        }
+        match key.code {
+            KeyCode::Char('X') => self.open_export(export::Scope::Tasks),
+            KeyCode::Char('|') => {
+                if let Some(t) = self.tasks.get(self.task_cursor).cloned() {
+                    self.open_send_to(Subject::new("task", &t.id, &t.title, &t));
+                }
+            }
+            KeyCode::Char('g') => self.ui.pending_g = true,
+            // Long jumps in an empty list: nothing to move, stay put
+            KeyCode::Char('G') | KeyCode::PageUp | KeyCode::PageDown | KeyCode::Home | KeyCode::End => {}
+            KeyCode::Down | KeyCode::Char('j') => {
+                if self.task_cursor + 1 < self.tasks.len() { self.task_cursor += 1; }
+            }
+            KeyCode::Up | KeyCode::Char('k') => {
+                self.task_cursor = self.task_cursor.saturating_sub(1);
+            }
+            KeyCode::Char(' ') => {
+                if let Some(t) = self.tasks.get(self.task_cursor).cloned() {
+                    let mut t    = t;
+                    t.completed  = !t.completed;
+                    t.dirty      = true;
+                    t.updated_at = chrono::Utc::now();
+                    self.db.upsert_task(&t).await?;
+                    if t.completed && t.repeat.is_some() { self.repeat_task(&t).await?; }
+                    self.refresh().await;
+                    if let Some(ref w) = self.sync { w.push_dirty().await; }
+                }
+            }
+            KeyCode::Char('f') => {
+                self.task_filter = self.task_filter.next(&self.task_tags);
+                self.sync_status = format!("Tasks: {}", self.task_filter.label());
+                self.load_tasks().await;
+                self.task_cursor = 0;
+            }
+            KeyCode::Char('d') | KeyCode::Delete => {
+                if let Some(t) = self.tasks.get(self.task_cursor).cloned() {
+                    self.ask(ConfirmAction::DeleteTask(t)).await?;
+                }
+            }
+            KeyCode::Char('e') => self.open_task_edit(),
+            KeyCode::Char('J') => self.move_task(1).await?,
+            KeyCode::Char('K') => self.move_task(-1).await?,
+            KeyCode::Char('s') => {
+                self.task_sort   = self.task_sort.next();
+                self.sync_status = format!("Tasks by {}", self.task_sort.label());
+                self.load_tasks().await;
+                self.task_cursor = 0;
+            }
+            KeyCode::Tab => self.active_panel = Panel::Calendar,
+            _            => self.active_panel = Panel::Calendar,
+        }
+        Ok(())
+    }
+
+    /// `e`: the selected task's title (with its tags and estimate), notes,
+    /// due date and priority in a form.
+    fn open_task_edit(&mut self) {
+        let Some(task) = self.tasks.get(self.task_cursor).cloned() else { return };
+        let mut title = task.title.clone();
+        for tag in &task.tags { title.push_str(&format!(" #{tag}")); }
+        if task.estimate_minutes > 0 { title.push_str(&format!(" ~{}", tasks::estimate_label(task.estimate_minutes))); }
+        self.task_edit = Some(TaskEdit {
+            field:    TaskField::Title,
+            title,
+            notes:    task.notes.clone().unwrap_or_default(),
+            due:      task.due.map_or_else(String::new, |d| d.format("%Y-%m-%d").to_string()),
+            priority: task.priority,
+            task,
+        });
+        self.ui.input_mode = InputMode::Insert;
+        self.active_panel  = Panel::TaskEdit;
+    }
+
+    async fn key_task_edit(&mut self, key: KeyEvent) -> Result<()> {
+        let Some(edit) = self.task_edit.as_mut() else { return Ok(()) };
+        let text = match edit.field {
+            TaskField::Title    => Some(&mut edit.title),
+            TaskField::Notes    => Some(&mut edit.notes),
+            TaskField::Due      => Some(&mut edit.due),
+            TaskField::Priority => None,
+        };
+        match (key.code, text) {
+            (KeyCode::Enter, _)                 => self.save_task_edit().await?,
+            (KeyCode::Tab | KeyCode::Down, _)   => edit.field = edit.field.step(1),
+            (KeyCode::BackTab | KeyCode::Up, _) => edit.field = edit.field.step(-1),
+            (KeyCode::Char(c), Some(text))      => text.push(c),
+            (KeyCode::Backspace, Some(text))    => { text.pop(); }
+            // Priority: stepped, or typed as a digit
+            (KeyCode::Right | KeyCode::Char('l' | '+'), None) => {
+                edit.priority = (edit.priority + 1).min(tasks::MAX_PRIORITY);
+            }
+            (KeyCode::Left | KeyCode::Char('h' | '-'), None) => {
+                edit.priority = (edit.priority - 1).max(0);
+            }
+            (KeyCode::Char(c @ '0'..='9'), None) => {
+                edit.priority = (c as i64 - '0' as i64).min(tasks::MAX_PRIORITY);
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// Writes the form back, queued for a push like any other change.
+    async fn save_task_edit(&mut self) -> Result<()> {
+        let Some(edit) = self.task_edit.as_ref() else { return Ok(()) };
+        let (title, tags)     = tasks::split_tags(&edit.title);
+        let (title, estimate) = tasks::split_estimate(&title);
+        if title.is_empty() {
+            self.sync_status = "✗ A task needs a title".into();
+            return Ok(());
+        }
+        let due = match edit.due.trim() {
+            ""  => None,
+            due => match parse_date(due, Local::now().date_naive()) {
+                Some(d) => Some(due_at(d)),
+                None    => {
+                    self.sync_status = format!("✗ Not a date: {due}");
+                    return Ok(());
+                }
+            },
+        };
+        let Some(edit) = self.task_edit.take() else { return Ok(()) };
+        let mut t = edit.task;
+        t.title      = title;
+        t.tags       = tags;
+        t.notes      = Some(edit.notes.trim().to_owned()).filter(|n| !n.is_empty());
+        t.due        = due;
+        t.priority   = edit.priority;
+        t.estimate_minutes = estimate;
+        t.dirty      = true;
+        t.updated_at = chrono::Utc::now();
+        self.db.upsert_task(&t).await?;
+
+        self.ui.input_mode = InputMode::Normal;
+        self.active_panel  = Panel::TaskList;
+        self.reload_data().await;
+        if let Some(ref w) = self.sync { w.push_dirty().await; }
+        self.sync_status = format!("✓ Saved “{}”", t.title);
+        Ok(())
+    }
+
+    /// `J`/`K`: swaps the selected task with the one below/above it as
+    /// listed, in the manual order only.
+    async fn move_task(&mut self, step: isize) -> Result<()> {
+        if self.task_sort != TaskSort::Manual {
+            self.sync_status = "Press s for manual order to move tasks".into();
+            return Ok(());
+        }
+        let Some(to) = self.task_cursor.checked_add_signed(step) else { return Ok(()) };
+        let (Some(a), Some(b)) = (self.tasks.get(self.task_cursor), self.tasks.get(to)) else { return Ok(()) };
+        self.db.swap_task_positions(&a.id, &b.id).await?;
+        self.load_tasks().await;
+        self.task_cursor = to;
+        if let Some(ref w) = self.sync { w.push_dirty().await; }
+        Ok(())
+    }
 
-            if event::poll(tick)? {
-                if let Event::Key(key) = event::read()? {
-                    self.on_key(key).await?;
+    async fn key_onboarding(&mut self, key: crossterm::event::KeyEvent) {
+        let Some(ref w) = self.sync else { return };
+        match key.code {
+            KeyCode::Char(c)   => self.ui.auth_code.push(c),
+            KeyCode::Backspace => { self.ui.auth_code.pop(); }
+            KeyCode::Enter if !self.ui.auth_code.trim().is_empty() => {
+                // Code pasted from the redirect URL, e.g. when :8085 isn't reachable
+                w.exchange_code(self.ui.auth_code.trim().to_owned()).await;
+                self.ui.auth_code.clear();
+                self.ui.auth_status = "Exchanging code…".into();
+            }
+            KeyCode::Enter => {
+                w.start_auth().await;
+                self.ui.auth_status = "Starting…".into();
+            }
+            _ => {}
+        }
+    }
+
+    async fn key_meals(&mut self, key: crossterm::event::KeyEvent) -> Result<()> {
+        let date = self.selected_date;
+        let slot = self.ui.meal_slot as i64;
+
+        if self.ui.input_mode == InputMode::Insert {
+            match key.code {
+                KeyCode::Char(c)   => self.ui.meal_input.push(c),
+                KeyCode::Backspace => { self.ui.meal_input.pop(); }
+                KeyCode::Enter => {
+                    self.db.set_meal(date, slot, &self.ui.meal_input).await?;
+                    self.ui.input_mode = InputMode::Normal;
+                    self.refresh().await;
                 }
+                _ => {}
             }
+            return Ok(());
         }
 
-        if let Some(ref w) = self.sync { w.shutdown().await; }
+        match key.code {
+            // Rows are days: moving between them moves the selected date
+            KeyCode::Down  | KeyCode::Char('j') => self.shift_day(1).await,
+            KeyCode::Up    | KeyCode::Char('k') => self.shift_day(-1).await,
+            KeyCode::Char(']') => self.shift_day(7).await,
+            KeyCode::Char('[') => self.shift_day(-7).await,
+            KeyCode::Left  | KeyCode::Char('h') => self.ui.meal_slot = self.ui.meal_slot.saturating_sub(1),
+            KeyCode::Right | KeyCode::Char('l') => self.ui.meal_slot = (self.ui.meal_slot + 1).min(2),
+            KeyCode::Enter | KeyCode::Char('i') => {
+                self.ui.meal_input = self.meals.iter()
+                    .find(|m| m.date == date && m.slot == slot)
+                    .map(|m| m.text.clone())
+                    .unwrap_or_default();
+                self.ui.input_mode = InputMode::Insert;
+            }
+            KeyCode::Char('x') | KeyCode::Delete => {
+                self.db.set_meal(date, slot, "").await?;
+                self.refresh().await;
+            }
+            _ => {}
+        }
         Ok(())
     }
 
-    fn on_sync_event(&mut self, ev: SyncEvent) {
-        self.sync_status = match ev {
-            SyncEvent::SyncStarted                        => "⟳ Syncing…".into(),
-            SyncEvent::SyncComplete { pulled, pushed } =>
-                format!("✓ +{pulled} pulled, {pushed} pushed"),
-            SyncEvent::SyncError(msg)                     => format!("✗ {msg}"),
-            SyncEvent::AuthRequired                       => "Auth required — run: lm auth google".into(),
+    // ── Checklists ────────────────────────────────────────────────────────────
+
+    async fn open_checklists(&mut self) {
+        self.ui.list_items_focused = false;
+        self.ui.input_mode         = InputMode::Normal;
+        self.reload_checklists().await;
+        self.active_panel = Panel::Checklists;
+    }
+
+    async fn reload_checklists(&mut self) {
+        self.checklists     = self.db.checklists().await.unwrap_or_default();
+        self.ui.list_cursor = self.ui.list_cursor.min(self.checklists.len().saturating_sub(1));
+        self.reload_checklist_items().await;
+    }
+
+    async fn reload_checklist_items(&mut self) {
+        self.checklist_items = match self.checklists.get(self.ui.list_cursor) {
+            Some(l) => self.db.checklist_items(&l.id).await.unwrap_or_default(),
+            None    => Vec::new(),
         };
+        self.ui.list_item_cursor = self.ui.list_item_cursor.min(self.checklist_items.len().saturating_sub(1));
     }
 
-    // ── Input ─────────────────────────────────────────────────────────────────
+    async fn key_checklists(&mut self, key: crossterm::event::KeyEvent) -> Result<()> {
+        let list_id = self.checklists.get(self.ui.list_cursor).map(|l| l.id.clone());
 
-    async fn on_key(&mut self, key: crossterm::event::KeyEvent) -> Result<()> {
-        // Global keys (handled before panel-specific logic)
-        match (key.code, key.modifiers) {
-            (KeyCode::Char('q'), _) => { self.running = false; return Ok(()); }
-            (KeyCode::Char('s'), KeyModifiers::CONTROL) => {
-                if let Some(ref w) = self.sync { w.sync_now().await; }
-                return Ok(());
-            }
-            (KeyCode::Char('?'), _) => { self.active_panel = Panel::Help; return Ok(()); }
-            (KeyCode::Esc, _) => {
-                self.active_panel       = Panel::Calendar;
-                self.ui.input_mode      = InputMode::Normal;
-                self.ui.event_form_step = EventFormStep::Title;
-                return Ok(());
+        if self.ui.input_mode == InputMode::Insert {
+            match key.code {
+                KeyCode::Char(c)   => self.ui.list_input.push(c),
+                KeyCode::Backspace => { self.ui.list_input.pop(); }
+                // Empty Enter finishes a burst of adds
+                KeyCode::Enter if self.ui.list_input.trim().is_empty() => {
+                    self.ui.input_mode = InputMode::Normal;
+                }
+                KeyCode::Enter => {
+                    let text = std::mem::take(&mut self.ui.list_input);
+                    match (self.ui.list_items_focused, list_id) {
+                        (true, Some(id)) => {
+                            self.db.add_checklist_item(&id, &text).await?;
+                            self.reload_checklist_items().await;
+                            self.ui.list_item_cursor = self.checklist_items.len().saturating_sub(1);
+                        }
+                        _ => match self.db.create_checklist(&text).await {
+                            Ok(list) => {
+                                self.reload_checklists().await;
+                                self.ui.list_cursor = self.checklists.iter()
+                                    .position(|l| l.id == list.id).unwrap_or(0);
+                                self.reload_checklist_items().await;
+                                // Straight into adding items to the new list
+                                self.ui.list_items_focused = true;
+                            }
+                            Err(_) => self.sync_status = format!("A list named \"{}\" already exists", text.trim()),
+                        },
+                    }
+                }
+                _ => {}
             }
-            _ => {}
+            return Ok(());
         }
 
-        let panel = self.active_panel.clone();
-        match panel {
-            Panel::Calendar     => self.key_calendar(key).await?,
-            Panel::EventList    => self.key_events(key).await?,
-            Panel::TaskList     => self.key_tasks(key).await?,
-            Panel::EventDetail
-            | Panel::TaskDetail => self.key_form(key).await?,
-            Panel::Help         => {}
+        if !self.ui.list_items_focused {
+            match key.code {
+                KeyCode::Down | KeyCode::Char('j') => {
+                    if self.ui.list_cursor + 1 < self.checklists.len() { self.ui.list_cursor += 1; }
+                    self.ui.list_item_cursor = 0;
+                    self.reload_checklist_items().await;
+                }
+                KeyCode::Up | KeyCode::Char('k') => {
+                    self.ui.list_cursor      = self.ui.list_cursor.saturating_sub(1);
+                    self.ui.list_item_cursor = 0;
+                    self.reload_checklist_items().await;
+                }
+                KeyCode::Enter | KeyCode::Tab | KeyCode::Right | KeyCode::Char('l') if list_id.is_some() => {
+                    self.ui.list_items_focused = true;
+                }
+                KeyCode::Char('n') | KeyCode::Char('a') => {
+                    self.ui.list_input.clear();
+                    self.ui.input_mode = InputMode::Insert;
+                }
+                KeyCode::Char('x') | KeyCode::Delete => {
+                    if let Some(id) = list_id {
+                        self.db.delete_checklist(&id).await?;
+                        self.reload_checklists().await;
+                    }
+                }
+                KeyCode::Char('R') => {
+                    if let Some(id) = list_id {
+                        self.db.reset_checklist(&id).await?;
+                        self.reload_checklist_items().await;
+                    }
+                }
+                _ => {}
+            }
+            return Ok(());
         }
-        Ok(())
-    }
 
-    async fn key_calendar(&mut self, key: crossterm::event::KeyEvent) -> Result<()> {
+        let item = self.checklist_items.get(self.ui.list_item_cursor).cloned();
         match key.code {
-            KeyCode::Right | KeyCode::Char('l') => self.shift_day(1).await,
-            KeyCode::Left  | KeyCode::Char('h') => self.shift_day(-1).await,
-            KeyCode::Down  | KeyCode::Char('j') => self.shift_day(7).await,
-            KeyCode::Up    | KeyCode::Char('k') => self.shift_day(-7).await,
-            KeyCode::Char(']') => self.next_month(),
-            KeyCode::Char('[') => self.prev_month(),
-            KeyCode::Char('t') => {
-                let t = Local::now().date_naive();
-                self.selected_date = t;
-                self.view_month    = t.month();
-                self.view_year     = t.year();
-                self.refresh().await;
+            KeyCode::Down | KeyCode::Char('j') => {
+                let last = self.checklist_items.len().saturating_sub(1);
+                self.ui.list_item_cursor = (self.ui.list_item_cursor + 1).min(last);
             }
-            KeyCode::Enter => self.active_panel = Panel::EventList,
-            KeyCode::Tab   => self.active_panel = Panel::TaskList,
-            KeyCode::Char('n') => {
-                self.ui.new_event_title.clear();
-                self.ui.event_form_step = EventFormStep::Title;
-                self.ui.event_start_h   = 9;
-                self.ui.event_start_m   = 0;
-                self.ui.event_end_h     = 10;
-                self.ui.event_end_m     = 0;
-                self.ui.time_field      = TimeField::Hour;
-                self.ui.input_mode      = InputMode::Insert;
-                self.active_panel       = Panel::EventDetail;
+            KeyCode::Up | KeyCode::Char('k') => {
+                self.ui.list_item_cursor = self.ui.list_item_cursor.saturating_sub(1);
             }
-            KeyCode::Char('N') => {
-                self.ui.new_task_title.clear();
+            KeyCode::Tab | KeyCode::Left | KeyCode::Char('h') => self.ui.list_items_focused = false,
+            KeyCode::Char(' ') | KeyCode::Enter => {
+                if let Some(it) = item {
+                    self.db.set_checklist_item_checked(&it.id, !it.checked).await?;
+                    self.reload_checklist_items().await;
+                }
+            }
+            KeyCode::Char('a') | KeyCode::Char('i') => {
+                self.ui.list_input.clear();
                 self.ui.input_mode = InputMode::Insert;
-                self.active_panel  = Panel::TaskDetail;
+            }
+            KeyCode::Char('n') => {
+                // New list from either pane
+                self.ui.list_items_focused = false;
+                self.ui.list_input.clear();
+                self.ui.input_mode = InputMode::Insert;
+            }
+            KeyCode::Char('x') | KeyCode::Delete => {
+                if let Some(it) = item {
+                    self.db.delete_checklist_item(&it.id).await?;
+                    self.reload_checklist_items().await;
+                }
+            }
+            KeyCode::Char('R') => {
+                if let Some(id) = list_id {
+                    self.db.reset_checklist(&id).await?;
+                    self.reload_checklist_items().await;
+                }
             }
             _ => {}
         }
         Ok(())
     }
 
-    async fn key_events(&mut self, key: crossterm::event::KeyEvent) -> Result<()> {
+    // ── Ideal week ────────────────────────────────────────────────────────────
+
+    async fn load_week_plan(&mut self) {
+        let mon = week_start(self.selected_date);
+        let (s, _) = day_bounds(mon);
+        let (_, e) = day_bounds(add_days(mon, 6).unwrap_or(mon));
+        self.week_events = self.db.events_overlapping(s, e).await.unwrap_or_default();
+        self.week_plan   = planner::week_plan(&self.config.ideal_week, mon, &self.week_events);
+        self.ui.plan_cursor = self.ui.plan_cursor.min(self.week_plan.len().saturating_sub(1));
+    }
+
+    async fn key_ideal_week(&mut self, key: crossterm::event::KeyEvent) -> Result<()> {
         match key.code {
             KeyCode::Down | KeyCode::Char('j') => {
-                if self.event_cursor + 1 < self.events.len() { self.event_cursor += 1; }
+                let last = self.week_plan.len().saturating_sub(1);
+                self.ui.plan_cursor = (self.ui.plan_cursor + 1).min(last);
             }
             KeyCode::Up | KeyCode::Char('k') => {
-                self.event_cursor = self.event_cursor.saturating_sub(1);
+                self.ui.plan_cursor = self.ui.plan_cursor.saturating_sub(1);
             }
-            KeyCode::Char('d') | KeyCode::Delete => {
-                if let Some(ev) = self.events.get(self.event_cursor).cloned() {
-                    let mut e  = ev;
-                    e.deleted  = true;
-                    e.dirty    = true;
-                    self.db.upsert_event(&e).await?;
-                    self.refresh().await;
+            KeyCode::Char(']') => { self.shift_day(7).await;  self.load_week_plan().await; }
+            KeyCode::Char('[') => { self.shift_day(-7).await; self.load_week_plan().await; }
+            // Turn the planned block into a real event
+            KeyCode::Enter | KeyCode::Char('m') => {
+                if let Some(b) = self.week_plan.get(self.ui.plan_cursor).filter(|b| !b.met).cloned() {
+                    self.db.upsert_event(&DbEvent::new(&b.title, b.start, b.end)).await?;
                     if let Some(ref w) = self.sync { w.push_dirty().await; }
+                    self.load_week_plan().await;
+                    self.refresh().await;
                 }
             }
-            KeyCode::Tab => self.active_panel = Panel::TaskList,
-            _            => self.active_panel = Panel::Calendar,
+            _ => {}
         }
         Ok(())
     }
 
-    async fn key_tasks(&mut self, key: crossterm::event::KeyEvent) -> Result<()> {
+    // ── Import quarantine ─────────────────────────────────────────────────────
+
+    async fn key_quarantine(&mut self, key: crossterm::event::KeyEvent) -> Result<()> {
+        let Some(item) = self.quarantine.get(self.ui.review_cursor).cloned() else { return Ok(()) };
+
+        if self.ui.input_mode == InputMode::Insert {
+            match key.code {
+                KeyCode::Char(c)   => self.ui.review_input.push(c),
+                KeyCode::Backspace => { self.ui.review_input.pop(); }
+                KeyCode::Enter => {
+                    let Some((at, date_only)) = parse_fix_time(&self.ui.review_input) else {
+                        self.sync_status = "Enter YYYY-MM-DD HH:MM, or YYYY-MM-DD for all-day".into();
+                        return Ok(());
+                    };
+                    match self.ui.review_start {
+                        // First Enter sets the start, then the end is prompted
+                        None => {
+                            self.ui.review_start = Some((at, date_only));
+                            self.ui.review_input = match item.end.filter(|e| *e > at) {
+                                Some(e) if date_only => fix_time_string(e - Duration::days(1), true),
+                                Some(e)              => fix_time_string(e, false),
+                                None                 => fix_time_string(at + Duration::hours(1), date_only),
+                            };
+                        }
+                        Some((start, start_date_only)) => {
+                            let all_day = start_date_only && date_only;
+                            // All-day ends are typed inclusive, stored exclusive
+                            let end = if all_day { at + Duration::days(1) } else { at };
+                            match validate::check_times(Some(start), Some(end)) {
+                                Ok((start, end)) => self.apply_fix(&item, start, end, all_day).await?,
+                                Err(reason)      => self.sync_status = format!("✗ Still invalid: {reason}"),
+                            }
+                        }
+                    }
+                }
+                _ => {}
+            }
+            return Ok(());
+        }
+
         match key.code {
             KeyCode::Down | KeyCode::Char('j') => {
-                if self.task_cursor + 1 < self.tasks.len() { self.task_cursor += 1; }
+                let last = self.quarantine.len().saturating_sub(1);
+                self.ui.review_cursor = (self.ui.review_cursor + 1).min(last);
             }
             KeyCode::Up | KeyCode::Char('k') => {
-                self.task_cursor = self.task_cursor.saturating_sub(1);
+                self.ui.review_cursor = self.ui.review_cursor.saturating_sub(1);
             }
-            KeyCode::Char(' ') => {
-                if let Some(t) = self.tasks.get(self.task_cursor).cloned() {
-                    let mut t    = t;
-                    t.completed  = !t.completed;
-                    t.dirty      = true;
-                    t.updated_at = chrono::Utc::now();
-                    self.db.upsert_task(&t).await?;
-                    self.refresh().await;
-                    if let Some(ref w) = self.sync { w.push_dirty().await; }
-                }
+            KeyCode::Enter | KeyCode::Char('e') => {
+                self.ui.review_start = None;
+                self.ui.review_input = item.start
+                    .map(|s| fix_time_string(s, item.all_day))
+                    .unwrap_or_default();
+                self.ui.input_mode = InputMode::Insert;
             }
-            KeyCode::Tab => self.active_panel = Panel::Calendar,
-            _            => self.active_panel = Panel::Calendar,
+            KeyCode::Char('x') | KeyCode::Char('d') | KeyCode::Delete => {
+                self.db.resolve_quarantined(&item.source, &item.remote_id).await?;
+                self.reload_quarantine().await;
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// Stores the corrected event. Google items keep their remote link so the
+    /// push corrects Google's copy too; feed items become ordinary events.
+    async fn apply_fix(
+        &mut self, item: &Quarantined, start: DateTime<Utc>, end: DateTime<Utc>, all_day: bool,
+    ) -> Result<()> {
+        let mut ev = DbEvent::new(&item.title, start, end);
+        ev.all_day     = all_day;
+        ev.description = item.description.clone();
+        if let Some(cal) = item.source.strip_prefix("google:") {
+            if let Some(id) = self.db.event_id_for_sync_id(&item.remote_id).await? {
+                ev.id = id;
+            }
+            ev.calendar_id = Some(cal.to_owned());
+            ev.sync_id     = Some(item.remote_id.clone());
         }
+        self.db.upsert_event(&ev).await?;
+        self.db.resolve_quarantined(&item.source, &item.remote_id).await?;
+        if let Some(ref w) = self.sync { w.push_dirty().await; }
+
+        self.ui.input_mode = InputMode::Normal;
+        self.sync_status   = format!("✓ Fixed \"{}\"", item.title);
+        self.reload_quarantine().await;
+        self.refresh().await;
         Ok(())
     }
 
+    async fn reload_quarantine(&mut self) {
+        self.quarantine       = self.db.quarantined().await.unwrap_or_default();
+        self.ui.review_cursor = self.ui.review_cursor.min(self.quarantine.len().saturating_sub(1));
+    }
+
     // ── Multi-step form handler ───────────────────────────────────────────────
 
     async fn key_form(&mut self, key: crossterm::event::KeyEvent) -> Result<()> {
@@ -269,16 +3185,30 @@ impl App {
                 EventFormStep::Title => match key.code {
                     KeyCode::Char(c)   => self.ui.new_event_title.push(c),
                     KeyCode::Backspace => { self.ui.new_event_title.pop(); }
+                    KeyCode::Enter if !self.ui.new_event_title.trim().is_empty() => {
+                        self.ui.event_form_step = EventFormStep::Tag;
+                    }
+                    _ => {}
+                },
+
+                // Step 2: optional tag; Tab cycles through the existing ones
+                EventFormStep::Tag => match key.code {
+                    KeyCode::Char(c) if !c.is_whitespace() => self.ui.event_tag.push(c),
+                    KeyCode::Backspace => { self.ui.event_tag.pop(); }
+                    KeyCode::Tab => {
+                        self.ui.event_tag = self.next_tag(self.form_tag().as_ref()).unwrap_or_default();
+                    }
+                    // Google's colours, then none again
+                    KeyCode::Right => self.ui.event_color = (self.ui.event_color + 1) % (EVENT_COLORS.len() + 1),
+                    KeyCode::Left  => self.ui.event_color = (self.ui.event_color + EVENT_COLORS.len()) % (EVENT_COLORS.len() + 1),
                     KeyCode::Enter => {
-                        if !self.ui.new_event_title.trim().is_empty() {
-                            self.ui.event_form_step = EventFormStep::StartTime;
-                            self.ui.time_field      = TimeField::Hour;
-                        }
+                        self.ui.event_form_step = EventFormStep::StartTime;
+                        self.ui.time_field      = TimeField::Hour;
                     }
                     _ => {}
                 },
 
-                // Step 2: pick start time
+                // Step 3: pick start time
                 EventFormStep::StartTime => match key.code {
                     KeyCode::Up   | KeyCode::Char('k') => self.adjust_start_time(1),
                     KeyCode::Down | KeyCode::Char('j') => self.adjust_start_time(-1),
@@ -286,6 +3216,8 @@ impl App {
                     KeyCode::Right | KeyCode::Char('l') | KeyCode::Tab => {
                         self.ui.time_field = TimeField::Minute;
                     }
+                    KeyCode::Char('+') => self.ui.event_lead = step_lead(self.ui.event_lead, 1),
+                    KeyCode::Char('-') => self.ui.event_lead = step_lead(self.ui.event_lead, -1),
                     KeyCode::Enter => {
                         self.ui.event_form_step = EventFormStep::EndTime;
                         self.ui.time_field      = TimeField::Hour;
@@ -293,7 +3225,7 @@ impl App {
                     _ => {}
                 },
 
-                // Step 3: pick end time, then save
+                // Step 4: pick end time
                 EventFormStep::EndTime => match key.code {
                     KeyCode::Up   | KeyCode::Char('k') => self.adjust_end_time(1),
                     KeyCode::Down | KeyCode::Char('j') => self.adjust_end_time(-1),
@@ -301,7 +3233,50 @@ impl App {
                     KeyCode::Right | KeyCode::Char('l') | KeyCode::Tab => {
                         self.ui.time_field = TimeField::Minute;
                     }
-                    KeyCode::Enter => self.commit_form().await?,
+                    KeyCode::Enter => self.ui.event_form_step = EventFormStep::Guests,
+                    _ => {}
+                },
+
+                // Step 5: optional guests; the first Enter checks their
+                // availability with Google, the next one saves
+                EventFormStep::Guests => match key.code {
+                    KeyCode::Char(c) => {
+                        self.ui.event_guests.push(c);
+                        self.ui.guest_check = GuestCheck::NotChecked;
+                    }
+                    KeyCode::Backspace => {
+                        self.ui.event_guests.pop();
+                        self.ui.guest_check = GuestCheck::NotChecked;
+                    }
+                    KeyCode::Enter => {
+                        let guests = self.form_guests();
+                        if let Some(bad) = guests.iter().find(|g| !g.contains('@')) {
+                            self.sync_status = format!("✗ Not an email address: {bad}");
+                            return Ok(());
+                        }
+                        match (&self.sync, self.form_slot(), &self.ui.guest_check) {
+                            (Some(w), Some((from, to)), GuestCheck::NotChecked) if !guests.is_empty() => {
+                                w.check_availability(guests, from, to).await;
+                                self.ui.guest_check = GuestCheck::Pending;
+                                self.sync_status    = "⟳ Checking guests' availability…".into();
+                            }
+                            _ => {
+                                // Listed in the form first; the next Enter saves anyway
+                                if self.ui.event_conflicts.is_none() {
+                                    let clashes = self.form_conflicts().await;
+                                    if !clashes.is_empty() {
+                                        self.sync_status = format!(
+                                            "⚠ Overlaps {} event{} — Enter saves anyway",
+                                            clashes.len(), if clashes.len() == 1 { "" } else { "s" },
+                                        );
+                                        self.ui.event_conflicts = Some(clashes);
+                                        return Ok(());
+                                    }
+                                }
+                                self.commit_form().await?;
+                            }
+                        }
+                    }
                     _ => {}
                 },
             },
@@ -311,6 +3286,28 @@ impl App {
         Ok(())
     }
 
+    /// Start and end picked in the event form (wall time stored as UTC).
+    fn form_slot(&self) -> Option<(DateTime<Utc>, DateTime<Utc>)> {
+        let start = self.selected_date.and_hms_opt(self.ui.event_start_h, self.ui.event_start_m, 0)?;
+        let end   = self.selected_date.and_hms_opt(self.ui.event_end_h, self.ui.event_end_m, 0)?;
+        Some((start.and_utc(), end.and_utc()))
+    }
+
+    /// Tag typed into the event form: lowercase, without a leading `#`.
+    fn form_tag(&self) -> Option<String> {
+        let tag = self.ui.event_tag.trim().trim_start_matches('#').to_lowercase();
+        (!tag.is_empty()).then_some(tag)
+    }
+
+    /// Guest emails typed into the event form, split on commas and spaces.
+    fn form_guests(&self) -> Vec<String> {
+        self.ui.event_guests
+            .split(|c: char| c == ',' || c == ';' || c.is_whitespace())
+            .filter(|g| !g.is_empty())
+            .map(str::to_owned)
+            .collect()
+    }
+
     fn adjust_start_time(&mut self, delta: i32) {
         match self.ui.time_field {
             TimeField::Hour   => {
@@ -341,24 +3338,43 @@ impl App {
         match self.active_panel {
             Panel::EventDetail => {
                 let title = self.ui.new_event_title.trim().to_owned();
-                if !title.is_empty() {
-                    let start = self.selected_date
-                        .and_hms_opt(self.ui.event_start_h, self.ui.event_start_m, 0)
-                        .unwrap()
-                        .and_utc();
-                    let end = self.selected_date
-                        .and_hms_opt(self.ui.event_end_h, self.ui.event_end_m, 0)
-                        .unwrap()
-                        .and_utc();
-                    self.db.upsert_event(&DbEvent::new(&title, start, end)).await?;
+                if let (false, Some((start, end))) = (title.is_empty(), self.form_slot()) {
+                    let mut ev = DbEvent::new(&title, start, end);
+                    ev.lead_minutes = self.ui.event_lead;
+                    ev.color        = (self.ui.event_color > 0).then(|| self.ui.event_color.to_string());
+                    if let Some(tag) = self.form_tag() {
+                        self.db.ensure_tag(&tag).await?;
+                        ev.tag = Some(tag);
+                    }
+                    self.db.upsert_event(&ev).await?;
+                    let guests = self.form_guests();
+                    if !guests.is_empty() {
+                        self.db.set_attendees(&ev.id, &guests).await?;
+                    }
                     if let Some(ref w) = self.sync { w.push_dirty().await; }
                 }
                 self.ui.event_form_step = EventFormStep::Title;
+                self.ui.guest_check     = GuestCheck::NotChecked;
+                self.ui.event_conflicts = None;
             }
             Panel::TaskDetail => {
-                let title = self.ui.new_task_title.trim().to_owned();
+                let (title, tags)     = tasks::split_tags(&self.ui.new_task_title);
+                let (title, repeat)   = tasks::split_repeat(&title);
+                let (title, estimate) = tasks::split_estimate(&title);
                 if !title.is_empty() {
-                    self.db.upsert_task(&Task::new(&title)).await?;
+                    let mut t = Task::new(&title);
+                    t.tags    = tags;
+                    t.estimate_minutes = estimate;
+                    if let Some(schedule) = repeat {
+                        // First due on the first scheduled day from today
+                        let today = Local::now().date_naive();
+                        let rule  = self.config.holidays.recurring_tasks;
+                        t.due     = add_days(today, -1)
+                            .and_then(|d| tasks::next_occurrence(&schedule, d, rule, &self.holidays).0)
+                            .map(due_at);
+                        t.repeat  = Some(schedule.label());
+                    }
+                    self.db.upsert_task(&t).await?;
                     if let Some(ref w) = self.sync { w.push_dirty().await; }
                 }
             }
@@ -372,30 +3388,338 @@ impl App {
 
     // ── Helpers ───────────────────────────────────────────────────────────────
 
+    /// Adds the next occurrence of a repeating task that was just completed.
+    async fn repeat_task(&mut self, done: &Task) -> Result<()> {
+        let today = Local::now().date_naive();
+        let rule  = self.config.holidays.recurring_tasks;
+        let Some((t, moved)) = tasks::next_repeat(done, today, rule, &self.holidays) else { return Ok(()) };
+        let Some(next) = t.due.map(|d| d.date_naive()) else { return Ok(()) };
+        self.db.upsert_task(&t).await?;
+        self.sync_status = match moved {
+            Some(h) => format!("↻ Next: {} ({} is {})", next.format("%a %b %-d"), h.date.format("%b %-d"), h.name),
+            None    => format!("↻ Next: {}", next.format("%a %b %-d")),
+        };
+        Ok(())
+    }
+
     async fn shift_day(&mut self, d: i64) {
-        let date           = self.selected_date + Duration::days(d);
+        let Some(date)     = add_days(self.selected_date, d) else { return };
         self.selected_date = date;
         self.view_month    = date.month();
         self.view_year     = date.year();
         self.refresh().await;
     }
 
-    fn next_month(&mut self) {
-        if self.view_month == 12 { self.view_month = 1;  self.view_year += 1; }
-        else                     { self.view_month += 1; }
+    /// Moves the grid `months` on (back when negative), keeping the
+    /// selected day.
+    async fn shift_view(&mut self, months: i64) {
+        let Some((year, month)) = shift_month(self.view_year, self.view_month, months) else { return };
+        self.view_year  = year;
+        self.view_month = month;
+        self.reload_holidays();
+        self.reload_month_events().await;
+    }
+
+    /// `0`, `%` and `$`: selects the day `percent` of the way through the
+    /// month on the grid, so the first, the middle (`%`, or `25%` for a
+    /// quarter) and the last. Vim's `H` / `M` / `L` are taken by the time
+    /// report, meal plan and checklists.
+    async fn jump_in_month(&mut self, percent: u32) {
+        let days = days_in_month(self.view_year, self.view_month);
+        let day  = 1 + ((days - 1) * percent + 50) / 100;
+        if let Some(date) = NaiveDate::from_ymd_opt(self.view_year, self.view_month, day) {
+            self.go_to(date).await;
+        }
+    }
+
+    fn reload_holidays(&mut self) {
+        let mut month = self.holidays.holidays_in_month(self.view_year, self.view_month);
+        month.retain(|h| self.holidays_shown(&h.country));
+        self.month_holidays = month;
+    }
+
+    async fn reload_month_events(&mut self) {
+        let Some((from, to)) = month_bounds(self.view_year, self.view_month) else { return };
+        let events = self.db.events_overlapping(from, to).await.unwrap_or_default();
+        self.month_events = self.filter_tagged(events);
+    }
+
+    /// The selected day's events, narrowed to `tag_filter` when one is set.
+    async fn load_day_events(&mut self) {
+        let (s, e)  = day_bounds(self.selected_date);
+        let events  = self.db.events_overlapping(s, e).await.unwrap_or_default();
+        self.day_conflicts = events.iter()
+            .filter(|a| events.iter().any(|b| b.id != a.id && a.clashes(b.lead_start(), b.end) && b.clashes(a.lead_start(), a.end)))
+            .map(|a| a.id.clone())
+            .collect();
+        self.events = self.filter_tagged(events);
+        if self.pin_all_day { self.events.sort_by_key(|e| !e.all_day); }
     }
 
-    fn prev_month(&mut self) {
-        if self.view_month == 1 { self.view_month = 12; self.view_year -= 1; }
-        else                    { self.view_month -= 1; }
+    /// Events the form's slot would overlap, for the warning before saving.
+    async fn form_conflicts(&self) -> Vec<DbEvent> {
+        let Some((start, end)) = self.form_slot() else { return Vec::new() };
+        // The new event's own lead time is taken up too
+        let start = start - Duration::minutes(self.ui.event_lead);
+        self.db.events_overlapping(start, end).await.unwrap_or_default()
+            .into_iter()
+            .filter(|e| e.clashes(start, end))
+            .collect()
+    }
+
+    /// All tasks through task_filter and task_sort. A tag filter whose last
+    /// task has gone falls back to all.
+    async fn load_tasks(&mut self) {
+        let all        = self.db.all_tasks().await.unwrap_or_default();
+        self.task_tags = tasks::all_tags(&all);
+        if let TaskFilter::Tag(ref t) = self.task_filter {
+            if !self.task_tags.contains(t) { self.task_filter = TaskFilter::All; }
+        }
+        let today  = Local::now().date_naive();
+        self.tasks = tasks::view(all, &self.task_filter, self.task_sort, today);
+    }
+
+    fn filter_tagged(&self, mut events: Vec<DbEvent>) -> Vec<DbEvent> {
+        if let Some(ref tag) = self.tag_filter {
+            events.retain(|e| e.tag.as_ref() == Some(tag));
+        }
+        events
+    }
+
+    /// Colour of an event's tag in the current theme; untagged events (and
+    /// tags since removed from the table) use the event colour.
+    /// An event's own Google colour, else its tag's.
+    pub fn event_color(&self, ev: &DbEvent) -> ratatui::style::Color {
+        ev.color().map_or_else(|| self.tag_color(ev.tag.as_deref()), |(_, hex)| theme::hex_to_color(hex))
+    }
+
+    pub fn tag_color(&self, tag: Option<&str>) -> ratatui::style::Color {
+        tag.and_then(|n| self.tags.iter().find(|t| t.name == n))
+            .map_or(self.theme.event_color(), |t| self.theme.tag_color(&t.color))
+    }
+
+    /// How many events `date` has on the month grid, and the colour of its
+    /// mark: the tag most of them carry (the earliest on a tie), else the
+    /// first event's own. `None` on days without events.
+    pub fn day_density(&self, date: NaiveDate) -> Option<(usize, ratatui::style::Color)> {
+        let (s, e) = day_bounds(date);
+        let day: Vec<&DbEvent> = self.month_events.iter().filter(|ev| ev.start < e && ev.end > s).collect();
+        let first = day.first()?;
+        let mut tags: Vec<(&str, usize)> = Vec::new();
+        for tag in day.iter().filter_map(|ev| ev.tag.as_deref()) {
+            match tags.iter_mut().find(|(t, _)| *t == tag) {
+                Some((_, n)) => *n += 1,
+                None         => tags.push((tag, 1)),
+            }
+        }
+        // max_by_key takes the last of equals, so look from the end
+        let color = tags.iter().rev().max_by_key(|(_, n)| *n)
+            .map_or_else(|| self.event_color(first), |(tag, _)| self.tag_color(Some(tag)));
+        Some((day.len(), color))
+    }
+
+    /// Clock change inside a timed event, in the event's own zone if known.
+    pub fn event_dst(&self, ev: &DbEvent) -> Option<DstShift> {
+        if ev.all_day { return None; }
+        let tz = ev.timezone.as_deref().and_then(|n| n.parse().ok()).unwrap_or(self.tz);
+        dst::crossing(ev.start, ev.end, tz)
+    }
+
+    /// Clock change between the start and end picked in the event form.
+    pub fn form_dst(&self) -> Option<DstShift> {
+        let start = NaiveTime::from_hms_opt(self.ui.event_start_h, self.ui.event_start_m, 0)?;
+        let end   = NaiveTime::from_hms_opt(self.ui.event_end_h, self.ui.event_end_m, 0)?;
+        dst::wall_crossing(self.selected_date, start, end, self.tz)
+    }
+
+    /// Working locations on `date`: your own (the first synced calendar)
+    /// first, then any colleagues' calendars you sync.
+    pub fn locations_on(&self, date: NaiveDate) -> Vec<&WorkingLocation> {
+        let own = self.own_calendar();
+        let mut out: Vec<&WorkingLocation> = self.working_locations.iter().filter(|w| w.on(date)).collect();
+        out.sort_by_key(|w| w.calendar_id != own);
+        out
+    }
+
+    /// The badge on a day cell: your own working location that day.
+    pub fn own_location_on(&self, date: NaiveDate) -> Option<&WorkingLocation> {
+        let own = self.own_calendar();
+        self.working_locations.iter().find(|w| w.calendar_id == own && w.on(date))
+    }
+
+    fn own_calendar(&self) -> &str {
+        self.config.google.as_ref()
+            .and_then(|g| g.calendar_ids.first())
+            .map_or("primary", String::as_str)
+    }
+
+    /// Holidays on the selected date, if it's in the visible month.
+    pub fn selected_holidays(&self) -> impl Iterator<Item = &Holiday> {
+        self.month_holidays.iter().filter(|h| h.date == self.selected_date)
+    }
+
+    async fn load_stats(&mut self) {
+        let year = Local::now().year();
+        self.pto_summary = match &self.config.pto {
+            Some(cfg) => {
+                let events = self.db.pto_events().await.unwrap_or_default();
+                pto::summarize(&events, cfg, &self.holidays, year)
+            }
+            None => Vec::new(),
+        };
+        let now    = chrono::Utc::now();
+        let events = self.db
+            .events_overlapping(now - chrono::Duration::days(attendance::WINDOW_DAYS), now)
+            .await.unwrap_or_default();
+        self.meeting_load = attendance::summarize(&events, now);
+
+        let today     = Local::now().date_naive();
+        let first     = focus::first_monday(today);
+        let (from, _) = day_bounds(first);
+        let (_, to)   = day_bounds(add_days(week_start(today), 6).unwrap_or(today));
+        let events    = self.db.events_overlapping(from, to).await.unwrap_or_default();
+        let days_off  = self.holidays.days_off(first.year()..=today.year());
+        self.focus_weeks = focus::report(&events, &self.config.focus, &days_off, today);
+    }
+
+    async fn load_digest(&mut self) {
+        let today      = Local::now().date_naive();
+        let (from, to) = day_bounds(today);
+        let events     = self.db.events_overlapping(from, to).await.unwrap_or_default();
+        let tasks      = self.db.all_tasks().await.unwrap_or_default();
+        let mut digest = Digest::build(today, today, &events, &tasks, &self.holidays);
+        digest.holidays.retain(|h| self.holidays_shown(&h.country));
+        self.digest    = Some(digest);
+    }
+
+    async fn reload_feed_events(&mut self) {
+        let (s, e) = day_bounds(self.selected_date);
+        self.feed_events = self.db.feed_events_overlapping(s, e).await.unwrap_or_default();
+    }
+
+    async fn reload_meals(&mut self) {
+        if !self.config.ui.meal_plan { return; }
+        let mon    = week_start(self.selected_date);
+        let sun    = add_days(mon, 6).unwrap_or(mon);
+        self.meals = self.db.meals_between(mon, sun).await.unwrap_or_default();
+    }
+
+    async fn reload_forecast(&mut self) {
+        if self.config.weather.is_none() { return; }
+        self.forecast = self.db.forecast(Local::now().date_naive()).await.unwrap_or_default();
     }
 
     async fn refresh(&mut self) {
-        let s = self.selected_date.and_hms_opt(0, 0, 0).unwrap().and_utc();
-        let e = self.selected_date.and_hms_opt(23, 59, 59).unwrap().and_utc();
-        self.events       = self.db.events_in_range(s, e).await.unwrap_or_default();
-        self.tasks        = self.db.all_tasks().await.unwrap_or_default();
+        // Everything is re-read below, this instance's own writes included
+        if let Some(w) = self.watcher.as_mut() { w.settle().await; }
+        let (s, e) = day_bounds(self.selected_date);
+        self.load_day_events().await;
+        self.feed_events  = self.db.feed_events_overlapping(s, e).await.unwrap_or_default();
+        self.load_tasks().await;
         self.event_cursor = 0;
         self.task_cursor  = 0;
+        self.reload_meals().await;
+        self.reload_forecast().await;
+        self.reload_holidays();
+        self.working_locations = self.db.working_locations().await.unwrap_or_default();
+        self.tags = self.db.tags().await.unwrap_or_default();
+        self.timer = self.db.running_timer().await.unwrap_or_default();
+        self.reload_habits().await;
+        self.reload_month_events().await;
+    }
+
+    /// `r`: re-read what the focused panel shows, for changes made outside
+    /// this instance (CLI, another machine). Cursors stay put where possible.
+    async fn reload_panel(&mut self) {
+        let what = match self.active_panel {
+            Panel::Calendar | Panel::EventList => {
+                self.load_day_events().await;
+                self.event_cursor = self.event_cursor.min(self.events.len().saturating_sub(1));
+                self.reload_feed_events().await;
+                self.reload_holidays();
+                self.reload_month_events().await;
+                "events"
+            }
+            Panel::TaskList => {
+                self.load_tasks().await;
+                self.task_cursor = self.task_cursor.min(self.tasks.len().saturating_sub(1));
+                "tasks"
+            }
+            Panel::MealPlan   => { self.reload_meals().await;      "meals" }
+            Panel::Checklists => { self.reload_checklists().await; "checklists" }
+            Panel::IdealWeek  => { self.load_week_plan().await;    "week" }
+            Panel::Quarantine => { self.reload_quarantine().await; "review list" }
+            Panel::Stats      => { self.load_stats().await;        "stats" }
+            Panel::Today      => { self.load_digest().await;       "digest" }
+            Panel::Logs       => { self.reload_logs();             "log" }
+            Panel::SyncLog    => { self.reload_sync_log().await;   "sync log" }
+            Panel::Reports    => { self.load_report().await;       "report" }
+            Panel::Habits     => { self.reload_habits().await;     "habits" }
+            Panel::Plugins    => { self.plugins.refresh(self.ui.plugin_tab); "panel" }
+            _ => return,
+        };
+        self.sync_status = format!("↻ Reloaded {what}");
+    }
+
+    /// `R`: re-read everything, including the month grid's markers.
+    async fn reload_all(&mut self) {
+        self.reload_data().await;
+        self.sync_status = "↻ Reloaded all data".into();
+    }
+
+    /// Re-reads everything on screen, keeping the cursors where they were.
+    /// Also runs whenever the watcher sees a write not yet re-read: another
+    /// process's, or a sync by this one's worker.
+    async fn reload_data(&mut self) {
+        let (ec, tc) = (self.event_cursor, self.task_cursor);
+        self.refresh().await;
+        self.event_cursor = ec.min(self.events.len().saturating_sub(1));
+        self.task_cursor  = tc.min(self.tasks.len().saturating_sub(1));
+        self.reload_quarantine().await;
+    }
+}
+
+/// Tasks are due on a day; Google Tasks keeps that as midnight UTC.
+fn due_at(date: NaiveDate) -> DateTime<Utc> {
+    date.and_time(NaiveTime::MIN).and_utc()
+}
+
+/// Lead time one `LEAD_STEP` up or down, within 0..=MAX_LEAD.
+fn step_lead(mins: i64, dir: i64) -> i64 {
+    (mins + dir * LEAD_STEP).clamp(0, MAX_LEAD)
+}
+
+/// Cursor after a long jump in a list of `len` showing `rows` at a time:
+/// PageUp/PageDown, Home/`gg` (`gg` = second `g` of a pair), End/`G`.
+fn list_jump(code: KeyCode, gg: bool, cursor: usize, len: usize, rows: u16) -> Option<usize> {
+    let last = len.checked_sub(1)?;
+    let page = (rows.saturating_sub(1) as usize).max(1);
+    match code {
+        KeyCode::PageDown                 => Some((cursor + page).min(last)),
+        KeyCode::PageUp                   => Some(cursor.saturating_sub(page)),
+        KeyCode::Home                     => Some(0),
+        KeyCode::Char('g') if gg          => Some(0),
+        KeyCode::End | KeyCode::Char('G') => Some(last),
+        _ => None,
+    }
+}
+
+/// Palette position of `hex`, or `fallback` when it isn't a swatch.
+fn swatch_of(palette: &[String], hex: &str, fallback: usize) -> usize {
+    palette.iter().position(|c| c.eq_ignore_ascii_case(hex)).unwrap_or(fallback)
+}
+
+/// `YYYY-MM-DD HH:MM` (wall time, stored as UTC like the event form) or a
+/// bare `YYYY-MM-DD`; the flag says which.
+fn parse_fix_time(s: &str) -> Option<(DateTime<Utc>, bool)> {
+    let s = s.trim();
+    if let Ok(dt) = NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M") {
+        return Some((dt.and_utc(), false));
     }
+    let d = NaiveDate::parse_from_str(s, "%Y-%m-%d").ok()?;
+    Some((d.and_hms_opt(0, 0, 0)?.and_utc(), true))
+}
+
+fn fix_time_string(at: DateTime<Utc>, date_only: bool) -> String {
+    at.format(if date_only { "%Y-%m-%d" } else { "%Y-%m-%d %H:%M" }).to_string()
 }
@@ -0,0 +1,165 @@
+//! `Ctrl+p`: every action by name, narrowed as you type, so nothing needs
+//! its key remembered. Picking one replays its key on the panel it belongs
+//! to, so it behaves exactly as the key would.
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+use super::{App, Panel};
+use crate::ui::InputMode;
+
+/// What picking a command does.
+#[derive(Clone, Copy)]
+pub enum Run {
+    /// A key sent to the month grid.
+    Grid(char),
+    /// A `Ctrl` key, sent to the panel the palette opened on.
+    Ctrl(char),
+    /// config.toml in `$EDITOR`.
+    Settings,
+}
+
+pub struct Command {
+    pub label: &'static str,
+    /// As the help and status bar write it; empty for none.
+    pub keys:  &'static str,
+    pub run:   Run,
+}
+
+const fn grid(label: &'static str, keys: &'static str, c: char) -> Command {
+    Command { label, keys, run: Run::Grid(c) }
+}
+
+const fn ctrl(label: &'static str, keys: &'static str, c: char) -> Command {
+    Command { label, keys, run: Run::Ctrl(c) }
+}
+
+/// In the order listed before anything is typed: the everyday ones first.
+const COMMANDS: &[Command] = &[
+    grid("Go to today",                "t",       't'),
+    grid("Go to date…",                "g",       'g'),
+    grid("New event",                  "n",       'n'),
+    grid("New task",                   "N",       'N'),
+    ctrl("Sync now",                   "Ctrl+s",  's'),
+    grid("Today at a glance",          "Space G", 'G'),
+    grid("Plan my day",                "Space p", 'p'),
+    grid("Next month",                 "]",       ']'),
+    grid("Previous month",             "[",       '['),
+    grid("Switch theme",               "Space T", 'T'),
+    grid("Edit theme",                 "Space E", 'E'),
+    grid("Show or hide holidays",      "Space V", 'V'),
+    Command { label: "Open settings (config.toml)", keys: "", run: Run::Settings },
+    grid("Stats",                      "Space S", 'S'),
+    grid("Time report",                "Space H", 'H'),
+    grid("Habits",                     "Space B", 'B'),
+    grid("Checklists",                 "Space L", 'L'),
+    grid("Ideal week",                 "Space W", 'W'),
+    grid("Meal plan",                  "Space M", 'M'),
+    grid("Custom panels",              "Space C", 'C'),
+    grid("Export",                     "Space X", 'X'),
+    grid("Find duplicate events",      "Space U", 'U'),
+    grid("Pin all-day events",         "^",       '^'),
+    grid("Start or stop a pomodoro",   "P",       'P'),
+    ctrl("Start or stop the timer",    "Ctrl+t",  't'),
+    grid("Connect Google",             "Space A", 'A'),
+    grid("Disconnect",                 "Space D", 'D'),
+    grid("Change the sync interval",   "Space I", 'I'),
+    grid("Review quarantined changes", "Space Q", 'Q'),
+    grid("Sync log",                   "Space Y", 'Y'),
+    grid("Logs",                       "Space O", 'O'),
+    grid("Reload everything",          "R",       'R'),
+    grid("All keys (help)",            "?",       '?'),
+    grid("Quit",                       "q",       'q'),
+];
+
+/// What's listed for `query`: the commands that do something here, best
+/// match first.
+pub fn matches(app: &App, query: &str) -> Vec<&'static Command> {
+    let mut found: Vec<(i64, &'static Command)> = COMMANDS.iter()
+        .filter(|c| match c.run {
+            Run::Grid('M')       => app.config.ui.meal_plan,
+            Run::Grid('D' | 'I') => app.sync.is_some(),
+            _                    => true,
+        })
+        .filter_map(|c| Some((score(query, c.label)?, c)))
+        .collect();
+    // Stable, so equal scores keep the list's order
+    found.sort_by_key(|(s, _)| -s);
+    found.into_iter().map(|(_, c)| c).collect()
+}
+
+/// None unless `query`'s letters appear in `label` in order (case and
+/// spaces ignored). Higher is better: runs of adjacent letters and letters
+/// starting a word count extra, and a gap costs a little.
+fn score(query: &str, label: &str) -> Option<i64> {
+    let label: Vec<char> = label.to_lowercase().chars().collect();
+    let mut score = 0;
+    let mut at    = 0;
+    let mut last  = None;
+    for q in query.to_lowercase().chars().filter(|c| !c.is_whitespace()) {
+        let i = at + label[at..].iter().position(|&c| c == q)?;
+        score += match last {
+            Some(prev) if prev + 1 == i => 3,
+            _ if i == 0 || !label[i - 1].is_alphanumeric() => 2,
+            Some(prev) => -((i - prev) as i64).min(3),
+            None       => 0,
+        };
+        last = Some(i);
+        at   = i + 1;
+    }
+    Some(score)
+}
+
+impl App {
+    /// `Ctrl+p`, from any panel that isn't taking text.
+    pub(super) fn open_palette(&mut self) {
+        self.ui.palette_query.clear();
+        self.ui.palette_cursor = 0;
+        self.ui.input_mode     = InputMode::Insert;
+        self.palette_from      = Some(self.active_panel.clone());
+        self.active_panel      = Panel::Palette;
+    }
+
+    pub(super) fn key_palette(&mut self, key: KeyEvent) {
+        let count = matches(self, &self.ui.palette_query).len();
+        let last  = count.saturating_sub(1);
+        let ctrl  = key.modifiers.contains(KeyModifiers::CONTROL);
+        match key.code {
+            KeyCode::Down                 => self.ui.palette_cursor = (self.ui.palette_cursor + 1).min(last),
+            KeyCode::Char('n') if ctrl    => self.ui.palette_cursor = (self.ui.palette_cursor + 1).min(last),
+            KeyCode::Up                   => self.ui.palette_cursor = self.ui.palette_cursor.saturating_sub(1),
+            KeyCode::Char('p') if ctrl    => self.ui.palette_cursor = self.ui.palette_cursor.saturating_sub(1),
+            KeyCode::Backspace => {
+                self.ui.palette_query.pop();
+                self.ui.palette_cursor = 0;
+            }
+            KeyCode::Char(c) if !ctrl => {
+                self.ui.palette_query.push(c);
+                self.ui.palette_cursor = 0;
+            }
+            KeyCode::Enter => self.run_palette(),
+            _ => {}
+        }
+    }
+
+    /// Back to where it was opened.
+    pub(super) fn close_palette(&mut self) {
+        self.active_panel  = self.palette_from.take().unwrap_or(Panel::Calendar);
+        self.ui.input_mode = InputMode::Normal;
+    }
+
+    /// Closes the palette and queues the picked command's key, which the
+    /// event loop sends on next.
+    fn run_palette(&mut self) {
+        let picked = matches(self, &self.ui.palette_query).get(self.ui.palette_cursor).copied();
+        self.close_palette();
+        let Some(cmd) = picked else { return };
+        match cmd.run {
+            Run::Grid(c) => {
+                self.active_panel = Panel::Calendar;
+                self.palette_run  = Some(KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE));
+            }
+            Run::Ctrl(c) => self.palette_run = Some(KeyEvent::new(KeyCode::Char(c), KeyModifiers::CONTROL)),
+            Run::Settings => self.edit_config = true,
+        }
+    }
+}
@@ -0,0 +1,95 @@
+//! Event check-in: what actually happened to a past event.
+//!
+//! Unmarked events are assumed to have happened, so meeting load is only
+//! reduced by events explicitly marked skipped or cancelled.
+
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::model::Event;
+
+/// How many days back the stats dashboard looks.
+pub const WINDOW_DAYS: i64 = 28;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Attendance {
+    Attended,
+    Skipped,
+    Cancelled,
+}
+
+impl Attendance {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Attended  => "attended",
+            Self::Skipped   => "skipped",
+            Self::Cancelled => "cancelled",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "attended"  => Some(Self::Attended),
+            "skipped"   => Some(Self::Skipped),
+            "cancelled" => Some(Self::Cancelled),
+            _           => None,
+        }
+    }
+
+    /// Unmarked → attended → skipped → cancelled → unmarked.
+    pub fn cycle(current: Option<Self>) -> Option<Self> {
+        match current {
+            None                  => Some(Self::Attended),
+            Some(Self::Attended)  => Some(Self::Skipped),
+            Some(Self::Skipped)   => Some(Self::Cancelled),
+            Some(Self::Cancelled) => None,
+        }
+    }
+
+    pub fn glyph(self) -> &'static str {
+        match self {
+            Self::Attended  => "✓",
+            Self::Skipped   => "✗",
+            Self::Cancelled => "⊘",
+        }
+    }
+}
+
+/// Hours of timed events over the stats window, split by check-in.
+#[derive(Debug, Clone, Default)]
+pub struct MeetingLoad {
+    pub events:    usize,
+    pub scheduled: f64,
+    pub attended:  f64,
+    pub skipped:   f64,
+    pub cancelled: f64,
+    pub unmarked:  f64,
+}
+
+impl MeetingLoad {
+    /// Hours that actually happened: attended plus unmarked.
+    pub fn actual(&self) -> f64 { self.attended + self.unmarked }
+
+    pub fn per_week(hours: f64) -> f64 { hours * 7.0 / WINDOW_DAYS as f64 }
+}
+
+/// Only events already over count; all-day events, PTO and the local focus
+/// and timer layers aren't meetings.
+pub fn summarize(events: &[Event], now: DateTime<Utc>) -> MeetingLoad {
+    let since = now - Duration::days(WINDOW_DAYS);
+    let mut load = MeetingLoad::default();
+    for ev in events.iter().filter(|e| !e.all_day && !e.pto && !e.is_local_layer() && e.end <= now) {
+        // Clip events straddling the window start
+        let hours = (ev.end - ev.start.max(since)).num_minutes().max(0) as f64 / 60.0;
+        load.events    += 1;
+        load.scheduled += hours;
+        match ev.attendance {
+            Some(Attendance::Attended)  => load.attended  += hours,
+            Some(Attendance::Skipped)   => load.skipped   += hours,
+            Some(Attendance::Cancelled) => load.cancelled += hours,
+            None                        => load.unmarked  += hours,
+        }
+    }
+    load
+}
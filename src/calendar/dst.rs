@@ -0,0 +1,88 @@
+//! Daylight-saving transitions: spotting events whose wall-clock times
+//! straddle a clock change in their timezone. Also the system zone itself,
+//! which changes under a running app when the machine travels.
+
+use chrono::{DateTime, Duration, FixedOffset, NaiveDate, NaiveTime, Offset, TimeZone, Utc};
+use chrono_tz::Tz;
+
+/// A clock change inside an event's span.
+#[derive(Debug, Clone)]
+pub struct DstShift {
+    pub tz:     Tz,
+    /// First instant on the new offset.
+    pub at:     DateTime<Utc>,
+    pub before: FixedOffset,
+}
+
+impl DstShift {
+    /// e.g. "clocks 02:00 CET → 03:00 CEST"
+    pub fn describe(&self) -> String {
+        let pre  = self.at.with_timezone(&self.before).format("%H:%M");
+        let post = self.at.with_timezone(&self.tz).format("%H:%M %Z");
+        let abbr = (self.at - Duration::seconds(1)).with_timezone(&self.tz).format("%Z");
+        format!("clocks {pre} {abbr} → {post}")
+    }
+
+    /// Wall-clock times of `start`/`end` on either side of the change,
+    /// e.g. "01:30 CET – 04:30 CEST".
+    pub fn wall_times(&self, start: DateTime<Utc>, end: DateTime<Utc>) -> String {
+        format!(
+            "{} – {}",
+            start.with_timezone(&self.tz).format("%H:%M %Z"),
+            end.with_timezone(&self.tz).format("%H:%M %Z"),
+        )
+    }
+}
+
+/// The zone named in config (or on an event), else the system zone, else UTC.
+pub fn zone(name: Option<&str>) -> Tz {
+    name.and_then(|n| n.parse().ok())
+        .or_else(system)
+        .unwrap_or(Tz::UTC)
+}
+
+/// The zone the OS is set to now, if it names one chrono-tz knows.
+pub fn system() -> Option<Tz> {
+    iana_time_zone::get_timezone().ok().and_then(|n| n.parse().ok())
+}
+
+/// A wall time entered in `from` as the wall time of the same instant in
+/// `to`: 09:00 in Berlin is 03:00 in New York.
+pub fn rebase(t: DateTime<Utc>, from: Tz, to: Tz) -> DateTime<Utc> {
+    match from.from_local_datetime(&t.naive_utc()).earliest() {
+        Some(at) => at.with_timezone(&to).naive_local().and_utc(),
+        // A time skipped by a clock change in `from`; nothing to keep
+        None     => t,
+    }
+}
+
+fn offset_at(tz: Tz, t: DateTime<Utc>) -> FixedOffset {
+    tz.offset_from_utc_datetime(&t.naive_utc()).fix()
+}
+
+/// The transition between `start` and `end`, if the offset differs at the two
+/// ends. Events spanning several transitions report the first.
+pub fn crossing(start: DateTime<Utc>, end: DateTime<Utc>, tz: Tz) -> Option<DstShift> {
+    let before = offset_at(tz, start);
+    if end <= start || offset_at(tz, end) == before { return None; }
+
+    // Binary search for the first instant off the starting offset
+    let (mut lo, mut hi) = (start, end);
+    while hi - lo > Duration::seconds(1) {
+        let mid = lo + (hi - lo) / 2;
+        if offset_at(tz, mid) == before { lo = mid } else { hi = mid }
+    }
+    Some(DstShift { tz, at: hi, before })
+}
+
+/// Same check for wall-clock times typed into the event form. Times that fall
+/// in a spring-forward gap resolve to the instant just after it.
+pub fn wall_crossing(date: NaiveDate, start: NaiveTime, end: NaiveTime, tz: Tz) -> Option<DstShift> {
+    let instant = |t: NaiveTime| {
+        let local = date.and_time(t);
+        tz.from_local_datetime(&local).earliest()
+            .or_else(|| tz.from_local_datetime(&(local + Duration::hours(1))).earliest())
+            .map(|d| d.with_timezone(&Utc))
+    };
+    crossing(instant(start)?, instant(end)?, tz)
+}
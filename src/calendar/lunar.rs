@@ -0,0 +1,175 @@
+//! Vietnamese lunisolar calendar (âm lịch).
+//!
+//! Ho Ngoc Duc's astronomical algorithm: new moons and solar longitude are
+//! computed for the UTC+7 meridian, the month containing the winter solstice
+//! is month 11, and a 13-month year repeats the first month with no major
+//! solar term. Accurate for roughly 1800–2199.
+
+use chrono::{Datelike, NaiveDate};
+use std::f64::consts::PI;
+
+/// Vietnam's meridian; Chinese dates would use 8.0.
+const TIME_ZONE: f64 = 7.0;
+
+/// Julian day number of 0001-01-01 minus one, for chrono's day count.
+const JD_CE_OFFSET: i64 = 1721425;
+
+/// Mean synodic month in days.
+const SYNODIC_MONTH: f64 = 29.530588853;
+
+/// Julian day of the new moon used as k = 0 (1900-01-01).
+const NEW_MOON_EPOCH: f64 = 2415021.076998695;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LunarDate {
+    pub day:   u32,
+    pub month: u32,
+    pub year:  i32,
+    /// The repeated (intercalary) month of a 13-month year.
+    pub leap:  bool,
+}
+
+fn jd(date: NaiveDate) -> i64 { date.num_days_from_ce() as i64 + JD_CE_OFFSET }
+
+fn from_jd(jd: i64) -> Option<NaiveDate> {
+    NaiveDate::from_num_days_from_ce_opt(i32::try_from(jd - JD_CE_OFFSET).ok()?)
+}
+
+/// Julian day (fractional, UT) of the k-th new moon after the epoch.
+fn new_moon(k: i64) -> f64 {
+    let k  = k as f64;
+    let t  = k / 1236.85;
+    let (t2, t3) = (t * t, t * t * t);
+    let dr = PI / 180.0;
+
+    let mut jd1 = 2415020.75933 + 29.53058868 * k + 0.0001178 * t2 - 0.000000155 * t3;
+    jd1 += 0.00033 * ((166.56 + 132.87 * t - 0.009173 * t2) * dr).sin();
+    let m   = 359.2242 + 29.10535608 * k - 0.0000333 * t2 - 0.00000347 * t3;
+    let mpr = 306.0253 + 385.81691806 * k + 0.0107306 * t2 + 0.00001236 * t3;
+    let f   = 21.2964 + 390.67050646 * k - 0.0016528 * t2 - 0.00000239 * t3;
+
+    let c1 = (0.1734 - 0.000393 * t) * (m * dr).sin() + 0.0021 * (2.0 * dr * m).sin()
+        - 0.4068 * (mpr * dr).sin() + 0.0161 * (dr * 2.0 * mpr).sin()
+        - 0.0004 * (dr * 3.0 * mpr).sin()
+        + 0.0104 * (dr * 2.0 * f).sin() - 0.0051 * (dr * (m + mpr)).sin()
+        - 0.0074 * (dr * (m - mpr)).sin() + 0.0004 * (dr * (2.0 * f + m)).sin()
+        - 0.0004 * (dr * (2.0 * f - m)).sin() - 0.0006 * (dr * (2.0 * f + mpr)).sin()
+        + 0.0010 * (dr * (2.0 * f - mpr)).sin() + 0.0005 * (dr * (2.0 * mpr + m)).sin();
+
+    let delta_t = if t < -11.0 {
+        0.001 + 0.000839 * t + 0.0002261 * t2 - 0.00000845 * t3 - 0.000000081 * t * t3
+    } else {
+        -0.000278 + 0.000265 * t + 0.000262 * t2
+    };
+    jd1 + c1 - delta_t
+}
+
+/// Apparent solar longitude in radians, normalised to [0, 2π).
+fn sun_longitude(jdn: f64) -> f64 {
+    let t  = (jdn - 2451545.0) / 36525.0;
+    let t2 = t * t;
+    let dr = PI / 180.0;
+    let m  = 357.52910 + 35999.05030 * t - 0.0001559 * t2 - 0.00000048 * t * t2;
+    let l0 = 280.46645 + 36000.76983 * t + 0.0003032 * t2;
+    let dl = (1.914600 - 0.004817 * t - 0.000014 * t2) * (dr * m).sin()
+        + (0.019993 - 0.000101 * t) * (dr * 2.0 * m).sin()
+        + 0.000290 * (dr * 3.0 * m).sin();
+    ((l0 + dl) * dr).rem_euclid(2.0 * PI)
+}
+
+/// Major solar term (0–11, 30° sectors) at local midnight starting `day`.
+fn solar_term(day: i64) -> i64 {
+    (sun_longitude(day as f64 - 0.5 - TIME_ZONE / 24.0) / PI * 6.0).floor() as i64
+}
+
+fn new_moon_day(k: i64) -> i64 {
+    (new_moon(k) + 0.5 + TIME_ZONE / 24.0).floor() as i64
+}
+
+/// First day of the lunar month containing the winter solstice of `year`.
+fn month11(year: i32) -> Option<i64> {
+    let off = jd(NaiveDate::from_ymd_opt(year, 12, 31)?) as f64 - 2415021.0;
+    let k   = (off / SYNODIC_MONTH).floor() as i64;
+    let nm  = new_moon_day(k);
+    Some(if solar_term(nm) >= 9 { new_moon_day(k - 1) } else { nm })
+}
+
+/// Offset (in months after month 11) of the leap month in a 13-month year.
+fn leap_month_offset(a11: i64) -> i64 {
+    let k = ((a11 as f64 - NEW_MOON_EPOCH) / SYNODIC_MONTH + 0.5).floor() as i64;
+    let mut i    = 1;
+    let mut arc  = solar_term(new_moon_day(k + i));
+    loop {
+        let last = arc;
+        i += 1;
+        arc = solar_term(new_moon_day(k + i));
+        if arc == last || i >= 14 { break; }
+    }
+    i - 1
+}
+
+pub fn solar_to_lunar(date: NaiveDate) -> Option<LunarDate> {
+    let day_number = jd(date);
+    let k = ((day_number as f64 - NEW_MOON_EPOCH) / SYNODIC_MONTH).floor() as i64;
+    let mut month_start = new_moon_day(k + 1);
+    if month_start > day_number { month_start = new_moon_day(k); }
+
+    let year = date.year();
+    let mut a11 = month11(year)?;
+    let b11;
+    let mut lunar_year;
+    if a11 >= month_start {
+        lunar_year = year;
+        b11 = a11;
+        a11 = month11(year - 1)?;
+    } else {
+        lunar_year = year + 1;
+        b11 = month11(year + 1)?;
+    }
+
+    let day  = (day_number - month_start + 1) as u32;
+    let diff = (month_start - a11) / 29;
+    let mut leap  = false;
+    let mut month = diff + 11;
+    if b11 - a11 > 365 {
+        let leap_diff = leap_month_offset(a11);
+        if diff >= leap_diff {
+            month = diff + 10;
+            leap  = diff == leap_diff;
+        }
+    }
+    if month > 12 { month -= 12; }
+    if month >= 11 && diff < 4 { lunar_year -= 1; }
+
+    Some(LunarDate { day, month: month as u32, year: lunar_year, leap })
+}
+
+/// Gregorian date of a lunar date; None if `leap` is set but that month
+/// isn't the leap month of `year`, or the month has no such day (day 30 of
+/// a 29-day month).
+pub fn lunar_to_solar(year: i32, month: u32, day: u32, leap: bool) -> Option<NaiveDate> {
+    let (a11, b11) = if month < 11 {
+        (month11(year - 1)?, month11(year)?)
+    } else {
+        (month11(year)?, month11(year + 1)?)
+    };
+    let k = (0.5 + (a11 as f64 - NEW_MOON_EPOCH) / SYNODIC_MONTH).floor() as i64;
+    let mut off = (month as i64 - 11).rem_euclid(12);
+
+    if b11 - a11 > 365 {
+        let leap_off   = leap_month_offset(a11);
+        let leap_month = (leap_off - 2).rem_euclid(12);
+        if leap && month as i64 != leap_month {
+            return None;
+        }
+        if leap || off >= leap_off { off += 1; }
+    } else if leap {
+        return None;
+    }
+
+    let start = new_moon_day(k + off);
+    if day == 0 || day as i64 > new_moon_day(k + off + 1) - start {
+        return None;
+    }
+    from_jd(start + day as i64 - 1)
+}
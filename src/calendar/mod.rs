@@ -1,9 +1,13 @@
-use chrono::{Datelike, NaiveDate};
+pub mod dst;
+pub mod lunar;
+
+use chrono::{DateTime, Datelike, Duration, NaiveDate, NaiveTime, Utc, Weekday};
+use std::collections::HashSet;
 
 /// Returns weeks for a given month. Each week is 7 Option<NaiveDate> slots
-/// (None = padding day outside the month).
+/// (None = padding day outside the month). Empty for an invalid month.
 pub fn month_weeks(year: i32, month: u32) -> Vec<Vec<Option<NaiveDate>>> {
-    let first         = NaiveDate::from_ymd_opt(year, month, 1).unwrap();
+    let Some(first)   = NaiveDate::from_ymd_opt(year, month, 1) else { return Vec::new() };
     let start_offset  = first.weekday().num_days_from_monday() as i64;
     let days_in_month = days_in_month(year, month) as i64;
 
@@ -26,12 +30,240 @@ pub fn month_weeks(year: i32, month: u32) -> Vec<Vec<Option<NaiveDate>>> {
     weeks
 }
 
+/// 0 for a month outside 1–12 (or a year chrono can't represent).
 pub fn days_in_month(year: i32, month: u32) -> u32 {
-    let next = if month == 12 {
-        NaiveDate::from_ymd_opt(year + 1, 1, 1)
+    if NaiveDate::from_ymd_opt(year, 1, 1).is_none() { return 0; }
+    match month {
+        1 | 3 | 5 | 7 | 8 | 10 | 12 => 31,
+        4 | 6 | 9 | 11              => 30,
+        2 if is_leap_year(year)     => 29,
+        2                           => 28,
+        _                           => 0,
+    }
+}
+
+pub fn is_leap_year(year: i32) -> bool {
+    NaiveDate::from_ymd_opt(year, 2, 29).is_some()
+}
+
+/// `[start, end)` of a day in UTC, clamped rather than overflowing at the
+/// edges of chrono's range.
+pub fn day_bounds(date: NaiveDate) -> (DateTime<Utc>, DateTime<Utc>) {
+    let start = date.and_time(NaiveTime::MIN).and_utc();
+    (start, start.checked_add_signed(Duration::days(1)).unwrap_or(DateTime::<Utc>::MAX_UTC))
+}
+
+/// `date` moved by `days`, or None past the ends of the calendar.
+pub fn add_days(date: NaiveDate, days: i64) -> Option<NaiveDate> {
+    date.checked_add_signed(Duration::try_days(days)?)
+}
+
+/// `[start, end)` of a month in UTC, as the month grid loads it; None for
+/// an invalid month.
+pub fn month_bounds(year: i32, month: u32) -> Option<(DateTime<Utc>, DateTime<Utc>)> {
+    let first = NaiveDate::from_ymd_opt(year, month, 1)?;
+    let last  = add_days(first, days_in_month(year, month) as i64 - 1)?;
+    Some((day_bounds(first).0, day_bounds(last).1))
+}
+
+/// The month `months` after `year`-`month` (before, when negative), or None
+/// where the year either side of it is past what chrono represents.
+pub fn shift_month(year: i32, month: u32, months: i64) -> Option<(i32, u32)> {
+    let index = year as i64 * 12 + month as i64 - 1 + months;
+    let year  = i32::try_from(index.div_euclid(12)).ok()?;
+    NaiveDate::from_ymd_opt(year.checked_sub(1)?, 1, 1)?;
+    NaiveDate::from_ymd_opt(year.checked_add(1)?, 12, 31)?;
+    Some((year, index.rem_euclid(12) as u32 + 1))
+}
+
+/// The `nth` `weekday` of a month (1 = first); negative `nth` counts from the
+/// end of the month (-1 = last).
+pub fn nth_weekday(year: i32, month: u32, weekday: Weekday, nth: i32) -> Option<NaiveDate> {
+    if nth > 0 {
+        let first  = NaiveDate::from_ymd_opt(year, month, 1)?;
+        let offset = (7 + weekday.num_days_from_monday() as i64
+                        - first.weekday().num_days_from_monday() as i64) % 7;
+        let date   = first + Duration::days(offset + 7 * (nth as i64 - 1));
+        (date.month() == month).then_some(date)
+    } else if nth < 0 {
+        let last   = NaiveDate::from_ymd_opt(year, month, days_in_month(year, month))?;
+        let offset = (7 + last.weekday().num_days_from_monday() as i64
+                        - weekday.num_days_from_monday() as i64) % 7;
+        let date   = last - Duration::days(offset + 7 * (-nth as i64 - 1));
+        (date.month() == month).then_some(date)
     } else {
-        NaiveDate::from_ymd_opt(year, month + 1, 1)
+        None
+    }
+}
+
+/// Monday of the week containing `date`.
+pub fn week_start(date: NaiveDate) -> NaiveDate {
+    add_days(date, -(date.weekday().num_days_from_monday() as i64)).unwrap_or(date)
+}
+
+pub fn is_weekend(date: NaiveDate) -> bool {
+    matches!(date.weekday(), Weekday::Sat | Weekday::Sun)
+}
+
+/// Mon–Fri and not one of the given days off.
+pub fn is_working_day(date: NaiveDate, days_off: &HashSet<NaiveDate>) -> bool {
+    !is_weekend(date) && !days_off.contains(&date)
+}
+
+// ─── Typed dates ──────────────────────────────────────────────────────────────
+
+const MONTHS: [&str; 12] = [
+    "january", "february", "march", "april", "may", "june",
+    "july", "august", "september", "october", "november", "december",
+];
+
+/// A typed date, as the `g` prompt, due dates and moves take it:
+/// `YYYY-MM-DD`; `today`, `tomorrow`, `yesterday`; an offset (`+3`, `+3d`,
+/// `-1w`, `+2m`, `+1y`); a month and day, the year optional (`mar 14`,
+/// `14 march`, `mar 14 2027`), or a month alone for its first; a weekday
+/// (`fri` is the coming one, today included; `next fri` after today,
+/// `last fri` before it); or `next` / `last` `week`, `month` or `year`.
+/// Everything relative is from `from`.
+pub fn parse_date(text: &str, from: NaiveDate) -> Option<NaiveDate> {
+    let text = text.trim().to_lowercase().replace(',', " ");
+    if let Ok(date) = NaiveDate::parse_from_str(&text, "%Y-%m-%d") { return Some(date); }
+    if text.starts_with(['+', '-']) { return shift(from, &text); }
+
+    let words: Vec<&str> = text.split_whitespace().collect();
+    match words.as_slice() {
+        ["today"]     => Some(from),
+        ["tomorrow"]  => add_days(from, 1),
+        ["yesterday"] => add_days(from, -1),
+        [dir @ ("next" | "last"), unit] => {
+            let back = *dir == "last";
+            match *unit {
+                "week"  => shift(from, if back { "-1w" } else { "+1w" }),
+                "month" => shift(from, if back { "-1m" } else { "+1m" }),
+                "year"  => shift(from, if back { "-1y" } else { "+1y" }),
+                day     => add_days(from, match (back, days_to(from, day.parse().ok()?)) {
+                    (false, 0) => 7,
+                    (false, n) => n,
+                    (true, n)  => n - 7,
+                }),
+            }
+        }
+        [day] if day.parse::<Weekday>().is_ok() => add_days(from, days_to(from, day.parse().ok()?)),
+        _ => month_day(&words, from.year()),
+    }
+}
+
+/// `+3` / `+3d` days, `w` weeks, `m` months or `y` years from `from`.
+fn shift(from: NaiveDate, offset: &str) -> Option<NaiveDate> {
+    let (n, unit) = match offset.char_indices().last()? {
+        (i, c @ ('d' | 'w' | 'm' | 'y')) => (&offset[..i], c),
+        _                                 => (offset, 'd'),
+    };
+    let n: i64 = n.parse().ok()?;
+    let months = |m: i64| {
+        let by = chrono::Months::new(u32::try_from(m.unsigned_abs()).ok()?);
+        if m < 0 { from.checked_sub_months(by) } else { from.checked_add_months(by) }
+    };
+    match unit {
+        'w' => add_days(from, n.checked_mul(7)?),
+        'm' => months(n),
+        'y' => months(n.checked_mul(12)?),
+        _   => add_days(from, n),
+    }
+}
+
+/// Days from `from` to the next `day`, 0 when it's that day.
+fn days_to(from: NaiveDate, day: Weekday) -> i64 {
+    (7 + day.num_days_from_monday() as i64 - from.weekday().num_days_from_monday() as i64) % 7
+}
+
+/// `mar 14`, `14 mar`, `march 14 2027`, `mar` (the 1st), `mar 2027`.
+fn month_day(words: &[&str], year: i32) -> Option<NaiveDate> {
+    let month  = |w: &str| w.len() >= 3 && MONTHS.iter().any(|m| m.starts_with(w));
+    let number = |w: &str| w.parse::<i32>().ok();
+    let (name, rest): (&str, Vec<i32>) = match words {
+        [m, rest @ ..] if month(m) => (m, rest.iter().map(|w| number(w)).collect::<Option<_>>()?),
+        [d, m, rest @ ..] if month(m) => {
+            (m, std::iter::once(*d).chain(rest.iter().copied()).map(number).collect::<Option<_>>()?)
+        }
+        _ => return None,
     };
-    let first = NaiveDate::from_ymd_opt(year, month, 1).unwrap();
-    (next.unwrap() - first).num_days() as u32
+    let m = MONTHS.iter().position(|full| full.starts_with(name))? as u32 + 1;
+    match rest.as_slice() {
+        []                     => NaiveDate::from_ymd_opt(year, m, 1),
+        [y] if *y >= 1000      => NaiveDate::from_ymd_opt(*y, m, 1),
+        [d]                    => NaiveDate::from_ymd_opt(year, m, u32::try_from(*d).ok()?),
+        [d, y] if *y >= 1000   => NaiveDate::from_ymd_opt(*y, m, u32::try_from(*d).ok()?),
+        _                      => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    #[test]
+    fn february_follows_leap_years() {
+        assert_eq!(days_in_month(2028, 2), 29);
+        assert_eq!(days_in_month(2027, 2), 28);
+        assert_eq!(days_in_month(2100, 2), 28);
+        assert_eq!(days_in_month(2000, 2), 29);
+        assert_eq!(days_in_month(2026, 13), 0);
+        assert!(month_weeks(2026, 0).is_empty());
+    }
+
+    #[test]
+    fn months_cross_year_boundaries() {
+        assert_eq!(shift_month(2026, 12, 1), Some((2027, 1)));
+        assert_eq!(shift_month(2027, 1, -1), Some((2026, 12)));
+        assert_eq!(shift_month(2026, 10, -22), Some((2024, 12)));
+        assert_eq!(shift_month(2026, 10, 27), Some((2029, 1)));
+        assert_eq!(shift_month(i32::MAX, 1, 0), None);
+        assert_eq!(shift_month(2026, 1, i64::MAX / 2), None);
+    }
+
+    #[test]
+    fn month_bounds_span_the_whole_month() {
+        let (from, to) = month_bounds(2026, 12).unwrap();
+        assert_eq!(from, day_bounds(date(2026, 12, 1)).0);
+        assert_eq!(to, day_bounds(date(2027, 1, 1)).0);
+        let (_, to) = month_bounds(2028, 2).unwrap();
+        assert_eq!(to, day_bounds(date(2028, 3, 1)).0);
+        assert_eq!(month_bounds(2026, 13), None);
+    }
+
+    #[test]
+    fn day_helpers_stop_at_the_ends_of_the_calendar() {
+        assert_eq!(add_days(date(2026, 12, 31), 1), Some(date(2027, 1, 1)));
+        assert_eq!(add_days(date(2028, 2, 28), 1), Some(date(2028, 2, 29)));
+        assert_eq!(add_days(NaiveDate::MAX, 1), None);
+        assert_eq!(add_days(NaiveDate::MIN, -1), None);
+        assert_eq!(add_days(date(2026, 1, 1), i64::MAX), None);
+        assert_eq!(day_bounds(NaiveDate::MAX).1, DateTime::<Utc>::MAX_UTC);
+        assert_eq!(week_start(NaiveDate::MIN), NaiveDate::MIN);
+    }
+
+    #[test]
+    fn dates_before_1970_and_after_2100() {
+        let (from, to) = day_bounds(date(1965, 3, 1));
+        assert_eq!((to - from).num_hours(), 24);
+        assert!(from.timestamp() < 0);
+        assert_eq!(week_start(date(2101, 1, 1)), date(2100, 12, 27));
+        assert_eq!(nth_weekday(1960, 2, Weekday::Mon, -1), Some(date(1960, 2, 29)));
+    }
+
+    #[test]
+    fn typed_dates_count_from_the_given_day() {
+        let thu = date(2026, 10, 15);
+        assert_eq!(parse_date("+3", thu), Some(date(2026, 10, 18)));
+        assert_eq!(parse_date("-2w", thu), Some(date(2026, 10, 1)));
+        assert_eq!(parse_date("next fri", thu), Some(date(2026, 10, 16)));
+        assert_eq!(parse_date("mar 14", thu), Some(date(2026, 3, 14)));
+        assert_eq!(parse_date("+9223372036854775807w", thu), None);
+        assert_eq!(parse_date("-99999999999999d", thu), None);
+        assert_eq!(parse_date("+", thu), None);
+    }
 }
@@ -1,29 +1,468 @@
 use anyhow::Result;
+use chrono::Weekday;
 use serde::Deserialize;
 use std::path::PathBuf;
 
+pub use crate::holidays::HolidayConfig;
+pub use crate::status::StatusLineConfig;
 use crate::sync::google::GoogleConfig;
+use crate::sync::outlook::OutlookConfig;
+use crate::sync::taskwarrior::TaskwarriorConfig;
+use crate::sync::todoist::TodoistConfig;
+use crate::sync::vault::VaultConfig;
+use crate::weather::WeatherConfig;
 
-#[derive(Debug, Deserialize, Default)]
+#[derive(Debug, Clone, Deserialize, Default)]
 pub struct AppConfig {
-    pub google: Option<GoogleConfig>,
-    pub sync:   Option<SyncConfig>,
+    pub google:   Option<GoogleConfig>,
+    /// Microsoft 365: Outlook calendars and To Do lists, next to or
+    /// instead of Google.
+    pub outlook:  Option<OutlookConfig>,
+    /// Todoist projects as task lists.
+    pub todoist:  Option<TodoistConfig>,
+    /// Taskwarrior projects as task lists, through the `task` command.
+    pub taskwarrior: Option<TaskwarriorConfig>,
+    /// A directory every event and task is mirrored to as plain files.
+    pub vault:    Option<VaultConfig>,
+    pub sync:     Option<SyncConfig>,
+    pub pto:      Option<PtoConfig>,
+    #[serde(default)]
+    pub ui:       UiConfig,
+    #[serde(default)]
+    pub holidays: HolidayConfig,
+    /// IANA zone for DST checks, e.g. "Europe/Berlin"; defaults to the system zone.
+    pub timezone: Option<String>,
+    /// Read-only ICS subscriptions (holiday calendars, fixtures, …).
+    #[serde(default)]
+    pub feeds: Vec<FeedConfig>,
+    /// Ideal-week template, compared against real events with `W`.
+    #[serde(default)]
+    pub ideal_week: Vec<IdealBlock>,
+    #[serde(default)]
+    pub backup:   BackupConfig,
+    #[serde(default)]
+    pub db:       DbConfig,
+    /// Rewrites applied to event titles as they're pulled.
+    #[serde(default)]
+    pub titles:   TitlesConfig,
+    /// Reminders before events start, and where they're delivered.
+    #[serde(default)]
+    pub notify:   NotifyConfig,
+    /// Working hours the focus report in the stats view measures.
+    #[serde(default)]
+    pub focus:    FocusConfig,
+    /// The `P` timer, and whether finished sessions go on the calendar.
+    #[serde(default)]
+    pub pomodoro: PomodoroConfig,
+    /// Encrypted copy of every change, appended to a file of your choosing.
+    #[serde(default)]
+    pub journal:  JournalConfig,
+    /// How much the log keeps, and how much detail goes into it.
+    #[serde(default)]
+    pub logs:     LogConfig,
+    /// Custom panels in the `C` overlay, each fed by a command's JSON.
+    #[serde(default)]
+    pub panels:   Vec<PanelConfig>,
+    /// Commands `|` sends the selected event or task to, as JSON.
+    #[serde(default)]
+    pub actions:  Vec<ActionConfig>,
+    /// Templates for `lm status-line`.
+    #[serde(default)]
+    pub status_line: StatusLineConfig,
+    /// Where the forecast under the month grid is for; none without it.
+    pub weather:  Option<WeatherConfig>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Deserialize)]
+pub struct FeedConfig {
+    /// Shown next to the feed's events; also the cache key.
+    pub name: String,
+    /// https:// or webcal:// URL of an .ics file.
+    pub url:  String,
+}
+
+fn default_panel_every() -> u64 { 300 }
+fn default_panel_timeout() -> u64 { 30 }
+
+/// A custom panel: what `command` prints, listed (see `plugins`).
+#[derive(Debug, Clone, Deserialize)]
+pub struct PanelConfig {
+    pub name:    String,
+    /// Run with `sh -c`; prints the panel's items as JSON.
+    pub command: String,
+    /// Seconds between runs while the panel is shown.
+    #[serde(default = "default_panel_every")]
+    pub every:   u64,
+    /// Seconds before a run is given up on.
+    #[serde(default = "default_panel_timeout")]
+    pub timeout: u64,
+}
+
+/// What an action is offered on.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ActionOn {
+    #[default]
+    Both,
+    Events,
+    Tasks,
+}
+
+/// A "send to" action: `command` gets the selected event or task on stdin
+/// (see `plugins::actions`).
+#[derive(Debug, Clone, Deserialize)]
+pub struct ActionConfig {
+    pub name:    String,
+    /// Run with `sh -c`.
+    pub command: String,
+    #[serde(default)]
+    pub on:      ActionOn,
+    /// Seconds before a run is given up on.
+    #[serde(default = "default_panel_timeout")]
+    pub timeout: u64,
+}
+
+/// One recurring block of the ideal week, e.g. deep work Mon–Fri 09:00–12:00.
+#[derive(Debug, Clone, Deserialize)]
+pub struct IdealBlock {
+    pub title: String,
+    pub days:  Vec<Weekday>,
+    /// "HH:MM"
+    pub start: String,
+    pub end:   String,
+}
+
+#[derive(Debug, Clone, Deserialize, Default)]
+#[serde(default)]
+pub struct TitlesConfig {
+    /// Drop emoji (and the joiners/selectors around them).
+    pub strip_emoji: bool,
+    /// Applied in order, each to the output of the previous one.
+    pub rules:       Vec<TitleRule>,
+}
+
+/// One rewrite. A rule with neither `replace` nor `prefix` does nothing.
+#[derive(Debug, Clone, Deserialize)]
+pub struct TitleRule {
+    /// Regex; omitted = every title.
+    pub pattern:  Option<String>,
+    /// Replaces every match (`$1` etc. refer to groups); "" deletes it.
+    pub replace:  Option<String>,
+    /// Prepended when the rule matches, e.g. "[W] ".
+    pub prefix:   Option<String>,
+    /// Only for this Google calendar id or feed name.
+    pub calendar: Option<String>,
+}
+
+/// Daily rolling snapshots of the database (see `lm backup`).
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct BackupConfig {
+    pub enabled: bool,
+    /// Snapshots kept, counting manual and pre-migration ones.
+    pub keep:    usize,
+    /// Encrypted off-site copies (`[backup.remote]`).
+    pub remote:  Option<RemoteBackupConfig>,
+}
+
+impl Default for BackupConfig {
+    fn default() -> Self {
+        Self { enabled: true, keep: 7, remote: None }
+    }
+}
+
+fn default_backup_region() -> String { "us-east-1".into() }
+fn default_backup_secret_env() -> String { "LM_BACKUP_SECRET".into() }
+fn default_backup_passphrase_env() -> String { "LM_BACKUP_PASSPHRASE".into() }
+fn default_remote_keep() -> usize { 7 }
+
+/// Where the daily snapshot is copied, encrypted, after it's taken (see
+/// `db::cloud`).
+#[derive(Debug, Clone, Deserialize)]
+pub struct RemoteBackupConfig {
+    /// A WebDAV folder (`https://…/lm-backups/`) or an S3 bucket and
+    /// prefix (`s3://bucket/lm-backups`).
+    pub url:            String,
+    /// S3 only: the service's address, for anything but AWS itself
+    /// (MinIO, R2, B2, …). Buckets are addressed path-style.
+    #[serde(default)]
+    pub endpoint:       Option<String>,
+    #[serde(default = "default_backup_region")]
+    pub region:         String,
+    /// The WebDAV user, or the S3 access key id.
+    #[serde(default)]
+    pub user:           Option<String>,
+    /// Environment variable holding the WebDAV password or S3 secret key.
+    #[serde(default = "default_backup_secret_env")]
+    pub secret_env:     String,
+    /// Environment variable holding the passphrase copies are sealed with.
+    #[serde(default = "default_backup_passphrase_env")]
+    pub passphrase_env: String,
+    /// Copies kept on the remote, oldest deleted first.
+    #[serde(default = "default_remote_keep")]
+    pub keep:           usize,
+}
+
+/// Housekeeping for lifemanager.db (see `lm db compact`).
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct DbConfig {
+    /// Synced deletions are kept this long before rows are purged for good.
+    pub purge_after_days: i64,
+}
+
+impl Default for DbConfig {
+    fn default() -> Self {
+        Self { purge_after_days: 30 }
+    }
+}
+
+/// Append-only encrypted change journal (see `lm journal`). Off unless
+/// `path` is set.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct JournalConfig {
+    /// File to append to; `~/` is expanded.
+    pub path:           Option<String>,
+    /// Environment variable holding the passphrase.
+    pub passphrase_env: String,
+}
+
+impl Default for JournalConfig {
+    fn default() -> Self {
+        Self { path: None, passphrase_env: "LM_JOURNAL_PASSPHRASE".into() }
+    }
+}
+
+/// Daily log files in the data dir (see `lm logs`).
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct LogConfig {
+    /// Days of log files kept, today's included.
+    pub keep:  usize,
+    /// Lowest level written: trace, debug, info, warn or error. `RUST_LOG`
+    /// overrides it.
+    pub level: String,
+}
+
+impl Default for LogConfig {
+    fn default() -> Self {
+        Self { keep: 7, level: "info".into() }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct PomodoroConfig {
+    pub minutes:         i64,
+    /// Log each session that runs its full length as an event on the local
+    /// focus layer, so the day shows deep work next to meetings.
+    pub log_to_calendar: bool,
+}
+
+impl Default for PomodoroConfig {
+    fn default() -> Self {
+        Self { minutes: 25, log_to_calendar: false }
+    }
+}
+
+/// The working day the stats view's focus report looks at.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct FocusConfig {
+    /// "HH:MM"
+    pub day_start:         String,
+    pub day_end:           String,
+    /// Gaps between meetings shorter than this count as fragmentation.
+    pub short_gap_minutes: i64,
+}
+
+impl Default for FocusConfig {
+    fn default() -> Self {
+        Self { day_start: "09:00".into(), day_end: "17:00".into(), short_gap_minutes: 30 }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct NotifyConfig {
+    pub enabled:        bool,
+    /// How long before a timed event starts its reminder goes out.
+    pub lead_minutes:   i64,
+    /// The TUI's "starting soon" banner shows this long before an event
+    /// (less its own lead time); 0 for none.
+    pub banner_minutes: i64,
+    /// Every backend listed gets every reminder.
+    pub backends:       Vec<NotifyBackend>,
+    /// "HH:MM" local time to send today's agenda to backends with `agenda = true`.
+    pub agenda_at:      Option<String>,
+    /// For reminders no rule matches.
+    pub urgency:        Urgency,
+    /// Sound theme name ("message-new-instant") the desktop backend asks for.
+    pub sound:          Option<String>,
+    /// The first rule matching an event sets its reminder's urgency and sound.
+    pub rules:          Vec<NotifyRule>,
+}
+
+impl Default for NotifyConfig {
+    fn default() -> Self {
+        Self {
+            enabled:        true,
+            lead_minutes:   10,
+            banner_minutes: 10,
+            backends:       vec![NotifyBackend::Desktop, NotifyBackend::Bell],
+            agenda_at:      None,
+            urgency:        Urgency::Normal,
+            sound:          None,
+            rules:          Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Urgency {
+    Low,
+    #[default]
+    Normal,
+    Critical,
+}
+
+impl Urgency {
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Low      => "low",
+            Self::Normal   => "normal",
+            Self::Critical => "critical",
+        }
+    }
+}
+
+/// `[[notify.rules]]`: matches an event when every field given matches, so
+/// `tag = "travel"` picks out a category and `title = "Flight"` one event.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct NotifyRule {
+    /// Event tag, with or without the `#`.
+    pub tag:      Option<String>,
+    /// Calendar id; "primary" for the main one.
+    pub calendar: Option<String>,
+    /// Part of the title, any case.
+    pub title:    Option<String>,
+    pub urgency:  Option<Urgency>,
+    pub sound:    Option<String>,
+}
+
+/// `[[notify.backends]]` entries, picked by `kind`.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+pub enum NotifyBackend {
+    /// Desktop notification over D-Bus (mako, dunst, …).
+    Desktop,
+    /// Terminal bell plus a toast in the TUI; a plain line on stdout otherwise.
+    Bell,
+    /// POSTs the reminder as JSON.
+    Webhook { url: String },
+    /// Runs `sh -c command` with LM_TITLE, LM_BODY, LM_START, LM_URGENCY and
+    /// LM_SOUND set.
+    Command { command: String },
+    /// Messages a chat through a bot from @BotFather.
+    Telegram {
+        token:   String,
+        chat_id: String,
+        #[serde(default)]
+        agenda:  bool,
+    },
+    /// Posts to a room as the user the access token belongs to.
+    Matrix {
+        /// e.g. "https://matrix.org"
+        homeserver:   String,
+        /// Room id ("!abc:matrix.org"), not an alias.
+        room:         String,
+        access_token: String,
+        #[serde(default)]
+        agenda:       bool,
+    },
+}
+
+/// Optional display features — all off by default.
+#[derive(Debug, Clone, Deserialize, Default)]
+#[serde(default)]
+pub struct UiConfig {
+    /// Meal-plan strip under the events panel and the `M` week grid.
+    pub meal_plan:      bool,
+    /// Vietnamese lunar day under each day of the month grid.
+    pub lunar_dates:    bool,
+    /// Two panes: the calendar, and the day's events and due tasks in one
+    /// list instead of separate events and tasks panels.
+    pub compact:        bool,
+    /// Delete events and tasks and disconnect Google without asking first.
+    pub skip_confirm:   bool,
+    /// A holiday's emoji in its month grid cell, in place of the event dot.
+    pub emoji:          bool,
+    /// All-day events and holidays in a band above the day's events rather
+    /// than among them by start (`^` flips it for the session).
+    pub pin_all_day:    bool,
+    /// Open on today's digest (overdue and due tasks, events, holidays
+    /// ahead) when there's anything in it.
+    pub startup_digest: bool,
+    /// ISO week numbers in a column left of the month grid.
+    pub week_numbers:   bool,
+    /// `[holidays]` countries left off the calendar, e.g. ["VN"]; still
+    /// days off for recurring tasks and PTO. `V` changes it for the session.
+    pub hide_holidays:  Vec<String>,
+    /// Holidays in a dim colour on the month grid rather than bold.
+    pub dim_holidays:   bool,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
 pub struct SyncConfig {
+    /// Seconds between automatic syncs; 300 when unset, 30 at the least,
+    /// 0 for none.
     pub interval_seconds: Option<u64>,
+    /// False syncs only on `Ctrl+s` (and Google push, if set up).
     pub auto_sync:        Option<bool>,
 }
 
+impl SyncConfig {
+    /// Seconds between automatic syncs, or None when they're off.
+    pub fn every(&self) -> Option<u64> {
+        match (self.auto_sync, self.interval_seconds) {
+            (Some(false), _) | (_, Some(0)) => None,
+            (_, secs)                       => Some(secs.unwrap_or(300).max(30)),
+        }
+    }
+}
+
+fn default_pto_days() -> f64 { 20.0 }
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct PtoConfig {
+    #[serde(default = "default_pto_days")]
+    pub annual_days:    f64,
+    /// Unused days carried into the next year (0 = use it or lose it).
+    #[serde(default)]
+    pub carry_over_max: f64,
+    /// First year to track; defaults to the year of the earliest PTO event.
+    pub since:          Option<i32>,
+}
+
 impl AppConfig {
+    pub fn path() -> PathBuf {
+        config_dir().join("config.toml")
+    }
+
     pub fn load() -> Result<Self> {
-        let path = config_dir().join("config.toml");
-        if path.exists() {
-            Ok(toml::from_str(&std::fs::read_to_string(&path)?)?)
+        let path = Self::path();
+        let mut cfg: AppConfig = if path.exists() {
+            toml::from_str(&std::fs::read_to_string(&path)?)?
         } else {
-            Ok(AppConfig::default())
+            AppConfig::default()
+        };
+        if cfg.google.is_none() {
+            cfg.google = GoogleConfig::embedded();
         }
+        Ok(cfg)
     }
 }
 
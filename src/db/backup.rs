@@ -0,0 +1,118 @@
+//! Rolling snapshots of lifemanager.db in `<data dir>/backups`, taken daily,
+//! before migrations and before destructive commands.
+
+use anyhow::{anyhow, bail, Result};
+use chrono::Local;
+use sqlx::{Row, SqlitePool};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use super::migrations;
+
+pub(super) const PREFIX: &str = "lifemanager-";
+
+pub fn dir() -> PathBuf {
+    super::data_dir().join("backups")
+}
+
+/// Writes a consistent copy of the live database (`VACUUM INTO` is safe with
+/// other connections open) named after the current time and `label`.
+pub async fn snapshot(pool: &SqlitePool, label: &str) -> Result<PathBuf> {
+    let dir = dir();
+    std::fs::create_dir_all(&dir)?;
+    let path = dir.join(format!("{PREFIX}{}-{label}.db", Local::now().format("%Y%m%d-%H%M%S")));
+    // Two snapshots in the same second would collide
+    let _ = std::fs::remove_file(&path);
+    sqlx::query("VACUUM INTO ?").bind(path.to_string_lossy().as_ref())
+        .execute(pool).await?;
+    tracing::info!("Backed up database to {}", path.display());
+    Ok(path)
+}
+
+/// Backup files, oldest first.
+pub fn list() -> Result<Vec<PathBuf>> {
+    let Ok(entries) = std::fs::read_dir(dir()) else { return Ok(Vec::new()) };
+    let mut files: Vec<PathBuf> = entries
+        .filter_map(|e| e.ok().map(|e| e.path()))
+        .filter(|p| {
+            let name = p.file_name().and_then(|n| n.to_str()).unwrap_or("");
+            name.starts_with(PREFIX) && name.ends_with(".db")
+        })
+        .collect();
+    // Names start with a timestamp, so this is chronological
+    files.sort();
+    Ok(files)
+}
+
+/// Whether the newest backup is older than `max_age` (or there is none).
+pub fn due(max_age: Duration) -> bool {
+    let newest = list().ok()
+        .and_then(|l| l.last().cloned())
+        .and_then(|p| p.metadata().ok()?.modified().ok());
+    match newest {
+        Some(t) => SystemTime::now().duration_since(t).is_ok_and(|age| age >= max_age),
+        None    => true,
+    }
+}
+
+/// Deletes all but the newest `keep` backups; returns how many were removed.
+pub fn rotate(keep: usize) -> Result<usize> {
+    let files = list()?;
+    let excess = files.len().saturating_sub(keep.max(1));
+    for f in &files[..excess] {
+        std::fs::remove_file(f)?;
+    }
+    Ok(excess)
+}
+
+/// Resolves a `lm restore` argument: a path, or a file name in the backup dir.
+pub fn find(arg: &str) -> Result<PathBuf> {
+    let path = PathBuf::from(arg);
+    if path.is_file() { return Ok(path); }
+    let in_dir = dir().join(arg);
+    if in_dir.is_file() { return Ok(in_dir); }
+    Err(anyhow!("No backup named {arg} (see  lm restore  for the list)"))
+}
+
+/// Checks that `file` is an intact lm database this build can open.
+/// Returns its schema version.
+pub async fn verify(file: &Path) -> Result<i64> {
+    let url  = format!("sqlite://{}?mode=ro", file.display());
+    let pool = SqlitePool::connect(&url).await
+        .map_err(|e| anyhow!("{} is not a readable SQLite database: {e}", file.display()))?;
+
+    let check: String = sqlx::query("PRAGMA integrity_check").fetch_one(&pool).await
+        .map_err(|e| anyhow!("{} is not a readable SQLite database: {e}", file.display()))?
+        .get(0);
+    if check != "ok" {
+        bail!("{} failed the integrity check: {check}", file.display());
+    }
+    if !migrations::table_exists(&pool, "events").await? {
+        bail!("{} doesn't look like a LifeManager database", file.display());
+    }
+    let version = if migrations::table_exists(&pool, "schema_version").await? {
+        sqlx::query("SELECT MAX(version) FROM schema_version").fetch_one(&pool).await?
+            .get::<Option<i64>, _>(0).unwrap_or(0)
+    } else {
+        0
+    };
+    pool.close().await;
+
+    if version > migrations::latest() {
+        bail!(
+            "{} is schema v{version}, newer than this lm understands (v{})",
+            file.display(), migrations::latest()
+        );
+    }
+    Ok(version)
+}
+
+/// Copies `file` over the database at `db_path`. The live pool must be
+/// closed first; leftover WAL files would otherwise be replayed onto it.
+pub fn replace(file: &Path, db_path: &Path) -> Result<()> {
+    for ext in ["db-wal", "db-shm"] {
+        let _ = std::fs::remove_file(db_path.with_extension(ext));
+    }
+    std::fs::copy(file, db_path)?;
+    Ok(())
+}
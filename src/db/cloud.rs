@@ -0,0 +1,302 @@
+//! Off-site copies of the backups (`[backup.remote]` in config.toml): after
+//! the daily snapshot, the newest one is sealed and uploaded to a WebDAV
+//! folder or an S3-compatible bucket, and all but the newest `keep` copies
+//! there are deleted. `lm restore --from-remote` brings one back.
+//!
+//! A copy is the snapshot's name with `.enc` on the end, and two lines: a
+//! plaintext header with the key-derivation salt, then the database sealed
+//! with ChaCha20-Poly1305 as the journal seals its lines, under a key
+//! derived from the passphrase. The server only ever sees ciphertext.
+
+use anyhow::{anyhow, bail, Context, Result};
+use base64::{engine::general_purpose::STANDARD as B64, Engine};
+use chrono::Utc;
+use reqwest::{Client, Method, RequestBuilder, Url};
+use ring::hmac;
+use ring::rand::{SecureRandom, SystemRandom};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::path::PathBuf;
+
+use super::backup;
+use super::journal::{derive, open_sealed, seal, Sealed};
+use crate::config::RemoteBackupConfig;
+
+const FORMAT:     u32  = 1;
+const ITERATIONS: u32  = 600_000;
+const SUFFIX:     &str = ".enc";
+
+#[derive(Serialize, Deserialize)]
+struct Header {
+    lm_backup:  u32,
+    iterations: u32,
+    salt:       String,
+}
+
+// ─── Sealing ──────────────────────────────────────────────────────────────────
+
+fn encrypt(plain: &[u8], passphrase: &str) -> Result<Vec<u8>> {
+    let rng = SystemRandom::new();
+    let mut salt = [0u8; 16];
+    rng.fill(&mut salt).map_err(|_| anyhow!("No randomness available"))?;
+    let key    = derive(passphrase, &salt, ITERATIONS)?;
+    let header = Header { lm_backup: FORMAT, iterations: ITERATIONS, salt: B64.encode(salt) };
+    let sealed = seal(&key, &rng, plain)?;
+    Ok(format!("{}\n{}\n", serde_json::to_string(&header)?, serde_json::to_string(&sealed)?).into_bytes())
+}
+
+fn decrypt(data: &[u8], passphrase: &str) -> Result<Vec<u8>> {
+    let text = std::str::from_utf8(data).map_err(|_| anyhow!("Not an lm backup copy"))?;
+    let mut lines = text.lines();
+    let header: Header = lines.next().and_then(|l| serde_json::from_str(l).ok())
+        .ok_or_else(|| anyhow!("Not an lm backup copy"))?;
+    if header.lm_backup != FORMAT {
+        bail!("Backup copy format v{} is newer than this lm understands", header.lm_backup);
+    }
+    let sealed: Sealed = lines.next().and_then(|l| serde_json::from_str(l).ok())
+        .ok_or_else(|| anyhow!("The backup copy is cut short"))?;
+    // The copy comes from the remote: a round count of its own could stall
+    // the restore for hours
+    if header.iterations != ITERATIONS {
+        bail!("The backup copy's header asks for {} key-derivation rounds, not {ITERATIONS}; it's damaged or not from lm", header.iterations);
+    }
+    let key = derive(passphrase, &B64.decode(&header.salt)?, ITERATIONS)?;
+    open_sealed(&key, &sealed).map_err(|_| anyhow!("Wrong passphrase, or the copy is damaged"))
+}
+
+// ─── Stores ───────────────────────────────────────────────────────────────────
+
+enum Kind {
+    /// A folder URL, ending in `/`.
+    WebDav(String),
+    S3 { endpoint: String, bucket: String, prefix: String },
+}
+
+/// The configured remote, with its credentials read from the environment.
+pub struct Store {
+    http:       Client,
+    kind:       Kind,
+    region:     String,
+    user:       Option<String>,
+    secret:     Option<String>,
+    passphrase: String,
+    keep:       usize,
+}
+
+impl Store {
+    pub fn from_config(cfg: &RemoteBackupConfig) -> Result<Self> {
+        let passphrase = std::env::var(&cfg.passphrase_env).ok().filter(|p| !p.is_empty()).ok_or_else(|| anyhow!(
+            "[backup.remote] is set up but ${} isn't — export the backup passphrase first",
+            cfg.passphrase_env,
+        ))?;
+        let secret = std::env::var(&cfg.secret_env).ok().filter(|s| !s.is_empty());
+        let kind   = match cfg.url.strip_prefix("s3://") {
+            Some(rest) => {
+                let (bucket, prefix) = rest.split_once('/').unwrap_or((rest, ""));
+                if bucket.is_empty() { bail!("[backup.remote] url has no bucket: {}", cfg.url); }
+                if cfg.user.is_none() || secret.is_none() {
+                    bail!("S3 backups need [backup.remote] user (the access key id) and ${}", cfg.secret_env);
+                }
+                let endpoint = cfg.endpoint.clone()
+                    .unwrap_or_else(|| format!("https://s3.{}.amazonaws.com", cfg.region));
+                let prefix = prefix.trim_matches('/');
+                Kind::S3 {
+                    endpoint: endpoint.trim_end_matches('/').to_owned(),
+                    bucket:   bucket.to_owned(),
+                    prefix:   if prefix.is_empty() { String::new() } else { format!("{prefix}/") },
+                }
+            }
+            None if cfg.url.starts_with("https://") || cfg.url.starts_with("http://") => {
+                Kind::WebDav(format!("{}/", cfg.url.trim_end_matches('/')))
+            }
+            None => bail!("[backup.remote] url should be https://… (WebDAV) or s3://bucket/prefix"),
+        };
+        Ok(Self {
+            http: Client::builder()
+                .timeout(std::time::Duration::from_secs(300))
+                .user_agent("LifeManager/0.1")
+                .build()?,
+            kind,
+            region: cfg.region.clone(),
+            user: cfg.user.clone(),
+            secret,
+            passphrase,
+            keep: cfg.keep.max(1),
+        })
+    }
+
+    /// Where copies go, for messages.
+    pub fn describe(&self) -> String {
+        match &self.kind {
+            Kind::WebDav(url)               => url.clone(),
+            Kind::S3 { bucket, prefix, .. } => format!("s3://{bucket}/{prefix}"),
+        }
+    }
+
+    /// Copies on the remote, oldest first.
+    pub async fn list(&self) -> Result<Vec<String>> {
+        let body = match &self.kind {
+            Kind::WebDav(url) => self.webdav(Method::from_bytes(b"PROPFIND")?, url)
+                .header("Depth", "1")
+                .send().await?.error_for_status()?.text().await?,
+            Kind::S3 { prefix, .. } => {
+                let query = format!("list-type=2&prefix={}", uri_encode(prefix, false));
+                self.s3(Method::GET, "", &query, &[])?.send().await?.error_for_status()?.text().await?
+            }
+        };
+        // Hrefs (WebDAV) and keys (S3) both end in the copy's name
+        let tag = regex::Regex::new(r"<(?:[A-Za-z]+:)?(?:href|Key)>([^<]+)</").expect("valid regex");
+        let mut names: Vec<String> = tag.captures_iter(&body)
+            .filter_map(|c| c[1].trim_end_matches('/').rsplit('/').next().map(str::to_owned))
+            .filter(|n| n.starts_with(backup::PREFIX) && n.ends_with(SUFFIX))
+            .collect();
+        names.sort();
+        names.dedup();
+        Ok(names)
+    }
+
+    async fn put(&self, name: &str, data: Vec<u8>) -> Result<()> {
+        let req = match &self.kind {
+            Kind::WebDav(url)       => self.webdav(Method::PUT, &format!("{url}{name}")).body(data),
+            Kind::S3 { prefix, .. } => self.s3(Method::PUT, &format!("{prefix}{name}"), "", &data)?.body(data),
+        };
+        req.send().await?.error_for_status()?;
+        Ok(())
+    }
+
+    async fn get(&self, name: &str) -> Result<Vec<u8>> {
+        let req = match &self.kind {
+            Kind::WebDav(url)       => self.webdav(Method::GET, &format!("{url}{name}")),
+            Kind::S3 { prefix, .. } => self.s3(Method::GET, &format!("{prefix}{name}"), "", &[])?,
+        };
+        Ok(req.send().await?.error_for_status()?.bytes().await?.to_vec())
+    }
+
+    async fn delete(&self, name: &str) -> Result<()> {
+        let req = match &self.kind {
+            Kind::WebDav(url)       => self.webdav(Method::DELETE, &format!("{url}{name}")),
+            Kind::S3 { prefix, .. } => self.s3(Method::DELETE, &format!("{prefix}{name}"), "", &[])?,
+        };
+        req.send().await?.error_for_status()?;
+        Ok(())
+    }
+
+    fn webdav(&self, method: Method, url: &str) -> RequestBuilder {
+        let req = self.http.request(method, url);
+        match &self.user {
+            Some(user) => req.basic_auth(user, self.secret.as_deref()),
+            None       => req,
+        }
+    }
+
+    /// A request for `key` in the bucket (the bucket itself when empty),
+    /// signed with AWS Signature Version 4.
+    fn s3(&self, method: Method, key: &str, query: &str, body: &[u8]) -> Result<RequestBuilder> {
+        let Kind::S3 { endpoint, bucket, .. } = &self.kind else { bail!("Not an S3 remote") };
+        let path = match key {
+            "" => format!("/{}", uri_encode(bucket, false)),
+            _  => format!("/{}/{}", uri_encode(bucket, false), uri_encode(key, true)),
+        };
+        let url  = Url::parse(&format!("{endpoint}{path}{}{query}", if query.is_empty() { "" } else { "?" }))?;
+        let host = match (url.host_str(), url.port()) {
+            (Some(h), Some(p)) => format!("{h}:{p}"),
+            (Some(h), None)    => h.to_owned(),
+            (None, _)          => bail!("[backup.remote] endpoint has no host: {endpoint}"),
+        };
+        let now        = Utc::now();
+        let amz_date   = now.format("%Y%m%dT%H%M%SZ").to_string();
+        let date       = now.format("%Y%m%d").to_string();
+        let hash       = hex(&Sha256::digest(body));
+        let canonical  = format!(
+            "{method}\n{path}\n{query}\nhost:{host}\nx-amz-content-sha256:{hash}\nx-amz-date:{amz_date}\n\n\
+             host;x-amz-content-sha256;x-amz-date\n{hash}"
+        );
+        let scope      = format!("{date}/{}/s3/aws4_request", self.region);
+        let to_sign    = format!("AWS4-HMAC-SHA256\n{amz_date}\n{scope}\n{}", hex(&Sha256::digest(canonical.as_bytes())));
+        let secret     = self.secret.as_deref().unwrap_or_default();
+        let mut key    = sign(format!("AWS4{secret}").as_bytes(), date.as_bytes());
+        for part in [self.region.as_str(), "s3", "aws4_request"] {
+            key = sign(&key, part.as_bytes());
+        }
+        let signature  = hex(&sign(&key, to_sign.as_bytes()));
+        let auth       = format!(
+            "AWS4-HMAC-SHA256 Credential={}/{scope}, SignedHeaders=host;x-amz-content-sha256;x-amz-date, Signature={signature}",
+            self.user.as_deref().unwrap_or_default(),
+        );
+        Ok(self.http.request(method, url)
+            .header("x-amz-date", amz_date)
+            .header("x-amz-content-sha256", hash)
+            .header("Authorization", auth))
+    }
+}
+
+fn sign(key: &[u8], data: &[u8]) -> Vec<u8> {
+    hmac::sign(&hmac::Key::new(hmac::HMAC_SHA256, key), data).as_ref().to_vec()
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+/// Percent-encoding as SigV4 wants it: everything but unreserved
+/// characters (and `/` in keys).
+fn uri_encode(s: &str, keep_slash: bool) -> String {
+    s.bytes().map(|b| match b {
+        b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => (b as char).to_string(),
+        b'/' if keep_slash => "/".into(),
+        _ => format!("%{b:02X}"),
+    }).collect()
+}
+
+// ─── Upload / download ────────────────────────────────────────────────────────
+
+/// Seals and uploads the newest local snapshot unless the remote has it,
+/// then trims the remote to `keep` copies. Returns the name uploaded.
+pub async fn upload_latest(store: &Store) -> Result<Option<String>> {
+    let Some(file) = backup::list()?.pop() else { return Ok(None) };
+    let name = format!("{}{SUFFIX}", file.file_name().unwrap_or_default().to_string_lossy());
+    let mut names = store.list().await?;
+    let uploaded = if names.contains(&name) {
+        None
+    } else {
+        let plain = std::fs::read(&file)?;
+        let pass  = store.passphrase.clone();
+        // Key derivation takes a moment; keep it off the async threads
+        let data  = tokio::task::spawn_blocking(move || encrypt(&plain, &pass)).await??;
+        store.put(&name, data).await.with_context(|| format!("Uploading {name} to {}", store.describe()))?;
+        tracing::info!("Uploaded backup {name} to {}", store.describe());
+        names.push(name.clone());
+        names.sort();
+        Some(name)
+    };
+    let excess = names.len().saturating_sub(store.keep);
+    for old in &names[..excess] {
+        store.delete(old).await.with_context(|| format!("Deleting {old} from {}", store.describe()))?;
+        tracing::info!("Deleted old remote backup {old}");
+    }
+    Ok(uploaded)
+}
+
+/// Downloads and opens copy `name` (`latest` for the newest) into the
+/// backup folder, ready for `lm restore`; returns the snapshot's path.
+pub async fn download(store: &Store, name: &str) -> Result<PathBuf> {
+    let names = store.list().await?;
+    let name  = match name {
+        "latest" => names.last().cloned().ok_or_else(|| anyhow!("No backups at {} yet", store.describe()))?,
+        name     => {
+            let name = if name.ends_with(SUFFIX) { name.to_owned() } else { format!("{name}{SUFFIX}") };
+            if !names.contains(&name) {
+                bail!("No backup named {name} at {} (see  lm restore --from-remote)", store.describe());
+            }
+            name
+        }
+    };
+    let data  = store.get(&name).await?;
+    let pass  = store.passphrase.clone();
+    let plain = tokio::task::spawn_blocking(move || decrypt(&data, &pass)).await??;
+    let dir   = backup::dir();
+    std::fs::create_dir_all(&dir)?;
+    let path  = dir.join(name.trim_end_matches(SUFFIX));
+    std::fs::write(&path, plain).with_context(|| format!("Can't write {}", path.display()))?;
+    Ok(path)
+}
@@ -0,0 +1,327 @@
+//! Append-only, encrypted change journal (`[journal]` in config.toml).
+//!
+//! Every write to events and their guests, tasks, meals, checklists, habits
+//! and the timer is also appended to a user-chosen file, one JSON line per change, so a copy in a synced folder
+//! survives losing both lifemanager.db and the Google side. The first line is
+//! a plaintext header with the key-derivation salt and a check value; every
+//! other line is `{"n": nonce, "c": ciphertext}`, sealed with
+//! ChaCha20-Poly1305 under a key derived from the passphrase.
+
+use anyhow::{anyhow, bail, Context, Result};
+use base64::{engine::general_purpose::STANDARD as B64, Engine};
+use chrono::{DateTime, NaiveDate, Utc};
+use ring::aead::{Aad, LessSafeKey, Nonce, UnboundKey, CHACHA20_POLY1305, NONCE_LEN};
+use ring::pbkdf2;
+use ring::rand::{SecureRandom, SystemRandom};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fs::OpenOptions;
+use std::io::{BufRead, BufReader, Write};
+use std::num::NonZeroU32;
+use std::path::{Path, PathBuf};
+use tokio::io::AsyncWriteExt;
+use tokio::sync::Mutex;
+use uuid::Uuid;
+
+use super::{Database, Event, Task, Timer};
+use crate::config::JournalConfig;
+
+const FORMAT:     u32  = 1;
+const ITERATIONS: u32  = 600_000;
+/// Sealed into the header, so a wrong passphrase fails up front rather than
+/// writing lines nobody can read.
+const CHECK:      &[u8] = b"lifemanager journal";
+
+#[derive(Serialize, Deserialize)]
+struct Header {
+    lm_journal: u32,
+    iterations: u32,
+    salt:       String,
+    check:      Sealed,
+}
+
+/// Also how `cloud` seals the off-site backup copies.
+#[derive(Serialize, Deserialize)]
+pub(super) struct Sealed {
+    n: String,
+    c: String,
+}
+
+/// One recorded change: the full record after the write.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Entry {
+    pub at:   DateTime<Utc>,
+    /// `event`, `guests`, `task`, `meal`, `checklist`, `habit` or `timer`.
+    pub kind: String,
+    pub data: Value,
+}
+
+pub struct Journal {
+    path: PathBuf,
+    key:  LessSafeKey,
+    rng:  SystemRandom,
+    /// Keeps lines from concurrent writers in this process whole.
+    lock: Mutex<()>,
+}
+
+impl Journal {
+    /// The configured journal; `None` when `[journal] path` isn't set.
+    pub fn from_config(cfg: &JournalConfig) -> Result<Option<Self>> {
+        let Some(ref raw) = cfg.path else { return Ok(None) };
+        let path = match raw.strip_prefix("~/") {
+            Some(rest) => dirs::home_dir().ok_or_else(|| anyhow!("No home directory"))?.join(rest),
+            None       => PathBuf::from(raw),
+        };
+        let passphrase = std::env::var(&cfg.passphrase_env).map_err(|_| anyhow!(
+            "[journal] is set up but ${} isn't — export the journal passphrase first",
+            cfg.passphrase_env,
+        ))?;
+        Self::open(&path, &passphrase).map(Some)
+    }
+
+    /// Opens `path`, writing a fresh header if the file is new or empty.
+    pub fn open(path: &Path, passphrase: &str) -> Result<Self> {
+        if passphrase.is_empty() { bail!("The journal passphrase is empty"); }
+        let rng = SystemRandom::new();
+        if let Some(header) = read_header(path)? {
+            let key = derive(passphrase, &B64.decode(&header.salt)?, ITERATIONS)?;
+            open_sealed(&key, &header.check)
+                .ok()
+                .filter(|plain| plain == CHECK)
+                .ok_or_else(|| anyhow!("Wrong passphrase for the journal at {}", path.display()))?;
+            return Ok(Self { path: path.to_owned(), key, rng, lock: Mutex::new(()) });
+        }
+
+        let mut salt = [0u8; 16];
+        rng.fill(&mut salt).map_err(|_| anyhow!("No randomness available"))?;
+        let key    = derive(passphrase, &salt, ITERATIONS)?;
+        let header = Header {
+            lm_journal: FORMAT,
+            iterations: ITERATIONS,
+            salt:       B64.encode(salt),
+            check:      seal(&key, &rng, CHECK)?,
+        };
+        if let Some(dir) = path.parent() { std::fs::create_dir_all(dir)?; }
+        let mut file = OpenOptions::new().create(true).append(true).open(path)
+            .with_context(|| format!("Can't create the journal at {}", path.display()))?;
+        writeln!(file, "{}", serde_json::to_string(&header)?)?;
+        tracing::info!("Started a change journal at {}", path.display());
+        Ok(Self { path: path.to_owned(), key, rng, lock: Mutex::new(()) })
+    }
+
+    pub fn path(&self) -> &Path { &self.path }
+
+    /// Written through `tokio::fs`, as it runs on every database write.
+    pub async fn append(&self, kind: &str, data: &Value) -> Result<()> {
+        let entry  = Entry { at: Utc::now(), kind: kind.to_owned(), data: data.clone() };
+        let sealed = seal(&self.key, &self.rng, serde_json::to_string(&entry)?.as_bytes())?;
+        let line   = format!("{}\n", serde_json::to_string(&sealed)?);
+        let _guard = self.lock.lock().await;
+        let mut file = tokio::fs::OpenOptions::new().append(true).open(&self.path).await?;
+        file.write_all(line.as_bytes()).await?;
+        file.flush().await?;
+        Ok(())
+    }
+
+    /// Every entry, oldest first. Lines that don't decrypt (a torn final
+    /// write, say) are skipped and counted in the second value.
+    pub fn entries(&self) -> Result<(Vec<Entry>, usize)> {
+        let file = std::fs::File::open(&self.path)?;
+        let mut entries = Vec::new();
+        let mut skipped = 0;
+        for line in BufReader::new(file).lines().skip(1) {
+            let line = line?;
+            if line.trim().is_empty() { continue; }
+            let entry = serde_json::from_str::<Sealed>(&line).ok()
+                .and_then(|s| open_sealed(&self.key, &s).ok())
+                .and_then(|plain| serde_json::from_slice::<Entry>(&plain).ok());
+            match entry {
+                Some(e) => entries.push(e),
+                None    => skipped += 1,
+            }
+        }
+        Ok((entries, skipped))
+    }
+}
+
+fn read_header(path: &Path) -> Result<Option<Header>> {
+    let Ok(file) = std::fs::File::open(path) else { return Ok(None) };
+    let Some(first) = BufReader::new(file).lines().next().transpose()? else { return Ok(None) };
+    let header: Header = serde_json::from_str(&first)
+        .with_context(|| format!("{} isn't an lm journal", path.display()))?;
+    if header.lm_journal != FORMAT {
+        bail!("Journal format v{} is newer than this lm understands", header.lm_journal);
+    }
+    // The file sits in a synced folder: a round count of its own could stall
+    // every start for hours
+    if header.iterations != ITERATIONS {
+        bail!("{}'s header asks for {} key-derivation rounds, not {ITERATIONS}; it's damaged or not from lm",
+            path.display(), header.iterations);
+    }
+    Ok(Some(header))
+}
+
+pub(super) fn derive(passphrase: &str, salt: &[u8], iterations: u32) -> Result<LessSafeKey> {
+    let rounds = NonZeroU32::new(iterations).ok_or_else(|| anyhow!("Bad key-derivation header"))?;
+    let mut key = [0u8; 32];
+    pbkdf2::derive(pbkdf2::PBKDF2_HMAC_SHA256, rounds, salt, passphrase.as_bytes(), &mut key);
+    let unbound = UnboundKey::new(&CHACHA20_POLY1305, &key).map_err(|_| anyhow!("Bad encryption key"))?;
+    Ok(LessSafeKey::new(unbound))
+}
+
+pub(super) fn seal(key: &LessSafeKey, rng: &SystemRandom, plain: &[u8]) -> Result<Sealed> {
+    let mut nonce = [0u8; NONCE_LEN];
+    rng.fill(&mut nonce).map_err(|_| anyhow!("No randomness available"))?;
+    let mut buf = plain.to_vec();
+    key.seal_in_place_append_tag(Nonce::assume_unique_for_key(nonce), Aad::empty(), &mut buf)
+        .map_err(|_| anyhow!("Encryption failed"))?;
+    Ok(Sealed { n: B64.encode(nonce), c: B64.encode(buf) })
+}
+
+pub(super) fn open_sealed(key: &LessSafeKey, sealed: &Sealed) -> Result<Vec<u8>> {
+    let nonce: [u8; NONCE_LEN] = B64.decode(&sealed.n)?.try_into()
+        .map_err(|_| anyhow!("Bad nonce"))?;
+    let mut buf = B64.decode(&sealed.c)?;
+    let plain = key.open_in_place(Nonce::assume_unique_for_key(nonce), Aad::empty(), &mut buf)
+        .map_err(|_| anyhow!("Decryption failed"))?;
+    Ok(plain.to_vec())
+}
+
+// ─── Restore ──────────────────────────────────────────────────────────────────
+
+#[derive(Debug, Default)]
+pub struct Restored {
+    pub events:     usize,
+    pub tasks:      usize,
+    pub meals:      usize,
+    pub checklists: usize,
+    pub habits:     usize,
+    /// Events whose guest list (and answers) came back.
+    pub guests:     usize,
+}
+
+/// Writes the latest journalled state of every record into `db`. Records the
+/// journal doesn't mention are left alone. `db` shouldn't have a journal
+/// attached, or the restore would journal itself.
+pub async fn restore(db: &Database, entries: &[Entry]) -> Result<Restored> {
+    let mut latest: HashMap<(String, String), &Entry> = HashMap::new();
+    for e in entries {
+        let key = match e.kind.as_str() {
+            "meal" => format!("{}/{}", e.data["date"].as_str().unwrap_or(""), e.data["slot"]),
+            _      => e.data["id"].as_str().unwrap_or("").to_owned(),
+        };
+        latest.insert((e.kind.clone(), key), e);
+    }
+    let mut latest: Vec<&Entry> = latest.into_values().collect();
+    latest.sort_by_key(|e| e.at);
+
+    let mut out = Restored::default();
+    for e in latest {
+        match e.kind.as_str() {
+            "event" => {
+                db.upsert_event(&serde_json::from_value::<Event>(e.data.clone())?).await?;
+                out.events += 1;
+            }
+            "task" => {
+                db.upsert_task(&serde_json::from_value::<Task>(e.data.clone())?).await?;
+                out.tasks += 1;
+            }
+            "meal" => {
+                let date = NaiveDate::parse_from_str(e.data["date"].as_str().unwrap_or(""), "%Y-%m-%d")?;
+                let slot = e.data["slot"].as_i64().unwrap_or(0);
+                db.set_meal(date, slot, e.data["text"].as_str().unwrap_or("")).await?;
+                out.meals += 1;
+            }
+            "checklist" => {
+                restore_checklist(db, &e.data).await?;
+                out.checklists += 1;
+            }
+            "habit" => {
+                restore_habit(db, &e.data).await?;
+                out.habits += 1;
+            }
+            "guests" => {
+                restore_guests(db, &e.data).await?;
+                out.guests += 1;
+            }
+            "timer" => match serde_json::from_value::<Option<Timer>>(e.data["running"].clone())? {
+                Some(t) => db.start_timer(&t).await?,
+                None    => { db.stop_timer(None).await?; }
+            },
+            other => tracing::warn!("Skipping unknown journal entry kind {other}"),
+        }
+    }
+    Ok(out)
+}
+
+async fn restore_checklist(db: &Database, data: &Value) -> Result<()> {
+    let id = data["id"].as_str().unwrap_or("");
+    let mut tx = db.pool.begin().await?;
+    sqlx::query("DELETE FROM checklist_items WHERE list_id=?").bind(id).execute(&mut *tx).await?;
+    if data["deleted"].as_bool() == Some(true) {
+        sqlx::query("DELETE FROM checklists WHERE id=?").bind(id).execute(&mut *tx).await?;
+    } else {
+        sqlx::query(
+            "INSERT INTO checklists (id,name,created_at) VALUES (?,?,?)
+             ON CONFLICT(id) DO UPDATE SET name=excluded.name"
+        )
+        .bind(id).bind(data["name"].as_str().unwrap_or("")).bind(Utc::now().to_rfc3339())
+        .execute(&mut *tx).await?;
+        let items = data["items"].as_array().cloned().unwrap_or_default();
+        for (pos, item) in items.iter().enumerate() {
+            sqlx::query(
+                "INSERT OR REPLACE INTO checklist_items (id,list_id,text,checked,position)
+                 VALUES (?,?,?,?,?)"
+            )
+            .bind(item["id"].as_str().map(str::to_owned).unwrap_or_else(|| Uuid::new_v4().to_string()))
+            .bind(id).bind(item["text"].as_str().unwrap_or(""))
+            .bind(item["checked"].as_bool().unwrap_or(false) as i32).bind(pos as i64 + 1)
+            .execute(&mut *tx).await?;
+        }
+    }
+    tx.commit().await?;
+    Ok(())
+}
+
+async fn restore_habit(db: &Database, data: &Value) -> Result<()> {
+    let id = data["id"].as_str().unwrap_or("");
+    let mut tx = db.pool.begin().await?;
+    sqlx::query("DELETE FROM habit_checks WHERE habit_id=?").bind(id).execute(&mut *tx).await?;
+    if data["deleted"].as_bool() == Some(true) {
+        sqlx::query("DELETE FROM habits WHERE id=?").bind(id).execute(&mut *tx).await?;
+    } else {
+        sqlx::query(
+            "INSERT INTO habits (id,name,schedule,position,created_at)
+             VALUES (?,?,?,(SELECT COALESCE(MAX(position),0)+1 FROM habits),?)
+             ON CONFLICT(id) DO UPDATE SET name=excluded.name, schedule=excluded.schedule"
+        )
+        .bind(id).bind(data["name"].as_str().unwrap_or("")).bind(data["schedule"].as_str().unwrap_or("daily"))
+        .bind(data["created_at"].as_str().map(str::to_owned).unwrap_or_else(|| Utc::now().to_rfc3339()))
+        .execute(&mut *tx).await?;
+        for day in data["done"].as_array().into_iter().flatten().filter_map(Value::as_str) {
+            sqlx::query("INSERT OR IGNORE INTO habit_checks (habit_id, date) VALUES (?, ?)")
+                .bind(id).bind(day).execute(&mut *tx).await?;
+        }
+    }
+    tx.commit().await?;
+    Ok(())
+}
+
+async fn restore_guests(db: &Database, data: &Value) -> Result<()> {
+    let id = data["id"].as_str().unwrap_or("");
+    let mut tx = db.pool.begin().await?;
+    sqlx::query("DELETE FROM event_attendees WHERE event_id=?").bind(id).execute(&mut *tx).await?;
+    for g in data["guests"].as_array().into_iter().flatten() {
+        sqlx::query(
+            "INSERT OR IGNORE INTO event_attendees (event_id, email, name, response_status, is_self, organizer, unsent)
+             VALUES (?, ?, ?, ?, ?, ?, ?)"
+        )
+        .bind(id).bind(g["email"].as_str().unwrap_or("")).bind(g["name"].as_str()).bind(g["response"].as_str())
+        .bind(g["is_self"].as_bool().unwrap_or(false) as i32).bind(g["organizer"].as_bool().unwrap_or(false) as i32)
+        .bind(g["unsent"].as_bool().unwrap_or(false) as i32)
+        .execute(&mut *tx).await?;
+    }
+    tx.commit().await?;
+    Ok(())
+}
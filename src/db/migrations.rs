@@ -0,0 +1,287 @@
+//! Numbered, forward-only schema migrations.
+//!
+//! `schema_version` records the last applied migration. Pending migrations
+//! run in order, each in its own transaction; `Database::migrate` snapshots
+//! the file first. Never edit a migration that has shipped — append a new
+//! one instead.
+
+use anyhow::{bail, Result};
+use sqlx::{sqlite::SqliteConnection, Connection, Row, SqlitePool};
+
+enum Step {
+    Sql(&'static str),
+    /// ADD COLUMN unless it already exists (databases from before versioning
+    /// may have some of these columns already).
+    AddColumn(&'static str, &'static str, &'static str),
+}
+
+use Step::*;
+
+struct Migration {
+    version: i64,
+    name:    &'static str,
+    steps:   &'static [Step],
+}
+
+const MIGRATIONS: &[Migration] = &[
+    Migration { version: 1, name: "initial schema", steps: &[
+        Sql("CREATE TABLE IF NOT EXISTS events (
+                id TEXT PRIMARY KEY, title TEXT NOT NULL, description TEXT,
+                start TEXT NOT NULL, end TEXT NOT NULL, all_day INTEGER NOT NULL DEFAULT 0,
+                calendar_id TEXT, sync_id TEXT, etag TEXT,
+                dirty INTEGER NOT NULL DEFAULT 1, deleted INTEGER NOT NULL DEFAULT 0,
+                created_at TEXT NOT NULL, updated_at TEXT NOT NULL
+            )"),
+        Sql("CREATE INDEX IF NOT EXISTS idx_events_start ON events(start)"),
+        Sql("CREATE TABLE IF NOT EXISTS tasks (
+                id TEXT PRIMARY KEY, title TEXT NOT NULL, notes TEXT, due TEXT,
+                completed INTEGER NOT NULL DEFAULT 0, priority INTEGER NOT NULL DEFAULT 0,
+                task_list_id TEXT, sync_id TEXT,
+                dirty INTEGER NOT NULL DEFAULT 1, deleted INTEGER NOT NULL DEFAULT 0,
+                created_at TEXT NOT NULL, updated_at TEXT NOT NULL
+            )"),
+        Sql("CREATE INDEX IF NOT EXISTS idx_tasks_due ON tasks(due)"),
+        Sql("CREATE TABLE IF NOT EXISTS oauth_tokens (
+                provider TEXT PRIMARY KEY, access_token TEXT NOT NULL,
+                refresh_token TEXT, expires_at TEXT
+            )"),
+    ]},
+    Migration { version: 2, name: "pto flag", steps: &[
+        AddColumn("events", "pto", "INTEGER NOT NULL DEFAULT 0"),
+    ]},
+    Migration { version: 3, name: "meal plan", steps: &[
+        Sql("CREATE TABLE IF NOT EXISTS meals (
+                date TEXT NOT NULL, slot INTEGER NOT NULL, text TEXT NOT NULL,
+                PRIMARY KEY (date, slot)
+            )"),
+    ]},
+    Migration { version: 4, name: "checklists", steps: &[
+        Sql("CREATE TABLE IF NOT EXISTS checklists (
+                id TEXT PRIMARY KEY, name TEXT NOT NULL UNIQUE, created_at TEXT NOT NULL
+            )"),
+        Sql("CREATE TABLE IF NOT EXISTS checklist_items (
+                id TEXT PRIMARY KEY, list_id TEXT NOT NULL, text TEXT NOT NULL,
+                checked INTEGER NOT NULL DEFAULT 0, position INTEGER NOT NULL
+            )"),
+    ]},
+    Migration { version: 5, name: "event timezone", steps: &[
+        AddColumn("events", "timezone", "TEXT"),
+    ]},
+    Migration { version: 6, name: "ics feeds", steps: &[
+        Sql("CREATE TABLE IF NOT EXISTS feeds (
+                name TEXT PRIMARY KEY, url TEXT NOT NULL, fetched_at TEXT NOT NULL
+            )"),
+        Sql("CREATE TABLE IF NOT EXISTS feed_events (
+                feed TEXT NOT NULL, uid TEXT NOT NULL, title TEXT NOT NULL,
+                start TEXT NOT NULL, end TEXT NOT NULL, all_day INTEGER NOT NULL DEFAULT 0,
+                PRIMARY KEY (feed, uid)
+            )"),
+        Sql("CREATE INDEX IF NOT EXISTS idx_feed_events_start ON feed_events(start)"),
+    ]},
+    Migration { version: 7, name: "import quarantine", steps: &[
+        Sql("CREATE TABLE IF NOT EXISTS quarantine (
+                source TEXT NOT NULL, remote_id TEXT NOT NULL, title TEXT NOT NULL,
+                description TEXT, start TEXT, end TEXT, all_day INTEGER NOT NULL DEFAULT 0,
+                reason TEXT NOT NULL, raw TEXT NOT NULL, received_at TEXT NOT NULL,
+                resolved INTEGER NOT NULL DEFAULT 0,
+                PRIMARY KEY (source, remote_id)
+            )"),
+    ]},
+    Migration { version: 8, name: "original remote titles", steps: &[
+        AddColumn("events", "remote_title", "TEXT"),
+    ]},
+    Migration { version: 9, name: "range query indexes", steps: &[
+        Sql("CREATE INDEX IF NOT EXISTS idx_events_end ON events(end)"),
+        Sql("CREATE INDEX IF NOT EXISTS idx_events_calendar ON events(calendar_id, start)"),
+    ]},
+    Migration { version: 10, name: "sent reminders", steps: &[
+        Sql("CREATE TABLE IF NOT EXISTS reminders_sent (
+                event_id TEXT NOT NULL, start TEXT NOT NULL, sent_at TEXT NOT NULL,
+                PRIMARY KEY (event_id, start)
+            )"),
+    ]},
+    Migration { version: 11, name: "event attendance", steps: &[
+        AddColumn("events", "attendance", "TEXT"),
+    ]},
+    Migration { version: 12, name: "working locations", steps: &[
+        Sql("CREATE TABLE IF NOT EXISTS working_locations (
+                sync_id TEXT PRIMARY KEY, calendar_id TEXT NOT NULL,
+                start TEXT NOT NULL, end TEXT NOT NULL, all_day INTEGER NOT NULL DEFAULT 0,
+                kind TEXT NOT NULL, label TEXT
+            )"),
+    ]},
+    Migration { version: 13, name: "event attendees", steps: &[
+        Sql("CREATE TABLE IF NOT EXISTS event_attendees (
+                event_id TEXT NOT NULL, email TEXT NOT NULL, response_status TEXT,
+                PRIMARY KEY (event_id, email)
+            )"),
+    ]},
+    Migration { version: 14, name: "event tags", steps: &[
+        Sql("CREATE TABLE IF NOT EXISTS tags (name TEXT PRIMARY KEY, color TEXT NOT NULL)"),
+        AddColumn("events", "tag", "TEXT"),
+    ]},
+    Migration { version: 15, name: "task tags", steps: &[
+        AddColumn("tasks", "tags", "TEXT"),
+    ]},
+    Migration { version: 16, name: "task order", steps: &[
+        AddColumn("tasks", "position", "INTEGER NOT NULL DEFAULT 0"),
+        AddColumn("tasks", "moved", "INTEGER NOT NULL DEFAULT 0"),
+        // Existing tasks keep the order they were added in
+        Sql("UPDATE tasks SET position = (
+                SELECT COUNT(*) FROM tasks t
+                WHERE t.created_at < tasks.created_at
+                   OR (t.created_at = tasks.created_at AND t.id <= tasks.id)
+            )"),
+    ]},
+    Migration { version: 17, name: "time tracking", steps: &[
+        AddColumn("events", "time_entry", "INTEGER NOT NULL DEFAULT 0"),
+        // At most one running timer, kept here so it survives a restart
+        Sql("CREATE TABLE IF NOT EXISTS timer (
+                id         INTEGER PRIMARY KEY CHECK (id = 1),
+                label      TEXT,
+                tag        TEXT,
+                started_at TEXT NOT NULL
+            )"),
+    ]},
+    Migration { version: 18, name: "habits", steps: &[
+        Sql("CREATE TABLE IF NOT EXISTS habits (
+                id         TEXT PRIMARY KEY,
+                name       TEXT NOT NULL,
+                schedule   TEXT NOT NULL DEFAULT 'daily',
+                position   INTEGER NOT NULL,
+                created_at TEXT NOT NULL
+            )"),
+        Sql("CREATE TABLE IF NOT EXISTS habit_checks (
+                habit_id TEXT NOT NULL,
+                date     TEXT NOT NULL,
+                PRIMARY KEY (habit_id, date)
+            )"),
+    ]},
+    Migration { version: 19, name: "repeating tasks", steps: &[
+        AddColumn("tasks", "repeat", "TEXT"),
+    ]},
+    Migration { version: 20, name: "oauth client per token", steps: &[
+        AddColumn("oauth_tokens", "client_id", "TEXT"),
+        AddColumn("oauth_tokens", "client_secret", "TEXT"),
+    ]},
+    Migration { version: 21, name: "organizer and calendar names", steps: &[
+        AddColumn("events", "organizer", "TEXT"),
+        AddColumn("events", "creator", "TEXT"),
+        Sql("CREATE TABLE IF NOT EXISTS calendars (
+                id TEXT PRIMARY KEY, name TEXT NOT NULL
+            )"),
+    ]},
+    Migration { version: 22, name: "settings", steps: &[
+        Sql("CREATE TABLE IF NOT EXISTS settings (
+                key TEXT PRIMARY KEY, value TEXT NOT NULL
+            )"),
+    ]},
+    Migration { version: 23, name: "lead time", steps: &[
+        AddColumn("events", "lead_minutes", "INTEGER NOT NULL DEFAULT 0"),
+    ]},
+    Migration { version: 24, name: "task estimates", steps: &[
+        AddColumn("tasks", "estimate_minutes", "INTEGER NOT NULL DEFAULT 0"),
+    ]},
+    Migration { version: 25, name: "attendee responses", steps: &[
+        AddColumn("event_attendees", "name", "TEXT"),
+        AddColumn("event_attendees", "is_self", "INTEGER NOT NULL DEFAULT 0"),
+        AddColumn("event_attendees", "organizer", "INTEGER NOT NULL DEFAULT 0"),
+        AddColumn("event_attendees", "unsent", "INTEGER NOT NULL DEFAULT 0"),
+    ]},
+    Migration { version: 26, name: "meeting links", steps: &[
+        AddColumn("events", "meet_url", "TEXT"),
+    ]},
+    Migration { version: 27, name: "event colours", steps: &[
+        AddColumn("events", "color", "TEXT"),
+    ]},
+    Migration { version: 28, name: "granted scopes", steps: &[
+        AddColumn("oauth_tokens", "scope", "TEXT"),
+    ]},
+    Migration { version: 29, name: "sync log", steps: &[
+        Sql("CREATE TABLE IF NOT EXISTS sync_log (
+                id INTEGER PRIMARY KEY AUTOINCREMENT, at TEXT NOT NULL,
+                direction TEXT NOT NULL, entity TEXT NOT NULL, action TEXT NOT NULL,
+                title TEXT NOT NULL, result TEXT NOT NULL
+            )"),
+        Sql("CREATE INDEX IF NOT EXISTS idx_sync_log_at ON sync_log(at)"),
+    ]},
+    Migration { version: 30, name: "weather cache", steps: &[
+        Sql("CREATE TABLE IF NOT EXISTS weather (
+                date TEXT PRIMARY KEY, code INTEGER NOT NULL, high REAL NOT NULL, low REAL NOT NULL,
+                place TEXT NOT NULL, fetched_at TEXT NOT NULL
+            )"),
+    ]},
+    Migration { version: 31, name: "reminder state", steps: &[
+        Sql("CREATE TABLE IF NOT EXISTS reminder_state (
+                event_id TEXT NOT NULL, start TEXT NOT NULL,
+                snoozed_until TEXT, dismissed INTEGER NOT NULL DEFAULT 0,
+                PRIMARY KEY (event_id, start)
+            )"),
+    ]},
+];
+
+pub fn latest() -> i64 {
+    MIGRATIONS.last().map(|m| m.version).unwrap_or(0)
+}
+
+pub async fn current(pool: &SqlitePool) -> Result<i64> {
+    sqlx::query("CREATE TABLE IF NOT EXISTS schema_version (version INTEGER NOT NULL)")
+        .execute(pool).await?;
+    let row = sqlx::query("SELECT MAX(version) AS v FROM schema_version").fetch_one(pool).await?;
+    Ok(row.get::<Option<i64>, _>("v").unwrap_or(0))
+}
+
+pub async fn table_exists(pool: &SqlitePool, name: &str) -> Result<bool> {
+    Ok(sqlx::query("SELECT 1 FROM sqlite_master WHERE type='table' AND name=?")
+        .bind(name).fetch_optional(pool).await?.is_some())
+}
+
+/// The version before migrating, if migrations are pending on a database
+/// that already holds data (one worth backing up first).
+pub async fn pending_upgrade(pool: &SqlitePool) -> Result<Option<i64>> {
+    let from = current(pool).await?;
+    if from >= latest() { return Ok(None); }
+    // Databases from before versioning have tables but no version row
+    let existing = from > 0 || table_exists(pool, "events").await?;
+    Ok(existing.then_some(from))
+}
+
+/// Brings the schema up to date.
+pub async fn run(pool: &SqlitePool) -> Result<()> {
+    let from = current(pool).await?;
+    if from > latest() {
+        bail!(
+            "Database schema v{from} is newer than this lm understands (v{}) — please upgrade lm",
+            latest()
+        );
+    }
+
+    // One connection throughout: a pooled connection opened between two
+    // migrations keeps the older schema, and sqlx then misreads `SELECT *`
+    // rows once a later migration has added columns
+    let mut conn = pool.acquire().await?;
+    for m in MIGRATIONS.iter().filter(|m| m.version > from) {
+        let mut tx = conn.begin().await?;
+        for step in m.steps {
+            match step {
+                Sql(sql) => { sqlx::query(sql).execute(&mut *tx).await?; }
+                AddColumn(table, column, decl) => add_column(&mut tx, table, column, decl).await?,
+            }
+        }
+        sqlx::query("INSERT INTO schema_version (version) VALUES (?)")
+            .bind(m.version).execute(&mut *tx).await?;
+        tx.commit().await?;
+        tracing::info!("Applied migration {} ({})", m.version, m.name);
+    }
+    Ok(())
+}
+
+async fn add_column(conn: &mut SqliteConnection, table: &str, column: &str, decl: &str) -> Result<()> {
+    let cols = sqlx::query(&format!("PRAGMA table_info({table})"))
+        .fetch_all(&mut *conn).await?;
+    if !cols.iter().any(|r| r.get::<String, _>("name") == column) {
+        sqlx::query(&format!("ALTER TABLE {table} ADD COLUMN {column} {decl}"))
+            .execute(&mut *conn).await?;
+    }
+    Ok(())
+}
@@ -1,120 +1,293 @@
 use anyhow::Result;
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, Local, NaiveDate, Utc};
 use serde::{Deserialize, Serialize};
 use sqlx::{sqlite::SqlitePool, Row};
-use std::path::PathBuf;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
 use uuid::Uuid;
 
+use crate::attendance::Attendance;
+use crate::dedupe::{self, Merge};
+use crate::theme::TAG_COLORS;
+
+pub mod backup;
+pub mod cloud;
+pub mod journal;
+mod migrations;
+pub mod watch;
+
 // ─── Domain models ────────────────────────────────────────────────────────────
 
+pub use crate::model::{
+    Event, Habit, Task, EVENT_COLORS, FOCUS_CALENDAR, LOCAL_CALENDAR_PREFIX, OUTLOOK_PREFIX, PLAN_CALENDAR,
+    TASKWARRIOR_PREFIX, TIME_CALENDAR, TODOIST_PREFIX,
+};
+
+/// One cell of the meal plan: `slot` 0 = breakfast, 1 = lunch, 2 = dinner.
+#[derive(Debug, Clone)]
+pub struct Meal {
+    pub date: NaiveDate,
+    pub slot: i64,
+    pub text: String,
+}
+
+/// One day of the cached weather forecast (see `weather`), temperatures in
+/// the configured unit.
+#[derive(Debug, Clone)]
+pub struct Forecast {
+    pub date: NaiveDate,
+    /// WMO weather code.
+    pub code: i64,
+    pub high: f64,
+    pub low:  f64,
+}
+
+/// A named local checklist (groceries, packing). Never synced to Google Tasks.
+#[derive(Debug, Clone)]
+pub struct Checklist {
+    pub id:   String,
+    pub name: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct ChecklistItem {
+    pub id:      String,
+    pub text:    String,
+    pub checked: bool,
+}
+
+/// Where someone works for a stretch of time, from Google's working-location
+/// events. Pulled read-only and kept apart from ordinary events.
+#[derive(Debug, Clone)]
+pub struct WorkingLocation {
+    pub sync_id:     String,
+    pub calendar_id: String,
+    pub start:       DateTime<Utc>,
+    pub end:         DateTime<Utc>,
+    pub all_day:     bool,
+    /// `home`, `office` or `custom`.
+    pub kind:        String,
+    /// Building/desk or custom place name, when Google has one.
+    pub label:       Option<String>,
+}
+
+/// The running `Ctrl+t` timer; stopping it records a time entry.
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct Event {
-    pub id: String,
-    pub title: String,
-    pub description: Option<String>,
-    pub start: DateTime<Utc>,
-    pub end: DateTime<Utc>,
-    pub all_day: bool,
-    pub calendar_id: Option<String>,
-    pub sync_id: Option<String>,
-    pub etag: Option<String>,
-    pub dirty: bool,
-    pub deleted: bool,
-    pub created_at: DateTime<Utc>,
-    pub updated_at: DateTime<Utc>,
+pub struct Timer {
+    pub label:      Option<String>,
+    pub tag:        Option<String>,
+    pub started_at: DateTime<Utc>,
+}
+
+/// An event category. `color` is a theme field name (see `TAG_COLORS`) or a
+/// literal colour.
+#[derive(Debug, Clone)]
+pub struct Tag {
+    pub name:  String,
+    pub color: String,
 }
 
-impl Event {
-    pub fn new(title: &str, start: DateTime<Utc>, end: DateTime<Utc>) -> Self {
-        let now = Utc::now();
-        Self {
-            id: Uuid::new_v4().to_string(), title: title.to_owned(),
-            description: None, start, end, all_day: false,
-            calendar_id: None, sync_id: None, etag: None,
-            dirty: true, deleted: false, created_at: now, updated_at: now,
+impl WorkingLocation {
+    pub fn badge(&self) -> &'static str {
+        match self.kind.as_str() {
+            "home"   => "🏠",
+            "office" => "🏢",
+            _        => "📍",
+        }
+    }
+
+    pub fn describe(&self) -> String {
+        match (&self.label, self.kind.as_str()) {
+            (Some(l), _)     => l.clone(),
+            (None, "home")   => "Home".into(),
+            (None, "office") => "Office".into(),
+            (None, _)        => "Elsewhere".into(),
+        }
+    }
+
+    /// Covers any part of `date`; all-day ends are exclusive.
+    pub fn on(&self, date: NaiveDate) -> bool {
+        if self.all_day {
+            self.start.date_naive() <= date && date < self.end.date_naive()
+        } else {
+            self.start.date_naive() <= date && date <= self.end.date_naive()
         }
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct Task {
-    pub id: String,
-    pub title: String,
-    pub notes: Option<String>,
-    pub due: Option<DateTime<Utc>>,
-    pub completed: bool,
-    pub priority: i64,
-    pub task_list_id: Option<String>,
-    pub sync_id: Option<String>,
-    pub dirty: bool,
-    pub deleted: bool,
-    pub created_at: DateTime<Utc>,
-    pub updated_at: DateTime<Utc>,
+/// Someone invited to an event, and their answer.
+#[derive(Debug, Clone)]
+pub struct Attendee {
+    pub email:     String,
+    pub name:      Option<String>,
+    pub response:  Rsvp,
+    /// The signed-in Google account.
+    pub is_self:   bool,
+    pub organizer: bool,
+}
+
+impl Attendee {
+    /// `Ana Ruiz <ana@example.com>`, or the address alone.
+    pub fn describe(&self) -> String {
+        match self.name {
+            Some(ref name) => format!("{name} <{}>", self.email),
+            None           => self.email.clone(),
+        }
+    }
+}
+
+/// An attendee's answer, as Google's `responseStatus`.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum Rsvp {
+    #[default]
+    NeedsAction,
+    Accepted,
+    Tentative,
+    Declined,
 }
 
-impl Task {
-    pub fn new(title: &str) -> Self {
-        let now = Utc::now();
-        Self {
-            id: Uuid::new_v4().to_string(), title: title.to_owned(),
-            notes: None, due: None, completed: false, priority: 0,
-            task_list_id: None, sync_id: None,
-            dirty: true, deleted: false, created_at: now, updated_at: now,
+impl Rsvp {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::NeedsAction => "needsAction",
+            Self::Accepted    => "accepted",
+            Self::Tentative   => "tentative",
+            Self::Declined    => "declined",
+        }
+    }
+
+    pub fn parse(s: &str) -> Self {
+        match s {
+            "accepted"  => Self::Accepted,
+            "tentative" => Self::Tentative,
+            "declined"  => Self::Declined,
+            _           => Self::NeedsAction,
+        }
+    }
+
+    pub fn glyph(self) -> &'static str {
+        match self {
+            Self::NeedsAction => "·",
+            Self::Accepted    => "✓",
+            Self::Tentative   => "?",
+            Self::Declined    => "✗",
+        }
+    }
+
+    /// As Google's own buttons put it.
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::NeedsAction => "no answer",
+            Self::Accepted    => "yes",
+            Self::Tentative   => "maybe",
+            Self::Declined    => "no",
         }
     }
 }
 
+/// A read-only event from a subscribed ICS feed.
+#[derive(Debug, Clone)]
+pub struct FeedEvent {
+    pub feed:    String,
+    pub uid:     String,
+    pub title:   String,
+    pub start:   DateTime<Utc>,
+    pub end:     DateTime<Utc>,
+    pub all_day: bool,
+}
+
+/// One change a sync made or tried to make (`lm sync log`, `Y`), or would
+/// make (`lm sync --dry-run`).
+#[derive(Debug, Clone)]
+pub struct SyncLogEntry {
+    pub at:        DateTime<Utc>,
+    /// `pull` or `push`.
+    pub direction: String,
+    /// `event` or `task`.
+    pub entity:    String,
+    /// `create`, `update`, `delete`, `keep local`, `quarantine`, …
+    pub action:    String,
+    pub title:     String,
+    /// `ok`, or why it failed or was held back.
+    pub result:    String,
+}
+
+/// A remote event that failed validation on import, held for review.
+#[derive(Debug, Clone)]
+pub struct Quarantined {
+    /// `google:<calendar id>` or `feed:<feed name>`.
+    pub source:      String,
+    /// Google event id or ICS UID.
+    pub remote_id:   String,
+    pub title:       String,
+    pub description: Option<String>,
+    /// Whatever could be parsed, as a starting point for a fix.
+    pub start:       Option<DateTime<Utc>>,
+    pub end:         Option<DateTime<Utc>>,
+    pub all_day:     bool,
+    pub reason:      String,
+    /// The item as received (JSON or VEVENT lines).
+    pub raw:         String,
+    pub received_at: DateTime<Utc>,
+}
+
 // ─── Database ─────────────────────────────────────────────────────────────────
 
 #[derive(Clone)]
 pub struct Database {
     pool: SqlitePool,
+    path: PathBuf,
+    /// Where changes are also appended, when `[journal]` is configured.
+    journal: Option<Arc<journal::Journal>>,
 }
 
 impl Database {
+    /// Where `connect` opens the database; there's no file before the
+    /// first run.
+    pub fn file() -> PathBuf { data_dir().join("lifemanager.db") }
+
     pub async fn connect() -> Result<Self> {
-        let db_path = data_dir().join("lifemanager.db");
+        let db_path = Self::file();
         std::fs::create_dir_all(db_path.parent().unwrap())?;
         let url = format!("sqlite://{}?mode=rwc", db_path.display());
-        Ok(Self { pool: SqlitePool::connect(&url).await? })
+        Ok(Self { pool: SqlitePool::connect(&url).await?, path: db_path, journal: None })
     }
 
+    /// Records every later change to events and their guests, tasks, meals,
+    /// checklists, habits and the timer in `journal` too, when there is one (see `journal.rs`).
+    pub fn with_journal(mut self, journal: Option<journal::Journal>) -> Self {
+        self.journal = journal.map(Arc::new);
+        self
+    }
+
+    /// Applies pending schema migrations (see `migrations.rs`), snapshotting
+    /// an existing database first.
     pub async fn migrate(&self) -> Result<()> {
-        sqlx::query(
-            "CREATE TABLE IF NOT EXISTS events (
-                id TEXT PRIMARY KEY, title TEXT NOT NULL, description TEXT,
-                start TEXT NOT NULL, end TEXT NOT NULL, all_day INTEGER NOT NULL DEFAULT 0,
-                calendar_id TEXT, sync_id TEXT, etag TEXT,
-                dirty INTEGER NOT NULL DEFAULT 1, deleted INTEGER NOT NULL DEFAULT 0,
-                created_at TEXT NOT NULL, updated_at TEXT NOT NULL
-            )"
-        ).execute(&self.pool).await?;
-
-        sqlx::query("CREATE INDEX IF NOT EXISTS idx_events_start ON events(start)")
-            .execute(&self.pool).await?;
+        if let Some(from) = migrations::pending_upgrade(&self.pool).await? {
+            self.backup(&format!("pre-migrate-v{from}")).await?;
+        }
+        migrations::run(&self.pool).await?;
+        tracing::info!("DB schema at v{}", migrations::latest());
+        Ok(())
+    }
 
-        sqlx::query(
-            "CREATE TABLE IF NOT EXISTS tasks (
-                id TEXT PRIMARY KEY, title TEXT NOT NULL, notes TEXT, due TEXT,
-                completed INTEGER NOT NULL DEFAULT 0, priority INTEGER NOT NULL DEFAULT 0,
-                task_list_id TEXT, sync_id TEXT,
-                dirty INTEGER NOT NULL DEFAULT 1, deleted INTEGER NOT NULL DEFAULT 0,
-                created_at TEXT NOT NULL, updated_at TEXT NOT NULL
-            )"
-        ).execute(&self.pool).await?;
-
-        sqlx::query("CREATE INDEX IF NOT EXISTS idx_tasks_due ON tasks(due)")
-            .execute(&self.pool).await?;
+    pub fn path(&self) -> &Path { &self.path }
 
-        sqlx::query(
-            "CREATE TABLE IF NOT EXISTS oauth_tokens (
-                provider TEXT PRIMARY KEY, access_token TEXT NOT NULL,
-                refresh_token TEXT, expires_at TEXT
-            )"
-        ).execute(&self.pool).await?;
+    /// A watcher for commits to the database, this pool's included (see
+    /// `watch.rs`).
+    pub async fn watch(&self) -> Result<watch::Watcher> {
+        watch::Watcher::open(&self.path).await
+    }
 
-        tracing::info!("DB migrations complete");
-        Ok(())
+    /// Closes every pooled connection (checkpointing the WAL), e.g. before
+    /// the file is replaced by a restore.
+    pub async fn close(&self) { self.pool.close().await; }
+
+    /// Snapshot into the backups dir (see `backup.rs`); `label` ends up in the
+    /// file name.
+    pub async fn backup(&self, label: &str) -> Result<PathBuf> {
+        backup::snapshot(&self.pool, label).await
     }
 
     // ── Events ────────────────────────────────────────────────────────────────
@@ -122,45 +295,129 @@ impl Database {
     pub async fn upsert_event(&self, e: &Event) -> Result<()> {
         sqlx::query(
             "INSERT INTO events
-                (id,title,description,start,end,all_day,calendar_id,sync_id,etag,dirty,deleted,created_at,updated_at)
-             VALUES (?,?,?,?,?,?,?,?,?,?,?,?,?)
+                (id,title,description,start,end,all_day,calendar_id,sync_id,etag,dirty,deleted,pto,attendance,timezone,remote_title,tag,time_entry,organizer,creator,lead_minutes,meet_url,color,created_at,updated_at)
+             VALUES (?,?,?,?,?,?,?,?,?,?,?,?,?,?,?,?,?,?,?,?,?,?,?,?)
              ON CONFLICT(id) DO UPDATE SET
                 title=excluded.title, description=excluded.description,
                 start=excluded.start, end=excluded.end, all_day=excluded.all_day,
                 calendar_id=excluded.calendar_id, sync_id=excluded.sync_id,
                 etag=excluded.etag, dirty=excluded.dirty, deleted=excluded.deleted,
-                updated_at=excluded.updated_at"
+                pto=excluded.pto, attendance=excluded.attendance, timezone=excluded.timezone, remote_title=excluded.remote_title,
+                tag=excluded.tag, time_entry=excluded.time_entry,
+                organizer=excluded.organizer, creator=excluded.creator, lead_minutes=excluded.lead_minutes,
+                meet_url=excluded.meet_url, color=excluded.color, updated_at=excluded.updated_at"
         )
         .bind(&e.id).bind(&e.title).bind(&e.description)
         .bind(e.start.to_rfc3339()).bind(e.end.to_rfc3339())
         .bind(e.all_day as i32).bind(&e.calendar_id)
         .bind(&e.sync_id).bind(&e.etag)
-        .bind(e.dirty as i32).bind(e.deleted as i32)
+        .bind(e.dirty as i32).bind(e.deleted as i32).bind(e.pto as i32)
+        .bind(e.attendance.map(Attendance::as_str)).bind(&e.timezone)
+        .bind(&e.remote_title).bind(&e.tag).bind(e.time_entry as i32)
+        .bind(&e.organizer).bind(&e.creator).bind(e.lead_minutes).bind(&e.meet_url).bind(&e.color)
         .bind(e.created_at.to_rfc3339()).bind(e.updated_at.to_rfc3339())
         .execute(&self.pool).await?;
+        self.record("event", || serde_json::to_value(e).ok()).await;
         Ok(())
     }
 
-    pub async fn events_in_range(&self, from: DateTime<Utc>, to: DateTime<Utc>) -> Result<Vec<Event>> {
+    /// Events that overlap `[from, to)` at all — including ones that began
+    /// before `from` or run past `to` — so multi-day events show on every
+    /// day they cover. All-day events sort first.
+    pub async fn events_overlapping(&self, from: DateTime<Utc>, to: DateTime<Utc>) -> Result<Vec<Event>> {
         let rows = sqlx::query(
-            "SELECT * FROM events WHERE start >= ? AND start < ? AND deleted=0 ORDER BY start"
+            "SELECT * FROM events WHERE start < ? AND end > ? AND deleted=0
+             ORDER BY all_day DESC, start"
         )
-        .bind(from.to_rfc3339()).bind(to.to_rfc3339())
+        .bind(to.to_rfc3339()).bind(from.to_rfc3339())
         .fetch_all(&self.pool).await?;
-        rows.iter().map(row_to_event).collect()
+        Ok(parse_rows(&rows, row_to_event))
     }
 
+    /// Timed events starting from `from` that only exist here: never synced
+    /// and not on a local layer. Their wall times assume the zone they were
+    /// typed in.
+    pub async fn floating_events(&self, from: DateTime<Utc>) -> Result<Vec<Event>> {
+        let rows = sqlx::query(
+            "SELECT * FROM events WHERE start >= ? AND sync_id IS NULL AND all_day=0 AND deleted=0
+               AND COALESCE(calendar_id,'') NOT LIKE ?
+             ORDER BY start"
+        )
+        .bind(from.to_rfc3339()).bind(format!("{LOCAL_CALENDAR_PREFIX}%"))
+        .fetch_all(&self.pool).await?;
+        Ok(parse_rows(&rows, row_to_event))
+    }
+
+    /// Case-insensitive substring match on title and description, newest
+    /// first. `%` and `_` in `text` are matched literally.
+    pub async fn search_events(&self, text: &str, limit: i64) -> Result<Vec<Event>> {
+        let escaped = text.replace('\\', "\\\\").replace('%', "\\%").replace('_', "\\_");
+        let pattern = format!("%{escaped}%");
+        let rows = sqlx::query(
+            "SELECT * FROM events
+             WHERE deleted=0 AND (title LIKE ?1 ESCAPE '\\' OR description LIKE ?1 ESCAPE '\\')
+             ORDER BY start DESC LIMIT ?2"
+        )
+        .bind(pattern).bind(limit)
+        .fetch_all(&self.pool).await?;
+        Ok(parse_rows(&rows, row_to_event))
+    }
+
+    pub async fn pto_events(&self) -> Result<Vec<Event>> {
+        let rows = sqlx::query("SELECT * FROM events WHERE pto=1 AND deleted=0 ORDER BY start")
+            .fetch_all(&self.pool).await?;
+        Ok(parse_rows(&rows, row_to_event))
+    }
+
+    /// Every live event off the local layers, by start: what `U` looks for
+    /// duplicates in.
+    pub async fn calendar_events(&self) -> Result<Vec<Event>> {
+        let rows = sqlx::query(
+            "SELECT * FROM events WHERE deleted=0 AND COALESCE(calendar_id,'') NOT LIKE ? ORDER BY start"
+        )
+        .bind(format!("{LOCAL_CALENDAR_PREFIX}%"))
+        .fetch_all(&self.pool).await?;
+        Ok(parse_rows(&rows, row_to_event))
+    }
+
+    /// Changed events bound for Google: not on a local layer or an Outlook
+    /// calendar.
     pub async fn dirty_events(&self) -> Result<Vec<Event>> {
-        let rows = sqlx::query("SELECT * FROM events WHERE dirty=1")
+        let rows = sqlx::query(
+            "SELECT * FROM events WHERE dirty=1
+               AND COALESCE(calendar_id,'') NOT LIKE ? AND COALESCE(calendar_id,'') NOT LIKE ?"
+        )
+            .bind(format!("{LOCAL_CALENDAR_PREFIX}%")).bind(format!("{OUTLOOK_PREFIX}%"))
             .fetch_all(&self.pool).await?;
-        rows.iter().map(row_to_event).collect()
+        Ok(parse_rows(&rows, row_to_event))
+    }
+
+    /// Changed events on calendars whose ids start with `prefix`.
+    pub async fn dirty_events_under(&self, prefix: &str) -> Result<Vec<Event>> {
+        let rows = sqlx::query("SELECT * FROM events WHERE dirty=1 AND calendar_id LIKE ?")
+            .bind(format!("{prefix}%"))
+            .fetch_all(&self.pool).await?;
+        Ok(parse_rows(&rows, row_to_event))
+    }
+
+    /// Events of `calendar_id` overlapping `[from, to)` that came from the
+    /// remote and are unchanged here: what a full pull of that window
+    /// should list again.
+    pub async fn synced_events_in(&self, calendar_id: &str, from: DateTime<Utc>, to: DateTime<Utc>) -> Result<Vec<Event>> {
+        let rows = sqlx::query(
+            "SELECT * FROM events WHERE calendar_id=? AND sync_id IS NOT NULL AND deleted=0 AND dirty=0
+               AND start < ? AND end > ?"
+        )
+            .bind(calendar_id).bind(to.to_rfc3339()).bind(from.to_rfc3339())
+            .fetch_all(&self.pool).await?;
+        Ok(parse_rows(&rows, row_to_event))
     }
 
     /// Upsert an event that came from a remote (Google Calendar) pull.
     /// Deduplicates by sync_id and preserves locally-dirty events.
     pub async fn upsert_remote_event(&self, e: &Event) -> Result<()> {
         if let Some(sid) = &e.sync_id {
-            if let Some(row) = sqlx::query("SELECT id, dirty FROM events WHERE sync_id=?")
+            if let Some(row) = sqlx::query("SELECT id, dirty, pto, attendance, tag, time_entry, lead_minutes FROM events WHERE sync_id=?")
                 .bind(sid).fetch_optional(&self.pool).await?
             {
                 let local_id: String = row.get("id");
@@ -171,6 +428,12 @@ impl Database {
                 let mut updated = e.clone();
                 updated.id    = local_id;
                 updated.dirty = false;
+                updated.pto   = row.get::<i32, _>("pto") != 0;
+                updated.attendance = row.get::<Option<String>, _>("attendance")
+                    .as_deref().and_then(Attendance::parse);
+                updated.tag   = row.get("tag");
+                updated.time_entry = row.get::<i32, _>("time_entry") != 0;
+                updated.lead_minutes = row.get("lead_minutes");
                 return self.upsert_event(&updated).await;
             }
         }
@@ -179,12 +442,156 @@ impl Database {
         self.upsert_event(&new_e).await
     }
 
+    /// Local id of the event linked to remote `sync_id`, if any.
+    pub async fn event_id_for_sync_id(&self, sync_id: &str) -> Result<Option<String>> {
+        let row = sqlx::query("SELECT id FROM events WHERE sync_id=?")
+            .bind(sync_id).fetch_optional(&self.pool).await?;
+        Ok(row.map(|r| r.get("id")))
+    }
+
     pub async fn mark_event_clean(&self, id: &str, sync_id: Option<&str>, etag: Option<&str>) -> Result<()> {
         sqlx::query(
             "UPDATE events SET dirty=0, sync_id=COALESCE(?,sync_id), etag=COALESCE(?,etag) WHERE id=?"
         )
         .bind(sync_id).bind(etag).bind(id)
         .execute(&self.pool).await?;
+        self.record_row("event", "SELECT * FROM events WHERE id=?", id, row_to_event).await
+    }
+
+    /// Drops every row linked to remote `sync_id`, with its guests and sent
+    /// reminders: Google cancelled the event, so local edits to it have
+    /// nowhere to go. Journalled as deleted first so a replay doesn't bring
+    /// it back. Returns how many rows went.
+    pub async fn purge_remote_event(&self, sync_id: &str) -> Result<u64> {
+        let ids: Vec<String> = sqlx::query("SELECT id FROM events WHERE sync_id=?")
+            .bind(sync_id).fetch_all(&self.pool).await?
+            .iter().map(|r| r.get("id")).collect();
+        if ids.is_empty() { return Ok(0); }
+        sqlx::query("UPDATE events SET deleted=1, dirty=0, updated_at=? WHERE sync_id=?")
+            .bind(Utc::now().to_rfc3339()).bind(sync_id)
+            .execute(&self.pool).await?;
+        for id in &ids {
+            self.record_row("event", "SELECT * FROM events WHERE id=?", id, row_to_event).await?;
+        }
+
+        let mut tx = self.pool.begin().await?;
+        for id in &ids {
+            sqlx::query("DELETE FROM event_attendees WHERE event_id=?").bind(id).execute(&mut *tx).await?;
+            sqlx::query("DELETE FROM reminders_sent WHERE event_id=?").bind(id).execute(&mut *tx).await?;
+            sqlx::query("DELETE FROM reminder_state WHERE event_id=?").bind(id).execute(&mut *tx).await?;
+        }
+        sqlx::query("DELETE FROM events WHERE sync_id=?").bind(sync_id).execute(&mut *tx).await?;
+        tx.commit().await?;
+        Ok(ids.len() as u64)
+    }
+
+    /// Takes back a delete Google refused because the event changed there
+    /// since, or any change to an event on a pull-only calendar: the event
+    /// shows again, and the next pull brings Google's copy.
+    pub async fn undo_event_delete(&self, id: &str) -> Result<()> {
+        sqlx::query("UPDATE events SET deleted=0, dirty=0 WHERE id=?")
+            .bind(id).execute(&self.pool).await?;
+        self.record_row("event", "SELECT * FROM events WHERE id=?", id, row_to_event).await
+    }
+
+    /// Replaces the guest list of an event.
+    pub async fn set_attendees(&self, event_id: &str, emails: &[String]) -> Result<()> {
+        let mut tx = self.pool.begin().await?;
+        sqlx::query("DELETE FROM event_attendees WHERE event_id=?")
+            .bind(event_id).execute(&mut *tx).await?;
+        for email in emails {
+            sqlx::query("INSERT OR IGNORE INTO event_attendees (event_id, email) VALUES (?, ?)")
+                .bind(event_id).bind(email).execute(&mut *tx).await?;
+        }
+        tx.commit().await?;
+        self.record_guests(event_id).await
+    }
+
+    /// Remembers a Google calendar's name (for `primary`, the account's
+    /// address), shown as where its events live.
+    pub async fn set_calendar_name(&self, id: &str, name: &str) -> Result<()> {
+        sqlx::query("INSERT INTO calendars (id, name) VALUES (?, ?) ON CONFLICT(id) DO UPDATE SET name=excluded.name")
+            .bind(id).bind(name).execute(&self.pool).await?;
+        Ok(())
+    }
+
+    pub async fn calendar_name(&self, id: &str) -> Result<Option<String>> {
+        let row = sqlx::query("SELECT name FROM calendars WHERE id=?")
+            .bind(id).fetch_optional(&self.pool).await?;
+        Ok(row.map(|r| r.get("name")))
+    }
+
+    /// A value the app keeps for itself between runs, like the last
+    /// system timezone seen.
+    pub async fn setting(&self, key: &str) -> Result<Option<String>> {
+        let row = sqlx::query("SELECT value FROM settings WHERE key=?")
+            .bind(key).fetch_optional(&self.pool).await?;
+        Ok(row.map(|r| r.get("value")))
+    }
+
+    pub async fn set_setting(&self, key: &str, value: &str) -> Result<()> {
+        sqlx::query("INSERT INTO settings (key, value) VALUES (?, ?) ON CONFLICT(key) DO UPDATE SET value=excluded.value")
+            .bind(key).bind(value).execute(&self.pool).await?;
+        Ok(())
+    }
+
+    /// The organizer first, then by address.
+    pub async fn attendees(&self, event_id: &str) -> Result<Vec<Attendee>> {
+        let rows = sqlx::query("SELECT * FROM event_attendees WHERE event_id=? ORDER BY organizer DESC, email")
+            .bind(event_id).fetch_all(&self.pool).await?;
+        Ok(rows.iter().map(|r| Attendee {
+            email:     r.get("email"),
+            name:      r.get("name"),
+            response:  r.get::<Option<String>, _>("response_status").as_deref().map(Rsvp::parse).unwrap_or_default(),
+            is_self:   r.get::<i32, _>("is_self") != 0,
+            organizer: r.get::<i32, _>("organizer") != 0,
+        }).collect())
+    }
+
+    /// Replaces an event's guests with Google's list, unless an answer given
+    /// here is still to be sent (the next pull has it either way).
+    pub async fn set_remote_attendees(&self, event_id: &str, attendees: &[Attendee]) -> Result<()> {
+        let mut tx = self.pool.begin().await?;
+        let unsent = sqlx::query("SELECT 1 FROM event_attendees WHERE event_id=? AND unsent=1")
+            .bind(event_id).fetch_optional(&mut *tx).await?;
+        if unsent.is_some() { return Ok(()); }
+        sqlx::query("DELETE FROM event_attendees WHERE event_id=?")
+            .bind(event_id).execute(&mut *tx).await?;
+        for a in attendees {
+            sqlx::query(
+                "INSERT OR IGNORE INTO event_attendees (event_id, email, name, response_status, is_self, organizer)
+                 VALUES (?, ?, ?, ?, ?, ?)"
+            )
+            .bind(event_id).bind(&a.email).bind(&a.name).bind(a.response.as_str())
+            .bind(a.is_self as i32).bind(a.organizer as i32)
+            .execute(&mut *tx).await?;
+        }
+        tx.commit().await?;
+        self.record_guests(event_id).await
+    }
+
+    /// Records your answer to an invitation, to be sent on the next push.
+    /// False if you aren't among the event's guests.
+    pub async fn set_response(&self, event_id: &str, response: Rsvp) -> Result<bool> {
+        let done = sqlx::query("UPDATE event_attendees SET response_status=?, unsent=1 WHERE event_id=? AND is_self=1")
+            .bind(response.as_str()).bind(event_id).execute(&self.pool).await?;
+        if done.rows_affected() == 0 { return Ok(false); }
+        self.record_guests(event_id).await?;
+        Ok(true)
+    }
+
+    /// Events with an answer given here and not yet sent to Google.
+    pub async fn unsent_responses(&self) -> Result<Vec<Event>> {
+        let rows = sqlx::query(
+            "SELECT * FROM events WHERE id IN (SELECT event_id FROM event_attendees WHERE unsent=1)
+               AND sync_id IS NOT NULL AND deleted=0"
+        ).fetch_all(&self.pool).await?;
+        Ok(parse_rows(&rows, row_to_event))
+    }
+
+    pub async fn mark_response_sent(&self, event_id: &str) -> Result<()> {
+        sqlx::query("UPDATE event_attendees SET unsent=0 WHERE event_id=?")
+            .bind(event_id).execute(&self.pool).await?;
         Ok(())
     }
 
@@ -193,43 +600,84 @@ impl Database {
     pub async fn upsert_task(&self, t: &Task) -> Result<()> {
         sqlx::query(
             "INSERT INTO tasks
-                (id,title,notes,due,completed,priority,task_list_id,sync_id,dirty,deleted,created_at,updated_at)
-             VALUES (?,?,?,?,?,?,?,?,?,?,?,?)
+                (id,title,notes,due,completed,priority,task_list_id,sync_id,dirty,deleted,tags,position,repeat,estimate_minutes,created_at,updated_at)
+             VALUES (?,?,?,?,?,?,?,?,?,?,?,
+                     COALESCE(NULLIF(?,0),(SELECT COALESCE(MAX(position),0)+1 FROM tasks)),?,?,?,?)
              ON CONFLICT(id) DO UPDATE SET
                 title=excluded.title, notes=excluded.notes, due=excluded.due,
                 completed=excluded.completed, priority=excluded.priority,
                 task_list_id=excluded.task_list_id, sync_id=excluded.sync_id,
-                dirty=excluded.dirty, deleted=excluded.deleted, updated_at=excluded.updated_at"
+                dirty=excluded.dirty, deleted=excluded.deleted, tags=excluded.tags,
+                position=excluded.position, repeat=excluded.repeat,
+                estimate_minutes=excluded.estimate_minutes, updated_at=excluded.updated_at"
         )
         .bind(&t.id).bind(&t.title).bind(&t.notes)
         .bind(t.due.as_ref().map(|d| d.to_rfc3339()))
         .bind(t.completed as i32).bind(t.priority).bind(&t.task_list_id)
         .bind(&t.sync_id).bind(t.dirty as i32).bind(t.deleted as i32)
+        .bind((!t.tags.is_empty()).then(|| t.tags.join(" "))).bind(t.position).bind(&t.repeat)
+        .bind(t.estimate_minutes)
         .bind(t.created_at.to_rfc3339()).bind(t.updated_at.to_rfc3339())
         .execute(&self.pool).await?;
-        Ok(())
+        // Re-read: a new task's position is only known now
+        self.record_row("task", "SELECT * FROM tasks WHERE id=?", &t.id, row_to_task).await
     }
 
+    /// Changed tasks bound for Google: not on an Outlook (To Do) list, a
+    /// Todoist project or a Taskwarrior one.
     pub async fn dirty_tasks(&self) -> Result<Vec<Task>> {
-        let rows = sqlx::query("SELECT * FROM tasks WHERE dirty=1")
+        // In list order, so a moved task's new neighbour is already pushed
+        let rows = sqlx::query(
+            "SELECT * FROM tasks WHERE dirty=1
+               AND COALESCE(task_list_id,'') NOT LIKE ? AND COALESCE(task_list_id,'') NOT LIKE ?
+               AND COALESCE(task_list_id,'') NOT LIKE ?
+             ORDER BY position"
+        )
+            .bind(format!("{OUTLOOK_PREFIX}%")).bind(format!("{TODOIST_PREFIX}%")).bind(format!("{TASKWARRIOR_PREFIX}%"))
+            .fetch_all(&self.pool).await?;
+        Ok(parse_rows(&rows, row_to_task))
+    }
+
+    /// Changed tasks on lists whose ids start with `prefix`, in list order.
+    pub async fn dirty_tasks_under(&self, prefix: &str) -> Result<Vec<Task>> {
+        let rows = sqlx::query("SELECT * FROM tasks WHERE dirty=1 AND task_list_id LIKE ? ORDER BY position")
+            .bind(format!("{prefix}%"))
+            .fetch_all(&self.pool).await?;
+        Ok(parse_rows(&rows, row_to_task))
+    }
+
+    /// Tasks of `list_id` that came from the remote and are unchanged here.
+    pub async fn synced_tasks_in(&self, list_id: &str) -> Result<Vec<Task>> {
+        let rows = sqlx::query(
+            "SELECT * FROM tasks WHERE task_list_id=? AND sync_id IS NOT NULL AND deleted=0 AND dirty=0"
+        )
+            .bind(list_id)
             .fetch_all(&self.pool).await?;
-        rows.iter().map(row_to_task).collect()
+        Ok(parse_rows(&rows, row_to_task))
     }
 
     pub async fn mark_task_clean(&self, id: &str, sync_id: Option<&str>) -> Result<()> {
         sqlx::query(
-            "UPDATE tasks SET dirty=0, sync_id=COALESCE(?,sync_id) WHERE id=?"
+            "UPDATE tasks SET dirty=0, moved=0, sync_id=COALESCE(?,sync_id) WHERE id=?"
         )
         .bind(sync_id).bind(id)
         .execute(&self.pool).await?;
-        Ok(())
+        self.record_row("task", "SELECT * FROM tasks WHERE id=?", id, row_to_task).await
+    }
+
+    /// Takes back local changes to a task on a pull-only list: it's no
+    /// longer marked for pushing, and the next pull brings Google's copy.
+    pub async fn undo_task_edit(&self, id: &str) -> Result<()> {
+        sqlx::query("UPDATE tasks SET deleted=0, dirty=0, moved=0 WHERE id=?")
+            .bind(id).execute(&self.pool).await?;
+        self.record_row("task", "SELECT * FROM tasks WHERE id=?", id, row_to_task).await
     }
 
     /// Upsert a task that came from a remote (Google Tasks) pull.
     /// Deduplicates by sync_id and preserves locally-dirty tasks.
     pub async fn upsert_remote_task(&self, t: &Task) -> Result<()> {
         if let Some(sid) = &t.sync_id {
-            if let Some(row) = sqlx::query("SELECT id, dirty FROM tasks WHERE sync_id=?")
+            if let Some(row) = sqlx::query("SELECT id, dirty, priority, tags, position, repeat, estimate_minutes FROM tasks WHERE sync_id=?")
                 .bind(sid).fetch_optional(&self.pool).await?
             {
                 let local_id: String = row.get("id");
@@ -240,6 +688,18 @@ impl Database {
                 let mut updated = t.clone();
                 updated.id    = local_id;
                 updated.dirty = false;
+                // Todoist and Taskwarrior have priorities of their own,
+                // Taskwarrior tags too; Google Tasks neither
+                let list = t.task_list_id.as_deref().unwrap_or_default();
+                if !list.starts_with(TODOIST_PREFIX) && !list.starts_with(TASKWARRIOR_PREFIX) {
+                    updated.priority = row.get("priority");
+                }
+                if !list.starts_with(TASKWARRIOR_PREFIX) {
+                    updated.tags = split_tags(row.get("tags"));
+                }
+                updated.position = row.get("position");
+                updated.repeat   = row.get("repeat");
+                updated.estimate_minutes = row.get("estimate_minutes");
                 return self.upsert_task(&updated).await;
             }
         }
@@ -248,11 +708,761 @@ impl Database {
         self.upsert_task(&new_t).await
     }
 
+    /// Swaps two tasks' places in the manual order. Both are queued for a
+    /// push, which moves them on Google Tasks too.
+    pub async fn swap_task_positions(&self, a: &str, b: &str) -> Result<()> {
+        let pos = |id: &str| sqlx::query_scalar::<_, i64>("SELECT position FROM tasks WHERE id=?")
+            .bind(id.to_owned()).fetch_one(&self.pool);
+        let (pa, pb) = (pos(a).await?, pos(b).await?);
+        let now = Utc::now().to_rfc3339();
+        let mut tx = self.pool.begin().await?;
+        for (id, p) in [(a, pb), (b, pa)] {
+            sqlx::query("UPDATE tasks SET position=?, moved=1, dirty=1, updated_at=? WHERE id=?")
+                .bind(p).bind(&now).bind(id).execute(&mut *tx).await?;
+        }
+        tx.commit().await?;
+        for id in [a, b] {
+            self.record_row("task", "SELECT * FROM tasks WHERE id=?", id, row_to_task).await?;
+        }
+        Ok(())
+    }
+
+    pub async fn task_moved(&self, id: &str) -> Result<bool> {
+        let moved: Option<i64> = sqlx::query_scalar("SELECT moved FROM tasks WHERE id=?")
+            .bind(id).fetch_optional(&self.pool).await?;
+        Ok(moved == Some(1))
+    }
+
+    /// The sync_id of the synced task just above `t` in its list, for
+    /// Google's `previous`; `None` when `t` goes first. Tasks made here
+    /// have no list until a pull fills it in: they push to `@default`.
+    pub async fn synced_task_before(&self, t: &Task) -> Result<Option<String>> {
+        Ok(sqlx::query_scalar(
+            "SELECT sync_id FROM tasks
+             WHERE COALESCE(task_list_id,'@default') = COALESCE(?,'@default')
+               AND sync_id IS NOT NULL AND deleted=0 AND position < ?
+             ORDER BY position DESC LIMIT 1"
+        )
+        .bind(&t.task_list_id).bind(t.position)
+        .fetch_optional(&self.pool).await?)
+    }
+
+    /// Takes a pulled list's order (sync_ids, Google's order) onto the local
+    /// tasks, reusing the positions they already hold so tasks from other
+    /// lists keep their places. Skipped while a local move awaits a push.
+    pub async fn apply_remote_order(&self, list_id: &str, order: &[String]) -> Result<()> {
+        let pending: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM tasks WHERE COALESCE(task_list_id,'@default')=? AND moved=1")
+            .bind(list_id).fetch_one(&self.pool).await?;
+        if pending > 0 { return Ok(()); }
+
+        let rows = sqlx::query("SELECT sync_id, position FROM tasks WHERE task_list_id=? AND sync_id IS NOT NULL")
+            .bind(list_id).fetch_all(&self.pool).await?;
+        let held: HashMap<String, i64> = rows.iter()
+            .map(|r| (r.get("sync_id"), r.get("position")))
+            .collect();
+        let order: Vec<&String> = order.iter().filter(|s| held.contains_key(*s)).collect();
+        let mut slots: Vec<i64> = order.iter().map(|s| held[*s]).collect();
+        slots.sort_unstable();
+
+        let moves: Vec<(&String, i64)> = order.into_iter().zip(slots)
+            .filter(|(sid, pos)| held[*sid] != *pos)
+            .collect();
+        let mut tx = self.pool.begin().await?;
+        for (sid, pos) in &moves {
+            sqlx::query("UPDATE tasks SET position=? WHERE sync_id=?")
+                .bind(pos).bind(*sid).execute(&mut *tx).await?;
+        }
+        tx.commit().await?;
+        for (sid, _) in moves {
+            self.record_row("task", "SELECT * FROM tasks WHERE sync_id=?", sid, row_to_task).await?;
+        }
+        Ok(())
+    }
+
     pub async fn all_tasks(&self) -> Result<Vec<Task>> {
         let rows = sqlx::query(
             "SELECT * FROM tasks WHERE deleted=0 ORDER BY priority DESC, due, title"
         ).fetch_all(&self.pool).await?;
-        rows.iter().map(row_to_task).collect()
+        Ok(parse_rows(&rows, row_to_task))
+    }
+
+    /// Changes whenever an event or task is written or removed: the newest
+    /// `updated_at` and the row count of each table.
+    pub async fn change_stamp(&self) -> Result<String> {
+        let row = sqlx::query(
+            "SELECT (SELECT COALESCE(MAX(updated_at),'') || '/' || COUNT(*) FROM events)
+                 || ' ' || (SELECT COALESCE(MAX(updated_at),'') || '/' || COUNT(*) FROM tasks)"
+        ).fetch_one(&self.pool).await?;
+        Ok(row.get(0))
+    }
+
+    // ── Meals ─────────────────────────────────────────────────────────────────
+
+    pub async fn meals_between(&self, from: NaiveDate, to: NaiveDate) -> Result<Vec<Meal>> {
+        let rows = sqlx::query(
+            "SELECT date, slot, text FROM meals WHERE date >= ? AND date <= ? ORDER BY date, slot"
+        )
+        .bind(from.to_string()).bind(to.to_string())
+        .fetch_all(&self.pool).await?;
+        rows.iter().map(|r| Ok(Meal {
+            date: NaiveDate::parse_from_str(r.get("date"), "%Y-%m-%d")?,
+            slot: r.get("slot"),
+            text: r.get("text"),
+        })).collect()
+    }
+
+    /// Empty text clears the cell.
+    pub async fn set_meal(&self, date: NaiveDate, slot: i64, text: &str) -> Result<()> {
+        if text.trim().is_empty() {
+            sqlx::query("DELETE FROM meals WHERE date=? AND slot=?")
+                .bind(date.to_string()).bind(slot)
+                .execute(&self.pool).await?;
+        } else {
+            sqlx::query(
+                "INSERT INTO meals (date,slot,text) VALUES (?,?,?)
+                 ON CONFLICT(date,slot) DO UPDATE SET text=excluded.text"
+            )
+            .bind(date.to_string()).bind(slot).bind(text.trim())
+            .execute(&self.pool).await?;
+        }
+        self.record("meal", || Some(serde_json::json!({
+            "date": date.to_string(), "slot": slot, "text": text.trim(),
+        }))).await;
+        Ok(())
+    }
+
+    // ── Weather ───────────────────────────────────────────────────────────────
+
+    /// When the forecast for `place` was downloaded, if it's the one cached.
+    pub async fn weather_fetched(&self, place: &str) -> Result<Option<DateTime<Utc>>> {
+        let row = sqlx::query("SELECT MAX(fetched_at) AS at FROM weather WHERE place=?")
+            .bind(place).fetch_one(&self.pool).await?;
+        Ok(row.get::<Option<String>, _>("at")
+            .and_then(|at| DateTime::parse_from_rfc3339(&at).ok())
+            .map(|at| at.with_timezone(&Utc)))
+    }
+
+    /// Swaps the cached forecast for a fresh download.
+    pub async fn replace_weather(&self, place: &str, days: &[Forecast]) -> Result<()> {
+        let now = Utc::now().to_rfc3339();
+        let mut tx = self.pool.begin().await?;
+        sqlx::query("DELETE FROM weather").execute(&mut *tx).await?;
+        for d in days {
+            sqlx::query("INSERT INTO weather (date,code,high,low,place,fetched_at) VALUES (?,?,?,?,?,?)")
+                .bind(d.date.to_string()).bind(d.code).bind(d.high).bind(d.low).bind(place).bind(&now)
+                .execute(&mut *tx).await?;
+        }
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// The cached forecast from `from` on.
+    pub async fn forecast(&self, from: NaiveDate) -> Result<Vec<Forecast>> {
+        let rows = sqlx::query("SELECT date, code, high, low FROM weather WHERE date >= ? ORDER BY date")
+            .bind(from.to_string())
+            .fetch_all(&self.pool).await?;
+        rows.iter().map(|r| Ok(Forecast {
+            date: NaiveDate::parse_from_str(r.get("date"), "%Y-%m-%d")?,
+            code: r.get("code"),
+            high: r.get("high"),
+            low:  r.get("low"),
+        })).collect()
+    }
+
+    // ── Checklists ────────────────────────────────────────────────────────────
+
+    pub async fn checklists(&self) -> Result<Vec<Checklist>> {
+        let rows = sqlx::query("SELECT id, name FROM checklists ORDER BY name COLLATE NOCASE")
+            .fetch_all(&self.pool).await?;
+        Ok(rows.iter().map(|r| Checklist { id: r.get("id"), name: r.get("name") }).collect())
+    }
+
+    pub async fn create_checklist(&self, name: &str) -> Result<Checklist> {
+        let list = Checklist { id: Uuid::new_v4().to_string(), name: name.trim().to_owned() };
+        sqlx::query("INSERT INTO checklists (id,name,created_at) VALUES (?,?,?)")
+            .bind(&list.id).bind(&list.name).bind(Utc::now().to_rfc3339())
+            .execute(&self.pool).await?;
+        self.record_checklist(&list.id).await?;
+        Ok(list)
+    }
+
+    pub async fn delete_checklist(&self, id: &str) -> Result<()> {
+        sqlx::query("DELETE FROM checklist_items WHERE list_id=?").bind(id).execute(&self.pool).await?;
+        sqlx::query("DELETE FROM checklists WHERE id=?").bind(id).execute(&self.pool).await?;
+        self.record("checklist", || Some(serde_json::json!({ "id": id, "deleted": true }))).await;
+        Ok(())
+    }
+
+    pub async fn checklist_items(&self, list_id: &str) -> Result<Vec<ChecklistItem>> {
+        let rows = sqlx::query(
+            "SELECT id, text, checked FROM checklist_items WHERE list_id=? ORDER BY position"
+        )
+        .bind(list_id).fetch_all(&self.pool).await?;
+        Ok(rows.iter().map(|r| ChecklistItem {
+            id:      r.get("id"),
+            text:    r.get("text"),
+            checked: r.get::<i32, _>("checked") != 0,
+        }).collect())
+    }
+
+    pub async fn add_checklist_item(&self, list_id: &str, text: &str) -> Result<()> {
+        sqlx::query(
+            "INSERT INTO checklist_items (id,list_id,text,checked,position)
+             VALUES (?,?,?,0,(SELECT COALESCE(MAX(position),0)+1 FROM checklist_items WHERE list_id=?))"
+        )
+        .bind(Uuid::new_v4().to_string()).bind(list_id).bind(text.trim()).bind(list_id)
+        .execute(&self.pool).await?;
+        self.record_checklist(list_id).await
+    }
+
+    pub async fn set_checklist_item_checked(&self, id: &str, checked: bool) -> Result<()> {
+        sqlx::query("UPDATE checklist_items SET checked=? WHERE id=?")
+            .bind(checked as i32).bind(id).execute(&self.pool).await?;
+        let list_id = self.checklist_of(id).await?;
+        self.record_checklist(&list_id).await
+    }
+
+    pub async fn delete_checklist_item(&self, id: &str) -> Result<()> {
+        let list_id = self.checklist_of(id).await?;
+        sqlx::query("DELETE FROM checklist_items WHERE id=?").bind(id).execute(&self.pool).await?;
+        self.record_checklist(&list_id).await
+    }
+
+    /// Unchecks every item so the list can be reused (next shop, next trip).
+    pub async fn reset_checklist(&self, list_id: &str) -> Result<()> {
+        sqlx::query("UPDATE checklist_items SET checked=0 WHERE list_id=?")
+            .bind(list_id).execute(&self.pool).await?;
+        self.record_checklist(list_id).await
+    }
+
+    async fn checklist_of(&self, item_id: &str) -> Result<String> {
+        let row = sqlx::query("SELECT list_id FROM checklist_items WHERE id=?")
+            .bind(item_id).fetch_optional(&self.pool).await?;
+        Ok(row.map(|r| r.get("list_id")).unwrap_or_default())
+    }
+
+    // ── Habits ────────────────────────────────────────────────────────────────
+
+    pub async fn habits(&self) -> Result<Vec<Habit>> {
+        let rows = sqlx::query("SELECT id, name, schedule, created_at FROM habits ORDER BY position")
+            .fetch_all(&self.pool).await?;
+        rows.iter().map(|r| Ok(Habit {
+            id:         r.get("id"),
+            name:       r.get("name"),
+            schedule:   r.get("schedule"),
+            created_at: parse_dt(r.get("created_at"))?,
+        })).collect()
+    }
+
+    pub async fn add_habit(&self, name: &str, schedule: &str) -> Result<()> {
+        let id = Uuid::new_v4().to_string();
+        sqlx::query(
+            "INSERT INTO habits (id,name,schedule,position,created_at)
+             VALUES (?,?,?,(SELECT COALESCE(MAX(position),0)+1 FROM habits),?)"
+        )
+        .bind(&id).bind(name.trim()).bind(schedule)
+        .bind(Local::now().naive_local().and_utc().to_rfc3339())
+        .execute(&self.pool).await?;
+        self.record_habit(&id).await
+    }
+
+    pub async fn delete_habit(&self, id: &str) -> Result<()> {
+        sqlx::query("DELETE FROM habit_checks WHERE habit_id=?").bind(id).execute(&self.pool).await?;
+        sqlx::query("DELETE FROM habits WHERE id=?").bind(id).execute(&self.pool).await?;
+        self.record_habit(id).await
+    }
+
+    /// Every (habit id, day) it was done.
+    pub async fn habit_checks(&self) -> Result<HashSet<(String, NaiveDate)>> {
+        let rows = sqlx::query("SELECT habit_id, date FROM habit_checks")
+            .fetch_all(&self.pool).await?;
+        Ok(rows.iter()
+            .filter_map(|r| {
+                let date = NaiveDate::parse_from_str(&r.get::<String, _>("date"), "%Y-%m-%d").ok()?;
+                Some((r.get("habit_id"), date))
+            })
+            .collect())
+    }
+
+    /// Marks the habit done on `date`, or undone if it already was; returns
+    /// whether it's done now.
+    pub async fn toggle_habit(&self, id: &str, date: NaiveDate) -> Result<bool> {
+        let day = date.format("%Y-%m-%d").to_string();
+        let removed = sqlx::query("DELETE FROM habit_checks WHERE habit_id=? AND date=?")
+            .bind(id).bind(&day).execute(&self.pool).await?.rows_affected() > 0;
+        if !removed {
+            sqlx::query("INSERT INTO habit_checks (habit_id, date) VALUES (?, ?)")
+                .bind(id).bind(&day).execute(&self.pool).await?;
+        }
+        self.record_habit(id).await?;
+        Ok(!removed)
+    }
+
+    // ── ICS feeds ─────────────────────────────────────────────────────────────
+
+    /// Replaces a feed's cached events with a fresh download.
+    pub async fn replace_feed_events(&self, name: &str, url: &str, events: &[FeedEvent]) -> Result<()> {
+        let mut tx = self.pool.begin().await?;
+        sqlx::query("DELETE FROM feed_events WHERE feed=?").bind(name).execute(&mut *tx).await?;
+        for e in events {
+            sqlx::query(
+                "INSERT OR REPLACE INTO feed_events (feed,uid,title,start,end,all_day)
+                 VALUES (?,?,?,?,?,?)"
+            )
+            .bind(name).bind(&e.uid).bind(&e.title)
+            .bind(e.start.to_rfc3339()).bind(e.end.to_rfc3339()).bind(e.all_day as i32)
+            .execute(&mut *tx).await?;
+        }
+        sqlx::query(
+            "INSERT INTO feeds (name,url,fetched_at) VALUES (?,?,?)
+             ON CONFLICT(name) DO UPDATE SET url=excluded.url, fetched_at=excluded.fetched_at"
+        )
+        .bind(name).bind(url).bind(Utc::now().to_rfc3339())
+        .execute(&mut *tx).await?;
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// Drops cached feeds that are no longer in config.
+    pub async fn prune_feeds(&self, keep: &[String]) -> Result<()> {
+        let rows = sqlx::query("SELECT name FROM feeds").fetch_all(&self.pool).await?;
+        for r in rows {
+            let name: String = r.get("name");
+            if !keep.contains(&name) {
+                sqlx::query("DELETE FROM feed_events WHERE feed=?").bind(&name).execute(&self.pool).await?;
+                sqlx::query("DELETE FROM feeds WHERE name=?").bind(&name).execute(&self.pool).await?;
+                sqlx::query("DELETE FROM quarantine WHERE source=?")
+                    .bind(format!("feed:{name}")).execute(&self.pool).await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Feed events overlapping `[from, to)`.
+    pub async fn feed_events_overlapping(&self, from: DateTime<Utc>, to: DateTime<Utc>) -> Result<Vec<FeedEvent>> {
+        let rows = sqlx::query(
+            "SELECT * FROM feed_events WHERE start < ? AND end > ? ORDER BY all_day DESC, start"
+        )
+        .bind(to.to_rfc3339()).bind(from.to_rfc3339())
+        .fetch_all(&self.pool).await?;
+        Ok(parse_rows(&rows, row_to_feed_event))
+    }
+
+    // ── Working locations ─────────────────────────────────────────────────────
+
+    pub async fn upsert_working_location(&self, w: &WorkingLocation) -> Result<()> {
+        sqlx::query(
+            "INSERT INTO working_locations (sync_id,calendar_id,start,end,all_day,kind,label)
+             VALUES (?,?,?,?,?,?,?)
+             ON CONFLICT(sync_id) DO UPDATE SET
+                calendar_id=excluded.calendar_id, start=excluded.start, end=excluded.end,
+                all_day=excluded.all_day, kind=excluded.kind, label=excluded.label"
+        )
+        .bind(&w.sync_id).bind(&w.calendar_id)
+        .bind(w.start.to_rfc3339()).bind(w.end.to_rfc3339())
+        .bind(w.all_day as i32).bind(&w.kind).bind(&w.label)
+        .execute(&self.pool).await?;
+        // Older versions imported these as ordinary events; retire that copy
+        sqlx::query("UPDATE events SET deleted=1 WHERE sync_id=? AND dirty=0 AND deleted=0")
+            .bind(&w.sync_id).execute(&self.pool).await?;
+        Ok(())
+    }
+
+    pub async fn delete_working_location(&self, sync_id: &str) -> Result<()> {
+        sqlx::query("DELETE FROM working_locations WHERE sync_id=?")
+            .bind(sync_id).execute(&self.pool).await?;
+        Ok(())
+    }
+
+    pub async fn working_locations(&self) -> Result<Vec<WorkingLocation>> {
+        let rows = sqlx::query("SELECT * FROM working_locations ORDER BY start")
+            .fetch_all(&self.pool).await?;
+        Ok(parse_rows(&rows, row_to_working_location))
+    }
+
+    // ── Tags ──────────────────────────────────────────────────────────────────
+
+    pub async fn tags(&self) -> Result<Vec<Tag>> {
+        let rows = sqlx::query("SELECT name, color FROM tags ORDER BY name")
+            .fetch_all(&self.pool).await?;
+        Ok(rows.iter().map(|r| Tag { name: r.get("name"), color: r.get("color") }).collect())
+    }
+
+    /// Creates `name` if it's new, giving it the next colour in turn.
+    pub async fn ensure_tag(&self, name: &str) -> Result<()> {
+        let count: i64 = sqlx::query("SELECT COUNT(*) AS n FROM tags")
+            .fetch_one(&self.pool).await?.get("n");
+        sqlx::query("INSERT INTO tags (name, color) VALUES (?, ?) ON CONFLICT(name) DO NOTHING")
+            .bind(name).bind(TAG_COLORS[count as usize % TAG_COLORS.len()])
+            .execute(&self.pool).await?;
+        Ok(())
+    }
+
+    // ── Timer ─────────────────────────────────────────────────────────────────
+
+    pub async fn running_timer(&self) -> Result<Option<Timer>> {
+        let row = sqlx::query("SELECT label, tag, started_at FROM timer WHERE id=1")
+            .fetch_optional(&self.pool).await?;
+        row.map(|r| Ok(Timer {
+            label:      r.get("label"),
+            tag:        r.get("tag"),
+            started_at: parse_dt(r.get("started_at"))?,
+        })).transpose()
+    }
+
+    pub async fn start_timer(&self, t: &Timer) -> Result<()> {
+        sqlx::query("INSERT OR REPLACE INTO timer (id, label, tag, started_at) VALUES (1, ?, ?, ?)")
+            .bind(&t.label).bind(&t.tag).bind(t.started_at.to_rfc3339())
+            .execute(&self.pool).await?;
+        self.record("timer", || Some(serde_json::json!({ "id": "timer", "running": t }))).await;
+        Ok(())
+    }
+
+    /// Stops the timer. `entry` is saved only when this call was the one that
+    /// stopped it, so two instances stopping at once can't record it twice.
+    pub async fn stop_timer(&self, entry: Option<&Event>) -> Result<bool> {
+        let stopped = sqlx::query("DELETE FROM timer WHERE id=1").execute(&self.pool).await?.rows_affected() > 0;
+        if stopped {
+            self.record("timer", || Some(serde_json::json!({ "id": "timer", "running": null }))).await;
+        }
+        if let (true, Some(e)) = (stopped, entry) {
+            self.upsert_event(e).await?;
+        }
+        Ok(stopped)
+    }
+
+    // ── Change journal ────────────────────────────────────────────────────────
+
+    /// Appends a change to the journal, if one is attached. `data` is only
+    /// built then. A failed append is logged, never fatal to the write.
+    async fn record(&self, kind: &str, data: impl FnOnce() -> Option<serde_json::Value>) {
+        let Some(j)    = &self.journal else { return };
+        let Some(data) = data() else { return };
+        if let Err(e) = j.append(kind, &data).await {
+            tracing::warn!("Journal append to {} failed: {e}", j.path().display());
+        }
+    }
+
+    /// Journals a row as it now stands, after an UPDATE that only had its id.
+    async fn record_row<T: Serialize>(
+        &self, kind: &str, sql: &str, id: &str,
+        parse: fn(&sqlx::sqlite::SqliteRow) -> Result<T>,
+    ) -> Result<()> {
+        if self.journal.is_none() { return Ok(()); }
+        if let Some(row) = sqlx::query(sql).bind(id).fetch_optional(&self.pool).await? {
+            let item = parse(&row)?;
+            self.record(kind, || serde_json::to_value(&item).ok()).await;
+        }
+        Ok(())
+    }
+
+    /// Checklists are journalled whole: name and every item, in order.
+    async fn record_checklist(&self, list_id: &str) -> Result<()> {
+        if self.journal.is_none() { return Ok(()); }
+        let Some(row) = sqlx::query("SELECT name FROM checklists WHERE id=?")
+            .bind(list_id).fetch_optional(&self.pool).await? else { return Ok(()) };
+        let items: Vec<serde_json::Value> = self.checklist_items(list_id).await?.iter()
+            .map(|i| serde_json::json!({ "id": i.id, "text": i.text, "checked": i.checked }))
+            .collect();
+        self.record("checklist", || Some(serde_json::json!({
+            "id": list_id, "name": row.get::<String, _>("name"), "items": items,
+        }))).await;
+        Ok(())
+    }
+
+    /// An event's guests are journalled whole, answers not yet sent included.
+    async fn record_guests(&self, event_id: &str) -> Result<()> {
+        if self.journal.is_none() { return Ok(()); }
+        let rows = sqlx::query("SELECT * FROM event_attendees WHERE event_id=? ORDER BY organizer DESC, email")
+            .bind(event_id).fetch_all(&self.pool).await?;
+        let guests: Vec<serde_json::Value> = rows.iter().map(|r| serde_json::json!({
+            "email":     r.get::<String, _>("email"),
+            "name":      r.get::<Option<String>, _>("name"),
+            "response":  r.get::<Option<String>, _>("response_status"),
+            "is_self":   r.get::<i32, _>("is_self") != 0,
+            "organizer": r.get::<i32, _>("organizer") != 0,
+            "unsent":    r.get::<i32, _>("unsent") != 0,
+        })).collect();
+        self.record("guests", || Some(serde_json::json!({ "id": event_id, "guests": guests }))).await;
+        Ok(())
+    }
+
+    /// Habits are journalled whole: name, schedule and every day done; a
+    /// deleted one as `deleted`.
+    async fn record_habit(&self, id: &str) -> Result<()> {
+        if self.journal.is_none() { return Ok(()); }
+        let Some(row) = sqlx::query("SELECT name, schedule, created_at FROM habits WHERE id=?")
+            .bind(id).fetch_optional(&self.pool).await? else {
+            self.record("habit", || Some(serde_json::json!({ "id": id, "deleted": true }))).await;
+            return Ok(());
+        };
+        let done: Vec<String> = sqlx::query("SELECT date FROM habit_checks WHERE habit_id=? ORDER BY date")
+            .bind(id).fetch_all(&self.pool).await?
+            .iter().map(|r| r.get("date")).collect();
+        self.record("habit", || Some(serde_json::json!({
+            "id":         id,
+            "name":       row.get::<String, _>("name"),
+            "schedule":   row.get::<String, _>("schedule"),
+            "created_at": row.get::<String, _>("created_at"),
+            "done":       done,
+        }))).await;
+        Ok(())
+    }
+
+    // ── Quarantine ────────────────────────────────────────────────────────────
+
+    /// Records the items `source` rejected on its latest import and forgets
+    /// older rejects that now come through clean (or are gone). A reviewed
+    /// item stays resolved unless the remote copy changed.
+    pub async fn replace_quarantine(&self, source: &str, items: &[Quarantined]) -> Result<()> {
+        let mut tx = self.pool.begin().await?;
+        let held = sqlx::query("SELECT remote_id FROM quarantine WHERE source=?")
+            .bind(source).fetch_all(&mut *tx).await?;
+        for r in held {
+            let id: String = r.get("remote_id");
+            if !items.iter().any(|q| q.remote_id == id) {
+                sqlx::query("DELETE FROM quarantine WHERE source=? AND remote_id=?")
+                    .bind(source).bind(&id).execute(&mut *tx).await?;
+            }
+        }
+        for q in items {
+            sqlx::query(
+                "INSERT INTO quarantine
+                    (source,remote_id,title,description,start,end,all_day,reason,raw,received_at)
+                 VALUES (?,?,?,?,?,?,?,?,?,?)
+                 ON CONFLICT(source,remote_id) DO UPDATE SET
+                    resolved=CASE WHEN raw=excluded.raw THEN resolved ELSE 0 END,
+                    title=excluded.title, description=excluded.description,
+                    start=excluded.start, end=excluded.end, all_day=excluded.all_day,
+                    reason=excluded.reason, raw=excluded.raw, received_at=excluded.received_at"
+            )
+            .bind(source).bind(&q.remote_id).bind(&q.title).bind(&q.description)
+            .bind(q.start.map(|d| d.to_rfc3339())).bind(q.end.map(|d| d.to_rfc3339()))
+            .bind(q.all_day as i32).bind(&q.reason).bind(&q.raw)
+            .bind(q.received_at.to_rfc3339())
+            .execute(&mut *tx).await?;
+        }
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// Quarantined items still awaiting review, newest first.
+    pub async fn quarantined(&self) -> Result<Vec<Quarantined>> {
+        let rows = sqlx::query(
+            "SELECT * FROM quarantine WHERE resolved=0 ORDER BY received_at DESC, title"
+        ).fetch_all(&self.pool).await?;
+        Ok(parse_rows(&rows, row_to_quarantined))
+    }
+
+    /// Marks an item reviewed (fixed or discarded).
+    pub async fn resolve_quarantined(&self, source: &str, remote_id: &str) -> Result<()> {
+        sqlx::query("UPDATE quarantine SET resolved=1 WHERE source=? AND remote_id=?")
+            .bind(source).bind(remote_id).execute(&self.pool).await?;
+        Ok(())
+    }
+
+    // ── Sync log ──────────────────────────────────────────────────────────────
+
+    pub async fn log_sync(&self, e: &SyncLogEntry) -> Result<()> {
+        sqlx::query("INSERT INTO sync_log (at,direction,entity,action,title,result) VALUES (?,?,?,?,?,?)")
+            .bind(e.at.to_rfc3339()).bind(&e.direction).bind(&e.entity)
+            .bind(&e.action).bind(&e.title).bind(&e.result)
+            .execute(&self.pool).await?;
+        Ok(())
+    }
+
+    /// The newest `limit` entries, newest first.
+    pub async fn sync_log(&self, limit: i64) -> Result<Vec<SyncLogEntry>> {
+        let rows = sqlx::query("SELECT * FROM sync_log ORDER BY id DESC LIMIT ?")
+            .bind(limit).fetch_all(&self.pool).await?;
+        Ok(parse_rows(&rows, row_to_sync_log))
+    }
+
+    /// The stored event linked to remote `sync_id`, deleted or not.
+    pub async fn event_by_sync_id(&self, sync_id: &str) -> Result<Option<Event>> {
+        let row = sqlx::query("SELECT * FROM events WHERE sync_id=?")
+            .bind(sync_id).fetch_optional(&self.pool).await?;
+        row.as_ref().map(row_to_event).transpose()
+    }
+
+    /// The stored task linked to remote `sync_id`, deleted or not.
+    pub async fn task_by_sync_id(&self, sync_id: &str) -> Result<Option<Task>> {
+        let row = sqlx::query("SELECT * FROM tasks WHERE sync_id=?")
+            .bind(sync_id).fetch_optional(&self.pool).await?;
+        row.as_ref().map(row_to_task).transpose()
+    }
+
+    // ── Reminders ─────────────────────────────────────────────────────────────
+
+    /// Timed events starting after wall time `now` whose start, less their
+    /// own lead time, is within `now + lead`, that no frontend has sent a reminder
+    /// for yet and that aren't dismissed or snoozed on the banner. Keyed on
+    /// start too, so a moved event reminds again.
+    pub async fn due_reminders(&self, now: DateTime<Utc>, lead: chrono::Duration) -> Result<Vec<Event>> {
+        let rows = sqlx::query(
+            "SELECT * FROM events e
+             WHERE e.deleted=0 AND e.all_day=0 AND e.start > ?
+               AND julianday(e.start) - e.lead_minutes / 1440.0 <= julianday(?)
+               AND NOT EXISTS (SELECT 1 FROM reminders_sent r
+                               WHERE r.event_id=e.id AND r.start=e.start)
+               AND NOT EXISTS (SELECT 1 FROM reminder_state b
+                               WHERE b.event_id=e.id AND b.start=e.start
+                                 AND (b.dismissed=1 OR b.snoozed_until IS NOT NULL))
+             ORDER BY e.start"
+        )
+        .bind(now.to_rfc3339()).bind((now + lead).to_rfc3339())
+        .fetch_all(&self.pool).await?;
+        Ok(parse_rows(&rows, row_to_event))
+    }
+
+    /// Records the reminder as sent. False if another process got there
+    /// first, in which case the caller shouldn't send it again.
+    pub async fn claim_reminder(&self, e: &Event) -> Result<bool> {
+        let done = sqlx::query(
+            "INSERT OR IGNORE INTO reminders_sent (event_id, start, sent_at) VALUES (?,?,?)"
+        )
+        .bind(&e.id).bind(e.start.to_rfc3339()).bind(Utc::now().to_rfc3339())
+        .execute(&self.pool).await?;
+        Ok(done.rows_affected() == 1)
+    }
+
+    /// Same idea for the daily agenda, keyed on the date.
+    pub async fn claim_agenda(&self, date: NaiveDate) -> Result<bool> {
+        let done = sqlx::query(
+            "INSERT OR IGNORE INTO reminders_sent (event_id, start, sent_at) VALUES ('agenda',?,?)"
+        )
+        .bind(date.to_string()).bind(Utc::now().to_rfc3339())
+        .execute(&self.pool).await?;
+        Ok(done.rows_affected() == 1)
+    }
+
+    /// Timed events for the "starting soon" banner: starting (less their
+    /// lead time) within `within` of wall time `now`, or started less than
+    /// `grace` ago, unless skipped, dismissed or snoozed past `Utc::now()`.
+    pub async fn banner_events(
+        &self, now: DateTime<Utc>, within: chrono::Duration, grace: chrono::Duration,
+    ) -> Result<Vec<Event>> {
+        let rows = sqlx::query(
+            "SELECT * FROM events e
+             WHERE e.deleted=0 AND e.all_day=0 AND e.time_entry=0 AND e.start > ?
+               AND COALESCE(e.attendance, '') NOT IN ('skipped', 'cancelled')
+               AND julianday(e.start) - e.lead_minutes / 1440.0 <= julianday(?)
+               AND NOT EXISTS (SELECT 1 FROM reminder_state r
+                               WHERE r.event_id=e.id AND r.start=e.start
+                                 AND (r.dismissed=1 OR r.snoozed_until > ?))
+             ORDER BY e.start"
+        )
+        .bind((now - grace).to_rfc3339()).bind((now + within).to_rfc3339()).bind(Utc::now().to_rfc3339())
+        .fetch_all(&self.pool).await?;
+        Ok(parse_rows(&rows, row_to_event))
+    }
+
+    /// Puts the banner for `e` away until `until`, when it's shown and sent
+    /// again (see `snoozes_due`).
+    pub async fn snooze_reminder(&self, e: &Event, until: DateTime<Utc>) -> Result<()> {
+        sqlx::query(
+            "INSERT OR REPLACE INTO reminder_state (event_id, start, snoozed_until, dismissed) VALUES (?,?,?,0)"
+        )
+        .bind(&e.id).bind(e.start.to_rfc3339()).bind(until.to_rfc3339())
+        .execute(&self.pool).await?;
+        Ok(())
+    }
+
+    /// No more banner for `e`, across restarts, unless it's moved.
+    pub async fn dismiss_reminder(&self, e: &Event) -> Result<()> {
+        sqlx::query(
+            "INSERT OR REPLACE INTO reminder_state (event_id, start, snoozed_until, dismissed) VALUES (?,?,NULL,1)"
+        )
+        .bind(&e.id).bind(e.start.to_rfc3339())
+        .execute(&self.pool).await?;
+        Ok(())
+    }
+
+    /// Events whose snooze has run out (by `Utc::now()`, as `snooze_reminder`
+    /// sets it) and that aren't over at wall time `now`.
+    pub async fn snoozes_due(&self, now: DateTime<Utc>) -> Result<Vec<Event>> {
+        let rows = sqlx::query(
+            "SELECT e.* FROM events e
+             JOIN reminder_state r ON r.event_id=e.id AND r.start=e.start
+             WHERE e.deleted=0 AND e.end > ? AND r.dismissed=0
+               AND r.snoozed_until IS NOT NULL AND r.snoozed_until <= ?
+             ORDER BY e.start"
+        )
+        .bind(now.to_rfc3339()).bind(Utc::now().to_rfc3339())
+        .fetch_all(&self.pool).await?;
+        Ok(parse_rows(&rows, row_to_event))
+    }
+
+    /// Like `claim_reminder`, for a snooze that has run out: false if
+    /// another process already sent it again.
+    pub async fn claim_snooze(&self, e: &Event) -> Result<bool> {
+        let done = sqlx::query(
+            "UPDATE reminder_state SET snoozed_until=NULL
+             WHERE event_id=? AND start=? AND snoozed_until IS NOT NULL"
+        )
+        .bind(&e.id).bind(e.start.to_rfc3339())
+        .execute(&self.pool).await?;
+        Ok(done.rows_affected() == 1)
+    }
+
+    // ── Maintenance ───────────────────────────────────────────────────────────
+
+    /// Hard-deletes events and tasks soft-deleted more than `days` ago whose
+    /// deletion has already been pushed (dirty=0), with their guests and
+    /// reminder rows, and sync log entries as old. Returns (events, tasks).
+    pub async fn purge_deleted(&self, days: i64) -> Result<(u64, u64)> {
+        let cutoff = (Utc::now() - chrono::Duration::days(days.max(0))).to_rfc3339();
+        let purged = "SELECT id FROM events WHERE deleted=1 AND dirty=0 AND updated_at < ?";
+
+        let mut tx = self.pool.begin().await?;
+        for table in ["event_attendees", "reminders_sent", "reminder_state"] {
+            sqlx::query(&format!("DELETE FROM {table} WHERE event_id IN ({purged})"))
+                .bind(&cutoff).execute(&mut *tx).await?;
+        }
+        let events = sqlx::query("DELETE FROM events WHERE deleted=1 AND dirty=0 AND updated_at < ?")
+            .bind(&cutoff).execute(&mut *tx).await?.rows_affected();
+        let tasks  = sqlx::query("DELETE FROM tasks WHERE deleted=1 AND dirty=0 AND updated_at < ?")
+            .bind(&cutoff).execute(&mut *tx).await?.rows_affected();
+        sqlx::query("DELETE FROM reminders_sent WHERE start < ?")
+            .bind(&cutoff).execute(&mut *tx).await?;
+        sqlx::query("DELETE FROM reminder_state WHERE start < ?")
+            .bind(&cutoff).execute(&mut *tx).await?;
+        sqlx::query("DELETE FROM sync_log WHERE at < ?")
+            .bind(&cutoff).execute(&mut *tx).await?;
+        tx.commit().await?;
+        Ok((events, tasks))
+    }
+
+    /// Merges the duplicates `dedupe::certain` finds, each with the picks
+    /// `dedupe::suggest` makes; returns the merges written.
+    pub async fn dedupe(&self) -> Result<Vec<Merge>> {
+        let events = self.calendar_events().await?;
+        let now    = Utc::now();
+        let mut merges = Vec::new();
+        for group in dedupe::certain(&events) {
+            let group: Vec<Event> = group.iter().map(|&i| events[i].clone()).collect();
+            let merged = dedupe::merge(&group, &dedupe::suggest(&group), now);
+            for ev in &merged.dropped {
+                self.upsert_event(ev).await?;
+            }
+            self.upsert_event(&merged.kept).await?;
+            merges.push(merged);
+        }
+        Ok(merges)
+    }
+
+    /// Bytes in use by the database file (pages × page size).
+    pub async fn size(&self) -> Result<u64> {
+        let pages: i64 = sqlx::query("PRAGMA page_count").fetch_one(&self.pool).await?.get(0);
+        let size:  i64 = sqlx::query("PRAGMA page_size").fetch_one(&self.pool).await?.get(0);
+        Ok((pages * size) as u64)
+    }
+
+    /// Rebuilds the file to drop free pages and refreshes planner statistics.
+    pub async fn vacuum(&self) -> Result<()> {
+        sqlx::query("VACUUM").execute(&self.pool).await?;
+        sqlx::query("ANALYZE").execute(&self.pool).await?;
+        Ok(())
     }
 
     // ── OAuth tokens ──────────────────────────────────────────────────────────
@@ -275,6 +1485,19 @@ impl Database {
         Ok(())
     }
 
+    pub async fn delete_token(&self, provider: &str) -> Result<()> {
+        sqlx::query("DELETE FROM oauth_tokens WHERE provider=?")
+            .bind(provider).execute(&self.pool).await?;
+        Ok(())
+    }
+
+    /// Detaches every local row from its remote copy (used after sign-out).
+    pub async fn clear_sync_ids(&self) -> Result<()> {
+        sqlx::query("UPDATE events SET sync_id=NULL, etag=NULL").execute(&self.pool).await?;
+        sqlx::query("UPDATE tasks SET sync_id=NULL").execute(&self.pool).await?;
+        Ok(())
+    }
+
     pub async fn get_token(
         &self, provider: &str,
     ) -> Result<Option<(String, Option<String>, Option<DateTime<Utc>>)>> {
@@ -293,25 +1516,70 @@ impl Database {
             (access, refresh, exp)
         }))
     }
+
+    /// The OAuth client the stored tokens were issued to. Google only
+    /// refreshes a token for that client, so it outranks config.toml.
+    pub async fn token_client(&self, provider: &str) -> Result<Option<(String, Option<String>)>> {
+        let row = sqlx::query(
+            "SELECT client_id, client_secret FROM oauth_tokens WHERE provider=? AND client_id IS NOT NULL"
+        )
+        .bind(provider).fetch_optional(&self.pool).await?;
+        Ok(row.map(|r| (r.get("client_id"), r.get("client_secret"))))
+    }
+
+    /// The scopes the stored tokens were granted; `None` for tokens from
+    /// before they were recorded.
+    pub async fn token_scope(&self, provider: &str) -> Result<Option<String>> {
+        let row = sqlx::query("SELECT scope FROM oauth_tokens WHERE provider=?")
+            .bind(provider).fetch_optional(&self.pool).await?;
+        Ok(row.and_then(|r| r.get("scope")))
+    }
+
+    pub async fn save_token_scope(&self, provider: &str, scope: &str) -> Result<()> {
+        sqlx::query("UPDATE oauth_tokens SET scope=? WHERE provider=?")
+            .bind(scope).bind(provider)
+            .execute(&self.pool).await?;
+        Ok(())
+    }
+
+    pub async fn save_token_client(
+        &self, provider: &str, client_id: &str, client_secret: Option<&str>,
+    ) -> Result<()> {
+        sqlx::query("UPDATE oauth_tokens SET client_id=?, client_secret=? WHERE provider=?")
+            .bind(client_id).bind(client_secret).bind(provider)
+            .execute(&self.pool).await?;
+        Ok(())
+    }
 }
 
 // ─── Row helpers ─────────────────────────────────────────────────────────────
 
 fn row_to_event(row: &sqlx::sqlite::SqliteRow) -> Result<Event> {
     Ok(Event {
-        id:          row.get("id"),
-        title:       row.get("title"),
-        description: row.get("description"),
-        start:       parse_dt(row.get("start"))?,
-        end:         parse_dt(row.get("end"))?,
-        all_day:     row.get::<i32, _>("all_day") != 0,
-        calendar_id: row.get("calendar_id"),
-        sync_id:     row.get("sync_id"),
-        etag:        row.get("etag"),
-        dirty:       row.get::<i32, _>("dirty") != 0,
-        deleted:     row.get::<i32, _>("deleted") != 0,
-        created_at:  parse_dt(row.get("created_at"))?,
-        updated_at:  parse_dt(row.get("updated_at"))?,
+        id:           row.get("id"),
+        title:        row.get("title"),
+        description:  row.get("description"),
+        start:        parse_dt(row.get("start"))?,
+        end:          parse_dt(row.get("end"))?,
+        all_day:      row.get::<i32, _>("all_day") != 0,
+        calendar_id:  row.get("calendar_id"),
+        sync_id:      row.get("sync_id"),
+        etag:         row.get("etag"),
+        dirty:        row.get::<i32, _>("dirty") != 0,
+        deleted:      row.get::<i32, _>("deleted") != 0,
+        pto:          row.get::<i32, _>("pto") != 0,
+        attendance:   row.get::<Option<String>, _>("attendance").as_deref().and_then(Attendance::parse),
+        timezone:     row.get("timezone"),
+        remote_title: row.get("remote_title"),
+        tag:          row.get("tag"),
+        time_entry:   row.get::<i32, _>("time_entry") != 0,
+        organizer:    row.get("organizer"),
+        creator:      row.get("creator"),
+        lead_minutes: row.get("lead_minutes"),
+        meet_url:     row.get("meet_url"),
+        color:        row.get("color"),
+        created_at:   parse_dt(row.get("created_at"))?,
+        updated_at:   parse_dt(row.get("updated_at"))?,
     })
 }
 
@@ -328,11 +1596,80 @@ fn row_to_task(row: &sqlx::sqlite::SqliteRow) -> Result<Task> {
         sync_id:      row.get("sync_id"),
         dirty:        row.get::<i32, _>("dirty") != 0,
         deleted:      row.get::<i32, _>("deleted") != 0,
+        tags:         split_tags(row.get("tags")),
+        position:     row.get("position"),
+        repeat:       row.get("repeat"),
+        estimate_minutes: row.get("estimate_minutes"),
         created_at:   parse_dt(row.get("created_at"))?,
         updated_at:   parse_dt(row.get("updated_at"))?,
     })
 }
 
+fn split_tags(s: Option<String>) -> Vec<String> {
+    s.map(|s| s.split_whitespace().map(str::to_owned).collect()).unwrap_or_default()
+}
+
+fn row_to_feed_event(row: &sqlx::sqlite::SqliteRow) -> Result<FeedEvent> {
+    Ok(FeedEvent {
+        feed:    row.get("feed"),
+        uid:     row.get("uid"),
+        title:   row.get("title"),
+        start:   parse_dt(row.get("start"))?,
+        end:     parse_dt(row.get("end"))?,
+        all_day: row.get::<i32, _>("all_day") != 0,
+    })
+}
+
+fn row_to_working_location(row: &sqlx::sqlite::SqliteRow) -> Result<WorkingLocation> {
+    Ok(WorkingLocation {
+        sync_id:     row.get("sync_id"),
+        calendar_id: row.get("calendar_id"),
+        start:       parse_dt(row.get("start"))?,
+        end:         parse_dt(row.get("end"))?,
+        all_day:     row.get::<i32, _>("all_day") != 0,
+        kind:        row.get("kind"),
+        label:       row.get("label"),
+    })
+}
+
+fn row_to_quarantined(row: &sqlx::sqlite::SqliteRow) -> Result<Quarantined> {
+    let start: Option<String> = row.get("start");
+    let end:   Option<String> = row.get("end");
+    Ok(Quarantined {
+        source:      row.get("source"),
+        remote_id:   row.get("remote_id"),
+        title:       row.get("title"),
+        description: row.get("description"),
+        start:       start.and_then(|s| parse_dt(s).ok()),
+        end:         end.and_then(|s| parse_dt(s).ok()),
+        all_day:     row.get::<i32, _>("all_day") != 0,
+        reason:      row.get("reason"),
+        raw:         row.get("raw"),
+        received_at: parse_dt(row.get("received_at"))?,
+    })
+}
+
+fn row_to_sync_log(row: &sqlx::sqlite::SqliteRow) -> Result<SyncLogEntry> {
+    Ok(SyncLogEntry {
+        at:        parse_dt(row.get("at"))?,
+        direction: row.get("direction"),
+        entity:    row.get("entity"),
+        action:    row.get("action"),
+        title:     row.get("title"),
+        result:    row.get("result"),
+    })
+}
+
+/// Converts rows, skipping (and logging) any that fail to parse so one corrupt
+/// row doesn't hide the rest of the result.
+fn parse_rows<T>(
+    rows: &[sqlx::sqlite::SqliteRow], f: fn(&sqlx::sqlite::SqliteRow) -> Result<T>,
+) -> Vec<T> {
+    rows.iter()
+        .filter_map(|r| f(r).map_err(|e| tracing::warn!("Skipping unreadable row: {e}")).ok())
+        .collect()
+}
+
 fn parse_dt(s: String) -> Result<DateTime<Utc>> {
     Ok(DateTime::parse_from_rfc3339(&s)?.with_timezone(&Utc))
 }
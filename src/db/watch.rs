@@ -0,0 +1,58 @@
+//! Notices writes to lifemanager.db made by anything else — `lm` commands,
+//! the sync daemon, a second TUI — so open frontends can re-read.
+//!
+//! `PRAGMA data_version` changes when any connection but the one asking
+//! commits, so the watcher keeps a connection of its own outside the pool.
+//! That makes this process's pool "another connection" too: its own edits
+//! and in-process worker syncs count as changes. A frontend that re-reads
+//! after its own write calls `settle` first; worker syncs are left to show
+//! up as changes, since that's how what they pull reaches the screen.
+
+use anyhow::Result;
+use sqlx::{Connection, Row, SqliteConnection};
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+/// How often the watcher actually asks SQLite.
+const POLL: Duration = Duration::from_secs(1);
+
+pub struct Watcher {
+    conn:    SqliteConnection,
+    version: i64,
+    polled:  Instant,
+}
+
+impl Watcher {
+    pub async fn open(db_path: &Path) -> Result<Self> {
+        let url      = format!("sqlite://{}?mode=ro", db_path.display());
+        let mut conn = SqliteConnection::connect(&url).await?;
+        let version  = data_version(&mut conn).await?;
+        Ok(Self { conn, version, polled: Instant::now() })
+    }
+
+    /// True once per batch of commits made since the last call. Cheap to
+    /// call every frame: SQLite is asked at most once per `POLL`.
+    pub async fn changed(&mut self) -> bool {
+        if self.polled.elapsed() < POLL { return false; }
+        self.polled = Instant::now();
+        match data_version(&mut self.conn).await {
+            Ok(v) if v != self.version => { self.version = v; true }
+            Ok(_)  => false,
+            Err(e) => { tracing::warn!("DB watch failed: {e}"); false }
+        }
+    }
+
+    /// Takes every commit so far as seen, for a caller about to re-read
+    /// anyway. Called before the re-read, so a commit landing during it
+    /// still counts.
+    pub async fn settle(&mut self) {
+        match data_version(&mut self.conn).await {
+            Ok(v)  => self.version = v,
+            Err(e) => tracing::warn!("DB watch failed: {e}"),
+        }
+    }
+}
+
+async fn data_version(conn: &mut SqliteConnection) -> Result<i64> {
+    Ok(sqlx::query("PRAGMA data_version").fetch_one(conn).await?.get(0))
+}
@@ -0,0 +1,272 @@
+//! Likely duplicate events — the same meeting imported twice, or copied
+//! into a second calendar — and merging a group of them into one.
+//!
+//! Two events are taken for the same one when their titles match once case
+//! and punctuation are set aside (or one contains the other, or they share
+//! at least half their words) and their times overlap. Timed events only
+//! pair with timed ones, all-day with all-day.
+//!
+//! `certain` is the narrower set merged after every sync without asking:
+//! rows for one Google event, and exact copies where at most one is on
+//! Google.
+
+use chrono::{DateTime, Utc};
+use std::collections::{HashMap, HashSet};
+
+use crate::model::Event;
+
+/// Groups of two or more indices into `events` that look like one event,
+/// each in start order, the groups in order of their first start. Deleted
+/// events and the local layers (timer entries, focus sessions) are left
+/// out: those sit on top of meetings on purpose.
+pub fn clusters(events: &[Event]) -> Vec<Vec<usize>> {
+    let mut order: Vec<usize> = (0..events.len())
+        .filter(|&i| !events[i].deleted && !events[i].is_local_layer())
+        .collect();
+    order.sort_by_key(|&i| events[i].start);
+    let words: Vec<Vec<String>> = events.iter().map(|e| words(&e.title)).collect();
+
+    let mut parent: Vec<usize> = (0..events.len()).collect();
+    for (n, &a) in order.iter().enumerate() {
+        // In start order, nothing after the first to start past a's end
+        // can overlap it
+        for &b in order[n + 1..].iter().take_while(|&&b| events[b].start <= events[a].end) {
+            if overlap(&events[a], &events[b]) && similar(&words[a], &words[b]) {
+                let (ra, rb) = (root(&mut parent, a), root(&mut parent, b));
+                parent[rb] = ra;
+            }
+        }
+    }
+
+    let mut groups: Vec<Vec<usize>>   = Vec::new();
+    let mut at: HashMap<usize, usize> = HashMap::new();
+    for &i in &order {
+        let r = root(&mut parent, i);
+        match at.get(&r) {
+            Some(&g) => groups[g].push(i),
+            None     => {
+                at.insert(r, groups.len());
+                groups.push(vec![i]);
+            }
+        }
+    }
+    groups.retain(|g| g.len() > 1);
+    groups
+}
+
+/// Groups that can be merged with `suggest`'s picks without asking: rows
+/// linked to the same Google event (a push and a pull crossing), and copies
+/// with the same calendar, times and title as `words` reads it. A group
+/// holding two different Google events is cut back to its rows per event,
+/// so merging never deletes anything at Google.
+pub fn certain(events: &[Event]) -> Vec<Vec<usize>> {
+    let live = |i: &usize| !events[*i].deleted && !events[*i].is_local_layer();
+    let mut parent: Vec<usize> = (0..events.len()).collect();
+    let mut by_sync: HashMap<&str, usize>   = HashMap::new();
+    let mut by_copy: HashMap<String, usize> = HashMap::new();
+    for i in (0..events.len()).filter(live) {
+        let e = &events[i];
+        let copy = format!(
+            "{}|{}|{}|{}|{}",
+            e.calendar_id.as_deref().unwrap_or("primary"), e.start.to_rfc3339(), e.end.to_rfc3339(),
+            e.all_day, words(&e.title).join(" "),
+        );
+        let same_id   = e.sync_id.as_deref().map(|s| *by_sync.entry(s).or_insert(i));
+        let same_copy = *by_copy.entry(copy).or_insert(i);
+        for j in same_id.into_iter().chain([same_copy]) {
+            let (ri, rj) = (root(&mut parent, i), root(&mut parent, j));
+            parent[ri] = rj;
+        }
+    }
+
+    let mut groups: Vec<Vec<usize>>   = Vec::new();
+    let mut at: HashMap<usize, usize> = HashMap::new();
+    for i in (0..events.len()).filter(live) {
+        let r = root(&mut parent, i);
+        match at.get(&r) {
+            Some(&g) => groups[g].push(i),
+            None     => {
+                at.insert(r, groups.len());
+                groups.push(vec![i]);
+            }
+        }
+    }
+    let mut safe = Vec::new();
+    for group in groups {
+        let ids: HashSet<&str> = group.iter().filter_map(|&i| events[i].sync_id.as_deref()).collect();
+        if ids.len() <= 1 {
+            safe.push(group);
+            continue;
+        }
+        for id in ids {
+            safe.push(group.iter().copied().filter(|&i| events[i].sync_id.as_deref() == Some(id)).collect());
+        }
+    }
+    safe.retain(|g: &Vec<usize>| g.len() > 1);
+    for g in &mut safe { g.sort_by_key(|&i| events[i].start); }
+    safe.sort_by_key(|g| events[g[0]].start);
+    safe
+}
+
+fn root(parent: &mut [usize], mut i: usize) -> usize {
+    while parent[i] != i {
+        parent[i] = parent[parent[i]];
+        i = parent[i];
+    }
+    i
+}
+
+fn overlap(a: &Event, b: &Event) -> bool {
+    a.all_day == b.all_day && (a.start == b.start || (a.start < b.end && b.start < a.end))
+}
+
+/// "Q3 Planning — Offsite!" is `["q3", "planning", "offsite"]`.
+fn words(title: &str) -> Vec<String> {
+    title.to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .map(str::to_owned)
+        .collect()
+}
+
+fn similar(a: &[String], b: &[String]) -> bool {
+    if a.is_empty() || b.is_empty() { return false; }
+    let (ja, jb) = (format!(" {} ", a.join(" ")), format!(" {} ", b.join(" ")));
+    if ja.contains(&jb) || jb.contains(&ja) { return true; }
+    let sa: HashSet<&String> = a.iter().collect();
+    let sb: HashSet<&String> = b.iter().collect();
+    let shared = sa.intersection(&sb).count();
+    shared * 2 >= sa.union(&sb).count()
+}
+
+// ─── Merging ─────────────────────────────────────────────────────────────────
+
+/// What a merge takes from one member of the group or another. `Keep` is
+/// the event that survives: its id, calendar and Google identity.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Field {
+    Keep,
+    Title,
+    When,
+    Description,
+    Tag,
+}
+
+impl Field {
+    pub const ALL: [Field; 5] = [Self::Keep, Self::Title, Self::When, Self::Description, Self::Tag];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Keep        => "Keep",
+            Self::Title       => "Title",
+            Self::When        => "When",
+            Self::Description => "Notes",
+            Self::Tag         => "Tag",
+        }
+    }
+
+    /// The field of `ev` on one line.
+    pub fn show(self, ev: &Event) -> String {
+        match self {
+            Self::Keep => format!(
+                "{}{}",
+                ev.calendar_id.as_deref().unwrap_or("primary"),
+                if ev.sync_id.is_some() { " · synced" } else { " · local" },
+            ),
+            Self::Title => ev.title.clone(),
+            Self::When if ev.all_day => {
+                let last = ev.end - chrono::Duration::days(1);
+                if last.date_naive() > ev.start.date_naive() {
+                    format!("{} – {}", ev.start.format("%a %b %-d"), last.format("%a %b %-d"))
+                } else {
+                    format!("{} all day", ev.start.format("%a %b %-d"))
+                }
+            }
+            Self::When => format!("{} {}–{}", ev.start.format("%a %b %-d"), ev.start.format("%H:%M"), ev.end.format("%H:%M")),
+            Self::Description => ev.description.as_deref()
+                .and_then(|d| d.lines().find(|l| !l.trim().is_empty()))
+                .map_or_else(|| "—".into(), |l| l.trim().to_owned()),
+            Self::Tag => ev.tag.as_ref().map_or_else(|| "—".into(), |t| format!("#{t}")),
+        }
+    }
+}
+
+/// Which member wins each of `Field::ALL`, by index into the group.
+pub type Picks = [usize; Field::ALL.len()];
+
+/// A starting point: keep the first event Google knows (else the first),
+/// and take notes and a tag from another member where it has none.
+pub fn suggest(group: &[Event]) -> Picks {
+    let keep   = group.iter().position(|e| e.sync_id.is_some()).unwrap_or(0);
+    let or_any = |has: fn(&Event) -> bool| {
+        if group.get(keep).is_some_and(has) { keep } else { group.iter().position(has).unwrap_or(keep) }
+    };
+    [
+        keep,
+        keep,
+        keep,
+        or_any(|e| e.description.as_deref().is_some_and(|d| !d.trim().is_empty())),
+        or_any(|e| e.tag.is_some()),
+    ]
+}
+
+/// A merged group, ready to be written back.
+pub struct Merge {
+    pub kept:    Event,
+    pub dropped: Vec<Event>,
+}
+
+/// Merges `group` as `picks` says. The kept event takes the picked fields
+/// (and any PTO mark, check-in or lead time only another had), and is
+/// dirty if that changed it.
+///
+/// The rest are deleted. One with its own Google event is marked dirty so
+/// the copy there goes too; one sharing the kept event's sync id (a second
+/// row for the same Google event) or with none is dropped here only, its
+/// sync id cleared so deleting it can never reach the event kept.
+pub fn merge(group: &[Event], picks: &Picks, now: DateTime<Utc>) -> Merge {
+    let pick = |f: Field| {
+        let i = picks[Field::ALL.iter().position(|&g| g == f).unwrap_or(0)];
+        &group[i.min(group.len() - 1)]
+    };
+    let keep     = picks[0].min(group.len() - 1);
+    let mut kept = group[keep].clone();
+    let before   = (kept.title.clone(), kept.start, kept.end, kept.all_day, kept.description.clone(), kept.tag.clone());
+
+    let title = pick(Field::Title);
+    kept.title        = title.title.clone();
+    kept.remote_title = title.remote_title.clone();
+    let when = pick(Field::When);
+    kept.start    = when.start;
+    kept.end      = when.end;
+    kept.all_day  = when.all_day;
+    kept.timezone = when.timezone.clone().or(kept.timezone);
+    kept.description = pick(Field::Description).description.clone();
+    kept.tag         = pick(Field::Tag).tag.clone();
+
+    let others = group.iter().enumerate().filter(|(i, _)| *i != keep).map(|(_, e)| e);
+    for other in others.clone() {
+        if other.pto { kept.pto = true; }
+        kept.attendance = kept.attendance.or(other.attendance);
+        if kept.lead_minutes == 0 { kept.lead_minutes = other.lead_minutes; }
+    }
+    if before != (kept.title.clone(), kept.start, kept.end, kept.all_day, kept.description.clone(), kept.tag.clone()) {
+        kept.dirty      = true;
+        kept.updated_at = now;
+    }
+
+    let dropped = others.map(|other| {
+        let mut d = other.clone();
+        d.deleted    = true;
+        d.updated_at = now;
+        if d.sync_id.is_some() && d.sync_id != kept.sync_id {
+            d.dirty = true;
+        } else {
+            d.sync_id = None;
+            d.etag    = None;
+            d.dirty   = false;
+        }
+        d
+    }).collect();
+    Merge { kept, dropped }
+}
@@ -0,0 +1,176 @@
+//! A day at a glance: its events, the tasks due and overdue, and the public
+//! holidays coming up. `lm today` prints it, `lm digest` also as HTML or a
+//! mail for cron; with `[ui] startup_digest` the TUI opens on it, and
+//! `Space G` shows it again. All of them lay it out from `sections`.
+
+use chrono::{Datelike, NaiveDate};
+
+use crate::attendance::Attendance;
+use crate::calendar::{add_days, day_bounds};
+use crate::holidays::{Holiday, HolidayEngine};
+use crate::model::{Event, Task};
+use crate::tasks;
+
+/// How far ahead holidays are listed, the day itself included.
+pub const HOLIDAY_DAYS: i64 = 14;
+
+#[derive(Debug, Clone)]
+pub struct Digest {
+    pub date:     NaiveDate,
+    /// The day's events, all-day ones first, then by start.
+    pub events:   Vec<Event>,
+    /// Open tasks due on the day, highest priority first.
+    pub due:      Vec<Task>,
+    /// Open tasks due before `today`, oldest first.
+    pub overdue:  Vec<Task>,
+    pub holidays: Vec<Holiday>,
+}
+
+/// A heading and its rows, as both the overlay and `lm today` lay them out.
+pub struct Section {
+    pub heading: &'static str,
+    pub rows:    Vec<Row>,
+}
+
+/// "09:00–09:30" and "Standup", "Oct 12" and "Pay rent".
+pub struct Row {
+    pub when: String,
+    pub what: String,
+}
+
+/// Counts as the day's: not logged time, and not skipped or cancelled.
+fn counts(e: &Event) -> bool {
+    !e.time_entry && !e.deleted
+        && !matches!(e.attendance, Some(Attendance::Skipped | Attendance::Cancelled))
+}
+
+impl Digest {
+    /// `events` may run past the day either side; anything not on `date` is
+    /// passed over. Overdue is as of `today`, which `date` needn't be.
+    pub fn build(date: NaiveDate, today: NaiveDate, events: &[Event], tasks: &[Task], holidays: &HolidayEngine) -> Self {
+        let (from, to) = day_bounds(date);
+        let mut events: Vec<Event> = events.iter()
+            .filter(|e| counts(e) && e.start < to && e.end > from)
+            .cloned()
+            .collect();
+        events.sort_by_key(|e| (!e.all_day, e.start));
+
+        let mut due: Vec<Task> = tasks.iter()
+            .filter(|t| !t.completed && !t.deleted && t.due.is_some_and(|d| d.date_naive() == date))
+            .cloned()
+            .collect();
+        tasks::sort_tasks(&mut due, tasks::TaskSort::Priority);
+        let mut overdue: Vec<Task> = tasks::overdue(tasks, today).into_iter().cloned().collect();
+        tasks::sort_tasks(&mut overdue, tasks::TaskSort::Due);
+
+        let last     = add_days(date, HOLIDAY_DAYS - 1).unwrap_or(date);
+        let holidays = (date.year()..=last.year())
+            .flat_map(|y| holidays.holidays_in_year(y))
+            .filter(|h| h.date >= date && h.date <= last)
+            .collect();
+
+        Self { date, events, due, overdue, holidays }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.events.is_empty() && self.due.is_empty() && self.overdue.is_empty() && self.holidays.is_empty()
+    }
+
+    /// The non-empty sections, in the order a morning runs: what's late,
+    /// what's due, the day's events, then the holidays ahead.
+    pub fn sections(&self) -> Vec<Section> {
+        let (from, to) = day_bounds(self.date);
+        let overdue = self.overdue.iter().map(|t| {
+            let due  = t.due.map(|d| d.date_naive()).unwrap_or(self.date);
+            let late = (self.date - due).num_days();
+            Row { when: due.format("%b %-d").to_string(), what: format!("{}  ({late}d late)", task_label(t)) }
+        }).collect();
+        let due = self.due.iter().map(|t| Row {
+            when: match t.priority {
+                0 => String::new(),
+                p => tasks::priority_label(p).to_owned(),
+            },
+            what: task_label(t),
+        }).collect();
+        let events = self.events.iter().map(|e| Row {
+            when: match (e.all_day, e.start < from, e.end > to) {
+                (true, _, _) | (_, true, true) => "all day".into(),
+                (_, true, false)               => format!("…–{}", e.end.format("%H:%M")),
+                (_, false, true)               => format!("{}–…", e.start.format("%H:%M")),
+                (_, false, false)              => format!("{}–{}", e.start.format("%H:%M"), e.end.format("%H:%M")),
+            },
+            what: e.title.clone(),
+        }).collect();
+        let holidays = self.holidays.iter().map(|h| Row {
+            when: match (h.date - self.date).num_days() {
+                0 => "today".into(),
+                1 => "tomorrow".into(),
+                n => format!("{} · in {n}d", h.date.format("%a %b %-d")),
+            },
+            what: format!("{} {} ({})", h.emoji, h.name, h.country),
+        }).collect();
+
+        [("Overdue", overdue), ("Due", due), ("Events", events), ("Holidays ahead", holidays)]
+            .into_iter()
+            .filter(|(_, rows): &(_, Vec<Row>)| !rows.is_empty())
+            .map(|(heading, rows)| Section { heading, rows })
+            .collect()
+    }
+
+    /// "Friday, October 16, 2026".
+    pub fn title(&self) -> String {
+        self.date.format("%A, %B %-d, %Y").to_string()
+    }
+
+    /// Plain text: the date, then each section with its rows indented.
+    pub fn to_text(&self) -> String {
+        let mut out = vec![self.title()];
+        let sections = self.sections();
+        if sections.is_empty() { out.push("  Nothing on.".into()); }
+        for s in sections {
+            out.push(String::new());
+            out.push(s.heading.to_owned());
+            let width = s.rows.iter().map(|r| r.when.chars().count()).max().unwrap_or(0);
+            out.extend(s.rows.iter().map(|r| format!("  {:<width$}  {}", r.when, r.what).trim_end().to_owned()));
+        }
+        out.join("\n")
+    }
+
+    /// A standalone page, styled inline enough to survive a mail client.
+    pub fn to_html(&self) -> String {
+        let mut body = String::new();
+        let sections = self.sections();
+        if sections.is_empty() { body.push_str("<p>Nothing on.</p>\n"); }
+        for s in sections {
+            body.push_str(&format!("<h2>{}</h2>\n<table>\n", esc(s.heading)));
+            for r in &s.rows {
+                body.push_str(&format!("<tr><th>{}</th><td>{}</td></tr>\n", esc(&r.when), esc(&r.what)));
+            }
+            body.push_str("</table>\n");
+        }
+        format!(
+            "<!DOCTYPE html>
+<html><head><meta charset=\"utf-8\"><title>{title}</title>
+<style>
+body {{ font-family: system-ui, sans-serif; max-width: 36rem; margin: 2rem auto; color: #222; }}
+h2 {{ font-size: 1rem; margin: 1.5rem 0 0.5rem; }}
+th {{ text-align: left; padding-right: 1.5rem; color: #666; font-weight: normal; white-space: nowrap; }}
+</style></head>
+<body>
+<h1>{title}</h1>
+{body}</body></html>
+",
+            title = esc(&self.title()),
+        )
+    }
+}
+
+fn esc(s: &str) -> String {
+    s.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+/// The title, with its tags.
+fn task_label(t: &Task) -> String {
+    let tags: String = t.tags.iter().map(|tag| format!(" #{tag}")).collect();
+    format!("{}{tags}", t.title)
+}
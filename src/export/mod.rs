@@ -0,0 +1,351 @@
+//! The `X` export: the selected day, week or month of events, or the task
+//! list as filtered, written as iCalendar, Markdown, JSON, an Emacs org
+//! agenda file or todo.txt (`lm export` writes everything the same ways).
+//!
+//! Times are wall time (stored as UTC like everywhere else), so iCalendar
+//! gets floating times: 09:00 stays 09:00 in whatever zone imports it.
+
+use anyhow::Result;
+use chrono::{DateTime, Datelike, Duration, Months, NaiveDate, NaiveTime, Utc};
+use std::path::PathBuf;
+
+use crate::calendar::{add_days, week_start};
+use crate::model::{Event, Task};
+
+/// What gets exported; `s` cycles through them.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum Scope {
+    #[default]
+    Day,
+    Week,
+    Month,
+    Tasks,
+}
+
+impl Scope {
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Day   => "day",
+            Self::Week  => "week",
+            Self::Month => "month",
+            Self::Tasks => "tasks",
+        }
+    }
+
+    pub fn next(self) -> Self {
+        match self {
+            Self::Day   => Self::Week,
+            Self::Week  => Self::Month,
+            Self::Month => Self::Tasks,
+            Self::Tasks => Self::Day,
+        }
+    }
+
+    /// First and last day covered around `date`; none for tasks.
+    pub fn days(self, date: NaiveDate) -> Option<(NaiveDate, NaiveDate)> {
+        match self {
+            Self::Day   => Some((date, date)),
+            Self::Week  => {
+                let monday = week_start(date);
+                Some((monday, add_days(monday, 6).unwrap_or(monday)))
+            }
+            Self::Month => {
+                let first = date.with_day0(0)?;
+                let next  = first.checked_add_months(Months::new(1))?;
+                Some((first, add_days(next, -1).unwrap_or(first)))
+            }
+            Self::Tasks => None,
+        }
+    }
+
+    /// "Thu Oct 15 2026", "Week of Oct 12 2026", "October 2026" or "Tasks".
+    pub fn describe(self, date: NaiveDate) -> String {
+        match self {
+            Self::Day   => date.format("%a %b %-d %Y").to_string(),
+            Self::Week  => format!("Week of {}", week_start(date).format("%b %-d %Y")),
+            Self::Month => date.format("%B %Y").to_string(),
+            Self::Tasks => "Tasks".into(),
+        }
+    }
+}
+
+/// `f` cycles through them.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum Format {
+    #[default]
+    Ics,
+    Markdown,
+    Json,
+    /// Emacs org-mode: events as timestamped headings, tasks as TODOs.
+    Org,
+    /// todo.txt: tasks only.
+    TodoTxt,
+}
+
+impl Format {
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Ics      => "iCalendar",
+            Self::Markdown => "Markdown",
+            Self::Json     => "JSON",
+            Self::Org      => "Org",
+            Self::TodoTxt  => "todo.txt",
+        }
+    }
+
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::Ics      => "ics",
+            Self::Markdown => "md",
+            Self::Json     => "json",
+            Self::Org      => "org",
+            Self::TodoTxt  => "txt",
+        }
+    }
+
+    pub fn next(self) -> Self {
+        match self {
+            Self::Ics      => Self::Markdown,
+            Self::Markdown => Self::Json,
+            Self::Json     => Self::Org,
+            Self::Org      => Self::TodoTxt,
+            Self::TodoTxt  => Self::Ics,
+        }
+    }
+
+    /// From `--format`: `ics`, `md`, `json`, `org` or `todotxt`.
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            "ics" | "ical" | "icalendar" => Some(Self::Ics),
+            "md" | "markdown"            => Some(Self::Markdown),
+            "json"                       => Some(Self::Json),
+            "org"                        => Some(Self::Org),
+            "todotxt" | "todo.txt"       => Some(Self::TodoTxt),
+            _                            => None,
+        }
+    }
+}
+
+/// `~/lifemanager-week-2026-10-12.ics` and the like.
+pub fn default_path(scope: Scope, format: Format, date: NaiveDate) -> String {
+    let from = scope.days(date).map_or(date, |(from, _)| from);
+    format!("~/lifemanager-{}-{}.{}", scope.label(), from.format("%Y-%m-%d"), format.extension())
+}
+
+/// `~/…` expanded to the home directory.
+pub fn expand(path: &str) -> PathBuf {
+    match (path.strip_prefix("~/"), dirs::home_dir()) {
+        (Some(rest), Some(home)) => home.join(rest),
+        _                        => PathBuf::from(path),
+    }
+}
+
+pub fn render(format: Format, title: &str, events: &[Event], tasks: &[Task]) -> Result<String> {
+    Ok(match format {
+        Format::Ics      => ics(events, tasks),
+        Format::Markdown => markdown(title, events, tasks),
+        Format::Json     => serde_json::to_string_pretty(&serde_json::json!({
+            "title":  title,
+            "events": events,
+            "tasks":  tasks,
+        }))? + "\n",
+        Format::Org      => org(title, events, tasks),
+        Format::TodoTxt  => todo_txt(tasks),
+    })
+}
+
+// ─── iCalendar ───────────────────────────────────────────────────────────────
+
+fn ics(events: &[Event], tasks: &[Task]) -> String {
+    let stamp = Utc::now().format("%Y%m%dT%H%M%SZ").to_string();
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_owned(),
+        "VERSION:2.0".to_owned(),
+        "PRODID:-//LifeManager//Export//EN".to_owned(),
+    ];
+    for ev in events {
+        lines.push("BEGIN:VEVENT".into());
+        lines.push(format!("UID:{}@lifemanager", ev.id));
+        lines.push(format!("DTSTAMP:{stamp}"));
+        if ev.all_day {
+            lines.push(format!("DTSTART;VALUE=DATE:{}", ev.start.format("%Y%m%d")));
+            lines.push(format!("DTEND;VALUE=DATE:{}", ev.end.format("%Y%m%d")));
+        } else {
+            lines.push(format!("DTSTART:{}", ev.start.format("%Y%m%dT%H%M%S")));
+            lines.push(format!("DTEND:{}", ev.end.format("%Y%m%dT%H%M%S")));
+        }
+        lines.push(format!("SUMMARY:{}", escape(&ev.title)));
+        if let Some(ref d) = ev.description {
+            lines.push(format!("DESCRIPTION:{}", escape(d)));
+        }
+        if let Some(ref tag) = ev.tag {
+            lines.push(format!("CATEGORIES:{}", escape(tag)));
+        }
+        lines.push("END:VEVENT".into());
+    }
+    for t in tasks {
+        lines.push("BEGIN:VTODO".into());
+        lines.push(format!("UID:{}@lifemanager", t.id));
+        lines.push(format!("DTSTAMP:{stamp}"));
+        lines.push(format!("SUMMARY:{}", escape(&t.title)));
+        if let Some(ref n) = t.notes {
+            lines.push(format!("DESCRIPTION:{}", escape(n)));
+        }
+        if let Some(due) = t.due {
+            lines.push(format!("DUE;VALUE=DATE:{}", due.format("%Y%m%d")));
+        }
+        lines.push(format!("STATUS:{}", if t.completed { "COMPLETED" } else { "NEEDS-ACTION" }));
+        if !t.tags.is_empty() {
+            let tags: Vec<String> = t.tags.iter().map(|t| escape(t)).collect();
+            lines.push(format!("CATEGORIES:{}", tags.join(",")));
+        }
+        lines.push("END:VTODO".into());
+    }
+    lines.push("END:VCALENDAR".into());
+    lines.iter().map(|l| fold(l)).collect::<Vec<_>>().join("\r\n") + "\r\n"
+}
+
+fn escape(s: &str) -> String {
+    s.replace('\\', "\\\\").replace(';', "\\;").replace(',', "\\,").replace('\n', "\\n")
+}
+
+/// Lines over 75 octets continue on the next, indented by a space
+/// (RFC 5545 §3.1), without splitting a character.
+fn fold(line: &str) -> String {
+    let mut out = String::with_capacity(line.len());
+    let mut len = 0;
+    for c in line.chars() {
+        if len + c.len_utf8() > 75 {
+            out.push_str("\r\n ");
+            len = 1;
+        }
+        out.push(c);
+        len += c.len_utf8();
+    }
+    out
+}
+
+// ─── Markdown ────────────────────────────────────────────────────────────────
+
+fn markdown(title: &str, events: &[Event], tasks: &[Task]) -> String {
+    let mut out = format!("# {title}\n");
+    if !events.is_empty() {
+        out.push_str("\n## Events\n");
+        let mut day = None;
+        for ev in events {
+            let date = ev.start.date_naive();
+            if day != Some(date) {
+                out.push_str(&format!("\n### {}\n\n", date.format("%A, %B %-d")));
+                day = Some(date);
+            }
+            let time = if ev.all_day {
+                "all day".to_owned()
+            } else {
+                format!("{}–{}", ev.start.format("%H:%M"), ev.end.format("%H:%M"))
+            };
+            out.push_str(&format!("- {time} {}", ev.title));
+            if let Some(ref tag) = ev.tag { out.push_str(&format!(" #{tag}")); }
+            out.push('\n');
+        }
+    }
+    if !tasks.is_empty() {
+        out.push_str("\n## Tasks\n\n");
+        for t in tasks {
+            out.push_str(&format!("- [{}] {}", if t.completed { "x" } else { " " }, t.title));
+            if let Some(due) = t.due { out.push_str(&format!(" (due {})", due.format("%b %-d"))); }
+            for tag in &t.tags { out.push_str(&format!(" #{tag}")); }
+            out.push('\n');
+        }
+    }
+    out
+}
+
+// ─── Org ─────────────────────────────────────────────────────────────────────
+
+/// `<2026-10-20 Tue 09:00-10:00>`, `<2026-10-22 Thu>` or a range of either.
+fn org_span(start: DateTime<Utc>, end: DateTime<Utc>, all_day: bool) -> String {
+    if all_day {
+        // The end is exclusive here and inclusive in org
+        let last = (end - Duration::days(1)).max(start);
+        if last.date_naive() == start.date_naive() {
+            format!("<{}>", start.format("%Y-%m-%d %a"))
+        } else {
+            format!("<{}>--<{}>", start.format("%Y-%m-%d %a"), last.format("%Y-%m-%d %a"))
+        }
+    } else if end.date_naive() == start.date_naive() {
+        format!("<{}-{}>", start.format("%Y-%m-%d %a %H:%M"), end.format("%H:%M"))
+    } else {
+        format!("<{}>--<{}>", start.format("%Y-%m-%d %a %H:%M"), end.format("%Y-%m-%d %a %H:%M"))
+    }
+}
+
+/// `[#A]` for the top priority down to `[#C]`.
+pub fn org_priority(priority: i64) -> Option<char> {
+    match priority {
+        p if p >= 3 => Some('A'),
+        2           => Some('B'),
+        1           => Some('C'),
+        _           => None,
+    }
+}
+
+fn org(title: &str, events: &[Event], tasks: &[Task]) -> String {
+    let mut out = format!("#+TITLE: {title}\n");
+    if !events.is_empty() {
+        out.push_str("\n* Events\n");
+        for ev in events {
+            out.push_str(&format!("** {}", ev.title));
+            if let Some(ref tag) = ev.tag { out.push_str(&format!(" :{tag}:")); }
+            out.push_str(&format!("\n   {}\n", org_span(ev.start, ev.end, ev.all_day)));
+            if let Some(d) = ev.description.as_deref().map(str::trim).filter(|d| !d.is_empty()) {
+                for line in d.lines() { out.push_str(&format!("   {line}\n")); }
+            }
+        }
+    }
+    if !tasks.is_empty() {
+        out.push_str("\n* Tasks\n");
+        for t in tasks {
+            out.push_str(&format!("** {} ", if t.completed { "DONE" } else { "TODO" }));
+            if let Some(p) = org_priority(t.priority) { out.push_str(&format!("[#{p}] ")); }
+            out.push_str(&t.title);
+            if !t.tags.is_empty() { out.push_str(&format!(" :{}:", t.tags.join(":"))); }
+            out.push('\n');
+            if let Some(due) = t.due {
+                let stamp = if due.time() == NaiveTime::MIN { due.format("%Y-%m-%d %a") } else { due.format("%Y-%m-%d %a %H:%M") };
+                out.push_str(&format!("   DEADLINE: <{stamp}>\n"));
+            }
+            if let Some(n) = t.notes.as_deref().map(str::trim).filter(|n| !n.is_empty()) {
+                for line in n.lines() { out.push_str(&format!("   {line}\n")); }
+            }
+        }
+    }
+    out
+}
+
+// ─── todo.txt ────────────────────────────────────────────────────────────────
+
+/// `(A) 2026-10-12 Buy paint +home due:2026-10-21`: priority, creation
+/// date, title, tags as projects, then the due date. A done task starts
+/// `x <date>` and keeps its priority as `pri:A`, as the format asks. Notes
+/// don't fit.
+fn todo_txt(tasks: &[Task]) -> String {
+    let mut out = String::new();
+    for t in tasks {
+        let mut line = Vec::new();
+        let priority = org_priority(t.priority);
+        if t.completed {
+            line.push("x".to_owned());
+            line.push(t.updated_at.format("%Y-%m-%d").to_string());
+        } else if let Some(p) = priority {
+            line.push(format!("({p})"));
+        }
+        line.push(t.created_at.format("%Y-%m-%d").to_string());
+        line.push(t.title.clone());
+        line.extend(t.tags.iter().map(|tag| format!("+{tag}")));
+        if let Some(due) = t.due { line.push(format!("due:{}", due.format("%Y-%m-%d"))); }
+        if let (true, Some(p)) = (t.completed, priority) { line.push(format!("pri:{p}")); }
+        out.push_str(&line.join(" "));
+        out.push('\n');
+    }
+    out
+}
@@ -0,0 +1,104 @@
+//! Meeting load against focus time, week by week.
+//!
+//! Only working days count, clipped to the `[focus]` working hours. Events
+//! checked in as skipped or cancelled didn't take any time, so they're left
+//! out; all-day events, PTO and logged pomodoro sessions aren't meetings.
+//! Those sessions are totalled separately as deep work.
+
+pub mod pomodoro;
+
+use chrono::{DateTime, Duration, NaiveDate, NaiveTime, Utc};
+use std::collections::HashSet;
+
+use crate::attendance::Attendance;
+use crate::calendar::{add_days, day_bounds, is_working_day, week_start};
+use crate::config::FocusConfig;
+use crate::db::Event;
+
+/// Weeks shown in the stats view, the current one last.
+pub const WEEKS: i64 = 4;
+
+#[derive(Debug, Clone)]
+pub struct FocusWeek {
+    pub monday:        NaiveDate,
+    pub meeting_hours: f64,
+    /// Gaps between meetings shorter than `short_gap_minutes`.
+    pub short_gaps:    usize,
+    /// Longest stretch of working hours without a meeting, on any day.
+    pub longest_block: Duration,
+    /// Logged pomodoro sessions, any day and any hour.
+    pub deep_work_hours: f64,
+}
+
+fn parse_hm(s: &str) -> Option<NaiveTime> {
+    NaiveTime::parse_from_str(s.trim(), "%H:%M").ok()
+}
+
+/// First Monday of the report for `today`.
+pub fn first_monday(today: NaiveDate) -> NaiveDate {
+    add_days(week_start(today), -7 * (WEEKS - 1)).unwrap_or(today)
+}
+
+/// Meeting intervals within `[from, to)`, clipped to it and merged where they overlap.
+fn busy(events: &[Event], from: DateTime<Utc>, to: DateTime<Utc>) -> Vec<(DateTime<Utc>, DateTime<Utc>)> {
+    let mut spans: Vec<_> = events.iter()
+        .filter(|e| !e.all_day && !e.pto && !e.is_local_layer())
+        .filter(|e| !matches!(e.attendance, Some(Attendance::Skipped | Attendance::Cancelled)))
+        .filter(|e| e.start < to && e.end > from)
+        .map(|e| (e.start.max(from), e.end.min(to)))
+        .collect();
+    spans.sort();
+    let mut merged: Vec<(DateTime<Utc>, DateTime<Utc>)> = Vec::new();
+    for (s, e) in spans {
+        match merged.last_mut() {
+            Some(last) if s <= last.1 => last.1 = last.1.max(e),
+            _                         => merged.push((s, e)),
+        }
+    }
+    merged
+}
+
+/// One row per week from `first_monday(today)`. An unparsable `[focus]`
+/// window yields no rows.
+pub fn report(
+    events: &[Event], cfg: &FocusConfig, days_off: &HashSet<NaiveDate>, today: NaiveDate,
+) -> Vec<FocusWeek> {
+    let (Some(day_start), Some(day_end)) = (parse_hm(&cfg.day_start), parse_hm(&cfg.day_end)) else {
+        return Vec::new();
+    };
+    if day_end <= day_start { return Vec::new(); }
+    let short = Duration::minutes(cfg.short_gap_minutes);
+
+    let mut out = Vec::new();
+    let mut monday = first_monday(today);
+    for _ in 0..WEEKS {
+        let mut week = FocusWeek {
+            monday, meeting_hours: 0.0, short_gaps: 0, longest_block: Duration::zero(),
+            deep_work_hours: 0.0,
+        };
+        let (week_from, week_to) = (day_bounds(monday).0, day_bounds(add_days(monday, 6).unwrap_or(monday)).1);
+        week.deep_work_hours = events.iter()
+            .filter(|e| e.is_focus_session() && e.start >= week_from && e.start < week_to)
+            .fold(0.0, |h, e| h + (e.end - e.start).num_minutes() as f64 / 60.0);
+        for d in (0..7).filter_map(|i| add_days(monday, i)) {
+            if !is_working_day(d, days_off) { continue; }
+            // Times are wall-clock, stored as UTC like everywhere else
+            let (from, to) = (d.and_time(day_start).and_utc(), d.and_time(day_end).and_utc());
+            let spans = busy(events, from, to);
+
+            let mut cursor = from;
+            for (i, &(s, e)) in spans.iter().enumerate() {
+                let gap = s - cursor;
+                if i > 0 && gap > Duration::zero() && gap < short { week.short_gaps += 1; }
+                week.longest_block  = week.longest_block.max(gap);
+                week.meeting_hours += (e - s).num_minutes() as f64 / 60.0;
+                cursor = e;
+            }
+            week.longest_block = week.longest_block.max(to - cursor);
+        }
+        out.push(week);
+        let Some(next) = add_days(monday, 7) else { break };
+        monday = next;
+    }
+    out
+}
@@ -0,0 +1,60 @@
+//! The `P` focus timer. A session that runs its full length can be logged on
+//! the local focus layer (`[pomodoro] log_to_calendar`); one stopped early
+//! isn't logged.
+
+use chrono::{DateTime, Local, Utc};
+use std::time::{Duration, Instant};
+
+use crate::db::{Event, FOCUS_CALENDAR};
+
+#[derive(Debug, Clone)]
+pub struct Session {
+    /// Wall-clock start, stored as UTC like every event time.
+    pub started_at: DateTime<Utc>,
+    started:        Instant,
+    length:         Duration,
+    /// What the session was for: the task selected when it started.
+    pub label:      Option<String>,
+}
+
+impl Session {
+    pub fn start(minutes: i64, label: Option<String>) -> Self {
+        Self {
+            started_at: Local::now().naive_local().and_utc(),
+            started:    Instant::now(),
+            length:     Duration::from_secs(minutes.max(1) as u64 * 60),
+            label,
+        }
+    }
+
+    pub fn remaining(&self) -> Duration {
+        self.length.saturating_sub(self.started.elapsed())
+    }
+
+    pub fn elapsed(&self) -> Duration { self.started.elapsed().min(self.length) }
+
+    pub fn done(&self) -> bool { self.remaining().is_zero() }
+
+    /// `12:34` left, for the status bar.
+    pub fn countdown(&self) -> String {
+        let s = self.remaining().as_secs();
+        format!("{:02}:{:02}", s / 60, s % 60)
+    }
+
+    pub fn title(&self) -> String {
+        match self.label {
+            Some(ref l) => format!("🍅 {l}"),
+            None        => "🍅 Focus".into(),
+        }
+    }
+
+    /// The finished session as an event on the focus layer. Never dirty:
+    /// the layer stays on this machine.
+    pub fn to_event(&self) -> Event {
+        let length = chrono::Duration::from_std(self.length).unwrap_or_default();
+        let mut ev = Event::new(&self.title(), self.started_at, self.started_at + length);
+        ev.calendar_id = Some(FOCUS_CALENDAR.to_owned());
+        ev.dirty       = false;
+        ev
+    }
+}
@@ -0,0 +1,110 @@
+//! Habits: a name, the days it's due, and the days it was done. Streaks are
+//! counted over due days only, so a Mon/Wed/Fri habit isn't broken by a
+//! Tuesday.
+
+use chrono::{Datelike, NaiveDate, Weekday};
+use std::collections::HashSet;
+
+use crate::calendar::{add_days, is_weekend};
+use crate::model::Habit;
+
+/// Which days a habit is due.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Schedule {
+    Daily,
+    Weekdays,
+    Days(Vec<Weekday>),
+}
+
+impl Schedule {
+    /// `daily`, `weekdays`, or days like `mon,wed,fri`. Anything else is
+    /// read as daily.
+    pub fn parse(s: &str) -> Self {
+        match s.trim().to_lowercase().as_str() {
+            "" | "daily" => Self::Daily,
+            "weekdays"   => Self::Weekdays,
+            days => {
+                let days: Vec<Weekday> = days.split(',').filter_map(|d| d.trim().parse().ok()).collect();
+                if days.is_empty() { Self::Daily } else { Self::Days(days) }
+            }
+        }
+    }
+
+    /// As stored, and as typed after `@`.
+    pub fn label(&self) -> String {
+        match self {
+            Self::Daily     => "daily".into(),
+            Self::Weekdays  => "weekdays".into(),
+            Self::Days(ds)  => ds.iter().map(|d| d.to_string().to_lowercase()).collect::<Vec<_>>().join(","),
+        }
+    }
+
+    pub fn due_on(&self, date: NaiveDate) -> bool {
+        match self {
+            Self::Daily    => true,
+            Self::Weekdays => !is_weekend(date),
+            Self::Days(ds) => ds.contains(&date.weekday()),
+        }
+    }
+}
+
+/// Splits a typed habit: "Gym @mon,wed,fri" is the habit "Gym" due on those
+/// days; without `@` it's daily.
+pub fn split_schedule(input: &str) -> (String, Schedule) {
+    match input.rsplit_once('@') {
+        Some((name, sched)) => (name.trim().to_owned(), Schedule::parse(sched)),
+        None                => (input.trim().to_owned(), Schedule::Daily),
+    }
+}
+
+pub type Checks = HashSet<(String, NaiveDate)>;
+
+pub fn done(h: &Habit, checks: &Checks, date: NaiveDate) -> bool {
+    checks.contains(&(h.id.clone(), date))
+}
+
+/// Due on `date`, and already around then.
+pub fn due(h: &Habit, date: NaiveDate) -> bool {
+    date >= h.created_at.date_naive() && Schedule::parse(&h.schedule).due_on(date)
+}
+
+/// Due days in a row done, back from `today`. Today not being done yet
+/// doesn't break the streak; it just isn't counted.
+pub fn streak(h: &Habit, checks: &Checks, today: NaiveDate) -> usize {
+    let first = h.created_at.date_naive();
+    let mut day = if done(h, checks, today) { Some(today) } else { add_days(today, -1) };
+    let mut n   = 0;
+    while let Some(d) = day.filter(|d| *d >= first) {
+        if due(h, d) {
+            if !done(h, checks, d) { break; }
+            n += 1;
+        }
+        day = add_days(d, -1);
+    }
+    n
+}
+
+/// The longest run of due days done within `[from, to]`.
+pub fn longest_streak(h: &Habit, checks: &Checks, from: NaiveDate, to: NaiveDate) -> usize {
+    let (mut best, mut run) = (0, 0);
+    let mut day = Some(from);
+    while let Some(d) = day.filter(|d| *d <= to) {
+        if due(h, d) {
+            run  = if done(h, checks, d) { run + 1 } else { 0 };
+            best = best.max(run);
+        }
+        day = add_days(d, 1);
+    }
+    best
+}
+
+/// A day on which every habit due was done (and at least one was due):
+/// underlined on the month grid, so streaks read as unbroken lines.
+pub fn kept(habits: &[Habit], checks: &Checks, date: NaiveDate) -> bool {
+    let mut any = false;
+    for h in habits.iter().filter(|h| due(h, date)) {
+        if !done(h, checks, date) { return false; }
+        any = true;
+    }
+    any
+}
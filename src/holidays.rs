@@ -0,0 +1,673 @@
+//! Public holidays — a pluggable engine of per-country providers — and the
+//! long-weekend finder.
+//!
+//! Each country is a `HolidayProvider`; the built-in ones are rule tables
+//! (fixed dates, nth weekdays, Easter offsets, lunar dates) plus the
+//! country's policy for holidays that land on a weekend. Holiday packs —
+//! TOML files in `~/.config/lifemanager/holidays/` — add more, or replace
+//! a built-in country. `[holidays]` in config.toml picks which providers
+//! the engine runs.
+
+use anyhow::{anyhow, Result};
+use chrono::{Datelike, Duration, NaiveDate, Weekday};
+use serde::Deserialize;
+use std::borrow::Cow;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use crate::calendar::{is_weekend, is_working_day, lunar, nth_weekday};
+
+/// Which countries' public holidays to show and count as days off.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct HolidayConfig {
+    pub enabled:         bool,
+    /// ISO country codes, e.g. ["US", "VN"]. See BUILTIN_COUNTRIES.
+    pub countries:       Vec<String>,
+    /// What a repeating task does when its next date is a day off.
+    pub recurring_tasks: OnHoliday,
+}
+
+impl Default for HolidayConfig {
+    fn default() -> Self {
+        Self { enabled: true, countries: vec!["US".into(), "VN".into()], recurring_tasks: OnHoliday::Keep }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OnHoliday {
+    /// Due on the holiday anyway.
+    #[default]
+    Keep,
+    /// Dropped; due on the next scheduled day instead.
+    Skip,
+    /// Moved to the next day that isn't a day off.
+    Shift,
+}
+
+#[derive(Debug, Clone)]
+pub struct Holiday {
+    pub date:    NaiveDate,
+    pub name:    Cow<'static, str>,
+    pub country: Cow<'static, str>,
+    pub emoji:   Cow<'static, str>,
+    /// false for observances that aren't a day off work (e.g. Trung Thu).
+    pub day_off: bool,
+}
+
+pub trait HolidayProvider: Send + Sync {
+    /// ISO 3166 alpha-2 code (or a pack's own code), as used in config.
+    fn country(&self) -> &str;
+    fn holidays(&self, year: i32) -> Vec<Holiday>;
+}
+
+// ─── Engine ───────────────────────────────────────────────────────────────────
+
+/// (provider index, year) → that provider's holidays, sorted by date.
+type YearTables = HashMap<(usize, i32), Arc<[Holiday]>>;
+
+pub struct HolidayEngine {
+    providers: Vec<Box<dyn HolidayProvider>>,
+    /// Computed on first use: month navigation asks for the same years
+    /// over and over.
+    cache:     Mutex<YearTables>,
+}
+
+impl HolidayEngine {
+    /// The configured countries, with the packs in the config dir.
+    pub fn from_config(cfg: &HolidayConfig) -> Self {
+        Self::new(cfg, if cfg.enabled { load_packs() } else { Vec::new() })
+    }
+
+    /// The configured countries, from `packs` or the built-in rules. A pack
+    /// with a built-in country's code is used instead of the built-in rules.
+    pub fn new(cfg: &HolidayConfig, mut packs: Vec<PackProvider>) -> Self {
+        let providers = if cfg.enabled {
+            cfg.countries.iter().filter_map(|c| {
+                let p = match packs.iter().position(|p| p.country.eq_ignore_ascii_case(c)) {
+                    Some(i) => Some(Box::new(packs.swap_remove(i)) as Box<dyn HolidayProvider>),
+                    None    => builtin_provider(c),
+                };
+                if p.is_none() {
+                    tracing::warn!(
+                        "Unknown holiday country {c:?} (built-in: {}; packs go in {})",
+                        BUILTIN_COUNTRIES.join(", "), packs_dir().display(),
+                    );
+                }
+                p
+            }).collect()
+        } else {
+            Vec::new()
+        };
+        Self { providers, cache: Mutex::new(HashMap::new()) }
+    }
+
+    pub fn countries(&self) -> Vec<&str> {
+        self.providers.iter().map(|p| p.country()).collect()
+    }
+
+    /// Provider `idx`'s holidays in `year`, from the cache when possible.
+    fn table(&self, idx: usize, year: i32) -> Arc<[Holiday]> {
+        let mut cache = self.cache.lock().unwrap_or_else(|e| e.into_inner());
+        cache.entry((idx, year)).or_insert_with(|| {
+            let mut hs = self.providers[idx].holidays(year);
+            hs.sort_by_key(|h| h.date);
+            hs.into()
+        }).clone()
+    }
+
+    /// Holidays of every enabled country in `year` matching `keep`, by date.
+    fn collect(&self, year: i32, keep: impl Fn(&Holiday) -> bool) -> Vec<Holiday> {
+        let mut out: Vec<Holiday> = (0..self.providers.len())
+            .flat_map(|i| self.table(i, year).iter().filter(|h| keep(h)).cloned().collect::<Vec<_>>())
+            .collect();
+        // Stable, so countries keep config order within a day
+        out.sort_by_key(|h| h.date);
+        out
+    }
+
+    /// All holidays of the enabled countries in `year`, sorted by date.
+    pub fn holidays_in_year(&self, year: i32) -> Vec<Holiday> {
+        self.collect(year, |_| true)
+    }
+
+    pub fn holidays_in_month(&self, year: i32, month: u32) -> Vec<Holiday> {
+        self.collect(year, |h| h.date.month() == month)
+    }
+
+    /// Dates that are days off work in any enabled country.
+    pub fn days_off(&self, years: std::ops::RangeInclusive<i32>) -> HashSet<NaiveDate> {
+        years.flat_map(|y| self.holidays_in_year(y))
+            .filter(|h| h.day_off)
+            .map(|h| h.date)
+            .collect()
+    }
+}
+
+// ─── Rule-based providers ─────────────────────────────────────────────────────
+
+#[derive(Debug, Clone, Copy)]
+enum Rule {
+    /// Same month/day every year.
+    Fixed(u32, u32),
+    /// One date only, from a pack's list (e.g. a lunar-calendar feast
+    /// worked out ahead for the next few years).
+    On(NaiveDate),
+    /// nth weekday of a month; negative nth counts from the end.
+    Nth(u32, Weekday, i32),
+    /// Last given weekday strictly before month/day (Canada's Victoria Day).
+    WeekdayBefore(u32, u32, Weekday),
+    /// Days relative to Western Easter Sunday.
+    Easter(i64),
+    /// Vietnamese lunar month/day, plus a day offset (e.g. -1 for New Year's Eve).
+    Lunar(u32, u32, i64),
+    /// Japanese equinox days (true = vernal, false = autumnal).
+    Equinox(bool),
+}
+
+/// What happens to a fixed-date day off that falls on a weekend.
+#[derive(Debug, Clone, Copy, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum Observed {
+    #[default]
+    Never,
+    /// Saturday → Friday, Sunday → Monday (US federal).
+    NearestWeekday,
+    /// Next working day not already a holiday (UK/CA/AU substitute days).
+    NextWeekday,
+    /// Sunday only → next non-holiday day (Japanese substitute holiday).
+    SundayToNext,
+}
+
+struct Def {
+    rule:    Rule,
+    name:    &'static str,
+    emoji:   &'static str,
+    day_off: bool,
+}
+
+const fn def(rule: Rule, name: &'static str, emoji: &'static str) -> Def {
+    Def { rule, name, emoji, day_off: true }
+}
+
+const fn observance(rule: Rule, name: &'static str, emoji: &'static str) -> Def {
+    Def { rule, name, emoji, day_off: false }
+}
+
+pub struct RuleProvider {
+    country:  &'static str,
+    defs:     &'static [Def],
+    observed: Observed,
+}
+
+impl HolidayProvider for RuleProvider {
+    fn country(&self) -> &str { self.country }
+
+    fn holidays(&self, year: i32) -> Vec<Holiday> {
+        with_observed(self.observed, year, |year| self.defs.iter().filter_map(|d| {
+            resolve(d.rule, year).map(|date| (d.rule, Holiday {
+                date,
+                name:    d.name.into(),
+                country: self.country.into(),
+                emoji:   d.emoji.into(),
+                day_off: d.day_off,
+            }))
+        }).collect())
+    }
+}
+
+/// The holidays dated in `year` plus the substitute days `observed` gives
+/// fixed-date days off that land on a weekend. A substitute can cross New
+/// Year (Saturday Jan 1 is observed on Friday Dec 31), so `resolve_year`
+/// is asked for the years either side too, and theirs kept when they land
+/// in `year`.
+fn with_observed(
+    observed: Observed, year: i32, resolve_year: impl Fn(i32) -> Vec<(Rule, Holiday)>,
+) -> Vec<Holiday> {
+    let resolved: Vec<(Rule, Holiday)> = [year.checked_sub(1), Some(year), year.checked_add(1)]
+        .into_iter()
+        .flatten()
+        .flat_map(resolve_year)
+        .collect();
+    let mut taken: HashSet<NaiveDate> =
+        resolved.iter().filter(|(_, h)| h.day_off).map(|(_, h)| h.date).collect();
+    let mut extra = Vec::new();
+    for (rule, h) in &resolved {
+        if !h.day_off || !matches!(rule, Rule::Fixed(..)) { continue; }
+        let shifted = match (observed, h.date.weekday()) {
+            (Observed::NearestWeekday, Weekday::Sat) => Some(h.date - Duration::days(1)),
+            (Observed::NearestWeekday, Weekday::Sun) => Some(h.date + Duration::days(1)),
+            (Observed::NextWeekday, _) if is_weekend(h.date) => {
+                let mut o = h.date + Duration::days(1);
+                while is_weekend(o) || taken.contains(&o) { o += Duration::days(1); }
+                Some(o)
+            }
+            (Observed::SundayToNext, Weekday::Sun) => {
+                let mut o = h.date + Duration::days(1);
+                while taken.contains(&o) { o += Duration::days(1); }
+                Some(o)
+            }
+            _ => None,
+        };
+        if let Some(o) = shifted {
+            taken.insert(o);
+            if o.year() == year { extra.push(Holiday { date: o, ..h.clone() }); }
+        }
+    }
+    let mut out: Vec<Holiday> = resolved.into_iter().map(|(_, h)| h).filter(|h| h.date.year() == year).collect();
+    out.extend(extra);
+    out
+}
+
+/// None when a rule has no date in `year`.
+fn resolve(rule: Rule, year: i32) -> Option<NaiveDate> {
+    match rule {
+        Rule::Fixed(m, d)        => NaiveDate::from_ymd_opt(year, m, d),
+        Rule::On(date)           => Some(date).filter(|d| d.year() == year),
+        Rule::Nth(m, wd, n)      => nth_weekday(year, m, wd, n),
+        Rule::WeekdayBefore(m, d, wd) => {
+            let mut date = NaiveDate::from_ymd_opt(year, m, d)? - Duration::days(1);
+            while date.weekday() != wd { date -= Duration::days(1); }
+            Some(date)
+        }
+        Rule::Easter(off)        => easter(year).map(|e| e + Duration::days(off)),
+        Rule::Lunar(lm, ld, off) => lunar::lunar_to_solar(year, lm, ld, false).map(|d| d + Duration::days(off)),
+        Rule::Equinox(vernal)    => equinox(year, vernal),
+    }
+}
+
+/// Western Easter Sunday (anonymous Gregorian computus).
+fn easter(year: i32) -> Option<NaiveDate> {
+    let a = year % 19;
+    let (b, c) = (year / 100, year % 100);
+    let (d, e) = (b / 4, b % 4);
+    let f = (b + 8) / 25;
+    let g = (b - f + 1) / 3;
+    let h = (19 * a + b - d - g + 15) % 30;
+    let (i, k) = (c / 4, c % 4);
+    let l = (32 + 2 * e + 2 * i - h - k) % 7;
+    let m = (a + 11 * h + 22 * l) / 451;
+    let month = (h + l - 7 * m + 114) / 31;
+    let day   = (h + l - 7 * m + 114) % 31 + 1;
+    NaiveDate::from_ymd_opt(year, month as u32, day as u32)
+}
+
+/// Japanese equinox holidays (Shunbun / Shūbun no Hi), valid 1980–2099.
+fn equinox(year: i32, vernal: bool) -> Option<NaiveDate> {
+    let base = if vernal { 20.8431 } else { 23.2488 };
+    let y    = (year - 1980) as f64;
+    let day  = (base + 0.242194 * y - (y / 4.0).floor()).floor() as u32;
+    NaiveDate::from_ymd_opt(year, if vernal { 3 } else { 9 }, day)
+}
+
+// ─── Country tables ───────────────────────────────────────────────────────────
+
+use Rule::*;
+
+const US: &[Def] = &[
+    def(Fixed(1, 1),                   "New Year's Day",             "🎆"),
+    def(Nth(1, Weekday::Mon, 3),       "Martin Luther King Jr. Day", "🕊"),
+    def(Nth(2, Weekday::Mon, 3),       "Presidents' Day",            "🏛"),
+    def(Nth(5, Weekday::Mon, -1),      "Memorial Day",               "🎗"),
+    def(Fixed(6, 19),                  "Juneteenth",                 "✊"),
+    def(Fixed(7, 4),                   "Independence Day",           "🎇"),
+    def(Nth(9, Weekday::Mon, 1),       "Labor Day",                  "🛠"),
+    def(Nth(10, Weekday::Mon, 2),      "Columbus Day",               "⛵"),
+    def(Fixed(11, 11),                 "Veterans Day",               "🎖"),
+    def(Nth(11, Weekday::Thu, 4),      "Thanksgiving",               "🦃"),
+    def(Fixed(12, 25),                 "Christmas Day",              "🎄"),
+];
+
+const VN: &[Def] = &[
+    def(Fixed(1, 1),                   "Tết Dương lịch",             "🎆"),
+    def(Lunar(1, 1, -1),               "Giao thừa",                  "🧧"),
+    def(Lunar(1, 1, 0),                "Tết Nguyên Đán",             "🧧"),
+    def(Lunar(1, 1, 1),                "Tết Nguyên Đán",             "🧧"),
+    def(Lunar(1, 1, 2),                "Tết Nguyên Đán",             "🧧"),
+    def(Lunar(3, 10, 0),               "Giỗ Tổ Hùng Vương",          "🏯"),
+    def(Fixed(4, 30),                  "Ngày Giải phóng miền Nam",   "🇻🇳"),
+    def(Fixed(5, 1),                   "Quốc tế Lao động",           "🛠"),
+    observance(Lunar(7, 15, 0),        "Vu Lan",                     "🪷"),
+    observance(Lunar(8, 15, 0),        "Tết Trung Thu",              "🏮"),
+    def(Fixed(9, 2),                   "Quốc khánh",                 "🇻🇳"),
+];
+
+const DE: &[Def] = &[
+    def(Fixed(1, 1),                   "Neujahr",                    "🎆"),
+    def(Easter(-2),                    "Karfreitag",                 "✝"),
+    def(Easter(1),                     "Ostermontag",                "🐣"),
+    def(Fixed(5, 1),                   "Tag der Arbeit",             "🛠"),
+    def(Easter(39),                    "Christi Himmelfahrt",        "⛪"),
+    def(Easter(50),                    "Pfingstmontag",              "🕊"),
+    def(Fixed(10, 3),                  "Tag der Deutschen Einheit",  "🇩🇪"),
+    def(Fixed(12, 25),                 "1. Weihnachtstag",           "🎄"),
+    def(Fixed(12, 26),                 "2. Weihnachtstag",           "🎄"),
+];
+
+const GB: &[Def] = &[
+    def(Fixed(1, 1),                   "New Year's Day",             "🎆"),
+    def(Easter(-2),                    "Good Friday",                "✝"),
+    def(Easter(1),                     "Easter Monday",              "🐣"),
+    def(Nth(5, Weekday::Mon, 1),       "Early May Bank Holiday",     "🌷"),
+    def(Nth(5, Weekday::Mon, -1),      "Spring Bank Holiday",        "🌸"),
+    def(Nth(8, Weekday::Mon, -1),      "Summer Bank Holiday",        "☀"),
+    def(Fixed(12, 25),                 "Christmas Day",              "🎄"),
+    def(Fixed(12, 26),                 "Boxing Day",                 "🎁"),
+];
+
+const FR: &[Def] = &[
+    def(Fixed(1, 1),                   "Jour de l'an",               "🎆"),
+    def(Easter(1),                     "Lundi de Pâques",            "🐣"),
+    def(Fixed(5, 1),                   "Fête du Travail",            "🛠"),
+    def(Fixed(5, 8),                   "Victoire 1945",              "🕊"),
+    def(Easter(39),                    "Ascension",                  "⛪"),
+    def(Easter(50),                    "Lundi de Pentecôte",         "🕊"),
+    def(Fixed(7, 14),                  "Fête nationale",             "🇫🇷"),
+    def(Fixed(8, 15),                  "Assomption",                 "⛪"),
+    def(Fixed(11, 1),                  "Toussaint",                  "🕯"),
+    def(Fixed(11, 11),                 "Armistice 1918",             "🎖"),
+    def(Fixed(12, 25),                 "Noël",                       "🎄"),
+];
+
+const CA: &[Def] = &[
+    def(Fixed(1, 1),                   "New Year's Day",             "🎆"),
+    def(Easter(-2),                    "Good Friday",                "✝"),
+    def(WeekdayBefore(5, 25, Weekday::Mon), "Victoria Day",          "👑"),
+    def(Fixed(7, 1),                   "Canada Day",                 "🇨🇦"),
+    def(Nth(9, Weekday::Mon, 1),       "Labour Day",                 "🛠"),
+    def(Nth(10, Weekday::Mon, 2),      "Thanksgiving",               "🦃"),
+    def(Fixed(11, 11),                 "Remembrance Day",            "🎖"),
+    def(Fixed(12, 25),                 "Christmas Day",              "🎄"),
+    def(Fixed(12, 26),                 "Boxing Day",                 "🎁"),
+];
+
+const AU: &[Def] = &[
+    def(Fixed(1, 1),                   "New Year's Day",             "🎆"),
+    def(Fixed(1, 26),                  "Australia Day",              "🇦🇺"),
+    def(Easter(-2),                    "Good Friday",                "✝"),
+    def(Easter(1),                     "Easter Monday",              "🐣"),
+    def(Fixed(4, 25),                  "Anzac Day",                  "🎖"),
+    def(Nth(6, Weekday::Mon, 2),       "King's Birthday",            "👑"),
+    def(Fixed(12, 25),                 "Christmas Day",              "🎄"),
+    def(Fixed(12, 26),                 "Boxing Day",                 "🎁"),
+];
+
+const JP: &[Def] = &[
+    def(Fixed(1, 1),                   "元日",                        "🎍"),
+    def(Nth(1, Weekday::Mon, 2),       "成人の日",                    "👘"),
+    def(Fixed(2, 11),                  "建国記念の日",                "🇯🇵"),
+    def(Fixed(2, 23),                  "天皇誕生日",                  "👑"),
+    def(Equinox(true),                 "春分の日",                    "🌸"),
+    def(Fixed(4, 29),                  "昭和の日",                    "🌿"),
+    def(Fixed(5, 3),                   "憲法記念日",                  "📜"),
+    def(Fixed(5, 4),                   "みどりの日",                  "🌳"),
+    def(Fixed(5, 5),                   "こどもの日",                  "🎏"),
+    def(Nth(7, Weekday::Mon, 3),       "海の日",                      "🌊"),
+    def(Fixed(8, 11),                  "山の日",                      "⛰"),
+    def(Nth(9, Weekday::Mon, 3),       "敬老の日",                    "🍵"),
+    def(Equinox(false),                "秋分の日",                    "🍁"),
+    def(Nth(10, Weekday::Mon, 2),      "スポーツの日",                "🏅"),
+    def(Fixed(11, 3),                  "文化の日",                    "🎨"),
+    def(Fixed(11, 23),                 "勤労感謝の日",                "🌾"),
+];
+
+/// Countries with built-in rules, for config validation and help text.
+pub const BUILTIN_COUNTRIES: &[&str] = &["US", "VN", "DE", "GB", "FR", "CA", "AU", "JP"];
+
+fn builtin_provider(code: &str) -> Option<Box<dyn HolidayProvider>> {
+    let (country, defs, observed): (&'static str, &'static [Def], Observed) =
+        match code.to_ascii_uppercase().as_str() {
+            "US" => ("US", US, Observed::NearestWeekday),
+            "VN" => ("VN", VN, Observed::Never),
+            "DE" => ("DE", DE, Observed::Never),
+            "GB" | "UK" => ("GB", GB, Observed::NextWeekday),
+            "FR" => ("FR", FR, Observed::Never),
+            "CA" => ("CA", CA, Observed::NextWeekday),
+            "AU" => ("AU", AU, Observed::NextWeekday),
+            "JP" => ("JP", JP, Observed::SundayToNext),
+            _    => return None,
+        };
+    Some(Box::new(RuleProvider { country, defs, observed }))
+}
+
+// ─── Holiday packs ────────────────────────────────────────────────────────────
+//
+// A pack is one TOML file per country (or community, or religion):
+//
+//     country  = "IE"
+//     observed = "next_weekday"   # never | nearest_weekday | next_weekday | sunday_to_next
+//
+//     [[holiday]]
+//     name  = "St. Patrick's Day"
+//     emoji = "☘"
+//     month = 3
+//     day   = 17
+//
+//     [[holiday]]
+//     name    = "June Bank Holiday"
+//     month   = 6
+//     weekday = "mon"
+//     nth     = 1                # -1 for the last
+//
+//     [[holiday]]
+//     name    = "Eid al-Fitr"
+//     dates   = ["2026-03-20", "2027-03-10"]
+//     day_off = false            # an observance: shown, not a day off
+//
+// `easter = -2` (days from Easter Sunday) works too. Listing the pack's
+// code under `[holidays] countries` turns it on.
+
+#[derive(Debug, Deserialize)]
+struct PackFile {
+    country:  String,
+    #[serde(default)]
+    observed: Observed,
+    #[serde(default, rename = "holiday")]
+    holidays: Vec<PackEntry>,
+}
+
+#[derive(Debug, Deserialize)]
+struct PackEntry {
+    name:    String,
+    #[serde(default = "emoji_default")]
+    emoji:   String,
+    #[serde(default = "day_off_default")]
+    day_off: bool,
+    month:   Option<u32>,
+    day:     Option<u32>,
+    weekday: Option<Weekday>,
+    nth:     Option<i32>,
+    easter:  Option<i64>,
+    #[serde(default)]
+    dates:   Vec<NaiveDate>,
+}
+
+fn emoji_default() -> String { "📅".into() }
+
+fn day_off_default() -> bool { true }
+
+impl PackEntry {
+    /// The rules for this entry: one, or one per listed date.
+    fn rules(&self) -> Result<Vec<Rule>> {
+        let rule = match (self.month, self.day, self.weekday, self.nth, self.easter) {
+            _ if !self.dates.is_empty() => return Ok(self.dates.iter().map(|&d| Rule::On(d)).collect()),
+            (Some(m), Some(d), None, None, None) if NaiveDate::from_ymd_opt(2000, m, d).is_some() => {
+                Rule::Fixed(m, d)
+            }
+            (Some(m @ 1..=12), None, Some(wd), Some(n @ (-5..=-1 | 1..=5)), None) => Rule::Nth(m, wd, n),
+            (None, None, None, None, Some(off)) => Rule::Easter(off),
+            _ => return Err(anyhow!(
+                "{:?} needs month and day, month/weekday/nth, easter, or dates", self.name,
+            )),
+        };
+        Ok(vec![rule])
+    }
+}
+
+struct PackDef {
+    rule:    Rule,
+    name:    String,
+    emoji:   String,
+    day_off: bool,
+}
+
+pub struct PackProvider {
+    country:  String,
+    defs:     Vec<PackDef>,
+    observed: Observed,
+}
+
+impl PackProvider {
+    fn load(path: &Path) -> Result<Self> {
+        Self::parse(&std::fs::read_to_string(path)?)
+    }
+
+    /// A pack from its TOML text.
+    pub fn parse(text: &str) -> Result<Self> {
+        let file: PackFile = toml::from_str(text)?;
+        let mut defs = Vec::new();
+        for d in &file.holidays {
+            for rule in d.rules()? {
+                defs.push(PackDef { rule, name: d.name.clone(), emoji: d.emoji.clone(), day_off: d.day_off });
+            }
+        }
+        Ok(Self { country: file.country.trim().to_owned(), defs, observed: file.observed })
+    }
+}
+
+impl HolidayProvider for PackProvider {
+    fn country(&self) -> &str { &self.country }
+
+    fn holidays(&self, year: i32) -> Vec<Holiday> {
+        with_observed(self.observed, year, |year| self.defs.iter().filter_map(|d| {
+            resolve(d.rule, year).map(|date| (d.rule, Holiday {
+                date,
+                name:    d.name.clone().into(),
+                country: self.country.clone().into(),
+                emoji:   d.emoji.clone().into(),
+                day_off: d.day_off,
+            }))
+        }).collect())
+    }
+}
+
+fn packs_dir() -> PathBuf {
+    dirs::config_dir().unwrap_or_else(|| PathBuf::from(".")).join("lifemanager").join("holidays")
+}
+
+/// Every readable pack in the packs directory, in file-name order. A file
+/// that doesn't parse is skipped with a warning rather than failing startup.
+fn load_packs() -> Vec<PackProvider> {
+    let Ok(dir) = std::fs::read_dir(packs_dir()) else { return Vec::new() };
+    let mut paths: Vec<PathBuf> = dir.flatten().map(|e| e.path())
+        .filter(|p| p.extension().is_some_and(|x| x == "toml"))
+        .collect();
+    paths.sort();
+    paths.iter().filter_map(|path| match PackProvider::load(path) {
+        Ok(p)  => Some(p),
+        Err(e) => {
+            tracing::warn!("Skipping holiday pack {}: {e}", path.display());
+            None
+        }
+    }).collect()
+}
+
+// ─── Long weekends ────────────────────────────────────────────────────────────
+
+/// Working days we're willing to take off to bridge two breaks.
+const MAX_BRIDGE_DAYS: usize = 2;
+
+#[derive(Debug, Clone)]
+pub struct LongWeekend {
+    pub start:    NaiveDate,
+    pub end:      NaiveDate,
+    /// Working days that must be taken as leave to get the whole window off.
+    pub bridge:   Vec<NaiveDate>,
+    pub holidays: Vec<Holiday>,
+}
+
+impl LongWeekend {
+    pub fn days_off(&self) -> i64 { (self.end - self.start).num_days() + 1 }
+}
+
+/// Finds breaks of 3+ days around the holidays of `year`: holidays that already
+/// touch a weekend, plus windows made by taking up to MAX_BRIDGE_DAYS of leave.
+pub fn long_weekends(engine: &HolidayEngine, year: i32) -> Vec<LongWeekend> {
+    let holidays: Vec<Holiday> = (year - 1..=year + 1)
+        .flat_map(|y| engine.holidays_in_year(y))
+        .filter(|h| h.day_off)
+        .collect();
+    let off_dates: HashSet<NaiveDate> = holidays.iter().map(|h| h.date).collect();
+
+    // Maximal runs of consecutive non-working days, padded past the year ends
+    // so breaks spanning New Year are found whole.
+    let (Some(from), Some(to)) = (
+        NaiveDate::from_ymd_opt(year - 1, 12, 1),
+        NaiveDate::from_ymd_opt(year + 1, 1, 31),
+    ) else {
+        return Vec::new();
+    };
+    let mut runs: Vec<(NaiveDate, NaiveDate)> = Vec::new();
+    let mut d = from;
+    while d <= to {
+        if !is_working_day(d, &off_dates) {
+            match runs.last_mut() {
+                Some((_, end)) if *end + Duration::days(1) == d => *end = d,
+                _ => runs.push((d, d)),
+            }
+        }
+        d += Duration::days(1);
+    }
+
+    let holidays_between = |s: NaiveDate, e: NaiveDate| -> Vec<Holiday> {
+        holidays.iter().filter(|h| h.date >= s && h.date <= e).cloned().collect()
+    };
+
+    let mut out: Vec<LongWeekend> = Vec::new();
+    for (i, &(s, e)) in runs.iter().enumerate() {
+        let hs = holidays_between(s, e);
+        if !hs.is_empty() && (e - s).num_days() >= 2 {
+            out.push(LongWeekend { start: s, end: e, bridge: vec![], holidays: hs });
+        }
+
+        let Some(&(ns, ne)) = runs.get(i + 1) else { continue };
+        let gap = (ns - e).num_days() as usize - 1;
+        if gap == 0 || gap > MAX_BRIDGE_DAYS { continue; }
+        let hs = holidays_between(s, ne);
+        if hs.is_empty() { continue; }
+        let bridge = (1..=gap as i64).map(|n| e + Duration::days(n)).collect();
+        out.push(LongWeekend { start: s, end: ne, bridge, holidays: hs });
+    }
+
+    out.retain(|lw| lw.holidays.iter().any(|h| h.date.year() == year));
+    out.sort_by_key(|lw| (lw.start, lw.bridge.len()));
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn a_feb_29_holiday_only_falls_in_leap_years() {
+        let pack = PackProvider::parse("country = \"XX\"\n[[holiday]]\nname = \"Leap Day\"\nmonth = 2\nday = 29\n").unwrap();
+        let leap = NaiveDate::from_ymd_opt(2028, 2, 29).unwrap();
+        assert_eq!(pack.holidays(2028).iter().map(|h| h.date).collect::<Vec<_>>(), vec![leap]);
+        assert!(pack.holidays(2027).is_empty());
+        assert!(pack.holidays(2100).is_empty());
+    }
+
+    #[test]
+    fn a_saturday_new_year_is_observed_the_friday_before() {
+        let us = builtin_provider("US").unwrap();
+        for year in [2021, 2027] {
+            let eve = NaiveDate::from_ymd_opt(year, 12, 31).unwrap();
+            assert!(us.holidays(year).iter().any(|h| h.date == eve && h.name == "New Year's Day"), "{year}");
+            assert!(us.holidays(year + 1).iter().all(|h| h.date.year() == year + 1));
+        }
+        let eve = NaiveDate::from_ymd_opt(2026, 12, 31).unwrap();
+        assert!(us.holidays(2026).iter().all(|h| h.date != eve));
+    }
+}
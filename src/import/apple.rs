@@ -0,0 +1,383 @@
+//! Whole-calendar `.ics` exports — macOS / iCloud Calendar's File → Export,
+//! or a CalDAV server's — with the parts a feed reader can skip:
+//!
+//! - Times: UTC and TZID times become wall time in this machine's zone, and
+//!   floating ones are taken in the calendar's `X-WR-TIMEZONE` when it has
+//!   one (Apple writes floating times for calendars set to a zone).
+//! - Repeats: `RRULE` is expanded into its occurrences up to a year ahead,
+//!   less `EXDATE`s; a `RECURRENCE-ID` event replaces the occurrence it
+//!   names (or drops it, when cancelled).
+//! - `X-WR-CALNAME` becomes the events' tag, as a vdir's name does.
+
+use chrono::{DateTime, Datelike, Duration, Local, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Utc, Weekday};
+use chrono_tz::Tz;
+use std::collections::{HashMap, HashSet};
+
+use crate::calendar::add_days;
+use crate::db::Event;
+use crate::sync::ics::{self, Prop};
+
+/// Occurrences past this many from one series are left out.
+const MAX_OCCURRENCES: usize = 2000;
+
+/// What an export holds, converted.
+#[derive(Default)]
+pub struct Export {
+    pub name:   Option<String>,
+    pub events: Vec<Event>,
+    /// Repeating series expanded.
+    pub series: usize,
+}
+
+// ─── Times ────────────────────────────────────────────────────────────────────
+
+#[derive(Clone, Copy)]
+enum Zone {
+    Utc,
+    Tz(Tz),
+    /// No zone and no `X-WR-TIMEZONE`: the wall clock as written.
+    Floating,
+}
+
+/// A DTSTART-like value as written: the clock reading, the zone it's in,
+/// and whether it was a bare date.
+#[derive(Clone, Copy)]
+struct When {
+    at:   NaiveDateTime,
+    zone: Zone,
+    date: bool,
+}
+
+impl When {
+    fn parse(p: &Prop, calendar: Option<Tz>) -> Option<Self> {
+        let v = p.value.trim();
+        Self::parse_value(v, p.param("VALUE") == Some("DATE"), p.param("TZID"), calendar)
+    }
+
+    fn parse_value(v: &str, date_only: bool, tzid: Option<&str>, calendar: Option<Tz>) -> Option<Self> {
+        if date_only || v.len() == 8 {
+            let d = NaiveDate::parse_from_str(v, "%Y%m%d").ok()?;
+            return Some(Self { at: d.and_time(NaiveTime::MIN), zone: Zone::Floating, date: true });
+        }
+        if let Some(utc) = v.strip_suffix('Z') {
+            let at = NaiveDateTime::parse_from_str(utc, "%Y%m%dT%H%M%S").ok()?;
+            return Some(Self { at, zone: Zone::Utc, date: false });
+        }
+        let at = NaiveDateTime::parse_from_str(v, "%Y%m%dT%H%M%S").ok()?;
+        // Unknown TZIDs (Outlook's "Pacific Standard Time") fall back to the
+        // calendar's zone, as floating times do
+        let zone = tzid.and_then(tz_named).or(calendar).map_or(Zone::Floating, Zone::Tz);
+        Some(Self { at, zone, date: false })
+    }
+
+    /// The same clock reading, another day or time.
+    fn with(self, at: NaiveDateTime) -> Self {
+        Self { at, ..self }
+    }
+
+    /// Wall time here, stored as UTC like every local event. Dates stay
+    /// as they are.
+    fn wall(self) -> DateTime<Utc> {
+        let instant = match self.zone {
+            _ if self.date => return self.at.and_utc(),
+            Zone::Floating => return self.at.and_utc(),
+            Zone::Utc      => self.at.and_utc(),
+            Zone::Tz(tz)   => tz.from_local_datetime(&self.at).earliest()
+                .map_or_else(|| self.at.and_utc(), |t| t.with_timezone(&Utc)),
+        };
+        instant.with_timezone(&Local).naive_local().and_utc()
+    }
+}
+
+/// An IANA zone, also when written as a path (`/mozilla.org/…/Europe/Paris`).
+fn tz_named(name: &str) -> Option<Tz> {
+    let name = name.trim();
+    name.parse().ok().or_else(|| {
+        let parts: Vec<&str> = name.rsplit('/').take(2).collect();
+        parts.iter().rev().copied().collect::<Vec<_>>().join("/").parse().ok()
+    })
+}
+
+// ─── Recurrence ───────────────────────────────────────────────────────────────
+
+#[derive(Clone, Copy, PartialEq)]
+enum Freq {
+    Daily,
+    Weekly,
+    Monthly,
+    Yearly,
+}
+
+/// The RRULE parts Apple Calendar writes: FREQ, INTERVAL, COUNT, UNTIL,
+/// BYDAY (with ordinals for "second Tuesday"), BYMONTHDAY and BYMONTH.
+struct Rule {
+    freq:       Freq,
+    interval:   i64,
+    count:      Option<usize>,
+    until:      Option<NaiveDateTime>,
+    by_day:     Vec<(Option<i64>, Weekday)>,
+    month_days: Vec<i64>,
+    months:     Vec<u32>,
+}
+
+impl Rule {
+    fn parse(value: &str, start: When) -> Option<Self> {
+        let parts: HashMap<String, &str> = value.split(';')
+            .filter_map(|p| p.split_once('='))
+            .map(|(k, v)| (k.to_ascii_uppercase(), v))
+            .collect();
+        let freq = match parts.get("FREQ")?.to_ascii_uppercase().as_str() {
+            "DAILY"   => Freq::Daily,
+            "WEEKLY"  => Freq::Weekly,
+            "MONTHLY" => Freq::Monthly,
+            "YEARLY"  => Freq::Yearly,
+            _         => return None,
+        };
+        let list = |key: &str| parts.get(key).map_or_else(Vec::new, |v| v.split(',').map(str::trim).collect::<Vec<_>>());
+        let until = parts.get("UNTIL").and_then(|u| {
+            let u = When::parse_value(u, false, None, None)?;
+            // A UTC UNTIL is compared in the series' own zone
+            Some(match (u.zone, start.zone) {
+                (Zone::Utc, Zone::Tz(tz)) => u.at.and_utc().with_timezone(&tz).naive_local(),
+                _ if u.date               => u.at.date().and_time(NaiveTime::from_hms_opt(23, 59, 59)?),
+                _                         => u.at,
+            })
+        });
+        Some(Self {
+            freq,
+            interval:   parts.get("INTERVAL").and_then(|i| i.parse().ok()).unwrap_or(1).max(1),
+            count:      parts.get("COUNT").and_then(|c| c.parse().ok()),
+            until,
+            by_day:     list("BYDAY").iter().filter_map(|d| {
+                let (n, day) = d.split_at(d.len().checked_sub(2)?);
+                Some((n.parse().ok(), weekday(day)?))
+            }).collect(),
+            month_days: list("BYMONTHDAY").iter().filter_map(|d| d.parse().ok()).collect(),
+            months:     list("BYMONTH").iter().filter_map(|m| m.parse().ok()).collect(),
+        })
+    }
+
+    /// The days of the period starting `first` (a day, a week's Monday, a
+    /// month's or year's first day) the rule picks, in order.
+    fn days_in(&self, first: NaiveDate, start: NaiveDate) -> Vec<NaiveDate> {
+        let mut days = match self.freq {
+            Freq::Daily => vec![first],
+            Freq::Weekly => {
+                let picks: Vec<Weekday> = if self.by_day.is_empty() {
+                    vec![start.weekday()]
+                } else {
+                    self.by_day.iter().map(|(_, d)| *d).collect()
+                };
+                (0..7).filter_map(|i| add_days(first, i)).filter(|d| picks.contains(&d.weekday())).collect()
+            }
+            Freq::Monthly => self.days_of_month(first.year(), first.month(), start),
+            Freq::Yearly => {
+                let months = if self.months.is_empty() { vec![start.month()] } else { self.months.clone() };
+                months.iter().flat_map(|&m| self.days_of_month(first.year(), m, start)).collect()
+            }
+        };
+        if self.freq == Freq::Daily {
+            if !self.by_day.is_empty() { days.retain(|d| self.by_day.iter().any(|(_, w)| *w == d.weekday())); }
+            if !self.months.is_empty() { days.retain(|d| self.months.contains(&d.month())); }
+        }
+        days.sort();
+        days.dedup();
+        days
+    }
+
+    fn days_of_month(&self, year: i32, month: u32, start: NaiveDate) -> Vec<NaiveDate> {
+        let Some(first) = NaiveDate::from_ymd_opt(year, month, 1) else { return Vec::new() };
+        let len = crate::calendar::days_in_month(year, month) as i64;
+        let all: Vec<NaiveDate> = (0..len).filter_map(|i| add_days(first, i)).collect();
+        if !self.month_days.is_empty() {
+            return self.month_days.iter()
+                .filter_map(|&d| if d > 0 { all.get(d as usize - 1) } else { all.get((len + d) as usize) })
+                .copied()
+                .collect();
+        }
+        if !self.by_day.is_empty() {
+            return self.by_day.iter().flat_map(|&(n, wd)| {
+                let matching: Vec<NaiveDate> = all.iter().copied().filter(|d| d.weekday() == wd).collect();
+                match n {
+                    None               => matching,
+                    Some(n) if n > 0   => matching.get(n as usize - 1).copied().into_iter().collect(),
+                    Some(n)            => matching.len().checked_sub(n.unsigned_abs() as usize)
+                        .and_then(|i| matching.get(i).copied()).into_iter().collect(),
+                }
+            }).collect();
+        }
+        all.get(start.day() as usize - 1).copied().into_iter().collect()
+    }
+
+    /// Start times from `start` on, through `horizon`.
+    fn occurrences(&self, start: NaiveDateTime, horizon: NaiveDateTime) -> Vec<NaiveDateTime> {
+        let day0 = start.date();
+        let mut out = Vec::new();
+        for period in 0.. {
+            let first = match self.freq {
+                Freq::Daily   => add_days(day0, period * self.interval),
+                Freq::Weekly  => add_days(day0, period * 7 * self.interval - day0.weekday().num_days_from_monday() as i64),
+                Freq::Monthly => {
+                    let m = day0.month0() as i64 + period * self.interval;
+                    NaiveDate::from_ymd_opt(day0.year() + (m / 12) as i32, (m % 12) as u32 + 1, 1)
+                }
+                Freq::Yearly  => NaiveDate::from_ymd_opt(day0.year() + (period * self.interval) as i32, 1, 1),
+            };
+            let Some(first) = first else { break };
+            if first.and_time(NaiveTime::MIN) > horizon { break; }
+            for day in self.days_in(first, day0) {
+                let at = day.and_time(start.time());
+                if at < start { continue; }
+                if at > horizon || self.until.is_some_and(|u| at > u) { return out; }
+                out.push(at);
+                if self.count.is_some_and(|c| out.len() >= c) || out.len() >= MAX_OCCURRENCES { return out; }
+            }
+        }
+        out
+    }
+}
+
+fn weekday(s: &str) -> Option<Weekday> {
+    Some(match s.to_ascii_uppercase().as_str() {
+        "MO" => Weekday::Mon,
+        "TU" => Weekday::Tue,
+        "WE" => Weekday::Wed,
+        "TH" => Weekday::Thu,
+        "FR" => Weekday::Fri,
+        "SA" => Weekday::Sat,
+        "SU" => Weekday::Sun,
+        _    => return None,
+    })
+}
+
+// ─── Reading ──────────────────────────────────────────────────────────────────
+
+/// One VEVENT's unfolded lines, nested blocks (VALARM) left out.
+struct Component {
+    lines: Vec<String>,
+}
+
+impl Component {
+    fn props(&self) -> Vec<Prop<'_>> {
+        self.lines.iter().filter_map(|l| ics::parse_prop(l)).collect()
+    }
+}
+
+/// Title, description (with the location ahead of it) and times of one
+/// VEVENT, as an event here.
+fn convert(props: &[Prop], start: When, calendar: Option<Tz>) -> Event {
+    let get   = |name: &str| props.iter().find(|p| p.name == name);
+    let title = get("SUMMARY").map(|p| ics::unescape_with(p.value, ' ')).unwrap_or_else(|| "(no title)".into());
+    let end   = match (get("DTEND").and_then(|p| When::parse(p, calendar)), get("DURATION").and_then(|p| ics::parse_duration(p.value))) {
+        (Some(end), _)              => end.wall(),
+        (None, Some(length))        => start.wall() + length,
+        (None, None) if start.date  => start.wall() + Duration::days(1),
+        (None, None)                => start.wall(),
+    };
+    let mut ev = Event::new(title.trim(), start.wall(), end.max(start.wall()));
+    ev.all_day = start.date;
+    ev.dirty   = false;
+    let location    = get("LOCATION").map(|p| ics::unescape_with(p.value, ' ')).filter(|l| !l.trim().is_empty());
+    let description = get("DESCRIPTION").map(|p| ics::unescape_with(p.value, '\n')).filter(|d| !d.trim().is_empty());
+    ev.description  = match (location, description) {
+        (Some(l), Some(d)) => Some(format!("Location: {l}\n\n{d}")),
+        (Some(l), None)    => Some(format!("Location: {l}")),
+        (None, d)          => d,
+    };
+    ev
+}
+
+/// Every event in `text`, repeats expanded through `horizon` days from now.
+pub fn parse(text: &str, horizon: i64) -> Export {
+    let lines = ics::unfold(text);
+    let header = |name: &str| lines.iter()
+        .take_while(|l| !l.trim().eq_ignore_ascii_case("BEGIN:VEVENT"))
+        .filter_map(|l| ics::parse_prop(l))
+        .find(|p| p.name == name)
+        .map(|p| ics::unescape_with(p.value, ' ').trim().to_owned());
+    let calendar = header("X-WR-TIMEZONE").as_deref().and_then(tz_named);
+
+    // VEVENTs only: VTIMEZONE / VALARM blocks nest, so depth is tracked
+    let mut components = Vec::new();
+    let mut current: Option<Component> = None;
+    let mut depth = 0;
+    for line in lines.iter() {
+        let upper = line.trim().to_ascii_uppercase();
+        if upper == "BEGIN:VEVENT" {
+            current = Some(Component { lines: Vec::new() });
+            depth = 0;
+        } else if upper == "END:VEVENT" {
+            components.extend(current.take());
+        } else if let Some(ref mut c) = current {
+            if upper.starts_with("BEGIN:") { depth += 1; }
+            if depth == 0 { c.lines.push(line.clone()); }
+            if upper.starts_with("END:") { depth -= 1; }
+        }
+    }
+
+    // Moved or cancelled occurrences, by series and their original start
+    let mut overrides: HashMap<(String, DateTime<Utc>), Option<Event>> = HashMap::new();
+    let mut masters = Vec::new();
+    for c in &components {
+        let props = c.props();
+        let get   = |name: &str| props.iter().find(|p| p.name == name);
+        let uid   = get("UID").map(|p| p.value.trim().to_owned()).unwrap_or_default();
+        let cancelled = get("STATUS").is_some_and(|p| p.value.trim().eq_ignore_ascii_case("CANCELLED"));
+        match get("RECURRENCE-ID").and_then(|p| When::parse(p, calendar)) {
+            Some(original) => {
+                let replacement = get("DTSTART").and_then(|p| When::parse(p, calendar))
+                    .filter(|_| !cancelled)
+                    .map(|start| convert(&props, start, calendar));
+                overrides.insert((uid, original.wall()), replacement);
+            }
+            None if !cancelled => masters.push(c),
+            None => {}
+        }
+    }
+
+    let mut export = Export { name: header("X-WR-CALNAME").filter(|n| !n.is_empty()), ..Default::default() };
+    let until = Local::now().naive_local() + Duration::days(horizon);
+    for c in masters {
+        let props = c.props();
+        let get   = |name: &str| props.iter().find(|p| p.name == name);
+        let Some(start) = get("DTSTART").and_then(|p| When::parse(p, calendar)) else { continue };
+        let first = convert(&props, start, calendar);
+        let uid  = get("UID").map(|p| p.value.trim().to_owned()).unwrap_or_default();
+        let Some(rule) = get("RRULE").and_then(|p| Rule::parse(p.value, start)) else {
+            export.events.push(first);
+            continue;
+        };
+
+        let skip: HashSet<DateTime<Utc>> = props.iter().filter(|p| p.name == "EXDATE")
+            .flat_map(|p| {
+                let (date, tzid) = (p.param("VALUE") == Some("DATE"), p.param("TZID"));
+                p.value.split(',').filter_map(move |v| When::parse_value(v.trim(), date, tzid, calendar)).map(When::wall)
+            })
+            .collect();
+        let length = first.end - first.start;
+        // The horizon in the series' own clock, near enough
+        let horizon = match start.zone {
+            Zone::Tz(tz) => Local::now().with_timezone(&tz).naive_local() + Duration::days(horizon),
+            _            => until,
+        };
+        export.series += 1;
+        for at in rule.occurrences(start.at, horizon) {
+            let wall = start.with(at).wall();
+            if skip.contains(&wall) { continue; }
+            match overrides.remove(&(uid.clone(), wall)) {
+                Some(Some(moved)) => export.events.push(moved),
+                Some(None)        => {}
+                None              => {
+                    let mut ev = first.clone();
+                    ev.id    = uuid::Uuid::new_v4().to_string();
+                    ev.start = wall;
+                    ev.end   = wall + length;
+                    export.events.push(ev);
+                }
+            }
+        }
+    }
+    // Moved occurrences whose original isn't in the expansion still happened
+    export.events.extend(overrides.into_values().flatten());
+    export
+}
@@ -0,0 +1,470 @@
+//! Bringing in what a new user kept elsewhere: khal or vdirsyncer calendars
+//! (folders of `.ics` files), calcurse's appointments and todos, and the
+//! `~/.lifemanager` folder early builds kept their database in. Offered on
+//! the first run, before the database exists, and any time with `lm import`.
+//! Emacs org agenda files, todo.txt lists and whole-calendar `.ics` exports
+//! (macOS Calendar's, see `apple`) aren't looked for; they're named, as in
+//! `lm import ~/org/agenda.org` or `lm import --apple Home.ics`.
+//!
+//! Imported events and tasks are local: nothing goes to Google unless one
+//! is edited later. Repeating calcurse appointments come in as their first
+//! occurrence, and recurrence rules in vdir `.ics` files aren't expanded (as
+//! for feeds, see `sync::ics`); an export's are.
+
+mod apple;
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Duration, Local, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Utc};
+use regex::Regex;
+use std::collections::HashSet;
+use std::io::{BufRead, Write};
+use std::path::{Path, PathBuf};
+
+use crate::db::{Database, Event, Task};
+use crate::export::expand;
+use crate::sync::ics;
+
+pub enum Source {
+    /// One calendar: a folder of `.ics` files, one event each.
+    Vdir { name: String, path: PathBuf },
+    /// calcurse's data folder, holding `apts` and `todo`.
+    Calcurse(PathBuf),
+    /// A `lifemanager.db` from before the XDG layout.
+    Legacy(PathBuf),
+    /// An Emacs org file: TODO headings and timestamped ones.
+    Org(PathBuf),
+    /// A todo.txt list.
+    TodoTxt(PathBuf),
+    /// A calendar exported whole to one `.ics` file, as macOS Calendar does.
+    Apple(PathBuf),
+}
+
+impl Source {
+    pub fn describe(&self) -> String {
+        match self {
+            Self::Vdir { name, path } => format!("calendar “{name}”  {}", tilde(path)),
+            Self::Calcurse(path)      => format!("calcurse  {}", tilde(path)),
+            Self::Legacy(path)        => format!("LifeManager database  {}", tilde(path)),
+            Self::Org(path)           => format!("org file  {}", tilde(path)),
+            Self::TodoTxt(path)       => format!("todo.txt  {}", tilde(path)),
+            Self::Apple(path)         => format!("calendar export  {}", tilde(path)),
+        }
+    }
+
+    /// A file named on the command line: `.org` is org, `.txt` todo.txt,
+    /// `.ics` a calendar export.
+    pub fn from_file(path: &Path) -> Option<Self> {
+        let ext = path.extension()?.to_string_lossy().to_lowercase();
+        match ext.as_str() {
+            "org" => Some(Self::Org(path.to_path_buf())),
+            "txt" => Some(Self::TodoTxt(path.to_path_buf())),
+            "ics" => Some(Self::Apple(path.to_path_buf())),
+            _     => None,
+        }
+    }
+}
+
+fn tilde(path: &Path) -> String {
+    match dirs::home_dir().and_then(|h| path.strip_prefix(h).ok().map(Path::to_path_buf)) {
+        Some(rest) => format!("~/{}", rest.display()),
+        None       => path.display().to_string(),
+    }
+}
+
+// ─── Detection ────────────────────────────────────────────────────────────────
+
+/// Everything found in the usual places, and wherever khal's and
+/// vdirsyncer's configs point.
+pub fn detect() -> Vec<Source> {
+    let (Some(home), Some(config)) = (dirs::home_dir(), dirs::config_dir()) else { return Vec::new() };
+    let data = dirs::data_dir().unwrap_or_else(|| home.join(".local/share"));
+    let mut found = Vec::new();
+
+    let mut roots: Vec<PathBuf> = ["khal/config", "vdirsyncer/config"].iter()
+        .filter_map(|f| std::fs::read_to_string(config.join(f)).ok())
+        .flat_map(|text| config_paths(&text))
+        .collect();
+    roots.extend([data.join("khal/calendars"), home.join(".calendars")]);
+    let mut seen = HashSet::new();
+    for root in roots {
+        for path in vdirs(&root) {
+            let Ok(real) = path.canonicalize() else { continue };
+            if !seen.insert(real) { continue; }
+            let name = path.file_name().map_or_else(|| "calendar".into(), |n| n.to_string_lossy().into_owned());
+            found.push(Source::Vdir { name, path });
+        }
+    }
+
+    if let Some(dir) = [data.join("calcurse"), home.join(".calcurse")].into_iter().find(|d| d.join("apts").is_file()) {
+        found.push(Source::Calcurse(dir));
+    }
+    let legacy = home.join(".lifemanager/lifemanager.db");
+    if legacy.is_file() {
+        found.push(Source::Legacy(legacy));
+    }
+    found
+}
+
+/// `path = …` lines of a khal or vdirsyncer config; a trailing `*` (khal's
+/// discover type) stands for the folder.
+fn config_paths(text: &str) -> Vec<PathBuf> {
+    text.lines().filter_map(|line| {
+        let (key, value) = line.split_once('=')?;
+        if key.trim() != "path" { return None; }
+        let value = value.trim().trim_matches('"').trim_end_matches('*').trim_end_matches('/');
+        Some(expand(value))
+    }).collect()
+}
+
+/// `root` if it holds `.ics` files, else the folders in it that do.
+fn vdirs(root: &Path) -> Vec<PathBuf> {
+    if has_ics(root) { return vec![root.to_path_buf()]; }
+    let Ok(entries) = std::fs::read_dir(root) else { return Vec::new() };
+    let mut dirs: Vec<PathBuf> = entries.flatten().map(|e| e.path()).filter(|p| has_ics(p)).collect();
+    dirs.sort();
+    dirs
+}
+
+fn has_ics(dir: &Path) -> bool {
+    std::fs::read_dir(dir).is_ok_and(|mut es| es.any(|e| e.is_ok_and(|e| is_ics(&e.path()))))
+}
+
+fn is_ics(path: &Path) -> bool {
+    path.extension().is_some_and(|x| x.eq_ignore_ascii_case("ics"))
+}
+
+// ─── Asking ───────────────────────────────────────────────────────────────────
+
+/// Lists `found` and asks on the terminal; false on anything but yes.
+pub fn confirm(found: &[Source]) -> Result<bool> {
+    println!("\nFound calendar data from another app:\n");
+    for s in found { println!("  {}", s.describe()); }
+    print!("\nImport it into LifeManager? Imported events stay on this machine until you edit them. [Y/n] ");
+    std::io::stdout().flush()?;
+    let mut answer = String::new();
+    std::io::stdin().lock().read_line(&mut answer)?;
+    Ok(matches!(answer.trim().to_lowercase().as_str(), "" | "y" | "yes"))
+}
+
+/// Copies a legacy database into place; only before the first `connect`,
+/// so nothing is overwritten. The original stays where it was.
+pub fn adopt(legacy: &Path) -> Result<()> {
+    let to = Database::file();
+    if to.exists() { anyhow::bail!("{} already exists", to.display()); }
+    std::fs::create_dir_all(to.parent().unwrap_or(Path::new(".")))?;
+    std::fs::copy(legacy, &to).with_context(|| format!("copying {}", legacy.display()))?;
+    println!("  Moved in {} (the original is untouched)", tilde(legacy));
+    Ok(())
+}
+
+// ─── Importing ────────────────────────────────────────────────────────────────
+
+#[derive(Default)]
+struct Loaded {
+    events:    Vec<Event>,
+    tasks:     Vec<Task>,
+    /// calcurse appointments that repeat, brought in once.
+    repeating: usize,
+    /// Repeating series in an export, brought in occurrence by occurrence.
+    series:    usize,
+}
+
+/// Writes the events and tasks of every source but `Legacy` (see `adopt`),
+/// printing what each brought, then merges exact copies of events (and
+/// skips tasks by title) so importing twice changes nothing.
+pub async fn run(db: &Database, sources: &[Source]) -> Result<()> {
+    let mut titles: HashSet<String> = db.all_tasks().await?.into_iter().map(|t| t.title).collect();
+    for source in sources {
+        let loaded = match source {
+            Source::Vdir { name, path } => load_vdir(name, path),
+            Source::Calcurse(dir)       => load_calcurse(dir),
+            Source::Org(path)           => load_org(path),
+            Source::TodoTxt(path)       => load_todotxt(path),
+            Source::Apple(path)         => load_apple(path),
+            Source::Legacy(_)           => continue,
+        };
+        let mut loaded = match loaded {
+            Ok(l)  => l,
+            Err(e) => {
+                println!("  ✗ {}: {e:#}", source.describe());
+                continue;
+            }
+        };
+        loaded.tasks.retain(|t| titles.insert(t.title.clone()));
+        for ev in &loaded.events { db.upsert_event(ev).await?; }
+        for t in &loaded.tasks { db.upsert_task(t).await?; }
+        let n = |n: usize, what: &str| format!("{n} {what}{}", if n == 1 { "" } else { "s" });
+        let mut line = format!("  ✓ {}: {}", source.describe(), n(loaded.events.len(), "event"));
+        if !loaded.tasks.is_empty() { line.push_str(&format!(", {}", n(loaded.tasks.len(), "task"))); }
+        if loaded.repeating > 0 { line.push_str(&format!(" ({} repeating, as their first time)", loaded.repeating)); }
+        if loaded.series > 0 { line.push_str(&format!(" ({} repeating series, through the next year)", loaded.series)); }
+        println!("{line}");
+    }
+    let merged = db.dedupe().await?;
+    if !merged.is_empty() {
+        println!("  Merged {} already here", merged.len());
+    }
+    Ok(())
+}
+
+/// Not dirty, so nothing is pushed until the user edits it.
+fn local_event(title: &str, start: DateTime<Utc>, end: DateTime<Utc>, all_day: bool) -> Event {
+    let mut ev = Event::new(title, start, end);
+    ev.all_day = all_day;
+    ev.dirty   = false;
+    ev
+}
+
+fn load_vdir(name: &str, dir: &Path) -> Result<Loaded> {
+    let tag = name.trim().to_lowercase().replace(char::is_whitespace, "-");
+    let mut loaded = Loaded::default();
+    for entry in std::fs::read_dir(dir)?.flatten() {
+        let path = entry.path();
+        if !is_ics(&path) { continue; }
+        let Ok(text) = std::fs::read_to_string(&path) else { continue };
+        let (events, _) = ics::parse(&text, name);
+        for fe in events {
+            let mut ev = local_event(&fe.title, fe.start, fe.end, fe.all_day);
+            ev.tag = Some(tag.clone()).filter(|t| !t.is_empty());
+            loaded.events.push(ev);
+        }
+    }
+    Ok(loaded)
+}
+
+/// `apts` lines are `MM/DD/YYYY @ HH:MM -> MM/DD/YYYY @ HH:MM |text` for
+/// appointments and `MM/DD/YYYY [n] |text` for all-day events, maybe with a
+/// `{repeat}` and a `>note` before the text (`!` instead of `|` flags an
+/// alarm). `todo` lines are `[priority]>note text`, negative when done.
+fn load_calcurse(dir: &Path) -> Result<Loaded> {
+    let apt = Regex::new(
+        r"^(\d\d/\d\d/\d{4}) (?:@ (\d\d:\d\d) -> (\d\d/\d\d/\d{4}) @ (\d\d:\d\d)|\[\d+\])\s*(\{[^}]*\})?\s*(?:>([0-9a-f]+))?\s*[|!](.*)$",
+    )?;
+    let todo = Regex::new(r"^\[(-?\d+)\](?:>([0-9a-f]+))?\s?(.*)$")?;
+    let note = |hash: Option<regex::Match>| hash
+        .and_then(|h| std::fs::read_to_string(dir.join("notes").join(h.as_str())).ok())
+        .map(|n| n.trim().to_owned())
+        .filter(|n| !n.is_empty());
+    let date = |s: &str| NaiveDate::parse_from_str(s, "%m/%d/%Y").ok();
+    let local = |d: NaiveDate, t: &str| {
+        let n = NaiveDateTime::new(d, chrono::NaiveTime::parse_from_str(t, "%H:%M").ok()?);
+        Local.from_local_datetime(&n).earliest().map(|l| l.with_timezone(&Utc))
+    };
+
+    let mut loaded = Loaded::default();
+    let apts = std::fs::read_to_string(dir.join("apts")).with_context(|| format!("reading {}/apts", dir.display()))?;
+    for line in apts.lines() {
+        let Some(c) = apt.captures(line) else { continue };
+        let Some(day) = date(&c[1]) else { continue };
+        let title = c[7].trim();
+        let mut ev = match (c.get(2), c.get(3), c.get(4)) {
+            (Some(from), Some(to_day), Some(to)) => {
+                let start = local(day, from.as_str());
+                let end   = date(to_day.as_str()).and_then(|d| local(d, to.as_str()));
+                let (Some(start), Some(end)) = (start, end) else { continue };
+                local_event(title, start, end.max(start), false)
+            }
+            _ => {
+                let Some(start) = day.and_hms_opt(0, 0, 0).map(|d| d.and_utc()) else { continue };
+                local_event(title, start, start + Duration::days(1), true)
+            }
+        };
+        ev.description = note(c.get(6));
+        if c.get(5).is_some() { loaded.repeating += 1; }
+        loaded.events.push(ev);
+    }
+
+    if let Ok(todos) = std::fs::read_to_string(dir.join("todo")) {
+        for line in todos.lines() {
+            let Some(c) = todo.captures(line) else { continue };
+            let level: i64 = c[1].parse().unwrap_or(0);
+            let mut t = Task::new(c[3].trim());
+            t.completed = level < 0;
+            // calcurse: 1 is the most urgent, 9 the least, 0 none
+            t.priority  = match level.abs() {
+                0     => 0,
+                1..=3 => 3,
+                4..=6 => 2,
+                _     => 1,
+            };
+            t.notes = note(c.get(2));
+            t.dirty = false;
+            loaded.tasks.push(t);
+        }
+    }
+    Ok(loaded)
+}
+
+/// `A` is the top priority (3) down to `C` (1); anything lower is none.
+fn letter_priority(letter: &str) -> i64 {
+    match letter {
+        "A" => 3,
+        "B" => 2,
+        "C" => 1,
+        _   => 0,
+    }
+}
+
+/// Headings with TODO or DONE are tasks, due at their DEADLINE (else when
+/// SCHEDULED), with `[#A]` priorities and `:tags:`. Other headings with an
+/// active timestamp, in the heading or as SCHEDULED, are events: a date
+/// alone is all day, a time without an end lasts an hour. The text under
+/// a heading becomes the notes; property drawers and CLOSED lines don't.
+fn load_org(path: &Path) -> Result<Loaded> {
+    let heading = Regex::new(r"^\*+\s+(?:(TODO|DONE)\s+)?(?:\[#([A-Z])\]\s+)?(.*?)(?:\s+(:[^\s:]+(?::[^\s:]+)*:))?\s*$")?;
+    let stamp   = Regex::new(
+        r"<(\d{4}-\d\d-\d\d)(?: [^\d\s>]+)?(?: (\d\d?:\d\d)(?:-(\d\d?:\d\d))?)?[^>]*>(?:--<(\d{4}-\d\d-\d\d)(?: [^\d\s>]+)?(?: (\d\d?:\d\d))?[^>]*>)?",
+    )?;
+    let text = std::fs::read_to_string(path).with_context(|| format!("reading {}", path.display()))?;
+
+    struct Entry<'a> {
+        keyword:   Option<String>,
+        priority:  Option<String>,
+        title:     String,
+        tags:      Vec<String>,
+        when:      Option<regex::Captures<'a>>,
+        scheduled: Option<regex::Captures<'a>>,
+        deadline:  Option<regex::Captures<'a>>,
+        body:      Vec<&'a str>,
+    }
+    let mut entries: Vec<Entry> = Vec::new();
+    let mut drawer = false;
+    for line in text.lines() {
+        if let Some(c) = line.starts_with('*').then(|| heading.captures(line)).flatten() {
+            let title = c.get(3).map_or("", |m| m.as_str());
+            let when  = stamp.captures(title);
+            let title = stamp.replace_all(title, "").split_whitespace().collect::<Vec<_>>().join(" ");
+            entries.push(Entry {
+                keyword:  c.get(1).map(|m| m.as_str().to_owned()),
+                priority: c.get(2).map(|m| m.as_str().to_owned()),
+                title,
+                tags:     c.get(4).map_or_else(Vec::new, |m| m.as_str().split(':').filter(|t| !t.is_empty()).map(str::to_owned).collect()),
+                when, scheduled: None, deadline: None, body: Vec::new(),
+            });
+            drawer = false;
+            continue;
+        }
+        let Some(entry) = entries.last_mut() else { continue };
+        let trimmed = line.trim();
+        if drawer {
+            drawer = !trimmed.eq_ignore_ascii_case(":END:");
+            continue;
+        }
+        if trimmed.starts_with(':') && trimmed.ends_with(':') && trimmed.len() > 1 {
+            drawer = true;
+            continue;
+        }
+        let planning = ["SCHEDULED:", "DEADLINE:", "CLOSED:"].iter().any(|k| trimmed.contains(k));
+        if planning {
+            for part in trimmed.split_inclusive('>') {
+                if part.contains("SCHEDULED:") { entry.scheduled = stamp.captures(part); }
+                if part.contains("DEADLINE:")  { entry.deadline  = stamp.captures(part); }
+            }
+        } else if entry.when.is_none() && entry.keyword.is_none() && stamp.is_match(trimmed) && stamp.replace_all(trimmed, "").trim().is_empty() {
+            entry.when = stamp.captures(line);
+        } else {
+            entry.body.push(line);
+        }
+    }
+
+    let date = |m: Option<regex::Match>| m.and_then(|m| NaiveDate::parse_from_str(m.as_str(), "%Y-%m-%d").ok());
+    let time = |m: Option<regex::Match>| m.and_then(|m| NaiveTime::parse_from_str(m.as_str(), "%H:%M").ok());
+    let mut loaded = Loaded::default();
+    for entry in entries {
+        let notes = entry.body.iter().map(|l| l.trim()).collect::<Vec<_>>().join("\n").trim().to_owned();
+        let notes = Some(notes).filter(|n| !n.is_empty());
+        if entry.title.is_empty() { continue; }
+        if let Some(keyword) = entry.keyword {
+            let mut t = Task::new(&entry.title);
+            t.completed = keyword == "DONE";
+            t.priority  = entry.priority.as_deref().map_or(0, letter_priority);
+            t.tags      = entry.tags;
+            t.due       = entry.deadline.as_ref().or(entry.scheduled.as_ref())
+                .and_then(|c| Some(date(c.get(1))?.and_time(time(c.get(2)).unwrap_or(NaiveTime::MIN)).and_utc()));
+            t.notes = notes;
+            t.dirty = false;
+            loaded.tasks.push(t);
+            continue;
+        }
+        let Some(c) = entry.when.as_ref().or(entry.scheduled.as_ref()) else { continue };
+        let Some(day) = date(c.get(1)) else { continue };
+        let last = date(c.get(4)).unwrap_or(day).max(day);
+        let mut ev = match time(c.get(2)) {
+            None => local_event(
+                &entry.title,
+                day.and_time(NaiveTime::MIN).and_utc(),
+                (last + Duration::days(1)).and_time(NaiveTime::MIN).and_utc(),
+                true,
+            ),
+            Some(from) => {
+                let start = day.and_time(from).and_utc();
+                let end   = match (time(c.get(3)), time(c.get(5))) {
+                    (Some(to), _)    => day.and_time(to).and_utc(),
+                    (None, Some(to)) => last.and_time(to).and_utc(),
+                    (None, None)     => start + Duration::hours(1),
+                };
+                local_event(&entry.title, start, end.max(start), false)
+            }
+        };
+        ev.tag         = entry.tags.into_iter().next();
+        ev.description = notes;
+        loaded.events.push(ev);
+    }
+    Ok(loaded)
+}
+
+/// Events tagged with the calendar's name, like a vdir's.
+fn load_apple(path: &Path) -> Result<Loaded> {
+    let text   = std::fs::read_to_string(path).with_context(|| format!("reading {}", path.display()))?;
+    let export = apple::parse(&text, 365);
+    let tag    = export.name.map(|n| n.to_lowercase().replace(char::is_whitespace, "-"));
+    let mut loaded = Loaded { events: export.events, series: export.series, ..Default::default() };
+    for ev in &mut loaded.events { ev.tag = tag.clone(); }
+    Ok(loaded)
+}
+
+/// One task a line: `x` and a date when done, `(A)` priority (or `pri:A`),
+/// an optional creation date, then the title with `+project` and `@context`
+/// words as tags and `due:YYYY-MM-DD`.
+fn load_todotxt(path: &Path) -> Result<Loaded> {
+    let text = std::fs::read_to_string(path).with_context(|| format!("reading {}", path.display()))?;
+    let is_date = |w: &str| NaiveDate::parse_from_str(w, "%Y-%m-%d").is_ok();
+    let mut loaded = Loaded::default();
+    for line in text.lines() {
+        let mut words: Vec<&str> = line.split_whitespace().collect();
+        if words.is_empty() { continue; }
+        let completed = words[0] == "x";
+        if completed {
+            words.remove(0);
+            // Completion date, then maybe a creation date
+            if words.first().is_some_and(|w| is_date(w)) { words.remove(0); }
+        }
+        let mut priority = 0;
+        if let Some(p) = words.first().and_then(|w| w.strip_prefix('(')?.strip_suffix(')')).filter(|p| p.len() == 1) {
+            priority = letter_priority(p);
+            words.remove(0);
+        }
+        if words.first().is_some_and(|w| is_date(w)) { words.remove(0); }
+
+        let mut t = Task::new("");
+        let mut title = Vec::new();
+        for w in words {
+            if let Some(tag) = w.strip_prefix('+').or_else(|| w.strip_prefix('@')).filter(|t| !t.is_empty()) {
+                t.tags.push(tag.to_owned());
+            } else if let Some(due) = w.strip_prefix("due:").and_then(|d| NaiveDate::parse_from_str(d, "%Y-%m-%d").ok()) {
+                t.due = Some(due.and_time(NaiveTime::MIN).and_utc());
+            } else if let Some(p) = w.strip_prefix("pri:") {
+                priority = letter_priority(p);
+            } else {
+                title.push(w);
+            }
+        }
+        if title.is_empty() { continue; }
+        t.title     = title.join(" ");
+        t.completed = completed;
+        t.priority  = priority;
+        t.dirty     = false;
+        loaded.tasks.push(t);
+    }
+    Ok(loaded)
+}
@@ -0,0 +1,23 @@
+//! The part of LifeManager that needs no terminal, database or async
+//! runtime: the data model, calendar and DST arithmetic, holidays, habit
+//! and task recurrence, the agenda order, duplicate detection, the export
+//! formats, the day's digest and the status line.
+//!
+//! The `lm` binary (the `tui` feature, on by default) builds on it. With
+//! `--no-default-features` only this is built, and it targets wasm32 as
+//! well, so a web viewer of exported data can share the date logic with
+//! the TUI rather than reimplement it. Storage stays on the binary's side:
+//! everything here works on slices of `model` values, however they were
+//! loaded.
+
+pub mod agenda;
+pub mod attendance;
+pub mod calendar;
+pub mod dedupe;
+pub mod digest;
+pub mod export;
+pub mod habits;
+pub mod holidays;
+pub mod model;
+pub mod status;
+pub mod tasks;
@@ -0,0 +1,131 @@
+//! The TUI's log: one `lifemanager.log.YYYY-MM-DD` file per day in the data
+//! dir, pruned to `[logs] keep`, and read back by `lm logs` and the `O`
+//! viewer. Commands other than the TUI log to stderr instead.
+
+use anyhow::Result;
+use std::path::PathBuf;
+use tracing_appender::non_blocking::WorkerGuard;
+use tracing_appender::rolling::{RollingFileAppender, Rotation};
+use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt, EnvFilter};
+
+use crate::config::LogConfig;
+
+const PREFIX: &str = "lifemanager.log";
+
+pub fn dir() -> PathBuf {
+    dirs::data_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join("lifemanager")
+}
+
+/// Starts file logging. Lines are buffered until the guard drops, so keep it
+/// alive for as long as the app runs.
+pub fn init(cfg: &LogConfig) -> Result<WorkerGuard> {
+    let dir = dir();
+    std::fs::create_dir_all(&dir)?;
+    let appender = RollingFileAppender::builder()
+        .rotation(Rotation::DAILY)
+        .filename_prefix(PREFIX)
+        .max_log_files(cfg.keep.max(1))
+        .build(&dir)?;
+    let (writer, guard) = tracing_appender::non_blocking(appender);
+    let filter = EnvFilter::try_from_default_env()
+        .or_else(|_| EnvFilter::try_new(&cfg.level))
+        .unwrap_or_else(|_| EnvFilter::new("info"));
+    tracing_subscriber::registry()
+        .with(filter)
+        .with(tracing_subscriber::fmt::layer().with_writer(writer).with_ansi(false))
+        .init();
+    Ok(guard)
+}
+
+// ─── Reading ──────────────────────────────────────────────────────────────────
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Level {
+    Trace,
+    Debug,
+    Info,
+    Warn,
+    Error,
+}
+
+impl Level {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "trace" => Some(Self::Trace),
+            "debug" => Some(Self::Debug),
+            "info"  => Some(Self::Info),
+            "warn"  => Some(Self::Warn),
+            "error" => Some(Self::Error),
+            _       => None,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Trace => "trace",
+            Self::Debug => "debug",
+            Self::Info  => "info",
+            Self::Warn  => "warn",
+            Self::Error => "error",
+        }
+    }
+
+    /// The viewer's `l`: info → warn → error → trace → debug → info.
+    pub fn next(self) -> Self {
+        match self {
+            Self::Trace => Self::Debug,
+            Self::Debug => Self::Info,
+            Self::Info  => Self::Warn,
+            Self::Warn  => Self::Error,
+            Self::Error => Self::Trace,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct LogLine {
+    pub level: Level,
+    pub text:  String,
+}
+
+/// The last `tail` lines at `min` or above, oldest first, across every kept
+/// file. Continuation lines (a multi-line error, say) take the level of the
+/// line they follow.
+pub fn read(min: Level, tail: usize) -> Result<Vec<LogLine>> {
+    let mut files: Vec<PathBuf> = match std::fs::read_dir(dir()) {
+        Ok(entries) => entries
+            .filter_map(|e| e.ok())
+            .filter(|e| e.file_name().to_string_lossy().starts_with(PREFIX))
+            .map(|e| e.path())
+            .collect(),
+        Err(_) => return Ok(Vec::new()),
+    };
+    // The date suffix sorts oldest first
+    files.sort();
+
+    // Newest file first, stopping once there's enough
+    let ansi = regex::Regex::new(r"\x1b\[[0-9;]*m")?;
+    let mut out: Vec<LogLine> = Vec::new();
+    for path in files.iter().rev() {
+        let raw = std::fs::read_to_string(path)?;
+        let mut level = Level::Info;
+        let mut lines: Vec<LogLine> = Vec::new();
+        for line in raw.lines() {
+            // Files from before `with_ansi(false)` carry colour codes
+            let text = ansi.replace_all(line, "").into_owned();
+            if let Some(l) = text.split_whitespace().nth(1).and_then(Level::parse) {
+                level = l;
+            }
+            if level >= min && !text.trim().is_empty() {
+                lines.push(LogLine { level, text });
+            }
+        }
+        lines.append(&mut out);
+        out = lines;
+        if out.len() >= tail { break; }
+    }
+    let skip = out.len().saturating_sub(tail);
+    Ok(out.split_off(skip))
+}
@@ -1,17 +1,33 @@
 mod app;
-mod calendar;
 mod config;
 mod db;
+mod focus;
+mod import;
+mod logs;
+mod mcp;
+mod notify;
+mod planner;
+mod plugins;
+mod pto;
+mod review;
+mod serve;
 mod sync;
-mod tasks;
 mod theme;
+mod timelog;
 mod ui;
+mod weather;
+
+use lifemanager::{agenda, attendance, calendar, dedupe, digest, export, habits, holidays, model, status, tasks};
 
 use anyhow::{anyhow, Result};
+use chrono::Datelike;
 use app::App;
-use config::AppConfig;
-use db::Database;
-use sync::google::GoogleCalendarClient;
+use config::{AppConfig, BackupConfig, SyncConfig};
+use db::{backup, cloud, journal::{self, Journal}, Database};
+use sync::google::{Access, GoogleCalendarClient, GoogleConfig};
+use sync::audit;
+use sync::outlook::OutlookClient;
+use sync::titles::TitleRules;
 use sync::worker::SyncWorker;
 use theme::ThemeConfig;
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
@@ -20,16 +36,189 @@ use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 async fn main() -> Result<()> {
     let args: Vec<String> = std::env::args().collect();
 
-    // ── lm auth google ────────────────────────────────────────────────────────
+    // ── lm auth google [--device] [--client-id ID [--client-secret S]] ────────
+    // ── lm auth google --read-only ────────────────────────────────────────────
+    // ── lm auth google --revoke [--clear-sync-ids] ────────────────────────────
     if args.get(1).map(|s| s.as_str()) == Some("auth")
         && args.get(2).map(|s| s.as_str()) == Some("google")
     {
-        return cmd_auth_google().await;
+        let flag  = |f: &str| args.iter().skip(3).any(|a| a == f);
+        let value = |f: &str| args.iter().skip(3).position(|a| a == f)
+            .and_then(|i| args.get(i + 4))
+            .cloned();
+        if flag("--revoke") {
+            return cmd_auth_revoke(flag("--clear-sync-ids")).await;
+        }
+        let client = match (value("--client-id"), value("--client-secret")) {
+            (Some(id), secret) => Some((id, secret)),
+            (None, Some(_))    => return Err(anyhow!("--client-secret needs --client-id")),
+            (None, None)       => None,
+        };
+        return cmd_auth_google(flag("--device"), client, flag("--read-only")).await;
+    }
+
+    // ── lm auth outlook [--revoke] ────────────────────────────────────────────
+    if args.get(1).map(|s| s.as_str()) == Some("auth")
+        && args.get(2).map(|s| s.as_str()) == Some("outlook")
+    {
+        return cmd_auth_outlook(args.iter().skip(3).any(|a| a == "--revoke")).await;
     }
 
-    // ── lm sync ───────────────────────────────────────────────────────────────
+    // ── lm sync [--dry-run] ───────────────────────────────────────────────────
+    // ── lm sync log [N] ───────────────────────────────────────────────────────
     if args.get(1).map(|s| s.as_str()) == Some("sync") {
-        return cmd_sync().await;
+        return match args.get(2).map(|s| s.as_str()) {
+            None              => cmd_sync().await,
+            Some("--dry-run") => cmd_sync_dry_run().await,
+            Some("log")       => {
+                let n = args.get(3)
+                    .map(|n| n.parse::<i64>().map_err(|_| anyhow!("Invalid count: {n}")))
+                    .transpose()?;
+                cmd_sync_log(n.unwrap_or(50)).await
+            }
+            _ => Err(anyhow!("Usage: lm sync [--dry-run] | lm sync log [N]")),
+        };
+    }
+
+    // ── lm backup / lm restore [file] ─────────────────────────────────────────
+    // ── lm restore --from-remote [name | latest] ──────────────────────────────
+    if args.get(1).map(|s| s.as_str()) == Some("backup") {
+        return cmd_backup().await;
+    }
+    if args.get(1).map(|s| s.as_str()) == Some("restore") {
+        if args.get(2).map(|s| s.as_str()) == Some("--from-remote") {
+            return cmd_restore_remote(args.get(3).map(|s| s.as_str())).await;
+        }
+        return cmd_restore(args.get(2).map(|s| s.as_str())).await;
+    }
+
+    // ── lm journal [dump | restore] ───────────────────────────────────────────
+    if args.get(1).map(|s| s.as_str()) == Some("journal") {
+        return cmd_journal(args.get(2).map(|s| s.as_str())).await;
+    }
+
+    // ── lm db compact [--days N] ─────────────────────────────────────────────
+    // ── lm db dedupe ──────────────────────────────────────────────────────────
+    if args.get(1).map(|s| s.as_str()) == Some("db") {
+        return match args.get(2).map(|s| s.as_str()) {
+            Some("compact") => {
+                let days = args.iter().position(|a| a == "--days")
+                    .and_then(|i| args.get(i + 1))
+                    .map(|d| d.parse::<i64>().map_err(|_| anyhow!("Invalid --days: {d}")))
+                    .transpose()?;
+                cmd_db_compact(days).await
+            }
+            Some("dedupe") => cmd_db_dedupe().await,
+            _ => Err(anyhow!("Usage: lm db compact [--days N] | lm db dedupe")),
+        };
+    }
+
+    // ── lm import [--yes] ─────────────────────────────────────────────────────
+    // ── lm import FILE.org | FILE.txt | FILE.ics … ────────────────────────────
+    // ── lm import --apple EXPORT.ics ──────────────────────────────────────────
+    if args.get(1).map(|s| s.as_str()) == Some("import") {
+        let (mut files, mut apple) = (Vec::new(), Vec::new());
+        let mut rest = args.iter().skip(2).map(|s| s.as_str());
+        while let Some(a) = rest.next() {
+            match a {
+                "--apple" => apple.push(rest.next().ok_or_else(|| anyhow!("Usage: lm import --apple EXPORT.ics"))?),
+                a if a.starts_with("--") => {}
+                a => files.push(a),
+            }
+        }
+        return cmd_import(&files, &apple, args.iter().any(|a| a == "--yes")).await;
+    }
+
+    // ── lm export [--format ics|md|json|org|todotxt] [--out FILE] ─────────────
+    if args.get(1).map(|s| s.as_str()) == Some("export") {
+        let flag = |name: &str| args.iter().position(|a| a == name)
+            .and_then(|i| args.get(i + 1))
+            .map(|s| s.as_str());
+        let format = flag("--format")
+            .map(|f| export::Format::parse(f).ok_or_else(|| anyhow!("Invalid --format: {f} (ics, md, json, org, todotxt)")))
+            .transpose()?
+            .unwrap_or(export::Format::Ics);
+        return cmd_export(format, flag("--out")).await;
+    }
+
+    // ── lm remind [--test] ────────────────────────────────────────────────────
+    if args.get(1).map(|s| s.as_str()) == Some("remind") {
+        return cmd_remind(args.iter().any(|a| a == "--test")).await;
+    }
+
+    // ── lm today ──────────────────────────────────────────────────────────────
+    // ── lm digest [--tomorrow] [--html] [--mail] ──────────────────────────────
+    if args.get(1).map(|s| s.as_str()) == Some("today") {
+        return cmd_digest(false, false, false).await;
+    }
+    if args.get(1).map(|s| s.as_str()) == Some("digest") {
+        let has = |name: &str| args.iter().any(|a| a == name);
+        return cmd_digest(has("--tomorrow"), has("--html"), has("--mail")).await;
+    }
+
+    // ── lm search <text> ──────────────────────────────────────────────────────
+    if args.get(1).map(|s| s.as_str()) == Some("search") {
+        let text = args[2..].join(" ");
+        if text.trim().is_empty() { return Err(anyhow!("Usage: lm search <text>")); }
+        return cmd_search(text.trim()).await;
+    }
+
+    // ── lm long-weekends [year] ───────────────────────────────────────────────
+    if args.get(1).map(|s| s.as_str()) == Some("long-weekends") {
+        return cmd_long_weekends(args.get(2).map(|s| s.as_str()));
+    }
+
+    // ── lm review [year] [--md FILE] [--html FILE] ───────────────────────────
+    if args.get(1).map(|s| s.as_str()) == Some("review") {
+        let flag = |name: &str| args.iter().position(|a| a == name)
+            .and_then(|i| args.get(i + 1))
+            .map(|s| s.as_str());
+        let year = args.get(2).filter(|a| !a.starts_with("--")).map(|s| s.as_str());
+        return cmd_review(year, flag("--md"), flag("--html")).await;
+    }
+
+    // ── lm panel [name] ───────────────────────────────────────────────────────
+    if args.get(1).map(|s| s.as_str()) == Some("panel") {
+        return cmd_panel(args.get(2).map(|s| s.as_str())).await;
+    }
+
+    // ── lm logs [--tail N] [--level warn] ─────────────────────────────────────
+    if args.get(1).map(|s| s.as_str()) == Some("logs") {
+        let flag = |name: &str| args.iter().position(|a| a == name)
+            .and_then(|i| args.get(i + 1))
+            .map(|s| s.as_str());
+        let tail = flag("--tail")
+            .map(|n| n.parse::<usize>().map_err(|_| anyhow!("Invalid --tail: {n}")))
+            .transpose()?
+            .unwrap_or(50);
+        let level = flag("--level")
+            .map(|l| logs::Level::parse(l).ok_or_else(|| anyhow!("Invalid --level: {l} (trace, debug, info, warn, error)")))
+            .transpose()?
+            .unwrap_or(logs::Level::Trace);
+        return cmd_logs(tail, level);
+    }
+
+    // ── lm status-line [--format TEMPLATE] [--json] ───────────────────────────
+    if args.get(1).map(|s| s.as_str()) == Some("status-line") {
+        let format = args.iter().position(|a| a == "--format")
+            .and_then(|i| args.get(i + 1))
+            .map(|s| s.as_str());
+        return cmd_status_line(format, args.iter().any(|a| a == "--json")).await;
+    }
+
+    // ── lm mcp ────────────────────────────────────────────────────────────────
+    if args.get(1).map(|s| s.as_str()) == Some("mcp") {
+        return cmd_mcp().await;
+    }
+
+    // ── lm serve [--port N] ───────────────────────────────────────────────────
+    if args.get(1).map(|s| s.as_str()) == Some("serve") {
+        let port = args.iter().position(|a| a == "--port")
+            .and_then(|i| args.get(i + 1))
+            .map(|p| p.parse::<u16>().map_err(|_| anyhow!("Invalid --port: {p}")))
+            .transpose()?
+            .unwrap_or(9090);
+        return cmd_serve(port).await;
     }
 
     // ── lm (TUI) ──────────────────────────────────────────────────────────────
@@ -38,22 +227,44 @@ async fn main() -> Result<()> {
 
 // ─── Auth command ─────────────────────────────────────────────────────────────
 
-async fn cmd_auth_google() -> Result<()> {
+/// `own` is the user's own OAuth client from `--client-id`. Without it,
+/// signing in again keeps whichever client the current tokens came from.
+/// `read_only` asks for read-only scopes even without `[google] read_only`.
+async fn cmd_auth_google(device: bool, own: Option<(String, Option<String>)>, read_only: bool) -> Result<()> {
     // Logging to stderr so it doesn't interfere with terminal output
     tracing_subscriber::registry()
         .with(tracing_subscriber::fmt::layer().with_writer(std::io::stderr))
         .init();
 
     let cfg = AppConfig::load()?;
-    let google = cfg.google.ok_or_else(|| anyhow!(
-        "No [google] section found in ~/.config/lifemanager/config.toml\n\
-         Copy config.example.toml and fill in your client_id and client_secret."
-    ))?;
-
-    let db = Database::connect().await?;
+    let db  = Database::connect().await?;
     db.migrate().await?;
 
+    let google = match own {
+        Some((id, secret)) => Some(GoogleConfig::with_client(cfg.google, id, secret)),
+        None               => GoogleConfig::resolve(cfg.google, &db).await?,
+    };
+    let google = google.ok_or_else(|| anyhow!(
+        "No Google client_id configured. Either pass your own OAuth client:\n\
+         \n  lm auth google --client-id ID.apps.googleusercontent.com\n\n\
+         or copy config.example.toml to ~/.config/lifemanager/config.toml and fill in client_id\n\
+         (client_secret is optional)."
+    ))?;
+    let embedded = google.is_embedded();
+    let google   = GoogleConfig { read_only: google.read_only || read_only, ..google };
+
     let mut client = GoogleCalendarClient::new(google, db);
+
+    if device {
+        let dc = client.request_device_code().await?;
+        println!("\nOn any device with a browser, visit:\n\n  {}\n", dc.verification_url);
+        println!("and enter the code:\n\n  {}\n", dc.user_code);
+        println!("Waiting for approval (expires in {} min)…", dc.expires_in / 60);
+
+        client.poll_device_token(&dc).await.map_err(|e| own_client_hint(e, embedded))?;
+        return print_granted(&client).await;
+    }
+
     let url = client.build_auth_url();
 
     println!("\nOpening Google authorization in your browser…");
@@ -62,33 +273,125 @@ async fn cmd_auth_google() -> Result<()> {
     // Try to open in browser; ignore errors (user can open manually)
     let _ = open::that(&url);
 
+    if embedded {
+        println!("If Google says the app is blocked or has reached its user limit, sign in");
+        println!("with your own OAuth client instead:  lm auth google --client-id ID\n");
+    }
     println!("Waiting for Google to redirect back (listening on :8085)…");
 
     let code = GoogleCalendarClient::listen_for_callback().await?;
-    client.exchange_code(&code).await?;
+    client.exchange_code(&code).await.map_err(|e| own_client_hint(e, embedded))?;
+    print_granted(&client).await
+}
 
-    println!("\nSuccess! Google Calendar and Tasks are now authorized.");
+/// What the consent screen let through: each scope can be unticked there.
+async fn print_granted(client: &GoogleCalendarClient) -> Result<()> {
+    let grant = client.grant().await?;
+    let says  = |access: Access| match access {
+        Access::Write => "read and write",
+        Access::Read  => "read-only",
+        Access::None  => "not granted",
+    };
+    println!("\nSuccess! Signed in to Google.");
+    println!("  Calendar  {}", says(grant.calendar));
+    println!("  Tasks     {}", says(grant.tasks));
+    if grant.read_only() {
+        println!("Nothing is pushed: changes made here stay on this machine.");
+    }
     println!("Run  lm  to start the app — it will sync automatically.");
-
     Ok(())
 }
 
-// ─── Manual sync command ──────────────────────────────────────────────────────
+/// Points at `--client-id` when the shared, compiled-in client is refused.
+fn own_client_hint(e: anyhow::Error, embedded: bool) -> anyhow::Error {
+    if !embedded { return e; }
+    anyhow!("{e}\n\nThe built-in OAuth client was refused. Sign in with your own instead:\n\
+             \n  lm auth google --client-id ID.apps.googleusercontent.com")
+}
 
-async fn cmd_sync() -> Result<()> {
+async fn cmd_auth_revoke(clear_sync_ids: bool) -> Result<()> {
+    tracing_subscriber::registry()
+        .with(tracing_subscriber::fmt::layer().with_writer(std::io::stderr))
+        .init();
+
+    let db = Database::connect().await?;
+    db.migrate().await?;
+
+    if db.get_token("google").await?.is_none() {
+        println!("Not signed in to Google — nothing to revoke.");
+    } else {
+        let cfg    = AppConfig::load()?;
+        let google = GoogleConfig::resolve(cfg.google, &db).await?
+            .ok_or_else(|| anyhow!("No [google] section in config.toml"))?;
+        GoogleCalendarClient::new(google, db.clone()).revoke().await?;
+        println!("Google access revoked and local tokens deleted.");
+    }
+
+    if clear_sync_ids {
+        let saved = db.backup("pre-clear-sync-ids").await?;
+        println!("Backed up to {}", saved.display());
+        db.clear_sync_ids().await?;
+        println!("Local events and tasks detached from Google (sync ids cleared).");
+    }
+    Ok(())
+}
+
+/// Signs in to Microsoft 365 with the device flow, or with `revoke`
+/// forgets the stored tokens.
+async fn cmd_auth_outlook(revoke: bool) -> Result<()> {
     tracing_subscriber::registry()
         .with(tracing_subscriber::fmt::layer().with_writer(std::io::stderr))
         .init();
 
     let cfg = AppConfig::load()?;
-    if cfg.google.is_none() {
-        println!("No [google] config found. Run  lm auth google  first.");
+    let db  = Database::connect().await?;
+    db.migrate().await?;
+
+    let outlook = cfg.outlook.ok_or_else(|| anyhow!(
+        "No [outlook] section in config.toml. Register an app in Azure (Entra ID) with\n\
+         \"Allow public client flows\" on, then add:\n\
+         \n  [outlook]\n  client_id = \"00000000-0000-0000-0000-000000000000\""
+    ))?;
+    let mut client = OutlookClient::new(outlook, db);
+
+    if revoke {
+        client.sign_out().await?;
+        println!("Outlook tokens deleted. To remove the app's access too, visit https://myapps.microsoft.com");
         return Ok(());
     }
 
-    let db     = Database::connect().await?;
+    let dc = client.request_device_code().await?;
+    println!("\nOn any device with a browser, visit:\n\n  {}\n", dc.verification_url);
+    println!("and enter the code:\n\n  {}\n", dc.user_code);
+    println!("Waiting for approval (expires in {} min)…", dc.expires_in / 60);
+    client.poll_device_token(&dc).await?;
+
+    println!("\nSuccess! Signed in to Microsoft 365 (Outlook calendar and To Do).");
+    println!("Run  lm  to start the app — it will sync automatically.");
+    Ok(())
+}
+
+// ─── Manual sync command ──────────────────────────────────────────────────────
+
+async fn cmd_sync() -> Result<()> {
+    tracing_subscriber::registry()
+        .with(tracing_subscriber::fmt::layer().with_writer(std::io::stderr))
+        .init();
+
+    let mut cfg = AppConfig::load()?;
+    let db      = Database::connect().await?.with_journal(Journal::from_config(&cfg.journal)?);
     db.migrate().await?;
-    let worker = SyncWorker::spawn(db.clone(), cfg.google);
+    cfg.google  = GoogleConfig::resolve(cfg.google, &db).await?;
+    if cfg.google.is_none() && cfg.outlook.is_none() && cfg.todoist.is_none() && cfg.taskwarrior.is_none() && cfg.vault.is_none() {
+        println!("No [google], [outlook], [todoist], [taskwarrior] or [vault] config found. Run  lm auth google  first.");
+        return Ok(());
+    }
+    maintain(&db, &cfg).await;
+    // Push channels and the timer are for a long-running TUI, not a one-off sync
+    if let Some(g) = cfg.google.as_mut() { g.push_url = None; }
+    let titles = TitleRules::from_config(&cfg.titles);
+    let once   = SyncConfig { auto_sync: Some(false), ..Default::default() };
+    let worker = SyncWorker::spawn(db.clone(), &cfg, titles, &once);
     worker.sync_now().await;
 
     // Give the worker time to complete before exiting
@@ -98,38 +401,652 @@ async fn cmd_sync() -> Result<()> {
     Ok(())
 }
 
-// ─── TUI ─────────────────────────────────────────────────────────────────────
+/// Lists what `lm sync` would pull and push, changing nothing.
+async fn cmd_sync_dry_run() -> Result<()> {
+    let mut cfg = AppConfig::load()?;
+    let db      = Database::connect().await?;
+    db.migrate().await?;
+    cfg.google  = GoogleConfig::resolve(cfg.google, &db).await?;
+    let titles  = TitleRules::from_config(&cfg.titles);
+    let plans   = sync::worker::dry_run(&cfg, &db, &titles).await?;
+    if plans.is_empty() {
+        println!("No [google], [outlook], [todoist] or [taskwarrior] config found. Run  lm auth google  first.");
+        return Ok(());
+    }
+    for (backend, plan) in &plans {
+        let Some(plan) = plan else {
+            let how = match *backend {
+                "todoist" => "Set  token  under [todoist]".to_owned(),
+                _         => format!("Run  lm auth {backend}  first"),
+            };
+            println!("[{backend}] not signed in — a sync skips it. {how}.");
+            continue;
+        };
+        if plan.is_empty() {
+            println!("[{backend}] nothing to sync: it and this machine agree.");
+            continue;
+        }
+        println!("[{backend}]");
+        for direction in [audit::PULL, audit::PUSH] {
+            let steps: Vec<_> = plan.iter().filter(|e| e.direction == direction).collect();
+            if steps.is_empty() { continue; }
+            println!("Would {direction}:");
+            for e in steps {
+                let why = if e.result == "would" { String::new() } else { format!("  ({})", e.result) };
+                println!("  {:<5}  {:<10}  {}{why}", e.entity, e.action, e.title);
+            }
+        }
+    }
+    let n = plans.iter().flat_map(|(_, p)| p.iter().flatten()).filter(|e| e.result == "would").count();
+    println!("{n} change{} — nothing was written. `lm sync` makes them.", if n == 1 { "" } else { "s" });
+    Ok(())
+}
 
-async fn run_tui() -> Result<()> {
-    let log_dir = dirs::data_dir()
-        .unwrap_or_else(|| std::path::PathBuf::from("."))
-        .join("lifemanager");
-    std::fs::create_dir_all(&log_dir)?;
-    let file_appender = tracing_appender::rolling::daily(&log_dir, "lifemanager.log");
-    let (non_blocking, _guard) = tracing_appender::non_blocking(file_appender);
+/// The newest `n` sync log entries, oldest first.
+async fn cmd_sync_log(n: i64) -> Result<()> {
+    let db = Database::connect().await?;
+    db.migrate().await?;
+    let entries = db.sync_log(n.max(1)).await?;
+    if entries.is_empty() {
+        println!("The sync log is empty: nothing has been pulled or pushed yet.");
+    }
+    for e in entries.iter().rev() { println!("{}", audit::line(e)); }
+    Ok(())
+}
+
+// ─── Backup / restore ─────────────────────────────────────────────────────────
+
+/// Startup housekeeping: daily snapshot, then purge of old synced deletions.
+async fn maintain(db: &Database, cfg: &AppConfig) {
+    auto_backup(db, &cfg.backup).await;
+    match db.purge_deleted(cfg.db.purge_after_days).await {
+        Ok((0, 0))   => {}
+        Ok((ev, tk)) => tracing::info!("Purged {ev} deleted events and {tk} deleted tasks"),
+        Err(e)       => tracing::warn!("Purge failed: {e}"),
+    }
+}
+
+/// Daily snapshot (if none in the last 24 h), then trim to `[backup] keep`
+/// and copy the newest off-site if `[backup.remote]` is set.
+async fn auto_backup(db: &Database, cfg: &BackupConfig) {
+    if !cfg.enabled { return; }
+    if backup::due(std::time::Duration::from_secs(24 * 60 * 60)) {
+        if let Err(e) = db.backup("daily").await {
+            tracing::warn!("Daily backup failed: {e}");
+        }
+    }
+    if let Err(e) = backup::rotate(cfg.keep) {
+        tracing::warn!("Backup rotation failed: {e}");
+    }
+    if let Some(ref remote) = cfg.remote {
+        let uploaded = async { cloud::upload_latest(&cloud::Store::from_config(remote)?).await };
+        if let Err(e) = uploaded.await {
+            tracing::warn!("Remote backup failed: {e:#}");
+        }
+    }
+}
+
+async fn cmd_backup() -> Result<()> {
+    let cfg = AppConfig::load()?;
+    let db  = Database::connect().await?;
+    db.migrate().await?;
+    let path = db.backup("manual").await?;
+    backup::rotate(cfg.backup.keep)?;
+    println!("Backed up to {}", path.display());
+    if let Some(ref remote) = cfg.backup.remote {
+        let store = cloud::Store::from_config(remote)?;
+        if let Some(name) = cloud::upload_latest(&store).await? {
+            println!("Uploaded {name} to {}", store.describe());
+        }
+    }
+    Ok(())
+}
+
+async fn cmd_db_compact(days: Option<i64>) -> Result<()> {
+    let cfg  = AppConfig::load()?;
+    let days = days.unwrap_or(cfg.db.purge_after_days);
+    let db   = Database::connect().await?;
+    db.migrate().await?;
+
+    let saved  = db.backup("pre-compact").await?;
+    backup::rotate(cfg.backup.keep)?;
+    let before = db.size().await?;
+    let (events, tasks) = db.purge_deleted(days).await?;
+    db.vacuum().await?;
+    let after  = db.size().await?;
+
+    println!("Backed up to {}", saved.display());
+    println!("Purged {events} events and {tasks} tasks deleted over {days} days ago.");
+    println!(
+        "Database {} KB → {} KB (reclaimed {} KB).",
+        before / 1024, after / 1024, before.saturating_sub(after) / 1024,
+    );
+    Ok(())
+}
+
+/// Merges events that are certainly the same one (see `dedupe::certain`),
+/// after a snapshot, and points at `U` for the ones that only look alike.
+async fn cmd_db_dedupe() -> Result<()> {
+    let cfg = AppConfig::load()?;
+    let db  = Database::connect().await?;
+    db.migrate().await?;
+
+    let saved  = db.backup("pre-dedupe").await?;
+    backup::rotate(cfg.backup.keep)?;
+    let merges = db.dedupe().await?;
+    println!("Backed up to {}", saved.display());
+    if merges.is_empty() {
+        println!("No duplicate events.");
+    } else {
+        for m in &merges {
+            let when = m.kept.start.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M");
+            println!("  Merged {} × {when}  {}", m.dropped.len() + 1, m.kept.title);
+        }
+        println!("Merged {} duplicate group{}.", merges.len(), if merges.len() == 1 { "" } else { "s" });
+        if merges.iter().any(|m| m.kept.dirty) {
+            println!("Merged details go to Google on the next sync.");
+        }
+    }
+
+    let alike = dedupe::clusters(&db.calendar_events().await?).len();
+    if alike > 0 {
+        let (groups, look) = if alike == 1 { ("group", "looks") } else { ("groups", "look") };
+        println!("{alike} more {groups} {look} alike — review them with U in the calendar.");
+    }
+    Ok(())
+}
+
+/// Lists the off-site copies, or fetches one into the backup folder and
+/// restores it like any other snapshot.
+async fn cmd_restore_remote(name: Option<&str>) -> Result<()> {
+    let cfg    = AppConfig::load()?;
+    let remote = cfg.backup.remote.as_ref()
+        .ok_or_else(|| anyhow!("No [backup.remote] in config.toml"))?;
+    let store  = cloud::Store::from_config(remote)?;
+    let Some(name) = name else {
+        let names = store.list().await?;
+        if names.is_empty() {
+            println!("No backups at {} yet.", store.describe());
+        } else {
+            println!("\nBackups at {} (newest last)\n", store.describe());
+            for n in &names { println!("  {n}"); }
+            println!("\nRestore one with  lm restore --from-remote <name | latest>  (quit any running lm first).");
+        }
+        return Ok(());
+    };
+    let path = cloud::download(&store, name).await?;
+    println!("Downloaded to {}", path.display());
+    cmd_restore(Some(&path.to_string_lossy())).await
+}
+
+async fn cmd_restore(file: Option<&str>) -> Result<()> {
+    let Some(file) = file else {
+        let files = backup::list()?;
+        if files.is_empty() {
+            println!("No backups in {} yet.", backup::dir().display());
+        } else {
+            println!("\nBackups in {} (newest last)\n", backup::dir().display());
+            for f in &files {
+                let kb = f.metadata().map(|m| m.len() / 1024).unwrap_or(0);
+                println!("  {:<48} {kb:>6} KB", f.file_name().unwrap_or_default().to_string_lossy());
+            }
+            println!("\nRestore one with  lm restore <file>  (quit any running lm first).");
+        }
+        return Ok(());
+    };
+
+    let file    = backup::find(file)?;
+    let version = backup::verify(&file).await?;
+
+    // The current data is snapshotted too, so a restore can itself be undone
+    let db    = Database::connect().await?;
+    let saved = db.backup("pre-restore").await?;
+    let path  = db.path().to_owned();
+    db.close().await;
+    backup::replace(&file, &path)?;
+
+    let db = Database::connect().await?;
+    db.migrate().await?;
+    println!("Restored {} (schema v{version}).", file.display());
+    println!("The previous database was saved to {}", saved.display());
+    Ok(())
+}
+
+// ─── Change journal ───────────────────────────────────────────────────────────
+
+async fn cmd_journal(action: Option<&str>) -> Result<()> {
+    let cfg = AppConfig::load()?;
+    let Some(j) = Journal::from_config(&cfg.journal)? else {
+        println!("No journal configured — set [journal] path in config.toml.");
+        return Ok(());
+    };
+    let (entries, skipped) = j.entries()?;
+
+    match action {
+        None => {
+            println!("\nJournal {}\n", j.path().display());
+            for kind in ["event", "guests", "task", "meal", "checklist", "habit", "timer"] {
+                let n = entries.iter().filter(|e| e.kind == kind).count();
+                println!("  {kind:<10} {n:>6} changes");
+            }
+            if let (Some(first), Some(last)) = (entries.first(), entries.last()) {
+                println!("\n  {} → {}", first.at.format("%Y-%m-%d %H:%M"), last.at.format("%Y-%m-%d %H:%M"));
+            }
+        }
+        Some("dump") => {
+            for e in &entries {
+                println!("{}", serde_json::to_string(e)?);
+            }
+        }
+        Some("restore") => {
+            // Without a journal attached, so the restore isn't journalled again
+            let db = Database::connect().await?;
+            db.migrate().await?;
+            let saved = db.backup("pre-journal-restore").await?;
+            backup::rotate(cfg.backup.keep)?;
+            let done = journal::restore(&db, &entries).await?;
+            println!("Backed up to {}", saved.display());
+            println!(
+                "Restored {} events ({} guest lists), {} tasks, {} meals, {} checklists and {} habits from {}.",
+                done.events, done.guests, done.tasks, done.meals, done.checklists, done.habits, j.path().display(),
+            );
+        }
+        Some(other) => return Err(anyhow!("Unknown journal action {other} — use  lm journal [dump | restore]")),
+    }
+    if skipped > 0 {
+        eprintln!("{skipped} line(s) couldn't be read and were skipped.");
+    }
+    Ok(())
+}
+
+// ─── Reminders ────────────────────────────────────────────────────────────────
+
+/// One reminder pass for cron/systemd timers on machines without the TUI
+/// open; `--test` sends a sample through every backend instead.
+async fn cmd_remind(test: bool) -> Result<()> {
+    // Quiet unless something fails: this runs from cron every minute
+    let filter = tracing_subscriber::EnvFilter::try_from_default_env()
+        .unwrap_or_else(|_| tracing_subscriber::EnvFilter::new("warn"));
     tracing_subscriber::registry()
-        .with(tracing_subscriber::fmt::layer().with_writer(non_blocking))
+        .with(filter)
+        .with(tracing_subscriber::fmt::layer().with_writer(std::io::stderr))
         .init();
 
-    tracing::info!("Starting LifeManager");
+    let cfg       = AppConfig::load()?;
+    let notifiers = notify::Notifiers::from_config(&cfg.notify, None);
+    if notifiers.is_empty() {
+        println!("No [[notify.backends]] configured.");
+        return Ok(());
+    }
 
-    let cfg   = AppConfig::load().unwrap_or_default();
-    let theme = ThemeConfig::load()?;
-    let db    = Database::connect().await?;
+    if test {
+        let now = chrono::Utc::now();
+        notifiers.send(&notify::Notification {
+            event_id: "test".into(),
+            title:    "LifeManager test reminder".into(),
+            body:     format!("{} · sent by lm remind --test", now.format("%H:%M")),
+            start:    now,
+            urgency:  cfg.notify.urgency,
+            sound:    cfg.notify.sound.clone(),
+        }).await;
+        return Ok(());
+    }
+    if !cfg.notify.enabled {
+        println!("Reminders are off ([notify] enabled = false).");
+        return Ok(());
+    }
+
+    let db = Database::connect().await?;
+    db.migrate().await?;
+    notify::send_due(&db, &notifiers, &cfg.notify, notify::wall_now()).await?;
+    notify::send_agenda(&db, &notifiers, &cfg.notify).await?;
+    Ok(())
+}
+
+// ─── Import ───────────────────────────────────────────────────────────────────
+
+/// Before the database exists: offers what `import::detect` finds, and
+/// moves in a legacy database straight away. Returns the sources left to
+/// import once connected.
+fn first_run_import() -> Result<Vec<import::Source>> {
+    use std::io::IsTerminal;
+    if Database::file().exists() || !std::io::stdin().is_terminal() { return Ok(Vec::new()); }
+    let found = import::detect();
+    if found.is_empty() || !import::confirm(&found)? { return Ok(Vec::new()); }
+    if let Some(import::Source::Legacy(path)) = found.iter().find(|s| matches!(s, import::Source::Legacy(_))) {
+        import::adopt(path)?;
+    }
+    Ok(found)
+}
+
+/// `apple` files are calendar exports whatever they're called.
+async fn cmd_import(files: &[&str], apple: &[&str], yes: bool) -> Result<()> {
+    if !files.is_empty() || !apple.is_empty() {
+        let mut sources = files.iter()
+            .map(|f| import::Source::from_file(std::path::Path::new(f))
+                .ok_or_else(|| anyhow!("Don't know how to import {f} — name an .org file, a todo.txt (.txt) or a calendar export (.ics)")))
+            .collect::<Result<Vec<_>>>()?;
+        sources.extend(apple.iter().map(|f| import::Source::Apple(f.into())));
+        let db = Database::connect().await?;
+        db.migrate().await?;
+        let saved = db.backup("pre-import").await?;
+        println!("Backed up to {}", saved.display());
+        return import::run(&db, &sources).await;
+    }
+
+    let found = import::detect();
+    if found.is_empty() {
+        println!("No khal, vdirsyncer or calcurse data found, nor an old ~/.lifemanager.");
+        return Ok(());
+    }
+    if !yes && !import::confirm(&found)? { return Ok(()); }
+
+    let db = Database::connect().await?;
+    db.migrate().await?;
+    let saved = db.backup("pre-import").await?;
+    println!("Backed up to {}", saved.display());
+    for s in &found {
+        if let import::Source::Legacy(path) = s {
+            println!("  {} — already set up here; use  lm restore {}  to switch to it", s.describe(), path.display());
+        }
+    }
+    import::run(&db, &found).await
+}
+
+// ─── Export ───────────────────────────────────────────────────────────────────
+
+/// Every event (not local layers) and task, to `out` or stdout.
+async fn cmd_export(format: export::Format, out: Option<&str>) -> Result<()> {
+    let db = Database::connect().await?;
+    db.migrate().await?;
+    let events = db.calendar_events().await?;
+    let tasks  = db.all_tasks().await?;
+    let text   = export::render(format, "LifeManager", &events, &tasks)?;
+    match out {
+        Some(path) => {
+            std::fs::write(path, text)?;
+            eprintln!("Wrote {} events and {} tasks to {path}", events.len(), tasks.len());
+        }
+        None => print!("{text}"),
+    }
+    Ok(())
+}
+
+// ─── Search ───────────────────────────────────────────────────────────────────
+
+async fn cmd_search(text: &str) -> Result<()> {
+    const LIMIT: i64 = 50;
+    let db = Database::connect().await?;
+    db.migrate().await?;
+
+    let found = db.search_events(text, LIMIT).await?;
+    if found.is_empty() {
+        println!("No events match \"{text}\".");
+        return Ok(());
+    }
+    for ev in &found {
+        let when = if ev.all_day {
+            ev.start.format("%a %Y-%m-%d  all-day").to_string()
+        } else {
+            format!("{}–{}", ev.start.format("%a %Y-%m-%d  %H:%M"), ev.end.format("%H:%M"))
+        };
+        println!("  {when:<28} {}", ev.title);
+    }
+    if found.len() as i64 == LIMIT {
+        println!("\n  (first {LIMIT} matches — narrow the search for more)");
+    }
+    Ok(())
+}
+
+// ─── Digest ───────────────────────────────────────────────────────────────────
+
+/// Today's (or tomorrow's) digest on stdout. `mail` puts headers in front,
+/// so it can go straight into `sendmail you@example.com`.
+async fn cmd_digest(tomorrow: bool, html: bool, mail: bool) -> Result<()> {
+    let cfg = AppConfig::load()?;
+    let db  = Database::connect().await?;
     db.migrate().await?;
+    let today      = chrono::Local::now().date_naive();
+    let date       = if tomorrow { calendar::add_days(today, 1).unwrap_or(today) } else { today };
+    let (from, to) = calendar::day_bounds(date);
+    let events     = db.events_overlapping(from, to).await?;
+    let tasks      = db.all_tasks().await?;
+    let engine     = holidays::HolidayEngine::from_config(&cfg.holidays);
+    let digest     = digest::Digest::build(date, today, &events, &tasks, &engine);
+
+    if mail {
+        println!("Subject: Agenda for {}", date.format("%a %b %-d"));
+        println!("MIME-Version: 1.0");
+        println!("Content-Type: text/{}; charset=utf-8", if html { "html" } else { "plain" });
+        println!("Content-Transfer-Encoding: 8bit");
+        println!();
+    }
+    println!("{}", if html { digest.to_html() } else { digest.to_text() });
+    Ok(())
+}
 
-    let has_google = cfg.google.is_some();
-    let worker     = SyncWorker::spawn(db.clone(), cfg.google);
+// ─── Long weekend finder ──────────────────────────────────────────────────────
+
+fn cmd_long_weekends(year_arg: Option<&str>) -> Result<()> {
+    let year = match year_arg {
+        Some(y) => y.parse::<i32>().map_err(|_| anyhow!("Invalid year: {y}"))?,
+        None    => chrono::Local::now().year(),
+    };
+
+    let cfg    = AppConfig::load()?;
+    let engine = holidays::HolidayEngine::from_config(&cfg.holidays);
+    let countries = engine.countries();
+    if countries.is_empty() {
+        println!("Holidays are disabled — enable some countries under [holidays] in config.toml.");
+        return Ok(());
+    }
 
-    let mut app = App::new(db, theme).await?;
-    app.attach_sync_worker(worker);
+    let found = holidays::long_weekends(&engine, year);
+    if found.is_empty() {
+        println!("No long weekends found in {year}.");
+        return Ok(());
+    }
 
-    if has_google {
-        if let Some(ref w) = app.sync {
-            w.sync_now().await;
+    println!("\nLong weekends in {year} ({} holidays)\n", countries.join(" + "));
+    for lw in &found {
+        let leave = if lw.bridge.is_empty() {
+            "no leave needed".to_owned()
+        } else {
+            let days: Vec<String> = lw.bridge.iter().map(|d| d.format("%a %b %-d").to_string()).collect();
+            format!("take {}", days.join(", "))
+        };
+        let mut names: Vec<String> = Vec::new();
+        for h in &lw.holidays {
+            let label = format!("{} {} ({})", h.emoji, h.name, h.country);
+            if !names.contains(&label) { names.push(label); }
         }
+        println!(
+            "  {} – {}  {:>2} days off  {:<28} {}",
+            lw.start.format("%a %b %e"),
+            lw.end.format("%a %b %e"),
+            lw.days_off(),
+            leave,
+            names.join(", "),
+        );
+    }
+    println!();
+    Ok(())
+}
+
+// ─── Year in review ───────────────────────────────────────────────────────────
+
+async fn cmd_review(year_arg: Option<&str>, md: Option<&str>, html: Option<&str>) -> Result<()> {
+    let year = match year_arg {
+        Some(y) => y.parse::<i32>().map_err(|_| anyhow!("Invalid year: {y}"))?,
+        None    => chrono::Local::now().year(),
+    };
+    let first = chrono::NaiveDate::from_ymd_opt(year, 1, 1).ok_or_else(|| anyhow!("Invalid year: {year}"))?;
+    let last  = chrono::NaiveDate::from_ymd_opt(year, 12, 31).ok_or_else(|| anyhow!("Invalid year: {year}"))?;
+
+    let cfg = AppConfig::load()?;
+    let db  = Database::connect().await?;
+    db.migrate().await?;
+    let events   = db.events_overlapping(calendar::day_bounds(first).0, calendar::day_bounds(last).1).await?;
+    let tasks    = db.all_tasks().await?;
+    let holidays = holidays::HolidayEngine::from_config(&cfg.holidays).holidays_in_year(year);
+    let habits   = db.habits().await?;
+    let checks   = db.habit_checks().await?;
+    let review   = review::build(year, &events, &tasks, (&habits, &checks), holidays);
+
+    print!("{}", review.to_text());
+    if let Some(path) = md {
+        std::fs::write(path, review.to_markdown())?;
+        println!("  Wrote {path}");
+    }
+    if let Some(path) = html {
+        std::fs::write(path, review.to_html())?;
+        println!("  Wrote {path}");
+    }
+    Ok(())
+}
+
+// ─── Logs ─────────────────────────────────────────────────────────────────────
+
+/// Prints the latest log lines — what to look at when sync misbehaves,
+/// without knowing where the data dir is.
+fn cmd_logs(tail: usize, min: logs::Level) -> Result<()> {
+    let lines = logs::read(min, tail)?;
+    if lines.is_empty() {
+        println!("No log lines at {} or above in {}", min.label(), logs::dir().display());
+        return Ok(());
+    }
+    for l in lines {
+        println!("{}", l.text);
+    }
+    Ok(())
+}
+
+// ─── Status line ──────────────────────────────────────────────────────────────
+
+/// One line for tmux or a status bar; `--json` is waybar's custom module
+/// format, with the markup waybar reads escaped.
+async fn cmd_status_line(format: Option<&str>, json: bool) -> Result<()> {
+    let mut cfg = AppConfig::load()?.status_line;
+    if let Some(f) = format { cfg.format = f.to_owned(); }
+    let db = Database::connect().await?;
+    db.migrate().await?;
+    let now    = chrono::Local::now().naive_local().and_utc();
+    let until  = calendar::day_bounds(now.date_naive()).1.max(now + chrono::Duration::hours(cfg.horizon_hours.max(0)));
+    let events = db.events_overlapping(now, until).await?;
+    let tasks  = db.all_tasks().await?;
+    let line   = status::build(&cfg, &events, &tasks, now);
+    if json {
+        let pango = |s: &str| s.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;");
+        println!("{}", serde_json::json!({
+            "text":    pango(&line.text),
+            "tooltip": pango(&line.tooltip),
+            "class":   line.class,
+        }));
+    } else {
+        println!("{}", line.text);
+    }
+    Ok(())
+}
+
+// ─── Serve ────────────────────────────────────────────────────────────────────
+
+async fn cmd_serve(port: u16) -> Result<()> {
+    let db = Database::connect().await?;
+    db.migrate().await?;
+    serve::run(db, port).await
+}
+
+// ─── MCP server ───────────────────────────────────────────────────────────────
+
+async fn cmd_mcp() -> Result<()> {
+    let cfg = AppConfig::load()?;
+    let db  = Database::connect().await?;
+    db.migrate().await?;
+    mcp::run(db, cfg).await
+}
+
+// ─── Custom panels ───────────────────────────────────────────────────────────
+
+/// Runs a `[[panels]]` command once and prints what the `C` overlay would
+/// list, for checking a script; without a name, lists the panels.
+async fn cmd_panel(name: Option<&str>) -> Result<()> {
+    let cfg = AppConfig::load()?;
+    let Some(name) = name else {
+        if cfg.panels.is_empty() { println!("No [[panels]] in config.toml"); }
+        for p in &cfg.panels { println!("  {:<20} {}", p.name, p.command); }
+        return Ok(());
+    };
+    let panel = cfg.panels.iter().find(|p| p.name.eq_ignore_ascii_case(name))
+        .ok_or_else(|| anyhow!("No panel named \"{name}\" (lm panel lists them)"))?;
+    let items = plugins::run(panel).await.map_err(|e| anyhow!("{}: {e}", panel.name))?;
+    if items.is_empty() { println!("Nothing to show"); }
+    for item in &items {
+        println!("  {}  {}", item.title, item.detail.as_deref().unwrap_or(""));
+        if let Some(ref url) = item.url { println!("      {url}"); }
+    }
+    Ok(())
+}
+
+// ─── TUI ─────────────────────────────────────────────────────────────────────
+
+async fn run_tui() -> Result<()> {
+    let mut cfg = AppConfig::load().unwrap_or_default();
+    let _guard  = logs::init(&cfg.logs)?;
+
+    tracing::info!("Starting LifeManager");
+
+    let theme = ThemeConfig::load()?;
+    let first = first_run_import()?;
+    let db    = Database::connect().await?;
+    db.migrate().await?;
+    if !first.is_empty() {
+        import::run(&db, &first).await?;
     }
+    cfg.google = GoogleConfig::resolve(cfg.google, &db).await?;
+
+    // Nothing below stands between launch and the first frame
+    let (ready_tx, ready_rx) = tokio::sync::oneshot::channel();
+    let (bg_db, bg_cfg)      = (db.clone(), cfg.clone());
+    tokio::spawn(async move { let _ = ready_tx.send(start_background(bg_db, bg_cfg).await); });
 
+    let mut app = App::new(db, theme, cfg);
+    app.start_up(ready_rx);
     app.run().await?;
     Ok(())
 }
+
+/// The slow part of startup, done while the TUI shows its first frame:
+/// unlocking the journal (key derivation takes a moment), then the sync
+/// worker and reminders. Backups and purging don't hold anything up.
+async fn start_background(db: Database, cfg: AppConfig) -> Result<app::Startup> {
+    let journal = {
+        let jc = cfg.journal.clone();
+        tokio::task::spawn_blocking(move || Journal::from_config(&jc)).await??
+    };
+    let db = db.with_journal(journal);
+    tokio::spawn({
+        let (db, cfg) = (db.clone(), cfg.clone());
+        async move {
+            maintain(&db, &cfg).await;
+            // A session left open for days still takes (and uploads) the
+            // daily snapshot
+            let mut hourly = tokio::time::interval(std::time::Duration::from_secs(60 * 60));
+            hourly.tick().await;
+            loop {
+                hourly.tick().await;
+                auto_backup(&db, &cfg.backup).await;
+            }
+        }
+    });
+
+    let titles = TitleRules::from_config(&cfg.titles);
+    let every  = cfg.sync.clone().unwrap_or_default();
+    let sync   = SyncWorker::spawn(db.clone(), &cfg, titles, &every);
+
+    let (toast_tx, toasts) = tokio::sync::mpsc::unbounded_channel();
+    let notifiers = notify::Notifiers::from_config(&cfg.notify, Some(toast_tx));
+    if cfg.notify.enabled && !notifiers.is_empty() {
+        notify::spawn(db.clone(), notifiers, cfg.notify.clone());
+    }
+    Ok(app::Startup { db, sync, toasts })
+}
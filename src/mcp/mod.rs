@@ -0,0 +1,329 @@
+//! `lm mcp`: the calendar and tasks as a Model Context Protocol server on
+//! stdin/stdout, so an AI assistant can look things up and schedule them
+//! straight into the database. Register it with the assistant as a stdio
+//! server running `lm mcp`.
+//!
+//! Messages are JSON-RPC 2.0, one a line. Times go both ways as wall time
+//! without a zone (`2026-10-20T09:00`), the way they're stored; created
+//! items are dirty, so a running TUI (or the next `lm sync`) pushes them.
+
+use anyhow::{anyhow, bail, Result};
+use chrono::{Datelike, Duration, Local, NaiveDate, NaiveTime, Utc, Weekday};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+
+use crate::calendar::{add_days, day_bounds};
+use crate::config::AppConfig;
+use crate::db::{Database, Event, Task};
+use crate::serve::{NewEvent, NewTask};
+use crate::{holidays, planner, tasks};
+
+/// The protocol revision answered when the client doesn't name one.
+const PROTOCOL_VERSION: &str = "2024-11-05";
+
+const PARSE_ERROR:      i64 = -32700;
+const METHOD_NOT_FOUND: i64 = -32601;
+
+/// Answers requests on stdin until it closes.
+pub async fn run(db: Database, cfg: AppConfig) -> Result<()> {
+    let mut lines = BufReader::new(tokio::io::stdin()).lines();
+    let mut out   = tokio::io::stdout();
+    while let Some(line) = lines.next_line().await? {
+        if line.trim().is_empty() { continue; }
+        let reply = match serde_json::from_str::<Value>(&line) {
+            Ok(msg) => answer(&db, &cfg, &msg).await,
+            Err(e)  => Some(json!({ "jsonrpc": "2.0", "id": null, "error": { "code": PARSE_ERROR, "message": e.to_string() } })),
+        };
+        if let Some(reply) = reply {
+            out.write_all(format!("{reply}\n").as_bytes()).await?;
+            out.flush().await?;
+        }
+    }
+    Ok(())
+}
+
+/// The reply to `msg`; None for notifications, which get none.
+async fn answer(db: &Database, cfg: &AppConfig, msg: &Value) -> Option<Value> {
+    let id     = msg.get("id").cloned();
+    let method = msg["method"].as_str().unwrap_or_default();
+    let params = &msg["params"];
+    let result = match method {
+        "initialize" => Ok(json!({
+            "protocolVersion": params["protocolVersion"].as_str().unwrap_or(PROTOCOL_VERSION),
+            "capabilities":    { "tools": {} },
+            "serverInfo":      { "name": "lifemanager", "version": env!("CARGO_PKG_VERSION") },
+        })),
+        "ping"       => Ok(json!({})),
+        "tools/list" => Ok(json!({ "tools": tools() })),
+        "tools/call" => {
+            let name = params["name"].as_str().unwrap_or_default();
+            let args = params.get("arguments").cloned().unwrap_or_else(|| json!({}));
+            Ok(match call(db, cfg, name, args).await {
+                Ok(value) => json!({ "content": [{ "type": "text", "text": pretty(&value) }] }),
+                Err(e)    => json!({ "content": [{ "type": "text", "text": format!("{e:#}") }], "isError": true }),
+            })
+        }
+        _ => Err(format!("No method {method}")),
+    };
+    let id = id?;
+    Some(match result {
+        Ok(result)   => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+        Err(message) => json!({ "jsonrpc": "2.0", "id": id, "error": { "code": METHOD_NOT_FOUND, "message": message } }),
+    })
+}
+
+fn pretty(value: &Value) -> String {
+    serde_json::to_string_pretty(value).unwrap_or_else(|_| value.to_string())
+}
+
+// ─── Tools ────────────────────────────────────────────────────────────────────
+
+fn tools() -> Value {
+    let date = json!({ "type": "string", "description": "YYYY-MM-DD" });
+    let when = json!({ "type": "string", "description": "YYYY-MM-DDTHH:MM local time, or YYYY-MM-DD for all day" });
+    json!([
+        {
+            "name": "list_events",
+            "description": "Events overlapping the days from `from` to `to` (inclusive), in local time.",
+            "inputSchema": {
+                "type": "object",
+                "properties": { "from": date, "to": date },
+                "required": ["from"],
+            },
+        },
+        {
+            "name": "create_event",
+            "description": "Adds an event. Without `end` it lasts an hour, or the one day when all day.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "title":       { "type": "string" },
+                    "start":       when,
+                    "end":         when,
+                    "all_day":     { "type": "boolean" },
+                    "description": { "type": "string" },
+                },
+                "required": ["title", "start"],
+            },
+        },
+        {
+            "name": "find_free_slot",
+            "description": "Free times of at least `duration_minutes` within working hours, earliest first, skipping weekends unless asked.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "duration_minutes": { "type": "integer", "minimum": 5 },
+                    "from":             date,
+                    "days":             { "type": "integer", "description": "How many days to search (default 7)" },
+                    "day_start":        { "type": "string", "description": "HH:MM (default [focus] day_start)" },
+                    "day_end":          { "type": "string", "description": "HH:MM (default [focus] day_end)" },
+                    "include_weekends": { "type": "boolean" },
+                    "count":            { "type": "integer", "description": "How many slots to return (default 5)" },
+                },
+                "required": ["duration_minutes"],
+            },
+        },
+        {
+            "name": "list_tasks",
+            "description": "Open tasks, most important first; with include_done also finished ones.",
+            "inputSchema": {
+                "type": "object",
+                "properties": { "include_done": { "type": "boolean" }, "tag": { "type": "string" } },
+            },
+        },
+        {
+            "name": "create_task",
+            "description": "Adds a task. Priority is 0 (none) to 3 (high).",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "title":    { "type": "string" },
+                    "due":      date,
+                    "priority": { "type": "integer", "minimum": 0, "maximum": 3 },
+                    "notes":    { "type": "string" },
+                    "tags":     { "type": "array", "items": { "type": "string" } },
+                },
+                "required": ["title"],
+            },
+        },
+        {
+            "name": "complete_task",
+            "description": "Marks an open task done, by id or by title. A repeating task gets its next occurrence.",
+            "inputSchema": {
+                "type": "object",
+                "properties": { "id": { "type": "string" }, "title": { "type": "string" } },
+            },
+        },
+    ])
+}
+
+async fn call(db: &Database, cfg: &AppConfig, name: &str, args: Value) -> Result<Value> {
+    match name {
+        "list_events"    => list_events(db, serde_json::from_value(args)?).await,
+        "create_event"   => {
+            let ev = serde_json::from_value::<NewEvent>(args)?.into_event()?;
+            db.upsert_event(&ev).await?;
+            Ok(event_json(&ev))
+        }
+        "find_free_slot" => find_free_slot(db, cfg, serde_json::from_value(args)?).await,
+        "list_tasks"     => list_tasks(db, serde_json::from_value(args)?).await,
+        "create_task"    => {
+            let t = serde_json::from_value::<NewTask>(args)?.into_task()?;
+            db.upsert_task(&t).await?;
+            Ok(task_json(&t))
+        }
+        "complete_task"  => complete_task(db, cfg, serde_json::from_value(args)?).await,
+        _                => bail!("No tool {name}"),
+    }
+}
+
+fn event_json(e: &Event) -> Value {
+    json!({
+        "id":          e.id,
+        "title":       e.title,
+        "start":       e.start.format("%Y-%m-%dT%H:%M").to_string(),
+        "end":         e.end.format("%Y-%m-%dT%H:%M").to_string(),
+        "all_day":     e.all_day,
+        "description": e.description,
+        "calendar_id": e.calendar_id,
+        "tag":         e.tag,
+    })
+}
+
+fn task_json(t: &Task) -> Value {
+    json!({
+        "id":        t.id,
+        "title":     t.title,
+        "done":      t.completed,
+        "due":       t.due.map(|d| d.format("%Y-%m-%d").to_string()),
+        "priority":  t.priority,
+        "tags":      t.tags,
+        "notes":     t.notes,
+        "repeat":    t.repeat,
+    })
+}
+
+fn date(s: &str) -> Result<NaiveDate> {
+    NaiveDate::parse_from_str(s, "%Y-%m-%d").map_err(|_| anyhow!("{s} isn't YYYY-MM-DD"))
+}
+
+fn time(s: &str) -> Result<NaiveTime> {
+    planner::parse_hm(s).ok_or_else(|| anyhow!("{s} isn't HH:MM"))
+}
+
+#[derive(Deserialize)]
+struct Range {
+    from: String,
+    to:   Option<String>,
+}
+
+async fn list_events(db: &Database, args: Range) -> Result<Value> {
+    let from = date(&args.from)?;
+    let to   = args.to.as_deref().map(date).transpose()?.unwrap_or(from).max(from);
+    let events = db.events_overlapping(day_bounds(from).0, day_bounds(to).1).await?;
+    Ok(events.iter().filter(|e| !e.time_entry).map(event_json).collect())
+}
+
+#[derive(Deserialize)]
+struct SlotQuery {
+    duration_minutes: i64,
+    from:             Option<String>,
+    days:             Option<i64>,
+    day_start:        Option<String>,
+    day_end:          Option<String>,
+    #[serde(default)]
+    include_weekends: bool,
+    count:            Option<usize>,
+}
+
+async fn find_free_slot(db: &Database, cfg: &AppConfig, args: SlotQuery) -> Result<Value> {
+    if args.duration_minutes < 5 { bail!("duration_minutes should be at least 5"); }
+    let length = Duration::try_minutes(args.duration_minutes).ok_or_else(|| anyhow!("duration_minutes is too large"))?;
+    let now    = Local::now().naive_local().and_utc();
+    let first  = args.from.as_deref().map(date).transpose()?.unwrap_or(now.date_naive());
+    let opens  = time(args.day_start.as_deref().unwrap_or(&cfg.focus.day_start))?;
+    let closes = time(args.day_end.as_deref().unwrap_or(&cfg.focus.day_end))?;
+    let want   = args.count.unwrap_or(5).max(1);
+
+    let mut slots = Vec::new();
+    for offset in 0..args.days.unwrap_or(7).clamp(1, 60) {
+        // Past the end of the calendar there's nothing left to search
+        let Some(day) = add_days(first, offset) else { break };
+        if !args.include_weekends && matches!(day.weekday(), Weekday::Sat | Weekday::Sun) { continue; }
+        let (from, to) = (day.and_time(opens).and_utc(), day.and_time(closes).and_utc());
+        let events = db.events_overlapping(from, to).await?;
+        for (start, end) in planner::free_gaps(&events, from, to, now.max(from)) {
+            if end - start < length { continue; }
+            slots.push(json!({
+                "start":      start.format("%Y-%m-%dT%H:%M").to_string(),
+                "end":        (start + length).format("%Y-%m-%dT%H:%M").to_string(),
+                "free_until": end.format("%H:%M").to_string(),
+            }));
+            if slots.len() == want { return Ok(slots.into()); }
+        }
+    }
+    Ok(slots.into())
+}
+
+#[derive(Deserialize)]
+struct TaskQuery {
+    #[serde(default)]
+    include_done: bool,
+    tag:          Option<String>,
+}
+
+async fn list_tasks(db: &Database, args: TaskQuery) -> Result<Value> {
+    let mut found = db.all_tasks().await?;
+    found.retain(|t| args.include_done || !t.completed);
+    if let Some(ref tag) = args.tag { found.retain(|t| t.tags.iter().any(|x| x.eq_ignore_ascii_case(tag))); }
+    Ok(found.iter().map(task_json).collect())
+}
+
+#[derive(Deserialize)]
+struct TaskRef {
+    id:    Option<String>,
+    title: Option<String>,
+}
+
+/// By id, else the open task with that title, else the only open one whose
+/// title contains it.
+async fn complete_task(db: &Database, cfg: &AppConfig, args: TaskRef) -> Result<Value> {
+    let open: Vec<Task> = db.all_tasks().await?.into_iter().filter(|t| !t.completed).collect();
+    let task = match (&args.id, args.title.as_deref().map(str::to_lowercase)) {
+        (Some(id), _) => open.iter().find(|t| &t.id == id).ok_or_else(|| anyhow!("No open task with id {id}"))?,
+        (None, Some(title)) => {
+            let exact: Vec<&Task> = open.iter().filter(|t| t.title.to_lowercase() == title).collect();
+            let found = if exact.is_empty() {
+                open.iter().filter(|t| t.title.to_lowercase().contains(&title)).collect()
+            } else {
+                exact
+            };
+            match found.as_slice() {
+                [t]  => *t,
+                []   => bail!("No open task titled {title:?}"),
+                many => bail!("{} open tasks match; pass an id: {}", many.len(),
+                    many.iter().map(|t| format!("{} ({})", t.title, t.id)).collect::<Vec<_>>().join(", ")),
+            }
+        }
+        (None, None) => bail!("Pass the task's id or title"),
+    };
+
+    let mut done = task.clone();
+    done.completed  = true;
+    done.dirty      = true;
+    done.updated_at = Utc::now();
+    db.upsert_task(&done).await?;
+    let next = repeat(db, cfg, &done).await?;
+    Ok(json!({ "completed": task_json(&done), "next": next.as_ref().map(task_json) }))
+}
+
+/// The next occurrence of a repeating task just completed, as the TUI adds
+/// it.
+async fn repeat(db: &Database, cfg: &AppConfig, done: &Task) -> Result<Option<Task>> {
+    let engine = holidays::HolidayEngine::from_config(&cfg.holidays);
+    let today  = Local::now().date_naive();
+    let Some((t, _)) = tasks::next_repeat(done, today, cfg.holidays.recurring_tasks, &engine) else { return Ok(None) };
+    db.upsert_task(&t).await?;
+    Ok(Some(t))
+}
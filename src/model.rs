@@ -0,0 +1,205 @@
+//! The data the calendar works on, independent of where it's kept: events,
+//! tasks and habits as the SQLite database stores them and `X` exports them.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::attendance::Attendance;
+
+/// Calendar ids under this prefix are layers kept on this machine; their
+/// events are never pushed to Google.
+pub const LOCAL_CALENDAR_PREFIX: &str = "local:";
+/// Calendar and task list ids under this prefix sync with Microsoft 365
+/// (Outlook and To Do) rather than Google.
+pub const OUTLOOK_PREFIX: &str = "outlook:";
+/// Task list ids under this prefix are Todoist projects.
+pub const TODOIST_PREFIX: &str = "todoist:";
+/// Task list ids under this prefix are Taskwarrior projects.
+pub const TASKWARRIOR_PREFIX: &str = "taskwarrior:";
+/// Google Calendar's event colours, `colorId` 1 to 11 in order, as its web
+/// UI names and shows them.
+pub const EVENT_COLORS: [(&str, &str); 11] = [
+    ("Lavender", "#7986cb"), ("Sage", "#33b679"), ("Grape", "#8e24aa"), ("Flamingo", "#e67c73"),
+    ("Banana", "#f6bf26"), ("Tangerine", "#f4511e"), ("Peacock", "#039be5"), ("Graphite", "#616161"),
+    ("Blueberry", "#3f51b5"), ("Basil", "#0b8043"), ("Tomato", "#d50000"),
+];
+
+/// Finished pomodoro sessions (`[pomodoro] log_to_calendar`).
+pub const FOCUS_CALENDAR: &str = "local:focus";
+/// Time entries recorded with the `Ctrl+t` timer.
+pub const TIME_CALENDAR: &str = "local:time";
+/// Task blocks accepted from "plan my day" (`p`).
+pub const PLAN_CALENDAR: &str = "local:plan";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Event {
+    pub id: String,
+    pub title: String,
+    pub description: Option<String>,
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+    pub all_day: bool,
+    pub calendar_id: Option<String>,
+    pub sync_id: Option<String>,
+    pub etag: Option<String>,
+    pub dirty: bool,
+    pub deleted: bool,
+    /// Local-only: the event is time off counted against the PTO allowance.
+    pub pto: bool,
+    /// Local-only check-in for a past event; `None` means unmarked.
+    pub attendance: Option<Attendance>,
+    /// IANA zone the event was scheduled in (from Google), if known.
+    pub timezone: Option<String>,
+    /// Title as Google has it, when `[titles]` rules rewrote it on pull.
+    pub remote_title: Option<String>,
+    /// Local-only category, a name from the `tags` table.
+    pub tag: Option<String>,
+    /// Local-only: the event is tracked time, counted in the time report.
+    #[serde(default)]
+    pub time_entry: bool,
+    /// Who scheduled it and who created it, as Google reports them, e.g.
+    /// `Ana Ruiz <ana@example.com>`. None on local events.
+    #[serde(default)]
+    pub organizer: Option<String>,
+    #[serde(default)]
+    pub creator: Option<String>,
+    /// Local-only travel or preparation time before the start, in minutes:
+    /// reminders go out that much earlier and the day list shades it.
+    #[serde(default)]
+    pub lead_minutes: i64,
+    /// The video call to join: Google's Meet link or conference entry point.
+    /// None on local events (see `meeting_url`).
+    #[serde(default)]
+    pub meet_url: Option<String>,
+    /// Google's `colorId`, `"1"` to `"11"` (see `EVENT_COLORS`); None keeps
+    /// the tag's colour here and the calendar's in Google.
+    #[serde(default)]
+    pub color: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl Event {
+    pub fn new(title: &str, start: DateTime<Utc>, end: DateTime<Utc>) -> Self {
+        let now = Utc::now();
+        Self {
+            id: Uuid::new_v4().to_string(), title: title.to_owned(),
+            description: None, start, end, all_day: false,
+            calendar_id: None, sync_id: None, etag: None,
+            dirty: true, deleted: false, pto: false, attendance: None, timezone: None, remote_title: None,
+            tag: None, time_entry: false, organizer: None, creator: None, lead_minutes: 0, meet_url: None, color: None, created_at: now, updated_at: now,
+        }
+    }
+
+    /// Where to join the call: the link Google gave, else the first Meet,
+    /// Zoom, Teams, Webex or Whereby link in the notes.
+    pub fn meeting_url(&self) -> Option<&str> {
+        self.meet_url.as_deref().or_else(|| self.description.as_deref().and_then(find_meeting_url))
+    }
+
+    /// Name and shade of the event's Google colour, if it has a known one.
+    pub fn color(&self) -> Option<(&'static str, &'static str)> {
+        let id: usize = self.color.as_deref()?.parse().ok()?;
+        EVENT_COLORS.get(id.checked_sub(1)?).copied()
+    }
+
+    pub fn is_focus_session(&self) -> bool {
+        self.calendar_id.as_deref() == Some(FOCUS_CALENDAR)
+    }
+
+    /// On a layer this machine keeps (focus sessions, timer entries): time
+    /// spent working, never a meeting.
+    pub fn is_local_layer(&self) -> bool {
+        self.calendar_id.as_deref().is_some_and(|c| c.starts_with(LOCAL_CALENDAR_PREFIX))
+    }
+
+    /// When getting there or getting ready begins: the start, less the
+    /// lead time.
+    pub fn lead_start(&self) -> DateTime<Utc> {
+        self.start - chrono::Duration::minutes(self.lead_minutes.max(0))
+    }
+
+    /// Whether it holds time another event in `[start, end)` would clash
+    /// with, lead time included. All-day events, PTO, local layers and
+    /// skipped or cancelled events don't, and back-to-back events don't
+    /// overlap.
+    pub fn clashes(&self, start: DateTime<Utc>, end: DateTime<Utc>) -> bool {
+        !self.all_day && !self.pto && !self.deleted && !self.is_local_layer()
+            && !matches!(self.attendance, Some(Attendance::Skipped | Attendance::Cancelled))
+            && self.lead_start() < end && self.end > start
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Task {
+    pub id: String,
+    pub title: String,
+    pub notes: Option<String>,
+    pub due: Option<DateTime<Utc>>,
+    pub completed: bool,
+    /// Local-only: 0 (none) up to `tasks::MAX_PRIORITY`; Google Tasks has
+    /// no priority.
+    pub priority: i64,
+    pub task_list_id: Option<String>,
+    pub sync_id: Option<String>,
+    pub dirty: bool,
+    pub deleted: bool,
+    /// Local-only, lowercase, without the `#`. Stored space-separated.
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Manual order (`J`/`K`), lowest first. 0 on a new task: upsert_task
+    /// puts it last.
+    #[serde(default)]
+    pub position: i64,
+    /// Local-only: when the task comes round again (a `habits::Schedule`
+    /// label). Completing it adds the next occurrence.
+    #[serde(default)]
+    pub repeat: Option<String>,
+    /// Local-only: how long it should take, in minutes (0 = no estimate).
+    /// What "plan my day" (`p`) books for it.
+    #[serde(default)]
+    pub estimate_minutes: i64,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl Task {
+    pub fn new(title: &str) -> Self {
+        let now = Utc::now();
+        Self {
+            id: Uuid::new_v4().to_string(), title: title.to_owned(),
+            notes: None, due: None, completed: false, priority: 0,
+            task_list_id: None, sync_id: None,
+            dirty: true, deleted: false, tags: Vec::new(), position: 0, repeat: None, estimate_minutes: 0,
+            created_at: now, updated_at: now,
+        }
+    }
+}
+
+/// A habit to keep; `schedule` is `daily`, `weekdays` or days like
+/// `mon,wed,fri` (see `habits::Schedule`).
+#[derive(Debug, Clone)]
+pub struct Habit {
+    pub id:         String,
+    pub name:       String,
+    pub schedule:   String,
+    pub created_at: DateTime<Utc>,
+}
+
+const MEETING_HOSTS: &[&str] = &[
+    "meet.google.com", "zoom.us", "teams.microsoft.com", "teams.live.com", "webex.com", "whereby.com",
+];
+
+/// The first `https://` link in `text` whose host is (or is under) one of
+/// the video services, e.g. `https://acme.zoom.us/j/123`.
+pub fn find_meeting_url(text: &str) -> Option<&str> {
+    text.match_indices("https://").find_map(|(at, _)| {
+        let url  = &text[at..];
+        let url  = &url[..url.find(|c: char| c.is_whitespace() || matches!(c, '"' | '<' | '>' | ')')).unwrap_or(url.len())];
+        let host = url["https://".len()..].split(['/', '?', '#', ':']).next().unwrap_or("");
+        MEETING_HOSTS.iter()
+            .any(|h| host == *h || host.ends_with(&format!(".{h}")))
+            .then_some(url)
+    })
+}
@@ -0,0 +1,184 @@
+use anyhow::{anyhow, bail};
+use std::io::Write;
+use std::time::Duration;
+use tokio::sync::mpsc;
+
+use super::{Notification, Notifier, Sending};
+use crate::config::Urgency;
+
+/// How long a `command` backend may run before it's killed: backends are
+/// sent to in turn, so a hung one would hold up every later reminder.
+const COMMAND_TIMEOUT: Duration = Duration::from_secs(30);
+
+pub struct Desktop;
+
+impl Notifier for Desktop {
+    fn name(&self) -> &'static str { "desktop" }
+
+    fn send<'a>(&'a self, n: &'a Notification) -> Sending<'a> {
+        Box::pin(async move {
+            let n = n.clone();
+            // D-Bus round trip is blocking
+            tokio::task::spawn_blocking(move || {
+                let mut out = notify_rust::Notification::new();
+                out.appname("LifeManager").summary(&n.title).body(&n.body);
+                // macOS has no urgency to set
+                #[cfg(not(target_os = "macos"))]
+                out.urgency(match n.urgency {
+                    Urgency::Low      => notify_rust::Urgency::Low,
+                    Urgency::Normal   => notify_rust::Urgency::Normal,
+                    Urgency::Critical => notify_rust::Urgency::Critical,
+                });
+                if let Some(sound) = &n.sound {
+                    out.sound_name(sound);
+                }
+                out.show().map(|_| ())
+            }).await??;
+            Ok(())
+        })
+    }
+}
+
+pub struct Bell {
+    pub toasts: Option<mpsc::UnboundedSender<Notification>>,
+}
+
+impl Notifier for Bell {
+    fn name(&self) -> &'static str { "bell" }
+
+    fn send<'a>(&'a self, n: &'a Notification) -> Sending<'a> {
+        Box::pin(async move {
+            match &self.toasts {
+                // The TUI rings the bell itself when it shows the toast
+                Some(tx) => { let _ = tx.send(n.clone()); }
+                None => {
+                    let mut out = std::io::stdout();
+                    writeln!(out, "\x07⏰ {} — {}", n.title, n.body)?;
+                    out.flush()?;
+                }
+            }
+            Ok(())
+        })
+    }
+}
+
+pub struct Webhook {
+    pub http: reqwest::Client,
+    pub url:  String,
+}
+
+impl Notifier for Webhook {
+    fn name(&self) -> &'static str { "webhook" }
+
+    fn send<'a>(&'a self, n: &'a Notification) -> Sending<'a> {
+        Box::pin(async move {
+            let body = serde_json::json!({
+                "event_id": n.event_id,
+                "title":    n.title,
+                "body":     n.body,
+                "start":    n.start.to_rfc3339(),
+                "urgency":  n.urgency.label(),
+                "sound":    n.sound,
+            });
+            self.http.post(&self.url).json(&body).send().await?.error_for_status()?;
+            Ok(())
+        })
+    }
+}
+
+pub struct Command {
+    pub command: String,
+}
+
+impl Notifier for Command {
+    fn name(&self) -> &'static str { "command" }
+
+    fn send<'a>(&'a self, n: &'a Notification) -> Sending<'a> {
+        Box::pin(async move {
+            let status = tokio::process::Command::new("sh")
+                .arg("-c").arg(&self.command)
+                .env("LM_TITLE", &n.title)
+                .env("LM_BODY", &n.body)
+                .env("LM_START", n.start.to_rfc3339())
+                .env("LM_EVENT_ID", &n.event_id)
+                .env("LM_URGENCY", n.urgency.label())
+                .env("LM_SOUND", n.sound.as_deref().unwrap_or(""))
+                .stdin(std::process::Stdio::null())
+                .kill_on_drop(true)
+                .status();
+            let status = tokio::time::timeout(COMMAND_TIMEOUT, status).await
+                .map_err(|_| anyhow!("`{}` timed out after {}s", self.command, COMMAND_TIMEOUT.as_secs()))??;
+            if !status.success() {
+                bail!("`{}` exited with {status}", self.command);
+            }
+            Ok(())
+        })
+    }
+}
+
+/// Plain-text message for chat backends: title on the first line.
+fn chat_text(n: &Notification) -> String {
+    format!("{}\n{}", n.title, n.body)
+}
+
+pub struct Telegram {
+    pub http:    reqwest::Client,
+    pub token:   String,
+    pub chat_id: String,
+    pub agenda:  bool,
+}
+
+impl Notifier for Telegram {
+    fn name(&self) -> &'static str { "telegram" }
+    fn wants_agenda(&self) -> bool { self.agenda }
+
+    fn send<'a>(&'a self, n: &'a Notification) -> Sending<'a> {
+        Box::pin(async move {
+            let url  = format!("https://api.telegram.org/bot{}/sendMessage", self.token);
+            // Low-urgency reminders arrive without a sound
+            let body = serde_json::json!({
+                "chat_id":              self.chat_id,
+                "text":                 chat_text(n),
+                "disable_notification": n.urgency == Urgency::Low,
+            });
+            // The URL holds the bot token: keep it out of errors and logs
+            let resp = self.http.post(url).json(&body).send().await.map_err(|e| e.without_url())?;
+            if !resp.status().is_success() {
+                let status = resp.status();
+                let reason = resp.json::<serde_json::Value>().await.ok()
+                    .and_then(|v| v["description"].as_str().map(str::to_owned))
+                    .unwrap_or_default();
+                bail!("Telegram returned {status}: {reason}");
+            }
+            Ok(())
+        })
+    }
+}
+
+pub struct Matrix {
+    pub http:         reqwest::Client,
+    pub homeserver:   String,
+    pub room:         String,
+    pub access_token: String,
+    pub agenda:       bool,
+}
+
+impl Notifier for Matrix {
+    fn name(&self) -> &'static str { "matrix" }
+    fn wants_agenda(&self) -> bool { self.agenda }
+
+    fn send<'a>(&'a self, n: &'a Notification) -> Sending<'a> {
+        Box::pin(async move {
+            let mut url = reqwest::Url::parse(&self.homeserver)?;
+            let txn     = uuid::Uuid::new_v4().to_string();
+            url.path_segments_mut()
+                .map_err(|_| anyhow::anyhow!("invalid homeserver URL {}", self.homeserver))?
+                .pop_if_empty()
+                .extend(["_matrix", "client", "v3", "rooms", &self.room, "send", "m.room.message", &txn]);
+            let body = serde_json::json!({ "msgtype": "m.text", "body": chat_text(n) });
+            self.http.put(url).bearer_auth(&self.access_token).json(&body)
+                .send().await?.error_for_status()?;
+            Ok(())
+        })
+    }
+}
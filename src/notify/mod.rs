@@ -0,0 +1,222 @@
+//! Event reminders and the backends that deliver them.
+//!
+//! Each `[[notify.backends]]` entry becomes a `Notifier`; every reminder
+//! goes to all of them, and the daily agenda to those that opt in. Sent reminders are recorded in the database, so the
+//! TUI and a cron'd `lm remind` never both deliver the same one.
+//!
+//! `[[notify.rules]]` give reminders for some events another urgency or
+//! sound, so a flight doesn't ping like the daily standup.
+
+mod backends;
+
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Local, NaiveTime, Utc};
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::mpsc;
+
+use crate::calendar::day_bounds;
+use crate::config::{NotifyBackend, NotifyConfig, NotifyRule, Urgency};
+use crate::db::{Database, Event};
+
+/// How often the TUI's background loop looks for due reminders.
+const CHECK_EVERY: Duration = Duration::from_secs(30);
+
+#[derive(Debug, Clone)]
+pub struct Notification {
+    pub event_id: String,
+    pub title:    String,
+    pub body:     String,
+    pub start:    DateTime<Utc>,
+    pub urgency:  Urgency,
+    pub sound:    Option<String>,
+}
+
+impl Notification {
+    /// With the urgency and sound of the first rule in `cfg` matching `ev`,
+    /// else `cfg`'s own. `now` is wall time, as event times are stored.
+    pub fn reminder(ev: &Event, now: DateTime<Utc>, cfg: &NotifyConfig) -> Self {
+        let mins = (ev.start - now).num_minutes().max(0);
+        let when = if mins == 0 { "now".to_owned() } else { format!("in {mins} min") };
+        let mut body = format!("{}–{} · starts {when}", ev.start.format("%H:%M"), ev.end.format("%H:%M"));
+        if ev.lead_minutes > 0 {
+            body.push_str(&format!(" · {} min lead time from {}", ev.lead_minutes, ev.lead_start().format("%H:%M")));
+        }
+        let rule = cfg.rules.iter().find(|r| matches(r, ev));
+        Self {
+            event_id: ev.id.clone(),
+            title:    ev.title.clone(),
+            body,
+            start:    ev.start,
+            urgency:  rule.and_then(|r| r.urgency).unwrap_or(cfg.urgency),
+            sound:    rule.and_then(|r| r.sound.clone()).or_else(|| cfg.sound.clone()),
+        }
+    }
+}
+
+fn matches(rule: &NotifyRule, ev: &Event) -> bool {
+    let tag = rule.tag.as_deref().map(|t| t.trim_start_matches('#'));
+    tag.is_none_or(|t| ev.tag.as_deref().is_some_and(|e| e.eq_ignore_ascii_case(t)))
+        && rule.calendar.as_deref().is_none_or(|c| ev.calendar_id.as_deref().unwrap_or("primary") == c)
+        && rule.title.as_deref().is_none_or(|t| ev.title.to_lowercase().contains(&t.to_lowercase()))
+}
+
+pub type Sending<'a> = Pin<Box<dyn Future<Output = Result<()>> + Send + 'a>>;
+
+pub trait Notifier: Send + Sync {
+    /// Shown in logs when delivery fails.
+    fn name(&self) -> &'static str;
+    fn send<'a>(&'a self, n: &'a Notification) -> Sending<'a>;
+    /// Whether the daily agenda goes here too (see `[notify] agenda_at`).
+    fn wants_agenda(&self) -> bool { false }
+}
+
+pub struct Notifiers {
+    backends: Vec<Box<dyn Notifier>>,
+}
+
+impl Notifiers {
+    /// `toasts` is where the bell backend hands reminders to a running TUI;
+    /// without one it prints them instead.
+    pub fn from_config(cfg: &NotifyConfig, toasts: Option<mpsc::UnboundedSender<Notification>>) -> Self {
+        let http = reqwest::Client::new();
+        let backends = cfg.backends.iter().map(|b| -> Box<dyn Notifier> {
+            match b {
+                NotifyBackend::Desktop           => Box::new(backends::Desktop),
+                NotifyBackend::Bell              => Box::new(backends::Bell { toasts: toasts.clone() }),
+                NotifyBackend::Webhook { url }   => Box::new(backends::Webhook { http: http.clone(), url: url.clone() }),
+                NotifyBackend::Command { command } => Box::new(backends::Command { command: command.clone() }),
+                NotifyBackend::Telegram { token, chat_id, agenda } => Box::new(backends::Telegram {
+                    http: http.clone(), token: token.clone(), chat_id: chat_id.clone(), agenda: *agenda,
+                }),
+                NotifyBackend::Matrix { homeserver, room, access_token, agenda } => Box::new(backends::Matrix {
+                    http:         http.clone(),
+                    homeserver:   homeserver.clone(),
+                    room:         room.clone(),
+                    access_token: access_token.clone(),
+                    agenda:       *agenda,
+                }),
+            }
+        }).collect();
+        Self { backends }
+    }
+
+    pub fn is_empty(&self) -> bool { self.backends.is_empty() }
+
+    /// Delivers to every backend; one failing doesn't stop the others.
+    pub async fn send(&self, n: &Notification) {
+        self.send_to(n, |_| true).await;
+    }
+
+    async fn send_to(&self, n: &Notification, wanted: impl Fn(&dyn Notifier) -> bool) {
+        for b in self.backends.iter().filter(|b| wanted(b.as_ref())) {
+            if let Err(e) = b.send(n).await {
+                tracing::warn!("Notification via {} failed: {e}", b.name());
+            }
+        }
+    }
+
+    fn wants_agenda(&self) -> bool { self.backends.iter().any(|b| b.wants_agenda()) }
+}
+
+/// Sends every reminder that is due at wall time `now` and unclaimed, and
+/// again those snoozed from the TUI's banner whose snooze ran out; returns
+/// how many.
+pub async fn send_due(db: &Database, notifiers: &Notifiers, cfg: &NotifyConfig, now: DateTime<Utc>) -> Result<usize> {
+    let mut sent = 0;
+    for ev in &db.snoozes_due(now).await? {
+        if !db.claim_snooze(ev).await? { continue; }
+        // Stands in for the first reminder, if that was snoozed before it went
+        db.claim_reminder(ev).await?;
+        notifiers.send(&Notification::reminder(ev, now, cfg)).await;
+        sent += 1;
+    }
+    let due  = db.due_reminders(now, chrono::Duration::minutes(cfg.lead_minutes.max(0))).await?;
+    for ev in &due {
+        if !db.claim_reminder(ev).await? { continue; }
+        notifiers.send(&Notification::reminder(ev, now, cfg)).await;
+        sent += 1;
+    }
+    Ok(sent)
+}
+
+/// Today's agenda, once per day from `[notify] agenda_at` on, to backends
+/// that asked for it. Returns whether it was sent by this call.
+pub async fn send_agenda(db: &Database, notifiers: &Notifiers, cfg: &NotifyConfig) -> Result<bool> {
+    let Some(at) = cfg.agenda_at.as_deref() else { return Ok(false) };
+    if !notifiers.wants_agenda() { return Ok(false); }
+    let at  = NaiveTime::parse_from_str(at, "%H:%M")
+        .map_err(|_| anyhow!("[notify] agenda_at must be HH:MM, got {at:?}"))?;
+    let now = Local::now();
+    if now.time() < at { return Ok(false); }
+
+    let today = now.date_naive();
+    if !db.claim_agenda(today).await? { return Ok(false); }
+
+    let (from, to) = day_bounds(today);
+    let events = db.events_overlapping(from, to).await?;
+    let body = if events.is_empty() {
+        "Nothing scheduled.".to_owned()
+    } else {
+        events.iter().map(|e| {
+            let time = if e.all_day { "all-day".to_owned() } else { e.start.format("%H:%M").to_string() };
+            format!("{time:<8}{}", e.title)
+        }).collect::<Vec<_>>().join("\n")
+    };
+    let agenda = Notification {
+        event_id: format!("agenda-{today}"),
+        title:    format!("Today — {}", today.format("%A, %B %-d")),
+        body,
+        start:    from,
+        urgency:  Urgency::Low,
+        sound:    None,
+    };
+    notifiers.send_to(&agenda, |b| b.wants_agenda()).await;
+    Ok(true)
+}
+
+/// The local clock read as the store keeps event times: wall time in a
+/// UTC-typed value (see `App::poll_banner`).
+pub fn wall_now() -> DateTime<Utc> {
+    Local::now().naive_local().and_utc()
+}
+
+/// Checks for due reminders (and the agenda) every `CHECK_EVERY` until the
+/// runtime shuts down.
+pub fn spawn(db: Database, notifiers: Notifiers, cfg: NotifyConfig) {
+    let notifiers = Arc::new(notifiers);
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(CHECK_EVERY);
+        loop {
+            interval.tick().await;
+            if let Err(e) = send_due(&db, &notifiers, &cfg, wall_now()).await {
+                tracing::warn!("Reminder check failed: {e}");
+            }
+            if let Err(e) = send_agenda(&db, &notifiers, &cfg).await {
+                tracing::warn!("Agenda failed: {e}");
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn reminders_count_down_in_wall_time() {
+        // 08:50 in Hanoi (UTC+7) is 01:50 UTC; the event is stored as 09:00
+        let instant = Utc.with_ymd_and_hms(2026, 10, 15, 1, 50, 0).unwrap();
+        let now     = instant.with_timezone(&chrono_tz::Asia::Ho_Chi_Minh).naive_local().and_utc();
+        let start   = Utc.with_ymd_and_hms(2026, 10, 15, 9, 0, 0).unwrap();
+        let ev      = Event::new("Standup", start, start + chrono::Duration::minutes(15));
+
+        let n = Notification::reminder(&ev, now, &NotifyConfig::default());
+        assert_eq!(n.body, "09:00–09:15 · starts in 10 min");
+        let n = Notification::reminder(&ev, start, &NotifyConfig::default());
+        assert_eq!(n.body, "09:00–09:15 · starts now");
+    }
+}
@@ -0,0 +1,155 @@
+//! Ideal-week planning: lays the `[[ideal_week]]` template over a real week
+//! so plan and reality can be compared block by block. Also "plan my day":
+//! the day's tasks, by their estimates, fitted into its free time.
+
+use chrono::{DateTime, Datelike, Duration, NaiveDate, NaiveTime, Timelike, Utc};
+use std::collections::HashSet;
+
+use crate::attendance::Attendance;
+use crate::calendar::add_days;
+use crate::config::IdealBlock;
+use crate::db::{Event, Task, PLAN_CALENDAR};
+use crate::tasks;
+
+/// A template block placed on a concrete date.
+#[derive(Debug, Clone)]
+pub struct PlannedBlock {
+    pub title: String,
+    pub start: DateTime<Utc>,
+    pub end:   DateTime<Utc>,
+    /// A real event overlaps the block.
+    pub met:   bool,
+}
+
+pub fn parse_hm(s: &str) -> Option<NaiveTime> {
+    NaiveTime::parse_from_str(s.trim(), "%H:%M").ok()
+}
+
+/// Template blocks for the 7 days from `monday`, ordered by start time.
+/// Blocks with unparseable or inverted times are skipped with a warning.
+pub fn week_plan(template: &[IdealBlock], monday: NaiveDate, events: &[Event]) -> Vec<PlannedBlock> {
+    let mut out = Vec::new();
+    for b in template {
+        let (Some(st), Some(et)) = (parse_hm(&b.start), parse_hm(&b.end)) else {
+            tracing::warn!("ideal_week block {:?}: times must be HH:MM", b.title);
+            continue;
+        };
+        if et <= st {
+            tracing::warn!("ideal_week block {:?}: end is not after start", b.title);
+            continue;
+        }
+        for i in 0..7 {
+            let Some(date) = add_days(monday, i) else { break };
+            if !b.days.contains(&date.weekday()) { continue; }
+            let start = date.and_time(st).and_utc();
+            let end   = date.and_time(et).and_utc();
+            let met   = events.iter().any(|e| !e.all_day && e.start < end && e.end > start);
+            out.push(PlannedBlock { title: b.title.clone(), start, end, met });
+        }
+    }
+    out.sort_by_key(|p| p.start);
+    out
+}
+
+// ─── Plan my day ─────────────────────────────────────────────────────────────
+
+/// Left free after each planned task, to get up and switch.
+const BREAK_MINUTES: i64 = 5;
+
+/// A task booked into a gap.
+#[derive(Debug, Clone)]
+pub struct TaskBlock {
+    pub task:  Task,
+    pub start: DateTime<Utc>,
+    pub end:   DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct DayPlan {
+    pub blocks:      Vec<TaskBlock>,
+    /// Estimated tasks no gap was long enough for.
+    pub unplaced:    Vec<Task>,
+    /// Tasks due with no estimate to book them by.
+    pub unestimated: Vec<Task>,
+}
+
+/// Proposes blocks for the open tasks due on `date` (on `today` also the
+/// overdue ones) within working hours `from`–`to`, starting no earlier than
+/// `not_before` (the time now, when planning today). Timed events hold
+/// their time, lead time included, and so do the local layers: a focus
+/// session or a block planned earlier isn't free. Tasks already planned
+/// that day, and those in `left_out`, aren't proposed again.
+///
+/// Most important first — priority, then the earliest due, then the
+/// manual order — each in the earliest gap it fits whole.
+pub fn plan_day(
+    tasks:      &[Task],
+    events:     &[Event],
+    date:       NaiveDate,
+    today:      NaiveDate,
+    (from, to): (NaiveTime, NaiveTime),
+    not_before: DateTime<Utc>,
+    left_out:   &HashSet<String>,
+) -> DayPlan {
+    let planned: HashSet<&str> = events.iter()
+        .filter(|e| e.calendar_id.as_deref() == Some(PLAN_CALENDAR) && !e.deleted)
+        .map(|e| e.title.as_str())
+        .collect();
+    let mut due: Vec<&Task> = tasks.iter()
+        .filter(|t| !t.completed && !t.deleted && !left_out.contains(&t.id) && !planned.contains(t.title.as_str()))
+        .filter(|t| t.due.is_some_and(|d| d.date_naive() == date) || (date == today && tasks::is_overdue(t, today)))
+        .collect();
+    due.sort_by(|a, b| b.priority.cmp(&a.priority).then(a.due.cmp(&b.due)).then(a.position.cmp(&b.position)));
+
+    let mut gaps = free_gaps(events, date.and_time(from).and_utc(), date.and_time(to).and_utc(), not_before);
+    let mut plan = DayPlan::default();
+    for t in due {
+        if t.estimate_minutes <= 0 {
+            plan.unestimated.push(t.clone());
+            continue;
+        }
+        let length = Duration::minutes(t.estimate_minutes);
+        match gaps.iter_mut().find(|(s, e)| *e - *s >= length) {
+            Some(gap) => {
+                plan.blocks.push(TaskBlock { task: t.clone(), start: gap.0, end: gap.0 + length });
+                gap.0 += length + Duration::minutes(BREAK_MINUTES);
+            }
+            None => plan.unplaced.push(t.clone()),
+        }
+    }
+    plan.blocks.sort_by_key(|b| b.start);
+    plan
+}
+
+/// The stretches of `[from, to)` no event holds, from `not_before` rounded
+/// up to the next five minutes.
+pub fn free_gaps(
+    events: &[Event], from: DateTime<Utc>, to: DateTime<Utc>, not_before: DateTime<Utc>,
+) -> Vec<(DateTime<Utc>, DateTime<Utc>)> {
+    let rounded = not_before.with_second(0).and_then(|t| t.with_nanosecond(0)).unwrap_or(not_before);
+    let rounded = rounded.checked_add_signed(Duration::minutes((5 - rounded.minute() as i64 % 5) % 5)).unwrap_or(rounded);
+    let mut busy: Vec<(DateTime<Utc>, DateTime<Utc>)> = events.iter()
+        .filter(|e| !e.all_day && !e.pto && !e.deleted)
+        .filter(|e| !matches!(e.attendance, Some(Attendance::Skipped | Attendance::Cancelled)))
+        .map(|e| (e.lead_start(), e.end))
+        .collect();
+    busy.sort();
+
+    let mut gaps = Vec::new();
+    let mut at   = from.max(rounded);
+    for (s, e) in busy {
+        if s > at && at < to { gaps.push((at, s.min(to))); }
+        at = at.max(e);
+    }
+    if at < to { gaps.push((at, to)); }
+    gaps
+}
+
+/// The accepted block as an event on the plan layer.
+pub fn block_event(block: &TaskBlock) -> Event {
+    let mut ev = Event::new(&block.task.title, block.start, block.end);
+    ev.calendar_id = Some(PLAN_CALENDAR.to_owned());
+    ev.tag         = block.task.tags.first().cloned();
+    ev.dirty       = false;
+    ev
+}
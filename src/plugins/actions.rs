@@ -0,0 +1,121 @@
+//! "Send to" actions declared with `[[actions]]`: `|` pipes the selected
+//! event or task to a command, e.g. one that starts a meeting-notes file
+//! from a template or adds a line to a timesheet.
+//!
+//! The command gets the item as one JSON object on stdin, the event or task
+//! as stored plus `"kind": "event"` or `"task"`, and `LM_KIND`, `LM_ID` and
+//! `LM_TITLE` in its environment. The first line it prints shows in the
+//! status bar.
+
+use anyhow::{anyhow, bail, Result};
+use serde::Serialize;
+use std::process::Stdio;
+use std::time::Duration;
+use tokio::io::AsyncWriteExt;
+use tokio::process::Command;
+use tokio::sync::mpsc;
+
+use crate::config::{ActionConfig, ActionOn};
+
+/// The selected item, ready to send.
+#[derive(Clone)]
+pub struct Subject {
+    pub kind:  &'static str,
+    pub id:    String,
+    pub title: String,
+    json:      String,
+}
+
+impl Subject {
+    pub fn new<T: Serialize>(kind: &'static str, id: &str, title: &str, item: &T) -> Result<Self> {
+        let mut value = serde_json::to_value(item)?;
+        if let Some(map) = value.as_object_mut() {
+            map.insert("kind".into(), kind.into());
+        }
+        Ok(Self { kind, id: id.to_owned(), title: title.to_owned(), json: value.to_string() })
+    }
+}
+
+/// Whether `action` is offered on items of `kind`.
+pub fn applies(action: &ActionConfig, kind: &str) -> bool {
+    match action.on {
+        ActionOn::Both   => true,
+        ActionOn::Events => kind == "event",
+        ActionOn::Tasks  => kind == "task",
+    }
+}
+
+/// Runs the action's command through the shell with `subject` on stdin,
+/// giving up after its `timeout`; the first line of output.
+pub async fn run(action: &ActionConfig, subject: &Subject) -> Result<String> {
+    let (shell, flag) = if cfg!(windows) { ("cmd", "/C") } else { ("sh", "-c") };
+    let mut child = Command::new(shell).arg(flag).arg(&action.command)
+        .env("LM_KIND", subject.kind)
+        .env("LM_ID", &subject.id)
+        .env("LM_TITLE", &subject.title)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()?;
+    if let Some(mut stdin) = child.stdin.take() {
+        // A command that doesn't read its input is fine
+        let _ = stdin.write_all(subject.json.as_bytes()).await;
+    }
+    let timeout = Duration::from_secs(action.timeout.max(1));
+    let out = tokio::time::timeout(timeout, child.wait_with_output()).await
+        .map_err(|_| anyhow!("timed out after {}s", timeout.as_secs()))??;
+    let first = |bytes: &[u8]| String::from_utf8_lossy(bytes).lines()
+        .find(|l| !l.trim().is_empty())
+        .map(|l| l.trim().to_owned());
+    if !out.status.success() {
+        bail!("{}: {}", out.status, first(&out.stderr).unwrap_or_else(|| "no output".into()));
+    }
+    Ok(first(&out.stdout).unwrap_or_default())
+}
+
+/// Every declared action, and the runs in flight.
+pub struct Actions {
+    pub list: Vec<ActionConfig>,
+    tx:       mpsc::UnboundedSender<(usize, String, Result<String>)>,
+    rx:       mpsc::UnboundedReceiver<(usize, String, Result<String>)>,
+}
+
+impl Actions {
+    pub fn new(list: Vec<ActionConfig>) -> Self {
+        let (tx, rx) = mpsc::unbounded_channel();
+        Self { list, tx, rx }
+    }
+
+    /// Indices of the actions offered on items of `kind`.
+    pub fn for_kind(&self, kind: &str) -> Vec<usize> {
+        (0..self.list.len()).filter(|&i| applies(&self.list[i], kind)).collect()
+    }
+
+    /// Starts action `i` on `subject` in the background.
+    pub fn send(&self, i: usize, subject: Subject) {
+        let Some(action) = self.list.get(i).cloned() else { return };
+        let tx = self.tx.clone();
+        tokio::spawn(async move {
+            let result = run(&action, &subject).await;
+            let _ = tx.send((i, subject.title, result));
+        });
+    }
+
+    /// A status line for the newest finished run, if any finished.
+    pub fn poll(&mut self) -> Option<String> {
+        let mut status = None;
+        while let Ok((i, title, result)) = self.rx.try_recv() {
+            let Some(action) = self.list.get(i) else { continue };
+            status = Some(match result {
+                Ok(line) if line.is_empty() => format!("✓ {}: “{title}” sent", action.name),
+                Ok(line)                    => format!("✓ {}: {line}", action.name),
+                Err(e) => {
+                    tracing::warn!("action {}: {e}", action.name);
+                    format!("✗ {}: {e}", action.name)
+                }
+            });
+        }
+        status
+    }
+}
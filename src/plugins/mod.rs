@@ -0,0 +1,148 @@
+//! Custom panels declared with `[[panels]]`: an external command prints
+//! JSON, and the `C` overlay lists it. Nothing here knows what the list is
+//! about, so a "PRs to review" or "server alerts" panel is a script, not a
+//! fork of the UI.
+//!
+//! The command's stdout is an array of items, or an object with an `items`
+//! array. An item is a plain string, or an object:
+//!
+//! ```json
+//! {"title": "Fix login redirect", "detail": "#412 · ana", "url": "https://…", "level": "warn"}
+//! ```
+//!
+//! Only `title` is required. Enter opens `url`; `level` (`info`, `ok`,
+//! `warn` or `error`) colours the row.
+
+pub mod actions;
+
+use anyhow::{anyhow, bail, Result};
+use serde::Deserialize;
+use std::time::{Duration, Instant};
+use tokio::process::Command;
+use tokio::sync::mpsc;
+
+use crate::config::PanelConfig;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Level {
+    #[default]
+    Info,
+    Ok,
+    Warn,
+    Error,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Item {
+    pub title:  String,
+    #[serde(default)]
+    pub detail: Option<String>,
+    #[serde(default)]
+    pub url:    Option<String>,
+    #[serde(default)]
+    pub level:  Level,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum RawItem {
+    Text(String),
+    Full(Item),
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Output {
+    List(Vec<RawItem>),
+    Wrapped { items: Vec<RawItem> },
+}
+
+pub fn parse(stdout: &str) -> Result<Vec<Item>> {
+    let out: Output = serde_json::from_str(stdout.trim())
+        .map_err(|e| anyhow!("not a JSON list of items: {e}"))?;
+    let (Output::List(raw) | Output::Wrapped { items: raw }) = out;
+    Ok(raw.into_iter().map(|r| match r {
+        RawItem::Text(title) => Item { title, detail: None, url: None, level: Level::Info },
+        RawItem::Full(item)  => item,
+    }).collect())
+}
+
+/// Runs the panel's command through the shell, giving up after its
+/// `timeout`.
+pub async fn run(panel: &PanelConfig) -> Result<Vec<Item>> {
+    let (shell, flag) = if cfg!(windows) { ("cmd", "/C") } else { ("sh", "-c") };
+    let output = Command::new(shell).arg(flag).arg(&panel.command).kill_on_drop(true).output();
+    let out = tokio::time::timeout(Duration::from_secs(panel.timeout.max(1)), output).await
+        .map_err(|_| anyhow!("timed out after {}s", panel.timeout.max(1)))??;
+    if !out.status.success() {
+        let stderr = String::from_utf8_lossy(&out.stderr);
+        bail!("{}: {}", out.status, stderr.lines().find(|l| !l.trim().is_empty()).unwrap_or("no output").trim());
+    }
+    parse(&String::from_utf8_lossy(&out.stdout))
+}
+
+/// A panel's last run.
+#[derive(Default)]
+pub struct State {
+    pub items:   Vec<Item>,
+    /// Why the last run failed; the items from before it stay listed.
+    pub error:   Option<String>,
+    pub fetched: Option<Instant>,
+    pub running: bool,
+}
+
+/// Every declared panel, and the runs in flight.
+pub struct Plugins {
+    pub panels: Vec<PanelConfig>,
+    pub states: Vec<State>,
+    tx:         mpsc::UnboundedSender<(usize, Result<Vec<Item>>)>,
+    rx:         mpsc::UnboundedReceiver<(usize, Result<Vec<Item>>)>,
+}
+
+impl Plugins {
+    pub fn new(panels: Vec<PanelConfig>) -> Self {
+        let (tx, rx) = mpsc::unbounded_channel();
+        let states   = panels.iter().map(|_| State::default()).collect();
+        Self { panels, states, tx, rx }
+    }
+
+    /// Never run, or last run longer ago than its `every`.
+    pub fn stale(&self, i: usize) -> bool {
+        let (Some(panel), Some(state)) = (self.panels.get(i), self.states.get(i)) else { return false };
+        state.fetched.is_none_or(|at| at.elapsed() >= Duration::from_secs(panel.every.max(1)))
+    }
+
+    /// Starts panel `i`'s command in the background, unless it's running.
+    pub fn refresh(&mut self, i: usize) {
+        let (Some(panel), Some(state)) = (self.panels.get(i).cloned(), self.states.get_mut(i)) else { return };
+        if state.running { return; }
+        state.running = true;
+        let tx = self.tx.clone();
+        tokio::spawn(async move {
+            let _ = tx.send((i, run(&panel).await));
+        });
+    }
+
+    /// Takes in finished runs; true if there were any.
+    pub fn poll(&mut self) -> bool {
+        let mut any = false;
+        while let Ok((i, result)) = self.rx.try_recv() {
+            let (Some(panel), Some(state)) = (self.panels.get(i), self.states.get_mut(i)) else { continue };
+            state.running = false;
+            state.fetched = Some(Instant::now());
+            match result {
+                Ok(items) => {
+                    state.items = items;
+                    state.error = None;
+                }
+                Err(e) => {
+                    tracing::warn!("panel {}: {e}", panel.name);
+                    state.error = Some(e.to_string());
+                }
+            }
+            any = true;
+        }
+        any
+    }
+}
@@ -0,0 +1,83 @@
+//! Vacation / PTO balance tracking.
+//!
+//! Events flagged `pto` are counted per working day (weekends and holidays
+//! are free); timed events of 4 hours or less count as half a day.
+
+use chrono::{Datelike, Duration, NaiveDate};
+use std::collections::{BTreeMap, HashSet};
+
+use crate::calendar::is_working_day;
+use crate::config::PtoConfig;
+use crate::db::Event;
+use crate::holidays::HolidayEngine;
+
+#[derive(Debug, Clone)]
+pub struct PtoYear {
+    pub year:       i32,
+    pub allowance:  f64,
+    pub carried_in: f64,
+    pub used:       f64,
+}
+
+impl PtoYear {
+    pub fn remaining(&self) -> f64 { self.allowance + self.carried_in - self.used }
+}
+
+/// Days of leave an event consumes up to `until`, keyed by date.
+fn days_used(ev: &Event, days_off: &HashSet<NaiveDate>, until: NaiveDate) -> Vec<(NaiveDate, f64)> {
+    let first = ev.start.date_naive();
+    // All-day ends are exclusive; timed events end on the day they end.
+    let last = if ev.all_day {
+        ev.end.date_naive().pred_opt().unwrap_or(first).max(first)
+    } else {
+        ev.end.date_naive().max(first)
+    };
+    // A bogus far-future end date shouldn't turn into a century-long loop
+    let last = last.min(until);
+    let weight = if !ev.all_day && first == last && ev.end - ev.start <= Duration::hours(4) {
+        0.5
+    } else {
+        1.0
+    };
+
+    let mut out = Vec::new();
+    let mut d = first;
+    while d <= last {
+        if is_working_day(d, days_off) { out.push((d, weight)); }
+        let Some(next) = d.succ_opt() else { break };
+        d = next;
+    }
+    out
+}
+
+/// Per-year balances from the first tracked year through `through_year`,
+/// newest first. Unused days carry into the next year up to `carry_over_max`.
+pub fn summarize(events: &[Event], cfg: &PtoConfig, holidays: &HolidayEngine, through_year: i32) -> Vec<PtoYear> {
+    let first_event = events.iter().map(|e| e.start.year()).min();
+    let since = cfg.since.or(first_event).unwrap_or(through_year).min(through_year);
+
+    let days_off: HashSet<NaiveDate> = holidays.days_off(since..=through_year);
+
+    let until = NaiveDate::from_ymd_opt(through_year, 12, 31).unwrap_or(NaiveDate::MAX);
+    let mut used: BTreeMap<i32, f64> = BTreeMap::new();
+    for ev in events {
+        for (date, w) in days_used(ev, &days_off, until) {
+            *used.entry(date.year()).or_default() += w;
+        }
+    }
+
+    let mut out: Vec<PtoYear> = Vec::new();
+    let mut carry = 0.0;
+    for year in since..=through_year {
+        let y = PtoYear {
+            year,
+            allowance:  cfg.annual_days,
+            carried_in: carry,
+            used:       used.get(&year).copied().unwrap_or(0.0),
+        };
+        carry = y.remaining().clamp(0.0, cfg.carry_over_max);
+        out.push(y);
+    }
+    out.reverse();
+    out
+}
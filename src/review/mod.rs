@@ -0,0 +1,191 @@
+//! Year in review (`lm review [year]`): a handful of fun totals for one
+//! calendar year, printed to the terminal or written as Markdown / HTML.
+//!
+//! Events count in the year they start. Tasks have no completion date, so a
+//! completed task counts in the year it was last changed.
+
+use chrono::{Datelike, NaiveDate};
+use std::collections::HashMap;
+
+use crate::db::{Event, Habit, Task};
+use crate::habits::{self, Checks};
+use crate::holidays::Holiday;
+
+#[derive(Debug, Clone)]
+pub struct YearReview {
+    pub year:          i32,
+    pub events:        usize,
+    /// Hours in timed events; all-day events aren't counted.
+    pub event_hours:   f64,
+    /// Month number and its event count.
+    pub busiest_month: Option<(u32, usize)>,
+    pub busiest_day:   Option<(NaiveDate, usize)>,
+    /// As first written, and how many times it came up.
+    pub top_title:     Option<(String, usize)>,
+    pub tasks_done:    usize,
+    /// Habit and its longest run of due days done within the year.
+    pub habit_streak:  Option<(String, usize)>,
+    /// Distinct days with a holiday, and the holidays on them.
+    pub holiday_days:  usize,
+    pub holidays:      Vec<Holiday>,
+}
+
+pub fn build(
+    year: i32, events: &[Event], tasks: &[Task], habits: (&[Habit], &Checks), holidays: Vec<Holiday>,
+) -> YearReview {
+    let events: Vec<&Event> = events.iter().filter(|e| e.start.year() == year).collect();
+
+    let mut months: HashMap<u32, usize>       = HashMap::new();
+    let mut days:   HashMap<NaiveDate, usize> = HashMap::new();
+    let mut titles: HashMap<String, (String, usize)> = HashMap::new();
+    for ev in &events {
+        *months.entry(ev.start.month()).or_default() += 1;
+        *days.entry(ev.start.date_naive()).or_default() += 1;
+        let key = ev.title.trim().to_lowercase();
+        if key.is_empty() { continue; }
+        titles.entry(key).or_insert_with(|| (ev.title.trim().to_owned(), 0)).1 += 1;
+    }
+
+    let event_hours = events.iter()
+        .filter(|e| !e.all_day)
+        .map(|e| (e.end - e.start).num_minutes().max(0) as f64 / 60.0)
+        .sum();
+
+    // Ties go to the earlier month / day / alphabetically first title
+    let busiest_month = months.into_iter().max_by_key(|&(m, n)| (n, std::cmp::Reverse(m)));
+    let busiest_day   = days.into_iter().max_by_key(|&(d, n)| (n, std::cmp::Reverse(d)));
+    let top_title     = titles.into_iter()
+        .max_by(|(ka, (_, a)), (kb, (_, b))| a.cmp(b).then(kb.cmp(ka)))
+        .map(|(_, t)| t)
+        .filter(|(_, n)| *n > 1);
+
+    let tasks_done = tasks.iter()
+        .filter(|t| t.completed && !t.deleted && t.updated_at.year() == year)
+        .count();
+
+    // The current year only counts up to today
+    let (habits, checks) = habits;
+    let first = NaiveDate::from_ymd_opt(year, 1, 1).unwrap_or_default();
+    let last  = NaiveDate::from_ymd_opt(year, 12, 31).unwrap_or_default()
+        .min(chrono::Local::now().date_naive());
+    let habit_streak = habits.iter()
+        .map(|h| (h.name.clone(), habits::longest_streak(h, checks, first, last)))
+        .filter(|(_, n)| *n > 0)
+        .max_by_key(|(_, n)| *n);
+
+    let mut holiday_days: Vec<NaiveDate> = holidays.iter().map(|h| h.date).collect();
+    holiday_days.dedup();
+
+    YearReview {
+        year,
+        events: events.len(),
+        event_hours,
+        busiest_month,
+        busiest_day,
+        top_title,
+        tasks_done,
+        habit_streak,
+        holiday_days: holiday_days.len(),
+        holidays,
+    }
+}
+
+fn month_name(m: u32) -> String {
+    NaiveDate::from_ymd_opt(2000, m, 1).map_or_else(String::new, |d| d.format("%B").to_string())
+}
+
+impl YearReview {
+    /// (label, value) rows shared by every output format.
+    fn rows(&self) -> Vec<(&'static str, String)> {
+        let mut rows = vec![
+            ("Events",       self.events.to_string()),
+            ("Hours booked", (self.event_hours.round() as i64).to_string()),
+        ];
+        if let Some((m, n)) = self.busiest_month {
+            rows.push(("Busiest month", format!("{} ({n} events)", month_name(m))));
+        }
+        if let Some((d, n)) = self.busiest_day {
+            rows.push(("Busiest day", format!("{} ({n} events)", d.format("%a %b %-d"))));
+        }
+        if let Some((ref t, n)) = self.top_title {
+            rows.push(("Most common event", format!("{t} (×{n})")));
+        }
+        rows.push(("Tasks completed", self.tasks_done.to_string()));
+        if let Some((ref h, n)) = self.habit_streak {
+            rows.push(("Longest habit streak", format!("{h} ({n} in a row)")));
+        }
+        rows.push(("Holidays spanned", format!("{} days", self.holiday_days)));
+        rows
+    }
+
+    fn holiday_labels(&self) -> Vec<String> {
+        let mut out: Vec<String> = Vec::new();
+        for h in &self.holidays {
+            let label = format!("{} {} {} ({})", h.date.format("%b %-d"), h.emoji, h.name, h.country);
+            if !out.contains(&label) { out.push(label); }
+        }
+        out
+    }
+
+    pub fn to_text(&self) -> String {
+        let mut out = format!("\n  ✨ {} in review\n\n", self.year);
+        if self.events == 0 && self.tasks_done == 0 {
+            out.push_str("  Nothing on the calendar — a quiet year.\n\n");
+        }
+        for (label, value) in self.rows() {
+            out.push_str(&format!("  {label:<21} {value}\n"));
+        }
+        out.push('\n');
+        out
+    }
+
+    pub fn to_markdown(&self) -> String {
+        let mut out = format!("# {} in review\n\n", self.year);
+        for (label, value) in self.rows() {
+            out.push_str(&format!("- **{label}:** {value}\n"));
+        }
+        let holidays = self.holiday_labels();
+        if !holidays.is_empty() {
+            out.push_str("\n## Holidays\n\n");
+            for h in holidays {
+                out.push_str(&format!("- {h}\n"));
+            }
+        }
+        out
+    }
+
+    pub fn to_html(&self) -> String {
+        let mut rows = String::new();
+        for (label, value) in self.rows() {
+            rows.push_str(&format!("<tr><th>{}</th><td>{}</td></tr>\n", esc(label), esc(&value)));
+        }
+        let holidays: String = self.holiday_labels().iter()
+            .map(|h| format!("<li>{}</li>\n", esc(h)))
+            .collect();
+        let holidays = if holidays.is_empty() {
+            String::new()
+        } else {
+            format!("<h2>Holidays</h2>\n<ul>\n{holidays}</ul>\n")
+        };
+        format!(
+            "<!DOCTYPE html>
+<html><head><meta charset=\"utf-8\"><title>{y} in review</title>
+<style>
+body {{ font-family: system-ui, sans-serif; max-width: 36rem; margin: 3rem auto; color: #222; }}
+th {{ text-align: left; padding-right: 2rem; color: #666; font-weight: normal; }}
+td {{ font-weight: bold; }}
+</style></head>
+<body>
+<h1>✨ {y} in review</h1>
+<table>
+{rows}</table>
+{holidays}</body></html>
+",
+            y = self.year,
+        )
+    }
+}
+
+fn esc(s: &str) -> String {
+    s.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
@@ -0,0 +1,335 @@
+//! `lm serve`: the database over HTTP on loopback, for widgets, scripts and
+//! status bars (waybar, polybar) that want the same events and tasks as the
+//! TUI without opening SQLite themselves.
+//!
+//! ```text
+//! GET  /events?from=2026-10-15&to=2026-10-21   events overlapping those days
+//! GET  /tasks[?done=true]                      open tasks, or every task
+//! GET  /calendar.ics                           everything, as an ICS feed
+//! POST /events   {"title", "start", "end"?, "all_day"?, "description"?, "calendar_id"?}
+//! POST /tasks    {"title", "due"?, "priority"?, "notes"?, "tags"?, "task_list_id"?}
+//! ```
+//!
+//! Times are wall time like everywhere else: `2026-10-20T09:00`, or a date
+//! alone for all day. Created items are dirty, so a running TUI (or the next
+//! `lm sync`) pushes them. Writes need `Content-Type: application/json`,
+//! which a cross-origin page can't send without a CORS preflight that's
+//! never answered. A page that rebinds its own hostname to 127.0.0.1 is
+//! same-origin and needs no preflight, so every request must also name
+//! this server in its `Host` (`127.0.0.1:<port>` or `localhost:<port>`).
+
+use anyhow::{anyhow, bail, Result};
+use chrono::{DateTime, Duration, Local, NaiveDate, NaiveDateTime, Utc};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{TcpListener, TcpStream};
+
+use crate::calendar::day_bounds;
+use crate::db::{Database, Event, Task};
+use crate::export::{self, Format};
+
+/// Requests with a bigger body are refused.
+const MAX_BODY: usize = 64 * 1024;
+/// Longest request line or header line, and most header lines.
+const MAX_LINE:    usize = 8 * 1024;
+const MAX_HEADERS: usize = 64;
+/// How long a client gets to send its whole request.
+const READ_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(10);
+/// Days `/events` covers without a `to`.
+const DEFAULT_DAYS: i64 = 7;
+
+/// Serves `db` on `127.0.0.1:port` until the process ends.
+pub async fn run(db: Database, port: u16) -> Result<()> {
+    let listener = TcpListener::bind(("127.0.0.1", port)).await?;
+    println!("Serving on http://127.0.0.1:{port}  (Ctrl-C stops)");
+    loop {
+        let (stream, _) = listener.accept().await?;
+        let db = db.clone();
+        tokio::spawn(async move {
+            if let Err(e) = connection(stream, &db, port).await {
+                tracing::debug!("serve: {e}");
+            }
+        });
+    }
+}
+
+// ─── HTTP ─────────────────────────────────────────────────────────────────────
+
+struct Request {
+    method: String,
+    path:   String,
+    query:  HashMap<String, String>,
+    host:   Option<String>,
+    json:   bool,
+    body:   Vec<u8>,
+}
+
+/// Why a request couldn't be read, as the status it's answered with.
+enum ReadError {
+    Malformed(anyhow::Error),
+    HeadersTooLarge,
+    BodyTooLarge,
+    Timeout,
+}
+
+impl From<std::io::Error> for ReadError {
+    fn from(e: std::io::Error) -> Self {
+        Self::Malformed(e.into())
+    }
+}
+
+struct Response {
+    status:       u16,
+    content_type: &'static str,
+    body:         String,
+}
+
+impl Response {
+    fn json(status: u16, value: Value) -> Self {
+        Self { status, content_type: "application/json", body: value.to_string() + "\n" }
+    }
+
+    fn error(status: u16, message: impl std::fmt::Display) -> Self {
+        Self::json(status, json!({ "error": message.to_string() }))
+    }
+}
+
+fn reason(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        201 => "Created",
+        400 => "Bad Request",
+        403 => "Forbidden",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        408 => "Request Timeout",
+        413 => "Payload Too Large",
+        415 => "Unsupported Media Type",
+        431 => "Request Header Fields Too Large",
+        _   => "Internal Server Error",
+    }
+}
+
+async fn connection(mut stream: TcpStream, db: &Database, port: u16) -> Result<()> {
+    let read = tokio::time::timeout(READ_TIMEOUT, read_request(&mut stream)).await.unwrap_or(Err(ReadError::Timeout));
+    let response = match read {
+        Ok(req) if !local_host(req.host.as_deref(), port) => Response::error(403, "Host must be 127.0.0.1 or localhost"),
+        Ok(req) => handle(db, &req).await.unwrap_or_else(|e| Response::error(500, format!("{e:#}"))),
+        Err(ReadError::Malformed(e))    => Response::error(400, e),
+        Err(ReadError::HeadersTooLarge) => Response::error(431, "request line or headers too large"),
+        Err(ReadError::BodyTooLarge)    => Response::error(413, "body too large"),
+        Err(ReadError::Timeout)         => Response::error(408, "request not sent in time"),
+    };
+    let head = format!(
+        "HTTP/1.1 {} {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+        response.status, reason(response.status), response.content_type, response.body.len(),
+    );
+    stream.write_all(head.as_bytes()).await?;
+    stream.write_all(response.body.as_bytes()).await?;
+    Ok(())
+}
+
+/// Whether `host` names this server rather than some hostname rebound to
+/// loopback.
+fn local_host(host: Option<&str>, port: u16) -> bool {
+    host.is_some_and(|h| h == format!("127.0.0.1:{port}") || h.eq_ignore_ascii_case(&format!("localhost:{port}")))
+}
+
+/// One line into `line` (cleared first), refused past `MAX_LINE` bytes.
+/// 0 at the end of the stream.
+async fn read_line(reader: &mut BufReader<&mut TcpStream>, line: &mut String) -> Result<usize, ReadError> {
+    line.clear();
+    let n = (&mut *reader).take(MAX_LINE as u64 + 1).read_line(line).await?;
+    if n > MAX_LINE { return Err(ReadError::HeadersTooLarge); }
+    Ok(n)
+}
+
+async fn read_request(stream: &mut TcpStream) -> Result<Request, ReadError> {
+    let mut reader = BufReader::new(stream);
+    let mut line   = String::new();
+    read_line(&mut reader, &mut line).await?;
+    let mut parts = line.split_whitespace();
+    let (Some(method), Some(target)) = (parts.next(), parts.next()) else {
+        return Err(ReadError::Malformed(anyhow!("malformed request line")));
+    };
+    let method = method.to_owned();
+    let (path, query) = target.split_once('?').unwrap_or((target, ""));
+    let path  = path.to_owned();
+    let query = query.split('&')
+        .filter_map(|pair| pair.split_once('=').or(Some((pair, ""))))
+        .filter(|(k, _)| !k.is_empty())
+        .map(|(k, v)| (decode(k), decode(v)))
+        .collect();
+
+    let (mut length, mut json, mut host) = (0, false, None);
+    for count in 0.. {
+        if read_line(&mut reader, &mut line).await? == 0 || line.trim().is_empty() { break; }
+        if count == MAX_HEADERS { return Err(ReadError::HeadersTooLarge); }
+        if let Some((name, value)) = line.split_once(':') {
+            match name.trim().to_ascii_lowercase().as_str() {
+                "content-length" => {
+                    length = value.trim().parse().map_err(|_| ReadError::Malformed(anyhow!("bad Content-Length")))?;
+                }
+                "content-type"   => json = value.trim().to_ascii_lowercase().starts_with("application/json"),
+                "host"           => host = Some(value.trim().to_owned()),
+                _ => {}
+            }
+        }
+    }
+    if length > MAX_BODY { return Err(ReadError::BodyTooLarge); }
+    let mut body = vec![0; length];
+    reader.read_exact(&mut body).await?;
+    Ok(Request { method, path, query, host, json, body })
+}
+
+/// Undoes URL encoding in a query string part.
+fn decode(s: &str) -> String {
+    let bytes = s.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'+' => out.push(b' '),
+            b'%' if i + 2 < bytes.len() => {
+                let hex = std::str::from_utf8(&bytes[i + 1..i + 3]).ok().and_then(|h| u8::from_str_radix(h, 16).ok());
+                match hex {
+                    Some(b) => { out.push(b); i += 2; }
+                    None    => out.push(b'%'),
+                }
+            }
+            b => out.push(b),
+        }
+        i += 1;
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+// ─── Routes ───────────────────────────────────────────────────────────────────
+
+async fn handle(db: &Database, req: &Request) -> Result<Response> {
+    let write = req.method == "POST";
+    if write && !req.json {
+        return Ok(Response::error(415, "send Content-Type: application/json"));
+    }
+    Ok(match (req.method.as_str(), req.path.trim_end_matches('/')) {
+        ("GET", "") => Response::json(200, json!({
+            "endpoints": ["GET /events?from=&to=", "GET /tasks?done=true", "GET /calendar.ics", "POST /events", "POST /tasks"],
+        })),
+        ("GET", "/events") => {
+            let day = |key: &str| req.query.get(key)
+                .map(|d| NaiveDate::parse_from_str(d, "%Y-%m-%d").map_err(|_| anyhow!("{key} should be YYYY-MM-DD, not {d}")))
+                .transpose();
+            let (from, to) = match (day("from"), day("to")) {
+                (Ok(from), Ok(to)) => (from, to),
+                (Err(e), _) | (_, Err(e)) => return Ok(Response::error(400, e)),
+            };
+            let from = from.unwrap_or_else(|| Local::now().date_naive());
+            let to   = to.unwrap_or(from + Duration::days(DEFAULT_DAYS - 1)).max(from);
+            // Time-log entries aren't meetings, as `lm mcp` list_events has it
+            let events: Vec<Event> = db.events_overlapping(day_bounds(from).0, day_bounds(to).1).await?
+                .into_iter().filter(|e| !e.time_entry).collect();
+            Response::json(200, serde_json::to_value(events)?)
+        }
+        ("GET", "/tasks") => {
+            let all = req.query.get("done").is_some_and(|d| matches!(d.as_str(), "true" | "1" | "yes"));
+            let tasks: Vec<Task> = db.all_tasks().await?.into_iter().filter(|t| all || !t.completed).collect();
+            Response::json(200, serde_json::to_value(tasks)?)
+        }
+        ("GET", "/calendar.ics") => Response {
+            status:       200,
+            content_type: "text/calendar; charset=utf-8",
+            body:         export::render(Format::Ics, "LifeManager", &db.calendar_events().await?, &db.all_tasks().await?)?,
+        },
+        ("POST", "/events") => match serde_json::from_slice::<NewEvent>(&req.body).map_err(anyhow::Error::from).and_then(NewEvent::into_event) {
+            Ok(ev) => {
+                db.upsert_event(&ev).await?;
+                Response::json(201, serde_json::to_value(ev)?)
+            }
+            Err(e) => Response::error(400, e),
+        },
+        ("POST", "/tasks") => match serde_json::from_slice::<NewTask>(&req.body).map_err(anyhow::Error::from).and_then(NewTask::into_task) {
+            Ok(t) => {
+                db.upsert_task(&t).await?;
+                Response::json(201, serde_json::to_value(t)?)
+            }
+            Err(e) => Response::error(400, e),
+        },
+        (_, "" | "/events" | "/tasks" | "/calendar.ics") => Response::error(405, format!("{} isn't allowed here", req.method)),
+        (_, path) => Response::error(404, format!("nothing at {path}")),
+    })
+}
+
+// ─── Writes ───────────────────────────────────────────────────────────────────
+
+/// `2026-10-20T09:00` (seconds optional, a space works too) as wall time,
+/// or `2026-10-20` as that day's midnight, all day.
+pub fn parse_when(s: &str) -> Result<(DateTime<Utc>, bool)> {
+    let s = s.trim();
+    if let Ok(day) = NaiveDate::parse_from_str(s, "%Y-%m-%d") {
+        return Ok((day_bounds(day).0, true));
+    }
+    ["%Y-%m-%dT%H:%M", "%Y-%m-%dT%H:%M:%S", "%Y-%m-%d %H:%M", "%Y-%m-%d %H:%M:%S"].iter()
+        .find_map(|f| NaiveDateTime::parse_from_str(s, f).ok())
+        .map(|at| (at.and_utc(), false))
+        .ok_or_else(|| anyhow!("{s} isn't YYYY-MM-DD or YYYY-MM-DDTHH:MM"))
+}
+
+/// A `POST /events` body; also `lm mcp`'s `create_event`.
+#[derive(Deserialize)]
+pub struct NewEvent {
+    pub title:       String,
+    pub start:       String,
+    pub end:         Option<String>,
+    pub all_day:     Option<bool>,
+    pub description: Option<String>,
+    pub calendar_id: Option<String>,
+}
+
+impl NewEvent {
+    /// An hour long without an `end`, or the one day when all day.
+    pub fn into_event(self) -> Result<Event> {
+        if self.title.trim().is_empty() { bail!("title is empty"); }
+        let (start, date_only) = parse_when(&self.start)?;
+        let all_day = self.all_day.unwrap_or(date_only);
+        let end = match self.end.as_deref().map(parse_when).transpose()? {
+            // An all-day end date is the last day, inclusive
+            Some((end, true)) if all_day => end + Duration::days(1),
+            Some((end, _))               => end,
+            None if all_day              => start + Duration::days(1),
+            None                         => start + Duration::hours(1),
+        };
+        if end <= start { bail!("end is before start"); }
+        let mut ev = Event::new(self.title.trim(), start, end);
+        ev.all_day     = all_day;
+        ev.description = self.description.filter(|d| !d.trim().is_empty());
+        ev.calendar_id = self.calendar_id;
+        Ok(ev)
+    }
+}
+
+/// A `POST /tasks` body; also `lm mcp`'s `create_task`.
+#[derive(Deserialize)]
+pub struct NewTask {
+    pub title:        String,
+    pub due:          Option<String>,
+    pub priority:     Option<i64>,
+    pub notes:        Option<String>,
+    #[serde(default)]
+    pub tags:         Vec<String>,
+    pub task_list_id: Option<String>,
+}
+
+impl NewTask {
+    pub fn into_task(self) -> Result<Task> {
+        if self.title.trim().is_empty() { bail!("title is empty"); }
+        let mut t = Task::new(self.title.trim());
+        t.due          = self.due.as_deref().map(parse_when).transpose()?.map(|(at, _)| at);
+        t.priority     = self.priority.unwrap_or(0).clamp(0, crate::tasks::MAX_PRIORITY);
+        t.notes        = self.notes.filter(|n| !n.trim().is_empty());
+        t.tags         = self.tags;
+        t.task_list_id = self.task_list_id;
+        Ok(t)
+    }
+}
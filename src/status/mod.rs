@@ -0,0 +1,119 @@
+//! `lm status-line`: one short line for a tmux status bar or a waybar /
+//! polybar custom module to poll, built from `[status_line]` templates.
+//!
+//! Placeholders: `{time}`, `{title}`, `{in}` ("in 25m", "now") and `{date}`
+//! of the next timed event; `{left}`, timed events still to come today;
+//! `{due}`, open tasks due today; `{overdue}`, what the `overdue` template
+//! makes of the overdue count, or nothing when there are none (`{n}` in it
+//! is the count).
+
+use chrono::{DateTime, Duration, Utc};
+use serde::Deserialize;
+
+use crate::attendance::Attendance;
+use crate::model::{Event, Task};
+use crate::tasks;
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct StatusLineConfig {
+    /// With an event coming up.
+    pub format:        String,
+    /// With none within `horizon_hours`.
+    pub idle:          String,
+    /// What `{overdue}` becomes when tasks are overdue.
+    pub overdue:       String,
+    /// How far ahead the next event is looked for.
+    pub horizon_hours: i64,
+}
+
+impl Default for StatusLineConfig {
+    fn default() -> Self {
+        Self {
+            format:        "{time} {title} ({in}){overdue}".into(),
+            idle:          "Nothing coming up{overdue}".into(),
+            overdue:       " · {n} overdue".into(),
+            horizon_hours: 24,
+        }
+    }
+}
+
+/// The line, and what a waybar module wants besides.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StatusLine {
+    pub text:    String,
+    /// The rest of today's events and the overdue tasks, a line each.
+    pub tooltip: String,
+    /// `overdue`, `soon` (within 15 minutes or under way), `idle` or `normal`.
+    pub class:   &'static str,
+}
+
+/// Counts as an event to show: timed, not logged time, and not one skipped
+/// or cancelled.
+fn counts(e: &Event) -> bool {
+    !e.all_day && !e.time_entry && !e.deleted
+        && !matches!(e.attendance, Some(Attendance::Skipped | Attendance::Cancelled))
+}
+
+/// "now", "in 25m", "in 2h 10m", "in 3d".
+pub fn until(now: DateTime<Utc>, at: DateTime<Utc>) -> String {
+    let mins = (at - now).num_minutes();
+    match mins {
+        m if m <= 0        => "now".into(),
+        m if m < 60        => format!("in {m}m"),
+        m if m < 24 * 60   => match m % 60 {
+            0    => format!("in {}h", m / 60),
+            rest => format!("in {}h {rest}m", m / 60),
+        },
+        m                  => format!("in {}d", m / (24 * 60)),
+    }
+}
+
+/// `template` with each `{key}` replaced; unknown ones stay as written.
+pub fn fill(template: &str, values: &[(&str, String)]) -> String {
+    values.iter().fold(template.to_owned(), |out, (key, value)| out.replace(&format!("{{{key}}}"), value))
+}
+
+/// `now` is wall time, like the events' times; `events` are those around
+/// it (anything ended or out of the horizon is passed over).
+pub fn build(cfg: &StatusLineConfig, events: &[Event], tasks: &[Task], now: DateTime<Utc>) -> StatusLine {
+    let today    = now.date_naive();
+    let horizon  = now + Duration::hours(cfg.horizon_hours.max(0));
+    let mut upcoming: Vec<&Event> = events.iter().filter(|e| counts(e) && e.end > now && e.start < horizon).collect();
+    upcoming.sort_by_key(|e| e.start);
+    let late     = tasks::overdue(tasks, today);
+    let due      = tasks.iter().filter(|t| !t.completed && !t.deleted && t.due.is_some_and(|d| d.date_naive() == today)).count();
+    let left     = upcoming.iter().filter(|e| e.start > now && e.start.date_naive() == today).count();
+    let overdue  = if late.is_empty() { String::new() } else { fill(&cfg.overdue, &[("n", late.len().to_string())]) };
+
+    let mut values = vec![
+        ("overdue", overdue),
+        ("left",    left.to_string()),
+        ("due",     due.to_string()),
+    ];
+    let (text, soon) = match upcoming.first() {
+        Some(next) => {
+            values.extend([
+                ("time",  next.start.format("%H:%M").to_string()),
+                ("title", next.title.clone()),
+                ("in",    until(now, next.start)),
+                ("date",  next.start.format("%a %b %-d").to_string()),
+            ]);
+            (fill(&cfg.format, &values), next.start - now <= Duration::minutes(15))
+        }
+        None => (fill(&cfg.idle, &values), false),
+    };
+
+    let mut tooltip: Vec<String> = upcoming.iter()
+        .filter(|e| e.start.date_naive() <= today)
+        .map(|e| format!("{}–{}  {}", e.start.format("%H:%M"), e.end.format("%H:%M"), e.title))
+        .collect();
+    tooltip.extend(late.iter().map(|t| format!("overdue  {}", t.title)));
+    let class = match (late.is_empty(), soon, upcoming.is_empty()) {
+        (false, _, _)    => "overdue",
+        (true, true, _)  => "soon",
+        (true, _, true)  => "idle",
+        _                => "normal",
+    };
+    StatusLine { text: text.trim().to_owned(), tooltip: tooltip.join("\n"), class }
+}
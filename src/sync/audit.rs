@@ -0,0 +1,75 @@
+//! What a sync changes. The worker writes each change it makes to the
+//! `sync_log` table (`lm sync log`, `Y` in the TUI); `lm sync --dry-run`
+//! works the same changes out without making them.
+//!
+//! Pulls only log what's new or different from the stored copy, so a sync
+//! that finds nothing to do leaves no trace.
+
+use chrono::{Local, Utc};
+
+use crate::db::{Database, Event, SyncLogEntry, Task};
+
+pub const PULL: &str = "pull";
+pub const PUSH: &str = "push";
+
+/// What storing a pulled event does to `existing`, the copy with its
+/// sync id: None when nothing changes.
+pub fn pulled_event(existing: Option<&Event>, incoming: &Event) -> Option<&'static str> {
+    let Some(old) = existing else { return Some("create") };
+    if old.dirty { return Some("keep local"); }
+    let same = match (&old.etag, &incoming.etag) {
+        (Some(a), Some(b)) => a == b,
+        _ => old.title == incoming.title && old.start == incoming.start && old.end == incoming.end
+            && old.all_day == incoming.all_day && old.description == incoming.description,
+    };
+    match (same, old.deleted) {
+        (true, false) => None,
+        (_, true)     => Some("restore"),
+        (false, _)    => Some("update"),
+    }
+}
+
+/// The same for a pulled task; deleted at Google is a delete here.
+pub fn pulled_task(existing: Option<&Task>, incoming: &Task) -> Option<&'static str> {
+    let Some(old) = existing else { return (!incoming.deleted).then_some("create") };
+    if old.dirty { return Some("keep local"); }
+    if incoming.deleted { return (!old.deleted).then_some("delete"); }
+    let same = old.title == incoming.title && old.notes == incoming.notes && old.due == incoming.due
+        && old.completed == incoming.completed && !old.deleted;
+    (!same).then_some("update")
+}
+
+/// What pushing a dirty item does at Google.
+pub fn pushed(deleted: bool, linked: bool) -> &'static str {
+    match (deleted, linked) {
+        (true, _)      => "delete",
+        (false, true)  => "update",
+        (false, false) => "create",
+    }
+}
+
+pub fn entry(direction: &str, entity: &str, action: &str, title: &str, result: &str) -> SyncLogEntry {
+    SyncLogEntry {
+        at:        Utc::now(),
+        direction: direction.to_owned(),
+        entity:    entity.to_owned(),
+        action:    action.to_owned(),
+        title:     title.to_owned(),
+        result:    result.to_owned(),
+    }
+}
+
+/// Writes one entry; a failure is only logged, never stops the sync.
+pub async fn record(db: &Database, direction: &str, entity: &str, action: &str, title: &str, result: &str) {
+    if let Err(e) = db.log_sync(&entry(direction, entity, action, title, result)).await {
+        tracing::warn!("log_sync: {e}");
+    }
+}
+
+/// `10-15 09:12  pull  event  update   Standup  ok`
+pub fn line(e: &SyncLogEntry) -> String {
+    format!(
+        "{}  {:<4}  {:<5}  {:<10}  {}  {}",
+        e.at.with_timezone(&Local).format("%m-%d %H:%M"), e.direction, e.entity, e.action, e.title, e.result,
+    )
+}
@@ -0,0 +1,50 @@
+//! Read-only ICS feed subscriptions, refreshed by the sync worker and cached
+//! in the `feed_events` table.
+
+use anyhow::Result;
+
+use crate::config::FeedConfig;
+use crate::db::Database;
+use crate::sync::ics;
+use crate::sync::titles::TitleRules;
+
+async fn fetch(http: &reqwest::Client, url: &str) -> Result<String> {
+    // webcal:// is just https:// with a hint for calendar apps
+    let url = match url.strip_prefix("webcal://") {
+        Some(rest) => format!("https://{rest}"),
+        None       => url.to_owned(),
+    };
+    Ok(http.get(&url).send().await?.error_for_status()?.text().await?)
+}
+
+/// Downloads every feed and replaces its cache. A feed that fails keeps its
+/// previous events. Returns the number of events cached.
+pub async fn refresh_all(
+    http: &reqwest::Client, db: &Database, feeds: &[FeedConfig], titles: &TitleRules,
+) -> Result<usize> {
+    let names: Vec<String> = feeds.iter().map(|f| f.name.clone()).collect();
+    db.prune_feeds(&names).await?;
+
+    let mut total = 0;
+    let mut first_err = None;
+    for feed in feeds {
+        match fetch(http, &feed.url).await {
+            Ok(body) => {
+                let (mut events, rejected) = ics::parse(&body, &feed.name);
+                for e in &mut events { e.title = titles.apply(&e.title, &feed.name); }
+                db.replace_feed_events(&feed.name, &feed.url, &events).await?;
+                db.replace_quarantine(&format!("feed:{}", feed.name), &rejected).await?;
+                tracing::info!("feed {}: {} events", feed.name, events.len());
+                total += events.len();
+            }
+            Err(e) => {
+                tracing::warn!("feed {}: {e}", feed.name);
+                first_err.get_or_insert(anyhow::anyhow!("feed {}: {e}", feed.name));
+            }
+        }
+    }
+    match first_err {
+        Some(e) if total == 0 => Err(e),
+        _                     => Ok(total),
+    }
+}
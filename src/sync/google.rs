@@ -1,37 +1,156 @@
 //! Google Calendar + Tasks OAuth2 & REST API client.
 //!
 //! Auth flow:
-//!   1. Call build_auth_url() → open in browser
+//!   1. Call build_auth_url() → open in browser (generates a PKCE verifier)
 //!   2. Call listen_for_callback() → captures redirect with ?code=
-//!   3. Call exchange_code(code) → stores tokens in DB
+//!   3. Call exchange_code(code) → sends the verifier, stores tokens in DB
 //!   4. All subsequent calls auto-refresh if expired
+//!
+//! Headless machines use the device flow instead: request_device_code() →
+//! show user_code + verification_url → poll_device_token() until approved.
+//!
+//! PKCE means no client secret has to ship with the binary: the client_id can
+//! be baked in at build time (LM_GOOGLE_CLIENT_ID) or set in config.toml, and
+//! client_secret is only sent when the user configured one.
+//!
+//! `lm auth google --client-id` signs in with the user's own OAuth client
+//! instead (for privacy, or when the embedded one hits Google's unverified-app
+//! caps). The client is stored with the tokens it was issued, and every later
+//! refresh uses it, whatever config.toml says.
 
 use anyhow::{anyhow, Result};
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
 use chrono::{DateTime, Duration, Utc};
-use reqwest::Client;
+use reqwest::{Client, StatusCode};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
+use sha2::{Digest, Sha256};
 use std::collections::HashMap;
 
-use crate::db::{Database, Event, Task};
+use crate::db::{Attendee, Database, Event, Quarantined, Rsvp, Task, WorkingLocation};
+use crate::sync::validate;
 
 const AUTH_URL:     &str = "https://accounts.google.com/o/oauth2/v2/auth";
 const TOKEN_URL:    &str = "https://oauth2.googleapis.com/token";
+const DEVICE_URL:   &str = "https://oauth2.googleapis.com/device/code";
+const REVOKE_URL:   &str = "https://oauth2.googleapis.com/revoke";
+const DEVICE_GRANT: &str = "urn:ietf:params:oauth:grant-type:device_code";
 const REDIRECT_URI: &str = "http://localhost:8085/callback";
 const SCOPES:       &str = "https://www.googleapis.com/auth/calendar \
                              https://www.googleapis.com/auth/tasks";
+const READ_ONLY:    &str = "https://www.googleapis.com/auth/calendar.readonly \
+                             https://www.googleapis.com/auth/tasks.readonly";
 
 // ─── Config ───────────────────────────────────────────────────────────────────
 
+/// Client id compiled into release builds; config.toml may override it.
+const EMBEDDED_CLIENT_ID: Option<&str> = option_env!("LM_GOOGLE_CLIENT_ID");
+
+fn default_client_id()  -> String      { EMBEDDED_CLIENT_ID.unwrap_or_default().to_owned() }
+fn default_calendars()  -> Vec<String> { vec!["primary".to_owned()] }
 fn default_task_lists() -> Vec<String> { vec!["@default".to_owned()] }
+fn default_push_port()  -> u16         { 8086 }
 
 #[derive(Debug, Clone, Deserialize)]
 pub struct GoogleConfig {
+    #[serde(default = "default_client_id")]
     pub client_id:     String,
-    pub client_secret: String,
+    /// Optional with PKCE — only needed for OAuth clients that still demand it.
+    #[serde(default)]
+    pub client_secret: Option<String>,
+    #[serde(default = "default_calendars")]
     pub calendar_ids:  Vec<String>,
     #[serde(default = "default_task_lists")]
     pub task_list_ids: Vec<String>,
+    /// Ask only for read-only scopes at sign-in, and never push, whatever
+    /// the stored token allows.
+    #[serde(default)]
+    pub read_only:     bool,
+    /// Public HTTPS address (a tunnel, or a reverse proxy) that forwards to
+    /// `push_port` on this machine. With it Google says when a synced
+    /// calendar changes, and the TUI pulls right away (see `push.rs`).
+    #[serde(default)]
+    pub push_url:      Option<String>,
+    #[serde(default = "default_push_port")]
+    pub push_port:     u16,
+    /// Calendar or task list id → which way it syncs; two-way when not
+    /// listed. Shared team calendars are usually `pull`.
+    #[serde(default)]
+    pub direction:     HashMap<String, Direction>,
+}
+
+/// Which way a calendar or task list syncs.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Direction {
+    /// Pull Google's changes and push local ones.
+    #[default]
+    #[serde(alias = "two-way")]
+    Both,
+    /// Only pull: local edits and deletes are never sent, and are undone.
+    #[serde(alias = "pull-only")]
+    Pull,
+    /// Only push: Google's changes aren't brought in.
+    #[serde(alias = "push-only")]
+    Push,
+}
+
+impl Direction {
+    pub fn pulls(self) -> bool { self != Self::Push }
+    pub fn pushes(self) -> bool { self != Self::Pull }
+}
+
+impl GoogleConfig {
+    /// Config built purely from the compiled-in client id, used when
+    /// config.toml has no [google] section.
+    pub fn embedded() -> Option<Self> {
+        EMBEDDED_CLIENT_ID.filter(|id| !id.is_empty()).map(|id| Self {
+            client_id:     id.to_owned(),
+            client_secret: None,
+            calendar_ids:  default_calendars(),
+            task_list_ids: default_task_lists(),
+            read_only:     false,
+            push_url:      None,
+            push_port:     default_push_port(),
+            direction:     HashMap::new(),
+        })
+    }
+
+    /// `base` signing in with a different OAuth client; the calendars and
+    /// task lists to sync stay as configured.
+    pub fn with_client(base: Option<Self>, client_id: String, client_secret: Option<String>) -> Self {
+        match base {
+            Some(g) => Self { client_id, client_secret, ..g },
+            None    => Self {
+                client_id, client_secret,
+                calendar_ids:  default_calendars(),
+                task_list_ids: default_task_lists(),
+                read_only:     false,
+                push_url:      None,
+                push_port:     default_push_port(),
+                direction:     HashMap::new(),
+            },
+        }
+    }
+
+    /// `base` with the client the stored tokens were issued to, if any.
+    pub async fn resolve(base: Option<Self>, db: &Database) -> Result<Option<Self>> {
+        Ok(match db.token_client("google").await? {
+            Some((id, secret)) => Some(Self::with_client(base, id, secret)),
+            None               => base.filter(|g| !g.client_id.is_empty()),
+        })
+    }
+
+    /// How calendar or task list `id` syncs.
+    pub fn direction(&self, id: &str) -> Direction {
+        self.direction.get(id).copied().unwrap_or_default()
+    }
+
+    /// Signing in with the client compiled into the binary, whose consent
+    /// screen is shared by every user (and capped while unverified).
+    pub fn is_embedded(&self) -> bool {
+        EMBEDDED_CLIENT_ID == Some(self.client_id.as_str())
+    }
 }
 
 // ─── Token response ───────────────────────────────────────────────────────────
@@ -41,6 +160,68 @@ struct TokenResponse {
     access_token:  String,
     refresh_token: Option<String>,
     expires_in:    Option<i64>,
+    /// Space-separated: what the user ticked on the consent screen.
+    scope:         Option<String>,
+}
+
+// ─── Scopes ───────────────────────────────────────────────────────────────────
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Access {
+    None,
+    Read,
+    Write,
+}
+
+/// What the sign-in allows, per API. Google's consent screen lets each
+/// scope be unticked, so one can be granted without the other.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Grant {
+    pub calendar: Access,
+    pub tasks:    Access,
+}
+
+impl Grant {
+    /// What tokens stored before scopes were recorded were issued for.
+    pub const FULL: Self = Self { calendar: Access::Write, tasks: Access::Write };
+    pub const NONE: Self = Self { calendar: Access::None, tasks: Access::None };
+
+    /// From a token response's `scope`.
+    pub fn parse(scope: &str) -> Self {
+        let granted: Vec<&str> = scope.split_whitespace()
+            .filter_map(|s| s.strip_prefix("https://www.googleapis.com/auth/"))
+            .collect();
+        let access = |write: &[&str], read: &[&str]| {
+            if write.iter().any(|s| granted.contains(s))     { Access::Write }
+            else if read.iter().any(|s| granted.contains(s)) { Access::Read }
+            else                                            { Access::None }
+        };
+        Self {
+            calendar: access(&["calendar", "calendar.events"], &["calendar.readonly", "calendar.events.readonly"]),
+            tasks:    access(&["tasks"], &["tasks.readonly"]),
+        }
+    }
+
+    /// At most read access to either.
+    pub fn capped(self) -> Self {
+        Self { calendar: self.calendar.min(Access::Read), tasks: self.tasks.min(Access::Read) }
+    }
+
+    pub fn read_only(self) -> bool {
+        self.calendar != Access::Write && self.tasks != Access::Write
+    }
+}
+
+/// Response of the device authorization endpoint.
+#[derive(Debug, Deserialize)]
+pub struct DeviceCode {
+    pub device_code:      String,
+    pub user_code:        String,
+    /// `verification_uri` in the RFC, which Microsoft follows.
+    #[serde(alias = "verification_uri")]
+    pub verification_url: String,
+    pub expires_in:       i64,
+    pub interval:         Option<u64>,
 }
 
 // ─── Calendar API types ───────────────────────────────────────────────────────
@@ -50,6 +231,7 @@ struct TokenResponse {
 pub struct GCalDateTime {
     pub date_time: Option<String>,
     pub date:      Option<String>,
+    pub time_zone: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -62,6 +244,117 @@ pub struct GCalEvent {
     pub end:         Option<GCalDateTime>,
     pub etag:        Option<String>,
     pub status:      Option<String>,
+    /// `default`, `workingLocation`, `outOfOffice`, …
+    pub event_type:  Option<String>,
+    pub working_location_properties: Option<GCalWorkingLocation>,
+    pub organizer:   Option<GCalPerson>,
+    pub creator:     Option<GCalPerson>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub attendees:   Vec<GCalAttendee>,
+    /// The Meet link, on events with a Google Meet call.
+    pub hangout_link:    Option<String>,
+    /// Calls added any other way (Zoom and Teams add-ons, …).
+    pub conference_data: Option<GCalConference>,
+    /// `"1"` to `"11"`; absent when it has its calendar's colour.
+    pub color_id:        Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GCalConference {
+    #[serde(default)]
+    pub entry_points: Vec<GCalEntryPoint>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GCalEntryPoint {
+    /// `video`, `phone`, `sip` or `more`
+    pub entry_point_type: Option<String>,
+    pub uri:              Option<String>,
+}
+
+impl GCalEvent {
+    /// The call's video link: Meet's, else the conference's video entry
+    /// point.
+    fn meet_url(&self) -> Option<String> {
+        self.hangout_link.clone().or_else(|| {
+            self.conference_data.as_ref()?.entry_points.iter()
+                .find(|p| p.entry_point_type.as_deref() == Some("video"))
+                .and_then(|p| p.uri.clone())
+        })
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GCalPerson {
+    pub email:        Option<String>,
+    pub display_name: Option<String>,
+    /// The signed-in account itself.
+    #[serde(rename = "self", default)]
+    pub is_self:      bool,
+}
+
+impl GCalPerson {
+    /// `Ana Ruiz <ana@example.com>`, or whichever half Google sent; `you`
+    /// for the signed-in account.
+    fn describe(&self) -> Option<String> {
+        if self.is_self { return Some("you".into()); }
+        match (&self.display_name, &self.email) {
+            (Some(name), Some(email)) => Some(format!("{name} <{email}>")),
+            (Some(one), None) | (None, Some(one)) => Some(one.clone()),
+            (None, None) => None,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GCalAttendee {
+    pub email:           Option<String>,
+    pub display_name:    Option<String>,
+    /// `needsAction`, `accepted`, `tentative` or `declined`.
+    pub response_status: Option<String>,
+    #[serde(rename = "self", default)]
+    pub is_self:         bool,
+    #[serde(default)]
+    pub organizer:       bool,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GCalWorkingLocation {
+    /// `homeOffice`, `officeLocation` or `customLocation`
+    #[serde(rename = "type")]
+    pub kind:            Option<String>,
+    pub office_location: Option<GCalLocationLabel>,
+    pub custom_location: Option<GCalLocationLabel>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GCalLocationLabel {
+    pub label: Option<String>,
+}
+
+/// One guest's calendar from a freeBusy query.
+#[derive(Debug, Clone)]
+pub struct Availability {
+    pub email: String,
+    pub busy:  Vec<(DateTime<Utc>, DateTime<Utc>)>,
+    /// Why Google couldn't say, e.g. `notFound` for calendars not shared
+    /// with you.
+    pub error: Option<String>,
+}
+
+/// A watch on one calendar's events: Google POSTs to the address it was
+/// opened with until `expires`.
+#[derive(Debug, Clone)]
+pub struct Channel {
+    pub id:          String,
+    pub resource_id: String,
+    pub calendar_id: String,
+    pub expires:     DateTime<Utc>,
 }
 
 // ─── Tasks API types ──────────────────────────────────────────────────────────
@@ -75,6 +368,8 @@ pub struct GTask {
     pub due:     Option<String>,
     pub deleted: Option<bool>,
     pub hidden:  Option<bool>,
+    /// Sorts lexically into the list's order (read-only; see `move_task`).
+    pub position: Option<String>,
 }
 
 // ─── Client ───────────────────────────────────────────────────────────────────
@@ -85,6 +380,7 @@ pub struct GoogleCalendarClient {
     db:               Database,
     access_token:     Option<String>,
     token_expires_at: Option<DateTime<Utc>>,
+    pkce_verifier:    Option<String>,
 }
 
 impl GoogleCalendarClient {
@@ -97,18 +393,24 @@ impl GoogleCalendarClient {
             config, db,
             access_token: None,
             token_expires_at: None,
+            pkce_verifier: None,
         }
     }
 
     // ── Auth flow ─────────────────────────────────────────────────────────────
 
-    pub fn build_auth_url(&self) -> String {
+    pub fn build_auth_url(&mut self) -> String {
+        let verifier  = pkce_verifier();
+        let challenge = URL_SAFE_NO_PAD.encode(Sha256::digest(verifier.as_bytes()));
+        self.pkce_verifier = Some(verifier);
         format!(
-            "{}?client_id={}&redirect_uri={}&response_type=code&scope={}&access_type=offline&prompt=consent",
+            "{}?client_id={}&redirect_uri={}&response_type=code&scope={}&access_type=offline&prompt=consent\
+             &code_challenge={}&code_challenge_method=S256",
             AUTH_URL,
             pct(&self.config.client_id),
             pct(REDIRECT_URI),
-            pct(SCOPES),
+            pct(self.scopes()),
+            challenge,
         )
     }
 
@@ -126,7 +428,7 @@ impl GoogleCalendarClient {
         // GET /callback?code=XXX HTTP/1.1
         let code = line
             .split_whitespace().nth(1)
-            .and_then(|path| path.splitn(2, '?').nth(1))
+            .and_then(|path| path.split_once('?').map(|(_, qs)| qs))
             .and_then(|qs| qs.split('&').find_map(|kv| {
                 let mut p = kv.splitn(2, '=');
                 if p.next()? == "code" { p.next().map(str::to_owned) } else { None }
@@ -142,23 +444,87 @@ impl GoogleCalendarClient {
     }
 
     pub async fn exchange_code(&mut self, code: &str) -> Result<()> {
+        let verifier = self.pkce_verifier.take()
+            .ok_or_else(|| anyhow!("exchange_code called before build_auth_url"))?;
         let mut p = HashMap::new();
         p.insert("code",          code);
         p.insert("client_id",     &self.config.client_id);
-        p.insert("client_secret", &self.config.client_secret);
+        p.insert("code_verifier", &verifier);
         p.insert("redirect_uri",  REDIRECT_URI);
         p.insert("grant_type",    "authorization_code");
+        if let Some(cs) = &self.config.client_secret {
+            p.insert("client_secret", cs);
+        }
 
         let resp: TokenResponse = self.http.post(TOKEN_URL).form(&p)
             .send().await?.error_for_status()?.json().await?;
         self.store_tokens(resp).await
     }
 
+    // ── Device flow ───────────────────────────────────────────────────────────
+
+    pub async fn request_device_code(&self) -> Result<DeviceCode> {
+        let mut p = HashMap::new();
+        p.insert("client_id", self.config.client_id.as_str());
+        p.insert("scope",     self.scopes());
+        Ok(self.http.post(DEVICE_URL).form(&p)
+            .send().await?.error_for_status()?.json().await?)
+    }
+
+    /// Polls the token endpoint until the user approves (or denies) the code.
+    pub async fn poll_device_token(&mut self, dc: &DeviceCode) -> Result<()> {
+        let deadline     = Utc::now() + Duration::seconds(dc.expires_in);
+        let mut interval = dc.interval.unwrap_or(5);
+
+        loop {
+            tokio::time::sleep(std::time::Duration::from_secs(interval)).await;
+            if Utc::now() >= deadline {
+                return Err(anyhow!("Device code expired — run the command again"));
+            }
+
+            let mut p = HashMap::new();
+            p.insert("client_id",   self.config.client_id.as_str());
+            p.insert("device_code", dc.device_code.as_str());
+            p.insert("grant_type",  DEVICE_GRANT);
+            if let Some(cs) = &self.config.client_secret {
+                p.insert("client_secret", cs.as_str());
+            }
+
+            let resp = self.http.post(TOKEN_URL).form(&p).send().await?;
+            if resp.status().is_success() {
+                let t: TokenResponse = resp.json().await?;
+                return self.store_tokens(t).await;
+            }
+            let body: Value = resp.json().await.unwrap_or_default();
+            match body["error"].as_str().unwrap_or("") {
+                "authorization_pending" => {}
+                "slow_down"             => interval += 5,
+                "access_denied"         => return Err(anyhow!("Authorization was denied")),
+                "expired_token"         => return Err(anyhow!("Device code expired — run the command again")),
+                other                   => return Err(anyhow!("Device authorization failed: {other}")),
+            }
+        }
+    }
+
     // ── Token management ──────────────────────────────────────────────────────
 
+    fn scopes(&self) -> &'static str {
+        if self.config.read_only { READ_ONLY } else { SCOPES }
+    }
+
+    /// What the stored sign-in allows, capped at read with `read_only`.
+    pub async fn grant(&self) -> Result<Grant> {
+        let granted = self.db.token_scope("google").await?.map_or(Grant::FULL, |s| Grant::parse(&s));
+        Ok(if self.config.read_only { granted.capped() } else { granted })
+    }
+
     async fn store_tokens(&mut self, t: TokenResponse) -> Result<()> {
         let exp = t.expires_in.map(|s| Utc::now() + Duration::seconds(s - 60));
         self.db.save_token("google", &t.access_token, t.refresh_token.as_deref(), exp).await?;
+        self.db.save_token_client("google", &self.config.client_id, self.config.client_secret.as_deref()).await?;
+        if let Some(scope) = &t.scope {
+            self.db.save_token_scope("google", scope).await?;
+        }
         self.access_token     = Some(t.access_token);
         self.token_expires_at = exp;
         Ok(())
@@ -166,10 +532,10 @@ impl GoogleCalendarClient {
 
     pub async fn ensure_authenticated(&mut self) -> Result<()> {
         // Already have a non-expired token in memory
-        if self.access_token.is_some() {
-            if !self.token_expires_at.map(|e| Utc::now() >= e).unwrap_or(false) {
-                return Ok(());
-            }
+        if self.access_token.is_some()
+            && !self.token_expires_at.map(|e| Utc::now() >= e).unwrap_or(false)
+        {
+            return Ok(());
         }
         // Try DB
         if let Some((access, refresh, expires)) = self.db.get_token("google").await? {
@@ -192,41 +558,108 @@ impl GoogleCalendarClient {
         let mut p = HashMap::new();
         p.insert("refresh_token", rt.as_str());
         p.insert("client_id",     cid.as_str());
-        p.insert("client_secret", cs.as_str());
         p.insert("grant_type",    "refresh_token");
+        if let Some(cs) = &cs {
+            p.insert("client_secret", cs.as_str());
+        }
 
         let resp: TokenResponse = self.http.post(TOKEN_URL).form(&p)
             .send().await?.error_for_status()?.json().await?;
         self.store_tokens(resp).await
     }
 
+    /// Revokes the grant at Google and forgets the stored tokens. A token
+    /// Google no longer recognises still counts as signed out.
+    pub async fn revoke(&mut self) -> Result<()> {
+        if let Some((access, refresh, _)) = self.db.get_token("google").await? {
+            // Revoking the refresh token also invalidates its access tokens
+            let token = refresh.unwrap_or(access);
+            let resp  = self.http.post(REVOKE_URL).form(&[("token", token.as_str())]).send().await?;
+            if !resp.status().is_success() {
+                tracing::warn!("revoke returned {} — dropping local tokens anyway", resp.status());
+            }
+        }
+        self.db.delete_token("google").await?;
+        self.access_token     = None;
+        self.token_expires_at = None;
+        Ok(())
+    }
+
     fn bearer(&self) -> String {
         format!("Bearer {}", self.access_token.as_deref().unwrap_or(""))
     }
 
     // ── Calendar API ──────────────────────────────────────────────────────────
 
-    pub async fn pull_events(&mut self, calendar_id: &str) -> Result<Vec<GCalEvent>> {
+    /// The calendar's events, cancelled ones included, over every page, and
+    /// its name (for `primary`, the account's address).
+    pub async fn pull_events(&mut self, calendar_id: &str) -> Result<(Vec<GCalEvent>, Option<String>)> {
         self.ensure_authenticated().await?;
         let url = format!(
             "https://www.googleapis.com/calendar/v3/calendars/{}/events",
             pct(calendar_id)
         );
-        let body: Value = self.http.get(&url)
-            .header("Authorization", self.bearer())
-            .query(&[
+        // Cancelled instances come back too and fill pages, so follow
+        // nextPageToken rather than trusting one page to hold everything
+        let mut events  = Vec::new();
+        let mut summary = None;
+        let mut page: Option<String> = None;
+        loop {
+            let mut query = vec![
                 ("singleEvents", "true"),
                 ("orderBy",      "startTime"),
                 ("maxResults",   "2500"),
-            ])
+                // Deleted events come back as cancelled, to be purged here
+                ("showDeleted",  "true"),
+            ];
+            if let Some(token) = page.as_deref() { query.push(("pageToken", token)); }
+            let body: Value = self.http.get(&url)
+                .header("Authorization", self.bearer())
+                .query(&query)
+                .send().await?.error_for_status()?.json().await?;
+
+            events.extend(body["items"].as_array().unwrap_or(&vec![]).iter()
+                .filter_map(|v| serde_json::from_value::<GCalEvent>(v.clone()).ok()));
+            if summary.is_none() { summary = body["summary"].as_str().map(str::to_owned); }
+            match body["nextPageToken"].as_str() {
+                Some(token) => page = Some(token.to_owned()),
+                None        => break,
+            }
+        }
+        Ok((events, summary))
+    }
+
+    /// Busy times of each guest's calendar within `[from, to)`.
+    pub async fn free_busy(
+        &mut self, emails: &[String], from: DateTime<Utc>, to: DateTime<Utc>,
+    ) -> Result<Vec<Availability>> {
+        self.ensure_authenticated().await?;
+        let items: Vec<Value> = emails.iter().map(|e| serde_json::json!({ "id": e })).collect();
+        let body: Value = self.http.post("https://www.googleapis.com/calendar/v3/freeBusy")
+            .header("Authorization", self.bearer())
+            .json(&serde_json::json!({
+                "timeMin": from.to_rfc3339(),
+                "timeMax": to.to_rfc3339(),
+                "items":   items,
+            }))
             .send().await?.error_for_status()?.json().await?;
 
-        Ok(body["items"].as_array().unwrap_or(&vec![]).iter()
-            .filter_map(|v| serde_json::from_value(v.clone()).ok())
-            .collect())
+        let parse = |v: &Value| v.as_str()
+            .and_then(|s| DateTime::parse_from_rfc3339(s).ok())
+            .map(|d| d.with_timezone(&Utc));
+        Ok(emails.iter().map(|email| {
+            let cal = &body["calendars"][email.as_str()];
+            Availability {
+                email: email.clone(),
+                busy:  cal["busy"].as_array().map(|spans| spans.iter()
+                    .filter_map(|b| Some((parse(&b["start"])?, parse(&b["end"])?)))
+                    .collect()).unwrap_or_default(),
+                error: cal["errors"][0]["reason"].as_str().map(str::to_owned),
+            }
+        }).collect())
     }
 
-    pub async fn push_event(&mut self, cal_id: &str, ev: &Event) -> Result<(String, String)> {
+    pub async fn push_event(&mut self, cal_id: &str, ev: &Event, attendees: &[Attendee]) -> Result<(String, String)> {
         self.ensure_authenticated().await?;
         let url = format!(
             "https://www.googleapis.com/calendar/v3/calendars/{}/events",
@@ -234,7 +667,7 @@ impl GoogleCalendarClient {
         );
         let body: Value = self.http.post(&url)
             .header("Authorization", self.bearer())
-            .json(&event_to_gcal(ev))
+            .json(&event_to_gcal(ev, attendees))
             .send().await?.error_for_status()?.json().await?;
         Ok((
             body["id"].as_str().unwrap_or("").to_owned(),
@@ -243,7 +676,7 @@ impl GoogleCalendarClient {
     }
 
     pub async fn update_event(
-        &mut self, cal_id: &str, remote_id: &str, ev: &Event,
+        &mut self, cal_id: &str, remote_id: &str, ev: &Event, attendees: &[Attendee],
     ) -> Result<String> {
         self.ensure_authenticated().await?;
         let url = format!(
@@ -252,19 +685,82 @@ impl GoogleCalendarClient {
         );
         let body: Value = self.http.put(&url)
             .header("Authorization", self.bearer())
-            .json(&event_to_gcal(ev))
+            .json(&event_to_gcal(ev, attendees))
             .send().await?.error_for_status()?.json().await?;
         Ok(body["etag"].as_str().unwrap_or("").to_owned())
     }
 
-    pub async fn delete_event(&mut self, cal_id: &str, remote_id: &str) -> Result<()> {
+    /// Sends your answer to an invitation. Google takes the guest list
+    /// whole, so `attendees` is all of it, yours changed.
+    pub async fn respond(&mut self, cal_id: &str, remote_id: &str, attendees: &[Attendee]) -> Result<()> {
         self.ensure_authenticated().await?;
         let url = format!(
             "https://www.googleapis.com/calendar/v3/calendars/{}/events/{}",
             pct(cal_id), pct(remote_id)
         );
-        self.http.delete(&url)
+        self.http.patch(&url)
+            .header("Authorization", self.bearer())
+            .json(&serde_json::json!({ "attendees": attendees_to_gcal(attendees) }))
+            .send().await?.error_for_status()?;
+        Ok(())
+    }
+
+    /// Deletes the event if it's still the version `etag` names (any version
+    /// without one). `Ok(false)` means it was changed at Google since, and
+    /// is left alone; one already gone counts as deleted.
+    pub async fn delete_event(&mut self, cal_id: &str, remote_id: &str, etag: Option<&str>) -> Result<bool> {
+        self.ensure_authenticated().await?;
+        let url = format!(
+            "https://www.googleapis.com/calendar/v3/calendars/{}/events/{}",
+            pct(cal_id), pct(remote_id)
+        );
+        let mut req = self.http.delete(&url).header("Authorization", self.bearer());
+        if let Some(etag) = etag.filter(|e| !e.is_empty()) {
+            req = req.header("If-Match", etag);
+        }
+        let resp = req.send().await?;
+        match resp.status() {
+            StatusCode::PRECONDITION_FAILED          => Ok(false),
+            StatusCode::NOT_FOUND | StatusCode::GONE => Ok(true),
+            _ => {
+                resp.error_for_status()?;
+                Ok(true)
+            }
+        }
+    }
+
+    // ── Push notifications ────────────────────────────────────────────────────
+
+    /// Opens a channel: Google POSTs to `address`, with `token` in
+    /// X-Goog-Channel-Token, whenever the calendar's events change. Google
+    /// picks the expiry (a week at most).
+    pub async fn watch_events(&mut self, cal_id: &str, address: &str, token: &str) -> Result<Channel> {
+        self.ensure_authenticated().await?;
+        let url = format!(
+            "https://www.googleapis.com/calendar/v3/calendars/{}/events/watch",
+            pct(cal_id)
+        );
+        let id = uuid::Uuid::new_v4().to_string();
+        let body: Value = self.http.post(&url)
             .header("Authorization", self.bearer())
+            .json(&serde_json::json!({ "id": id, "type": "web_hook", "address": address, "token": token }))
+            .send().await?.error_for_status()?.json().await?;
+        let expires = body["expiration"].as_str().and_then(|ms| ms.parse::<i64>().ok())
+            .and_then(DateTime::from_timestamp_millis)
+            .unwrap_or_else(|| Utc::now() + Duration::hours(1));
+        Ok(Channel {
+            id,
+            resource_id: body["resourceId"].as_str().unwrap_or("").to_owned(),
+            calendar_id: cal_id.to_owned(),
+            expires,
+        })
+    }
+
+    pub async fn stop_channel(&mut self, ch: &Channel) -> Result<()> {
+        self.ensure_authenticated().await?;
+        self.http.post("https://www.googleapis.com/calendar/v3/channels/stop")
+            .header("Authorization", self.bearer())
+            .json(&serde_json::json!({ "id": ch.id, "resourceId": ch.resource_id }))
             .send().await?.error_for_status()?;
         Ok(())
     }
@@ -292,8 +788,9 @@ impl GoogleCalendarClient {
             .collect())
     }
 
+    /// Inserts `task` after the task `previous` (at the top when `None`).
     pub async fn push_task(
-        &mut self, task_list_id: &str, task: &Task,
+        &mut self, task_list_id: &str, task: &Task, previous: Option<&str>,
     ) -> Result<(String, String)> {
         self.ensure_authenticated().await?;
         let url = format!(
@@ -302,6 +799,7 @@ impl GoogleCalendarClient {
         );
         let body: Value = self.http.post(&url)
             .header("Authorization", self.bearer())
+            .query(&[("previous", previous)])
             .json(&task_to_gtask(task))
             .send().await?.error_for_status()?.json().await?;
         Ok((
@@ -325,6 +823,23 @@ impl GoogleCalendarClient {
         Ok(body["etag"].as_str().unwrap_or("").to_owned())
     }
 
+    /// Moves a task to just after `previous` (to the top when `None`).
+    pub async fn move_task(
+        &mut self, task_list_id: &str, remote_id: &str, previous: Option<&str>,
+    ) -> Result<()> {
+        self.ensure_authenticated().await?;
+        let url = format!(
+            "https://tasks.googleapis.com/tasks/v1/lists/{}/tasks/{}/move",
+            pct(task_list_id), pct(remote_id)
+        );
+        self.http.post(&url)
+            .header("Authorization", self.bearer())
+            .query(&[("previous", previous)])
+            .body("") // sent with Content-Length: 0, which Google insists on
+            .send().await?.error_for_status()?;
+        Ok(())
+    }
+
     pub async fn delete_task(&mut self, task_list_id: &str, remote_id: &str) -> Result<()> {
         self.ensure_authenticated().await?;
         let url = format!(
@@ -340,36 +855,114 @@ impl GoogleCalendarClient {
 
 // ─── Calendar converters ──────────────────────────────────────────────────────
 
-fn event_to_gcal(ev: &Event) -> Value {
-    serde_json::json!({
+fn event_to_gcal(ev: &Event, attendees: &[Attendee]) -> Value {
+    let mut body = serde_json::json!({
         "summary":     ev.title,
         "description": ev.description,
         "start": if ev.all_day {
             serde_json::json!({ "date": ev.start.format("%Y-%m-%d").to_string() })
         } else {
-            serde_json::json!({ "dateTime": ev.start.to_rfc3339(), "timeZone": "UTC" })
+            serde_json::json!({ "dateTime": ev.start.to_rfc3339(), "timeZone": ev.timezone.as_deref().unwrap_or("UTC") })
         },
         "end": if ev.all_day {
             serde_json::json!({ "date": ev.end.format("%Y-%m-%d").to_string() })
         } else {
-            serde_json::json!({ "dateTime": ev.end.to_rfc3339(), "timeZone": "UTC" })
+            serde_json::json!({ "dateTime": ev.end.to_rfc3339(), "timeZone": ev.timezone.as_deref().unwrap_or("UTC") })
         },
-    })
+    });
+    if !attendees.is_empty() {
+        body["attendees"] = attendees_to_gcal(attendees);
+    }
+    // Left out, an update puts the event back to its calendar's colour
+    if let Some(ref id) = ev.color {
+        body["colorId"] = Value::from(id.as_str());
+    }
+    body
+}
+
+/// Answers go along, or a full update would reset everyone's to none.
+fn attendees_to_gcal(attendees: &[Attendee]) -> Value {
+    attendees.iter().map(|a| serde_json::json!({
+        "email":          a.email,
+        "responseStatus": a.response.as_str(),
+    })).collect()
 }
 
-pub fn gcal_to_local(g: &GCalEvent, calendar_id: &str) -> Option<Event> {
+/// The guests of a pulled event; ones without an address (rare, but the
+/// API allows it) are left out.
+pub fn gcal_attendees(g: &GCalEvent) -> Vec<Attendee> {
+    g.attendees.iter().filter_map(|a| Some(Attendee {
+        email:     a.email.clone()?,
+        name:      a.display_name.clone(),
+        response:  a.response_status.as_deref().map(Rsvp::parse).unwrap_or_default(),
+        is_self:   a.is_self,
+        organizer: a.organizer,
+    })).collect()
+}
+
+/// Whether Google reports the event (or this instance of it) as deleted.
+pub fn is_cancelled(g: &GCalEvent) -> bool {
+    g.status.as_deref() == Some("cancelled")
+}
+
+/// Converts a pulled event, or quarantines it when its times don't hold up.
+/// Cancelled events aren't converted: the caller purges them instead.
+pub fn gcal_to_local(g: &GCalEvent, calendar_id: &str) -> Result<Event, Box<Quarantined>> {
     let title   = g.summary.clone().unwrap_or_else(|| "(no title)".into());
-    let start   = parse_gcal_dt(g.start.as_ref()?)?;
-    let end     = parse_gcal_dt(g.end.as_ref()?)?;
-    let all_day = g.start.as_ref()?.date.is_some();
-    let deleted = g.status.as_deref() == Some("cancelled");
+    let start   = g.start.as_ref().and_then(parse_gcal_dt);
+    let end     = g.end.as_ref().and_then(parse_gcal_dt);
+    let all_day = g.start.as_ref().is_some_and(|s| s.date.is_some());
+    let (start, end) = match validate::check_times(start, end) {
+        Ok(times)   => times,
+        Err(reason) => return Err(Box::new(Quarantined {
+            source:      format!("google:{calendar_id}"),
+            remote_id:   g.id.clone().unwrap_or_default(),
+            title,
+            description: g.description.clone(),
+            start, end, all_day, reason,
+            raw:         serde_json::to_string_pretty(g).unwrap_or_default(),
+            received_at: Utc::now(),
+        })),
+    };
+    let tz      = g.start.as_ref().and_then(|s| s.time_zone.clone());
     let now     = Utc::now();
-    Some(Event {
+    Ok(Event {
         id: uuid::Uuid::new_v4().to_string(), title,
         description: g.description.clone(), start, end, all_day,
         calendar_id: Some(calendar_id.to_owned()),
         sync_id: g.id.clone(), etag: g.etag.clone(),
-        dirty: false, deleted, created_at: now, updated_at: now,
+        dirty: false, deleted: false, pto: false, attendance: None, timezone: tz, remote_title: None, tag: None,
+        time_entry: false,
+        organizer: g.organizer.as_ref().and_then(GCalPerson::describe),
+        creator:   g.creator.as_ref().and_then(GCalPerson::describe),
+        lead_minutes: 0,
+        meet_url: g.meet_url(),
+        color:    g.color_id.clone(),
+        created_at: now, updated_at: now,
+    })
+}
+
+/// A working-location event as a `WorkingLocation`; `None` for every other
+/// event type, or when its times don't parse.
+pub fn gcal_working_location(g: &GCalEvent, calendar_id: &str) -> Option<WorkingLocation> {
+    if g.event_type.as_deref() != Some("workingLocation") { return None; }
+    let props = g.working_location_properties.as_ref()?;
+    let (kind, label) = match props.kind.as_deref() {
+        Some("homeOffice")     => ("home", None),
+        Some("officeLocation") => ("office", props.office_location.as_ref().and_then(|l| l.label.clone())),
+        _                      => ("custom", props.custom_location.as_ref().and_then(|l| l.label.clone())),
+    };
+    let (start, end) = validate::check_times(
+        g.start.as_ref().and_then(parse_gcal_dt),
+        g.end.as_ref().and_then(parse_gcal_dt),
+    ).ok()?;
+    Some(WorkingLocation {
+        sync_id:     g.id.clone()?,
+        calendar_id: calendar_id.to_owned(),
+        start, end,
+        all_day:     g.start.as_ref().is_some_and(|s| s.date.is_some()),
+        kind:        kind.to_owned(),
+        label:       label.filter(|l| !l.is_empty()),
     })
 }
 
@@ -409,15 +1002,21 @@ pub fn gtask_to_local(g: &GTask, task_list_id: &str) -> Option<Task> {
         id: uuid::Uuid::new_v4().to_string(), title,
         notes: g.notes.clone(), due, completed, priority: 0,
         task_list_id: Some(task_list_id.to_owned()),
-        sync_id: g.id.clone(), dirty: false, deleted,
+        sync_id: g.id.clone(), dirty: false, deleted, tags: Vec::new(), position: 0, repeat: None, estimate_minutes: 0,
         created_at: now, updated_at: now,
     })
 }
 
 // ─── Utilities ────────────────────────────────────────────────────────────────
 
+/// 64-char PKCE code_verifier from two v4 UUIDs (RFC 7636 allows 43–128
+/// unreserved chars).
+fn pkce_verifier() -> String {
+    format!("{}{}", uuid::Uuid::new_v4().simple(), uuid::Uuid::new_v4().simple())
+}
+
 /// Minimal percent-encoding for URL path components.
-fn pct(s: &str) -> String {
+pub fn pct(s: &str) -> String {
     s.chars().flat_map(|c| {
         if c.is_alphanumeric() || matches!(c, '-' | '_' | '.' | '~') {
             vec![c]
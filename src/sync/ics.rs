@@ -0,0 +1,273 @@
+//! Minimal iCalendar (RFC 5545) reader for read-only feed subscriptions.
+//!
+//! Handles line folding, escaped text, DATE / DATE-TIME values (UTC, TZID or
+//! floating) and DTEND-or-DURATION. Recurrence rules are not expanded — most
+//! holiday and fixture feeds list each occurrence explicitly.
+//!
+//! Also reads back the one-event files the vault (`vault.rs`) writes, and
+//! lends its line parsing to the calendar export import (`import::apple`).
+
+use chrono::{DateTime, Duration, NaiveDate, NaiveDateTime, TimeZone, Utc};
+use chrono_tz::Tz;
+
+use crate::db::{FeedEvent, Quarantined};
+use crate::sync::validate;
+
+/// One content line: `NAME;PARAM=x;PARAM=y:value`.
+pub struct Prop<'a> {
+    pub name:   String,
+    pub params: Vec<(String, &'a str)>,
+    pub value:  &'a str,
+}
+
+impl Prop<'_> {
+    pub fn param(&self, key: &str) -> Option<&str> {
+        self.params.iter().find(|(k, _)| k == key).map(|(_, v)| v.trim_matches('"'))
+    }
+}
+
+/// Undo RFC 5545 line folding (CRLF followed by a space or tab).
+pub fn unfold(text: &str) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    for raw in text.lines() {
+        match (raw.strip_prefix([' ', '\t']), lines.last_mut()) {
+            (Some(cont), Some(prev)) => prev.push_str(cont),
+            _ => lines.push(raw.to_owned()),
+        }
+    }
+    lines
+}
+
+pub fn parse_prop(line: &str) -> Option<Prop<'_>> {
+    // The value starts at the first ':' outside a quoted parameter
+    let mut quoted = false;
+    let colon = line.char_indices().find(|&(_, c)| {
+        if c == '"' { quoted = !quoted; }
+        c == ':' && !quoted
+    })?.0;
+    let (head, value) = (&line[..colon], &line[colon + 1..]);
+    let mut parts = head.split(';');
+    let name   = parts.next()?.to_ascii_uppercase();
+    let params = parts.filter_map(|p| p.split_once('='))
+        .map(|(k, v)| (k.to_ascii_uppercase(), v))
+        .collect();
+    Some(Prop { name, params, value })
+}
+
+/// Text on one line: an escaped newline becomes a space.
+fn unescape(s: &str) -> String {
+    unescape_with(s, ' ')
+}
+
+pub fn unescape_with(s: &str, newline: char) -> String {
+    let mut out   = String::with_capacity(s.len());
+    let mut chars = s.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' { out.push(c); continue; }
+        match chars.next() {
+            Some('n') | Some('N') => out.push(newline),
+            Some(other)           => out.push(other),
+            None                  => {}
+        }
+    }
+    out
+}
+
+/// Returns the instant and whether it was a bare DATE (all-day).
+fn parse_when(p: &Prop) -> Option<(DateTime<Utc>, bool)> {
+    let v = p.value.trim();
+    if p.param("VALUE") == Some("DATE") || v.len() == 8 {
+        let d = NaiveDate::parse_from_str(v, "%Y%m%d").ok()?;
+        return Some((d.and_hms_opt(0, 0, 0)?.and_utc(), true));
+    }
+    if let Some(utc) = v.strip_suffix('Z') {
+        let n = NaiveDateTime::parse_from_str(utc, "%Y%m%dT%H%M%S").ok()?;
+        return Some((n.and_utc(), false));
+    }
+    let n = NaiveDateTime::parse_from_str(v, "%Y%m%dT%H%M%S").ok()?;
+    let at = match p.param("TZID").and_then(|z| z.parse::<Tz>().ok()) {
+        Some(tz) => tz.from_local_datetime(&n).earliest()?.with_timezone(&Utc),
+        // Floating time: keep the wall clock, as locally created events do
+        None     => n.and_utc(),
+    };
+    Some((at, false))
+}
+
+/// `P1D`, `PT1H30M`, `P2W`, … (no months/years in iCalendar durations).
+pub fn parse_duration(s: &str) -> Option<Duration> {
+    let s = s.trim();
+    let (neg, s) = match s.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None       => (false, s.strip_prefix('+').unwrap_or(s)),
+    };
+    let s = s.strip_prefix('P')?;
+    let mut total = Duration::zero();
+    let mut num   = String::new();
+    for c in s.chars() {
+        match c {
+            '0'..='9' => num.push(c),
+            'T'       => {}
+            unit => {
+                let n: i64 = num.parse().ok()?;
+                num.clear();
+                total += match unit {
+                    'W' => Duration::try_weeks(n)?,
+                    'D' => Duration::try_days(n)?,
+                    'H' => Duration::try_hours(n)?,
+                    'M' => Duration::try_minutes(n)?,
+                    'S' => Duration::try_seconds(n)?,
+                    _   => return None,
+                };
+            }
+        }
+    }
+    Some(if neg { -total } else { total })
+}
+
+/// Parses every VEVENT in `text`, splitting them into usable events and
+/// ones that failed validation. Cancelled events are dropped.
+pub fn parse(text: &str, feed: &str) -> (Vec<FeedEvent>, Vec<Quarantined>) {
+    let (mut events, mut rejected) = (Vec::new(), Vec::new());
+    let mut current: Option<Vec<String>> = None;
+
+    for line in unfold(text) {
+        let upper = line.trim().to_ascii_uppercase();
+        if upper == "BEGIN:VEVENT" {
+            current = Some(Vec::new());
+        } else if upper == "END:VEVENT" {
+            match current.take().and_then(|lines| parse_event(&lines, feed)) {
+                Some(Ok(ev)) => events.push(ev),
+                Some(Err(q)) => {
+                    tracing::warn!("feed {feed}: quarantined \"{}\": {}", q.title, q.reason);
+                    rejected.push(q);
+                }
+                None => {}
+            }
+        } else if let Some(ref mut lines) = current {
+            lines.push(line);
+        }
+    }
+    (events, rejected)
+}
+
+fn parse_event(lines: &[String], feed: &str) -> Option<Result<FeedEvent, Quarantined>> {
+    let props: Vec<Prop> = lines.iter().filter_map(|l| parse_prop(l)).collect();
+    let get = |name: &str| props.iter().find(|p| p.name == name);
+
+    if get("STATUS").is_some_and(|p| p.value.eq_ignore_ascii_case("CANCELLED")) {
+        return None;
+    }
+
+    let (start, end, all_day) = times(&props);
+    let title = get("SUMMARY").map(|p| unescape(p.value)).unwrap_or_else(|| "(no title)".into());
+    let uid   = get("UID").map(|p| p.value.to_owned())
+        .unwrap_or_else(|| format!("{}-{title}", start.map_or(0, |s| s.timestamp())));
+
+    Some(match validate::check_times(start, end) {
+        Ok((start, end)) => Ok(FeedEvent { feed: feed.to_owned(), uid, title, start, end, all_day }),
+        Err(reason) => Err(Quarantined {
+            source:      format!("feed:{feed}"),
+            remote_id:   uid,
+            title,
+            description: get("DESCRIPTION").map(|p| unescape(p.value)),
+            start, end, all_day, reason,
+            raw:         lines.join("\n"),
+            received_at: Utc::now(),
+        }),
+    })
+}
+
+/// Start, end and whether it's all-day, from DTSTART and DTEND or DURATION.
+fn times(props: &[Prop]) -> (Option<DateTime<Utc>>, Option<DateTime<Utc>>, bool) {
+    let get     = |name: &str| props.iter().find(|p| p.name == name);
+    let when    = get("DTSTART").and_then(parse_when);
+    let start   = when.map(|(s, _)| s);
+    let all_day = when.is_some_and(|(_, all_day)| all_day);
+    let end = match (get("DTEND"), get("DURATION")) {
+        (Some(p), _) => parse_when(p).map(|(e, _)| e),
+        (None, Some(p)) => start.zip(parse_duration(p.value)).and_then(|(s, d)| s.checked_add_signed(d)),
+        // RFC 5545: a date-only event without an end lasts one day
+        (None, None) if all_day => start.and_then(|s| s.checked_add_signed(Duration::days(1))),
+        (None, None) => start,
+    };
+    (start, end, all_day)
+}
+
+// ─── Vault files ──────────────────────────────────────────────────────────────
+
+/// What a vault file says about its event.
+#[derive(Debug, PartialEq)]
+pub struct EventFields {
+    pub title:       String,
+    pub description: Option<String>,
+    pub start:       DateTime<Utc>,
+    pub end:         DateTime<Utc>,
+    pub all_day:     bool,
+}
+
+/// The first VEVENT in `text`; `None` when there's none, or its times
+/// don't hold up.
+pub fn first_event(text: &str) -> Option<EventFields> {
+    let lines: Vec<String> = unfold(text).into_iter()
+        .skip_while(|l| !l.trim().eq_ignore_ascii_case("BEGIN:VEVENT"))
+        .skip(1)
+        .take_while(|l| !l.trim().eq_ignore_ascii_case("END:VEVENT"))
+        .collect();
+    let props: Vec<Prop> = lines.iter().filter_map(|l| parse_prop(l)).collect();
+    let get = |name: &str| props.iter().find(|p| p.name == name);
+    let (start, end, all_day) = times(&props);
+    let (start, end) = validate::check_times(start, end).ok()?;
+    Some(EventFields {
+        title:       get("SUMMARY").map(|p| unescape(p.value)).unwrap_or_else(|| "(no title)".into()),
+        description: get("DESCRIPTION").map(|p| unescape_with(p.value, '\n')).filter(|d| !d.is_empty()),
+        start, end, all_day,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn feed(events: &[(&str, &str)]) -> String {
+        let body: String = events.iter()
+            .map(|(title, start)| format!("BEGIN:VEVENT\r\nUID:{title}\r\nSUMMARY:{title}\r\nDTSTART{start}\r\nEND:VEVENT\r\n"))
+            .collect();
+        format!("BEGIN:VCALENDAR\r\n{body}END:VCALENDAR\r\n")
+    }
+
+    #[test]
+    fn dates_before_1970_and_after_2100_are_read() {
+        let (events, rejected) = parse(&feed(&[
+            ("moon",   ";VALUE=DATE:19690720"),
+            ("budget", ":19651231T230000Z"),
+            ("zoned",  ";TZID=Europe/Berlin:19610813T120000"),
+            ("far",    ";VALUE=DATE:21500101"),
+            ("leap",   ":21040229T090000"),
+        ]), "test");
+        assert!(rejected.is_empty());
+        let start = |t: &str| events.iter().find(|e| e.title == t).unwrap().start;
+        assert_eq!(start("moon").date_naive(), NaiveDate::from_ymd_opt(1969, 7, 20).unwrap());
+        assert!(start("budget").timestamp() < 0);
+        assert_eq!(start("zoned").format("%Y-%m-%d %H:%M").to_string(), "1961-08-13 11:00");
+        assert_eq!(start("far").format("%Y").to_string(), "2150");
+        assert_eq!(start("leap").date_naive(), NaiveDate::from_ymd_opt(2104, 2, 29).unwrap());
+    }
+
+    #[test]
+    fn unreadable_and_implausible_dates_are_quarantined() {
+        let (events, rejected) = parse(&feed(&[
+            ("ancient",  ";VALUE=DATE:18000101"),
+            ("distant",  ";VALUE=DATE:99991231"),
+            ("not leap", ";VALUE=DATE:21000229"),
+            ("month 13", ":20261301T090000Z"),
+        ]), "test");
+        assert!(events.is_empty());
+        let reasons: Vec<(&str, &str)> = rejected.iter().map(|q| (q.title.as_str(), q.reason.as_str())).collect();
+        assert_eq!(reasons, vec![
+            ("ancient",  "starts in year 1800"),
+            ("distant",  "starts in year 9999"),
+            ("not leap", "missing or unreadable start"),
+            ("month 13", "missing or unreadable start"),
+        ]);
+    }
+}
@@ -1,2 +1,13 @@
+pub mod audit;
+pub mod feeds;
 pub mod google;
+pub mod ics;
+pub mod outlook;
+pub mod provider;
+pub mod push;
+pub mod taskwarrior;
+pub mod titles;
+pub mod todoist;
+pub mod validate;
+pub mod vault;
 pub mod worker;
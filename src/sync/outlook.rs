@@ -0,0 +1,492 @@
+//! Microsoft 365 (Outlook calendar + To Do) client over Microsoft Graph.
+//!
+//! Sign-in is the OAuth device flow only (`lm auth outlook`): Graph gives
+//! public clients no secret, and a device code works the same on a laptop
+//! and over SSH. Tokens live in their own `outlook` row, refreshed like
+//! Google's.
+//!
+//! Graph has no "show deleted" for calendar views, so each pull asks for a
+//! fixed window (`past_days` back, `future_days` ahead) and anything stored
+//! in it that didn't come back was deleted in Outlook.
+
+use anyhow::{anyhow, bail, Result};
+use chrono::{DateTime, Duration, NaiveDateTime, Utc};
+use reqwest::{Client, RequestBuilder, StatusCode};
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::db::{Database, Event, Task, OUTLOOK_PREFIX};
+use crate::sync::google::{pct, DeviceCode};
+use crate::sync::provider::{PulledEvents, SyncProvider};
+use crate::sync::validate;
+
+const GRAPH:        &str = "https://graph.microsoft.com/v1.0";
+const LOGIN:        &str = "https://login.microsoftonline.com";
+const DEVICE_GRANT: &str = "urn:ietf:params:oauth:grant-type:device_code";
+const SCOPES:       &str = "offline_access Calendars.ReadWrite Tasks.ReadWrite";
+/// Names the account's default calendar, or its default To Do list.
+const DEFAULT:      &str = "default";
+
+// ─── Config ───────────────────────────────────────────────────────────────────
+
+fn default_tenant()      -> String      { "common".to_owned() }
+fn default_ids()         -> Vec<String> { vec![DEFAULT.to_owned()] }
+fn default_past_days()   -> i64         { 90 }
+fn default_future_days() -> i64         { 365 }
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct OutlookConfig {
+    /// Application (client) id of an Azure app registration with "Allow
+    /// public client flows" on.
+    pub client_id:     String,
+    /// `common`, `organizations`, `consumers`, or a tenant id.
+    #[serde(default = "default_tenant")]
+    pub tenant:        String,
+    /// Graph calendar ids; `default` is the account's own calendar.
+    #[serde(default = "default_ids")]
+    pub calendar_ids:  Vec<String>,
+    /// Graph To Do list ids; `default` is the "Tasks" list.
+    #[serde(default = "default_ids")]
+    pub task_list_ids: Vec<String>,
+    #[serde(default = "default_past_days")]
+    pub past_days:     i64,
+    #[serde(default = "default_future_days")]
+    pub future_days:   i64,
+}
+
+// ─── Token response ───────────────────────────────────────────────────────────
+
+#[derive(Debug, Deserialize)]
+struct TokenResponse {
+    access_token:  String,
+    refresh_token: Option<String>,
+    expires_in:    Option<i64>,
+}
+
+// ─── Graph types ──────────────────────────────────────────────────────────────
+
+#[derive(Debug, Deserialize)]
+struct GraphDateTime {
+    #[serde(rename = "dateTime")]
+    date_time: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct GraphBody {
+    content: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GraphAddress {
+    name:    Option<String>,
+    address: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GraphRecipient {
+    #[serde(rename = "emailAddress")]
+    email_address: Option<GraphAddress>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GraphEvent {
+    id:             String,
+    subject:        Option<String>,
+    body:           Option<GraphBody>,
+    start:          Option<GraphDateTime>,
+    end:            Option<GraphDateTime>,
+    #[serde(default)]
+    is_all_day:     bool,
+    #[serde(default)]
+    is_cancelled:   bool,
+    change_key:     Option<String>,
+    organizer:      Option<GraphRecipient>,
+    online_meeting: Option<Value>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GraphTask {
+    id:            String,
+    title:         Option<String>,
+    body:          Option<GraphBody>,
+    status:        Option<String>,
+    due_date_time: Option<GraphDateTime>,
+}
+
+// ─── Client ───────────────────────────────────────────────────────────────────
+
+pub struct OutlookClient {
+    http:             Client,
+    pub config:       OutlookConfig,
+    db:               Database,
+    access_token:     Option<String>,
+    token_expires_at: Option<DateTime<Utc>>,
+    /// Graph id of the default To Do list, once looked up.
+    default_list:     Option<String>,
+}
+
+impl OutlookClient {
+    pub fn new(config: OutlookConfig, db: Database) -> Self {
+        Self {
+            http: Client::builder()
+                .timeout(std::time::Duration::from_secs(30))
+                .user_agent("LifeManager/0.1")
+                .build().expect("http client"),
+            config, db,
+            access_token: None,
+            token_expires_at: None,
+            default_list: None,
+        }
+    }
+
+    fn token_url(&self) -> String {
+        format!("{LOGIN}/{}/oauth2/v2.0/token", self.config.tenant)
+    }
+
+    // ── Device flow ───────────────────────────────────────────────────────────
+
+    pub async fn request_device_code(&self) -> Result<DeviceCode> {
+        let url = format!("{LOGIN}/{}/oauth2/v2.0/devicecode", self.config.tenant);
+        Ok(self.http.post(&url)
+            .form(&[("client_id", self.config.client_id.as_str()), ("scope", SCOPES)])
+            .send().await?.error_for_status()?.json().await?)
+    }
+
+    /// Polls the token endpoint until the user approves (or declines) the code.
+    pub async fn poll_device_token(&mut self, dc: &DeviceCode) -> Result<()> {
+        let deadline     = Utc::now() + Duration::seconds(dc.expires_in);
+        let mut interval = dc.interval.unwrap_or(5);
+
+        loop {
+            tokio::time::sleep(std::time::Duration::from_secs(interval)).await;
+            if Utc::now() >= deadline {
+                bail!("Device code expired — run the command again");
+            }
+
+            let resp = self.http.post(self.token_url())
+                .form(&[
+                    ("client_id",   self.config.client_id.as_str()),
+                    ("device_code", dc.device_code.as_str()),
+                    ("grant_type",  DEVICE_GRANT),
+                ])
+                .send().await?;
+            if resp.status().is_success() {
+                let t: TokenResponse = resp.json().await?;
+                return self.store_tokens(t).await;
+            }
+            let body: Value = resp.json().await.unwrap_or_default();
+            match body["error"].as_str().unwrap_or("") {
+                "authorization_pending"  => {}
+                "slow_down"              => interval += 5,
+                "authorization_declined" => bail!("Authorization was declined"),
+                "expired_token"          => bail!("Device code expired — run the command again"),
+                other => bail!(
+                    "Device authorization failed: {other} {}",
+                    body["error_description"].as_str().unwrap_or("")
+                ),
+            }
+        }
+    }
+
+    // ── Token management ──────────────────────────────────────────────────────
+
+    async fn store_tokens(&mut self, t: TokenResponse) -> Result<()> {
+        let exp = t.expires_in.map(|s| Utc::now() + Duration::seconds(s - 60));
+        self.db.save_token("outlook", &t.access_token, t.refresh_token.as_deref(), exp).await?;
+        self.access_token     = Some(t.access_token);
+        self.token_expires_at = exp;
+        Ok(())
+    }
+
+    async fn ensure_authenticated(&mut self) -> Result<()> {
+        if self.access_token.is_some()
+            && !self.token_expires_at.map(|e| Utc::now() >= e).unwrap_or(false)
+        {
+            return Ok(());
+        }
+        if let Some((access, refresh, expires)) = self.db.get_token("outlook").await? {
+            if !expires.map(|e| Utc::now() >= e).unwrap_or(true) {
+                self.access_token     = Some(access);
+                self.token_expires_at = expires;
+                return Ok(());
+            }
+            if let Some(rt) = refresh {
+                let resp: TokenResponse = self.http.post(self.token_url())
+                    .form(&[
+                        ("client_id",     self.config.client_id.as_str()),
+                        ("refresh_token", rt.as_str()),
+                        ("grant_type",    "refresh_token"),
+                        ("scope",         SCOPES),
+                    ])
+                    .send().await?.error_for_status()?.json().await?;
+                return self.store_tokens(resp).await;
+            }
+        }
+        Err(anyhow!("Not signed in to Outlook. Run:  lm auth outlook"))
+    }
+
+    /// Forgets the stored tokens. Microsoft has no endpoint to revoke one
+    /// refresh token; the app's access is removed at
+    /// https://myapps.microsoft.com (or by an admin).
+    pub async fn sign_out(&mut self) -> Result<()> {
+        self.db.delete_token("outlook").await?;
+        self.access_token     = None;
+        self.token_expires_at = None;
+        Ok(())
+    }
+
+    /// An authenticated request to a Graph path (or a full `@odata.nextLink`).
+    async fn request(&mut self, method: reqwest::Method, path: &str) -> Result<RequestBuilder> {
+        self.ensure_authenticated().await?;
+        let url = if path.starts_with("https://") { path.to_owned() } else { format!("{GRAPH}{path}") };
+        Ok(self.http.request(method, url)
+            .bearer_auth(self.access_token.as_deref().unwrap_or(""))
+            .header("Prefer", "outlook.timezone=\"UTC\", outlook.body-content-type=\"text\""))
+    }
+
+    /// Every item of a collection, following `@odata.nextLink`.
+    async fn list(&mut self, path: &str, query: &[(&str, String)]) -> Result<Vec<Value>> {
+        let mut items = Vec::new();
+        let mut body: Value = self.request(reqwest::Method::GET, path).await?
+            .query(query)
+            .send().await?.error_for_status()?.json().await?;
+        loop {
+            if let Some(page) = body["value"].as_array() { items.extend(page.iter().cloned()); }
+            let Some(next) = body["@odata.nextLink"].as_str().map(str::to_owned) else { break };
+            body = self.request(reqwest::Method::GET, &next).await?
+                .send().await?.error_for_status()?.json().await?;
+        }
+        Ok(items)
+    }
+
+    /// Sends a delete; one already gone counts as deleted.
+    async fn delete(&mut self, path: &str) -> Result<()> {
+        let resp = self.request(reqwest::Method::DELETE, path).await?.send().await?;
+        if resp.status() != StatusCode::NOT_FOUND {
+            resp.error_for_status()?;
+        }
+        Ok(())
+    }
+
+    // ── Paths ─────────────────────────────────────────────────────────────────
+
+    fn calendar_path(local_id: &str) -> String {
+        match remote_id(local_id) {
+            DEFAULT => "/me/calendar".to_owned(),
+            id      => format!("/me/calendars/{}", pct(id)),
+        }
+    }
+
+    /// `/me/todo/lists/{id}`, looking up the default list the first time.
+    async fn list_path(&mut self, local_id: &str) -> Result<String> {
+        let id = match remote_id(local_id) {
+            DEFAULT => match self.default_list.clone() {
+                Some(id) => id,
+                None     => {
+                    let lists = self.list("/me/todo/lists", &[]).await?;
+                    let id = lists.iter()
+                        .find(|l| l["wellknownListName"] == "defaultList")
+                        .or_else(|| lists.first())
+                        .and_then(|l| l["id"].as_str())
+                        .ok_or_else(|| anyhow!("No To Do lists on this account"))?
+                        .to_owned();
+                    self.default_list = Some(id.clone());
+                    id
+                }
+            },
+            id => id.to_owned(),
+        };
+        Ok(format!("/me/todo/lists/{}", pct(&id)))
+    }
+}
+
+impl SyncProvider for OutlookClient {
+    fn name(&self) -> &'static str { "outlook" }
+
+    fn prefix(&self) -> &'static str { OUTLOOK_PREFIX }
+
+    fn calendar_ids(&self) -> Vec<String> {
+        self.config.calendar_ids.iter().map(|id| local_id(id)).collect()
+    }
+
+    fn task_list_ids(&self) -> Vec<String> {
+        self.config.task_list_ids.iter().map(|id| local_id(id)).collect()
+    }
+
+    async fn signed_in(&self) -> Result<bool> {
+        Ok(self.db.get_token("outlook").await?.is_some())
+    }
+
+    async fn pull_events(&mut self, calendar_id: &str) -> Result<PulledEvents> {
+        let now  = Utc::now();
+        let from = now - Duration::days(self.config.past_days);
+        let to   = now + Duration::days(self.config.future_days);
+        let path = Self::calendar_path(calendar_id);
+
+        let about: Value = self.request(reqwest::Method::GET, &path).await?
+            .send().await?.error_for_status()?.json().await?;
+        let items = self.list(&format!("{path}/calendarView"), &[
+            ("startDateTime", from.to_rfc3339()),
+            ("endDateTime",   to.to_rfc3339()),
+            ("$top",          "250".to_owned()),
+        ]).await?;
+
+        let events = items.into_iter()
+            .filter_map(|v| serde_json::from_value::<GraphEvent>(v).ok())
+            .filter(|g| !g.is_cancelled)
+            .filter_map(|g| graph_to_local(&g, calendar_id))
+            .collect();
+        Ok(PulledEvents { events, from, to, name: about["name"].as_str().map(str::to_owned) })
+    }
+
+    async fn pull_tasks(&mut self, list_id: &str) -> Result<Vec<Task>> {
+        let path  = self.list_path(list_id).await?;
+        let items = self.list(&format!("{path}/tasks"), &[("$top", "100".to_owned())]).await?;
+        Ok(items.into_iter()
+            .filter_map(|v| serde_json::from_value::<GraphTask>(v).ok())
+            .map(|g| graph_task_to_local(&g, list_id))
+            .collect())
+    }
+
+    async fn create_event(&mut self, ev: &Event) -> Result<(String, Option<String>)> {
+        let path = Self::calendar_path(ev.calendar_id.as_deref().unwrap_or(DEFAULT));
+        let body: Value = self.request(reqwest::Method::POST, &format!("{path}/events")).await?
+            .json(&event_to_graph(ev))
+            .send().await?.error_for_status()?.json().await?;
+        let id = body["id"].as_str().ok_or_else(|| anyhow!("Graph returned no event id"))?;
+        Ok((id.to_owned(), body["changeKey"].as_str().map(str::to_owned)))
+    }
+
+    async fn update_event(&mut self, ev: &Event) -> Result<Option<String>> {
+        let sid = ev.sync_id.as_deref().ok_or_else(|| anyhow!("event isn't linked to Outlook"))?;
+        let body: Value = self.request(reqwest::Method::PATCH, &format!("/me/events/{}", pct(sid))).await?
+            .json(&event_to_graph(ev))
+            .send().await?.error_for_status()?.json().await?;
+        Ok(body["changeKey"].as_str().map(str::to_owned))
+    }
+
+    async fn delete_event(&mut self, ev: &Event) -> Result<()> {
+        let Some(sid) = ev.sync_id.as_deref() else { return Ok(()) };
+        self.delete(&format!("/me/events/{}", pct(sid))).await
+    }
+
+    async fn create_task(&mut self, task: &Task) -> Result<String> {
+        let path = self.list_path(task.task_list_id.as_deref().unwrap_or(DEFAULT)).await?;
+        let body: Value = self.request(reqwest::Method::POST, &format!("{path}/tasks")).await?
+            .json(&task_to_graph(task))
+            .send().await?.error_for_status()?.json().await?;
+        body["id"].as_str().map(str::to_owned).ok_or_else(|| anyhow!("Graph returned no task id"))
+    }
+
+    async fn update_task(&mut self, task: &Task) -> Result<()> {
+        let sid  = task.sync_id.as_deref().ok_or_else(|| anyhow!("task isn't linked to To Do"))?;
+        let path = self.list_path(task.task_list_id.as_deref().unwrap_or(DEFAULT)).await?;
+        self.request(reqwest::Method::PATCH, &format!("{path}/tasks/{}", pct(sid))).await?
+            .json(&task_to_graph(task))
+            .send().await?.error_for_status()?;
+        Ok(())
+    }
+
+    async fn delete_task(&mut self, task: &Task) -> Result<()> {
+        let Some(sid) = task.sync_id.as_deref() else { return Ok(()) };
+        let path = self.list_path(task.task_list_id.as_deref().unwrap_or(DEFAULT)).await?;
+        self.delete(&format!("{path}/tasks/{}", pct(sid))).await
+    }
+}
+
+// ─── Converters ───────────────────────────────────────────────────────────────
+
+/// `outlook:<id>` for a Graph calendar or list id from config.toml.
+fn local_id(remote: &str) -> String {
+    format!("{OUTLOOK_PREFIX}{remote}")
+}
+
+fn remote_id(local: &str) -> &str {
+    local.strip_prefix(OUTLOOK_PREFIX).unwrap_or(local)
+}
+
+/// Graph's wall times, which the `Prefer` header makes UTC.
+fn parse_graph_dt(dt: &GraphDateTime) -> Option<DateTime<Utc>> {
+    NaiveDateTime::parse_from_str(&dt.date_time, "%Y-%m-%dT%H:%M:%S%.f").ok().map(|d| d.and_utc())
+}
+
+fn graph_dt(at: DateTime<Utc>) -> Value {
+    serde_json::json!({ "dateTime": at.format("%Y-%m-%dT%H:%M:%S").to_string(), "timeZone": "UTC" })
+}
+
+/// A pulled event; `None` (logged) when its times don't hold up.
+fn graph_to_local(g: &GraphEvent, calendar_id: &str) -> Option<Event> {
+    let title = g.subject.clone().filter(|s| !s.is_empty()).unwrap_or_else(|| "(no title)".into());
+    let (start, end) = match validate::check_times(
+        g.start.as_ref().and_then(parse_graph_dt),
+        g.end.as_ref().and_then(parse_graph_dt),
+    ) {
+        Ok(times)   => times,
+        Err(reason) => {
+            tracing::warn!("skipping \"{title}\" from {calendar_id}: {reason}");
+            return None;
+        }
+    };
+    let organizer = g.organizer.as_ref().and_then(|o| o.email_address.as_ref())
+        .and_then(|a| a.name.clone().or_else(|| a.address.clone()));
+    let now = Utc::now();
+    Some(Event {
+        id: uuid::Uuid::new_v4().to_string(), title,
+        description: g.body.as_ref().and_then(|b| b.content.clone()).filter(|c| !c.trim().is_empty()),
+        start, end, all_day: g.is_all_day,
+        calendar_id: Some(calendar_id.to_owned()),
+        sync_id: Some(g.id.clone()), etag: g.change_key.clone(),
+        dirty: false, deleted: false, pto: false, attendance: None, timezone: None, remote_title: None, tag: None,
+        time_entry: false,
+        organizer, creator: None,
+        lead_minutes: 0,
+        meet_url: g.online_meeting.as_ref().and_then(|m| m["joinUrl"].as_str()).map(str::to_owned),
+        color:    None,
+        created_at: now, updated_at: now,
+    })
+}
+
+fn event_to_graph(ev: &Event) -> Value {
+    // All-day events run midnight to midnight, which Graph insists on
+    let (start, end) = if ev.all_day {
+        let midnight = |at: DateTime<Utc>| at.date_naive().and_hms_opt(0, 0, 0).unwrap_or_default().and_utc();
+        (midnight(ev.start), midnight(ev.end).max(midnight(ev.start) + Duration::days(1)))
+    } else {
+        (ev.start, ev.end)
+    };
+    serde_json::json!({
+        "subject":  ev.title,
+        "body":     { "contentType": "text", "content": ev.description.clone().unwrap_or_default() },
+        "start":    graph_dt(start),
+        "end":      graph_dt(end),
+        "isAllDay": ev.all_day,
+    })
+}
+
+fn graph_task_to_local(g: &GraphTask, list_id: &str) -> Task {
+    let now = Utc::now();
+    Task {
+        id: uuid::Uuid::new_v4().to_string(),
+        title: g.title.clone().filter(|t| !t.is_empty()).unwrap_or_else(|| "(no title)".into()),
+        notes: g.body.as_ref().and_then(|b| b.content.clone()).filter(|c| !c.trim().is_empty()),
+        due: g.due_date_time.as_ref().and_then(parse_graph_dt),
+        completed: g.status.as_deref() == Some("completed"),
+        priority: 0,
+        task_list_id: Some(list_id.to_owned()),
+        sync_id: Some(g.id.clone()), dirty: false, deleted: false, tags: Vec::new(), position: 0, repeat: None,
+        estimate_minutes: 0,
+        created_at: now, updated_at: now,
+    }
+}
+
+fn task_to_graph(t: &Task) -> Value {
+    serde_json::json!({
+        "title":       t.title,
+        "body":        { "contentType": "text", "content": t.notes.clone().unwrap_or_default() },
+        "status":      if t.completed { "completed" } else { "notStarted" },
+        // null clears a due date removed here
+        "dueDateTime": t.due.map(graph_dt),
+    })
+}
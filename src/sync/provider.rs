@@ -0,0 +1,77 @@
+//! Sync backends besides Google. Google keeps its own path through the
+//! worker (scopes, attendees, task order, push channels); every other
+//! backend implements `SyncProvider` and goes through `run_provider`, a
+//! plain pull-then-push loop.
+//!
+//! A provider's calendars and task lists live here under ids starting with
+//! its `prefix()` (`outlook:default`), which is how the worker routes a
+//! changed row to the backend it belongs to.
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+
+use crate::db::{Event, Task};
+
+/// One calendar's events from a pull.
+pub struct PulledEvents {
+    /// Everything in `[from, to)`: a stored event in that window that isn't
+    /// here was deleted on the remote.
+    pub events: Vec<Event>,
+    pub from:   DateTime<Utc>,
+    pub to:     DateTime<Utc>,
+    /// The calendar's display name, when the remote has one.
+    pub name:   Option<String>,
+}
+
+pub trait SyncProvider {
+    /// The token row and what logs call it, e.g. `outlook`.
+    fn name(&self) -> &'static str;
+
+    /// What local calendar and task list ids of this backend start with.
+    fn prefix(&self) -> &'static str;
+
+    /// Local ids of the calendars to sync.
+    fn calendar_ids(&self) -> Vec<String>;
+
+    /// Local ids of the task lists to sync.
+    fn task_list_ids(&self) -> Vec<String>;
+
+    /// Whether there's a sign-in to sync with; without one a sync is
+    /// skipped quietly.
+    async fn signed_in(&self) -> Result<bool>;
+
+    /// Display name of local task list `list_id`, when the remote has one.
+    async fn list_name(&mut self, _list_id: &str) -> Result<Option<String>> {
+        Ok(None)
+    }
+
+    /// Events of local calendar `calendar_id`, converted, with the window
+    /// they cover.
+    async fn pull_events(&mut self, calendar_id: &str) -> Result<PulledEvents>;
+
+    /// Every task of local list `list_id`, converted.
+    async fn pull_tasks(&mut self, list_id: &str) -> Result<Vec<Task>>;
+
+    /// What becomes of a synced, unchanged task the last pull didn't list:
+    /// deleted on the remote, unless the backend leaves out finished ones.
+    fn gone_task(&self, task: Task) -> Task {
+        Task { deleted: true, ..task }
+    }
+
+    /// Creates `ev` on its calendar: the remote id and version tag.
+    async fn create_event(&mut self, ev: &Event) -> Result<(String, Option<String>)>;
+
+    /// Sends `ev` over its remote copy: the new version tag.
+    async fn update_event(&mut self, ev: &Event) -> Result<Option<String>>;
+
+    /// Deletes the remote copy of `ev`; one already gone counts as deleted.
+    async fn delete_event(&mut self, ev: &Event) -> Result<()>;
+
+    /// Creates `task` on its list: the remote id.
+    async fn create_task(&mut self, task: &Task) -> Result<String>;
+
+    async fn update_task(&mut self, task: &Task) -> Result<()>;
+
+    /// Deletes the remote copy of `task`; one already gone counts as deleted.
+    async fn delete_task(&mut self, task: &Task) -> Result<()>;
+}
@@ -0,0 +1,98 @@
+//! Google push notifications: instead of waiting for the next interval, the
+//! worker pulls as soon as Google says a synced calendar changed.
+//!
+//! Google only calls public HTTPS addresses, so `[google] push_url` is a
+//! tunnel (cloudflared, ngrok, tailscale funnel, …) or reverse proxy that
+//! forwards to `push_port` here, where `listen` takes the calls. Each
+//! calendar gets a channel carrying a token made up per run; calls without
+//! it are ignored. Channels expire (Google caps them at about a week), so
+//! `renew` replaces each one an hour before it does.
+
+use anyhow::Result;
+use chrono::{Duration, Utc};
+use std::sync::Arc;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::TcpListener;
+use tokio::sync::{mpsc, Mutex};
+
+use crate::sync::google::{Channel, GoogleCalendarClient};
+
+/// How long before a channel expires it's replaced.
+const RENEW_BEFORE: i64 = 60;
+
+/// Takes Google's calls on `port` (loopback only: the tunnel connects
+/// there), sending on `changed` for each one that carries `token` and
+/// reports a change. The first call on a new channel, `sync`, is only a
+/// hello.
+pub async fn listen(port: u16, token: String, changed: mpsc::Sender<()>) -> Result<()> {
+    let listener = TcpListener::bind(("127.0.0.1", port)).await?;
+    tracing::info!("Listening for Google push notifications on 127.0.0.1:{port}");
+    loop {
+        let (mut stream, _) = listener.accept().await?;
+        let (token, changed) = (token.clone(), changed.clone());
+        tokio::spawn(async move {
+            let (mut theirs, mut state) = (None, None);
+            let mut reader = BufReader::new(&mut stream);
+            let mut line   = String::new();
+            // Headers only: notifications have no body worth reading
+            while reader.read_line(&mut line).await.is_ok_and(|n| n > 0) && line.trim() != "" {
+                if let Some((name, value)) = line.split_once(':') {
+                    match name.trim().to_ascii_lowercase().as_str() {
+                        "x-goog-channel-token"  => theirs = Some(value.trim().to_owned()),
+                        "x-goog-resource-state" => state  = Some(value.trim().to_owned()),
+                        _ => {}
+                    }
+                }
+                line.clear();
+            }
+            let _ = stream.write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 0\r\nConnection: close\r\n\r\n").await;
+            match (theirs, state.as_deref()) {
+                (Some(t), Some("exists")) if t == token => { let _ = changed.try_send(()); }
+                (Some(t), _) if t == token => {}
+                _ => tracing::debug!("Ignored a push call without this run's token"),
+            }
+        });
+    }
+}
+
+/// Keeps one live channel per calendar in `calendar_ids` that's pulled:
+/// opens missing ones and replaces those about to expire, stopping the old.
+/// Failures are logged and tried again on the next call.
+pub async fn renew(
+    client:   &Arc<Mutex<GoogleCalendarClient>>,
+    address:  &str,
+    token:    &str,
+    channels: &mut Vec<Channel>,
+) {
+    let mut c   = client.lock().await;
+    let cal_ids: Vec<String> = c.config.calendar_ids.iter()
+        .filter(|id| c.config.direction(id).pulls()).cloned().collect();
+    let soon    = Utc::now() + Duration::minutes(RENEW_BEFORE);
+    for cal_id in &cal_ids {
+        let old = channels.iter().position(|ch| &ch.calendar_id == cal_id);
+        if old.is_some_and(|i| channels[i].expires > soon) { continue; }
+        match c.watch_events(cal_id, address, token).await {
+            Ok(ch) => {
+                tracing::info!("Push channel for {cal_id} open until {}", ch.expires);
+                if let Some(i) = old {
+                    let stale = channels.swap_remove(i);
+                    if let Err(e) = c.stop_channel(&stale).await {
+                        tracing::debug!("stop_channel({cal_id}): {e}");
+                    }
+                }
+                channels.push(ch);
+            }
+            Err(e) => tracing::warn!("watch_events({cal_id}): {e}"),
+        }
+    }
+}
+
+/// Closes every channel, so Google stops calling once the app is gone.
+pub async fn stop_all(client: &Arc<Mutex<GoogleCalendarClient>>, channels: &mut Vec<Channel>) {
+    let mut c = client.lock().await;
+    for ch in channels.drain(..) {
+        if let Err(e) = c.stop_channel(&ch).await {
+            tracing::debug!("stop_channel({}): {e}", ch.calendar_id);
+        }
+    }
+}
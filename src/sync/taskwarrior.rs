@@ -0,0 +1,266 @@
+//! Taskwarrior through its own CLI: `task export` for pulls and `task
+//! import` for pushes, so the `task` command and hooks keep working as
+//! before. Each configured project is a task list here (`taskwarrior:<project>`,
+//! `taskwarrior:all` for every task in none of the others), and tasks go through the same
+//! dirty-flag pipeline as the other backends: an edit made here that hasn't
+//! reached Taskwarrior yet wins, otherwise Taskwarrior's copy does.
+//!
+//! UUIDs are the sync ids; tags, H/M/L priorities (3/2/1 here) and due dates
+//! map both ways, annotations become the notes. Taskwarrior's times are UTC,
+//! converted to wall time like everything else here. Only pending and
+//! waiting tasks are pulled, so one that disappears was completed (or
+//! deleted) in Taskwarrior, and is marked done here.
+
+use anyhow::{anyhow, bail, Context, Result};
+use chrono::{DateTime, Local, NaiveDateTime, TimeZone, Utc};
+use serde::Deserialize;
+use serde_json::Value;
+use std::process::Stdio;
+use tokio::io::AsyncWriteExt;
+use tokio::process::Command;
+
+use crate::db::{Event, Task, TASKWARRIOR_PREFIX};
+use crate::sync::provider::{PulledEvents, SyncProvider};
+
+/// Names the list of every task outside the other configured projects.
+const ALL: &str = "all";
+/// Taskwarrior's date format in JSON.
+const TW_TIME: &str = "%Y%m%dT%H%M%SZ";
+
+// ─── Config ───────────────────────────────────────────────────────────────────
+
+fn default_command()  -> String { "task".to_owned() }
+fn default_projects() -> Vec<String> { vec![ALL.to_owned()] }
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct TaskwarriorConfig {
+    /// The `task` binary, if it isn't on PATH as `task`.
+    #[serde(default = "default_command")]
+    pub command:  String,
+    /// Projects to sync; `all` is every task not in one of the others.
+    #[serde(default = "default_projects")]
+    pub projects: Vec<String>,
+}
+
+// ─── Export types ─────────────────────────────────────────────────────────────
+
+#[derive(Debug, Deserialize)]
+struct TwAnnotation {
+    description: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct TwTask {
+    uuid:        String,
+    description: String,
+    status:      String,
+    #[serde(default)]
+    tags:        Vec<String>,
+    priority:    Option<String>,
+    due:         Option<String>,
+    #[serde(default)]
+    annotations: Vec<TwAnnotation>,
+    #[serde(default)]
+    urgency:     f64,
+}
+
+// ─── Client ───────────────────────────────────────────────────────────────────
+
+pub struct TaskwarriorClient {
+    pub config: TaskwarriorConfig,
+}
+
+impl TaskwarriorClient {
+    pub fn new(config: TaskwarriorConfig) -> Self {
+        Self { config }
+    }
+
+    /// Runs `task` with `args` (and `input` on stdin), without prompts or
+    /// chatter: its stdout.
+    async fn run(&self, args: &[&str], input: Option<&str>) -> Result<String> {
+        let mut child = Command::new(&self.config.command)
+            .args(["rc.confirmation=off", "rc.verbose=nothing", "rc.json.array=on"])
+            .args(args)
+            .stdin(if input.is_some() { Stdio::piped() } else { Stdio::null() })
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .with_context(|| format!("running {}", self.config.command))?;
+        if let (Some(text), Some(mut stdin)) = (input, child.stdin.take()) {
+            stdin.write_all(text.as_bytes()).await?;
+        }
+        let out = child.wait_with_output().await?;
+        if !out.status.success() {
+            bail!("{} {}: {}", self.config.command, args.join(" "), String::from_utf8_lossy(&out.stderr).trim());
+        }
+        Ok(String::from_utf8_lossy(&out.stdout).into_owned())
+    }
+
+    async fn export<T: serde::de::DeserializeOwned>(&self, filter: &[&str]) -> Result<Vec<T>> {
+        let mut args = filter.to_vec();
+        args.push("export");
+        let text = self.run(&args, None).await?;
+        if text.trim().is_empty() { return Ok(Vec::new()); }
+        serde_json::from_str(&text).context("reading task export")
+    }
+
+    /// Writes `body` over the task with its uuid, or adds it.
+    async fn import(&self, body: &Value) -> Result<()> {
+        self.run(&["import"], Some(&format!("[{body}]"))).await.map(drop)
+    }
+}
+
+/// The Taskwarrior project behind local list `list_id`; None for `all`.
+fn project(list_id: &str) -> Option<&str> {
+    Some(list_id.strip_prefix(TASKWARRIOR_PREFIX).unwrap_or(list_id)).filter(|p| *p != ALL)
+}
+
+impl SyncProvider for TaskwarriorClient {
+    fn name(&self) -> &'static str { "taskwarrior" }
+
+    fn prefix(&self) -> &'static str { TASKWARRIOR_PREFIX }
+
+    fn calendar_ids(&self) -> Vec<String> { Vec::new() }
+
+    fn task_list_ids(&self) -> Vec<String> {
+        self.config.projects.iter().map(|p| format!("{TASKWARRIOR_PREFIX}{p}")).collect()
+    }
+
+    async fn signed_in(&self) -> Result<bool> {
+        Ok(true)
+    }
+
+    async fn list_name(&mut self, list_id: &str) -> Result<Option<String>> {
+        Ok(Some(project(list_id).unwrap_or("Taskwarrior").to_owned()))
+    }
+
+    async fn pull_events(&mut self, _calendar_id: &str) -> Result<PulledEvents> {
+        bail!("Taskwarrior has no calendars")
+    }
+
+    async fn pull_tasks(&mut self, list_id: &str) -> Result<Vec<Task>> {
+        let scope: Vec<String> = match project(list_id) {
+            Some(p) => vec![format!("project:{p}")],
+            None    => self.config.projects.iter().filter(|p| *p != ALL).map(|p| format!("project.not:{p}")).collect(),
+        };
+        let mut filter: Vec<&str> = scope.iter().map(String::as_str).collect();
+        filter.push("(status:pending or status:waiting)");
+        let mut tasks: Vec<TwTask> = self.export(&filter).await?;
+        // Most urgent first, as `task next` lists them
+        tasks.sort_by(|a, b| b.urgency.total_cmp(&a.urgency));
+        Ok(tasks.iter().map(|t| tw_to_local(t, list_id)).collect())
+    }
+
+    fn gone_task(&self, task: Task) -> Task {
+        Task { completed: true, ..task }
+    }
+
+    async fn create_event(&mut self, _ev: &Event) -> Result<(String, Option<String>)> {
+        bail!("Taskwarrior has no calendars")
+    }
+
+    async fn update_event(&mut self, _ev: &Event) -> Result<Option<String>> {
+        bail!("Taskwarrior has no calendars")
+    }
+
+    async fn delete_event(&mut self, _ev: &Event) -> Result<()> {
+        bail!("Taskwarrior has no calendars")
+    }
+
+    async fn create_task(&mut self, task: &Task) -> Result<String> {
+        let uuid = uuid::Uuid::new_v4().to_string();
+        let mut body = task_to_tw(task, &uuid);
+        body["entry"] = task.created_at.format(TW_TIME).to_string().into();
+        self.import(&body).await?;
+        Ok(uuid)
+    }
+
+    /// Laid over Taskwarrior's copy, so what isn't mapped here (wait,
+    /// scheduled, dependencies, recurrence, UDAs) stays as it was.
+    async fn update_task(&mut self, task: &Task) -> Result<()> {
+        let uuid = task.sync_id.as_deref().ok_or_else(|| anyhow!("task isn't linked to Taskwarrior"))?;
+        let mut merged = self.export::<Value>(&[uuid]).await?.into_iter().next()
+            .unwrap_or_else(|| serde_json::json!({ "entry": task.created_at.format(TW_TIME).to_string() }));
+        let Some(fields) = merged.as_object_mut() else { bail!("unexpected task export for {uuid}") };
+        for key in ["due", "priority", "tags", "annotations", "end"] { fields.remove(key); }
+        if let Value::Object(body) = task_to_tw(task, uuid) { fields.extend(body); }
+        self.import(&merged).await
+    }
+
+    async fn delete_task(&mut self, task: &Task) -> Result<()> {
+        let Some(uuid) = task.sync_id.as_deref() else { return Ok(()) };
+        let gone = self.export::<TwTask>(&[uuid]).await?.iter().all(|t| t.status == "deleted");
+        if !gone {
+            self.run(&[uuid, "delete"], None).await?;
+        }
+        Ok(())
+    }
+}
+
+// ─── Converters ───────────────────────────────────────────────────────────────
+
+/// A Taskwarrior UTC time as wall time.
+fn tw_time(s: &str) -> Option<DateTime<Utc>> {
+    let at = NaiveDateTime::parse_from_str(s, TW_TIME).ok()?.and_utc();
+    Some(at.with_timezone(&Local).naive_local().and_utc())
+}
+
+/// Wall time as a Taskwarrior UTC time.
+fn to_tw_time(at: DateTime<Utc>) -> String {
+    let real = Local.from_local_datetime(&at.naive_utc()).earliest().map_or(at, |l| l.with_timezone(&Utc));
+    real.format(TW_TIME).to_string()
+}
+
+fn tw_to_local(t: &TwTask, list_id: &str) -> Task {
+    let notes = t.annotations.iter().map(|a| a.description.as_str()).collect::<Vec<_>>().join("\n");
+    let now = Utc::now();
+    Task {
+        id: uuid::Uuid::new_v4().to_string(),
+        title: t.description.clone(),
+        notes: Some(notes).filter(|n| !n.trim().is_empty()),
+        due: t.due.as_deref().and_then(tw_time),
+        completed: t.status == "completed",
+        priority: match t.priority.as_deref() {
+            Some("H") => 3,
+            Some("M") => 2,
+            Some("L") => 1,
+            _         => 0,
+        },
+        task_list_id: Some(list_id.to_owned()),
+        sync_id: Some(t.uuid.clone()), dirty: false, deleted: false, tags: t.tags.clone(), position: 0, repeat: None,
+        estimate_minutes: 0,
+        created_at: now, updated_at: now,
+    }
+}
+
+/// The fields mapped here, as `task import` takes them.
+fn task_to_tw(t: &Task, uuid: &str) -> Value {
+    let mut body = serde_json::json!({
+        "uuid":        uuid,
+        "description": t.title,
+        "status":      if t.completed { "completed" } else { "pending" },
+        "modified":    t.updated_at.format(TW_TIME).to_string(),
+    });
+    if let Some(p) = t.task_list_id.as_deref().and_then(project) { body["project"] = p.into(); }
+    if t.completed { body["end"] = t.updated_at.format(TW_TIME).to_string().into(); }
+    if let Some(due) = t.due { body["due"] = to_tw_time(due).into(); }
+    match t.priority {
+        p if p >= 3 => body["priority"] = "H".into(),
+        2           => body["priority"] = "M".into(),
+        1           => body["priority"] = "L".into(),
+        _           => {}
+    }
+    if !t.tags.is_empty() { body["tags"] = t.tags.clone().into(); }
+    // One annotation a line, stamped from the task's creation so pushing the
+    // same notes again changes nothing
+    let lines: Vec<Value> = t.notes.as_deref().unwrap_or_default().lines()
+        .filter(|l| !l.trim().is_empty())
+        .enumerate()
+        .map(|(i, l)| serde_json::json!({
+            "entry":       (t.created_at + chrono::Duration::seconds(i as i64)).format(TW_TIME).to_string(),
+            "description": l.trim(),
+        }))
+        .collect();
+    if !lines.is_empty() { body["annotations"] = lines.into(); }
+    body
+}
@@ -0,0 +1,99 @@
+//! `[titles]` rewrite rules for pulled event titles — trims corporate noise
+//! like "[EXTERNAL][Recording] Weekly Sync — Do Not Forward" down to what
+//! fits the events panel. Google keeps the original: see `for_push`.
+
+use regex::Regex;
+
+use crate::config::TitlesConfig;
+use crate::db::Event;
+
+struct Rule {
+    re:       Option<Regex>,
+    replace:  Option<String>,
+    prefix:   Option<String>,
+    calendar: Option<String>,
+}
+
+pub struct TitleRules {
+    strip_emoji: bool,
+    rules:       Vec<Rule>,
+}
+
+impl TitleRules {
+    /// Compiles the configured rules; ones with a bad regex are skipped.
+    pub fn from_config(cfg: &TitlesConfig) -> Self {
+        let rules = cfg.rules.iter().filter_map(|r| {
+            let re = match r.pattern.as_deref().map(Regex::new) {
+                Some(Ok(re)) => Some(re),
+                Some(Err(e)) => {
+                    tracing::warn!("[titles] skipping rule with invalid pattern: {e}");
+                    return None;
+                }
+                None => None,
+            };
+            Some(Rule {
+                re,
+                replace:  r.replace.clone(),
+                prefix:   r.prefix.clone(),
+                calendar: r.calendar.clone(),
+            })
+        }).collect();
+        Self { strip_emoji: cfg.strip_emoji, rules }
+    }
+
+    fn is_empty(&self) -> bool { !self.strip_emoji && self.rules.is_empty() }
+
+    /// `title` after every rule that applies to `calendar`. Whitespace left
+    /// behind by deletions is collapsed; a title rewritten to nothing is kept.
+    pub fn apply(&self, title: &str, calendar: &str) -> String {
+        if self.is_empty() { return title.to_owned(); }
+
+        let mut out = title.to_owned();
+        if self.strip_emoji {
+            out.retain(|c| !is_emoji(c));
+        }
+        for rule in &self.rules {
+            if rule.calendar.as_deref().is_some_and(|c| c != calendar) { continue; }
+            if rule.re.as_ref().is_some_and(|re| !re.is_match(&out)) { continue; }
+            if let (Some(re), Some(rep)) = (&rule.re, &rule.replace) {
+                out = re.replace_all(&out, rep.as_str()).into_owned();
+            }
+            if let Some(prefix) = &rule.prefix {
+                out.insert_str(0, prefix);
+            }
+        }
+
+        let out = out.split_whitespace().collect::<Vec<_>>().join(" ");
+        if out.is_empty() { title.to_owned() } else { out }
+    }
+
+    /// Rewrites a pulled event's title, remembering the original.
+    pub fn normalize(&self, ev: &mut Event) {
+        let cal   = ev.calendar_id.as_deref().unwrap_or("primary");
+        let title = self.apply(&ev.title, cal);
+        if title != ev.title {
+            ev.remote_title = Some(std::mem::replace(&mut ev.title, title));
+        }
+    }
+
+    /// The event as it should be pushed: with Google's original title unless
+    /// the title has been changed locally since it was rewritten.
+    pub fn for_push(&self, ev: &Event) -> Event {
+        let mut out = ev.clone();
+        if let Some(raw) = &ev.remote_title {
+            let cal = ev.calendar_id.as_deref().unwrap_or("primary");
+            if self.apply(raw, cal) == ev.title {
+                out.title = raw.clone();
+            }
+        }
+        out
+    }
+}
+
+/// Pictographs, dingbats, flags, plus the variation selector and zero-width
+/// joiner that glue multi-codepoint emoji together.
+fn is_emoji(c: char) -> bool {
+    matches!(c as u32,
+        0x1F000..=0x1FAFF | 0x2600..=0x27BF | 0x2300..=0x23FF | 0x2B00..=0x2BFF
+        | 0xFE0F | 0x200D | 0xE0020..=0xE007F)
+}
@@ -0,0 +1,230 @@
+//! Todoist tasks over the REST v2 API. Each configured project is a task
+//! list here (`todoist:<project id>`, `todoist:inbox` for the Inbox), and
+//! tasks go through the same dirty-flag pipeline as Google Tasks.
+//!
+//! Todoist's 1 (normal) to 4 (urgent) priorities map onto 0 to 3 here; a
+//! due date without a time is midnight UTC, like Google's. The API only
+//! lists open tasks, so one that disappears was completed (or deleted) in
+//! Todoist, and is marked done here.
+
+use anyhow::{anyhow, bail, Result};
+use chrono::{DateTime, NaiveDate, NaiveTime, Utc};
+use reqwest::{Client, Method, RequestBuilder, StatusCode};
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::db::{Event, Task, TODOIST_PREFIX};
+use crate::sync::google::pct;
+use crate::sync::provider::{PulledEvents, SyncProvider};
+
+const API:   &str = "https://api.todoist.com/rest/v2";
+/// Names the Inbox project, whatever its id.
+const INBOX: &str = "inbox";
+
+// ─── Config ───────────────────────────────────────────────────────────────────
+
+fn default_projects() -> Vec<String> { vec![INBOX.to_owned()] }
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct TodoistConfig {
+    /// Personal API token, from Todoist's Settings → Integrations → Developer.
+    pub token:    String,
+    /// Project ids to sync; `inbox` is the Inbox.
+    #[serde(default = "default_projects")]
+    pub projects: Vec<String>,
+}
+
+// ─── API types ────────────────────────────────────────────────────────────────
+
+#[derive(Debug, Deserialize)]
+struct TodoistDue {
+    date:     String,
+    datetime: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct TodoistTask {
+    id:           String,
+    content:      String,
+    #[serde(default)]
+    description:  String,
+    #[serde(default = "normal")]
+    priority:     i64,
+    due:          Option<TodoistDue>,
+    #[serde(default)]
+    is_completed: bool,
+    #[serde(default)]
+    order:        i64,
+}
+
+fn normal() -> i64 { 1 }
+
+// ─── Client ───────────────────────────────────────────────────────────────────
+
+pub struct TodoistClient {
+    http:       Client,
+    pub config: TodoistConfig,
+    /// Id of the Inbox project, once looked up.
+    inbox:      Option<String>,
+}
+
+impl TodoistClient {
+    pub fn new(config: TodoistConfig) -> Self {
+        Self {
+            http: Client::builder()
+                .timeout(std::time::Duration::from_secs(30))
+                .user_agent("LifeManager/0.1")
+                .build().expect("http client"),
+            config,
+            inbox: None,
+        }
+    }
+
+    fn request(&self, method: Method, path: &str) -> RequestBuilder {
+        self.http.request(method, format!("{API}{path}")).bearer_auth(&self.config.token)
+    }
+
+    /// The Todoist project id behind local list `list_id`.
+    async fn project_id(&mut self, list_id: &str) -> Result<String> {
+        match list_id.strip_prefix(TODOIST_PREFIX).unwrap_or(list_id) {
+            INBOX => {
+                if let Some(ref id) = self.inbox { return Ok(id.clone()); }
+                let projects: Vec<Value> = self.request(Method::GET, "/projects")
+                    .send().await?.error_for_status()?.json().await?;
+                let id = projects.iter()
+                    .find(|p| p["is_inbox_project"] == true)
+                    .and_then(|p| p["id"].as_str())
+                    .ok_or_else(|| anyhow!("Todoist returned no Inbox project"))?
+                    .to_owned();
+                self.inbox = Some(id.clone());
+                Ok(id)
+            }
+            id => Ok(id.to_owned()),
+        }
+    }
+
+    /// Closes or reopens the task to match `completed` here.
+    async fn set_completed(&self, sid: &str, completed: bool) -> Result<()> {
+        let verb = if completed { "close" } else { "reopen" };
+        self.request(Method::POST, &format!("/tasks/{}/{verb}", pct(sid)))
+            .send().await?.error_for_status()?;
+        Ok(())
+    }
+}
+
+impl SyncProvider for TodoistClient {
+    fn name(&self) -> &'static str { "todoist" }
+
+    fn prefix(&self) -> &'static str { TODOIST_PREFIX }
+
+    fn calendar_ids(&self) -> Vec<String> { Vec::new() }
+
+    fn task_list_ids(&self) -> Vec<String> {
+        self.config.projects.iter().map(|id| format!("{TODOIST_PREFIX}{id}")).collect()
+    }
+
+    async fn signed_in(&self) -> Result<bool> {
+        Ok(!self.config.token.trim().is_empty())
+    }
+
+    async fn list_name(&mut self, list_id: &str) -> Result<Option<String>> {
+        let project = self.project_id(list_id).await?;
+        let body: Value = self.request(Method::GET, &format!("/projects/{}", pct(&project)))
+            .send().await?.error_for_status()?.json().await?;
+        Ok(body["name"].as_str().map(str::to_owned))
+    }
+
+    async fn pull_events(&mut self, _calendar_id: &str) -> Result<PulledEvents> {
+        bail!("Todoist has no calendars")
+    }
+
+    async fn pull_tasks(&mut self, list_id: &str) -> Result<Vec<Task>> {
+        let project = self.project_id(list_id).await?;
+        let mut tasks: Vec<TodoistTask> = self.request(Method::GET, "/tasks")
+            .query(&[("project_id", project.as_str())])
+            .send().await?.error_for_status()?.json().await?;
+        tasks.sort_by_key(|t| t.order);
+        Ok(tasks.iter().map(|t| todoist_to_local(t, list_id)).collect())
+    }
+
+    fn gone_task(&self, task: Task) -> Task {
+        Task { completed: true, ..task }
+    }
+
+    async fn create_event(&mut self, _ev: &Event) -> Result<(String, Option<String>)> {
+        bail!("Todoist has no calendars")
+    }
+
+    async fn update_event(&mut self, _ev: &Event) -> Result<Option<String>> {
+        bail!("Todoist has no calendars")
+    }
+
+    async fn delete_event(&mut self, _ev: &Event) -> Result<()> {
+        bail!("Todoist has no calendars")
+    }
+
+    async fn create_task(&mut self, task: &Task) -> Result<String> {
+        let project = self.project_id(task.task_list_id.as_deref().unwrap_or(INBOX)).await?;
+        let mut body = task_to_todoist(task);
+        body["project_id"] = project.into();
+        let created: Value = self.request(Method::POST, "/tasks")
+            .json(&body)
+            .send().await?.error_for_status()?.json().await?;
+        let id = created["id"].as_str().ok_or_else(|| anyhow!("Todoist returned no task id"))?.to_owned();
+        if task.completed { self.set_completed(&id, true).await?; }
+        Ok(id)
+    }
+
+    async fn update_task(&mut self, task: &Task) -> Result<()> {
+        let sid = task.sync_id.as_deref().ok_or_else(|| anyhow!("task isn't linked to Todoist"))?;
+        self.request(Method::POST, &format!("/tasks/{}", pct(sid)))
+            .json(&task_to_todoist(task))
+            .send().await?.error_for_status()?;
+        self.set_completed(sid, task.completed).await
+    }
+
+    async fn delete_task(&mut self, task: &Task) -> Result<()> {
+        let Some(sid) = task.sync_id.as_deref() else { return Ok(()) };
+        let resp = self.request(Method::DELETE, &format!("/tasks/{}", pct(sid))).send().await?;
+        if resp.status() != StatusCode::NOT_FOUND {
+            resp.error_for_status()?;
+        }
+        Ok(())
+    }
+}
+
+// ─── Converters ───────────────────────────────────────────────────────────────
+
+fn todoist_to_local(t: &TodoistTask, list_id: &str) -> Task {
+    let due = t.due.as_ref().and_then(|d| match &d.datetime {
+        Some(at) => DateTime::parse_from_rfc3339(at).ok().map(|d| d.with_timezone(&Utc)),
+        None     => NaiveDate::parse_from_str(&d.date, "%Y-%m-%d").ok().map(|d| d.and_time(NaiveTime::MIN).and_utc()),
+    });
+    let now = Utc::now();
+    Task {
+        id: uuid::Uuid::new_v4().to_string(),
+        title: t.content.clone(),
+        notes: Some(t.description.clone()).filter(|d| !d.trim().is_empty()),
+        due, completed: t.is_completed,
+        priority: (t.priority - 1).clamp(0, crate::tasks::MAX_PRIORITY),
+        task_list_id: Some(list_id.to_owned()),
+        sync_id: Some(t.id.clone()), dirty: false, deleted: false, tags: Vec::new(), position: 0, repeat: None,
+        estimate_minutes: 0,
+        created_at: now, updated_at: now,
+    }
+}
+
+fn task_to_todoist(t: &Task) -> Value {
+    let mut body = serde_json::json!({
+        "content":     t.title,
+        "description": t.notes.clone().unwrap_or_default(),
+        "priority":    (t.priority + 1).clamp(1, 4),
+    });
+    match t.due {
+        // Midnight UTC is a date without a time
+        Some(at) if at.time() == NaiveTime::MIN => body["due_date"] = at.format("%Y-%m-%d").to_string().into(),
+        Some(at) => body["due_datetime"] = at.to_rfc3339().into(),
+        None     => body["due_string"] = "no date".into(),
+    }
+    body
+}
@@ -0,0 +1,31 @@
+//! Sanity checks for events arriving from Google or ICS feeds. Anything that
+//! fails is quarantined for review (`Q` in the TUI) instead of being guessed
+//! at, stored corrupt or silently dropped.
+
+use chrono::{DateTime, Datelike, Utc};
+
+/// Longest span accepted for a single event.
+pub const MAX_SPAN_DAYS: i64 = 366;
+
+/// Years outside this range are almost certainly a bad timestamp.
+const SANE_YEARS: std::ops::RangeInclusive<i32> = 1900..=2200;
+
+/// The start/end pair if it looks trustworthy, else the reason it doesn't.
+pub fn check_times(
+    start: Option<DateTime<Utc>>,
+    end:   Option<DateTime<Utc>>,
+) -> Result<(DateTime<Utc>, DateTime<Utc>), String> {
+    let start = start.ok_or("missing or unreadable start")?;
+    let end   = end.ok_or("missing or unreadable end")?;
+    if !SANE_YEARS.contains(&start.year()) {
+        return Err(format!("starts in year {}", start.year()));
+    }
+    if end < start {
+        return Err("ends before it starts".into());
+    }
+    let days = (end - start).num_days();
+    if days > MAX_SPAN_DAYS {
+        return Err(format!("lasts {days} days"));
+    }
+    Ok((start, end))
+}
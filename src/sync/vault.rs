@@ -0,0 +1,360 @@
+//! The vault: every event and task mirrored to a directory of plain files,
+//! `events/<id>.ics` and `tasks/<id>.md`, kept in step both ways, so the
+//! data can live in a git repo or an Obsidian vault and be edited there.
+//!
+//! Each sweep compares every file with its row. When they differ, whichever
+//! changed last wins: a file modified after the row was updated is read in
+//! (and pushed like any edit made in the TUI), otherwise the file is
+//! rewritten. Deleting a file deletes its item; a file added to the vault
+//! becomes a new item, its name the id. `.lifemanager-vault` lists what was
+//! mirrored, which is how a deleted file is told from one not written yet.
+//!
+//! Events on the local layers (plan, focus, time log) stay out of it.
+
+use anyhow::Result;
+use chrono::{DateTime, NaiveDate, NaiveDateTime, NaiveTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use crate::db::{Database, Event, Task};
+use crate::export::{self, Format};
+use crate::sync::ics;
+
+const MANIFEST: &str = ".lifemanager-vault";
+
+// ─── Config ───────────────────────────────────────────────────────────────────
+
+fn default_interval() -> u64 { 5 }
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct VaultConfig {
+    /// The directory to mirror into; `~/` is expanded.
+    pub dir:              String,
+    /// How often the running TUI looks for changes on either side.
+    #[serde(default = "default_interval")]
+    pub interval_seconds: u64,
+}
+
+// ─── Vault ────────────────────────────────────────────────────────────────────
+
+/// The ids mirrored at the last sweep.
+#[derive(Default, Serialize, Deserialize)]
+struct Manifest {
+    events: HashSet<String>,
+    tasks:  HashSet<String>,
+}
+
+/// A file in `events/` or `tasks/`, by id.
+struct File {
+    path:     PathBuf,
+    modified: DateTime<Utc>,
+}
+
+pub struct Vault {
+    dir: PathBuf,
+    db:  Database,
+    /// The database stamp and file listing at the last sweep: nothing to
+    /// do while neither changes.
+    last: Option<(String, Vec<(PathBuf, SystemTime)>)>,
+}
+
+impl Vault {
+    pub fn new(config: &VaultConfig, db: Database) -> Self {
+        Self { dir: export::expand(&config.dir), db, last: None }
+    }
+
+    /// Brings files and rows in step: how many items changed from the files.
+    pub async fn sweep(&mut self) -> Result<usize> {
+        let (events_dir, tasks_dir) = (self.dir.join("events"), self.dir.join("tasks"));
+        fs::create_dir_all(&events_dir)?;
+        fs::create_dir_all(&tasks_dir)?;
+
+        let state = (self.db.change_stamp().await?, listing(&[&events_dir, &tasks_dir]));
+        if self.last.as_ref() == Some(&state) { return Ok(0); }
+
+        let mut manifest: Manifest = fs::read_to_string(self.dir.join(MANIFEST)).ok()
+            .and_then(|s| serde_json::from_str(&s).ok())
+            .unwrap_or_default();
+        let imported = self.sweep_events(&events_dir, &mut manifest.events).await?
+            + self.sweep_tasks(&tasks_dir, &mut manifest.tasks).await?;
+        fs::write(self.dir.join(MANIFEST), serde_json::to_string_pretty(&manifest)?)?;
+
+        // What this sweep wrote is the new baseline
+        self.last = Some((self.db.change_stamp().await?, listing(&[&events_dir, &tasks_dir])));
+        Ok(imported)
+    }
+
+    // ── Events ────────────────────────────────────────────────────────────────
+
+    async fn sweep_events(&self, dir: &Path, mirrored: &mut HashSet<String>) -> Result<usize> {
+        let mut files    = files_in(dir, "ics");
+        let mut imported = 0;
+        let now          = Utc::now();
+
+        for mut ev in self.db.calendar_events().await? {
+            let path = dir.join(format!("{}.ics", ev.id));
+            let Some(file) = files.remove(&ev.id) else {
+                if mirrored.contains(&ev.id) {
+                    // Deleted from the vault
+                    ev.deleted = true;
+                    mark_changed(&mut ev, now);
+                    self.db.upsert_event(&ev).await?;
+                    mirrored.remove(&ev.id);
+                    imported += 1;
+                } else {
+                    write_event(&path, &ev)?;
+                    mirrored.insert(ev.id.clone());
+                }
+                continue;
+            };
+            mirrored.insert(ev.id.clone());
+            let Some(fields) = fs::read_to_string(&file.path).ok().and_then(|t| ics::first_event(&t)) else {
+                tracing::warn!("vault: can't read an event from {}; left alone", file.path.display());
+                continue;
+            };
+            if fields == event_fields(&ev) { continue; }
+            if file.modified > ev.updated_at {
+                apply_event(&mut ev, fields);
+                mark_changed(&mut ev, now);
+                self.db.upsert_event(&ev).await?;
+                imported += 1;
+            } else {
+                write_event(&path, &ev)?;
+            }
+        }
+
+        // Files without a live event: deleted here, or new in the vault
+        for (id, file) in files {
+            if mirrored.remove(&id) {
+                fs::remove_file(&file.path)?;
+                continue;
+            }
+            let Some(fields) = fs::read_to_string(&file.path).ok().and_then(|t| ics::first_event(&t)) else {
+                tracing::warn!("vault: can't read an event from {}; left alone", file.path.display());
+                continue;
+            };
+            let mut ev = Event::new(&fields.title, fields.start, fields.end);
+            ev.id = id.clone();
+            apply_event(&mut ev, fields);
+            self.db.upsert_event(&ev).await?;
+            mirrored.insert(id);
+            imported += 1;
+        }
+        Ok(imported)
+    }
+
+    // ── Tasks ─────────────────────────────────────────────────────────────────
+
+    async fn sweep_tasks(&self, dir: &Path, mirrored: &mut HashSet<String>) -> Result<usize> {
+        let mut files    = files_in(dir, "md");
+        let mut imported = 0;
+        let now          = Utc::now();
+
+        for mut task in self.db.all_tasks().await? {
+            let path = dir.join(format!("{}.md", task.id));
+            let Some(file) = files.remove(&task.id) else {
+                if mirrored.contains(&task.id) {
+                    task.deleted    = true;
+                    task.dirty      = true;
+                    task.updated_at = now;
+                    self.db.upsert_task(&task).await?;
+                    mirrored.remove(&task.id);
+                    imported += 1;
+                } else {
+                    fs::write(&path, task_markdown(&task))?;
+                    mirrored.insert(task.id.clone());
+                }
+                continue;
+            };
+            mirrored.insert(task.id.clone());
+            let Some(fields) = fs::read_to_string(&file.path).ok().and_then(|t| parse_task(&t)) else {
+                tracing::warn!("vault: can't read a task from {}; left alone", file.path.display());
+                continue;
+            };
+            if fields == task_fields(&task) { continue; }
+            if file.modified > task.updated_at {
+                apply_task(&mut task, fields);
+                task.dirty      = true;
+                task.updated_at = now;
+                self.db.upsert_task(&task).await?;
+                imported += 1;
+            } else {
+                fs::write(&path, task_markdown(&task))?;
+            }
+        }
+
+        for (id, file) in files {
+            if mirrored.remove(&id) {
+                fs::remove_file(&file.path)?;
+                continue;
+            }
+            let Some(fields) = fs::read_to_string(&file.path).ok().and_then(|t| parse_task(&t)) else {
+                tracing::warn!("vault: can't read a task from {}; left alone", file.path.display());
+                continue;
+            };
+            let mut task = Task::new(&fields.title);
+            task.id = id.clone();
+            apply_task(&mut task, fields);
+            self.db.upsert_task(&task).await?;
+            mirrored.insert(id);
+            imported += 1;
+        }
+        Ok(imported)
+    }
+}
+
+/// Every file's path and modification time, to tell whether any changed.
+fn listing(dirs: &[&Path]) -> Vec<(PathBuf, SystemTime)> {
+    let mut all: Vec<(PathBuf, SystemTime)> = dirs.iter()
+        .filter_map(|d| fs::read_dir(d).ok())
+        .flatten()
+        .filter_map(|e| e.ok())
+        .filter_map(|e| Some((e.path(), e.metadata().ok()?.modified().ok()?)))
+        .collect();
+    all.sort();
+    all
+}
+
+/// The `*.<ext>` files in `dir`, by file stem.
+fn files_in(dir: &Path, ext: &str) -> HashMap<String, File> {
+    fs::read_dir(dir).into_iter().flatten()
+        .filter_map(|e| e.ok())
+        .filter_map(|e| {
+            let path = e.path();
+            if path.extension().and_then(|x| x.to_str()) != Some(ext) { return None; }
+            let id       = path.file_stem()?.to_str()?.to_owned();
+            let modified = e.metadata().ok()?.modified().ok()?.into();
+            Some((id, File { path, modified }))
+        })
+        .collect()
+}
+
+// ─── Event files ──────────────────────────────────────────────────────────────
+
+fn write_event(path: &Path, ev: &Event) -> Result<()> {
+    fs::write(path, export::render(Format::Ics, &ev.title, std::slice::from_ref(ev), &[])?)?;
+    Ok(())
+}
+
+fn event_fields(ev: &Event) -> ics::EventFields {
+    ics::EventFields {
+        title:       ev.title.clone(),
+        description: ev.description.clone().filter(|d| !d.is_empty()),
+        start:       ev.start,
+        end:         ev.end,
+        all_day:     ev.all_day,
+    }
+}
+
+fn apply_event(ev: &mut Event, f: ics::EventFields) {
+    ev.title       = f.title;
+    ev.description = f.description;
+    ev.start       = f.start;
+    ev.end         = f.end;
+    ev.all_day     = f.all_day;
+}
+
+/// Edited from the vault: pushed like any edit, bar on a local layer.
+fn mark_changed(ev: &mut Event, now: DateTime<Utc>) {
+    ev.dirty      = !ev.is_local_layer();
+    ev.updated_at = now;
+}
+
+// ─── Task files ───────────────────────────────────────────────────────────────
+
+/// What a vault file says about its task.
+#[derive(Debug, PartialEq)]
+struct TaskFields {
+    title:     String,
+    notes:     Option<String>,
+    due:       Option<DateTime<Utc>>,
+    completed: bool,
+    priority:  i64,
+    tags:      Vec<String>,
+}
+
+fn task_fields(t: &Task) -> TaskFields {
+    TaskFields {
+        title:     t.title.clone(),
+        notes:     t.notes.clone().map(|n| n.trim().to_owned()).filter(|n| !n.is_empty()),
+        due:       t.due,
+        completed: t.completed,
+        priority:  t.priority,
+        tags:      t.tags.clone(),
+    }
+}
+
+fn apply_task(t: &mut Task, f: TaskFields) {
+    t.title     = f.title;
+    t.notes     = f.notes;
+    t.due       = f.due;
+    t.completed = f.completed;
+    t.priority  = f.priority;
+    t.tags      = f.tags;
+}
+
+/// Front matter Obsidian reads, then the title and the notes:
+///
+/// ```text
+/// ---
+/// done: false
+/// due: 2026-10-20
+/// priority: 2
+/// tags: [home, errands]
+/// ---
+/// # Buy paint
+/// ```
+fn task_markdown(t: &Task) -> String {
+    let mut out = format!("---\ndone: {}\n", t.completed);
+    if let Some(due) = t.due {
+        let due = if due.time() == NaiveTime::MIN { due.format("%Y-%m-%d") } else { due.format("%Y-%m-%d %H:%M") };
+        out.push_str(&format!("due: {due}\n"));
+    }
+    if t.priority > 0 { out.push_str(&format!("priority: {}\n", t.priority)); }
+    if !t.tags.is_empty() { out.push_str(&format!("tags: [{}]\n", t.tags.join(", "))); }
+    out.push_str(&format!("---\n# {}\n", t.title));
+    if let Some(notes) = t.notes.as_deref().map(str::trim).filter(|n| !n.is_empty()) {
+        out.push_str(&format!("\n{notes}\n"));
+    }
+    out
+}
+
+fn parse_task(text: &str) -> Option<TaskFields> {
+    let mut fields = TaskFields { title: String::new(), notes: None, due: None, completed: false, priority: 0, tags: Vec::new() };
+    let mut body = text;
+    if let Some(rest) = text.strip_prefix("---\n") {
+        let (front, after) = rest.split_once("\n---")?;
+        body = after.strip_prefix('\n').unwrap_or(after);
+        for line in front.lines() {
+            let Some((key, value)) = line.split_once(':') else { continue };
+            let value = value.trim();
+            match key.trim() {
+                "done"     => fields.completed = value == "true",
+                "due"      => fields.due = parse_due(value),
+                "priority" => fields.priority = value.parse().unwrap_or(0),
+                "tags"     => fields.tags = value.trim_matches(['[', ']']).split(',')
+                    .map(|t| t.trim().trim_start_matches('#').to_lowercase())
+                    .filter(|t| !t.is_empty())
+                    .collect(),
+                _ => {}
+            }
+        }
+    }
+    let body = body.trim_start();
+    let (title, notes) = body.split_once('\n').unwrap_or((body, ""));
+    fields.title = title.trim().trim_start_matches('#').trim().to_owned();
+    if fields.title.is_empty() { return None; }
+    fields.notes = Some(notes.trim().to_owned()).filter(|n| !n.is_empty());
+    Some(fields)
+}
+
+/// `2026-10-20` (midnight, like every due date set in the TUI) or
+/// `2026-10-20 14:00`.
+fn parse_due(s: &str) -> Option<DateTime<Utc>> {
+    NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M").ok()
+        .or_else(|| NaiveDate::parse_from_str(s, "%Y-%m-%d").ok().map(|d| d.and_time(NaiveTime::MIN)))
+        .map(|d| d.and_utc())
+}
@@ -1,11 +1,27 @@
-//! Background sync worker — Tokio task that auto-syncs every 5 min.
+//! Background sync worker — Tokio task that auto-syncs every `[sync]
+//! interval_seconds` (5 min unless set; never with `auto_sync = false`),
+//! and at once when Google pushes a change (see `push.rs`).
 
+use chrono::{DateTime, Utc};
 use std::sync::Arc;
 use tokio::sync::{mpsc, Mutex};
-use tokio::time::Duration;
+use tokio::time::{Duration, Instant, Interval};
 
-use crate::db::Database;
-use crate::sync::google::{gcal_to_local, gtask_to_local, GoogleCalendarClient, GoogleConfig};
+use crate::config::{AppConfig, FeedConfig, SyncConfig};
+use crate::db::{Database, SyncLogEntry, Task};
+use crate::sync::{audit, feeds, push};
+use crate::sync::outlook::OutlookClient;
+use crate::sync::provider::SyncProvider;
+use crate::sync::taskwarrior::TaskwarriorClient;
+use crate::sync::todoist::TodoistClient;
+use crate::sync::vault::Vault;
+use crate::sync::audit::{PULL, PUSH};
+use crate::sync::titles::TitleRules;
+use crate::weather;
+use crate::sync::google::{
+    gcal_attendees, gcal_to_local, gcal_working_location, gtask_to_local, is_cancelled, Access,
+    Availability, GTask, GoogleCalendarClient, GoogleConfig, Grant,
+};
 
 // ─── Channel types ────────────────────────────────────────────────────────────
 
@@ -13,15 +29,36 @@ use crate::sync::google::{gcal_to_local, gtask_to_local, GoogleCalendarClient, G
 pub enum SyncCommand {
     SyncNow,
     PushDirty,
+    /// Begin the browser OAuth flow: emits AuthUrl and listens for the callback.
+    StartAuth,
+    ExchangeCode(String),
+    /// Sign out: revoke at Google and delete the stored tokens.
+    Revoke,
+    /// Look up guests' free/busy times for a slot being scheduled.
+    CheckAvailability { guests: Vec<String>, from: DateTime<Utc>, to: DateTime<Utc> },
+    /// Seconds between automatic syncs from now on; None stops them.
+    SetInterval(Option<u64>),
     Shutdown,
 }
 
 #[derive(Debug, Clone)]
 pub enum SyncEvent {
     SyncStarted,
-    SyncComplete { pulled: usize, pushed: usize },
+    /// `read_only`: the sign-in allows no pushes at all.
+    SyncComplete { pulled: usize, pushed: usize, read_only: bool },
     SyncError(String),
     AuthRequired,
+    AuthUrl(String),
+    AuthComplete,
+    Disconnected,
+    /// ICS subscriptions were re-downloaded.
+    FeedsUpdated(usize),
+    /// Items created, changed or deleted from files in the vault.
+    VaultImported(usize),
+    /// A new forecast is cached.
+    WeatherUpdated,
+    /// Answer to `CheckAvailability`.
+    Availability(Result<Vec<Availability>, String>),
 }
 
 // ─── Worker handle ────────────────────────────────────────────────────────────
@@ -32,42 +69,210 @@ pub struct SyncWorker {
 }
 
 impl SyncWorker {
-    pub fn spawn(db: Database, google_config: Option<GoogleConfig>) -> Self {
+    /// Syncs with every backend `cfg` sets up; `sync` says how often.
+    pub fn spawn(db: Database, cfg: &AppConfig, titles: TitleRules, sync: &SyncConfig) -> Self {
+        let google_config = cfg.google.clone();
+        let feeds         = cfg.feeds.clone();
+        let (outlook, todoist, vault) = (cfg.outlook.clone(), cfg.todoist.clone(), cfg.vault.clone());
+        let taskwarrior   = cfg.taskwarrior.clone();
+        let weather       = cfg.weather.clone();
         let (cmd_tx,   mut cmd_rx)   = mpsc::channel::<SyncCommand>(32);
         let (event_tx,     event_rx) = mpsc::channel::<SyncEvent>(64);
+        // Weak so dropping the SyncWorker handle still ends the loop
+        let self_tx = cmd_tx.downgrade();
+        let push_to = google_config.as_ref()
+            .and_then(|g| g.push_url.clone().map(|url| (url, g.push_port)));
+        let every   = sync.every();
 
         tokio::spawn(async move {
-            let mut interval = tokio::time::interval(Duration::from_secs(300));
-            interval.tick().await; // discard first immediate tick
+            let mut interval = ticker(every);
 
             let client = google_config.map(|cfg| {
                 Arc::new(Mutex::new(GoogleCalendarClient::new(cfg, db.clone())))
             });
+            let mut outlook  = outlook.map(|cfg| OutlookClient::new(cfg, db.clone()));
+            let mut todoist  = todoist.map(TodoistClient::new);
+            let mut taskwarrior = taskwarrior.map(TaskwarriorClient::new);
+            let mut vault_every = vault.as_ref()
+                .map(|v| tokio::time::interval(Duration::from_secs(v.interval_seconds.max(1))));
+            let mut vault = vault.map(|cfg| Vault::new(&cfg, db.clone()));
+            // Checked at its own pace, auto-sync or not; the TTL decides
+            // whether anything is downloaded
+            let mut weather_every = weather.as_ref()
+                .map(|_| tokio::time::interval(Duration::from_secs(WEATHER_CHECK_SECS)));
+            let mut listener: Option<tokio::task::JoinHandle<()>> = None;
+            let http = reqwest::Client::new();
+            refresh_feeds(&http, &db, &feeds, &titles, &event_tx).await;
+
+            // Held here even without push, so the branch below just waits
+            let (push_tx, mut pushed) = mpsc::channel::<()>(1);
+            let push_token   = uuid::Uuid::new_v4().simple().to_string();
+            let mut channels = Vec::new();
+            let mut renew    = tokio::time::interval(Duration::from_secs(10 * 60));
+            if let Some((_, port)) = push_to {
+                let (token, tx) = (push_token.clone(), push_tx.clone());
+                tokio::spawn(async move {
+                    if let Err(e) = push::listen(port, token, tx).await {
+                        tracing::warn!("Push listener on :{port} stopped: {e}");
+                    }
+                });
+            }
 
             loop {
                 tokio::select! {
                     cmd = cmd_rx.recv() => match cmd {
                         Some(SyncCommand::Shutdown) | None => break,
                         Some(SyncCommand::SyncNow) => {
+                            refresh_feeds(&http, &db, &feeds, &titles, &event_tx).await;
                             if let Some(ref c) = client {
-                                run_sync(c.clone(), &db, &event_tx).await;
+                                run_sync(c.clone(), &db, &titles, &event_tx).await;
+                            }
+                            if let Some(ref mut o) = outlook {
+                                run_provider(o, &db, &titles, &event_tx).await;
+                            }
+                            if let Some(ref mut t) = todoist {
+                                run_provider(t, &db, &titles, &event_tx).await;
+                            }
+                            if let Some(ref mut t) = taskwarrior {
+                                run_provider(t, &db, &titles, &event_tx).await;
                             }
                         }
                         Some(SyncCommand::PushDirty) => {
                             if let Some(ref c) = client {
-                                push_dirty_events(c.clone(), &db, &event_tx).await;
+                                push_dirty_events(c.clone(), &db, &titles, &event_tx).await;
                                 push_dirty_tasks(c.clone(), &db, &event_tx).await;
                             }
+                            if let Some(ref mut o) = outlook {
+                                if matches!(o.signed_in().await, Ok(true)) {
+                                    push_provider(o, &db, &event_tx).await;
+                                }
+                            }
+                            if let Some(ref mut t) = todoist {
+                                if matches!(t.signed_in().await, Ok(true)) {
+                                    push_provider(t, &db, &event_tx).await;
+                                }
+                            }
+                            if let Some(ref mut t) = taskwarrior {
+                                push_provider(t, &db, &event_tx).await;
+                            }
+                        }
+                        Some(SyncCommand::StartAuth) => {
+                            let Some(ref c) = client else {
+                                let _ = event_tx.send(SyncEvent::SyncError(
+                                    "No Google client_id configured".into(),
+                                )).await;
+                                continue;
+                            };
+                            // Restarting frees :8085 from the previous attempt
+                            if let Some(h) = listener.take() { h.abort(); }
+                            let url = c.lock().await.build_auth_url();
+                            let _ = event_tx.send(SyncEvent::AuthUrl(url)).await;
+
+                            let (tx, etx) = (self_tx.clone(), event_tx.clone());
+                            listener = Some(tokio::spawn(async move {
+                                match GoogleCalendarClient::listen_for_callback().await {
+                                    Ok(code) => if let Some(tx) = tx.upgrade() {
+                                        let _ = tx.send(SyncCommand::ExchangeCode(code)).await;
+                                    },
+                                    Err(e) => { let _ = etx.send(SyncEvent::SyncError(e.to_string())).await; }
+                                }
+                            }));
+                        }
+                        Some(SyncCommand::ExchangeCode(code)) => {
+                            if let Some(ref c) = client {
+                                let res = c.lock().await.exchange_code(&code).await;
+                                match res {
+                                    Ok(()) => {
+                                        let _ = event_tx.send(SyncEvent::AuthComplete).await;
+                                        run_sync(c.clone(), &db, &titles, &event_tx).await;
+                                    }
+                                    Err(e) => {
+                                        let _ = event_tx.send(SyncEvent::SyncError(e.to_string())).await;
+                                    }
+                                }
+                            }
+                        }
+                        Some(SyncCommand::CheckAvailability { guests, from, to }) => {
+                            let answer = match client {
+                                Some(ref c) => c.lock().await.free_busy(&guests, from, to).await
+                                    .map_err(|e| e.to_string()),
+                                None => Err("Google isn't configured".into()),
+                            };
+                            let _ = event_tx.send(SyncEvent::Availability(answer)).await;
+                        }
+                        Some(SyncCommand::SetInterval(every)) => {
+                            interval = ticker(every);
+                            match every {
+                                Some(secs) => tracing::info!("Auto-sync every {secs}s"),
+                                None       => tracing::info!("Auto-sync off"),
+                            }
+                        }
+                        Some(SyncCommand::Revoke) => {
+                            if let Some(h) = listener.take() { h.abort(); }
+                            if let Some(ref c) = client {
+                                push::stop_all(c, &mut channels).await;
+                                let res = c.lock().await.revoke().await;
+                                let _ = event_tx.send(match res {
+                                    Ok(())  => SyncEvent::Disconnected,
+                                    Err(e)  => SyncEvent::SyncError(e.to_string()),
+                                }).await;
+                            }
                         }
                     },
-                    _ = interval.tick() => {
+                    _ = tick(&mut interval) => {
+                        refresh_feeds(&http, &db, &feeds, &titles, &event_tx).await;
+                        if let Some(ref c) = client {
+                            run_sync(c.clone(), &db, &titles, &event_tx).await;
+                        }
+                        if let Some(ref mut o) = outlook {
+                            run_provider(o, &db, &titles, &event_tx).await;
+                        }
+                        if let Some(ref mut t) = todoist {
+                            run_provider(t, &db, &titles, &event_tx).await;
+                        }
+                        if let Some(ref mut t) = taskwarrior {
+                            run_provider(t, &db, &titles, &event_tx).await;
+                        }
+                    }
+                    _ = tick(&mut weather_every) => {
+                        let Some(ref w) = weather else { continue };
+                        match weather::refresh(&http, &db, w).await {
+                            Ok(true)  => { let _ = event_tx.send(SyncEvent::WeatherUpdated).await; }
+                            Ok(false) => {}
+                            Err(e)    => tracing::warn!("weather: {e}"),
+                        }
+                    }
+                    _ = tick(&mut vault_every) => {
+                        let Some(ref mut v) = vault else { continue };
+                        match v.sweep().await {
+                            Ok(0)  => {}
+                            Ok(n)  => {
+                                let _ = event_tx.send(SyncEvent::VaultImported(n)).await;
+                                // Sent on like edits made in the TUI
+                                if let Some(tx) = self_tx.upgrade() { let _ = tx.try_send(SyncCommand::PushDirty); }
+                            }
+                            Err(e) => tracing::warn!("vault: {e}"),
+                        }
+                    }
+                    Some(()) = pushed.recv() => {
+                        // A burst of edits on the phone is one sync
+                        tokio::time::sleep(Duration::from_secs(2)).await;
+                        while pushed.try_recv().is_ok() {}
                         if let Some(ref c) = client {
-                            run_sync(c.clone(), &db, &event_tx).await;
+                            run_sync(c.clone(), &db, &titles, &event_tx).await;
+                        }
+                    }
+                    _ = renew.tick(), if push_to.is_some() => {
+                        let (Some(c), Some((url, _))) = (&client, &push_to) else { continue };
+                        if matches!(db.get_token("google").await, Ok(Some(_))) {
+                            push::renew(c, url, &push_token, &mut channels).await;
                         }
                     }
                 }
             }
 
+            if let Some(ref c) = client { push::stop_all(c, &mut channels).await; }
+            if let Some(h) = listener { h.abort(); }
             tracing::info!("Sync worker stopped");
         });
 
@@ -76,32 +281,104 @@ impl SyncWorker {
 
     pub async fn sync_now(&self)   { let _ = self.cmd_tx.send(SyncCommand::SyncNow).await; }
     pub async fn push_dirty(&self) { let _ = self.cmd_tx.send(SyncCommand::PushDirty).await; }
+    pub async fn start_auth(&self) { let _ = self.cmd_tx.send(SyncCommand::StartAuth).await; }
+    pub async fn revoke(&self)     { let _ = self.cmd_tx.send(SyncCommand::Revoke).await; }
+    pub async fn exchange_code(&self, code: String) {
+        let _ = self.cmd_tx.send(SyncCommand::ExchangeCode(code)).await;
+    }
+    pub async fn check_availability(&self, guests: Vec<String>, from: DateTime<Utc>, to: DateTime<Utc>) {
+        let _ = self.cmd_tx.send(SyncCommand::CheckAvailability { guests, from, to }).await;
+    }
+    pub async fn set_interval(&self, every: Option<u64>) {
+        let _ = self.cmd_tx.send(SyncCommand::SetInterval(every)).await;
+    }
     pub async fn shutdown(&self)   { let _ = self.cmd_tx.send(SyncCommand::Shutdown).await; }
 }
 
+// ─── Auto-sync timer ──────────────────────────────────────────────────────────
+
+/// How often the weather cache's age is looked at.
+const WEATHER_CHECK_SECS: u64 = 10 * 60;
+
+/// Fires `every` seconds, the first time one period from now (the worker
+/// syncs at startup anyway); None for no timer.
+fn ticker(every: Option<u64>) -> Option<Interval> {
+    every.map(|secs| {
+        let period = Duration::from_secs(secs.max(30));
+        tokio::time::interval_at(Instant::now() + period, period)
+    })
+}
+
+/// The next tick, or never without a timer.
+async fn tick(interval: &mut Option<Interval>) {
+    match interval {
+        Some(i) => { i.tick().await; }
+        None    => std::future::pending().await,
+    }
+}
+
+// ─── ICS feeds ────────────────────────────────────────────────────────────────
+
+async fn refresh_feeds(
+    http:   &reqwest::Client,
+    db:     &Database,
+    feeds:  &[FeedConfig],
+    titles: &TitleRules,
+    tx:     &mpsc::Sender<SyncEvent>,
+) {
+    if feeds.is_empty() {
+        // Clear any cache left from feeds since removed from config
+        let _ = db.prune_feeds(&[]).await;
+        return;
+    }
+    let _ = tx.send(match feeds::refresh_all(http, db, feeds, titles).await {
+        Ok(n)  => SyncEvent::FeedsUpdated(n),
+        Err(e) => SyncEvent::SyncError(e.to_string()),
+    }).await;
+}
+
 // ─── Full sync ────────────────────────────────────────────────────────────────
 
 async fn run_sync(
     client: Arc<Mutex<GoogleCalendarClient>>,
     db:     &Database,
+    titles: &TitleRules,
     tx:     &mpsc::Sender<SyncEvent>,
 ) {
+    if matches!(db.get_token("google").await, Ok(None)) {
+        let _ = tx.send(SyncEvent::AuthRequired).await;
+        return;
+    }
+
     let _ = tx.send(SyncEvent::SyncStarted).await;
     tracing::info!("Full sync started");
 
     let mut pulled = 0usize;
+    let grant      = grant(&client).await;
 
     // ── Pull calendar events ──────────────────────────────────────────────────
     let cal_ids = {
         let c = client.lock().await;
-        c.config.calendar_ids.clone()
+        if grant.calendar == Access::None {
+            tracing::warn!("Calendar access wasn't granted; not pulling events");
+            Vec::new()
+        } else {
+            c.config.calendar_ids.iter().filter(|id| c.config.direction(id).pulls()).cloned().collect()
+        }
     };
 
     for cal_id in &cal_ids {
         let events = {
             let mut c = client.lock().await;
             match c.pull_events(cal_id).await {
-                Ok(evs) => evs,
+                Ok((evs, name)) => {
+                    if let Some(name) = name {
+                        if let Err(e) = db.set_calendar_name(cal_id, &name).await {
+                            tracing::warn!("set_calendar_name({cal_id}): {e}");
+                        }
+                    }
+                    evs
+                }
                 Err(e)  => {
                     tracing::warn!("pull_events({cal_id}): {e}");
                     let _ = tx.send(SyncEvent::SyncError(e.to_string())).await;
@@ -110,18 +387,79 @@ async fn run_sync(
             }
         };
 
+        let mut rejected = Vec::new();
         for ge in &events {
-            if let Some(local) = gcal_to_local(ge, cal_id) {
+            // Working locations are badges, not events on the list
+            if ge.event_type.as_deref() == Some("workingLocation") {
+                let stored = match (gcal_working_location(ge, cal_id), &ge.id) {
+                    (Some(wl), _) if !is_cancelled(ge) => db.upsert_working_location(&wl).await,
+                    (_, Some(id)) => db.delete_working_location(id).await,
+                    _             => Ok(()),
+                };
+                if let Err(e) = stored { tracing::warn!("working location from {cal_id}: {e}"); }
+                continue;
+            }
+            if is_cancelled(ge) {
+                let Some(sid) = &ge.id else { continue };
+                let title = ge.summary.as_deref().unwrap_or(sid);
+                match db.purge_remote_event(sid).await {
+                    Ok(0)  => {}
+                    Ok(_)  => {
+                        tracing::info!("\"{title}\" was cancelled on {cal_id}; removed here");
+                        audit::record(db, PULL, "event", "delete", title, "ok").await;
+                    }
+                    Err(e) => tracing::warn!("purge_remote_event({sid}): {e}"),
+                }
+                continue;
+            }
+            match gcal_to_local(ge, cal_id) {
                 // upsert_remote_event deduplicates by sync_id and honours local dirty flag
-                if db.upsert_remote_event(&local).await.is_ok() { pulled += 1; }
+                Ok(mut local) => {
+                    titles.normalize(&mut local);
+                    let before = match local.sync_id {
+                        Some(ref sid) => db.event_by_sync_id(sid).await.ok().flatten(),
+                        None          => None,
+                    };
+                    let change = audit::pulled_event(before.as_ref(), &local);
+                    if let Err(e) = db.upsert_remote_event(&local).await {
+                        audit::record(db, PULL, "event", change.unwrap_or("update"), &local.title, &e.to_string()).await;
+                        continue;
+                    }
+                    if let Some(action) = change {
+                        audit::record(db, PULL, "event", action, &local.title, "ok").await;
+                    }
+                    pulled += 1;
+                    let stored = match ge.id {
+                        Some(ref sid) => db.event_id_for_sync_id(sid).await,
+                        None          => Ok(None),
+                    };
+                    if let Ok(Some(id)) = stored {
+                        if let Err(e) = db.set_remote_attendees(&id, &gcal_attendees(ge)).await {
+                            tracing::warn!("set_remote_attendees({id}): {e}");
+                        }
+                    }
+                }
+                Err(q) => {
+                    tracing::warn!("quarantined \"{}\" from {cal_id}: {}", q.title, q.reason);
+                    audit::record(db, PULL, "event", "quarantine", &q.title, &q.reason).await;
+                    rejected.push(*q);
+                }
             }
         }
+        if let Err(e) = db.replace_quarantine(&format!("google:{cal_id}"), &rejected).await {
+            tracing::error!("replace_quarantine({cal_id}): {e}");
+        }
     }
 
     // ── Pull Google Tasks ─────────────────────────────────────────────────────
     let task_list_ids = {
         let c = client.lock().await;
-        c.config.task_list_ids.clone()
+        if grant.tasks == Access::None {
+            tracing::warn!("Tasks access wasn't granted; not pulling tasks");
+            Vec::new()
+        } else {
+            c.config.task_list_ids.iter().filter(|id| c.config.direction(id).pulls()).cloned().collect()
+        }
     };
 
     for tl_id in &task_list_ids {
@@ -139,60 +477,153 @@ async fn run_sync(
 
         for gt in &tasks {
             if let Some(local) = gtask_to_local(gt, tl_id) {
-                if db.upsert_remote_task(&local).await.is_ok() { pulled += 1; }
+                let before = match local.sync_id {
+                    Some(ref sid) => db.task_by_sync_id(sid).await.ok().flatten(),
+                    None          => None,
+                };
+                let change = audit::pulled_task(before.as_ref(), &local);
+                match db.upsert_remote_task(&local).await {
+                    Ok(()) => {
+                        if let Some(action) = change {
+                            audit::record(db, PULL, "task", action, &local.title, "ok").await;
+                        }
+                        pulled += 1;
+                    }
+                    Err(e) => audit::record(db, PULL, "task", change.unwrap_or("update"), &local.title, &e.to_string()).await,
+                }
             }
         }
+
+        let mut listed: Vec<&GTask> = tasks.iter().filter(|t| t.deleted != Some(true)).collect();
+        listed.sort_by(|a, b| a.position.cmp(&b.position));
+        let order: Vec<String> = listed.iter().filter_map(|t| t.id.clone()).collect();
+        if let Err(e) = db.apply_remote_order(tl_id, &order).await {
+            tracing::warn!("apply_remote_order({tl_id}): {e}");
+        }
+    }
+
+    // ── Merge rows the pull doubled up ────────────────────────────────────────
+    match db.dedupe().await {
+        Ok(merges) if !merges.is_empty() => tracing::info!("Merged {} duplicate events after pulling", merges.len()),
+        Ok(_)  => {}
+        Err(e) => tracing::warn!("dedupe: {e}"),
     }
 
     // ── Push dirty local changes ──────────────────────────────────────────────
-    let pushed_ev = push_dirty_events(client.clone(), db, tx).await;
+    let pushed_ev = push_dirty_events(client.clone(), db, titles, tx).await;
     let pushed_tk = push_dirty_tasks(client, db, tx).await;
 
     let pushed = pushed_ev + pushed_tk;
-    let _ = tx.send(SyncEvent::SyncComplete { pulled, pushed }).await;
+    let _ = tx.send(SyncEvent::SyncComplete { pulled, pushed, read_only: grant.read_only() }).await;
     tracing::info!("Sync done: pulled={pulled} pushed={pushed}");
 }
 
+/// What the sign-in allows; nothing when that can't be read.
+async fn grant(client: &Arc<Mutex<GoogleCalendarClient>>) -> Grant {
+    client.lock().await.grant().await.unwrap_or_else(|e| {
+        tracing::warn!("reading granted scopes: {e}");
+        Grant::NONE
+    })
+}
+
 // ─── Push dirty calendar events ───────────────────────────────────────────────
 
 async fn push_dirty_events(
     client: Arc<Mutex<GoogleCalendarClient>>,
     db:     &Database,
+    titles: &TitleRules,
     tx:     &mpsc::Sender<SyncEvent>,
 ) -> usize {
+    // Read-only: local changes stay here, still marked for pushing
+    if grant(&client).await.calendar != Access::Write { return 0; }
     let dirty = match db.dirty_events().await {
         Ok(v)  => v,
         Err(e) => { tracing::error!("dirty_events: {e}"); return 0; }
     };
+    let config = client.lock().await.config.clone();
 
     let mut pushed = 0usize;
 
     for ev in &dirty {
         let cal_id = ev.calendar_id.as_deref().unwrap_or("primary");
+        if !config.direction(cal_id).pushes() {
+            let undone = match ev.sync_id {
+                Some(_) => db.undo_event_delete(&ev.id).await,
+                None    => db.mark_event_clean(&ev.id, None, None).await,
+            };
+            hold_back(db, tx, "calendar", &ev.title, ev.sync_id.is_some(), undone).await;
+            continue;
+        }
+        let guests = db.attendees(&ev.id).await.unwrap_or_default();
         let mut c  = client.lock().await;
 
         let result = if ev.deleted {
-            if let Some(sid) = &ev.sync_id {
-                c.delete_event(cal_id, sid).await.map(|_| (None, None))
-            } else { Ok((None, None)) }
+            match &ev.sync_id {
+                None      => Ok((None, None)),
+                // If-Match: a delete never throws away changes made at Google
+                // since the version we last pulled
+                Some(sid) => match c.delete_event(cal_id, sid, ev.etag.as_deref()).await {
+                    Ok(true)  => Ok((None, None)),
+                    Ok(false) => {
+                        tracing::warn!("not deleting {}: changed on Google since it was last pulled", ev.id);
+                        audit::record(db, PUSH, "event", "delete", &ev.title, "changed on Google since; kept").await;
+                        if let Err(e) = db.undo_event_delete(&ev.id).await {
+                            tracing::error!("undo_event_delete({}): {e}", ev.id);
+                        }
+                        let _ = tx.send(SyncEvent::SyncError(format!(
+                            "“{}” changed on Google since you deleted it — kept, with those changes after the next sync",
+                            ev.title,
+                        ))).await;
+                        continue;
+                    }
+                    Err(e) => Err(e),
+                },
+            }
         } else if let Some(sid) = &ev.sync_id {
-            c.update_event(cal_id, sid, ev).await.map(|etag| (None, Some(etag)))
+            c.update_event(cal_id, sid, &titles.for_push(ev), &guests).await.map(|etag| (None, Some(etag)))
         } else {
-            c.push_event(cal_id, ev).await.map(|(id, etag)| (Some(id), Some(etag)))
+            c.push_event(cal_id, ev, &guests).await.map(|(id, etag)| (Some(id), Some(etag)))
         };
 
+        // A local event deleted before it was ever pushed: nothing to tell Google
+        let action = (ev.sync_id.is_some() || !ev.deleted).then(|| audit::pushed(ev.deleted, ev.sync_id.is_some()));
         match result {
             Ok((sid, etag)) => {
+                if let Some(action) = action { audit::record(db, PUSH, "event", action, &ev.title, "ok").await; }
                 if db.mark_event_clean(&ev.id, sid.as_deref(), etag.as_deref()).await.is_ok() {
                     pushed += 1;
                 }
             }
             Err(e) => {
+                audit::record(db, PUSH, "event", action.unwrap_or("delete"), &ev.title, &e.to_string()).await;
                 tracing::warn!("push event failed for {}: {e}", ev.id);
                 let _ = tx.send(SyncEvent::SyncError(e.to_string())).await;
             }
         }
     }
+
+    // Answers to invitations go on their own, as a patch of the guest list
+    let answered = db.unsent_responses().await.unwrap_or_else(|e| {
+        tracing::error!("unsent_responses: {e}");
+        Vec::new()
+    });
+    for ev in &answered {
+        let (cal_id, Some(sid)) = (ev.calendar_id.as_deref().unwrap_or("primary"), &ev.sync_id) else { continue };
+        if !config.direction(cal_id).pushes() { continue; }
+        let guests = db.attendees(&ev.id).await.unwrap_or_default();
+        let result = client.lock().await.respond(cal_id, sid, &guests).await;
+        match result {
+            Ok(()) => {
+                audit::record(db, PUSH, "event", "respond", &ev.title, "ok").await;
+                if db.mark_response_sent(&ev.id).await.is_ok() { pushed += 1; }
+            }
+            Err(e) => {
+                audit::record(db, PUSH, "event", "respond", &ev.title, &e.to_string()).await;
+                tracing::warn!("sending answer failed for {}: {e}", ev.id);
+                let _ = tx.send(SyncEvent::SyncError(e.to_string())).await;
+            }
+        }
+    }
     pushed
 }
 
@@ -203,15 +634,27 @@ async fn push_dirty_tasks(
     db:     &Database,
     tx:     &mpsc::Sender<SyncEvent>,
 ) -> usize {
+    if grant(&client).await.tasks != Access::Write { return 0; }
     let dirty = match db.dirty_tasks().await {
         Ok(v)  => v,
         Err(e) => { tracing::error!("dirty_tasks: {e}"); return 0; }
     };
+    let config = client.lock().await.config.clone();
 
     let mut pushed = 0usize;
 
     for task in &dirty {
-        let tl_id = task.task_list_id.as_deref().unwrap_or("@default");
+        let tl_id    = task.task_list_id.as_deref().unwrap_or("@default");
+        if !config.direction(tl_id).pushes() {
+            let undone = match task.sync_id {
+                Some(_) => db.undo_task_edit(&task.id).await,
+                None    => db.mark_task_clean(&task.id, None).await,
+            };
+            hold_back(db, tx, "task list", &task.title, task.sync_id.is_some(), undone).await;
+            continue;
+        }
+        let previous = db.synced_task_before(task).await.unwrap_or_default();
+        let moved    = db.task_moved(&task.id).await.unwrap_or(false);
         let mut c = client.lock().await;
 
         let result = if task.deleted {
@@ -219,18 +662,24 @@ async fn push_dirty_tasks(
                 c.delete_task(tl_id, sid).await.map(|_| None)
             } else { Ok(None) }
         } else if let Some(sid) = &task.sync_id {
-            c.update_task(tl_id, sid, task).await.map(|_| None)
+            match c.update_task(tl_id, sid, task).await {
+                Ok(_) if moved => c.move_task(tl_id, sid, previous.as_deref()).await.map(|_| None),
+                r              => r.map(|_| None),
+            }
         } else {
-            c.push_task(tl_id, task).await.map(|(id, _)| Some(id))
+            c.push_task(tl_id, task, previous.as_deref()).await.map(|(id, _)| Some(id))
         };
 
+        let action = (task.sync_id.is_some() || !task.deleted).then(|| audit::pushed(task.deleted, task.sync_id.is_some()));
         match result {
             Ok(sid) => {
+                if let Some(action) = action { audit::record(db, PUSH, "task", action, &task.title, "ok").await; }
                 if db.mark_task_clean(&task.id, sid.as_deref()).await.is_ok() {
                     pushed += 1;
                 }
             }
             Err(e) => {
+                audit::record(db, PUSH, "task", action.unwrap_or("delete"), &task.title, &e.to_string()).await;
                 tracing::warn!("push task failed for {}: {e}", task.id);
                 let _ = tx.send(SyncEvent::SyncError(e.to_string())).await;
             }
@@ -238,3 +687,349 @@ async fn push_dirty_tasks(
     }
     pushed
 }
+
+// ─── Other providers ──────────────────────────────────────────────────────────
+
+/// Pull then push for a backend behind `SyncProvider`. Skipped without a
+/// sign-in: Google's `AuthRequired` prompt is the only one the TUI shows.
+async fn run_provider<P: SyncProvider>(
+    provider: &mut P,
+    db:       &Database,
+    titles:   &TitleRules,
+    tx:       &mpsc::Sender<SyncEvent>,
+) {
+    match provider.signed_in().await {
+        Ok(true)  => {}
+        Ok(false) => return,
+        Err(e)    => { tracing::warn!("{} token: {e}", provider.name()); return; }
+    }
+    let _ = tx.send(SyncEvent::SyncStarted).await;
+    tracing::info!("{} sync started", provider.name());
+    let mut pulled = 0usize;
+
+    for cal_id in provider.calendar_ids() {
+        let pull = match provider.pull_events(&cal_id).await {
+            Ok(p)  => p,
+            Err(e) => {
+                tracing::warn!("pull_events({cal_id}): {e}");
+                let _ = tx.send(SyncEvent::SyncError(e.to_string())).await;
+                continue;
+            }
+        };
+        if let Some(ref name) = pull.name {
+            if let Err(e) = db.set_calendar_name(&cal_id, name).await {
+                tracing::warn!("set_calendar_name({cal_id}): {e}");
+            }
+        }
+        for mut local in pull.events.iter().cloned() {
+            titles.normalize(&mut local);
+            let before = match local.sync_id {
+                Some(ref sid) => db.event_by_sync_id(sid).await.ok().flatten(),
+                None          => None,
+            };
+            let change = audit::pulled_event(before.as_ref(), &local);
+            match db.upsert_remote_event(&local).await {
+                Ok(()) => {
+                    if let Some(action) = change { audit::record(db, PULL, "event", action, &local.title, "ok").await; }
+                    pulled += 1;
+                }
+                Err(e) => audit::record(db, PULL, "event", change.unwrap_or("update"), &local.title, &e.to_string()).await,
+            }
+        }
+        // Gone from the window: deleted on the remote
+        let listed: Vec<&str> = pull.events.iter().filter_map(|e| e.sync_id.as_deref()).collect();
+        let stored = db.synced_events_in(&cal_id, pull.from, pull.to).await.unwrap_or_else(|e| {
+            tracing::warn!("synced_events_in({cal_id}): {e}");
+            Vec::new()
+        });
+        for ev in stored {
+            let Some(sid) = ev.sync_id.as_deref().filter(|sid| !listed.contains(sid)) else { continue };
+            match db.purge_remote_event(sid).await {
+                Ok(0)  => {}
+                Ok(_)  => audit::record(db, PULL, "event", "delete", &ev.title, "ok").await,
+                Err(e) => tracing::warn!("purge_remote_event({sid}): {e}"),
+            }
+        }
+    }
+
+    for tl_id in provider.task_list_ids() {
+        let tasks = match provider.pull_tasks(&tl_id).await {
+            Ok(ts) => ts,
+            Err(e) => {
+                tracing::warn!("pull_tasks({tl_id}): {e}");
+                let _ = tx.send(SyncEvent::SyncError(e.to_string())).await;
+                continue;
+            }
+        };
+        match provider.list_name(&tl_id).await {
+            Ok(Some(name)) => if let Err(e) = db.set_calendar_name(&tl_id, &name).await {
+                tracing::warn!("set_calendar_name({tl_id}): {e}");
+            },
+            Ok(None)       => {}
+            Err(e)         => tracing::warn!("list_name({tl_id}): {e}"),
+        }
+        let listed: Vec<&str> = tasks.iter().filter_map(|t| t.sync_id.as_deref()).collect();
+        let gone: Vec<Task> = db.synced_tasks_in(&tl_id).await.unwrap_or_default().into_iter()
+            .filter(|t| t.sync_id.as_deref().is_some_and(|sid| !listed.contains(&sid)))
+            .filter_map(|t| {
+                let was  = (t.deleted, t.completed);
+                let gone = provider.gone_task(t);
+                ((gone.deleted, gone.completed) != was).then_some(gone)
+            })
+            .collect();
+        for local in tasks.iter().chain(&gone) {
+            let before = match local.sync_id {
+                Some(ref sid) => db.task_by_sync_id(sid).await.ok().flatten(),
+                None          => None,
+            };
+            let change = audit::pulled_task(before.as_ref(), local);
+            match db.upsert_remote_task(local).await {
+                Ok(()) => {
+                    if let Some(action) = change { audit::record(db, PULL, "task", action, &local.title, "ok").await; }
+                    pulled += 1;
+                }
+                Err(e) => audit::record(db, PULL, "task", change.unwrap_or("update"), &local.title, &e.to_string()).await,
+            }
+        }
+    }
+
+    let pushed = push_provider(provider, db, tx).await;
+    let _ = tx.send(SyncEvent::SyncComplete { pulled, pushed, read_only: false }).await;
+    tracing::info!("{} sync done: pulled={pulled} pushed={pushed}", provider.name());
+}
+
+/// Sends the changed events and tasks filed under the provider's prefix.
+async fn push_provider<P: SyncProvider>(provider: &mut P, db: &Database, tx: &mpsc::Sender<SyncEvent>) -> usize {
+    let mut pushed = 0usize;
+
+    let events = db.dirty_events_under(provider.prefix()).await.unwrap_or_else(|e| {
+        tracing::error!("dirty_events_under: {e}");
+        Vec::new()
+    });
+    for ev in &events {
+        let result = match (ev.deleted, &ev.sync_id) {
+            (true, None)     => Ok((None, None)),
+            (true, Some(_))  => provider.delete_event(ev).await.map(|_| (None, None)),
+            (false, Some(_)) => provider.update_event(ev).await.map(|etag| (None, etag)),
+            (false, None)    => provider.create_event(ev).await.map(|(id, etag)| (Some(id), etag)),
+        };
+        let action = (ev.sync_id.is_some() || !ev.deleted).then(|| audit::pushed(ev.deleted, ev.sync_id.is_some()));
+        match result {
+            Ok((sid, etag)) => {
+                if let Some(action) = action { audit::record(db, PUSH, "event", action, &ev.title, "ok").await; }
+                if db.mark_event_clean(&ev.id, sid.as_deref(), etag.as_deref()).await.is_ok() { pushed += 1; }
+            }
+            Err(e) => {
+                audit::record(db, PUSH, "event", action.unwrap_or("delete"), &ev.title, &e.to_string()).await;
+                tracing::warn!("{} push event failed for {}: {e}", provider.name(), ev.id);
+                let _ = tx.send(SyncEvent::SyncError(e.to_string())).await;
+            }
+        }
+    }
+
+    let tasks = db.dirty_tasks_under(provider.prefix()).await.unwrap_or_else(|e| {
+        tracing::error!("dirty_tasks_under: {e}");
+        Vec::new()
+    });
+    for task in &tasks {
+        let result = match (task.deleted, &task.sync_id) {
+            (true, None)     => Ok(None),
+            (true, Some(_))  => provider.delete_task(task).await.map(|_| None),
+            (false, Some(_)) => provider.update_task(task).await.map(|_| None),
+            (false, None)    => provider.create_task(task).await.map(Some),
+        };
+        let action = (task.sync_id.is_some() || !task.deleted).then(|| audit::pushed(task.deleted, task.sync_id.is_some()));
+        match result {
+            Ok(sid) => {
+                if let Some(action) = action { audit::record(db, PUSH, "task", action, &task.title, "ok").await; }
+                if db.mark_task_clean(&task.id, sid.as_deref()).await.is_ok() { pushed += 1; }
+            }
+            Err(e) => {
+                audit::record(db, PUSH, "task", action.unwrap_or("delete"), &task.title, &e.to_string()).await;
+                tracing::warn!("{} push task failed for {}: {e}", provider.name(), task.id);
+                let _ = tx.send(SyncEvent::SyncError(e.to_string())).await;
+            }
+        }
+    }
+    pushed
+}
+
+// ─── Dry run ──────────────────────────────────────────────────────────────────
+
+/// One backend's part of a dry run: its name, and the plan, or None without
+/// a sign-in (a sync skips it).
+pub type Plan = (&'static str, Option<Vec<SyncLogEntry>>);
+
+/// What a sync would do now with every backend `cfg` sets up, worked out
+/// without doing it: remotes are only read and the database only queried
+/// (bar a token refresh). Every entry's result is `would`, or why it
+/// wouldn't happen. `cfg.google` is expected resolved already.
+pub async fn dry_run(cfg: &AppConfig, db: &Database, titles: &TitleRules) -> anyhow::Result<Vec<Plan>> {
+    let mut plans = Vec::new();
+    if let Some(ref google) = cfg.google {
+        let steps = match db.get_token("google").await? {
+            Some(_) => Some(dry_run_google(google.clone(), db, titles).await?),
+            None    => None,
+        };
+        plans.push(("google", steps));
+    }
+    if let Some(ref o) = cfg.outlook {
+        plans.push(dry_run_provider(&mut OutlookClient::new(o.clone(), db.clone()), db, titles).await?);
+    }
+    if let Some(ref t) = cfg.todoist {
+        plans.push(dry_run_provider(&mut TodoistClient::new(t.clone()), db, titles).await?);
+    }
+    if let Some(ref t) = cfg.taskwarrior {
+        plans.push(dry_run_provider(&mut TaskwarriorClient::new(t.clone()), db, titles).await?);
+    }
+    Ok(plans)
+}
+
+/// `run_provider` without the writes.
+async fn dry_run_provider<P: SyncProvider>(provider: &mut P, db: &Database, titles: &TitleRules) -> anyhow::Result<Plan> {
+    if !provider.signed_in().await? { return Ok((provider.name(), None)); }
+    let mut plan = Vec::new();
+    let would    = |direction, entity, action: &str, title: &str| audit::entry(direction, entity, action, title, "would");
+
+    for cal_id in provider.calendar_ids() {
+        let pull = provider.pull_events(&cal_id).await?;
+        for mut local in pull.events.iter().cloned() {
+            titles.normalize(&mut local);
+            let before = match local.sync_id {
+                Some(ref sid) => db.event_by_sync_id(sid).await?,
+                None          => None,
+            };
+            if let Some(action) = audit::pulled_event(before.as_ref(), &local) {
+                plan.push(would(PULL, "event", action, &local.title));
+            }
+        }
+        let listed: Vec<&str> = pull.events.iter().filter_map(|e| e.sync_id.as_deref()).collect();
+        for ev in db.synced_events_in(&cal_id, pull.from, pull.to).await? {
+            if ev.sync_id.as_deref().is_some_and(|sid| !listed.contains(&sid)) {
+                plan.push(would(PULL, "event", "delete", &ev.title));
+            }
+        }
+    }
+    for tl_id in provider.task_list_ids() {
+        let tasks  = provider.pull_tasks(&tl_id).await?;
+        let listed: Vec<&str> = tasks.iter().filter_map(|t| t.sync_id.as_deref()).collect();
+        let gone: Vec<Task> = db.synced_tasks_in(&tl_id).await?.into_iter()
+            .filter(|t| t.sync_id.as_deref().is_some_and(|sid| !listed.contains(&sid)))
+            .map(|t| provider.gone_task(t))
+            .collect();
+        for local in tasks.iter().chain(&gone) {
+            let before = match local.sync_id {
+                Some(ref sid) => db.task_by_sync_id(sid).await?,
+                None          => None,
+            };
+            if let Some(action) = audit::pulled_task(before.as_ref(), local) {
+                plan.push(would(PULL, "task", action, &local.title));
+            }
+        }
+    }
+
+    for ev in db.dirty_events_under(provider.prefix()).await?.iter().filter(|e| e.sync_id.is_some() || !e.deleted) {
+        plan.push(would(PUSH, "event", audit::pushed(ev.deleted, ev.sync_id.is_some()), &ev.title));
+    }
+    for t in db.dirty_tasks_under(provider.prefix()).await?.iter().filter(|t| t.sync_id.is_some() || !t.deleted) {
+        plan.push(would(PUSH, "task", audit::pushed(t.deleted, t.sync_id.is_some()), &t.title));
+    }
+    Ok((provider.name(), Some(plan)))
+}
+
+/// Google's part of `dry_run`: its own pull, with scopes and pull-only
+/// calendars holding pushes back.
+async fn dry_run_google(config: GoogleConfig, db: &Database, titles: &TitleRules) -> anyhow::Result<Vec<SyncLogEntry>> {
+    let mut c     = GoogleCalendarClient::new(config.clone(), db.clone());
+    let grant     = c.grant().await?;
+    let mut plan  = Vec::new();
+    let would     = |direction, entity, action: &str, title: &str| audit::entry(direction, entity, action, title, "would");
+
+    if grant.calendar != Access::None {
+        for cal_id in config.calendar_ids.iter().filter(|id| config.direction(id).pulls()) {
+            let (events, _) = c.pull_events(cal_id).await?;
+            for ge in events.iter().filter(|ge| ge.event_type.as_deref() != Some("workingLocation")) {
+                if is_cancelled(ge) {
+                    let Some(sid) = &ge.id else { continue };
+                    if let Some(old) = db.event_by_sync_id(sid).await?.filter(|e| !e.deleted) {
+                        plan.push(would(PULL, "event", "delete", &old.title));
+                    }
+                    continue;
+                }
+                match gcal_to_local(ge, cal_id) {
+                    Ok(mut local) => {
+                        titles.normalize(&mut local);
+                        let before = match local.sync_id {
+                            Some(ref sid) => db.event_by_sync_id(sid).await?,
+                            None          => None,
+                        };
+                        if let Some(action) = audit::pulled_event(before.as_ref(), &local) {
+                            plan.push(would(PULL, "event", action, &local.title));
+                        }
+                    }
+                    Err(q) => plan.push(audit::entry(PULL, "event", "quarantine", &q.title, &q.reason)),
+                }
+            }
+        }
+    }
+    if grant.tasks != Access::None {
+        for tl_id in config.task_list_ids.iter().filter(|id| config.direction(id).pulls()) {
+            for gt in c.pull_tasks(tl_id).await? {
+                let Some(local) = gtask_to_local(&gt, tl_id) else { continue };
+                let before = match local.sync_id {
+                    Some(ref sid) => db.task_by_sync_id(sid).await?,
+                    None          => None,
+                };
+                if let Some(action) = audit::pulled_task(before.as_ref(), &local) {
+                    plan.push(would(PULL, "task", action, &local.title));
+                }
+            }
+        }
+    }
+
+    // Pushes: what's dirty, unless it wouldn't be sent
+    let held = |what: &str, id: &str, access: Access| {
+        if !config.direction(id).pushes() { Some(format!("held back: pull-only {what}")) }
+        else if access != Access::Write  { Some("not sent: read-only sign-in".to_owned()) }
+        else                             { None }
+    };
+    for ev in db.dirty_events().await?.iter().filter(|e| e.sync_id.is_some() || !e.deleted) {
+        let action = audit::pushed(ev.deleted, ev.sync_id.is_some());
+        let cal_id = ev.calendar_id.as_deref().unwrap_or("primary");
+        let result = held("calendar", cal_id, grant.calendar).unwrap_or_else(|| "would".into());
+        plan.push(audit::entry(PUSH, "event", action, &ev.title, &result));
+    }
+    for t in db.dirty_tasks().await?.iter().filter(|t| t.sync_id.is_some() || !t.deleted) {
+        let action = audit::pushed(t.deleted, t.sync_id.is_some());
+        let tl_id  = t.task_list_id.as_deref().unwrap_or("@default");
+        let result = held("task list", tl_id, grant.tasks).unwrap_or_else(|| "would".into());
+        plan.push(audit::entry(PUSH, "task", action, &t.title, &result));
+    }
+    for ev in db.unsent_responses().await? {
+        let cal_id = ev.calendar_id.as_deref().unwrap_or("primary");
+        let result = held("calendar", cal_id, grant.calendar).unwrap_or_else(|| "would".into());
+        plan.push(audit::entry(PUSH, "event", "respond", &ev.title, &result));
+    }
+    Ok(plan)
+}
+
+// ─── Pull-only calendars ──────────────────────────────────────────────────────
+
+/// Tells why a change to a pull-only calendar or task list wasn't pushed:
+/// Google's copy comes back at the next pull, and new items stay here.
+async fn hold_back(
+    db: &Database, tx: &mpsc::Sender<SyncEvent>, what: &str, title: &str, linked: bool, undone: anyhow::Result<()>,
+) {
+    if let Err(e) = undone {
+        tracing::error!("taking back the change to \"{title}\": {e}");
+        return;
+    }
+    tracing::info!("not pushing \"{title}\": its {what} is pull-only");
+    let entity = if what == "calendar" { "event" } else { "task" };
+    audit::record(db, PUSH, entity, "hold back", title, &format!("pull-only {what}")).await;
+    let _ = tx.send(SyncEvent::SyncError(if linked {
+        format!("“{title}” is on a pull-only {what} — not sent; Google's version comes back at the next sync")
+    } else {
+        format!("“{title}” is on a pull-only {what} — kept on this machine only")
+    })).await;
+}
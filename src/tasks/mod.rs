@@ -1,16 +1,307 @@
-use crate::db::Task;
+use chrono::{Datelike, NaiveDate};
+use std::collections::HashSet;
 
-pub fn sort_tasks(tasks: &mut Vec<Task>) {
-    tasks.sort_by(|a, b| {
-        b.priority.cmp(&a.priority)
-            .then(a.due.cmp(&b.due))
-            .then(a.title.cmp(&b.title))
+use crate::calendar::{add_days, day_bounds};
+use crate::holidays::{Holiday, HolidayEngine, OnHoliday};
+use crate::model::Task;
+use crate::habits::Schedule;
+
+/// Which tasks the panel lists, cycled with `f`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub enum TaskFilter {
+    #[default]
+    All,
+    DueToday,
+    Overdue,
+    Tag(String),
+}
+
+impl TaskFilter {
+    pub fn label(&self) -> String {
+        match self {
+            Self::All      => "all".into(),
+            Self::DueToday => "due today".into(),
+            Self::Overdue  => "overdue".into(),
+            Self::Tag(t)   => format!("#{t}"),
+        }
+    }
+
+    /// All → due today → overdue → each of `tags` → all.
+    pub fn next(&self, tags: &[String]) -> Self {
+        let by_tag = |i: usize| tags.get(i).map_or(Self::All, |t| Self::Tag(t.clone()));
+        match self {
+            Self::All      => Self::DueToday,
+            Self::DueToday => Self::Overdue,
+            Self::Overdue  => by_tag(0),
+            Self::Tag(t)   => by_tag(tags.iter().position(|x| x == t).map_or(usize::MAX, |i| i + 1)),
+        }
+    }
+
+    fn keeps(&self, t: &Task, today: NaiveDate) -> bool {
+        match self {
+            Self::All      => true,
+            Self::DueToday => t.due.is_some_and(|d| d.date_naive() == today),
+            Self::Overdue  => is_overdue(t, today),
+            Self::Tag(tag) => t.tags.contains(tag),
+        }
+    }
+}
+
+/// Task order, cycled with `s`.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum TaskSort {
+    #[default]
+    Priority,
+    Due,
+    /// `J`/`K` order, kept in step with Google Tasks.
+    Manual,
+}
+
+impl TaskSort {
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Priority => "priority",
+            Self::Due      => "due",
+            Self::Manual   => "manual",
+        }
+    }
+
+    pub fn next(self) -> Self {
+        match self {
+            Self::Priority => Self::Due,
+            Self::Due      => Self::Manual,
+            Self::Manual   => Self::Priority,
+        }
+    }
+}
+
+/// Priorities go from 0 (none) up to this.
+pub const MAX_PRIORITY: i64 = 3;
+
+pub fn priority_label(priority: i64) -> &'static str {
+    match priority {
+        ..=0 => "none",
+        1    => "low",
+        2    => "medium",
+        _    => "high",
+    }
+}
+
+pub fn sort_tasks(tasks: &mut [Task], sort: TaskSort) {
+    match sort {
+        TaskSort::Priority => tasks.sort_by(|a, b| {
+            b.priority.cmp(&a.priority)
+                .then(a.due.cmp(&b.due))
+                .then(a.title.cmp(&b.title))
+        }),
+        // Undated tasks last
+        TaskSort::Due => tasks.sort_by(|a, b| {
+            a.due.is_none().cmp(&b.due.is_none())
+                .then(a.due.cmp(&b.due))
+                .then(b.priority.cmp(&a.priority))
+        }),
+        TaskSort::Manual => tasks.sort_by_key(|t| t.position),
+    }
+}
+
+/// Due dates are days (Google Tasks has no time), so a task is overdue from
+/// the day after it's due.
+pub fn is_overdue(t: &Task, today: NaiveDate) -> bool {
+    !t.completed && !t.deleted && t.due.is_some_and(|d| d.date_naive() < today)
+}
+
+pub fn overdue(tasks: &[Task], today: NaiveDate) -> Vec<&Task> {
+    tasks.iter().filter(|t| is_overdue(t, today)).collect()
+}
+
+/// What the panel shows: `tasks` narrowed by `filter`, in `sort` order.
+pub fn view(mut tasks: Vec<Task>, filter: &TaskFilter, sort: TaskSort, today: NaiveDate) -> Vec<Task> {
+    tasks.retain(|t| filter.keeps(t, today));
+    sort_tasks(&mut tasks, sort);
+    tasks
+}
+
+/// Every tag in use, sorted.
+pub fn all_tags(tasks: &[Task]) -> Vec<String> {
+    let mut tags: Vec<String> = tasks.iter().flat_map(|t| t.tags.iter().cloned()).collect();
+    tags.sort();
+    tags.dedup();
+    tags
+}
+
+/// Splits `#words` out of a typed title: "Buy milk #errands" is the task
+/// "Buy milk" tagged `errands`.
+pub fn split_tags(input: &str) -> (String, Vec<String>) {
+    let mut tags  = Vec::new();
+    let mut words = Vec::new();
+    for w in input.split_whitespace() {
+        match w.strip_prefix('#').filter(|t| !t.is_empty()) {
+            Some(t) => {
+                let t = t.to_lowercase();
+                if !tags.contains(&t) { tags.push(t); }
+            }
+            None => words.push(w),
+        }
+    }
+    (words.join(" "), tags)
+}
+
+/// Splits a repeat rule out of a typed title: "Take out trash @tue" comes
+/// round every Tuesday. Takes the same `@daily` / `@weekdays` / `@mon,thu`
+/// as habits; the last one wins.
+pub fn split_repeat(input: &str) -> (String, Option<Schedule>) {
+    let mut repeat = None;
+    let mut words  = Vec::new();
+    for w in input.split_whitespace() {
+        match w.strip_prefix('@').filter(|r| !r.is_empty()) {
+            Some(r) => repeat = Some(Schedule::parse(r)),
+            None    => words.push(w),
+        }
+    }
+    (words.join(" "), repeat)
+}
+
+/// Splits an estimate out of a typed title: "Write report ~1h30m" should
+/// take 90 minutes. `~45m`, `~2h`, `~1.5h` and a bare `~40` (minutes) all
+/// work; 0 if there's none.
+pub fn split_estimate(input: &str) -> (String, i64) {
+    let mut minutes = 0;
+    let mut words   = Vec::new();
+    for w in input.split_whitespace() {
+        match w.strip_prefix('~').and_then(parse_duration) {
+            Some(m) => minutes = m,
+            None    => words.push(w),
+        }
+    }
+    (words.join(" "), minutes)
+}
+
+fn parse_duration(s: &str) -> Option<i64> {
+    let s = s.to_lowercase();
+    let (hours, rest) = match s.split_once('h') {
+        Some((h, rest)) => (h.parse::<f64>().ok().filter(|h| h.is_finite() && *h >= 0.0)?, rest),
+        None            => (0.0, s.as_str()),
+    };
+    let rest    = rest.strip_suffix('m').unwrap_or(rest);
+    let minutes = if rest.is_empty() { 0 } else { rest.parse::<i64>().ok()? };
+    let total   = (hours * 60.0).round() as i64 + minutes;
+    (total > 0).then_some(total)
+}
+
+/// `45m`, `2h`, `1h30m`: an estimate as typed after `~`.
+pub fn estimate_label(minutes: i64) -> String {
+    match (minutes / 60, minutes % 60) {
+        (0, m) => format!("{m}m"),
+        (h, 0) => format!("{h}h"),
+        (h, m) => format!("{h}h{m}m"),
+    }
+}
+
+/// The first scheduled day after `after`, with `rule` applied when it lands
+/// on one of `days_off`. `None` if nothing comes up within two years.
+pub fn next_due(schedule: &Schedule, after: NaiveDate, rule: OnHoliday, days_off: &HashSet<NaiveDate>) -> Option<NaiveDate> {
+    let mut day = add_days(after, 1)?;
+    for _ in 0..731 {
+        if schedule.due_on(day) {
+            match rule {
+                OnHoliday::Skip if days_off.contains(&day) => {}
+                OnHoliday::Shift => {
+                    while days_off.contains(&day) { day = add_days(day, 1)?; }
+                    return Some(day);
+                }
+                _ => return Some(day),
+            }
+        }
+        day = add_days(day, 1)?;
+    }
+    None
+}
+
+/// `next_due` with `engine`'s days off, and the holiday that moved the day,
+/// if one did.
+pub fn next_occurrence(schedule: &Schedule, after: NaiveDate, rule: OnHoliday, engine: &HolidayEngine) -> (Option<NaiveDate>, Option<Holiday>) {
+    let Some(last) = after.year().checked_add(2) else { return (None, None) };
+    let days_off = engine.days_off(after.year()..=last);
+    let next     = next_due(schedule, after, rule, &days_off);
+    let regular  = next_due(schedule, after, OnHoliday::Keep, &days_off);
+    let moved    = regular.filter(|r| Some(*r) != next).and_then(|r| {
+        engine.holidays_in_year(r.year()).into_iter().find(|h| h.date == r && h.day_off)
     });
+    (next, moved)
+}
+
+/// The task to add when repeating `done` is completed: a copy due on its next
+/// scheduled day after its due day (`today` if it had none), and the holiday
+/// that moved it, if one did. None if it doesn't repeat or nothing comes up.
+pub fn next_repeat(done: &Task, today: NaiveDate, rule: OnHoliday, engine: &HolidayEngine) -> Option<(Task, Option<Holiday>)> {
+    let schedule      = Schedule::parse(done.repeat.as_deref()?);
+    let from          = done.due.map(|d| d.date_naive()).unwrap_or(today);
+    let (next, moved) = next_occurrence(&schedule, from, rule, engine);
+
+    let mut t = Task::new(&done.title);
+    t.notes        = done.notes.clone();
+    t.priority     = done.priority;
+    t.estimate_minutes = done.estimate_minutes;
+    t.task_list_id = done.task_list_id.clone();
+    t.tags         = done.tags.clone();
+    t.repeat       = done.repeat.clone();
+    t.due          = Some(day_bounds(next?).0);
+    Some((t, moved))
 }
 
-pub fn overdue(tasks: &[Task]) -> Vec<&Task> {
-    let now = chrono::Utc::now();
-    tasks.iter().filter(|t|
-        !t.completed && !t.deleted && t.due.map(|d| d < now).unwrap_or(false)
-    ).collect()
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Weekday;
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    #[test]
+    fn repeats_land_on_feb_29_in_leap_years_only() {
+        let none = HashSet::new();
+        assert_eq!(next_due(&Schedule::Daily, date(2028, 2, 28), OnHoliday::Keep, &none), Some(date(2028, 2, 29)));
+        assert_eq!(next_due(&Schedule::Daily, date(2027, 2, 28), OnHoliday::Keep, &none), Some(date(2027, 3, 1)));
+        assert_eq!(next_due(&Schedule::Daily, date(2028, 2, 29), OnHoliday::Keep, &none), Some(date(2028, 3, 1)));
+        // Feb 29 2028 is a Tuesday
+        let tue = Schedule::Days(vec![Weekday::Tue]);
+        assert_eq!(next_due(&tue, date(2028, 2, 23), OnHoliday::Keep, &none), Some(date(2028, 2, 29)));
+    }
+
+    #[test]
+    fn shifting_off_feb_29_and_across_the_year() {
+        let off = HashSet::from([date(2028, 2, 29), date(2027, 1, 1)]);
+        assert_eq!(next_due(&Schedule::Weekdays, date(2028, 2, 28), OnHoliday::Shift, &off), Some(date(2028, 3, 1)));
+        assert_eq!(next_due(&Schedule::Weekdays, date(2028, 2, 28), OnHoliday::Skip, &off), Some(date(2028, 3, 1)));
+        assert_eq!(next_due(&Schedule::Daily, date(2026, 12, 31), OnHoliday::Shift, &off), Some(date(2027, 1, 2)));
+    }
+
+    #[test]
+    fn a_repeat_copies_the_task_and_names_the_holiday_it_shifts_off() {
+        let cfg    = crate::holidays::HolidayConfig { countries: vec!["US".into()], ..Default::default() };
+        let engine = HolidayEngine::new(&cfg, Vec::new());
+        let mut done = Task::new("Standup");
+        done.repeat  = Some("thu".into());
+        done.tags    = vec!["work".into()];
+        done.due     = Some(day_bounds(date(2026, 11, 19)).0);
+
+        // Thanksgiving 2026 is Thursday Nov 26
+        let (next, moved) = next_repeat(&done, date(2026, 10, 15), OnHoliday::Shift, &engine).unwrap();
+        assert_eq!(next.due, Some(day_bounds(date(2026, 11, 27)).0));
+        assert_eq!((next.title.as_str(), next.tags, next.repeat), ("Standup", done.tags.clone(), done.repeat.clone()));
+        assert_eq!(moved.map(|h| h.date), Some(date(2026, 11, 26)));
+
+        let (next, moved) = next_repeat(&done, date(2026, 10, 15), OnHoliday::Keep, &engine).unwrap();
+        assert_eq!(next.due, Some(day_bounds(date(2026, 11, 26)).0));
+        assert!(moved.is_none());
+
+        done.repeat = None;
+        assert!(next_repeat(&done, date(2026, 10, 15), OnHoliday::Keep, &engine).is_none());
+    }
+
+    #[test]
+    fn no_repeat_past_the_end_of_the_calendar() {
+        assert_eq!(next_due(&Schedule::Daily, NaiveDate::MAX, OnHoliday::Keep, &HashSet::new()), None);
+    }
 }
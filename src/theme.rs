@@ -3,13 +3,20 @@ use ratatui::style::Color;
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 
-pub fn hex_to_color(hex: &str) -> Color {
-    let h = hex.trim_start_matches('#');
-    if h.len() != 6 { return Color::Reset; }
-    let r = u8::from_str_radix(&h[0..2], 16).unwrap_or(0);
-    let g = u8::from_str_radix(&h[2..4], 16).unwrap_or(0);
-    let b = u8::from_str_radix(&h[4..6], 16).unwrap_or(0);
-    Color::Rgb(r, g, b)
+/// Parses a theme colour: `#rrggbb`, an ANSI name (`red`, `lightblue`,
+/// `darkgray`, …), a 0–255 palette index, or `default` for the terminal's
+/// own colour. Anything else falls back to the terminal default too.
+pub fn hex_to_color(value: &str) -> Color {
+    let value = value.trim();
+    let h = value.trim_start_matches('#');
+    if h.len() == 6 && h.chars().all(|c| c.is_ascii_hexdigit()) {
+        let r = u8::from_str_radix(&h[0..2], 16).unwrap_or(0);
+        let g = u8::from_str_radix(&h[2..4], 16).unwrap_or(0);
+        let b = u8::from_str_radix(&h[4..6], 16).unwrap_or(0);
+        return Color::Rgb(r, g, b);
+    }
+    if value.eq_ignore_ascii_case("default") { return Color::Reset; }
+    value.parse().unwrap_or(Color::Reset)
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -47,6 +54,32 @@ impl Default for ThemeConfig {
     }
 }
 
+/// The editable colour fields, in editor order.
+pub const COLOR_FIELDS: [&str; 19] = [
+    "bg_primary", "bg_secondary", "bg_popup",
+    "border_normal", "border_focused", "border_selected",
+    "text_primary", "text_secondary", "text_muted", "text_accent",
+    "today_bg", "today_fg", "selected_bg", "selected_fg",
+    "event_dot", "weekend_fg", "success", "warning", "error",
+];
+
+/// Theme fields new tags take their colour from, in turn (`event_dot` stays
+/// for untagged events). A tag stores the field name rather than a value, so
+/// it follows theme switches.
+pub const TAG_COLORS: [&str; 6] = [
+    "text_accent", "success", "warning", "error", "weekend_fg", "border_selected",
+];
+
+fn is_hex(s: &str) -> bool {
+    let h = s.trim().trim_start_matches('#');
+    h.len() == 6 && h.chars().all(|c| c.is_ascii_hexdigit())
+}
+
+/// Whether `hex_to_color` understands `s` rather than falling back.
+pub fn is_color(s: &str) -> bool {
+    is_hex(s) || s.trim().eq_ignore_ascii_case("default") || s.trim().parse::<Color>().is_ok()
+}
+
 impl ThemeConfig {
     pub fn nord() -> Self { Self {
         name: "nord".into(),
@@ -76,17 +109,165 @@ impl ThemeConfig {
         char_tl: "╭".into(), char_tr: "╮".into(), char_bl: "╰".into(), char_br: "╯".into(),
     }}
 
+    pub fn catppuccin_latte() -> Self { Self {
+        name: "catppuccin-latte".into(),
+        bg_primary: "#eff1f5".into(), bg_secondary: "#e6e9ef".into(), bg_popup: "#dce0e8".into(),
+        border_normal: "#bcc0cc".into(), border_focused: "#1e66f5".into(), border_selected: "#8839ef".into(),
+        text_primary: "#4c4f69".into(), text_secondary: "#5c5f77".into(),
+        text_muted: "#8c8fa1".into(), text_accent: "#1e66f5".into(),
+        today_bg: "#8839ef".into(), today_fg: "#eff1f5".into(),
+        selected_bg: "#1e66f5".into(), selected_fg: "#eff1f5".into(),
+        event_dot: "#40a02b".into(), weekend_fg: "#d20f39".into(),
+        success: "#40a02b".into(), warning: "#df8e1d".into(), error: "#d20f39".into(),
+        char_h: "─".into(), char_v: "│".into(),
+        char_tl: "╭".into(), char_tr: "╮".into(), char_bl: "╰".into(), char_br: "╯".into(),
+    }}
+
+    pub fn solarized_light() -> Self { Self {
+        name: "solarized-light".into(),
+        bg_primary: "#fdf6e3".into(), bg_secondary: "#eee8d5".into(), bg_popup: "#eee8d5".into(),
+        border_normal: "#93a1a1".into(), border_focused: "#268bd2".into(), border_selected: "#6c71c4".into(),
+        text_primary: "#586e75".into(), text_secondary: "#657b83".into(),
+        text_muted: "#93a1a1".into(), text_accent: "#268bd2".into(),
+        today_bg: "#6c71c4".into(), today_fg: "#fdf6e3".into(),
+        selected_bg: "#268bd2".into(), selected_fg: "#fdf6e3".into(),
+        event_dot: "#859900".into(), weekend_fg: "#dc322f".into(),
+        success: "#859900".into(), warning: "#cb4b16".into(), error: "#dc322f".into(),
+        char_h: "─".into(), char_v: "│".into(),
+        char_tl: "╭".into(), char_tr: "╮".into(), char_bl: "╰".into(), char_br: "╯".into(),
+    }}
+
+    /// ANSI colours only, on the terminal's own background and text colour,
+    /// so it follows whatever palette the terminal has — light or dark, with
+    /// or without truecolor.
+    pub fn terminal() -> Self { Self {
+        name: "terminal".into(),
+        bg_primary: "default".into(), bg_secondary: "default".into(), bg_popup: "default".into(),
+        border_normal: "darkgray".into(), border_focused: "blue".into(), border_selected: "magenta".into(),
+        text_primary: "default".into(), text_secondary: "default".into(),
+        text_muted: "darkgray".into(), text_accent: "blue".into(),
+        today_bg: "magenta".into(), today_fg: "black".into(),
+        selected_bg: "blue".into(), selected_fg: "white".into(),
+        event_dot: "green".into(), weekend_fg: "red".into(),
+        success: "green".into(), warning: "yellow".into(), error: "red".into(),
+        char_h: "─".into(), char_v: "│".into(),
+        char_tl: "╭".into(), char_tr: "╮".into(), char_bl: "╰".into(), char_br: "╯".into(),
+    }}
+
+    fn built_ins() -> Vec<Self> {
+        vec![
+            Self::default(), Self::nord(), Self::gruvbox(),
+            Self::catppuccin_latte(), Self::solarized_light(), Self::terminal(),
+        ]
+    }
+
+    /// The theme last picked with `T` if it's still around, otherwise
+    /// `theme.toml` (written with the default on first run).
     pub fn load() -> Result<Self> {
         let path = config_dir().join("theme.toml");
-        if path.exists() {
-            Ok(toml::from_str(&std::fs::read_to_string(&path)?)?)
+        let base = if path.exists() {
+            toml::from_str(&std::fs::read_to_string(&path)?)?
         } else {
             let t = ThemeConfig::default();
             t.save()?;
-            Ok(t)
+            t
+        };
+        let picked = std::fs::read_to_string(config_dir().join("selected_theme")).ok()
+            .and_then(|name| Self::all_themes().into_iter().find(|t| t.name == name.trim()));
+        Ok(picked.unwrap_or(base))
+    }
+
+    /// Built-in themes, then `theme.toml`, then the user's files in
+    /// `~/.config/lifemanager/themes/` in file-name order. A file with a
+    /// name already in the list replaces that entry rather than repeating it.
+    pub fn all_themes() -> Vec<Self> {
+        let mut out   = Self::built_ins();
+        let mut paths = vec![config_dir().join("theme.toml")];
+        if let Ok(dir) = std::fs::read_dir(themes_dir()) {
+            let mut found: Vec<PathBuf> = dir.flatten().map(|e| e.path())
+                .filter(|p| p.extension().is_some_and(|x| x == "toml"))
+                .collect();
+            found.sort();
+            paths.extend(found);
+        }
+        for path in paths.into_iter().filter(|p| p.exists()) {
+            let parsed = std::fs::read_to_string(&path).map_err(anyhow::Error::from)
+                .and_then(|s| Ok(toml::from_str::<Self>(&s)?));
+            match parsed {
+                Ok(t) => match out.iter_mut().find(|o| o.name == t.name) {
+                    Some(slot) => *slot = t,
+                    None       => out.push(t),
+                },
+                Err(e) => tracing::warn!("Skipping theme {}: {e}", path.display()),
+            }
+        }
+        out
+    }
+
+    /// Remembers the theme by name for the next start, leaving the theme
+    /// files themselves alone.
+    pub fn select(&self) -> Result<()> {
+        let dir = config_dir();
+        std::fs::create_dir_all(&dir)?;
+        std::fs::write(dir.join("selected_theme"), &self.name)?;
+        Ok(())
+    }
+
+    /// Distinct colours across the built-in themes, for the editor's picker.
+    pub fn palette() -> Vec<String> {
+        let mut out: Vec<String> = Vec::new();
+        for t in Self::built_ins() {
+            for i in 0..COLOR_FIELDS.len() {
+                let c = t.color(i).to_ascii_lowercase();
+                if !out.contains(&c) { out.push(c); }
+            }
+        }
+        out
+    }
+
+    pub fn color(&self, field: usize) -> &str {
+        match field {
+            0  => &self.bg_primary,    1  => &self.bg_secondary,   2  => &self.bg_popup,
+            3  => &self.border_normal, 4  => &self.border_focused, 5  => &self.border_selected,
+            6  => &self.text_primary,  7  => &self.text_secondary,
+            8  => &self.text_muted,    9  => &self.text_accent,
+            10 => &self.today_bg,      11 => &self.today_fg,
+            12 => &self.selected_bg,   13 => &self.selected_fg,
+            14 => &self.event_dot,     15 => &self.weekend_fg,
+            16 => &self.success,       17 => &self.warning,        _  => &self.error,
         }
     }
 
+    pub fn set_color(&mut self, field: usize, value: &str) {
+        let slot = match field {
+            0  => &mut self.bg_primary,    1  => &mut self.bg_secondary,   2  => &mut self.bg_popup,
+            3  => &mut self.border_normal, 4  => &mut self.border_focused, 5  => &mut self.border_selected,
+            6  => &mut self.text_primary,  7  => &mut self.text_secondary,
+            8  => &mut self.text_muted,    9  => &mut self.text_accent,
+            10 => &mut self.today_bg,      11 => &mut self.today_fg,
+            12 => &mut self.selected_bg,   13 => &mut self.selected_fg,
+            14 => &mut self.event_dot,     15 => &mut self.weekend_fg,
+            16 => &mut self.success,       17 => &mut self.warning,        _  => &mut self.error,
+        };
+        *slot = if is_hex(value) {
+            format!("#{}", value.trim().trim_start_matches('#').to_ascii_lowercase())
+        } else {
+            value.trim().to_ascii_lowercase()
+        };
+    }
+
+    /// Writes the theme to `themes/<name>.toml` so it shows up under `T`.
+    pub fn save_custom(&self) -> Result<PathBuf> {
+        let dir = themes_dir();
+        std::fs::create_dir_all(&dir)?;
+        let file: String = self.name.chars()
+            .map(|c| if c.is_alphanumeric() || c == '-' || c == '_' { c } else { '-' })
+            .collect();
+        let path = dir.join(format!("{file}.toml"));
+        std::fs::write(&path, toml::to_string_pretty(self)?)?;
+        Ok(path)
+    }
+
     pub fn save(&self) -> Result<()> {
         let dir = config_dir();
         std::fs::create_dir_all(&dir)?;
@@ -105,6 +286,17 @@ impl ThemeConfig {
     pub fn event_color(&self)   -> Color { hex_to_color(&self.event_dot) }
     pub fn weekend_color(&self) -> Color { hex_to_color(&self.weekend_fg) }
     pub fn muted(&self)         -> Color { hex_to_color(&self.text_muted) }
+    pub fn warning_color(&self) -> Color { hex_to_color(&self.warning) }
+    pub fn success_color(&self) -> Color { hex_to_color(&self.success) }
+    pub fn error_color(&self)   -> Color { hex_to_color(&self.error) }
+
+    /// A tag's colour: a theme field name, or a literal colour.
+    pub fn tag_color(&self, color: &str) -> Color {
+        match COLOR_FIELDS.iter().position(|f| *f == color) {
+            Some(i) => hex_to_color(self.color(i)),
+            None    => hex_to_color(color),
+        }
+    }
 
     pub fn today_highlight(&self)    -> (Color, Color) {
         (hex_to_color(&self.today_bg), hex_to_color(&self.today_fg))
@@ -117,3 +309,7 @@ impl ThemeConfig {
 fn config_dir() -> PathBuf {
     dirs::config_dir().unwrap_or_else(|| PathBuf::from(".")).join("lifemanager")
 }
+
+fn themes_dir() -> PathBuf {
+    config_dir().join("themes")
+}
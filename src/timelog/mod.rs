@@ -0,0 +1,87 @@
+//! Time tracking: events marked as time entries (`m`) and entries recorded
+//! with the `Ctrl+t` timer, summed per week for the `H` report.
+
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use std::collections::HashMap;
+
+use crate::calendar::{add_days, day_bounds};
+use crate::db::{Event, Timer, FOCUS_CALENDAR, TIME_CALENDAR};
+
+/// The timer as a time entry ending at `end`, on the local time layer.
+pub fn entry(timer: &Timer, end: DateTime<Utc>) -> Event {
+    let title = timer.label.as_deref().unwrap_or("Tracked time");
+    let mut ev = Event::new(title, timer.started_at, end);
+    ev.calendar_id = Some(TIME_CALENDAR.to_owned());
+    ev.tag         = timer.tag.clone();
+    ev.time_entry  = true;
+    ev.dirty       = false;
+    ev
+}
+
+/// `1:05` since `timer` started, for the status bar.
+pub fn running_for(timer: &Timer, now: DateTime<Utc>) -> String {
+    let m = (now - timer.started_at).num_minutes().max(0);
+    format!("{}:{:02}", m / 60, m % 60)
+}
+
+/// What the report's bars are split by, toggled with `g`.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum GroupBy {
+    #[default]
+    Tag,
+    Calendar,
+}
+
+impl GroupBy {
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Tag      => "tag",
+            Self::Calendar => "calendar",
+        }
+    }
+
+    pub fn next(self) -> Self {
+        match self {
+            Self::Tag      => Self::Calendar,
+            Self::Calendar => Self::Tag,
+        }
+    }
+
+    fn key(self, ev: &Event) -> String {
+        match self {
+            Self::Tag => ev.tag.as_ref().map_or_else(|| "untagged".into(), |t| format!("#{t}")),
+            Self::Calendar => match ev.calendar_id.as_deref() {
+                Some(TIME_CALENDAR)  => "timer".into(),
+                Some(FOCUS_CALENDAR) => "focus".into(),
+                Some(id)             => id.to_owned(),
+                None                 => "local".into(),
+            },
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct WeekReport {
+    /// Hours per group, most first.
+    pub rows:  Vec<(String, f64)>,
+    pub total: f64,
+}
+
+/// Hours of timed time entries within the week starting `monday`. Entries
+/// that run over the week's edges count only their part inside it.
+pub fn week_report(events: &[Event], monday: NaiveDate, group: GroupBy) -> WeekReport {
+    let from = day_bounds(monday).0;
+    let to   = day_bounds(add_days(monday, 6).unwrap_or(monday)).1;
+
+    let mut hours: HashMap<String, f64> = HashMap::new();
+    for ev in events.iter().filter(|e| e.time_entry && !e.all_day && !e.deleted) {
+        let span = ev.end.min(to) - ev.start.max(from);
+        if span <= Duration::zero() { continue; }
+        *hours.entry(group.key(ev)).or_default() += span.num_minutes() as f64 / 60.0;
+    }
+
+    let mut rows: Vec<(String, f64)> = hours.into_iter().collect();
+    rows.sort_by(|a, b| b.1.total_cmp(&a.1).then(a.0.cmp(&b.0)));
+    let total = rows.iter().fold(0.0, |sum, (_, h)| sum + h);
+    WeekReport { rows, total }
+}
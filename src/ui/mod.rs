@@ -1,14 +1,35 @@
-use chrono::{Datelike, NaiveDate};
+use chrono::{DateTime, Datelike, NaiveDate, Utc};
+use std::cell::Cell;
+use unicode_width::UnicodeWidthStr;
 use ratatui::{
     layout::{Alignment, Constraint, Direction, Layout, Rect},
-    style::{Modifier, Style},
+    style::{Color, Modifier, Style},
     text::{Line, Span},
-    widgets::{block::Title, Block, BorderType, Borders, Clear, List, ListItem, ListState, Paragraph, Wrap},
+    widgets::{
+        block::{Position, Title}, Block, BorderType, Borders, Clear, List, ListItem, ListState, Paragraph,
+        Scrollbar, ScrollbarOrientation, ScrollbarState, Wrap,
+    },
     Frame,
 };
 
-use crate::app::{App, Panel};
-use crate::calendar::days_in_month;
+use crate::app::{palette, App, BulkAction, Panel, TaskField};
+use crate::agenda;
+use crate::attendance::{self, MeetingLoad};
+use crate::logs::{self, Level};
+use crate::notify::Notification;
+use crate::plugins;
+use crate::status;
+use crate::config::{UiConfig, Urgency};
+use crate::calendar::{add_days, day_bounds, days_in_month, dst, lunar, month_weeks, week_start};
+use crate::db::{Event, Forecast, Rsvp, Task, EVENT_COLORS, FOCUS_CALENDAR, PLAN_CALENDAR, TIME_CALENDAR};
+use crate::dedupe::{self, Field};
+use crate::export;
+use crate::habits;
+use crate::sync::google::Availability;
+use crate::tasks::{self, TaskFilter};
+use crate::theme::{hex_to_color, ThemeConfig, COLOR_FIELDS};
+use crate::timelog;
+use crate::weather;
 
 // ─── UI enums / state ─────────────────────────────────────────────────────────
 
@@ -20,8 +41,20 @@ pub enum InputMode { #[default] Normal, Insert }
 pub enum EventFormStep {
     #[default]
     Title,
+    Tag,
     StartTime,
     EndTime,
+    Guests,
+}
+
+/// Whether the guests typed into the event form are free for its slot.
+#[derive(Debug, Default, Clone)]
+pub enum GuestCheck {
+    #[default]
+    NotChecked,
+    Pending,
+    Done(Vec<Availability>),
+    Failed(String),
 }
 
 /// Which time field (hour or minute) is focused in the time picker.
@@ -40,6 +73,85 @@ pub struct UiState {
     pub event_end_h:     u32,
     pub event_end_m:     u32,
     pub time_field:      TimeField,
+    /// Travel or prep time before the start, `+` / `-` at step 3
+    pub event_lead:      i64,
+    // Event form step 4: comma-separated guest emails and their free/busy
+    pub event_tag:       String,
+    /// Google colour picked in the event form: a `colorId`, 0 for none
+    pub event_color:     usize,
+    pub event_guests:    String,
+    pub guest_check:     GuestCheck,
+    /// Events the new one overlaps, once Enter has listed them
+    pub event_conflicts: Option<Vec<Event>>,
+    // Google onboarding overlay
+    pub auth_url:        Option<String>,
+    pub auth_status:     String,
+    pub auth_code:       String,
+    // Meal-plan grid: focused column and the cell being edited
+    pub meal_slot:       usize,
+    pub meal_input:      String,
+    // Checklists overlay: selected list/item, which pane has focus, typed text
+    pub list_cursor:        usize,
+    pub list_item_cursor:   usize,
+    pub list_items_focused: bool,
+    pub list_input:         String,
+    // Ideal-week overlay: selected template block
+    pub plan_cursor:        usize,
+    // Quarantine review: selected item, typed time, start already entered
+    pub review_cursor:      usize,
+    pub review_input:       String,
+    pub review_start:       Option<(DateTime<Utc>, bool)>,
+    // List scrolling: first visible item and rows on screen, written back
+    // while drawing; `g` waiting for a second `g`
+    pub event_scroll:       Cell<usize>,
+    pub task_scroll:        Cell<usize>,
+    pub event_rows:         Cell<u16>,
+    pub task_rows:          Cell<u16>,
+    pub agenda_scroll:      Cell<usize>,
+    pub agenda_rows:        Cell<u16>,
+    pub pending_g:          bool,
+    /// Digits typed on the month grid, waiting for the motion they repeat
+    pub count:              Option<u32>,
+    /// `Space` on the month grid, waiting for the key it leads to
+    pub leader:             bool,
+    /// `v` selection anchor: an agenda row in the compact layout, else
+    /// the focused list's index
+    pub visual:             Option<usize>,
+    // Theme picker/editor: highlighted theme, selected field and palette
+    // swatch, typed hex or name (`theme_naming`), the theme to restore on Esc
+    pub theme_cursor:       usize,
+    pub theme_field:        usize,
+    pub theme_swatch:       usize,
+    pub theme_input:        String,
+    pub theme_naming:       bool,
+    pub theme_original:     Option<ThemeConfig>,
+    // Habits overlay: selected habit, typed "name @schedule"
+    pub habit_cursor:       usize,
+    pub habit_input:        String,
+    // Log viewer: lines scrolled up from the newest, lowest level shown
+    pub log_scroll:         usize,
+    // Sync log: entries scrolled up from the newest
+    pub sync_log_scroll:    usize,
+    pub log_level:          Level,
+    // Export overlay: what, as what, and where (typed while editing)
+    pub export_scope:       export::Scope,
+    pub export_format:      export::Format,
+    pub export_path:        String,
+    // Custom panels: the one shown and its selected item
+    pub plugin_tab:         usize,
+    pub plugin_cursor:      usize,
+    // Send to: the highlighted action
+    pub action_cursor:      usize,
+    // Command palette: what's typed and the highlighted match
+    pub palette_query:      String,
+    pub palette_cursor:     usize,
+    // Go to date: what's typed
+    pub goto_input:         String,
+    // Holidays shown (`V`): the highlighted row
+    pub holiday_cursor:     usize,
+    // Help: first line shown and rows on screen, written back while drawing
+    pub help_scroll:        Cell<usize>,
+    pub help_rows:          Cell<u16>,
 }
 
 impl Default for UiState {
@@ -54,21 +166,75 @@ impl Default for UiState {
             event_end_h:     10,
             event_end_m:     0,
             time_field:      TimeField::Hour,
+            event_lead:      0,
+            event_tag:       String::new(),
+            event_color:     0,
+            event_guests:    String::new(),
+            guest_check:     GuestCheck::NotChecked,
+            event_conflicts: None,
+            auth_url:        None,
+            auth_status:     String::new(),
+            auth_code:       String::new(),
+            meal_slot:       0,
+            meal_input:      String::new(),
+            list_cursor:        0,
+            list_item_cursor:   0,
+            list_items_focused: false,
+            list_input:         String::new(),
+            plan_cursor:        0,
+            review_cursor:      0,
+            review_input:       String::new(),
+            review_start:       None,
+            event_scroll:       Cell::new(0),
+            task_scroll:        Cell::new(0),
+            event_rows:         Cell::new(0),
+            task_rows:          Cell::new(0),
+            agenda_scroll:      Cell::new(0),
+            agenda_rows:        Cell::new(0),
+            pending_g:          false,
+            count:              None,
+            leader:             false,
+            visual:             None,
+            theme_cursor:       0,
+            theme_field:        0,
+            theme_swatch:       0,
+            theme_input:        String::new(),
+            theme_naming:       false,
+            theme_original:     None,
+            habit_cursor:       0,
+            habit_input:        String::new(),
+            log_scroll:         0,
+            sync_log_scroll:    0,
+            log_level:          Level::Info,
+            export_scope:       export::Scope::Day,
+            export_format:      export::Format::Ics,
+            export_path:        String::new(),
+            plugin_tab:         0,
+            plugin_cursor:      0,
+            action_cursor:      0,
+            palette_query:      String::new(),
+            palette_cursor:     0,
+            goto_input:         String::new(),
+            holiday_cursor:     0,
+            help_scroll:        Cell::new(0),
+            help_rows:          Cell::new(0),
         }
     }
 }
 
-// ─── Root draw ────────────────────────────────────────────────────────────────
-
-pub fn draw(f: &mut Frame, app: &App) {
-    let area = f.area();
+// ─── Layout ───────────────────────────────────────────────────────────────────
 
-    // Fill background
-    f.render_widget(
-        Block::default().style(Style::default().bg(app.theme.bg()).fg(app.theme.fg())),
-        area,
-    );
+/// Where the main panels sit; shared by `draw` and mouse hit-testing.
+pub struct Areas {
+    pub calendar: Rect,
+    pub events:   Rect,
+    pub meals:    Option<Rect>,
+    pub habits:   Option<Rect>,
+    pub tasks:    Rect,
+    pub status:   Rect,
+}
 
+pub fn layout(area: Rect, cfg: &UiConfig, habit_rows: u16) -> Areas {
     // Layout: [ content | status_bar(1) ]
     let root = Layout::default().direction(Direction::Vertical)
         .constraints([Constraint::Min(0), Constraint::Length(1)]).split(area);
@@ -77,27 +243,288 @@ pub fn draw(f: &mut Frame, app: &App) {
     let cols = Layout::default().direction(Direction::Horizontal)
         .constraints([Constraint::Length(34), Constraint::Min(0)]).split(root[0]);
 
-    // Right: [ events(50%) | tasks(50%) ]
-    let rows = Layout::default().direction(Direction::Vertical)
-        .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
-        .split(cols[1]);
+    // Left: [ calendar | habits(n + 2)? ]
+    let left = Layout::default().direction(Direction::Vertical)
+        .constraints([Constraint::Min(0), Constraint::Length(if habit_rows > 0 { habit_rows + 2 } else { 0 })])
+        .split(cols[0]);
+
+    // Compact right: [ agenda | meals(5)? ], the agenda in the events area
+    if cfg.compact {
+        let rows = Layout::default().direction(Direction::Vertical)
+            .constraints([Constraint::Min(0), Constraint::Length(if cfg.meal_plan { 5 } else { 0 })])
+            .split(cols[1]);
+        return Areas {
+            calendar: left[0],
+            events:   rows[0],
+            meals:    cfg.meal_plan.then(|| rows[1]),
+            habits:   (habit_rows > 0).then(|| left[1]),
+            tasks:    Rect::default(),
+            status:   root[1],
+        };
+    }
+
+    // Right: [ events(50%) | meals(5)? | tasks(50%) ]
+    let rows = if cfg.meal_plan {
+        Layout::default().direction(Direction::Vertical)
+            .constraints([Constraint::Percentage(50), Constraint::Length(5), Constraint::Min(0)])
+            .split(cols[1])
+    } else {
+        Layout::default().direction(Direction::Vertical)
+            .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+            .split(cols[1])
+    };
+
+    Areas {
+        calendar: left[0],
+        events:   rows[0],
+        meals:    cfg.meal_plan.then(|| rows[1]),
+        habits:   (habit_rows > 0).then(|| left[1]),
+        tasks:    rows[rows.len() - 1],
+        status:   root[1],
+    }
+}
+
+// ─── Mouse hit-testing ────────────────────────────────────────────────────────
+
+fn inside(r: Rect, x: u16, y: u16) -> bool {
+    x >= r.x && x < r.x + r.width && y >= r.y && y < r.y + r.height
+}
+
+/// Which main panel (calendar, events, tasks) contains the point.
+pub fn panel_at(areas: &Areas, x: u16, y: u16) -> Option<Panel> {
+    if inside(areas.calendar, x, y) { return Some(Panel::Calendar); }
+    if inside(areas.events, x, y)   { return Some(Panel::EventList); }
+    if inside(areas.tasks, x, y)    { return Some(Panel::TaskList); }
+    None
+}
+
+/// The day cell under (x, y) in the calendar panel, mirroring `draw_calendar`:
+/// two header rows, then one row per week (two with lunar dates), 4 columns
+/// per day after the week-number gutter. Below the grid, a day of a mini
+/// month, or the 1st for its title.
+pub fn calendar_date_at(app: &App, area: Rect, x: u16, y: u16) -> Option<NaiveDate> {
+    let inner = Block::default().borders(Borders::ALL).inner(area);
+    if !inside(inner, x, y) { return None; }
+    if let Some(date) = mini_date_at(app, inner, x, y) { return Some(date); }
+    let per_week = if app.config.ui.lunar_dates { 2 } else { 1 };
+    let week = (y.checked_sub(inner.y + 2)? / per_week) as i32;
+    let col  = (x.checked_sub(inner.x + week_gutter(app))? / 4) as i32;
+    if col >= 7 { return None; }
+
+    let first  = NaiveDate::from_ymd_opt(app.view_year, app.view_month, 1)?;
+    let offset = first.weekday().num_days_from_monday() as i32;
+    let day    = week * 7 + col - offset + 1;
+    if day < 1 || day > days_in_month(app.view_year, app.view_month) as i32 { return None; }
+    NaiveDate::from_ymd_opt(app.view_year, app.view_month, day as u32)
+}
+
+/// The event under row `y` of the events panel (feed events don't count).
+pub fn event_index_at(app: &App, area: Rect, y: u16) -> Option<usize> {
+    let pin = pinned_layout(app, area, app.pinned_events());
+    pin.index_at(y, &event_heights(app), app.ui.event_scroll.get())
+        .filter(|&i| i < app.events.len())
+}
+
+pub fn task_index_at(app: &App, area: Rect, y: u16) -> Option<usize> {
+    list_index_at(&vec![1; app.tasks.len()], app.ui.task_scroll.get(), area, y)
+}
+
+/// Row of the compact agenda under `y`; feed events aren't selectable.
+pub fn agenda_index_at(app: &App, area: Rect, y: u16) -> Option<usize> {
+    let items = app.agenda();
+    let pin   = pinned_layout(app, area, app.pinned_agenda(&items));
+    pin.index_at(y, &agenda_heights(app, &items), app.ui.agenda_scroll.get())
+        .filter(|&i| i < items.len())
+}
+
+/// Item under row `y` of a bordered `List` scrolled to `first`.
+fn list_index_at(heights: &[u16], first: usize, area: Rect, y: u16) -> Option<usize> {
+    list_index_in(heights, first, Block::default().borders(Borders::ALL).inner(area), y)
+}
+
+/// The same within `inner`, the list's own rows.
+fn list_index_in(heights: &[u16], first: usize, inner: Rect, y: u16) -> Option<usize> {
+    if y < inner.y || y >= inner.y + inner.height { return None; }
+    let mut top = inner.y;
+    for (i, h) in heights.iter().enumerate().skip(first) {
+        if y < top + h { return Some(i); }
+        top += h;
+    }
+    None
+}
+
+// ─── List scrolling ───────────────────────────────────────────────────────────
+
+/// Rows each entry of the events panel takes: the selected event grows a
+/// line for its DST note, and events with lead time one for that; feed
+/// events follow the day's own.
+fn event_heights(app: &App) -> Vec<u16> {
+    app.events.iter().enumerate()
+        .map(|(i, ev)| event_height(app, i, ev))
+        .chain(app.feed_events.iter().map(|_| 1))
+        .collect()
+}
+
+fn agenda_heights(app: &App, items: &[agenda::Item]) -> Vec<u16> {
+    items.iter()
+        .map(|item| match *item {
+            agenda::Item::Event(i) => event_height(app, i, &app.events[i]),
+            agenda::Item::Task(_)  => 1,
+        })
+        .chain(app.feed_events.iter().map(|_| 1))
+        .collect()
+}
+
+fn event_height(app: &App, i: usize, ev: &Event) -> u16 {
+    let dst = i == app.event_cursor && app.event_dst(ev).is_some();
+    1 + dst as u16 + has_lead(ev) as u16
+}
+
+/// Timed events with lead time get a shaded row for it above their own.
+fn has_lead(ev: &Event) -> bool {
+    ev.lead_minutes > 0 && !ev.all_day
+}
+
+/// First visible item: `prev` moved as little as needed to keep `cursor` on
+/// screen, and never so far that the bottom of the list leaves a gap.
+fn scroll_to(heights: &[u16], cursor: usize, prev: usize, rows: u16) -> usize {
+    if heights.is_empty() { return 0; }
+    let cursor    = cursor.min(heights.len() - 1);
+    let mut first = prev.min(cursor);
+    while first < cursor && heights[first..=cursor].iter().sum::<u16>() > rows {
+        first += 1;
+    }
+    while first > 0 && heights[first - 1..].iter().sum::<u16>() <= rows {
+        first -= 1;
+    }
+    first
+}
+
+/// ▲/▼ scrollbar on the right border when `heights` overflow `area`.
+fn draw_scrollbar(f: &mut Frame, app: &App, area: Rect, heights: &[u16], first: usize) {
+    let rows = area.height.saturating_sub(2);
+    if heights.iter().sum::<u16>() <= rows { return; }
+    render_scrollbar(f, app, area, ScrollbarState::new(heights.len().saturating_sub(1)).position(first));
+}
+
+/// The same for one-row lines: `total` of them, `rows` on screen from
+/// `first`, which is at most `total - rows`.
+fn draw_line_scrollbar(f: &mut Frame, app: &App, area: Rect, total: usize, rows: usize, first: usize) {
+    if total <= rows { return; }
+    render_scrollbar(f, app, area, ScrollbarState::new(total - rows + 1).position(first));
+}
+
+fn render_scrollbar(f: &mut Frame, app: &App, area: Rect, mut state: ScrollbarState) {
+    f.render_stateful_widget(
+        Scrollbar::new(ScrollbarOrientation::VerticalRight)
+            .begin_symbol(Some("▲"))
+            .end_symbol(Some("▼"))
+            .track_symbol(Some("│"))
+            .style(Style::default().fg(app.theme.border_active())),
+        area.inner(ratatui::layout::Margin { vertical: 1, horizontal: 0 }),
+        &mut state,
+    );
+}
+
+/// The key a click at column `x` of the status bar stands for, if any.
+pub fn status_key_at(app: &App, area: Rect, x: u16) -> Option<&'static str> {
+    let mut left = area.x;
+    for (key, span) in status_spans(app) {
+        let right = left.saturating_add(span.width() as u16);
+        if x >= left && x < right { return key; }
+        left = right;
+    }
+    None
+}
 
-    draw_calendar(f, app, cols[0]);
-    draw_events(f, app, rows[0]);
-    draw_tasks(f, app, rows[1]);
-    draw_statusbar(f, app, root[1]);
+// ─── Root draw ────────────────────────────────────────────────────────────────
+
+pub fn draw(f: &mut Frame, app: &App) {
+    let area = f.area();
+
+    // Fill background
+    f.render_widget(
+        Block::default().style(Style::default().bg(app.theme.bg()).fg(app.theme.fg())),
+        area,
+    );
+
+    let areas = layout(area, &app.config.ui, app.habit_rows());
+    draw_calendar(f, app, areas.calendar);
+    if app.config.ui.compact {
+        draw_agenda(f, app, areas.events);
+    } else {
+        draw_events(f, app, areas.events);
+        draw_tasks(f, app, areas.tasks);
+    }
+    if let Some(meals) = areas.meals {
+        draw_meals(f, app, meals);
+    }
+    if let Some(habits) = areas.habits {
+        draw_habits(f, app, habits);
+    }
+    draw_statusbar(f, app, areas.status);
+    if !app.banner.is_empty() && areas.status.y > area.y {
+        draw_banner(f, app, Rect::new(area.x, areas.status.y - 1, area.width, 1));
+    }
 
     // Overlays
     match app.active_panel {
         Panel::EventDetail => draw_event_form(f, area, app),
-        Panel::TaskDetail  => draw_popup(f, "New Task", &app.ui.new_task_title, area, app),
+        Panel::TaskDetail  => draw_popup(f, "New Task — #word adds a tag, @tue repeats", &app.ui.new_task_title, area, app),
         Panel::Help        => draw_help(f, area, app),
+        Panel::Stats       => draw_stats(f, area, app),
+        Panel::Today       => draw_today(f, area, app),
+        Panel::Onboarding  => draw_onboarding(f, area, app),
+        Panel::MealPlan    => draw_meal_plan(f, area, app),
+        Panel::Checklists  => draw_checklists(f, area, app),
+        Panel::IdealWeek   => draw_ideal_week(f, area, app),
+        Panel::Quarantine  => draw_quarantine(f, area, app),
+        Panel::ThemeEditor => draw_theme_editor(f, area, app),
+        Panel::ThemePicker => draw_theme_picker(f, area, app),
+        Panel::Holidays    => draw_holidays(f, area, app),
+        Panel::Logs        => draw_logs(f, area, app),
+        Panel::SyncLog     => draw_sync_log(f, area, app),
+        Panel::Reports     => draw_reports(f, area, app),
+        Panel::Habits      => draw_habits_overlay(f, area, app),
+        Panel::Export      => draw_export(f, area, app),
+        Panel::Bulk        => draw_bulk(f, area, app),
+        Panel::Confirm     => draw_confirm(f, area, app),
+        Panel::Dedupe      => draw_dedupe(f, area, app),
+        Panel::Plugins     => draw_plugins(f, area, app),
+        Panel::SendTo      => draw_send_to(f, area, app),
+        Panel::Palette     => draw_palette(f, area, app),
+        Panel::GoTo        => draw_popup(f, "Go to — 2025-03-14, mar 14, +3w, next friday", &app.ui.goto_input, area, app),
+        Panel::TaskEdit    => draw_task_edit(f, area, app),
+        Panel::Planning    => draw_planning(f, area, app),
+        Panel::EventInfo   => draw_event_info(f, area, app),
+        Panel::ZoneChange  => draw_zone_change(f, area, app),
         _ => {}
     }
+    if app.ui.leader {
+        draw_leader(f, area, app);
+    }
+
+    // Reminder toasts sit above everything, top-right
+    if let Some((n, _)) = &app.toast {
+        draw_toast(f, area, app, n);
+    }
 }
 
 // ─── Calendar ─────────────────────────────────────────────────────────────────
 
+/// Columns left of the month grid for ISO week numbers, when they're on.
+fn week_gutter(app: &App) -> u16 {
+    if app.config.ui.week_numbers { 4 } else { 0 }
+}
+
+/// The gutter's cell: a week number, or blank when `week` is None.
+fn week_cell(app: &App, week: Option<u32>) -> Option<Span<'static>> {
+    app.config.ui.week_numbers.then(|| match week {
+        Some(w) => Span::styled(format!("{w:>3} "), Style::default().fg(app.theme.muted())),
+        None    => Span::raw("    "),
+    })
+}
+
 fn draw_calendar(f: &mut Frame, app: &App, area: Rect) {
     let t       = &app.theme;
     let focused = app.active_panel == Panel::Calendar;
@@ -108,12 +535,20 @@ fn draw_calendar(f: &mut Frame, app: &App, area: Rect) {
         Style::default().fg(t.accent()).add_modifier(Modifier::BOLD),
     ));
 
-    let block = Block::default()
+    let mut block = Block::default()
         .title(Title::from(title))
         .borders(Borders::ALL)
         .border_type(BorderType::Rounded)
         .border_style(bs)
         .style(Style::default().bg(t.bg()));
+    if let Some(day) = app.forecast.iter().find(|d| d.date == app.selected_date) {
+        let sky = format!(" {} {}/{} ", weather::icon(day.code), weather::degrees(day.high), weather::degrees(day.low));
+        block = block.title(Title::from(Span::styled(sky, Style::default().fg(t.fg_dim()))).alignment(Alignment::Right));
+    }
+
+    if let Some(legend) = holiday_legend(app) {
+        block = block.title(Title::from(legend).position(Position::Bottom));
+    }
 
     let inner = block.inner(area);
     f.render_widget(block, area);
@@ -122,21 +557,22 @@ fn draw_calendar(f: &mut Frame, app: &App, area: Rect) {
     let mut lines: Vec<Line> = vec![];
 
     // Header row: Mo Tu We Th Fr Sa Su
-    let hdrs: Vec<Span> = ["Mo","Tu","We","Th","Fr","Sa","Su"].iter().enumerate().map(|(i, d)| {
+    let hdrs = ["Mo","Tu","We","Th","Fr","Sa","Su"].iter().enumerate().map(|(i, d)| {
         let style = if i >= 5 {
             Style::default().fg(t.weekend_color()).add_modifier(Modifier::BOLD)
         } else {
             Style::default().fg(t.fg_dim()).add_modifier(Modifier::BOLD)
         };
         Span::styled(format!(" {d} "), style)
-    }).collect();
-    lines.push(Line::from(hdrs));
+    });
+    let wk = app.config.ui.week_numbers.then(|| Span::styled(" Wk ", Style::default().fg(t.muted()).add_modifier(Modifier::BOLD)));
+    lines.push(Line::from(wk.into_iter().chain(hdrs).collect::<Vec<_>>()));
     lines.push(Line::from(Span::styled(
         "─".repeat(inner.width as usize),
         Style::default().fg(t.border()),
     )));
 
-    let first  = NaiveDate::from_ymd_opt(app.view_year, app.view_month, 1).unwrap();
+    let Some(first) = NaiveDate::from_ymd_opt(app.view_year, app.view_month, 1) else { return };
     let offset = first.weekday().num_days_from_monday() as i32;
     let total  = days_in_month(app.view_year, app.view_month) as i32;
     let today  = chrono::Local::now().date_naive();
@@ -145,13 +581,26 @@ fn draw_calendar(f: &mut Frame, app: &App, area: Rect) {
         let row_start = row * 7 - offset + 1;
         if row_start > total { break; }
 
-        let spans: Vec<Span> = (0..7i32).map(|col| {
+        let monday = add_days(first, (row * 7 - offset) as i64);
+        let gutter = week_cell(app, monday.map(|m| m.iso_week().week()));
+        let days   = (0..7i32).flat_map(|col| {
             let d = row * 7 + col - offset + 1;
             if d < 1 || d > total {
-                return Span::raw("    ");
+                return vec![Span::raw("    ")];
             }
-            let date  = NaiveDate::from_ymd_opt(app.view_year, app.view_month, d as u32).unwrap();
-            let label = format!(" {:2} ", d);
+            let Some(date) = NaiveDate::from_ymd_opt(app.view_year, app.view_month, d as u32) else {
+                return vec![Span::raw("    ")];
+            };
+            // A badge takes two columns, so the number moves left to make
+            // room; otherwise the last column holds the events' mark
+            let emoji = app.config.ui.emoji.then(|| {
+                app.month_holidays.iter().find(|h| h.date == date && (1..=2).contains(&h.emoji.width()))
+            }).flatten();
+            let (label, dot) = match (app.own_location_on(date), emoji) {
+                (Some(w), _)    => (format!("{d:>2}{}", pad(w.badge(), 2)), None),
+                (None, Some(h)) => (format!("{d:>2}{}", pad(&h.emoji, 2)), None),
+                (None, None)    => (format!(" {d:2}"), Some(app.day_density(date))),
+            };
 
             let style = if date == app.selected_date {
                 let (bg, fg) = t.selected_highlight();
@@ -159,15 +608,38 @@ fn draw_calendar(f: &mut Frame, app: &App, area: Rect) {
             } else if date == today {
                 let (bg, fg) = t.today_highlight();
                 Style::default().bg(bg).fg(fg).add_modifier(Modifier::BOLD)
+            } else if app.dim_holidays && app.month_holidays.iter().any(|h| h.date == date) {
+                Style::default().fg(t.warning_color()).add_modifier(Modifier::DIM)
+            } else if app.month_holidays.iter().any(|h| h.date == date && h.day_off) {
+                Style::default().fg(t.warning_color()).add_modifier(Modifier::BOLD)
+            } else if app.month_holidays.iter().any(|h| h.date == date) {
+                Style::default().fg(t.warning_color())
             } else if col >= 5 {
                 Style::default().fg(t.weekend_color())
             } else {
                 Style::default().fg(t.fg())
             };
-            Span::styled(label, style)
-        }).collect();
+            let style = if date <= today && habits::kept(&app.habits, &app.habit_checks, date) {
+                style.add_modifier(Modifier::UNDERLINED)
+            } else {
+                style
+            };
+            match dot {
+                None                   => vec![Span::styled(label, style)],
+                Some(None)             => vec![Span::styled(label, style), Span::styled(" ", style)],
+                Some(Some((n, color))) => vec![Span::styled(label, style), Span::styled(density_mark(n), style.fg(color))],
+            }
+        });
 
-        lines.push(Line::from(spans));
+        lines.push(Line::from(gutter.into_iter().chain(days).collect::<Vec<_>>()));
+        if app.config.ui.lunar_dates {
+            lines.push(lunar_footer(app, row * 7 - offset + 1, total));
+        }
+    }
+    lines.extend(forecast_strip(app, today, inner.width));
+    for ((year, month), top) in mini_months(app, inner) {
+        lines.resize(top as usize, Line::default());
+        lines.extend(mini_month(app, year, month, today, inner.width));
     }
 
     f.render_widget(
@@ -176,52 +648,532 @@ fn draw_calendar(f: &mut Frame, app: &App, area: Rect) {
     );
 }
 
+/// Along the calendar's bottom: each `[holidays]` country in the grid's
+/// holiday colour, or struck through while `V` hides it.
+fn holiday_legend(app: &App) -> Option<Line<'static>> {
+    let t         = &app.theme;
+    let countries = app.holidays.countries();
+    if countries.is_empty() { return None; }
+    let shown  = Style::default().fg(t.warning_color())
+        .add_modifier(if app.dim_holidays { Modifier::DIM } else { Modifier::BOLD });
+    let hidden = Style::default().fg(t.muted()).add_modifier(Modifier::CROSSED_OUT);
+    let mut spans = vec![Span::raw(" ")];
+    for (i, c) in countries.into_iter().enumerate() {
+        if i > 0 { spans.push(Span::styled(" · ", Style::default().fg(t.muted()))); }
+        spans.push(Span::styled(c.to_owned(), if app.holidays_shown(c) { shown } else { hidden }));
+    }
+    spans.push(Span::raw(" "));
+    Some(Line::from(spans))
+}
+
+/// The months either side of the grid's, each with the row it starts on,
+/// as many as fit under the grid and forecast: both, else the next alone
+/// (the one a month's end looks to), else none.
+fn mini_months(app: &App, inner: Rect) -> Vec<((i32, u32), u16)> {
+    let per_week = if app.config.ui.lunar_dates { 2 } else { 1 };
+    let weeks    = month_weeks(app.view_year, app.view_month).len() as u16;
+    let today    = chrono::Local::now().date_naive();
+    // A blank row between the grid (or forecast) and the first
+    let top      = 2 + weeks * per_week + forecast_strip(app, today, inner.width).len() as u16 + 1;
+    let room     = inner.height.saturating_sub(top);
+
+    let index  = app.view_year * 12 + app.view_month as i32 - 1;
+    let [prev, next] = [index - 1, index + 1].map(|i| (i.div_euclid(12), i.rem_euclid(12) as u32 + 1));
+    let height = |(y, m): (i32, u32)| 2 + month_weeks(y, m).len() as u16;
+    if height(prev) + height(next) <= room {
+        vec![(prev, top), (next, top + height(prev))]
+    } else if height(next) <= room {
+        vec![(next, top)]
+    } else {
+        Vec::new()
+    }
+}
+
+/// A mini month: a rule with its name, the weekdays, then its weeks, 3
+/// columns a day after the week-number gutter.
+fn mini_month(app: &App, year: i32, month: u32, today: NaiveDate, width: u16) -> Vec<Line<'static>> {
+    let t    = &app.theme;
+    let name = if year == app.view_year { month_name(month).to_owned() } else { format!("{} {year}", month_name(month)) };
+    let rule = |n: usize| Span::styled("─".repeat(n), Style::default().fg(t.border()));
+    let mut lines = vec![Line::from(vec![
+        rule(2),
+        Span::styled(format!(" {name} "), Style::default().fg(t.fg_dim()).add_modifier(Modifier::BOLD)),
+        rule((width as usize).saturating_sub(name.width() + 4)),
+    ])];
+
+    let hdrs = ["Mo","Tu","We","Th","Fr","Sa","Su"].iter().enumerate().map(|(i, d)| {
+        Span::styled(format!(" {d}"), Style::default().fg(if i >= 5 { t.weekend_color() } else { t.muted() }))
+    });
+    lines.push(Line::from(std::iter::once(Span::raw(" ".repeat(week_gutter(app) as usize))).chain(hdrs).collect::<Vec<_>>()));
+
+    for week in month_weeks(year, month) {
+        let monday = week.iter().flatten().next().map(|d| d.iso_week().week());
+        let gutter = week_cell(app, monday).unwrap_or_else(|| Span::raw(""));
+        let days   = week.into_iter().enumerate().map(|(col, date)| match date {
+            None       => Span::raw("   "),
+            Some(date) => {
+                let style = if date == today {
+                    let (bg, fg) = t.today_highlight();
+                    Style::default().bg(bg).fg(fg).add_modifier(Modifier::BOLD)
+                } else if col >= 5 {
+                    Style::default().fg(t.weekend_color())
+                } else {
+                    Style::default().fg(t.fg_dim())
+                };
+                Span::styled(format!("{:>3}", date.day()), style)
+            }
+        });
+        lines.push(Line::from(std::iter::once(gutter).chain(days).collect::<Vec<_>>()));
+    }
+    lines
+}
+
+/// The mini-month day under (x, y), or the 1st when on its title.
+fn mini_date_at(app: &App, inner: Rect, x: u16, y: u16) -> Option<NaiveDate> {
+    let row = y - inner.y;
+    let ((year, month), top) = mini_months(app, inner).into_iter()
+        .take_while(|&(_, top)| top <= row)
+        .last()?;
+    let weeks = month_weeks(year, month);
+    match row - top {
+        0 => NaiveDate::from_ymd_opt(year, month, 1),
+        1 => None,
+        r => {
+            let col = (x.checked_sub(inner.x + week_gutter(app))? / 3) as usize;
+            *weeks.get(r as usize - 2)?.get(col)?
+        }
+    }
+}
+
+/// A day cell's last column, by how many events it has: a dot each up to
+/// three, then the count raised (so "21⁵" doesn't read as 215), `⁺` past
+/// nine.
+fn density_mark(n: usize) -> &'static str {
+    const RAISED: [&str; 6] = ["⁴", "⁵", "⁶", "⁷", "⁸", "⁹"];
+    match n {
+        0     => " ",
+        1     => "·",
+        2     => "⁚",
+        3     => "⁖",
+        4..=9 => RAISED[n - 4],
+        _     => "⁺",
+    }
+}
+
+/// Lunar day under each cell of a week row (first day of a lunar month as d/m).
+fn lunar_footer(app: &App, first_day: i32, total: i32) -> Line<'static> {
+    let t = &app.theme;
+    let days = (first_day..first_day + 7).map(|d| {
+        let lunar = (1..=total).contains(&d)
+            .then(|| NaiveDate::from_ymd_opt(app.view_year, app.view_month, d as u32))
+            .flatten()
+            .and_then(lunar::solar_to_lunar);
+        match lunar {
+            None => Span::raw("    "),
+            Some(l) if l.day == 1 => Span::styled(
+                format!("{:>4}", format!("{}/{}{}", l.day, l.month, if l.leap { "+" } else { "" })),
+                Style::default().fg(t.accent()),
+            ),
+            Some(l) => Span::styled(
+                format!(" {:2} ", l.day),
+                Style::default().fg(if l.day == 15 { t.fg_dim() } else { t.muted() }),
+            ),
+        }
+    });
+    Line::from(week_cell(app, None).into_iter().chain(days).collect::<Vec<_>>())
+}
+
+/// The coming week's forecast under the grid, each day in its weekday's
+/// column: icon, high, low. Today's column is highlighted, so where the
+/// week wraps round is plain.
+fn forecast_strip(app: &App, today: NaiveDate, width: u16) -> Vec<Line<'static>> {
+    let t    = &app.theme;
+    let week = app.forecast.iter().filter(|d| d.date >= today && (d.date - today).num_days() < 7);
+    let mut cols: [Option<&Forecast>; 7] = [None; 7];
+    for d in week { cols[d.date.weekday().num_days_from_monday() as usize] = Some(d); }
+    if cols.iter().all(Option::is_none) { return Vec::new(); }
+
+    let today_col = today.weekday().num_days_from_monday() as usize;
+    let row = |cell: &dyn Fn(&Forecast) -> String, style: Style| {
+        let days = cols.iter().enumerate().map(|(i, d)| {
+            let style = if i == today_col { style.fg(t.accent()).add_modifier(Modifier::BOLD) } else { style };
+            match d {
+                Some(d) => Span::styled(cell(d), style),
+                None    => Span::raw("    "),
+            }
+        });
+        Line::from(week_cell(app, None).into_iter().chain(days).collect::<Vec<_>>())
+    };
+    vec![
+        Line::from(Span::styled("─".repeat(width as usize), Style::default().fg(t.border()))),
+        row(&|d| format!(" {} ", weather::icon(d.code)), Style::default()),
+        row(&|d| format!("{:>4}", weather::degrees(d.high)), Style::default().fg(t.fg())),
+        row(&|d| format!("{:>4}", weather::degrees(d.low)), Style::default().fg(t.fg_dim())),
+    ]
+}
+
 // ─── Events panel ─────────────────────────────────────────────────────────────
 
 fn draw_events(f: &mut Frame, app: &App, area: Rect) {
-    let t       = &app.theme;
     let focused = app.active_panel == Panel::EventList;
+    let block   = events_block(app, focused, " ● Events — ");
+
+    if app.loading {
+        f.render_widget(skeleton(block, &app.theme), area);
+        return;
+    }
+    if app.events.is_empty() && app.feed_events.is_empty() {
+        f.render_widget(
+            Paragraph::new("  No events").block(block).style(Style::default().fg(app.theme.fg_dim())),
+            area,
+        );
+        return;
+    }
+
+    let (marked, _) = app.visual_selection();
+    let mut items: Vec<ListItem> = app.events.iter().enumerate()
+        .map(|(i, ev)| event_item(app, i, ev, focused, marked.contains(&i)))
+        .chain(feed_items(app))
+        .collect();
+
+    let pin     = pinned_layout(app, area, app.pinned_events());
+    let cursor  = focused.then_some(app.event_cursor);
+    let rest    = items.split_off(pin.rows);
+    f.render_widget(block, area);
+    draw_pinned(f, app, &pin, items, cursor);
+
+    let heights = &event_heights(app)[pin.rows..];
+    let rows    = pin.list.height;
+    let first   = scroll_to(heights, pin.list_row(app.event_cursor).unwrap_or(app.ui.event_scroll.get()), app.ui.event_scroll.get(), rows);
+    app.ui.event_scroll.set(first);
+    app.ui.event_rows.set(rows);
+
+    let mut state = ListState::default()
+        .with_offset(first)
+        .with_selected(cursor.and_then(|c| pin.list_row(c)));
+    f.render_stateful_widget(List::new(rest).highlight_symbol("▶ "), pin.list, &mut state);
+    draw_scrollbar(f, app, pin.frame(area), heights, first);
+}
+
+// ─── Pinned all-day band ─────────────────────────────────────────────────────
+
+/// Where a day list goes when `pin_all_day` holds its first `rows` entries
+/// (the all-day events) and the day's holidays in a band above the rest.
+/// Without pinning the band is empty and the list has the whole panel.
+struct Pinned {
+    band:     Rect,
+    list:     Rect,
+    holidays: usize,
+    rows:     usize,
+}
+
+impl Pinned {
+    /// Row of the scrolling list showing entry `i`, if it's not pinned.
+    fn list_row(&self, i: usize) -> Option<usize> {
+        i.checked_sub(self.rows)
+    }
+
+    /// The list's rows with the panel border above and below, as the
+    /// scrollbar wants them.
+    fn frame(&self, area: Rect) -> Rect {
+        Rect { y: self.list.y - 1, height: self.list.height + 2, ..area }
+    }
+
+    /// The entry under row `y`: in the band, or in the list scrolled to `first`.
+    fn index_at(&self, y: u16, heights: &[u16], first: usize) -> Option<usize> {
+        if y >= self.band.y && y < self.band.y + self.band.height {
+            let row = (y - self.band.y) as usize;
+            return row.checked_sub(self.holidays).filter(|&i| i < self.rows);
+        }
+        list_index_in(&heights[self.rows.min(heights.len())..], first, self.list, y).map(|i| i + self.rows)
+    }
+}
+
+fn pinned_layout(app: &App, area: Rect, rows: usize) -> Pinned {
+    let inner    = Block::default().borders(Borders::ALL).inner(area);
+    let holidays = if app.pin_all_day { app.selected_holidays().count() } else { 0 };
+    if holidays + rows == 0 {
+        return Pinned { band: Rect { height: 0, ..inner }, list: inner, holidays: 0, rows: 0 };
+    }
+    // Never more than half the panel; a line under it sets it apart
+    let height = ((holidays + rows) as u16 + 1).min(inner.height / 2);
+    let split  = Layout::default().direction(Direction::Vertical)
+        .constraints([Constraint::Length(height), Constraint::Min(0)]).split(inner);
+    Pinned { band: split[0], list: split[1], holidays, rows }
+}
+
+/// The band: holidays, then the pinned entries, on the secondary background.
+fn draw_pinned(f: &mut Frame, app: &App, pin: &Pinned, pinned: Vec<ListItem>, cursor: Option<usize>) {
+    if pin.band.height == 0 { return; }
+    let t     = &app.theme;
+    let items: Vec<ListItem> = app.selected_holidays()
+        .map(|h| {
+            let style = Style::default().fg(t.warning_color());
+            ListItem::new(Line::from(vec![
+                Span::styled(format!(" {} ", h.emoji), style),
+                Span::styled(h.name.to_string(), if h.day_off { style.add_modifier(Modifier::BOLD) } else { style }),
+                Span::styled(format!(" ({})", h.country), Style::default().fg(t.fg_dim())),
+            ]))
+        })
+        .chain(pinned)
+        .collect();
+    let rows = Layout::default().direction(Direction::Vertical)
+        .constraints([Constraint::Min(0), Constraint::Length(1)]).split(pin.band);
+    let mut state = ListState::default()
+        .with_selected(cursor.filter(|&c| c < pin.rows).map(|c| c + pin.holidays));
+    f.render_stateful_widget(
+        List::new(items).style(Style::default().bg(t.bg2())).highlight_symbol("▶ "),
+        rows[0],
+        &mut state,
+    );
+    f.render_widget(
+        Paragraph::new(Span::styled("─".repeat(rows[1].width as usize), Style::default().fg(t.border()))),
+        rows[1],
+    );
+}
+
+/// The events panel's frame: `label` and the selected date, working
+/// locations, holidays along the bottom and the tag filter on the right.
+fn events_block<'a>(app: &App, focused: bool, label: &str) -> Block<'a> {
+    let t       = &app.theme;
     let bs      = Style::default().fg(if focused { t.border_active() } else { t.border() });
     let date_s  = app.selected_date.format("%A, %B %-d").to_string();
-    let title   = Line::from(Span::styled(
-        format!(" ● Events — {date_s} "),
+    let mut title = Line::from(Span::styled(
+        format!("{label}{date_s} "),
         Style::default().fg(t.accent()),
     ));
+    // Working locations: yours unlabelled, colleagues' by calendar name
+    for (i, w) in app.locations_on(app.selected_date).into_iter().enumerate() {
+        let who = if i == 0 && app.own_location_on(app.selected_date).is_some() {
+            String::new()
+        } else {
+            format!("{}: ", w.calendar_id.split('@').next().unwrap_or(&w.calendar_id))
+        };
+        title.spans.push(Span::styled(
+            format!("{} {who}{} ", w.badge(), w.describe()),
+            Style::default().fg(t.fg_dim()),
+        ));
+    }
 
-    let block = Block::default()
+    let mut block = Block::default()
         .title(Title::from(title))
         .borders(Borders::ALL)
         .border_type(BorderType::Rounded)
         .border_style(bs)
         .style(Style::default().bg(t.bg()));
 
-    if app.events.is_empty() {
+    // Pinned holidays are listed in the band instead
+    let holidays: Vec<String> = app.selected_holidays()
+        .filter(|_| !app.pin_all_day)
+        .map(|h| format!("{} {} ({})", h.emoji, h.name, h.country))
+        .collect();
+    if !holidays.is_empty() {
+        block = block.title(Title::from(Span::styled(
+            format!(" {} ", holidays.join(" · ")),
+            Style::default().fg(t.warning_color()),
+        )).position(Position::Bottom));
+    }
+
+    if let Some(ref tag) = app.tag_filter {
+        block = block.title(Title::from(Span::styled(
+            format!(" #{tag} only · f "),
+            Style::default().fg(app.tag_color(Some(tag))),
+        )).alignment(Alignment::Right));
+    }
+    block
+}
+
+/// One day-list row (two with lead time, or the selected event's DST note).
+fn event_item<'a>(app: &App, i: usize, ev: &Event, focused: bool, marked: bool) -> ListItem<'a> {
+    let t        = &app.theme;
+    let time     = if ev.all_day {
+        "all-day".to_owned()
+    } else {
+        ev.start.format("%H:%M").to_string()
+    };
+    let sel      = (i == app.event_cursor && focused) || marked;
+    let (bg, fg) = t.selected_highlight();
+    let ts       = if sel { Style::default().bg(bg).fg(fg) } else { Style::default().fg(t.fg()) };
+    let mut spans = vec![
+        Span::styled(" ● ", Style::default().fg(app.event_color(ev))),
+        Span::styled(format!("{time} "), Style::default().fg(t.fg_dim())),
+        Span::styled(ev.title.clone(), ts),
+    ];
+    if let Some(ref tag) = ev.tag {
+        spans.push(Span::styled(format!(" #{tag}"), Style::default().fg(t.fg_dim())));
+    }
+    if ev.time_entry {
+        spans.push(Span::styled(" ⏱", Style::default().fg(t.success_color())));
+    }
+    if ev.pto {
+        spans.push(Span::styled(" PTO", Style::default().fg(t.warning_color()).add_modifier(Modifier::BOLD)));
+    }
+    if ev.meeting_url().is_some() {
+        spans.push(Span::raw(" 📹"));
+    }
+    if app.day_conflicts.contains(&ev.id) {
+        spans.push(Span::styled(" ⚠ overlap", Style::default().fg(t.warning_color())));
+    }
+    if let Some(a) = ev.attendance {
+        spans.push(Span::styled(format!(" {} {}", a.glyph(), a.as_str()), Style::default().fg(t.fg_dim())));
+    }
+    let dst = app.event_dst(ev);
+    if dst.is_some() {
+        spans.push(Span::styled(" ⚠ DST", Style::default().fg(t.warning_color())));
+    }
+    let mut lines = Vec::new();
+    if has_lead(ev) {
+        // One shade block per quarter hour of travel or prep
+        let shade = "░".repeat(((ev.lead_minutes + 14) / 15).min(8) as usize);
+        lines.push(Line::from(vec![
+            Span::styled(format!("   {} ", ev.lead_start().format("%H:%M")), Style::default().fg(t.muted())),
+            Span::styled(shade, Style::default().fg(app.event_color(ev))),
+            Span::styled(format!(" {} min lead", ev.lead_minutes), Style::default().fg(t.muted())),
+        ]));
+    }
+    lines.push(Line::from(spans));
+    // Selected event: spell out the wall times on both sides of the change
+    if let (Some(shift), true) = (dst, i == app.event_cursor) {
+        lines.push(Line::from(Span::styled(
+            format!("     {} ({})", shift.wall_times(ev.start, ev.end), shift.describe()),
+            Style::default().fg(t.warning_color()),
+        )));
+    }
+    ListItem::new(lines)
+}
+
+/// Subscribed feed events: read-only, listed after the day's own events.
+fn feed_items<'a>(app: &'a App) -> impl Iterator<Item = ListItem<'a>> + 'a {
+    let t          = &app.theme;
+    let feed_style = Style::default().fg(t.muted()).add_modifier(Modifier::ITALIC);
+    app.feed_events.iter().map(move |fe| {
+        let time = if fe.all_day { "all-day".to_owned() } else { fe.start.format("%H:%M").to_string() };
+        ListItem::new(Line::from(vec![
+            Span::styled(" ◇ ", feed_style),
+            Span::styled(format!("{time} "), Style::default().fg(t.fg_dim())),
+            Span::styled(fe.title.clone(), feed_style),
+            Span::styled(format!("  {}", fe.feed), Style::default().fg(t.fg_dim())),
+        ]))
+    })
+}
+
+// ─── Agenda pane (compact layout) ────────────────────────────────────────────
+
+/// The day's events and due tasks in one list. Focus is the events or tasks
+/// panel's, whichever kind the selected row is, so their keys apply.
+fn draw_agenda(f: &mut Frame, app: &App, area: Rect) {
+    let focused = matches!(app.active_panel, Panel::EventList | Panel::TaskList);
+    let block   = events_block(app, focused, " ● ");
+
+    if app.loading {
+        f.render_widget(skeleton(block, &app.theme), area);
+        return;
+    }
+    let items = app.agenda();
+    if items.is_empty() && app.feed_events.is_empty() {
         f.render_widget(
-            Paragraph::new("  No events").block(block).style(Style::default().fg(t.fg_dim())),
+            Paragraph::new("  Nothing on this day").block(block).style(Style::default().fg(app.theme.fg_dim())),
             area,
         );
         return;
     }
 
-    let items: Vec<ListItem> = app.events.iter().enumerate().map(|(i, ev)| {
-        let time   = if ev.all_day {
-            "all-day".to_owned()
-        } else {
-            ev.start.format("%H:%M").to_string()
-        };
-        let sel    = i == app.event_cursor && focused;
-        let (bg, fg) = t.selected_highlight();
-        let ts     = if sel { Style::default().bg(bg).fg(fg) } else { Style::default().fg(t.fg()) };
-        ListItem::new(Line::from(vec![
-            Span::styled(" ● ", Style::default().fg(t.event_color())),
-            Span::styled(format!("{time} "), Style::default().fg(t.fg_dim())),
-            Span::styled(ev.title.clone(), ts),
-        ]))
+    let today = chrono::Local::now().date_naive();
+    let (marked_events, marked_tasks) = app.visual_selection();
+    let mut rows: Vec<ListItem> = items.iter()
+        .map(|item| match *item {
+            agenda::Item::Event(i) => event_item(
+                app, i, &app.events[i], app.active_panel == Panel::EventList, marked_events.contains(&i),
+            ),
+            agenda::Item::Task(i)  => task_item(
+                app, i, &app.tasks[i], app.active_panel == Panel::TaskList, today, marked_tasks.contains(&i),
+            ),
+        })
+        .chain(feed_items(app))
+        .collect();
+
+    let cursor  = app.agenda_cursor();
+    let pin     = pinned_layout(app, area, app.pinned_agenda(&items));
+    let rest    = rows.split_off(pin.rows);
+    f.render_widget(block, area);
+    draw_pinned(f, app, &pin, rows, cursor.filter(|_| focused));
+
+    let heights = &agenda_heights(app, &items)[pin.rows..];
+    let height  = pin.list.height;
+    let prev    = app.ui.agenda_scroll.get();
+    let first   = scroll_to(heights, cursor.and_then(|c| pin.list_row(c)).unwrap_or(prev), prev, height);
+    app.ui.agenda_scroll.set(first);
+    app.ui.agenda_rows.set(height);
+
+    let mut state = ListState::default()
+        .with_offset(first)
+        .with_selected(cursor.filter(|_| focused).and_then(|c| pin.list_row(c)));
+    f.render_stateful_widget(List::new(rest).highlight_symbol("▶ "), pin.list, &mut state);
+    draw_scrollbar(f, app, pin.frame(area), heights, first);
+}
+
+// ─── Meals strip ──────────────────────────────────────────────────────────────
+
+const MEAL_SLOTS: [&str; 3] = ["Breakfast", "Lunch", "Dinner"];
+
+fn draw_meals(f: &mut Frame, app: &App, area: Rect) {
+    let t     = &app.theme;
+    let title = Line::from(Span::styled(" 🍽 Meals ", Style::default().fg(t.accent())));
+    let block = Block::default()
+        .title(Title::from(title))
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .border_style(Style::default().fg(t.border()))
+        .style(Style::default().bg(t.bg()));
+
+    let lines: Vec<Line> = MEAL_SLOTS.iter().enumerate().map(|(slot, name)| {
+        let meal = app.meals.iter()
+            .find(|m| m.date == app.selected_date && m.slot == slot as i64);
+        Line::from(vec![
+            Span::styled(format!(" {name:<10}"), Style::default().fg(t.fg_dim())),
+            match meal {
+                Some(m) => Span::styled(m.text.clone(), Style::default().fg(t.fg())),
+                None    => Span::styled("—", Style::default().fg(t.muted())),
+            },
+        ])
+    }).collect();
+
+    f.render_widget(Paragraph::new(lines).block(block), area);
+}
+
+// ─── Habits strip ────────────────────────────────────────────────────────────
+
+/// Today's habits, numbered for their `Alt+N` toggle, with running streaks.
+fn draw_habits(f: &mut Frame, app: &App, area: Rect) {
+    let t     = &app.theme;
+    let title = Line::from(Span::styled(" Habits today · Alt+1–9 ", Style::default().fg(t.accent())));
+    let block = Block::default()
+        .title(Title::from(title))
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .border_style(Style::default().fg(t.border()))
+        .style(Style::default().bg(t.bg()));
+
+    let today = chrono::Local::now().date_naive();
+    let lines: Vec<Line> = app.habits_today().iter().enumerate().map(|(i, h)| {
+        let done   = habits::done(h, &app.habit_checks, today);
+        let streak = habits::streak(h, &app.habit_checks, today);
+        let mut spans = vec![
+            Span::styled(format!(" {} ", i + 1), Style::default().fg(t.fg_dim())),
+            if done {
+                Span::styled("✓ ", Style::default().fg(t.success_color()))
+            } else {
+                Span::styled("· ", Style::default().fg(t.muted()))
+            },
+            Span::styled(h.name.clone(), Style::default().fg(if done { t.fg_dim() } else { t.fg() })),
+        ];
+        if streak > 1 {
+            spans.push(Span::styled(format!("  🔥{streak}"), Style::default().fg(t.warning_color())));
+        }
+        Line::from(spans)
     }).collect();
 
-    let mut state = ListState::default();
-    state.select(if focused { Some(app.event_cursor) } else { None });
-    f.render_stateful_widget(List::new(items).block(block).highlight_symbol("▶ "), area, &mut state);
+    f.render_widget(Paragraph::new(lines).block(block), area);
 }
 
 // ─── Tasks panel ──────────────────────────────────────────────────────────────
@@ -230,69 +1182,171 @@ fn draw_tasks(f: &mut Frame, app: &App, area: Rect) {
     let t       = &app.theme;
     let focused = app.active_panel == Panel::TaskList;
     let bs      = Style::default().fg(if focused { t.border_active() } else { t.border() });
-    let title   = Line::from(Span::styled(" ○ Tasks ", Style::default().fg(t.accent())));
+    let today   = chrono::Local::now().date_naive();
+    let mut title = Line::from(Span::styled(" ○ Tasks ", Style::default().fg(t.accent())));
+    let late    = tasks::overdue(&app.tasks, today).len();
+    if late > 0 {
+        title.spans.push(Span::styled(format!("{late} overdue "), Style::default().fg(t.warning_color())));
+    }
+    let mode    = format!(" {} · by {} · f s ", app.task_filter.label(), app.task_sort.label());
 
     let block = Block::default()
         .title(Title::from(title))
+        .title(Title::from(Span::styled(mode, Style::default().fg(t.fg_dim()))).alignment(Alignment::Right))
         .borders(Borders::ALL)
         .border_type(BorderType::Rounded)
         .border_style(bs)
         .style(Style::default().bg(t.bg()));
 
+    if app.loading {
+        f.render_widget(skeleton(block, t), area);
+        return;
+    }
     if app.tasks.is_empty() {
+        let msg = match app.task_filter {
+            TaskFilter::All => "  No tasks".to_owned(),
+            ref filter      => format!("  No tasks match {}", filter.label()),
+        };
         f.render_widget(
-            Paragraph::new("  No tasks").block(block).style(Style::default().fg(t.fg_dim())),
+            Paragraph::new(msg).block(block).style(Style::default().fg(t.fg_dim())),
             area,
         );
         return;
     }
 
-    let items: Vec<ListItem> = app.tasks.iter().enumerate().map(|(i, task)| {
-        let check  = if task.completed { " ✔ " } else { " ○ " };
-        let cs     = if task.completed {
-            Style::default().fg(t.event_color())
-        } else {
-            Style::default().fg(t.fg_dim())
-        };
-        let sel      = i == app.task_cursor && focused;
-        let (bg, fg) = t.selected_highlight();
-        let ts       = if task.completed {
-            Style::default().fg(t.fg_dim()).add_modifier(Modifier::CROSSED_OUT)
-        } else if sel {
-            Style::default().bg(bg).fg(fg)
-        } else {
-            Style::default().fg(t.fg())
-        };
-        ListItem::new(Line::from(vec![
-            Span::styled(check, cs),
-            Span::styled(task.title.clone(), ts),
-        ]))
-    }).collect();
+    let (_, marked) = app.visual_selection();
+    let items: Vec<ListItem> = app.tasks.iter().enumerate()
+        .map(|(i, task)| task_item(app, i, task, focused, today, marked.contains(&i)))
+        .collect();
 
-    let mut state = ListState::default();
-    state.select(if focused { Some(app.task_cursor) } else { None });
+    let heights = vec![1; app.tasks.len()];
+    let rows    = area.height.saturating_sub(2);
+    let first   = scroll_to(&heights, app.task_cursor, app.ui.task_scroll.get(), rows);
+    app.ui.task_scroll.set(first);
+    app.ui.task_rows.set(rows);
+
+    let mut state = ListState::default()
+        .with_offset(first)
+        .with_selected(if focused { Some(app.task_cursor) } else { None });
     f.render_stateful_widget(List::new(items).block(block), area, &mut state);
+    draw_scrollbar(f, app, area, &heights, first);
 }
 
-// ─── Status bar ───────────────────────────────────────────────────────────────
-
-fn draw_statusbar(f: &mut Frame, app: &App, area: Rect) {
-    let t = &app.theme;
-    let (mode_str, mode_style) = match app.ui.input_mode {
-        InputMode::Normal => (" NORMAL ", Style::default().bg(t.accent()).fg(t.bg()).add_modifier(Modifier::BOLD)),
-        InputMode::Insert => (" INSERT ", Style::default().bg(t.event_color()).fg(t.bg()).add_modifier(Modifier::BOLD)),
+fn task_item<'a>(
+    app: &App, i: usize, task: &Task, focused: bool, today: NaiveDate, marked: bool,
+) -> ListItem<'a> {
+    let t      = &app.theme;
+    let check  = if task.completed { " ✔ " } else { " ○ " };
+    let cs     = if task.completed {
+        Style::default().fg(t.event_color())
+    } else {
+        Style::default().fg(t.fg_dim())
     };
-    let bar = Paragraph::new(Line::from(vec![
-        Span::styled(mode_str, mode_style),
-        Span::styled(
-            "  hjkl:nav  n:event  N:task  Space:done  d:del  Tab:panels  [:prev  ]:next  t:today  ?:help  ^s:sync  q:quit",
-            Style::default().fg(t.fg_dim()),
-        ),
-        Span::styled(
-            format!("  {}", app.sync_status),
-            Style::default().fg(t.muted()).add_modifier(Modifier::ITALIC),
+    let sel      = (i == app.task_cursor && focused) || marked;
+    let (bg, fg) = t.selected_highlight();
+    let ts       = if task.completed {
+        Style::default().fg(t.fg_dim()).add_modifier(Modifier::CROSSED_OUT)
+    } else if sel {
+        Style::default().bg(bg).fg(fg)
+    } else {
+        Style::default().fg(t.fg())
+    };
+    let mut spans = vec![
+        Span::styled(check, cs),
+        Span::styled(task.title.clone(), ts),
+    ];
+    if task.priority > 0 && !task.completed {
+        let bang = "!".repeat(task.priority.min(tasks::MAX_PRIORITY) as usize);
+        spans.push(Span::styled(format!(" {bang}"), Style::default().fg(t.warning_color())));
+    }
+    if task.repeat.is_some() {
+        spans.push(Span::styled(" ↻", Style::default().fg(t.fg_dim())));
+    }
+    if task.estimate_minutes > 0 && !task.completed {
+        spans.push(Span::styled(format!(" ~{}", tasks::estimate_label(task.estimate_minutes)), Style::default().fg(t.fg_dim())));
+    }
+    for tag in &task.tags {
+        spans.push(Span::styled(format!(" #{tag}"), Style::default().fg(t.fg_dim())));
+    }
+    if let Some(due) = task.due.filter(|_| !task.completed) {
+        let ds = if tasks::is_overdue(task, today) {
+            Style::default().fg(t.warning_color())
+        } else {
+            Style::default().fg(t.fg_dim())
+        };
+        spans.push(Span::styled(format!(" · {}", due.format("%b %-d")), ds));
+    }
+    ListItem::new(Line::from(spans))
+}
+
+/// Placeholder rows for a list whose data hasn't loaded yet.
+fn skeleton<'a>(block: Block<'a>, t: &ThemeConfig) -> Paragraph<'a> {
+    let lines: Vec<Line> = [14, 22, 9].iter()
+        .map(|&w| Line::from(format!("  {}", "░".repeat(w))))
+        .collect();
+    Paragraph::new(lines).block(block).style(Style::default().fg(t.border()))
+}
+
+// ─── Status bar ───────────────────────────────────────────────────────────────
+
+/// Key hints in the status bar; each one can also be clicked.
+const STATUS_HINTS: [(&str, &str); 12] = [
+    ("hjkl", "nav"), ("n", "event"), ("N", "task"), ("Space", "done"), ("d", "del"),
+    ("Tab", "panels"), ("[", "prev"), ("]", "next"), ("t", "today"), ("?", "help"),
+    ("^s", "sync"), ("q", "quit"),
+];
+
+/// The status bar's spans, each tagged with the key it stands for when
+/// clicked (the `key` half of a hint, or "Q" for the review counter).
+fn status_spans(app: &App) -> Vec<(Option<&'static str>, Span<'static>)> {
+    let t   = &app.theme;
+    let dim = Style::default().fg(t.fg_dim());
+    let (mode_str, mode_style) = match app.ui.input_mode {
+        InputMode::Normal if app.ui.visual.is_some() => (
+            " VISUAL ", Style::default().bg(t.warning_color()).fg(t.bg()).add_modifier(Modifier::BOLD),
         ),
-    ])).style(Style::default().bg(t.bg2()));
+        InputMode::Normal => (" NORMAL ", Style::default().bg(t.accent()).fg(t.bg()).add_modifier(Modifier::BOLD)),
+        InputMode::Insert => (" INSERT ", Style::default().bg(t.event_color()).fg(t.bg()).add_modifier(Modifier::BOLD)),
+    };
+
+    let mut spans = vec![(None, Span::styled(mode_str, mode_style))];
+    if let Some(n) = app.ui.count {
+        spans.push((None, Span::styled(format!(" {n} "), Style::default().fg(t.accent()).add_modifier(Modifier::BOLD))));
+    }
+    if let Some(ref p) = app.pomodoro {
+        spans.push((None, Span::styled(
+            format!(" 🍅 {} ", p.countdown()),
+            Style::default().bg(t.error_color()).fg(t.bg()).add_modifier(Modifier::BOLD),
+        )));
+    }
+    if let Some(ref timer) = app.timer {
+        let now = chrono::Local::now().naive_local().and_utc();
+        spans.push((None, Span::styled(
+            format!(" ⏱ {} ", timelog::running_for(timer, now)),
+            Style::default().bg(t.success_color()).fg(t.bg()).add_modifier(Modifier::BOLD),
+        )));
+    }
+    for (key, label) in STATUS_HINTS {
+        spans.push((None, Span::raw("  ")));
+        spans.push((Some(key), Span::styled(format!("{key}:{label}"), dim)));
+    }
+    spans.push((None, Span::styled(
+        format!("  {}", app.sync_status),
+        Style::default().fg(t.muted()).add_modifier(Modifier::ITALIC),
+    )));
+    if !app.quarantine.is_empty() {
+        spans.push((None, Span::raw("  ")));
+        spans.push((Some("Q"), Span::styled(
+            format!("⚠ {} to review (Q)", app.quarantine.len()),
+            Style::default().fg(t.warning_color()),
+        )));
+    }
+    spans
+}
+
+fn draw_statusbar(f: &mut Frame, app: &App, area: Rect) {
+    let spans: Vec<Span> = status_spans(app).into_iter().map(|(_, s)| s).collect();
+    let bar = Paragraph::new(Line::from(spans)).style(Style::default().bg(app.theme.bg2()));
     f.render_widget(bar, area);
 }
 
@@ -300,7 +1354,7 @@ fn draw_statusbar(f: &mut Frame, app: &App, area: Rect) {
 
 fn draw_event_form(f: &mut Frame, area: Rect, app: &App) {
     let t    = &app.theme;
-    let rect = centered(60, 50, area);
+    let rect = centered(60, 60, area);
     f.render_widget(Clear, rect);
 
     let block = Block::default()
@@ -325,8 +1379,10 @@ fn draw_event_form(f: &mut Frame, area: Rect, app: &App) {
     let sel = Style::default().bg(sel_bg).fg(sel_fg).add_modifier(Modifier::BOLD);
 
     let title_active = *step == EventFormStep::Title;
+    let tag_active   = *step == EventFormStep::Tag;
     let start_active = *step == EventFormStep::StartTime;
     let end_active   = *step == EventFormStep::EndTime;
+    let guest_active = *step == EventFormStep::Guests;
     let hour_focus   = app.ui.time_field == TimeField::Hour;
 
     // ── Title row ────────────────────────────────────────────────────────────
@@ -341,9 +1397,35 @@ fn draw_event_form(f: &mut Frame, area: Rect, app: &App) {
         Span::styled(title_val,    if title_active { fg  } else { dim }),
     ]);
 
+    // ── Tag row, with the known tags in their colours while it's active ──────
+    let tag_prefix = if tag_active { "▶ Tag    " } else { "  Tag    " };
+    let tag_name   = app.ui.event_tag.trim().trim_start_matches('#').to_lowercase();
+    let mut tag_line = Line::from(vec![
+        Span::styled(tag_prefix, if tag_active { acc } else { dim }),
+        Span::styled(
+            app.ui.event_tag.clone(),
+            if tag_name.is_empty() { dim } else { Style::default().fg(app.tag_color(Some(&tag_name))) },
+        ),
+    ]);
+    if tag_active { tag_line.spans.push(Span::styled("█", fg)); }
+    match EVENT_COLORS.get(app.ui.event_color.wrapping_sub(1)) {
+        Some((name, hex)) => tag_line.spans.push(Span::styled(format!("   ● {name}"), Style::default().fg(hex_to_color(hex)))),
+        None if tag_active => tag_line.spans.push(Span::styled("   ● calendar colour", dim)),
+        None => {}
+    }
+    let mut known_tags = Line::from(Span::styled("         ", dim));
+    if tag_active {
+        for tag in &app.tags {
+            known_tags.spans.push(Span::styled(
+                format!("#{} ", tag.name),
+                Style::default().fg(t.tag_color(&tag.color)),
+            ));
+        }
+    }
+
     // ── Start time row ───────────────────────────────────────────────────────
     let start_prefix = if start_active { "▶ Start  " } else { "  Start  " };
-    let start_line: Line = if start_active {
+    let mut start_line: Line = if start_active {
         Line::from(vec![
             Span::styled(start_prefix, acc),
             Span::styled(
@@ -366,6 +1448,14 @@ fn draw_event_form(f: &mut Frame, area: Rect, app: &App) {
         ])
     };
 
+    if app.ui.event_lead > 0 {
+        let from = (app.ui.event_start_h * 60 + app.ui.event_start_m) as i64 - app.ui.event_lead;
+        start_line.spans.push(Span::styled(
+            format!("   ░ {} min lead, from {:02}:{:02}", app.ui.event_lead, from.rem_euclid(1440) / 60, from.rem_euclid(60)),
+            if start_active { fg } else { dim },
+        ));
+    }
+
     // ── End time row ─────────────────────────────────────────────────────────
     let end_prefix = if end_active { "▶ End    " } else { "  End    " };
     let end_line: Line = if end_active {
@@ -391,21 +1481,86 @@ fn draw_event_form(f: &mut Frame, area: Rect, app: &App) {
         ])
     };
 
+    // ── Guests row, then each guest's availability once checked ──────────────
+    let guest_prefix = if guest_active { "▶ Guests " } else { "  Guests " };
+    let guest_line = Line::from(vec![
+        Span::styled(guest_prefix, if guest_active { acc } else { dim }),
+        Span::styled(
+            format!("{}{}", app.ui.event_guests, if guest_active { "█" } else { "" }),
+            if guest_active { fg } else { dim },
+        ),
+    ]);
+    let mut guest_status: Vec<Line> = Vec::new();
+    match &app.ui.guest_check {
+        GuestCheck::NotChecked => {}
+        GuestCheck::Pending    => guest_status.push(Line::from(Span::styled("         ⟳ checking…", dim))),
+        GuestCheck::Failed(e)  => guest_status.push(Line::from(Span::styled(
+            format!("         ✗ {e}"), Style::default().fg(t.warning_color()),
+        ))),
+        GuestCheck::Done(list) => for a in list {
+            let (mark, note, style) = match (&a.error, a.busy.first()) {
+                (Some(reason), _) => ("?", format!("unknown ({reason})"), dim),
+                (None, Some((s, e))) => (
+                    "⚠", format!("busy {}–{}", s.format("%H:%M"), e.format("%H:%M")),
+                    Style::default().fg(t.warning_color()),
+                ),
+                (None, None) => ("✓", "free".to_owned(), Style::default().fg(t.event_color())),
+            };
+            guest_status.push(Line::from(Span::styled(format!("         {mark} {}  {note}", a.email), style)));
+        },
+    }
+
+    // ── Overlapping events, listed by the first Enter on save ────────────────
+    let warn = Style::default().fg(t.warning_color());
+    let mut conflict_lines: Vec<Line> = Vec::new();
+    if let Some(ref clashes) = app.ui.event_conflicts {
+        conflict_lines.push(Line::from(Span::styled("  ⚠ Overlaps:", warn)));
+        for ev in clashes {
+            conflict_lines.push(Line::from(Span::styled(
+                format!("    {}–{}  {}", ev.start.format("%H:%M"), ev.end.format("%H:%M"), ev.title),
+                warn,
+            )));
+        }
+    }
+
+    // ── DST warning ──────────────────────────────────────────────────────────
+    let dst_line = match (step, app.form_dst()) {
+        (EventFormStep::Title | EventFormStep::Tag, _) | (_, None) => Line::from(""),
+        (_, Some(shift)) => Line::from(Span::styled(
+            format!("  ⚠ DST: {} during this event", shift.describe()),
+            Style::default().fg(t.warning_color()),
+        )),
+    };
+
     // ── Hint line ────────────────────────────────────────────────────────────
     let hint: Line = match step {
         EventFormStep::Title =>
-            Line::from(Span::styled("  Enter: set time   Esc: cancel", dim)),
+            Line::from(Span::styled("  Enter: set tag   Esc: cancel", dim)),
+        EventFormStep::Tag =>
+            Line::from(Span::styled("  Optional   Tab: next known tag   ←→: colour   Enter: set time", dim)),
         EventFormStep::StartTime =>
-            Line::from(Span::styled("  ↑↓ adjust   ←→ hour/min   Enter: set end", dim)),
+            Line::from(Span::styled("  ↑↓ adjust   ←→ hour/min   +/-: lead time   Enter: set end", dim)),
         EventFormStep::EndTime =>
-            Line::from(Span::styled("  ↑↓ adjust   ←→ hour/min   Enter: save", dim)),
+            Line::from(Span::styled("  ↑↓ adjust   ←→ hour/min   Enter: guests", dim)),
+        EventFormStep::Guests => Line::from(Span::styled(
+            match app.ui.guest_check {
+                GuestCheck::NotChecked if !app.ui.event_guests.trim().is_empty() && app.sync.is_some() =>
+                    "  Emails, comma-separated   Enter: check guests   Esc: cancel",
+                _ if app.ui.event_conflicts.is_some() =>
+                    "  Emails, comma-separated   Enter: save anyway   Esc: cancel",
+                _ => "  Emails, comma-separated   Enter: save   Esc: cancel",
+            },
+            dim,
+        )),
     };
 
     // ── Step indicator ───────────────────────────────────────────────────────
     let step_num = match step {
-        EventFormStep::Title     => "Step 1 / 3 — Title",
-        EventFormStep::StartTime => "Step 2 / 3 — Start time",
-        EventFormStep::EndTime   => "Step 3 / 3 — End time",
+        EventFormStep::Title     => "Step 1 / 5 — Title",
+        EventFormStep::Tag       => "Step 2 / 5 — Tag",
+        EventFormStep::StartTime => "Step 3 / 5 — Start time",
+        EventFormStep::EndTime   => "Step 4 / 5 — End time",
+        EventFormStep::Guests    => "Step 5 / 5 — Guests",
     };
     let step_line = Line::from(Span::styled(
         format!("  {step_num}"),
@@ -417,20 +1572,23 @@ fn draw_event_form(f: &mut Frame, area: Rect, app: &App) {
         dim,
     ));
 
-    let lines: Vec<Line> = vec![
+    let mut lines: Vec<Line> = vec![
         Line::from(""),
         step_line,
         Line::from(""),
         title_line,
         Line::from(""),
+        tag_line,
+        known_tags,
         start_line,
         Line::from(""),
         end_line,
         Line::from(""),
-        sep,
-        Line::from(""),
-        hint,
+        guest_line,
     ];
+    lines.extend(guest_status);
+    lines.extend(conflict_lines);
+    lines.extend([dst_line, sep, Line::from(""), hint]);
 
     f.render_widget(
         Paragraph::new(lines).style(Style::default().bg(t.popup_bg())),
@@ -462,6 +1620,124 @@ fn draw_popup(f: &mut Frame, label: &str, value: &str, area: Rect, app: &App) {
     );
 }
 
+// ─── Reminder toast ───────────────────────────────────────────────────────────
+
+fn draw_toast(f: &mut Frame, area: Rect, app: &App, n: &Notification) {
+    let t     = &app.theme;
+    let width = 48.min(area.width);
+    let rect  = Rect::new(area.x + area.width - width, area.y, width, 4.min(area.height));
+    f.render_widget(Clear, rect);
+
+    let (title, color) = match n.urgency {
+        Urgency::Critical => (" 🚨 Reminder ", t.error_color()),
+        Urgency::Low      => (" ⏰ Reminder ", t.fg_dim()),
+        Urgency::Normal   => (" ⏰ Reminder ", t.warning_color()),
+    };
+    let block = Block::default()
+        .title(Title::from(Span::styled(title, Style::default().fg(color).add_modifier(Modifier::BOLD))))
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .border_style(Style::default().fg(color))
+        .style(Style::default().bg(t.popup_bg()));
+    let lines = vec![
+        Line::from(Span::styled(format!(" {}", n.title), Style::default().fg(t.fg()).add_modifier(Modifier::BOLD))),
+        Line::from(Span::styled(format!(" {}", n.body), Style::default().fg(t.fg_dim()))),
+    ];
+    f.render_widget(Paragraph::new(lines).block(block), rect);
+}
+
+// ─── Starting-soon banner ────────────────────────────────────────────────────
+
+/// The first event on `app.banner` across the row above the status bar,
+/// until it's snoozed, dismissed or a few minutes under way.
+fn draw_banner(f: &mut Frame, app: &App, area: Rect) {
+    let t = &app.theme;
+    let Some(ev) = app.banner.first() else { return };
+    let now  = chrono::Local::now().naive_local().and_utc();
+    let more = match app.banner.len() - 1 {
+        0 => String::new(),
+        n => format!(" · +{n} more"),
+    };
+    let text  = format!(" ⏰ {} · starts {} ({}){more}", ev.title, status::until(now, ev.start), ev.start.format("%H:%M"));
+    let keys  = " z snooze 5m · Z 10m · Ctrl+x dismiss ";
+    let style = Style::default().bg(t.warning_color()).fg(t.bg());
+    let room  = (area.width as usize).saturating_sub(keys.width());
+    f.render_widget(Clear, area);
+    f.render_widget(Paragraph::new(Line::from(vec![
+        Span::styled(pad(&text, room), style.add_modifier(Modifier::BOLD)),
+        Span::styled(keys, style),
+    ])).style(style), area);
+}
+
+// ─── Leader keys ─────────────────────────────────────────────────────────────
+
+/// What `Space` on the month grid leads to, by group: each is the key the
+/// grid already takes, so the popup only has to list them.
+const LEADER_KEYS: [(&str, &[(char, &str)]); 4] = [
+    ("Add & plan", &[('n', "new event"), ('N', "new task"), ('p', "plan my day"), ('X', "export"), ('U', "duplicates")]),
+    ("Views", &[
+        ('G', "today at a glance"), ('S', "stats"), ('H', "time report"), ('B', "habits"), ('L', "checklists"),
+        ('W', "ideal week"), ('M', "meal plan"), ('C', "custom panels"),
+    ]),
+    ("Sync", &[
+        ('A', "connect Google"), ('D', "disconnect"), ('I', "sync interval"), ('Q', "review quarantine"),
+        ('R', "reload all"), ('O', "logs"), ('Y', "sync log"),
+    ]),
+    ("Look", &[('T', "theme"), ('E', "edit theme"), ('V', "holidays"), ('^', "pin all-day"), ('?', "all keys")]),
+];
+
+/// `LEADER_KEYS` less what does nothing here: the meal plan when it's off,
+/// disconnecting and the sync interval before the worker runs.
+pub fn leader_keys(app: &App) -> Vec<(&'static str, Vec<(char, &'static str)>)> {
+    LEADER_KEYS.iter().map(|(group, keys)| (*group, keys.iter().copied()
+        .filter(|(k, _)| match k {
+            'M' => app.config.ui.meal_plan,
+            'D' | 'I' => app.sync.is_some(),
+            _   => true,
+        })
+        .collect())).collect()
+}
+
+/// The which-key popup along the bottom while `Space` waits for its key.
+fn draw_leader(f: &mut Frame, area: Rect, app: &App) {
+    let t      = &app.theme;
+    let groups = leader_keys(app);
+    let tall   = groups.iter().map(|(_, keys)| keys.len()).max().unwrap_or(0) as u16;
+    let height = (tall + 4).min(area.height.saturating_sub(1));
+    let width  = area.width.min(groups.len() as u16 * 24 + 2);
+    let rect   = Rect::new(area.x, area.bottom().saturating_sub(height + 1), width, height);
+    f.render_widget(Clear, rect);
+
+    let block = Block::default()
+        .title(Title::from(Line::from(Span::styled(
+            " Space … ",
+            Style::default().fg(t.accent()).add_modifier(Modifier::BOLD),
+        ))))
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .border_style(Style::default().fg(t.border_active()))
+        .style(Style::default().bg(t.popup_bg()));
+    let inner = block.inner(rect);
+    f.render_widget(block, rect);
+
+    let rows = Layout::default().direction(Direction::Vertical)
+        .constraints([Constraint::Min(0), Constraint::Length(1)]).split(inner);
+    let cols = Layout::default().direction(Direction::Horizontal)
+        .constraints(groups.iter().map(|_| Constraint::Ratio(1, groups.len() as u32)).collect::<Vec<_>>())
+        .split(rows[0]);
+    let key = Style::default().fg(t.accent()).add_modifier(Modifier::BOLD);
+    let dim = Style::default().fg(t.fg_dim());
+    for ((group, keys), col) in groups.iter().zip(cols.iter()) {
+        let mut lines = vec![Line::from(Span::styled(format!(" {group}"), Style::default().fg(t.fg()).add_modifier(Modifier::BOLD)))];
+        lines.extend(keys.iter().map(|(k, label)| Line::from(vec![
+            Span::styled(format!("  {k} "), key),
+            Span::styled(*label, dim),
+        ])));
+        f.render_widget(Paragraph::new(lines), *col);
+    }
+    f.render_widget(Paragraph::new(Span::styled("  Esc / Space: close", dim)), rows[1]);
+}
+
 // ─── Help overlay ────────────────────────────────────────────────────────────
 
 fn draw_help(f: &mut Frame, area: Rect, app: &App) {
@@ -487,53 +1763,1739 @@ fn draw_help(f: &mut Frame, area: Rect, app: &App) {
         Line::from(Span::styled("  Navigation", accent)),
         Line::from(Span::styled("  h/j/k/l  ←↓↑→     Move by day", dim)),
         Line::from(Span::styled("  [ / ]              Prev / Next month", dim)),
+        Line::from(Span::styled("  3l, 2], 4k …       A count first repeats the move (calendar)", dim)),
+        Line::from(Span::styled("  0 / % / $          First / middle / last day of the month (25% a quarter in)", dim)),
         Line::from(Span::styled("  t                  Jump to today", dim)),
+        Line::from(Span::styled("  g                  Go to a date: 2025-03-14, mar 14, +3w, next friday", dim)),
+        Line::from(Span::styled("  Space (calendar)   Popup of the calendar's other keys, by group", dim)),
         Line::from(Span::styled("  Tab                Cycle panels", dim)),
+        Line::from(Span::styled("  PgUp/PgDn gg G     Page / top / bottom of event & task lists (g G here)", dim)),
+        Line::from(Span::styled("  r / R              Reload focused panel / all data from the DB", dim)),
+        Line::from(Span::styled("  P                  Start / stop a pomodoro (for the selected task)", dim)),
+        Line::from(Span::styled("  Ctrl+t             Start / stop a timer; stopping logs a time entry", dim)),
+        Line::from(Span::styled("  z / Z / Ctrl+x     Starting-soon banner: snooze 5 / 10 min, dismiss", dim)),
+        Line::from(Span::styled("  Mouse              Click a day, row or hint · wheel scrolls", dim)),
         Line::from(""),
         Line::from(Span::styled("  Events", accent)),
-        Line::from(Span::styled("  n                  New event (3-step: title → start → end)", dim)),
+        Line::from(Span::styled("  n                  New event (title → tag → start → end → guests)", dim)),
+        Line::from(Span::styled("    Tab                Next known tag (tag step)", dim)),
         Line::from(Span::styled("    Enter              Advance to next step", dim)),
+        Line::from(Span::styled("    Enter (guests)     Save; lists overlapping events first, again saves", dim)),
         Line::from(Span::styled("    ↑ / ↓              Adjust hour or minute", dim)),
         Line::from(Span::styled("    ← / →              Switch hour / minute field", dim)),
-        Line::from(Span::styled("  d / Del            Delete event", dim)),
+        Line::from(Span::styled("    + / - (start)      Lead time for travel or prep", dim)),
+        Line::from(Span::styled("  d / Del            Delete event (asks first)", dim)),
+        Line::from(Span::styled("  p                  Toggle PTO / vacation day", dim)),
+        Line::from(Span::styled("  o                  Join the event's video call (📹)", dim)),
+        Line::from(Span::styled("  a                  Check in: attended / skipped / cancelled", dim)),
+        Line::from(Span::styled("  m                  Count as tracked time (time report)", dim)),
+        Line::from(Span::styled("  M                  Move to another day: pick it on the calendar or type +1d / -1w", dim)),
+        Line::from(Span::styled("  + / -              Lead time ±5 min: reminds earlier, shaded", dim)),
+        Line::from(Span::styled("  #                  Cycle the event's tag", dim)),
+        Line::from(Span::styled("  f                  Filter the list and dots by tag", dim)),
+        Line::from(Span::styled("  ^                  Pin all-day events and holidays above the day", dim)),
         Line::from(Span::styled("  Enter              Focus event list", dim)),
+        Line::from(Span::styled("  Enter (in list)    Details: calendar, organizer, guests, notes", dim)),
+        Line::from(Span::styled("    y / n / m          Answer an invitation yes / no / maybe", dim)),
         Line::from(""),
         Line::from(Span::styled("  Tasks", accent)),
-        Line::from(Span::styled("  N                  New task", dim)),
+        Line::from(Span::styled("  N                  New task (#word tags it, ~45m estimates it, @tue repeats it)", dim)),
         Line::from(Span::styled("  Space              Toggle complete", dim)),
+        Line::from(Span::styled("  e                  Edit title, notes, due date, priority", dim)),
+        Line::from(Span::styled("  f                  Filter: all / due today / overdue / by tag", dim)),
+        Line::from(Span::styled("  s                  Sort: priority / due / manual", dim)),
+        Line::from(Span::styled("  J / K              Move task down / up (manual order)", dim)),
+        Line::from(Span::styled("  d / Del            Delete task (asks first)", dim)),
+        Line::from(Span::styled("  v (either list)    Select a range: d delete, m move, c calendar/list, x done", dim)),
         Line::from(""),
         Line::from(Span::styled("  Sync (Google Calendar + Tasks)", accent)),
         Line::from(Span::styled("  Ctrl+s             Force sync now", dim)),
-        Line::from(Span::styled("  Auto-sync every 5 minutes when configured", dim)),
+        Line::from(Span::styled("  A                  Connect Google account", dim)),
+        Line::from(Span::styled("  D                  Disconnect Google (revoke access)", dim)),
+        Line::from(Span::styled("  Y                  Sync log: what was pulled and pushed", dim)),
+        Line::from(Span::styled("  I                  Auto-sync interval: off / 1 / 5 / 15 / 60 min", dim)),
         Line::from(""),
         Line::from(Span::styled("  General", accent)),
         Line::from(Span::styled("  ?                  Toggle help", dim)),
+        Line::from(Span::styled("  Ctrl+p             Command palette: every action by name, filtered as you type", dim)),
+        Line::from(Span::styled("  S                  Stats (PTO, meeting load)", dim)),
+        Line::from(Span::styled("  H                  Time report: tracked hours per tag / calendar", dim)),
+        Line::from(Span::styled("  T                  Pick a theme (built-ins, theme.toml, themes/*.toml)", dim)),
+        Line::from(Span::styled("  E                  Edit theme colours with live preview, save as a custom theme", dim)),
+        Line::from(Span::styled("  V                  Holidays: show or hide each country's, dim them on the grid", dim)),
+        Line::from(Span::styled("  M                  Meal plan (when ui.meal_plan is on)", dim)),
+        Line::from(Span::styled("  L                  Checklists (local only, never synced)", dim)),
+        Line::from(Span::styled("  B                  Habits: add, remove, check off for the selected day", dim)),
+        Line::from(Span::styled("  Alt+1–9            Check off today's habit by its number in the strip", dim)),
+        Line::from(Span::styled("  W                  Ideal week: template vs actual events", dim)),
+        Line::from(Span::styled("  Q                  Review quarantined imports (fix or discard)", dim)),
+        Line::from(Span::styled("  p                  Plan the day's estimated tasks into its free time", dim)),
+        Line::from(Span::styled("  U                  Review and merge likely duplicate events", dim)),
+        Line::from(Span::styled("  C                  Custom panels from [[panels]] commands", dim)),
+        Line::from(Span::styled("  O                  Log viewer (l: level, r: reload)", dim)),
+        Line::from(Span::styled("  X                  Export day / week / month / tasks as ICS, Markdown or JSON", dim)),
+        Line::from(Span::styled("  | (either list)    Send the selected event or task to an [[actions]] command", dim)),
         Line::from(Span::styled("  Esc                Cancel / back", dim)),
         Line::from(Span::styled("  q                  Quit", dim)),
     ];
 
+    // Scrolled in wrapped rows, so narrow terminals can still reach the end
+    let help  = Paragraph::new(lines).style(Style::default().fg(t.fg())).wrap(Wrap { trim: false });
+    let rows  = rect.height.saturating_sub(2);
+    let total = help.line_count(rect.width.saturating_sub(2));
+    let first = app.ui.help_scroll.get().min(total.saturating_sub(rows as usize));
+    app.ui.help_scroll.set(first);
+    app.ui.help_rows.set(rows);
+
+    f.render_widget(help.block(block).scroll((first as u16, 0)), rect);
+    draw_line_scrollbar(f, app, rect, total, rows as usize, first);
+}
+
+// ─── Google onboarding overlay ───────────────────────────────────────────────
+
+fn draw_onboarding(f: &mut Frame, area: Rect, app: &App) {
+    let t    = &app.theme;
+    let rect = centered(80, 90, area);
+    f.render_widget(Clear, rect);
+
+    let title = Line::from(Span::styled(
+        " Connect Google Calendar ",
+        Style::default().fg(t.accent()).add_modifier(Modifier::BOLD),
+    ));
+    let block = Block::default()
+        .title(Title::from(title))
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .border_style(Style::default().fg(t.border_active()))
+        .style(Style::default().bg(t.popup_bg()));
+    let inner = block.inner(rect);
+    f.render_widget(block, rect);
+
+    let accent = Style::default().fg(t.accent()).add_modifier(Modifier::BOLD);
+    let dim    = Style::default().fg(t.fg_dim());
+    let fg     = Style::default().fg(t.fg());
+
+    let mut lines = vec![Line::from("")];
+    match &app.ui.auth_url {
+        None => {
+            lines.push(Line::from(Span::styled("  Sync needs access to your Google Calendar and Tasks.", fg)));
+            lines.push(Line::from(""));
+            lines.push(Line::from(Span::styled("  Enter: open Google sign-in in your browser   Esc: later", accent)));
+            if app.config.google.as_ref().is_some_and(|g| g.is_embedded()) {
+                lines.push(Line::from(""));
+                lines.push(Line::from(Span::styled(
+                    "  Blocked by Google, or rather use your own OAuth client? Quit and run", dim,
+                )));
+                lines.push(Line::from(Span::styled("  lm auth google --client-id ID", dim)));
+            }
+        }
+        Some(url) => {
+            lines.push(Line::from(Span::styled("  If the browser didn't open, visit:", fg)));
+            lines.push(Line::from(Span::styled(format!("  {url}"), Style::default().fg(t.accent()))));
+            lines.push(Line::from(""));
+            // Only when the whole code fits — a clipped QR code won't scan
+            let qr    = qr_lines(url);
+            let fits  = qr.first().map(|l| l.chars().count() + 2 <= inner.width as usize).unwrap_or(false)
+                && qr.len() + 10 <= inner.height as usize;
+            if fits {
+                // Fixed black-on-white: scanners struggle with inverted codes
+                let qs = Style::default().bg(Color::White).fg(Color::Black);
+                for l in qr {
+                    lines.push(Line::from(vec![Span::raw("  "), Span::styled(l, qs)]));
+                }
+                lines.push(Line::from(""));
+            }
+        }
+    }
+    if !app.ui.auth_status.is_empty() {
+        lines.push(Line::from(Span::styled(format!("  {}", app.ui.auth_status), dim)));
+    }
+    lines.push(Line::from(""));
+    lines.push(Line::from(vec![
+        Span::styled("  Or paste the code from the redirect URL: ", dim),
+        Span::styled(format!("{}█", app.ui.auth_code), fg),
+    ]));
+
     f.render_widget(
-        Paragraph::new(lines).block(block).style(Style::default().fg(t.fg()))
-            .wrap(Wrap { trim: false }),
-        rect,
+        Paragraph::new(lines).style(Style::default().bg(t.popup_bg())).wrap(Wrap { trim: false }),
+        inner,
     );
 }
 
-// ─── Utilities ────────────────────────────────────────────────────────────────
+/// QR code as half-block text rows (two modules per character cell).
+fn qr_lines(data: &str) -> Vec<String> {
+    use qrcode::{render::unicode::Dense1x2, QrCode};
+    match QrCode::new(data.as_bytes()) {
+        Ok(code) => code.render::<Dense1x2>()
+            .build()
+            .lines()
+            .map(str::to_owned)
+            .collect(),
+        Err(_) => Vec::new(),
+    }
+}
 
-fn centered(pct_x: u16, pct_y: u16, r: Rect) -> Rect {
-    let vert = Layout::default().direction(Direction::Vertical)
-        .constraints([
-            Constraint::Percentage((100 - pct_y) / 2),
-            Constraint::Percentage(pct_y),
-            Constraint::Percentage((100 - pct_y) / 2),
-        ]).split(r);
-    Layout::default().direction(Direction::Horizontal)
-        .constraints([
-            Constraint::Percentage((100 - pct_x) / 2),
-            Constraint::Percentage(pct_x),
-            Constraint::Percentage((100 - pct_x) / 2),
-        ]).split(vert[1])[1]
+// ─── Meal plan week grid ─────────────────────────────────────────────────────
+
+fn draw_meal_plan(f: &mut Frame, area: Rect, app: &App) {
+    let t    = &app.theme;
+    let rect = centered(80, 60, area);
+    f.render_widget(Clear, rect);
+
+    let mon   = week_start(app.selected_date);
+    let title = Line::from(Span::styled(
+        format!(" Meal plan — week of {} ", mon.format("%b %-d")),
+        Style::default().fg(t.accent()).add_modifier(Modifier::BOLD),
+    ));
+    let block = Block::default()
+        .title(Title::from(title))
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .border_style(Style::default().fg(t.border_active()))
+        .style(Style::default().bg(t.popup_bg()));
+    let inner = block.inner(rect);
+    f.render_widget(block, rect);
+
+    let dim  = Style::default().fg(t.fg_dim());
+    let fg   = Style::default().fg(t.fg());
+    let (sel_bg, sel_fg) = t.selected_highlight();
+    let sel  = Style::default().bg(sel_bg).fg(sel_fg);
+    let col  = (inner.width.saturating_sub(10) / 3).max(8) as usize;
+    let cell = |s: &str| -> String { pad(s, col - 1) + " " };
+
+    let mut lines = vec![Line::from(""), Line::from(
+        std::iter::once(Span::styled("          ", dim))
+            .chain(MEAL_SLOTS.iter().map(|n| Span::styled(cell(n), dim.add_modifier(Modifier::BOLD))))
+            .collect::<Vec<_>>(),
+    )];
+
+    for i in 0..7 {
+        let Some(date) = add_days(mon, i) else { break };
+        let is_row  = date == app.selected_date;
+        let mut row = vec![Span::styled(
+            format!("  {:<8}", date.format("%a %-d")),
+            if is_row { Style::default().fg(t.accent()).add_modifier(Modifier::BOLD) } else { dim },
+        )];
+        for slot in 0..3 {
+            let focused = is_row && slot == app.ui.meal_slot;
+            let text = if focused && app.ui.input_mode == InputMode::Insert {
+                format!("{}█", app.ui.meal_input)
+            } else {
+                app.meals.iter()
+                    .find(|m| m.date == date && m.slot == slot as i64)
+                    .map(|m| m.text.clone())
+                    .unwrap_or_else(|| "·".into())
+            };
+            row.push(Span::styled(cell(&text), if focused { sel } else { fg }));
+        }
+        lines.push(Line::from(row));
+    }
+
+    lines.push(Line::from(""));
+    lines.push(Line::from(Span::styled(
+        "  hjkl: move   Enter/i: edit   x: clear   [ ]: week   Esc: close", dim,
+    )));
+
+    f.render_widget(Paragraph::new(lines).style(Style::default().bg(t.popup_bg())), inner);
+}
+
+// ─── Checklists overlay ──────────────────────────────────────────────────────
+
+fn draw_checklists(f: &mut Frame, area: Rect, app: &App) {
+    let t    = &app.theme;
+    let rect = centered(70, 70, area);
+    f.render_widget(Clear, rect);
+
+    let title = Line::from(Span::styled(
+        " ☑ Checklists ",
+        Style::default().fg(t.accent()).add_modifier(Modifier::BOLD),
+    ));
+    let block = Block::default()
+        .title(Title::from(title))
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .border_style(Style::default().fg(t.border_active()))
+        .style(Style::default().bg(t.popup_bg()));
+    let inner = block.inner(rect);
+    f.render_widget(block, rect);
+
+    let rows = Layout::default().direction(Direction::Vertical)
+        .constraints([Constraint::Min(0), Constraint::Length(1)]).split(inner);
+    let cols = Layout::default().direction(Direction::Horizontal)
+        .constraints([Constraint::Length(24), Constraint::Min(0)]).split(rows[0]);
+
+    let dim    = Style::default().fg(t.fg_dim());
+    let (bg, fg) = t.selected_highlight();
+    let sel    = Style::default().bg(bg).fg(fg);
+    let typing = app.ui.input_mode == InputMode::Insert;
+    let lists_focused = !app.ui.list_items_focused;
+
+    // Left pane: list names (+ the new-list prompt)
+    let mut names: Vec<Line> = app.checklists.iter().enumerate().map(|(i, l)| {
+        let style = if i == app.ui.list_cursor {
+            if lists_focused && !typing { sel } else { Style::default().fg(t.accent()) }
+        } else {
+            Style::default().fg(t.fg())
+        };
+        Line::from(Span::styled(format!(" {} ", l.name), style))
+    }).collect();
+    if typing && lists_focused {
+        names.push(Line::from(Span::styled(format!(" + {}█", app.ui.list_input), Style::default().fg(t.accent()))));
+    } else if app.checklists.is_empty() {
+        names.push(Line::from(Span::styled(" No lists — n: new", dim)));
+    }
+    let list_block = Block::default()
+        .borders(Borders::RIGHT)
+        .border_style(Style::default().fg(t.border()));
+    f.render_widget(Paragraph::new(names).block(list_block), cols[0]);
+
+    // Right pane: items of the selected list
+    let mut items: Vec<Line> = app.checklist_items.iter().enumerate().map(|(i, it)| {
+        let (mark, style) = if it.checked {
+            ("[x]", Style::default().fg(t.muted()).add_modifier(Modifier::CROSSED_OUT))
+        } else {
+            ("[ ]", Style::default().fg(t.fg()))
+        };
+        let style = if i == app.ui.list_item_cursor && app.ui.list_items_focused && !typing { sel } else { style };
+        Line::from(Span::styled(format!(" {mark} {}", it.text), style))
+    }).collect();
+    if typing && app.ui.list_items_focused {
+        items.push(Line::from(Span::styled(format!(" [+] {}█", app.ui.list_input), Style::default().fg(t.accent()))));
+    } else if items.is_empty() && !app.checklists.is_empty() {
+        items.push(Line::from(Span::styled(" Empty — a: add items", dim)));
+    }
+    let done = app.checklist_items.iter().filter(|i| i.checked).count();
+    if !app.checklist_items.is_empty() {
+        items.insert(0, Line::from(Span::styled(
+            format!(" {done}/{} done", app.checklist_items.len()), dim,
+        )));
+    }
+    f.render_widget(Paragraph::new(items), cols[1]);
+
+    let hint = if typing {
+        "  Enter: add (empty Enter: done)   Esc: cancel"
+    } else {
+        "  n: new list   a: add   Space: check   x: delete   R: reset   Tab: switch   Esc: close"
+    };
+    f.render_widget(Paragraph::new(Span::styled(hint, dim)), rows[1]);
+}
+
+// ─── Ideal week overlay ──────────────────────────────────────────────────────
+
+fn draw_ideal_week(f: &mut Frame, area: Rect, app: &App) {
+    let t    = &app.theme;
+    let rect = centered(70, 80, area);
+    f.render_widget(Clear, rect);
+
+    let mon   = week_start(app.selected_date);
+    let met   = app.week_plan.iter().filter(|b| b.met).count();
+    let title = Line::from(Span::styled(
+        format!(" Ideal week — {} · {met}/{} blocks met ", mon.format("%b %-d"), app.week_plan.len()),
+        Style::default().fg(t.accent()).add_modifier(Modifier::BOLD),
+    ));
+    let block = Block::default()
+        .title(Title::from(title))
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .border_style(Style::default().fg(t.border_active()))
+        .style(Style::default().bg(t.popup_bg()));
+    let inner = block.inner(rect);
+    f.render_widget(block, rect);
+
+    let dim = Style::default().fg(t.fg_dim());
+    // Planned blocks are drawn faded so real events read as the foreground
+    let plan = Style::default().fg(t.muted()).add_modifier(Modifier::ITALIC);
+    let (sel_bg, sel_fg) = t.selected_highlight();
+    let hm  = |d: DateTime<Utc>| d.format("%H:%M").to_string();
+
+    let mut lines: Vec<Line> = Vec::new();
+    if app.config.ideal_week.is_empty() {
+        lines.push(Line::from(Span::styled("  No [[ideal_week]] blocks in config.toml", dim)));
+    }
+    for i in 0..7 {
+        let Some(date) = add_days(mon, i) else { break };
+        lines.push(Line::from(Span::styled(
+            format!(" {}", date.format("%a %-d")),
+            Style::default().fg(t.accent()).add_modifier(Modifier::BOLD),
+        )));
+
+        // Merge plan and reality for the day, ordered by start
+        let mut rows: Vec<(DateTime<Utc>, Line)> = Vec::new();
+        let (day_from, day_to) = day_bounds(date);
+        for ev in app.week_events.iter().filter(|e| e.start < day_to && e.end > day_from) {
+            let time = if ev.all_day { "all-day    ".to_owned() } else { format!("{}–{}", hm(ev.start), hm(ev.end)) };
+            rows.push((ev.start, Line::from(vec![
+                Span::styled("   ● ", Style::default().fg(t.event_color())),
+                Span::styled(format!("{time}  "), dim),
+                Span::styled(ev.title.clone(), Style::default().fg(t.fg())),
+            ])));
+        }
+        for (idx, b) in app.week_plan.iter().enumerate().filter(|(_, b)| b.start.date_naive() == date) {
+            let style = if idx == app.ui.plan_cursor { Style::default().bg(sel_bg).fg(sel_fg) } else { plan };
+            let mark  = if b.met {
+                Span::styled(" ✓", Style::default().fg(t.accent()))
+            } else {
+                Span::styled(" ·", dim)
+            };
+            rows.push((b.start, Line::from(vec![
+                Span::styled("   ░ ", plan),
+                Span::styled(format!("{}–{}  {}", hm(b.start), hm(b.end), b.title), style),
+                mark,
+            ])));
+        }
+        rows.sort_by_key(|(start, _)| *start);
+        lines.extend(rows.into_iter().map(|(_, l)| l));
+    }
+
+    lines.push(Line::from(""));
+    lines.push(Line::from(Span::styled(
+        "  ░ planned  ● actual   j/k: block   Enter/m: add as event   [ ]: week   Esc: close", dim,
+    )));
+
+    f.render_widget(Paragraph::new(lines).style(Style::default().bg(t.popup_bg())), inner);
+}
+
+// ─── Habits overlay ──────────────────────────────────────────────────────────
+
+fn draw_habits_overlay(f: &mut Frame, area: Rect, app: &App) {
+    let t    = &app.theme;
+    let rect = centered(60, 60, area);
+    f.render_widget(Clear, rect);
+
+    let title = Line::from(Span::styled(
+        format!(" Habits · {} ", app.selected_date.format("%a %b %-d")),
+        Style::default().fg(t.accent()).add_modifier(Modifier::BOLD),
+    ));
+    let block = Block::default()
+        .title(Title::from(title))
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .border_style(Style::default().fg(t.border_active()))
+        .style(Style::default().bg(t.popup_bg()));
+    let inner = block.inner(rect);
+    f.render_widget(block, rect);
+
+    let rows = Layout::default().direction(Direction::Vertical)
+        .constraints([Constraint::Min(0), Constraint::Length(1)]).split(inner);
+    let dim      = Style::default().fg(t.fg_dim());
+    let (bg, fg) = t.selected_highlight();
+    let today    = chrono::Local::now().date_naive();
+    let typing   = app.ui.input_mode == InputMode::Insert;
+
+    let mut lines: Vec<Line> = app.habits.iter().enumerate().map(|(i, h)| {
+        let style = if i == app.ui.habit_cursor && !typing { Style::default().bg(bg).fg(fg) } else { Style::default().fg(t.fg()) };
+        let mark = if habits::done(h, &app.habit_checks, app.selected_date) {
+            Span::styled(" ✓ ", Style::default().fg(t.success_color()))
+        } else if habits::due(h, app.selected_date) {
+            Span::styled(" · ", Style::default().fg(t.muted()))
+        } else {
+            Span::styled("   ", dim)
+        };
+        let mut spans = vec![
+            mark,
+            Span::styled(format!(" {} ", h.name), style),
+            Span::styled(format!("  {}", habits::Schedule::parse(&h.schedule).label()), dim),
+        ];
+        let streak = habits::streak(h, &app.habit_checks, today);
+        if streak > 0 {
+            spans.push(Span::styled(format!("  🔥{streak}"), Style::default().fg(t.warning_color())));
+        }
+        Line::from(spans)
+    }).collect();
+    if lines.is_empty() && !typing {
+        lines.push(Line::from(Span::styled(" No habits yet — a adds one", dim)));
+    }
+    if typing {
+        lines.push(Line::from(""));
+        lines.push(Line::from(Span::styled(
+            format!(" New habit: {}█", app.ui.habit_input),
+            Style::default().fg(t.accent()).add_modifier(Modifier::BOLD),
+        )));
+    }
+    let skip = app.ui.habit_cursor.saturating_sub(rows[0].height.saturating_sub(1) as usize);
+    f.render_widget(Paragraph::new(lines.split_off(skip.min(lines.len()))), rows[0]);
+
+    let hint = if typing {
+        "  Name, then @daily, @weekdays or @mon,wed,fri   Enter: add   Esc: cancel"
+    } else {
+        "  Space: done on this day   a: add   x: remove   Esc: close"
+    };
+    f.render_widget(Paragraph::new(Span::styled(hint, dim)), rows[1]);
+}
+
+// ─── Event details overlay ───────────────────────────────────────────────────
+
+fn draw_event_info(f: &mut Frame, area: Rect, app: &App) {
+    let Some(ref info) = app.event_info else { return };
+    let (t, ev) = (&app.theme, &info.event);
+    let rect    = centered(60, 60, area);
+    f.render_widget(Clear, rect);
+
+    let title = Line::from(Span::styled(
+        format!(" {} ", ev.title),
+        Style::default().fg(t.accent()).add_modifier(Modifier::BOLD),
+    ));
+    let block = Block::default()
+        .title(Title::from(title))
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .border_style(Style::default().fg(t.border_active()))
+        .style(Style::default().bg(t.popup_bg()));
+    let inner = block.inner(rect);
+    f.render_widget(block, rect);
+
+    let rows = Layout::default().direction(Direction::Vertical)
+        .constraints([Constraint::Min(0), Constraint::Length(1)]).split(inner);
+    let dim = Style::default().fg(t.fg_dim());
+    let fg  = Style::default().fg(t.fg());
+    let row = |label: &str, value: String| Line::from(vec![
+        Span::styled(format!("  {label:<11}"), dim),
+        Span::styled(value, fg),
+    ]);
+
+    let when = if ev.all_day {
+        format!("{} · all day", ev.start.format("%a %b %-d"))
+    } else if ev.start.date_naive() == ev.end.date_naive() {
+        format!("{} · {}–{}", ev.start.format("%a %b %-d"), ev.start.format("%H:%M"), ev.end.format("%H:%M"))
+    } else {
+        format!("{} – {}", ev.start.format("%a %b %-d %H:%M"), ev.end.format("%a %b %-d %H:%M"))
+    };
+    let source = match (ev.calendar_id.as_deref(), &info.calendar) {
+        (None, _)                             => "This machine (not synced)".to_owned(),
+        (Some(FOCUS_CALENDAR), _)             => "This machine · focus sessions".to_owned(),
+        (Some(TIME_CALENDAR), _)              => "This machine · tracked time".to_owned(),
+        (Some(PLAN_CALENDAR), _)              => "This machine · planned tasks".to_owned(),
+        (Some(id), Some(name)) if name != id  => format!("Google · {name} ({id})"),
+        (Some(id), _)                         => format!("Google · {id}"),
+    };
+
+    let mut lines = vec![Line::from(""), row("When", when), row("Calendar", source)];
+    if let Some(url) = ev.meeting_url() {
+        lines.push(row("Call", format!("📹 {url}")));
+    }
+    if let Some(ref o) = ev.organizer {
+        lines.push(row("Organizer", o.clone()));
+    }
+    if let Some(ref c) = ev.creator.as_ref().filter(|c| ev.organizer.as_ref() != Some(*c)) {
+        lines.push(row("Created by", c.to_string()));
+    }
+    if let Some(answer) = info.response() {
+        lines.push(Line::from(vec![
+            Span::styled(format!("  {:<11}", "Going?"), dim),
+            Span::styled(format!("{} {}", answer.glyph(), answer.label()), rsvp_style(app, answer).add_modifier(Modifier::BOLD)),
+        ]));
+    }
+    for (i, g) in info.guests.iter().enumerate() {
+        let mut who = g.describe();
+        if g.is_self   { who.push_str(" (you)"); }
+        if g.organizer { who.push_str(" · organizer"); }
+        lines.push(Line::from(vec![
+            Span::styled(format!("  {:<11}", if i == 0 { "Guests" } else { "" }), dim),
+            Span::styled(format!("{} ", g.response.glyph()), rsvp_style(app, g.response)),
+            Span::styled(who, if g.response == Rsvp::Declined { dim } else { fg }),
+        ]));
+    }
+    if let Some(ref tag) = ev.tag {
+        lines.push(Line::from(vec![
+            Span::styled(format!("  {:<11}", "Tag"), dim),
+            Span::styled(format!("#{tag}"), Style::default().fg(app.tag_color(Some(tag)))),
+        ]));
+    }
+    if let Some((name, hex)) = ev.color() {
+        lines.push(Line::from(vec![
+            Span::styled(format!("  {:<11}", "Colour"), dim),
+            Span::styled(format!("● {name}"), Style::default().fg(hex_to_color(hex))),
+        ]));
+    }
+    if let Some(d) = ev.description.as_deref().filter(|d| !d.trim().is_empty()) {
+        lines.push(Line::from(""));
+        lines.extend(d.lines().map(|l| Line::from(Span::styled(format!("  {l}"), fg))));
+    }
+    f.render_widget(Paragraph::new(lines).wrap(Wrap { trim: false }), rows[0]);
+    let mut hint = String::from(" ");
+    if info.response().is_some() { hint.push_str(" Going? y: yes   n: no   m: maybe  "); }
+    if ev.meeting_url().is_some() { hint.push_str(" o: join  "); }
+    hint.push_str(" Enter / Esc: close");
+    f.render_widget(Paragraph::new(Span::styled(hint, dim)), rows[1]);
+}
+
+fn rsvp_style(app: &App, response: Rsvp) -> Style {
+    let t = &app.theme;
+    Style::default().fg(match response {
+        Rsvp::Accepted    => t.success_color(),
+        Rsvp::Tentative   => t.warning_color(),
+        Rsvp::Declined    => t.error_color(),
+        Rsvp::NeedsAction => t.fg_dim(),
+    })
+}
+
+// ─── Timezone change prompt ──────────────────────────────────────────────────
+
+fn draw_zone_change(f: &mut Frame, area: Rect, app: &App) {
+    let Some(ref change) = app.zone_change else { return };
+    let t    = &app.theme;
+    let rect = centered(64, 50, area);
+    f.render_widget(Clear, rect);
+
+    let title = Line::from(Span::styled(
+        " Timezone changed ",
+        Style::default().fg(t.accent()).add_modifier(Modifier::BOLD),
+    ));
+    let block = Block::default()
+        .title(Title::from(title))
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .border_style(Style::default().fg(t.border_active()))
+        .style(Style::default().bg(t.popup_bg()));
+    let inner = block.inner(rect);
+    f.render_widget(block, rect);
+
+    let rows = Layout::default().direction(Direction::Vertical)
+        .constraints([Constraint::Min(0), Constraint::Length(1)]).split(inner);
+    let dim = Style::default().fg(t.fg_dim());
+    let fg  = Style::default().fg(t.fg());
+
+    let mut lines = vec![
+        Line::from(""),
+        Line::from(vec![
+            Span::styled(format!("  {}", change.from), fg),
+            Span::styled("  →  ", dim),
+            Span::styled(change.to.name(), Style::default().fg(t.accent()).add_modifier(Modifier::BOLD)),
+        ]),
+        Line::from(""),
+    ];
+    if change.floating.is_empty() {
+        lines.push(Line::from(Span::styled("  No upcoming events were typed in under the old zone.", dim)));
+    } else {
+        lines.push(Line::from(Span::styled(
+            format!("  ⚠ {} upcoming event(s) typed in here assume {}:", change.floating.len(), change.from),
+            Style::default().fg(t.warning_color()),
+        )));
+        for ev in &change.floating {
+            let moved = dst::rebase(ev.start, change.from, change.to);
+            let at    = if moved.date_naive() == ev.start.date_naive() { "%H:%M" } else { "%a %-d %H:%M" };
+            lines.push(Line::from(vec![
+                Span::styled(format!("  {}  ", ev.start.format("%a %b %-d %H:%M")), dim),
+                Span::styled(ev.title.clone(), fg),
+                Span::styled(format!("  → {} with a", moved.format(at)), dim),
+            ]));
+        }
+    }
+    f.render_widget(Paragraph::new(lines).wrap(Wrap { trim: false }), rows[0]);
+
+    let hint = if change.floating.is_empty() {
+        "  Enter: switch zones  k / Esc: keep the old one"
+    } else {
+        "  Enter: switch zones  a: switch and move these  k / Esc: keep the old one"
+    };
+    f.render_widget(Paragraph::new(Span::styled(hint, dim)), rows[1]);
+}
+
+// ─── Export overlay ──────────────────────────────────────────────────────────
+
+fn draw_export(f: &mut Frame, area: Rect, app: &App) {
+    let t    = &app.theme;
+    let rect = centered(60, 40, area);
+    f.render_widget(Clear, rect);
+
+    let title = Line::from(Span::styled(
+        " Export ",
+        Style::default().fg(t.accent()).add_modifier(Modifier::BOLD),
+    ));
+    let block = Block::default()
+        .title(Title::from(title))
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .border_style(Style::default().fg(t.border_active()))
+        .style(Style::default().bg(t.popup_bg()));
+    let inner = block.inner(rect);
+    f.render_widget(block, rect);
+
+    let rows = Layout::default().direction(Direction::Vertical)
+        .constraints([Constraint::Min(0), Constraint::Length(1)]).split(inner);
+    let dim    = Style::default().fg(t.fg_dim());
+    let fg     = Style::default().fg(t.fg());
+    let accent = Style::default().fg(t.accent()).add_modifier(Modifier::BOLD);
+    let typing = app.ui.input_mode == InputMode::Insert;
+
+    let (scope, format) = (app.ui.export_scope, app.ui.export_format);
+    let what = match scope {
+        export::Scope::Tasks => format!("{} tasks, as listed", app.tasks.len()),
+        _                    => scope.describe(app.selected_date),
+    };
+    let lines = vec![
+        Line::from(""),
+        Line::from(vec![Span::styled("  Export  ", dim), Span::styled(format!("{:<6}", scope.label()), accent), Span::styled(what, fg)]),
+        Line::from(vec![Span::styled("  As      ", dim), Span::styled(format.label(), accent)]),
+        Line::from(vec![
+            Span::styled("  To      ", dim),
+            Span::styled(
+                format!("{}{}", app.ui.export_path, if typing { "█" } else { "" }),
+                if typing { accent } else { fg },
+            ),
+        ]),
+    ];
+    f.render_widget(Paragraph::new(lines).wrap(Wrap { trim: false }), rows[0]);
+
+    let hint = if typing {
+        "  Path (~ is home)   Enter: done   Esc: stop editing"
+    } else {
+        "  s: what   f: format   e: edit path   Enter: write   Esc: close"
+    };
+    f.render_widget(Paragraph::new(Span::styled(hint, dim)), rows[1]);
+}
+
+// ─── Task edit overlay ───────────────────────────────────────────────────────
+
+fn draw_task_edit(f: &mut Frame, area: Rect, app: &App) {
+    let Some(ref edit) = app.task_edit else { return };
+    let t    = &app.theme;
+    let rect = centered(60, 40, area);
+    f.render_widget(Clear, rect);
+
+    let block = Block::default()
+        .title(Title::from(Line::from(Span::styled(
+            " Edit task ",
+            Style::default().fg(t.accent()).add_modifier(Modifier::BOLD),
+        ))))
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .border_style(Style::default().fg(t.border_active()))
+        .style(Style::default().bg(t.popup_bg()));
+    let inner = block.inner(rect);
+    f.render_widget(block, rect);
+
+    let rows = Layout::default().direction(Direction::Vertical)
+        .constraints([Constraint::Min(0), Constraint::Length(1)]).split(inner);
+    let dim    = Style::default().fg(t.fg_dim());
+    let fg     = Style::default().fg(t.fg());
+    let accent = Style::default().fg(t.accent()).add_modifier(Modifier::BOLD);
+
+    let mut lines = vec![Line::from("")];
+    for field in TaskField::ALL {
+        let active = field == edit.field;
+        let value  = match field {
+            TaskField::Title    => edit.title.clone(),
+            // One line: breaks in synced notes show as ⏎ and are kept
+            TaskField::Notes    => edit.notes.replace('\n', " ⏎ "),
+            TaskField::Due      => edit.due.clone(),
+            TaskField::Priority => format!(
+                "{} {}",
+                if edit.priority > 0 { "!".repeat(edit.priority as usize) } else { "·".into() },
+                tasks::priority_label(edit.priority),
+            ),
+        };
+        let value = match (field, active) {
+            (TaskField::Priority, true) => format!("◀ {value} ▶"),
+            (_, true)                   => format!("{value}█"),
+            _                           => value,
+        };
+        lines.push(Line::from(vec![
+            Span::styled(format!("  {:<10}", field.label()), if active { accent } else { dim }),
+            Span::styled(value, if active { accent } else { fg }),
+        ]));
+        let hint = match field {
+            TaskField::Title => "#words are tags, ~45m or ~1h30m an estimate",
+            TaskField::Due   => "YYYY-MM-DD, +3d / -1w from today, or empty for none",
+            _                => "",
+        };
+        if !hint.is_empty() { lines.push(Line::from(Span::styled(format!("            {hint}"), dim))); }
+    }
+    f.render_widget(Paragraph::new(lines), rows[0]);
+    f.render_widget(
+        Paragraph::new(Span::styled("  Tab / ↑↓: field   ←→ or 0-3: priority   Enter: save   Esc: cancel", dim)),
+        rows[1],
+    );
+}
+
+// ─── Plan my day ─────────────────────────────────────────────────────────────
+
+fn draw_planning(f: &mut Frame, area: Rect, app: &App) {
+    let Some(ref p) = app.planning else { return };
+    let t    = &app.theme;
+    let rect = centered(60, 60, area);
+    f.render_widget(Clear, rect);
+
+    let block = Block::default()
+        .title(Title::from(Line::from(Span::styled(
+            format!(" Plan for {} ", p.date.format("%a %b %-d")),
+            Style::default().fg(t.accent()).add_modifier(Modifier::BOLD),
+        ))))
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .border_style(Style::default().fg(t.border_active()))
+        .style(Style::default().bg(t.popup_bg()));
+    let inner = block.inner(rect);
+    f.render_widget(block, rect);
+
+    let rows = Layout::default().direction(Direction::Vertical)
+        .constraints([Constraint::Min(0), Constraint::Length(1)]).split(inner);
+    let dim  = Style::default().fg(t.fg_dim());
+    let warn = Style::default().fg(t.warning_color());
+
+    let mut lines = vec![Line::from("")];
+    if p.plan.blocks.is_empty() {
+        lines.push(Line::from(Span::styled("  Nothing to plan: no estimated tasks due that fit the free time.", dim)));
+    }
+    let (bg, fg) = t.selected_highlight();
+    for (i, b) in p.plan.blocks.iter().enumerate() {
+        let style = if i == p.cursor { Style::default().bg(bg).fg(fg) } else { Style::default().fg(t.fg()) };
+        let mut spans = vec![
+            Span::styled(format!("  {}–{}  ", b.start.format("%H:%M"), b.end.format("%H:%M")), dim),
+            Span::styled(b.task.title.clone(), style),
+            Span::styled(format!(" ~{}", tasks::estimate_label(b.task.estimate_minutes)), dim),
+        ];
+        if b.task.priority > 0 {
+            spans.push(Span::styled(format!(" {}", "!".repeat(b.task.priority.min(tasks::MAX_PRIORITY) as usize)), warn));
+        }
+        lines.push(Line::from(spans));
+    }
+    let list = |ts: &[Task]| ts.iter().map(|t| t.title.as_str()).collect::<Vec<_>>().join(", ");
+    if !p.plan.unplaced.is_empty() {
+        lines.push(Line::from(""));
+        lines.push(Line::from(vec![
+            Span::styled("  Didn't fit: ", warn),
+            Span::styled(list(&p.plan.unplaced), dim),
+        ]));
+    }
+    if !p.plan.unestimated.is_empty() {
+        lines.push(Line::from(""));
+        lines.push(Line::from(vec![
+            Span::styled("  No estimate: ", warn),
+            Span::styled(format!("{} — add ~30m to the title with e", list(&p.plan.unestimated)), dim),
+        ]));
+    }
+    f.render_widget(Paragraph::new(lines).wrap(Wrap { trim: false }), rows[0]);
+    f.render_widget(
+        Paragraph::new(Span::styled("  j/k: select   x: leave out   Enter: add to calendar   Esc: cancel", dim)),
+        rows[1],
+    );
+}
+
+// ─── Confirmation prompt ─────────────────────────────────────────────────────
+
+fn draw_confirm(f: &mut Frame, area: Rect, app: &App) {
+    let Some(ref c) = app.confirm else { return };
+    let t    = &app.theme;
+    let rect = centered(50, 25, area);
+    f.render_widget(Clear, rect);
+
+    let block = Block::default()
+        .title(Title::from(Line::from(Span::styled(
+            " Are you sure? ",
+            Style::default().fg(t.warning_color()).add_modifier(Modifier::BOLD),
+        ))))
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .border_style(Style::default().fg(t.warning_color()))
+        .style(Style::default().bg(t.popup_bg()));
+    let inner = block.inner(rect);
+    f.render_widget(block, rect);
+
+    let rows = Layout::default().direction(Direction::Vertical)
+        .constraints([Constraint::Min(0), Constraint::Length(1)]).split(inner);
+    let dim   = Style::default().fg(t.fg_dim());
+    let lines = vec![
+        Line::from(""),
+        Line::from(Span::styled(format!("  {}", c.action.question()), Style::default().fg(t.fg()).add_modifier(Modifier::BOLD))),
+        Line::from(""),
+        Line::from(Span::styled(format!("  {}", c.action.consequence()), dim)),
+    ];
+    f.render_widget(Paragraph::new(lines).wrap(Wrap { trim: false }), rows[0]);
+    f.render_widget(Paragraph::new(Span::styled("  y / Enter: yes   n / Esc: no", dim)), rows[1]);
+}
+
+// ─── Batch action overlay ────────────────────────────────────────────────────
+
+fn draw_bulk(f: &mut Frame, area: Rect, app: &App) {
+    let Some(ref bulk) = app.bulk else { return };
+    let t    = &app.theme;
+    let rect = centered(60, 50, area);
+    f.render_widget(Clear, rect);
+
+    let verb = match bulk.action {
+        BulkAction::Delete   => "Delete",
+        BulkAction::Move     => "Move",
+        BulkAction::Calendar => if bulk.events.is_empty() { "Move to list" } else { "Move to calendar" },
+        BulkAction::Complete => "Complete",
+    };
+    let title = Line::from(Span::styled(
+        format!(" {verb} {} ", bulk.describe()),
+        Style::default().fg(t.accent()).add_modifier(Modifier::BOLD),
+    ));
+    let block = Block::default()
+        .title(Title::from(title))
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .border_style(Style::default().fg(t.border_active()))
+        .style(Style::default().bg(t.popup_bg()));
+    let inner = block.inner(rect);
+    f.render_widget(block, rect);
+
+    let rows = Layout::default().direction(Direction::Vertical)
+        .constraints([Constraint::Min(0), Constraint::Length(2), Constraint::Length(1)]).split(inner);
+    let dim    = Style::default().fg(t.fg_dim());
+    let fg     = Style::default().fg(t.fg());
+    let accent = Style::default().fg(t.accent()).add_modifier(Modifier::BOLD);
+
+    // Only what the action touches: completing leaves events be, and a
+    // mixed selection moves its events between calendars, not its tasks
+    let events: &[_] = if bulk.action == BulkAction::Complete { &[] } else { &bulk.events };
+    let tasks: &[_]  = if bulk.action == BulkAction::Calendar && !bulk.events.is_empty() { &[] } else { &bulk.tasks };
+    let mut lines = vec![Line::from("")];
+    for ev in events {
+        let when = if ev.all_day { ev.start.format("%a %b %-d  all-day") } else { ev.start.format("%a %b %-d  %H:%M") };
+        lines.push(Line::from(vec![
+            Span::styled(format!("  ● {when:<19} "), dim),
+            Span::styled(ev.title.clone(), fg),
+        ]));
+    }
+    for task in tasks {
+        let due = task.due.map_or_else(|| "no date".to_owned(), |d| d.format("%a %b %-d").to_string());
+        lines.push(Line::from(vec![
+            Span::styled(format!("  ○ {due:<19} "), dim),
+            Span::styled(task.title.clone(), fg),
+        ]));
+    }
+    f.render_widget(Paragraph::new(lines), rows[0]);
+
+    let param = match bulk.action {
+        BulkAction::Move => Line::from(vec![
+            Span::styled("  To date  ", dim),
+            Span::styled(format!("{}█", bulk.input), accent),
+            Span::styled("   YYYY-MM-DD, or +3d / -1w from the day shown", dim),
+        ]),
+        BulkAction::Calendar => Line::from(vec![
+            Span::styled("  To  ", dim),
+            Span::styled(format!("◀ {} ▶", bulk.targets[bulk.target].1), accent),
+            Span::styled("   Tab: next", dim),
+        ]),
+        _ => Line::from(""),
+    };
+    f.render_widget(Paragraph::new(param), rows[1]);
+    f.render_widget(Paragraph::new(Span::styled("  Enter: confirm   Esc: cancel", dim)), rows[2]);
+}
+
+// ─── Duplicate review overlay ────────────────────────────────────────────────
+
+fn draw_dedupe(f: &mut Frame, area: Rect, app: &App) {
+    let Some(ref d) = app.dedupe else { return };
+    let t     = &app.theme;
+    let rect  = centered(85, 60, area);
+    let group = d.group();
+    f.render_widget(Clear, rect);
+
+    let title = Line::from(Span::styled(
+        format!(" Likely duplicates {}/{} ", d.cluster + 1, d.clusters.len()),
+        Style::default().fg(t.accent()).add_modifier(Modifier::BOLD),
+    ));
+    let block = Block::default()
+        .title(Title::from(title))
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .border_style(Style::default().fg(t.border_active()))
+        .style(Style::default().bg(t.popup_bg()));
+    let inner = block.inner(rect);
+    f.render_widget(block, rect);
+
+    let rows = Layout::default().direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(1),
+            Constraint::Length(Field::ALL.len() as u16 + 1),
+            Constraint::Length(1),
+            Constraint::Min(0),
+            Constraint::Length(1),
+        ])
+        .split(inner);
+    let dim      = Style::default().fg(t.fg_dim());
+    let fg       = Style::default().fg(t.fg());
+    let accent   = Style::default().fg(t.accent()).add_modifier(Modifier::BOLD);
+    let (bg, sf) = t.selected_highlight();
+    let keep     = d.picks[0];
+    f.render_widget(Paragraph::new(Span::styled(format!("  {} events alike in title and time", group.len()), dim)), rows[0]);
+
+    // One column per event, headed by where it lives; the row selected
+    // shows which one's value the merge takes
+    let mut widths = vec![Constraint::Length(9)];
+    widths.extend(group.iter().map(|_| Constraint::Ratio(1, group.len() as u32)));
+    let cols = Layout::default().direction(Direction::Horizontal).constraints(widths).split(rows[1]);
+
+    let mut labels = vec![Line::from("")];
+    labels.extend(Field::ALL.iter().enumerate().map(|(r, field)| {
+        let style = if r == d.field { Style::default().bg(bg).fg(sf) } else { dim };
+        Line::from(Span::styled(format!(" {:<7}", field.label()), style))
+    }));
+    f.render_widget(Paragraph::new(labels), cols[0]);
+
+    for (i, ev) in group.iter().enumerate() {
+        let calendar = ev.calendar_id.as_ref().map_or("primary", |id| d.calendars.get(id).unwrap_or(id));
+        let synced   = if ev.sync_id.is_some() { "synced" } else { "local only" };
+        let mut lines = vec![Line::from(Span::styled(format!(" {calendar} · {synced}"), dim))];
+        for (r, field) in Field::ALL.iter().enumerate() {
+            let value = match field {
+                Field::Keep if i == keep => "keep".to_owned(),
+                Field::Keep if ev.sync_id.is_some() && ev.sync_id != group[keep].sync_id => "delete, on Google too".to_owned(),
+                Field::Keep => "delete".to_owned(),
+                _           => field.show(ev),
+            };
+            let picked = d.picks[r] == i;
+            let style  = match (picked, r == d.field) {
+                (true, true)  => Style::default().bg(bg).fg(sf).add_modifier(Modifier::BOLD),
+                (true, false) => accent,
+                _             => dim,
+            };
+            lines.push(Line::from(vec![
+                Span::styled(if picked { " ● " } else { "   " }, style),
+                Span::styled(value, if picked { style } else { fg }),
+            ]));
+        }
+        f.render_widget(Paragraph::new(lines), cols[i + 1]);
+    }
+
+    if group.len() > 1 {
+        let merged  = dedupe::merge(&group, &d.picks, chrono::Utc::now());
+        let remote  = merged.dropped.iter().filter(|e| e.dirty).count();
+        let mut out = format!("  → “{}”  {}", merged.kept.title, Field::When.show(&merged.kept));
+        if let Some(ref tag) = merged.kept.tag { out.push_str(&format!("  #{tag}")); }
+        out.push_str(&format!("   {} deleted", merged.dropped.len()));
+        if remote > 0 { out.push_str(&format!(", {remote} on Google too")); }
+        f.render_widget(Paragraph::new(Span::styled(out, fg)), rows[3]);
+    }
+    f.render_widget(
+        Paragraph::new(Span::styled("  j/k: field   h/l: take from   Enter: merge   n/p: next/previous group   Esc: close", dim)),
+        rows[4],
+    );
+}
+
+// ─── Custom panels overlay ───────────────────────────────────────────────────
+
+fn draw_send_to(f: &mut Frame, area: Rect, app: &App) {
+    let Some(send_to) = app.send_to.as_ref() else { return };
+    let t      = &app.theme;
+    let height = (send_to.choices.len() as u16 + 3).min(area.height);
+    let width  = 50.min(area.width);
+    let rect   = Rect::new(area.x + (area.width - width) / 2, area.y + (area.height - height) / 2, width, height);
+    f.render_widget(Clear, rect);
+
+    let title = Line::from(Span::styled(
+        format!(" Send “{}” to ", send_to.subject.title),
+        Style::default().fg(t.accent()).add_modifier(Modifier::BOLD),
+    ));
+    let block = Block::default()
+        .title(Title::from(title))
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .border_style(Style::default().fg(t.border_active()))
+        .style(Style::default().bg(t.popup_bg()));
+    let inner = block.inner(rect);
+    f.render_widget(block, rect);
+
+    let rows = Layout::default().direction(Direction::Vertical)
+        .constraints([Constraint::Min(0), Constraint::Length(1)]).split(inner);
+    let (bg, fg) = t.selected_highlight();
+    let lines: Vec<Line> = send_to.choices.iter().enumerate().filter_map(|(n, &i)| {
+        let action = app.actions.list.get(i)?;
+        let style  = if n == app.ui.action_cursor { Style::default().bg(bg).fg(fg) } else { Style::default().fg(t.fg()) };
+        Some(Line::from(vec![
+            Span::styled(format!(" {} ", n + 1), Style::default().fg(t.accent())),
+            Span::styled(format!(" {} ", action.name), style),
+        ]))
+    }).collect();
+    f.render_widget(Paragraph::new(lines), rows[0]);
+    f.render_widget(Paragraph::new(Span::styled(
+        "  j/k or 1-9: pick  Enter: send  Esc: cancel", Style::default().fg(t.fg_dim()),
+    )), rows[1]);
+}
+
+/// `Ctrl+p`: the typed filter on top, matching commands under it with
+/// their keys on the right; high on the screen, so it covers less of the day.
+fn draw_palette(f: &mut Frame, area: Rect, app: &App) {
+    let t       = &app.theme;
+    let found   = palette::matches(app, &app.ui.palette_query);
+    let width   = 60.min(area.width);
+    let height  = (found.len() as u16 + 5).clamp(6, (area.height * 2 / 3).max(6)).min(area.height);
+    let rect    = Rect::new(area.x + (area.width - width) / 2, area.y + area.height / 6, width, height);
+    f.render_widget(Clear, rect);
+
+    let title = Line::from(Span::styled(
+        " Commands ",
+        Style::default().fg(t.accent()).add_modifier(Modifier::BOLD),
+    ));
+    let block = Block::default()
+        .title(Title::from(title))
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .border_style(Style::default().fg(t.border_active()))
+        .style(Style::default().bg(t.popup_bg()));
+    let inner = block.inner(rect);
+    f.render_widget(block, rect);
+
+    let rows = Layout::default().direction(Direction::Vertical)
+        .constraints([Constraint::Length(2), Constraint::Min(0), Constraint::Length(1)]).split(inner);
+    let dim = Style::default().fg(t.fg_dim());
+    f.render_widget(Paragraph::new(Line::from(vec![
+        Span::styled(" > ", Style::default().fg(t.accent()).add_modifier(Modifier::BOLD)),
+        Span::styled(format!("{}▏", app.ui.palette_query), Style::default().fg(t.fg())),
+    ])), rows[0]);
+
+    let (bg, fg) = t.selected_highlight();
+    let shown    = rows[1].height as usize;
+    let first    = (app.ui.palette_cursor + 1).saturating_sub(shown);
+    let room     = rows[1].width as usize;
+    let mut lines: Vec<Line> = found.iter().enumerate().skip(first).take(shown).map(|(i, c)| {
+        let style = if i == app.ui.palette_cursor { Style::default().bg(bg).fg(fg) } else { Style::default().fg(t.fg()) };
+        let gap   = room.saturating_sub(c.label.width() + c.keys.width() + 3);
+        Line::from(vec![
+            Span::styled(format!(" {}{}", c.label, " ".repeat(gap)), style),
+            Span::styled(format!(" {} ", c.keys), if i == app.ui.palette_cursor { style } else { dim }),
+        ])
+    }).collect();
+    if lines.is_empty() {
+        lines.push(Line::from(Span::styled(" No command matches", dim)));
+    }
+    f.render_widget(Paragraph::new(lines), rows[1]);
+    f.render_widget(Paragraph::new(Span::styled(
+        "  type to filter   ↑/↓: pick   Enter: run   Esc: close", dim,
+    )), rows[2]);
+}
+
+fn draw_plugins(f: &mut Frame, area: Rect, app: &App) {
+    let t     = &app.theme;
+    let rect  = centered(70, 70, area);
+    let tab   = app.ui.plugin_tab;
+    let Some(state) = app.plugins.states.get(tab) else { return };
+    f.render_widget(Clear, rect);
+
+    // Every panel's name along the top, the one shown picked out
+    let mut tabs = vec![Span::raw(" ")];
+    for (i, p) in app.plugins.panels.iter().enumerate() {
+        let style = if i == tab {
+            Style::default().fg(t.accent()).add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(t.fg_dim())
+        };
+        tabs.push(Span::styled(format!("{} {} ", i + 1, p.name), style));
+    }
+    let status = match (state.running, state.fetched) {
+        (true, _)         => " running… ".to_owned(),
+        (false, Some(at)) => format!(" {}s ago ", at.elapsed().as_secs()),
+        (false, None)     => String::new(),
+    };
+    let block = Block::default()
+        .title(Title::from(Line::from(tabs)))
+        .title(Title::from(Span::styled(status, Style::default().fg(t.fg_dim()))).alignment(Alignment::Right))
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .border_style(Style::default().fg(t.border_active()))
+        .style(Style::default().bg(t.popup_bg()));
+    let inner = block.inner(rect);
+    f.render_widget(block, rect);
+
+    let rows = Layout::default().direction(Direction::Vertical)
+        .constraints([Constraint::Min(0), Constraint::Length(1)]).split(inner);
+    let dim      = Style::default().fg(t.fg_dim());
+    let (bg, fg) = t.selected_highlight();
+
+    let mut list: Vec<Line> = Vec::new();
+    if let Some(ref e) = state.error {
+        list.push(Line::from(Span::styled(format!(" ✗ {e}"), Style::default().fg(t.warning_color()))));
+    }
+    for (i, item) in state.items.iter().enumerate() {
+        let (mark, color) = match item.level {
+            plugins::Level::Info  => ("•", t.fg()),
+            plugins::Level::Ok    => ("✓", t.accent()),
+            plugins::Level::Warn  => ("⚠", t.warning_color()),
+            plugins::Level::Error => ("✗", t.warning_color()),
+        };
+        let style = if i == app.ui.plugin_cursor { Style::default().bg(bg).fg(fg) } else { Style::default().fg(color) };
+        let mut spans = vec![Span::styled(format!(" {mark} {} ", item.title), style)];
+        if let Some(ref d) = item.detail { spans.push(Span::styled(format!(" {d}"), dim)); }
+        if item.url.is_some() { spans.push(Span::styled(" ↗", dim)); }
+        list.push(Line::from(spans));
+    }
+    if state.items.is_empty() && state.error.is_none() {
+        let empty = if state.fetched.is_none() { " Loading…" } else { " Nothing to show" };
+        list.push(Line::from(Span::styled(empty, dim)));
+    }
+    // Keep the cursor row on screen
+    let offset = usize::from(state.error.is_some());
+    let skip   = (app.ui.plugin_cursor + offset).saturating_sub(rows[0].height.saturating_sub(1) as usize);
+    f.render_widget(Paragraph::new(list.split_off(skip.min(list.len()))), rows[0]);
+
+    f.render_widget(
+        Paragraph::new(Span::styled("  j/k: select   Enter: open link   Tab / 1-9: panel   r: run again   Esc: close", dim)),
+        rows[1],
+    );
+}
+
+// ─── Quarantine review overlay ───────────────────────────────────────────────
+
+fn draw_quarantine(f: &mut Frame, area: Rect, app: &App) {
+    let t    = &app.theme;
+    let rect = centered(75, 75, area);
+    f.render_widget(Clear, rect);
+
+    let title = Line::from(Span::styled(
+        format!(" ⚠ Quarantined imports ({}) ", app.quarantine.len()),
+        Style::default().fg(t.accent()).add_modifier(Modifier::BOLD),
+    ));
+    let block = Block::default()
+        .title(Title::from(title))
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .border_style(Style::default().fg(t.border_active()))
+        .style(Style::default().bg(t.popup_bg()));
+    let inner = block.inner(rect);
+    f.render_widget(block, rect);
+
+    let rows = Layout::default().direction(Direction::Vertical)
+        .constraints([Constraint::Length(8), Constraint::Min(0), Constraint::Length(1)]).split(inner);
+
+    let dim    = Style::default().fg(t.fg_dim());
+    let (bg, fg) = t.selected_highlight();
+    let typing = app.ui.input_mode == InputMode::Insert;
+
+    let mut list: Vec<Line> = app.quarantine.iter().enumerate().map(|(i, q)| {
+        let style = if i == app.ui.review_cursor { Style::default().bg(bg).fg(fg) } else { Style::default().fg(t.fg()) };
+        Line::from(vec![
+            Span::styled(format!(" {} ", q.title), style),
+            Span::styled(format!(" {} · {}", q.source, q.reason), dim),
+        ])
+    }).collect();
+    if list.is_empty() {
+        list.push(Line::from(Span::styled(" Nothing to review — every import passed validation", dim)));
+    }
+    // Keep the cursor row on screen
+    let skip = app.ui.review_cursor.saturating_sub(rows[0].height.saturating_sub(1) as usize);
+    f.render_widget(Paragraph::new(list.split_off(skip.min(list.len()))), rows[0]);
+
+    // Detail of the selected item: what we could parse, then the raw data
+    let mut detail: Vec<Line> = Vec::new();
+    if let Some(q) = app.quarantine.get(app.ui.review_cursor) {
+        let when = |d: Option<DateTime<Utc>>| d.map_or("—".to_owned(), |d| d.format("%Y-%m-%d %H:%M").to_string());
+        detail.push(Line::from(Span::styled(
+            format!(" start {}   end {}   received {}", when(q.start), when(q.end), q.received_at.format("%b %-d %H:%M")),
+            Style::default().fg(t.fg()),
+        )));
+        if typing {
+            let label = if app.ui.review_start.is_none() { "Start" } else { "End" };
+            detail.push(Line::from(Span::styled(
+                format!(" {label}: {}█", app.ui.review_input),
+                Style::default().fg(t.accent()).add_modifier(Modifier::BOLD),
+            )));
+        }
+        detail.push(Line::from(""));
+        detail.extend(q.raw.lines().map(|l| Line::from(Span::styled(format!(" {l}"), dim))));
+    }
+    let detail_block = Block::default()
+        .borders(Borders::TOP)
+        .border_style(Style::default().fg(t.border()));
+    f.render_widget(Paragraph::new(detail).block(detail_block).wrap(Wrap { trim: false }), rows[1]);
+
+    let hint = if typing {
+        "  YYYY-MM-DD HH:MM (or YYYY-MM-DD for all-day)   Enter: next / save   Esc: cancel"
+    } else {
+        "  j/k: select   e/Enter: fix times   x: discard   Esc: close"
+    };
+    f.render_widget(Paragraph::new(Span::styled(hint, dim)), rows[2]);
+}
+
+// ─── Log viewer overlay ──────────────────────────────────────────────────────
+
+fn draw_logs(f: &mut Frame, area: Rect, app: &App) {
+    let t    = &app.theme;
+    let rect = centered(85, 80, area);
+    f.render_widget(Clear, rect);
+
+    let title = Line::from(Span::styled(
+        format!(" Log · {} and above ", app.ui.log_level.label()),
+        Style::default().fg(t.accent()).add_modifier(Modifier::BOLD),
+    ));
+    let block = Block::default()
+        .title(Title::from(title))
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .border_style(Style::default().fg(t.border_active()))
+        .style(Style::default().bg(t.popup_bg()));
+    let inner = block.inner(rect);
+    f.render_widget(block, rect);
+
+    let rows = Layout::default().direction(Direction::Vertical)
+        .constraints([Constraint::Min(0), Constraint::Length(1)]).split(inner);
+    let dim = Style::default().fg(t.fg_dim());
+
+    // The window ends log_scroll lines above the newest
+    let end   = app.log_lines.len().saturating_sub(app.ui.log_scroll);
+    let start = end.saturating_sub(rows[0].height as usize);
+    let mut lines: Vec<Line> = app.log_lines[start..end].iter().map(|l| {
+        let color = match l.level {
+            Level::Error => t.error_color(),
+            Level::Warn  => t.warning_color(),
+            Level::Info  => t.fg(),
+            _            => t.fg_dim(),
+        };
+        Line::from(Span::styled(format!(" {}", l.text), Style::default().fg(color)))
+    }).collect();
+    if lines.is_empty() {
+        lines.push(Line::from(Span::styled(
+            format!(" Nothing at {} or above yet", app.ui.log_level.label()), dim,
+        )));
+    }
+    f.render_widget(Paragraph::new(lines), rows[0]);
+    draw_line_scrollbar(f, app, rect, app.log_lines.len(), rows[0].height as usize, start);
+
+    let hint = format!(
+        "  j/k: scroll   g/G: oldest/newest   l: level   r: reload   Esc: close   {}",
+        logs::dir().display(),
+    );
+    f.render_widget(Paragraph::new(Span::styled(hint, dim)), rows[1]);
+}
+
+/// `Y`: what the sync pulled and pushed, newest at the bottom.
+fn draw_sync_log(f: &mut Frame, area: Rect, app: &App) {
+    let t    = &app.theme;
+    let rect = centered(85, 80, area);
+    f.render_widget(Clear, rect);
+
+    let title = Line::from(Span::styled(
+        " Sync log ",
+        Style::default().fg(t.accent()).add_modifier(Modifier::BOLD),
+    ));
+    let block = Block::default()
+        .title(Title::from(title))
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .border_style(Style::default().fg(t.border_active()))
+        .style(Style::default().bg(t.popup_bg()));
+    let inner = block.inner(rect);
+    f.render_widget(block, rect);
+
+    let rows = Layout::default().direction(Direction::Vertical)
+        .constraints([Constraint::Min(0), Constraint::Length(1)]).split(inner);
+    let dim = Style::default().fg(t.fg_dim());
+
+    let end   = app.sync_log.len().saturating_sub(app.ui.sync_log_scroll);
+    let start = end.saturating_sub(rows[0].height as usize);
+    let mut lines: Vec<Line> = app.sync_log[start..end].iter().map(|e| {
+        let arrow = if e.direction == "push" { "↑" } else { "↓" };
+        let mut spans = vec![
+            Span::styled(format!(" {}  ", e.at.with_timezone(&chrono::Local).format("%m-%d %H:%M")), dim),
+            Span::styled(format!("{arrow} {:<5} {:<10} ", e.entity, e.action), Style::default().fg(t.accent())),
+            Span::styled(e.title.clone(), Style::default().fg(t.fg())),
+        ];
+        if e.result != "ok" {
+            spans.push(Span::styled(format!("  {}", e.result), Style::default().fg(t.warning_color())));
+        }
+        Line::from(spans)
+    }).collect();
+    if lines.is_empty() {
+        lines.push(Line::from(Span::styled(" Nothing pulled or pushed yet", dim)));
+    }
+    f.render_widget(Paragraph::new(lines), rows[0]);
+    draw_line_scrollbar(f, app, rect, app.sync_log.len(), rows[0].height as usize, start);
+
+    f.render_widget(Paragraph::new(Span::styled(
+        "  ↓ pulled  ↑ pushed   j/k: scroll   g/G: oldest/newest   r: reload   Esc: close", dim,
+    )), rows[1]);
+}
+
+// ─── Themes ──────────────────────────────────────────────────────────────────
+
+fn draw_theme_picker(f: &mut Frame, area: Rect, app: &App) {
+    let t    = &app.theme;
+    let rect = centered(50, 60, area);
+    f.render_widget(Clear, rect);
+
+    let title = Line::from(Span::styled(
+        format!(" Themes ({}) ", app.themes.len()),
+        Style::default().fg(t.accent()).add_modifier(Modifier::BOLD),
+    ));
+    let block = Block::default()
+        .title(Title::from(title))
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .border_style(Style::default().fg(t.border_active()))
+        .style(Style::default().bg(t.popup_bg()));
+    let inner = block.inner(rect);
+    f.render_widget(block, rect);
+
+    let rows = Layout::default().direction(Direction::Vertical)
+        .constraints([Constraint::Min(0), Constraint::Length(1)]).split(inner);
+
+    let (bg, fg) = t.selected_highlight();
+    let active   = app.ui.theme_original.as_ref().map(|o| o.name.as_str());
+    let mut list: Vec<Line> = app.themes.iter().enumerate().map(|(i, theme)| {
+        let style = if i == app.ui.theme_cursor { Style::default().bg(bg).fg(fg) } else { Style::default().fg(t.fg()) };
+        let mark  = if Some(theme.name.as_str()) == active { "● " } else { "  " };
+        // A strip of each theme's own colours
+        let mut spans: Vec<Span> = [&theme.bg_primary, &theme.text_accent, &theme.event_dot, &theme.weekend_fg, &theme.warning]
+            .iter()
+            .map(|hex| Span::styled("  ", Style::default().bg(hex_to_color(hex))))
+            .collect();
+        spans.insert(0, Span::styled(format!(" {mark}"), Style::default().fg(t.accent())));
+        spans.push(Span::styled(format!(" {}", theme.name), style));
+        Line::from(spans)
+    }).collect();
+    let skip = app.ui.theme_cursor.saturating_sub(rows[0].height.saturating_sub(1) as usize);
+    f.render_widget(Paragraph::new(list.split_off(skip.min(list.len()))), rows[0]);
+
+    f.render_widget(Paragraph::new(Span::styled(
+        "  j/k: preview  Enter: use  e: edit  Esc: cancel", Style::default().fg(t.fg_dim()),
+    )), rows[1]);
+}
+
+/// `V`: a checkbox per holiday country, then the dim switch.
+fn draw_holidays(f: &mut Frame, area: Rect, app: &App) {
+    let t    = &app.theme;
+    let rect = centered(40, 40, area);
+    f.render_widget(Clear, rect);
+
+    let block = Block::default()
+        .title(Title::from(Line::from(Span::styled(
+            " Holidays ",
+            Style::default().fg(t.accent()).add_modifier(Modifier::BOLD),
+        ))))
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .border_style(Style::default().fg(t.border_active()))
+        .style(Style::default().bg(t.popup_bg()));
+    let inner = block.inner(rect);
+    f.render_widget(block, rect);
+
+    let rows = Layout::default().direction(Direction::Vertical)
+        .constraints([Constraint::Min(0), Constraint::Length(1)]).split(inner);
+
+    let (bg, fg) = t.selected_highlight();
+    let mut items: Vec<(bool, String)> = app.holidays.countries().into_iter()
+        .map(|c| (app.holidays_shown(c), format!("{c} holidays")))
+        .collect();
+    items.push((app.dim_holidays, "Dim them on the grid".into()));
+    let mut lines = vec![Line::from("")];
+    lines.extend(items.into_iter().enumerate().map(|(i, (on, label))| {
+        let style = if i == app.ui.holiday_cursor { Style::default().bg(bg).fg(fg) } else { Style::default().fg(t.fg()) };
+        Line::from(vec![
+            Span::styled(if on { "  [x] " } else { "  [ ] " }, Style::default().fg(t.accent())),
+            Span::styled(label, style),
+        ])
+    }));
+    f.render_widget(Paragraph::new(lines), rows[0]);
+
+    f.render_widget(Paragraph::new(Span::styled(
+        "  j/k: move  Space: toggle  Esc: close", Style::default().fg(t.fg_dim()),
+    )), rows[1]);
+}
+
+fn draw_theme_editor(f: &mut Frame, area: Rect, app: &App) {
+    let t    = &app.theme;
+    let rect = centered(60, 80, area);
+    f.render_widget(Clear, rect);
+
+    let title = Line::from(Span::styled(
+        format!(" Theme editor — {} ", t.name),
+        Style::default().fg(t.accent()).add_modifier(Modifier::BOLD),
+    ));
+    let block = Block::default()
+        .title(Title::from(title))
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .border_style(Style::default().fg(t.border_active()))
+        .style(Style::default().bg(t.popup_bg()));
+    let inner = block.inner(rect);
+    f.render_widget(block, rect);
+
+    let rows = Layout::default().direction(Direction::Vertical)
+        .constraints([Constraint::Min(0), Constraint::Length(4), Constraint::Length(1)]).split(inner);
+
+    let dim    = Style::default().fg(t.fg_dim());
+    let (bg, fg) = t.selected_highlight();
+    let typing = app.ui.input_mode == InputMode::Insert;
+
+    let mut fields: Vec<Line> = COLOR_FIELDS.iter().enumerate().map(|(i, name)| {
+        let hex   = t.color(i);
+        let sel   = i == app.ui.theme_field;
+        let style = if sel { Style::default().bg(bg).fg(fg) } else { Style::default().fg(t.fg()) };
+        let value = if sel && typing && !app.ui.theme_naming {
+            format!("{}█", app.ui.theme_input)
+        } else {
+            hex.to_owned()
+        };
+        Line::from(vec![
+            Span::styled("   ", Style::default().bg(hex_to_color(hex))),
+            Span::styled(format!(" {name:<16}"), style),
+            Span::styled(format!(" {value}"), dim),
+        ])
+    }).collect();
+    let skip = app.ui.theme_field.saturating_sub(rows[0].height.saturating_sub(1) as usize);
+    f.render_widget(Paragraph::new(fields.split_off(skip.min(fields.len()))), rows[0]);
+
+    // Palette strip, the current swatch marked
+    let swatches: Vec<Span> = ThemeConfig::palette().iter().enumerate().map(|(i, hex)| {
+        let mark = if i == app.ui.theme_swatch { "▼" } else { " " };
+        Span::styled(mark, Style::default().fg(t.fg()).bg(hex_to_color(hex)))
+    }).collect();
+    let strip = Block::default().borders(Borders::TOP).border_style(Style::default().fg(t.border()));
+    let mut bottom = vec![Line::from(swatches)];
+    if typing && app.ui.theme_naming {
+        bottom.push(Line::from(Span::styled(
+            format!(" Save as: {}█", app.ui.theme_input),
+            Style::default().fg(t.accent()).add_modifier(Modifier::BOLD),
+        )));
+    }
+    f.render_widget(Paragraph::new(bottom).block(strip).wrap(Wrap { trim: false }), rows[1]);
+
+    let hint = match (typing, app.ui.theme_naming) {
+        (true, true)  => "  Enter: save to themes/   Esc: back",
+        (true, false) => "  #rrggbb, ANSI name, 0–255 or default   Enter: apply   Esc: back",
+        _             => "  j/k: field  h/l: palette  #: hex  u: undo  s: save as  Esc: discard",
+    };
+    f.render_widget(Paragraph::new(Span::styled(hint, dim)), rows[2]);
+}
+
+// ─── Time report overlay ─────────────────────────────────────────────────────
+
+fn draw_reports(f: &mut Frame, area: Rect, app: &App) {
+    let t    = &app.theme;
+    let rect = centered(60, 60, area);
+    f.render_widget(Clear, rect);
+
+    let sunday = add_days(app.report_week, 6).unwrap_or(app.report_week);
+    let title = Line::from(Span::styled(
+        format!(" Time by {} · {} – {} ", app.report_group.label(), app.report_week.format("%b %-d"), sunday.format("%b %-d")),
+        Style::default().fg(t.accent()).add_modifier(Modifier::BOLD),
+    ));
+    let block = Block::default()
+        .title(Title::from(title))
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .border_style(Style::default().fg(t.border_active()))
+        .style(Style::default().bg(t.popup_bg()));
+    let inner = block.inner(rect);
+    f.render_widget(block, rect);
+
+    let rows = Layout::default().direction(Direction::Vertical)
+        .constraints([Constraint::Min(0), Constraint::Length(1)]).split(inner);
+    let dim = Style::default().fg(t.fg_dim());
+    let fg  = Style::default().fg(t.fg());
+
+    let report = &app.report;
+    let mut lines = vec![Line::from("")];
+    if report.rows.is_empty() {
+        lines.push(Line::from(Span::styled(
+            "  No tracked time this week — m marks an event, Ctrl+t runs a timer", dim,
+        )));
+    }
+    // Bars scale to the biggest group and fill what the labels leave
+    let name_w = report.rows.iter().map(|(n, _)| n.width()).max().unwrap_or(0).min(20);
+    let bar_w  = (rows[0].width as usize).saturating_sub(name_w + 14).max(1);
+    let peak   = report.rows.first().map_or(0.0, |(_, h)| *h);
+    for (name, hours) in &report.rows {
+        let bar   = if peak > 0.0 { (hours / peak * bar_w as f64).round() as usize } else { 0 };
+        let color = match app.report_group {
+            timelog::GroupBy::Tag => app.tag_color(name.strip_prefix('#')),
+            _                     => t.event_color(),
+        };
+        lines.push(Line::from(vec![
+            Span::styled(format!("  {} ", pad(name, name_w)), fg),
+            Span::styled(format!("{hours:>5.1} h "), fg),
+            Span::styled("█".repeat(bar.max(1)), Style::default().fg(color)),
+        ]));
+    }
+    if !report.rows.is_empty() {
+        lines.push(Line::from(""));
+        lines.push(Line::from(Span::styled(format!("  {:<name_w$} {:>5.1} h", "Total", report.total), Style::default().fg(t.accent()))));
+    }
+    f.render_widget(Paragraph::new(lines), rows[0]);
+    f.render_widget(Paragraph::new(Span::styled("  h/l: prev/next week   g: by tag / calendar   Esc: close", dim)), rows[1]);
+}
+
+// ─── Stats overlay ───────────────────────────────────────────────────────────
+
+fn draw_stats(f: &mut Frame, area: Rect, app: &App) {
+    let t    = &app.theme;
+    let rect = centered(70, 80, area);
+    f.render_widget(Clear, rect);
+
+    let title = Line::from(Span::styled(
+        " Stats ",
+        Style::default().fg(t.accent()).add_modifier(Modifier::BOLD),
+    ));
+    let block = Block::default()
+        .title(Title::from(title))
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .border_style(Style::default().fg(t.border_active()))
+        .style(Style::default().bg(t.popup_bg()));
+
+    let accent = Style::default().fg(t.accent()).add_modifier(Modifier::BOLD);
+    let dim    = Style::default().fg(t.fg_dim());
+    let fg     = Style::default().fg(t.fg());
+
+    let mut lines = vec![
+        Line::from(""),
+        Line::from(Span::styled("  Time off (PTO)", accent)),
+    ];
+    if app.config.pto.is_none() {
+        lines.push(Line::from(Span::styled(
+            "  Add a [pto] section to config.toml to track your allowance", dim,
+        )));
+    } else {
+        lines.push(Line::from(Span::styled(
+            "  Year   Allowance  Carried   Used  Remaining", dim,
+        )));
+        for y in &app.pto_summary {
+            let rem_style = if y.remaining() < 0.0 {
+                Style::default().fg(t.weekend_color()).add_modifier(Modifier::BOLD)
+            } else {
+                fg
+            };
+            lines.push(Line::from(vec![
+                Span::styled(
+                    format!("  {}   {:>9.1}  {:>7.1}  {:>5.1}", y.year, y.allowance, y.carried_in, y.used),
+                    fg,
+                ),
+                Span::styled(format!("  {:>9.1}", y.remaining()), rem_style),
+            ]));
+        }
+    }
+
+    let load = &app.meeting_load;
+    lines.push(Line::from(""));
+    lines.push(Line::from(Span::styled(
+        format!("  Meeting load (last {} days)", attendance::WINDOW_DAYS), accent,
+    )));
+    if load.events == 0 {
+        lines.push(Line::from(Span::styled("  No timed events in this period", dim)));
+    } else {
+        let row = |label: &str, hours: f64| Line::from(vec![
+            Span::styled(format!("  {label:<11}"), dim),
+            Span::styled(format!("{hours:>6.1} h  {:>5.1} h/week", MeetingLoad::per_week(hours)), fg),
+        ]);
+        lines.push(row("Scheduled", load.scheduled));
+        lines.push(row("Actual", load.actual()));
+        lines.push(Line::from(Span::styled(
+            format!(
+                "  {:.1} h attended · {:.1} h skipped · {:.1} h cancelled · {:.1} h unmarked",
+                load.attended, load.skipped, load.cancelled, load.unmarked,
+            ),
+            dim,
+        )));
+    }
+
+    lines.push(Line::from(""));
+    lines.push(Line::from(Span::styled(
+        format!("  Focus time ({}–{} on working days)", app.config.focus.day_start, app.config.focus.day_end),
+        accent,
+    )));
+    if app.focus_weeks.is_empty() {
+        lines.push(Line::from(Span::styled("  [focus] day_start / day_end must be HH:MM, start before end", dim)));
+    } else {
+        lines.push(Line::from(Span::styled(
+            "  Week of  Meetings          Short gaps  Longest  Deep work", dim,
+        )));
+        // Bars scale to the busiest week so the trend reads at a glance
+        let peak = app.focus_weeks.iter().map(|w| w.meeting_hours).fold(0.0, f64::max);
+        for w in &app.focus_weeks {
+            let bar = if peak > 0.0 { (w.meeting_hours / peak * 8.0).round() as usize } else { 0 };
+            let longest = w.longest_block.num_minutes();
+            lines.push(Line::from(vec![
+                Span::styled(format!("  {:<9}", w.monday.format("%b %-d")), fg),
+                Span::styled(format!("{:>5.1} h ", w.meeting_hours), fg),
+                Span::styled(format!("{:<8}", "▇".repeat(bar)), Style::default().fg(t.event_color())),
+                Span::styled(format!("  {:>10}  {:>2}h{:02}", w.short_gaps, longest / 60, longest % 60), fg),
+                Span::styled(format!("  {:>7.1} h", w.deep_work_hours), Style::default().fg(t.success_color())),
+            ]));
+        }
+    }
+    lines.push(Line::from(""));
+    lines.push(Line::from(Span::styled("  p on an event toggles PTO   a checks in   Esc: close", dim)));
+
+    f.render_widget(
+        Paragraph::new(lines).block(block).style(Style::default().fg(t.fg())),
+        rect,
+    );
+}
+
+// ─── Today overlay ───────────────────────────────────────────────────────────
+
+/// `Space G`, and the first screen with `[ui] startup_digest`.
+fn draw_today(f: &mut Frame, area: Rect, app: &App) {
+    let t    = &app.theme;
+    let rect = centered(60, 70, area);
+    f.render_widget(Clear, rect);
+
+    let Some(digest) = &app.digest else { return };
+    let title = Line::from(Span::styled(
+        format!(" {} ", digest.date.format("%A, %B %-d")),
+        Style::default().fg(t.accent()).add_modifier(Modifier::BOLD),
+    ));
+    let block = Block::default()
+        .title(Title::from(title))
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .border_style(Style::default().fg(t.border_active()))
+        .style(Style::default().bg(t.popup_bg()));
+
+    let dim = Style::default().fg(t.fg_dim());
+    let fg  = Style::default().fg(t.fg());
+    let mut lines = vec![Line::from("")];
+    let sections  = digest.sections();
+    if sections.is_empty() {
+        lines.push(Line::from(Span::styled("  Nothing on today", dim)));
+    }
+    for s in &sections {
+        let color = if s.heading == "Overdue" { t.error_color() } else { t.accent() };
+        lines.push(Line::from(Span::styled(format!("  {}", s.heading), Style::default().fg(color).add_modifier(Modifier::BOLD))));
+        let width = s.rows.iter().map(|r| r.when.width()).max().unwrap_or(0);
+        lines.extend(s.rows.iter().map(|r| Line::from(vec![
+            Span::styled(format!("  {}{}  ", r.when, " ".repeat(width - r.when.width())), dim),
+            Span::styled(r.what.clone(), fg),
+        ])));
+        lines.push(Line::from(""));
+    }
+    lines.push(Line::from(Span::styled("  r: reload   Esc: close", dim)));
+
+    f.render_widget(Paragraph::new(lines).block(block), rect);
+}
+
+// ─── Utilities ────────────────────────────────────────────────────────────────
+
+fn centered(pct_x: u16, pct_y: u16, r: Rect) -> Rect {
+    let vert = Layout::default().direction(Direction::Vertical)
+        .constraints([
+            Constraint::Percentage((100 - pct_y) / 2),
+            Constraint::Percentage(pct_y),
+            Constraint::Percentage((100 - pct_y) / 2),
+        ]).split(r);
+    Layout::default().direction(Direction::Horizontal)
+        .constraints([
+            Constraint::Percentage((100 - pct_x) / 2),
+            Constraint::Percentage(pct_x),
+            Constraint::Percentage((100 - pct_x) / 2),
+        ]).split(vert[1])[1]
+}
+
+/// `s` cut to at most `cols` terminal columns. Emoji and CJK take two, and
+/// a variation selector can widen the character before it, so the width is
+/// measured on the text so far rather than added up per character.
+fn fit(s: &str, cols: usize) -> String {
+    let mut out = String::new();
+    for c in s.chars() {
+        out.push(c);
+        if out.width() > cols {
+            out.pop();
+            break;
+        }
+    }
+    // A joiner left dangling would glue onto whatever follows
+    while out.ends_with('\u{200D}') { out.pop(); }
+    out
+}
+
+/// `s` fitted to `cols` columns and padded with spaces to fill them.
+fn pad(s: &str, cols: usize) -> String {
+    let s = fit(s, cols);
+    let w = s.width();
+    s + &" ".repeat(cols - w)
 }
 
 fn month_name(m: u32) -> &'static str {
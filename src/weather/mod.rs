@@ -0,0 +1,139 @@
+//! The week's forecast from Open-Meteo (no account or key), for the strip
+//! under the month grid and the selected day in its title. The sync worker
+//! refreshes it; the `weather` table caches it so it shows at once, and
+//! offline, until it's `ttl_minutes` old.
+
+use anyhow::{anyhow, Result};
+use chrono::{Duration, NaiveDate, Utc};
+use serde::Deserialize;
+
+use crate::db::{Database, Forecast};
+
+const FORECAST_URL: &str = "https://api.open-meteo.com/v1/forecast";
+const GEOCODING_URL: &str = "https://geocoding-api.open-meteo.com/v1/search";
+/// Days asked for, today included.
+const DAYS: u32 = 7;
+
+// ─── Config ───────────────────────────────────────────────────────────────────
+
+fn default_ttl() -> i64 { 180 }
+
+/// `[weather]`: a place by name, or by coordinates (which win when both
+/// are given).
+#[derive(Debug, Clone, Deserialize)]
+pub struct WeatherConfig {
+    /// Looked up with Open-Meteo's geocoding, e.g. "Berlin".
+    #[serde(default)]
+    pub location:    Option<String>,
+    #[serde(default)]
+    pub latitude:    Option<f64>,
+    #[serde(default)]
+    pub longitude:   Option<f64>,
+    /// °F instead of °C.
+    #[serde(default)]
+    pub fahrenheit:  bool,
+    /// How long a download is used before the next.
+    #[serde(default = "default_ttl")]
+    pub ttl_minutes: i64,
+}
+
+impl WeatherConfig {
+    /// What the cache is kept for; a change of place or unit refetches.
+    fn place(&self) -> String {
+        let unit = if self.fahrenheit { "F" } else { "C" };
+        match (self.latitude, self.longitude, &self.location) {
+            (Some(lat), Some(lon), _) => format!("{lat:.3},{lon:.3} {unit}"),
+            (_, _, Some(name))        => format!("{} {unit}", name.trim().to_lowercase()),
+            _                         => String::new(),
+        }
+    }
+}
+
+// ─── Open-Meteo ───────────────────────────────────────────────────────────────
+
+#[derive(Deserialize)]
+struct Geocoded {
+    #[serde(default)]
+    results: Vec<Place>,
+}
+
+#[derive(Deserialize)]
+struct Place {
+    latitude:  f64,
+    longitude: f64,
+}
+
+#[derive(Deserialize)]
+struct Response {
+    daily: Daily,
+}
+
+#[derive(Deserialize)]
+struct Daily {
+    time:               Vec<NaiveDate>,
+    weather_code:       Vec<Option<i64>>,
+    temperature_2m_max: Vec<Option<f64>>,
+    temperature_2m_min: Vec<Option<f64>>,
+}
+
+async fn coordinates(http: &reqwest::Client, cfg: &WeatherConfig) -> Result<(f64, f64)> {
+    if let (Some(lat), Some(lon)) = (cfg.latitude, cfg.longitude) { return Ok((lat, lon)); }
+    let name = cfg.location.as_deref().map(str::trim).filter(|n| !n.is_empty())
+        .ok_or_else(|| anyhow!("[weather] needs a location, or latitude and longitude"))?;
+    let found: Geocoded = http.get(GEOCODING_URL)
+        .query(&[("name", name), ("count", "1")])
+        .send().await?.error_for_status()?.json().await?;
+    found.results.first().map(|p| (p.latitude, p.longitude)).ok_or_else(|| anyhow!("no place called {name}"))
+}
+
+/// Downloads the forecast unless the cached one is younger than
+/// `ttl_minutes`; true when it did.
+pub async fn refresh(http: &reqwest::Client, db: &Database, cfg: &WeatherConfig) -> Result<bool> {
+    let place = cfg.place();
+    let fresh = db.weather_fetched(&place).await?
+        .is_some_and(|at| Utc::now() - at < Duration::minutes(cfg.ttl_minutes.max(1)));
+    if fresh { return Ok(false); }
+
+    let (lat, lon) = coordinates(http, cfg).await?;
+    let mut query = vec![
+        ("latitude",      lat.to_string()),
+        ("longitude",     lon.to_string()),
+        ("daily",         "weather_code,temperature_2m_max,temperature_2m_min".to_owned()),
+        ("timezone",      "auto".to_owned()),
+        ("forecast_days", DAYS.to_string()),
+    ];
+    if cfg.fahrenheit { query.push(("temperature_unit", "fahrenheit".to_owned())); }
+    let body: Response = http.get(FORECAST_URL).query(&query).send().await?.error_for_status()?.json().await?;
+
+    let d = body.daily;
+    let days: Vec<Forecast> = d.time.iter().enumerate().filter_map(|(i, &date)| Some(Forecast {
+        date,
+        code: (*d.weather_code.get(i)?)?,
+        high: (*d.temperature_2m_max.get(i)?)?,
+        low:  (*d.temperature_2m_min.get(i)?)?,
+    })).collect();
+    db.replace_weather(&place, &days).await?;
+    tracing::info!("weather: {} days for {place}", days.len());
+    Ok(true)
+}
+
+// ─── Display ──────────────────────────────────────────────────────────────────
+
+/// A two-column emoji for a WMO weather code, as Open-Meteo reports them.
+pub fn icon(code: i64) -> &'static str {
+    match code {
+        0 | 1             => "🌞",
+        2 | 3             => "⛅",
+        45 | 48           => "🌁",
+        51..=57           => "💧",
+        61..=67 | 80..=82 => "☔",
+        71..=77 | 85 | 86 => "⛄",
+        95..=99           => "⚡",
+        _                 => "⛅",
+    }
+}
+
+/// "14°", rounded.
+pub fn degrees(t: f64) -> String {
+    format!("{}°", t.round() as i64)
+}